@@ -0,0 +1,82 @@
+# English messages for rgit. Every message the code uses must be here;
+# other locales fall back to these.
+#
+# Only the sections below are translated; every other command prints
+# English in any locale (see src/i18n.rs).
+#
+# Syntax is Fluent (https://projectfluent.org).
+
+## Startup and errors
+
+config-load-failed = Failed to load configuration: { $error }
+config-value-ignored = { $problem }; using the default
+log-file-unwritable = Cannot write log file { $path }: { $error }
+error-heading = Error
+error-explain-hint = Run { $command } for causes and fixes
+error-help-hint = Use { $help } for help or { $learn } for tutorials
+
+## Dry run
+
+dry-run-banner = Dry run: working on a scratch copy of the repository
+dry-run-unsupported = --dry-run with rgit { $command }, which works outside the current repository
+
+## Shared command helpers
+
+timings-took = took { $duration }
+timings-other = other
+confirm-destructive = Are you sure you want to { $operation }?
+operation-summary = { $operation } summary:
+
+## Status
+
+status-scanning = Scanning { $path } (depth { $depth })...
+status-no-repositories = No repositories found
+status-scan-deeper = Use { $option } to scan deeper
+status-no-submodules = No submodules found
+status-submodules-with-issues = { $count ->
+        [one] { $count } submodule with issues:
+       *[other] { $count } submodules with issues:
+    }
+status-submodule-details = Use "{ $command }" for details
+status-tip = Tip:
+status-clean-repository = Repository is clean!
+status-next-steps = Next steps:
+status-hint-pull = Get latest changes
+status-hint-push = Share your changes
+status-hint-set-upstream = Set up tracking
+status-hint-quick-commit = Quick workflow for changes
+status-hint-sync = Sync with remote
+status-hint-stash-list = { $count ->
+        [one] Review { $count } stashed change
+       *[other] Review { $count } stashed changes
+    }
+status-hint-add = Select files to stage
+status-hint-add-all = Stage all changes
+status-hint-commit = Commit staged changes
+status-hint-quick-commit-staged = Quick commit workflow
+status-hint-sync-when-ready = Sync when ready
+status-hint-stash = Temporarily save changes
+status-working-tree-clean = Working tree clean
+status-changes-breakdown = { $total ->
+        [one] { $total } change
+       *[other] { $total } changes
+    } ({ $staged } staged, { $unstaged } unstaged, { $untracked } untracked)
+status-clean-and-up-to-date = Clean and up to date
+status-clean = Clean
+status-clean-behind = Clean, { $behind } behind
+status-clean-ahead = Clean, { $ahead } ahead
+status-clean-ahead-behind = Clean, { $ahead } ahead, { $behind } behind
+status-changes = { $count ->
+        [one] { $count } change
+       *[other] { $count } changes
+    }
+status-current = Current repository status:
+status-continue-despite-changes = Continue with { $operation } despite uncommitted changes?
+
+## Explain
+
+explain-codes-heading = { $rgit } error codes
+explain-list-hint = Run { $command } for causes and fixes
+explain-causes = Common causes:
+explain-fixes = How to fix it:
+explain-unknown-code = unknown error code '{ $code }'; run 'rgit explain' to list them
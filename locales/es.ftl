@@ -0,0 +1,76 @@
+# Mensajes de rgit en español. Lo que falte aquí se muestra en inglés.
+
+## Inicio y errores
+
+config-load-failed = No se pudo cargar la configuración: { $error }
+config-value-ignored = { $problem }; se usa el valor predeterminado
+log-file-unwritable = No se puede escribir el registro { $path }: { $error }
+error-heading = Error
+error-explain-hint = Ejecuta { $command } para ver causas y soluciones
+error-help-hint = Usa { $help } para obtener ayuda o { $learn } para ver tutoriales
+
+## Simulación
+
+dry-run-banner = Simulación: trabajando sobre una copia temporal del repositorio
+dry-run-unsupported = --dry-run con rgit { $command }, que trabaja fuera del repositorio actual
+
+## Utilidades compartidas
+
+timings-took = tardó { $duration }
+timings-other = otros
+confirm-destructive = ¿Seguro que quieres { $operation }?
+operation-summary = Resumen de { $operation }:
+
+## Estado
+
+status-scanning = Buscando en { $path } (profundidad { $depth })...
+status-no-repositories = No se encontraron repositorios
+status-scan-deeper = Usa { $option } para buscar más a fondo
+status-no-submodules = No se encontraron submódulos
+status-submodules-with-issues = { $count ->
+        [one] { $count } submódulo con problemas:
+       *[other] { $count } submódulos con problemas:
+    }
+status-submodule-details = Usa "{ $command }" para ver los detalles
+status-tip = Consejo:
+status-clean-repository = ¡El repositorio está limpio!
+status-next-steps = Próximos pasos:
+status-hint-pull = Traer los últimos cambios
+status-hint-push = Compartir tus cambios
+status-hint-set-upstream = Configurar el seguimiento
+status-hint-quick-commit = Flujo rápido para tus cambios
+status-hint-sync = Sincronizar con el remoto
+status-hint-stash-list = { $count ->
+        [one] Revisar { $count } cambio guardado
+       *[other] Revisar { $count } cambios guardados
+    }
+status-hint-add = Elegir archivos para preparar
+status-hint-add-all = Preparar todos los cambios
+status-hint-commit = Confirmar los cambios preparados
+status-hint-quick-commit-staged = Flujo de confirmación rápida
+status-hint-sync-when-ready = Sincronizar cuando estés listo
+status-hint-stash = Guardar los cambios temporalmente
+status-working-tree-clean = Directorio de trabajo limpio
+status-changes-breakdown = { $total ->
+        [one] { $total } cambio
+       *[other] { $total } cambios
+    } ({ $staged } preparados, { $unstaged } sin preparar, { $untracked } sin seguimiento)
+status-clean-and-up-to-date = Limpio y al día
+status-clean = Limpio
+status-clean-behind = Limpio, { $behind } por detrás
+status-clean-ahead = Limpio, { $ahead } por delante
+status-clean-ahead-behind = Limpio, { $ahead } por delante, { $behind } por detrás
+status-changes = { $count ->
+        [one] { $count } cambio
+       *[other] { $count } cambios
+    }
+status-current = Estado actual del repositorio:
+status-continue-despite-changes = ¿Continuar con { $operation } a pesar de los cambios sin confirmar?
+
+## Explicación de errores
+
+explain-codes-heading = Códigos de error de { $rgit }
+explain-list-hint = Ejecuta { $command } para ver causas y soluciones
+explain-causes = Causas habituales:
+explain-fixes = Cómo solucionarlo:
+explain-unknown-code = código de error desconocido '{ $code }'; ejecuta 'rgit explain' para ver la lista
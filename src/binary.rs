@@ -0,0 +1,517 @@
+//! Summaries of binary files in diffs.
+//!
+//! Where git prints "Binary files differ", rgit reports how the size changed
+//! and, for PNG, GIF, JPEG, BMP and WebP images, the dimensions on each side.
+//! With `diff.image_preview` set, PNGs are also drawn inline on terminals that
+//! speak the kitty graphics protocol or sixel. Previews are skipped when the
+//! output goes to a pipe or a pager.
+
+use colored::*;
+use flate2::read::ZlibDecoder;
+use std::fmt;
+use std::io::Read;
+
+use crate::utils::humanize_size;
+
+/// Largest image, in pixels, that is decoded for a preview
+const MAX_PREVIEW_PIXELS: u64 = 4096 * 4096;
+
+/// Widest and tallest a sixel preview is drawn, in pixels
+const MAX_SIXEL_SIZE: u32 = 256;
+
+/// Columns a kitty preview may take up
+const MAX_KITTY_COLUMNS: u32 = 40;
+
+// =============================================================================
+// Image Headers
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Gif,
+    Jpeg,
+    Bmp,
+    WebP,
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::WebP => "WebP",
+        })
+    }
+}
+
+/// Format and dimensions of an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageInfo {
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Recognize an image from its header, without decoding it
+pub fn image_info(data: &[u8]) -> Option<ImageInfo> {
+    let be16 = |at: usize| data.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as u32);
+    let le16 = |at: usize| data.get(at..at + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as u32);
+    let be32 = |at: usize| data.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let le32 = |at: usize| data.get(at..at + 4).map(|b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]).unsigned_abs());
+    let le24 = |at: usize| data.get(at..at + 3).map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]));
+    let image = |format, width, height| Some(ImageInfo { format, width, height });
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.get(12..16) == Some(b"IHDR") {
+        return image(ImageFormat::Png, be32(16)?, be32(20)?);
+    }
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return image(ImageFormat::Gif, le16(6)?, le16(8)?);
+    }
+    if data.starts_with(b"BM") {
+        return image(ImageFormat::Bmp, le32(18)?, le32(22)?);
+    }
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        return match data.get(12..16)? {
+            b"VP8 " => image(ImageFormat::WebP, le16(26)? & 0x3fff, le16(28)? & 0x3fff),
+            b"VP8L" => {
+                let bits = le32(21)?;
+                image(ImageFormat::WebP, (bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1)
+            }
+            b"VP8X" => image(ImageFormat::WebP, le24(24)? + 1, le24(27)? + 1),
+            _ => None,
+        };
+    }
+    if data.starts_with(&[0xff, 0xd8]) {
+        // Walk the segments up to the start-of-frame marker
+        let mut at = 2;
+        while at + 4 <= data.len() {
+            if data[at] != 0xff {
+                return None;
+            }
+            let marker = data[at + 1];
+            let length = be16(at + 2)? as usize;
+            let start_of_frame = matches!(marker, 0xc0..=0xcf) && !matches!(marker, 0xc4 | 0xc8 | 0xcc);
+            if start_of_frame {
+                return image(ImageFormat::Jpeg, be16(at + 7)?, be16(at + 5)?);
+            }
+            at += 2 + length;
+        }
+    }
+    None
+}
+
+// =============================================================================
+// Summaries
+// =============================================================================
+
+/// Describe a changed binary file; `None` stands for the missing side of an
+/// added or deleted file
+pub fn summary(path: &str, old: Option<&[u8]>, new: Option<&[u8]>) -> String {
+    let old_image = old.and_then(image_info);
+    let new_image = new.and_then(image_info);
+    let kind = match new_image.or(old_image) {
+        Some(image) => format!("{} image", image.format),
+        None => "Binary file".to_string(),
+    };
+    let dimensions = |image: Option<ImageInfo>| image
+        .map(|i| format!("{}x{}", i.width, i.height))
+        .unwrap_or_else(|| "?".to_string());
+    let has_image = old_image.is_some() || new_image.is_some();
+
+    let details = match (old, new) {
+        (Some(old), Some(new)) => {
+            let mut details = Vec::new();
+            if has_image {
+                details.push(format!("{} -> {}", dimensions(old_image), dimensions(new_image)));
+            }
+            details.push(format!("{} -> {} ({})",
+                    humanize_size(old.len() as u64),
+                    humanize_size(new.len() as u64),
+                    size_delta(old.len() as u64, new.len() as u64)));
+            format!("changed: {}", details.join(", "))
+        }
+        (None, Some(data)) | (Some(data), None) => {
+            let action = if new.is_some() { "added" } else { "deleted" };
+            let image = if new.is_some() { new_image } else { old_image };
+            match image {
+                Some(_) => format!("{}: {}, {}", action, dimensions(image), humanize_size(data.len() as u64)),
+                None => format!("{}: {}", action, humanize_size(data.len() as u64)),
+            }
+        }
+        (None, None) => "changed".to_string(),
+    };
+
+    format!("{} {} {}", kind, path.bold(), details)
+}
+
+/// Signed size change, e.g. `+1.5 KB`
+fn size_delta(old: u64, new: u64) -> String {
+    match new.cmp(&old) {
+        std::cmp::Ordering::Greater => format!("+{}", humanize_size(new - old)).green().to_string(),
+        std::cmp::Ordering::Less => format!("-{}", humanize_size(old - new)).red().to_string(),
+        std::cmp::Ordering::Equal => "same size".to_string(),
+    }
+}
+
+// =============================================================================
+// Previews
+// =============================================================================
+
+/// Terminal graphics protocol used to draw previews
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreviewProtocol {
+    Kitty,
+    Sixel,
+}
+
+impl PreviewProtocol {
+    /// Protocol selected by a `diff.image_preview` setting ("off", "auto",
+    /// "kitty" or "sixel"), or `None` when stdout is not a terminal
+    pub fn from_setting(setting: &str) -> Option<Self> {
+        if !atty::is(atty::Stream::Stdout) {
+            return None;
+        }
+        match setting {
+            "kitty" => Some(PreviewProtocol::Kitty),
+            "sixel" => Some(PreviewProtocol::Sixel),
+            "auto" => Self::detect(
+                std::env::var("TERM").ok().as_deref(),
+                std::env::var("TERM_PROGRAM").ok().as_deref(),
+                std::env::var_os("KITTY_WINDOW_ID").is_some(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Guess the protocol from the terminal's environment
+    fn detect(term: Option<&str>, program: Option<&str>, kitty_window: bool) -> Option<Self> {
+        let term = term.unwrap_or("");
+        let program = program.unwrap_or("").to_lowercase();
+        if kitty_window || term == "xterm-kitty" || ["wezterm", "ghostty"].contains(&program.as_str()) {
+            return Some(PreviewProtocol::Kitty);
+        }
+        if term.contains("sixel") || ["mlterm", "foot"].iter().any(|t| term.starts_with(t)) {
+            return Some(PreviewProtocol::Sixel);
+        }
+        None
+    }
+
+    /// Escape sequence that draws `data`, when it is an image this can show
+    pub fn render(&self, data: &[u8]) -> Option<String> {
+        let info = image_info(data).filter(|i| i.format == ImageFormat::Png)?;
+        match self {
+            PreviewProtocol::Kitty => Some(kitty_image(data, info)),
+            PreviewProtocol::Sixel => decode_png(data).map(|pixels| sixel_image(&pixels)),
+        }
+    }
+}
+
+/// Transmit a PNG as is with the kitty graphics protocol
+fn kitty_image(data: &[u8], info: ImageInfo) -> String {
+    // Roughly eight pixels to a cell; never upscale small images much
+    let columns = (info.width / 8).clamp(1, MAX_KITTY_COLUMNS);
+    let encoded = base64(data);
+    let chunks: Vec<&str> = encoded.as_bytes()
+        .chunks(4096)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap_or(""))
+        .collect();
+
+    let mut out = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        if index == 0 {
+            out.push_str(&format!("\x1b_Ga=T,f=100,c={},m={};{}\x1b\\", columns, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out.push('\n');
+    out
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(triple >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Decoded RGBA pixels
+#[derive(Debug)]
+struct Pixels {
+    width: u32,
+    height: u32,
+    rgba: Vec<[u8; 4]>,
+}
+
+/// Decode an 8-bit, non-interlaced PNG; other variants get no preview
+fn decode_png(data: &[u8]) -> Option<Pixels> {
+    let mut at = 8;
+    let (mut width, mut height, mut color_type) = (0, 0, 0);
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut compressed = Vec::new();
+
+    while at + 8 <= data.len() {
+        let length = u32::from_be_bytes(data[at..at + 4].try_into().ok()?) as usize;
+        let kind = &data[at + 4..at + 8];
+        let body = data.get(at + 8..at + 8 + length)?;
+        match kind {
+            b"IHDR" => {
+                width = u32::from_be_bytes(body.get(0..4)?.try_into().ok()?);
+                height = u32::from_be_bytes(body.get(4..8)?.try_into().ok()?);
+                let (depth, interlace) = (*body.get(8)?, *body.get(12)?);
+                color_type = *body.get(9)?;
+                if depth != 8 || interlace != 0 || u64::from(width) * u64::from(height) > MAX_PREVIEW_PIXELS {
+                    return None;
+                }
+            }
+            b"PLTE" => palette = body.chunks_exact(3).map(|c| [c[0], c[1], c[2], 255]).collect(),
+            b"tRNS" if color_type == 3 => {
+                for (entry, alpha) in palette.iter_mut().zip(body) {
+                    entry[3] = *alpha;
+                }
+            }
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+        // Length, type, data and CRC
+        at += 12 + length;
+    }
+
+    let channels = match color_type {
+        0 | 3 => 1,
+        4 => 2,
+        2 => 3,
+        6 => 4,
+        _ => return None,
+    };
+    let stride = width as usize * channels;
+    let mut raw = Vec::new();
+    ZlibDecoder::new(compressed.as_slice()).read_to_end(&mut raw).ok()?;
+    if width == 0 || raw.len() < (stride + 1) * height as usize {
+        return None;
+    }
+
+    let mut rgba = Vec::with_capacity(width as usize * height as usize);
+    let mut previous = vec![0u8; stride];
+    for row in raw.chunks_exact(stride + 1).take(height as usize) {
+        let mut line = row[1..].to_vec();
+        unfilter(row[0], &mut line, &previous, channels)?;
+        for pixel in line.chunks_exact(channels) {
+            rgba.push(match color_type {
+                0 => [pixel[0], pixel[0], pixel[0], 255],
+                4 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                2 => [pixel[0], pixel[1], pixel[2], 255],
+                6 => [pixel[0], pixel[1], pixel[2], pixel[3]],
+                _ => *palette.get(pixel[0] as usize)?,
+            });
+        }
+        previous = line;
+    }
+
+    Some(Pixels { width, height, rgba })
+}
+
+/// Undo a PNG row filter in place
+fn unfilter(filter: u8, line: &mut [u8], previous: &[u8], bpp: usize) -> Option<()> {
+    for i in 0..line.len() {
+        let left = if i >= bpp { line[i - bpp] } else { 0 };
+        let up = previous[i];
+        let up_left = if i >= bpp { previous[i - bpp] } else { 0 };
+        let predictor = match filter {
+            0 => 0,
+            1 => left,
+            2 => up,
+            3 => ((u16::from(left) + u16::from(up)) / 2) as u8,
+            4 => paeth(left, up, up_left),
+            _ => return None,
+        };
+        line[i] = line[i].wrapping_add(predictor);
+    }
+    Some(())
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let (pa, pb, pc) = ((p - i16::from(a)).abs(), (p - i16::from(b)).abs(), (p - i16::from(c)).abs());
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+/// Draw pixels as sixels with a 6x6x6 color cube, scaled down to fit
+fn sixel_image(pixels: &Pixels) -> String {
+    let scale = pixels.width.max(pixels.height).div_ceil(MAX_SIXEL_SIZE).max(1);
+    let (width, height) = (pixels.width.div_ceil(scale), pixels.height.div_ceil(scale));
+    let level = |v: u8| (u16::from(v) * 5 + 127) / 255;
+
+    // Palette index per pixel; transparent pixels are left blank
+    let index = |x: u32, y: u32| -> Option<u16> {
+        let [r, g, b, a] = pixels.rgba[((y * scale) * pixels.width + x * scale) as usize];
+        (a >= 128).then(|| level(r) * 36 + level(g) * 6 + level(b))
+    };
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    for color in 0..216u16 {
+        let percent = |l: u16| l * 20;
+        out.push_str(&format!("#{};2;{};{};{}", color, percent(color / 36), percent(color / 6 % 6), percent(color % 6)));
+    }
+
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut colors: Vec<u16> = rows.clone()
+            .flat_map(|y| (0..width).filter_map(move |x| index(x, y)))
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+
+        for (n, color) in colors.iter().enumerate() {
+            if n > 0 {
+                out.push('$');
+            }
+            out.push_str(&format!("#{}", color));
+            let sixels: Vec<u8> = (0..width)
+                .map(|x| rows.clone().fold(0u8, |bits, y| {
+                    bits | (u8::from(index(x, y) == Some(*color)) << (y - band))
+                }))
+                .collect();
+            push_run_length(&mut out, &sixels);
+        }
+        out.push('-');
+    }
+
+    out.push_str("\x1b\\\n");
+    out
+}
+
+/// Append sixel data, compressing runs of the same column
+fn push_run_length(out: &mut String, sixels: &[u8]) {
+    let mut i = 0;
+    while i < sixels.len() {
+        let run = sixels[i..].iter().take_while(|&&s| s == sixels[i]).count();
+        let glyph = (63 + sixels[i]) as char;
+        if run > 3 {
+            out.push_str(&format!("!{}{}", run, glyph));
+        } else {
+            out.extend(std::iter::repeat_n(glyph, run));
+        }
+        i += run;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    /// A PNG with the given RGBA rows; CRCs are left zero as they are never checked
+    fn png(width: u32, rows: &[Vec<[u8; 4]>]) -> Vec<u8> {
+        let chunk = |out: &mut Vec<u8>, kind: &[u8], body: &[u8]| {
+            out.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            out.extend_from_slice(kind);
+            out.extend_from_slice(body);
+            out.extend_from_slice(&[0; 4]);
+        };
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&(rows.len() as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut raw = Vec::new();
+        for row in rows {
+            // Sub filter, so decoding has to undo it
+            raw.push(1);
+            let mut left = [0u8; 4];
+            for pixel in row {
+                for c in 0..4 {
+                    raw.push(pixel[c].wrapping_sub(left[c]));
+                }
+                left = *pixel;
+            }
+        }
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).unwrap();
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &encoder.finish().unwrap());
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    #[test]
+    fn test_image_info() {
+        let red = [255, 0, 0, 255];
+        assert_eq!(image_info(&png(3, &[vec![red; 3], vec![red; 3]])),
+                Some(ImageInfo { format: ImageFormat::Png, width: 3, height: 2 }));
+
+        let gif = b"GIF89a\x40\x01\xf0\x00";
+        assert_eq!(image_info(gif), Some(ImageInfo { format: ImageFormat::Gif, width: 320, height: 240 }));
+
+        let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe0, 0x00, 0x04, 0x00, 0x00];
+        jpeg.extend_from_slice(&[0xff, 0xc0, 0x00, 0x11, 0x08, 0x00, 0x20, 0x00, 0x40]);
+        assert_eq!(image_info(&jpeg), Some(ImageInfo { format: ImageFormat::Jpeg, width: 64, height: 32 }));
+
+        assert_eq!(image_info(b"\x00\x01 not an image"), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = |path, old, new| console::strip_ansi_codes(&summary(path, old, new)).to_string();
+        let old = png(2, &[vec![[0, 0, 0, 255]; 2]]);
+        let new = png(4, &[vec![[0, 0, 0, 255]; 4], vec![[0, 0, 0, 255]; 4]]);
+
+        let changed = summary("logo.png", Some(&old), Some(&new));
+        assert!(changed.starts_with("PNG image logo.png changed: 2x1 -> 4x2, "), "{}", changed);
+        assert_eq!(summary("data.bin", None, Some(&[0; 2048])), "Binary file data.bin added: 2.0 KB");
+        assert_eq!(summary("data.bin", Some(&[0; 10]), Some(&[1; 4])),
+                "Binary file data.bin changed: 10 B -> 4 B (-6 B)");
+    }
+
+    #[test]
+    fn test_png_previews() {
+        let (red, clear) = ([255, 0, 0, 255], [0, 0, 0, 0]);
+        let data = png(5, &[vec![red; 5], vec![red, red, clear, red, red]]);
+
+        let pixels = decode_png(&data).unwrap();
+        assert_eq!((pixels.width, pixels.height), (5, 2));
+        assert_eq!(pixels.rgba[7], clear);
+
+        // Red is color 5*36 in the cube; the transparent pixel only has the top bit
+        let sixel = PreviewProtocol::Sixel.render(&data).unwrap();
+        assert!(sixel.starts_with("\x1bPq\"1;1;5;2"));
+        assert!(sixel.contains("#180BB@BB-"), "{:?}", sixel);
+
+        let kitty = PreviewProtocol::Kitty.render(&data).unwrap();
+        assert!(kitty.starts_with("\x1b_Ga=T,f=100,c=1,m=0;iVBORw0KGgo"));
+        assert_eq!(base64(b"rgit!"), "cmdpdCE=");
+    }
+
+    #[test]
+    fn test_detect_protocol() {
+        assert_eq!(PreviewProtocol::detect(Some("xterm-kitty"), None, false), Some(PreviewProtocol::Kitty));
+        assert_eq!(PreviewProtocol::detect(Some("xterm-256color"), Some("WezTerm"), false), Some(PreviewProtocol::Kitty));
+        assert_eq!(PreviewProtocol::detect(Some("foot"), None, false), Some(PreviewProtocol::Sixel));
+        assert_eq!(PreviewProtocol::detect(Some("xterm-256color"), None, false), None);
+    }
+}
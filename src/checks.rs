@@ -0,0 +1,316 @@
+use anyhow::{Context, Result};
+use git2::{BranchType, Repository};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::RgitError;
+use crate::utils::{calculate_repo_size, humanize_size, parse_size};
+
+/// Repository-local health checks, relative to the working tree root
+pub const CHECKS_FILE: &str = ".rgit/checks.toml";
+
+/// Extra health checks a repository defines for `rgit doctor`
+///
+/// ```toml
+/// [[required_files]]
+/// path = "LICENSE"
+///
+/// [branch_names]
+/// pattern = "^(feature|fix)/[a-z0-9-]+$"
+/// exempt = ["main", "develop"]
+/// severity = "warn"
+///
+/// [max_repo_size]
+/// warn = "500MB"
+/// fail = "1GB"
+///
+/// [[required_hooks]]
+/// name = "pre-commit"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomChecks {
+    /// Files that must exist in the working tree
+    #[serde(default)]
+    pub required_files: Vec<RequiredFile>,
+    /// Naming rule for local branches
+    pub branch_names: Option<BranchNameRule>,
+    /// Size limits for the `.git` directory
+    pub max_repo_size: Option<SizeLimit>,
+    /// Hooks that must be installed and executable
+    #[serde(default)]
+    pub required_hooks: Vec<RequiredHook>,
+}
+
+/// How a failed check is reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warn,
+    #[default]
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredFile {
+    pub path: PathBuf,
+    #[serde(default)]
+    pub severity: Severity,
+    /// Shown instead of the default hint when the file is missing
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BranchNameRule {
+    /// Regular expression every branch name must match
+    pub pattern: String,
+    /// Branches that don't have to match
+    #[serde(default)]
+    pub exempt: Vec<String>,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// Sizes such as "500MB"; exceeding `warn` warns, exceeding `fail` fails
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SizeLimit {
+    pub warn: Option<String>,
+    pub fail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequiredHook {
+    pub name: String,
+    #[serde(default)]
+    pub severity: Severity,
+}
+
+/// Result of one custom check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl From<Severity> for CheckStatus {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Warn => CheckStatus::Warn,
+            Severity::Fail => CheckStatus::Fail,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub hint: String,
+}
+
+impl CheckOutcome {
+    fn new(name: impl Into<String>, status: CheckStatus, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name: name.into(), status, detail: detail.into(), hint: hint.into() }
+    }
+}
+
+impl CustomChecks {
+    /// Load the checks for the working tree at `root`, if it defines any
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(CHECKS_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let checks: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        checks.validate()?;
+        Ok(Some(checks))
+    }
+
+    /// Catch mistakes that would otherwise only show up as odd results
+    fn validate(&self) -> Result<()> {
+        if let Some(rule) = &self.branch_names {
+            Regex::new(&rule.pattern)?;
+        }
+        if let Some(limit) = &self.max_repo_size {
+            for (key, value) in [("max_repo_size.warn", &limit.warn), ("max_repo_size.fail", &limit.fail)] {
+                if let Some(value) = value {
+                    if parse_size(value).is_none() {
+                        return Err(RgitError::InvalidConfigValue { key: key.to_string(), value: value.clone() }.into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Run every check against `repo`
+    pub fn evaluate(&self, repo: &Repository) -> Result<Vec<CheckOutcome>> {
+        let root = repo.workdir().unwrap_or_else(|| repo.path());
+        let mut outcomes = Vec::new();
+
+        for file in &self.required_files {
+            let name = format!("Required file {}", file.path.display());
+            outcomes.push(if root.join(&file.path).exists() {
+                CheckOutcome::new(name, CheckStatus::Pass, "Present", "")
+            } else {
+                let hint = file.message.clone().unwrap_or_else(|| format!("Add {} to the repository", file.path.display()));
+                CheckOutcome::new(name, file.severity.into(), "Missing", hint)
+            });
+        }
+
+        if let Some(rule) = &self.branch_names {
+            outcomes.push(check_branch_names(repo, rule)?);
+        }
+
+        if let Some(limit) = &self.max_repo_size {
+            outcomes.push(check_repo_size(calculate_repo_size(repo.path())?, limit));
+        }
+
+        let hooks_dir = hooks_dir(repo);
+        for hook in &self.required_hooks {
+            let name = format!("Required hook {}", hook.name);
+            let path = hooks_dir.join(&hook.name);
+            outcomes.push(if !path.is_file() {
+                CheckOutcome::new(name, hook.severity.into(), "Not installed",
+                                  format!("Install the hook at {}", path.display()))
+            } else if !is_executable(&path) {
+                CheckOutcome::new(name, hook.severity.into(), "Not executable",
+                                  format!("Run: chmod +x {}", path.display()))
+            } else {
+                CheckOutcome::new(name, CheckStatus::Pass, "Installed", "")
+            });
+        }
+
+        Ok(outcomes)
+    }
+}
+
+fn check_branch_names(repo: &Repository, rule: &BranchNameRule) -> Result<CheckOutcome> {
+    let pattern = Regex::new(&rule.pattern)?;
+    let mut offenders = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(name) = branch.name()? else { continue };
+        if !rule.exempt.iter().any(|e| e == name) && !pattern.is_match(name) {
+            offenders.push(name.to_string());
+        }
+    }
+
+    let name = "Branch names";
+    Ok(if offenders.is_empty() {
+        CheckOutcome::new(name, CheckStatus::Pass, format!("All branches match {}", rule.pattern), "")
+    } else {
+        CheckOutcome::new(name, rule.severity.into(),
+                          format!("{} don't match {}", offenders.join(", "), rule.pattern),
+                          "Rename them with 'rgit branch -m <old> <new>'")
+    })
+}
+
+fn check_repo_size(size: u64, limit: &SizeLimit) -> CheckOutcome {
+    let exceeds = |threshold: &Option<String>| {
+        threshold.as_deref().and_then(parse_size).filter(|max| size > *max)
+    };
+    let name = "Repository size limit";
+    let detail = humanize_size(size);
+    if let Some(max) = exceeds(&limit.fail) {
+        CheckOutcome::new(name, CheckStatus::Fail, format!("{} exceeds {}", detail, humanize_size(max)),
+                          "Run 'rgit gc --aggressive' or move large files out of history")
+    } else if let Some(max) = exceeds(&limit.warn) {
+        CheckOutcome::new(name, CheckStatus::Warn, format!("{} exceeds {}", detail, humanize_size(max)),
+                          "Run 'rgit gc' and watch for large files")
+    } else {
+        CheckOutcome::new(name, CheckStatus::Pass, format!("{} within limits", detail), "")
+    }
+}
+
+/// The hooks directory, honoring core.hooksPath
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo.config().ok().and_then(|config| config.get_path("core.hooksPath").ok());
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        None => repo.path().join("hooks"),
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn status_of<'a>(outcomes: &'a [CheckOutcome], name: &str) -> &'a CheckOutcome {
+        outcomes.iter().find(|o| o.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_evaluates_custom_checks() {
+        let repo = TestRepo::new();
+        repo.commit_file("LICENSE", "MIT\n", "Add license");
+        repo.branch("feature/ok").branch("Bad_Branch");
+        repo.write(CHECKS_FILE, r#"
+            [[required_files]]
+            path = "LICENSE"
+
+            [[required_files]]
+            path = "CODEOWNERS"
+            severity = "warn"
+            message = "Add a CODEOWNERS file"
+
+            [branch_names]
+            pattern = "^feature/[a-z-]+$"
+            exempt = ["main"]
+
+            [max_repo_size]
+            warn = "1KB"
+            fail = "1GB"
+
+            [[required_hooks]]
+            name = "pre-commit"
+        "#);
+
+        let checks = CustomChecks::load(repo.path()).unwrap().unwrap();
+        let outcomes = checks.evaluate(repo.repo()).unwrap();
+        assert_eq!(status_of(&outcomes, "Required file LICENSE").status, CheckStatus::Pass);
+        let codeowners = status_of(&outcomes, "Required file CODEOWNERS");
+        assert_eq!(codeowners.status, CheckStatus::Warn);
+        assert_eq!(codeowners.hint, "Add a CODEOWNERS file");
+        let branches = status_of(&outcomes, "Branch names");
+        assert_eq!(branches.status, CheckStatus::Fail);
+        assert!(branches.detail.starts_with("Bad_Branch don't match"));
+        assert_eq!(status_of(&outcomes, "Repository size limit").status, CheckStatus::Warn);
+        assert_eq!(status_of(&outcomes, "Required hook pre-commit").status, CheckStatus::Fail);
+    }
+
+    #[test]
+    fn test_rejects_invalid_checks_file() {
+        let repo = TestRepo::new();
+        assert!(CustomChecks::load(repo.path()).unwrap().is_none());
+
+        repo.write(CHECKS_FILE, "[max_repo_size]\nwarn = \"huge\"\n");
+        assert!(CustomChecks::load(repo.path()).is_err());
+        repo.write(CHECKS_FILE, "[[required_file]]\npath = \"LICENSE\"\n");
+        assert!(CustomChecks::load(repo.path()).is_err());
+    }
+}
@@ -42,6 +42,44 @@ pub struct Cli {
     #[arg(long, global = true, help = "Disable all colored output")]
     pub no_color: bool,
 
+    /// Do not pipe output into a pager
+    #[arg(long, global = true, help = "Do not page long output")]
+    pub no_pager: bool,
+
+    /// Show what a write command would change without changing anything
+    #[arg(
+        long,
+        global = true,
+        help = "Show what would change (refs, commits, index, files) without changing anything"
+    )]
+    pub dry_run: bool,
+
+    /// Answer every confirmation with its default, for scripts and CI
+    #[arg(
+        short = 'y',
+        long,
+        global = true,
+        help = "Don't ask; take the default answer to every confirmation (also RGIT_NONINTERACTIVE=1)"
+    )]
+    pub yes: bool,
+
+    /// Print how long the command spent in each phase
+    #[arg(
+        long,
+        global = true,
+        help = "Print how long each phase took (repo open, status scan, network, index write)"
+    )]
+    pub timings: bool,
+
+    /// Write logs to this file instead of the data directory's logs folder
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Write logs to FILE (rotated like the default log)"
+    )]
+    pub log_file: Option<PathBuf>,
+
     /// Use alternative configuration file
     #[arg(
         long,
@@ -82,10 +120,22 @@ pub enum Commands {
     #[command(visible_alias = "a")]
     Add(AddArgs),
 
+    /// Remove files or hunks from the index, keeping the working tree
+    Unstage(UnstageArgs),
+
+    /// Move or rename tracked files and record it in the index
+    Mv(MvArgs),
+
+    /// Remove tracked files from the index and working tree
+    Rm(RmArgs),
+
     /// Intelligent commit with validation and templates
     #[command(visible_alias = "c")]
     Commit(CommitArgs),
 
+    /// Move HEAD with a guided choice between --soft, --mixed and --hard
+    Reset(ResetArgs),
+
     /// Enhanced push with safety checks and progress
     #[command(visible_alias = "p")]
     Push(PushArgs),
@@ -107,6 +157,10 @@ pub enum Commands {
     #[command(visible_alias = "co")]
     Checkout(CheckoutArgs),
 
+    /// Switch branches, refusing to overwrite local changes
+    #[command(visible_alias = "sw")]
+    Switch(SwitchArgs),
+
     /// Interactive merge with conflict resolution assistance
     #[command(visible_alias = "m")]
     Merge(MergeArgs),
@@ -119,6 +173,9 @@ pub enum Commands {
     #[command(visible_alias = "cp")]
     CherryPick(CherryPickArgs),
 
+    /// Revert commits with conflict handling
+    Revert(RevertArgs),
+
     // ===== History and Information =====
     /// Enhanced log with beautiful formatting and filtering
     #[command(visible_alias = "l")]
@@ -128,20 +185,48 @@ pub enum Commands {
     #[command(visible_alias = "d")]
     Diff(DiffArgs),
 
-    /// Show commit details with enhanced formatting
+    /// Show commits, tags, trees and blobs with enhanced formatting
     Show(ShowArgs),
 
+    /// Print a file as of a revision, e.g. rgit cat HEAD~2:src/main.rs
+    Cat(CatArgs),
+
+    /// List the files of a revision with their modes, ids and sizes
+    Ls(LsArgs),
+
+    /// Print the blob id git gives a file's content
+    Hash(HashArgs),
+
+    /// Show an object's type, size and raw content (see rgit learn advanced)
+    Object(ObjectArgs),
+
     /// Search through commit history and content
     Grep(GrepArgs),
 
     /// Show file blame with context and history
     Blame(BlameArgs),
 
+    /// Find who to ask about a path: its recent committers and code owners
+    Who(WhoArgs),
+
+    /// List TODO, FIXME, XXX and HACK comments with who added them, most urgent first
+    Todos(TodosArgs),
+
+    /// Validate CODEOWNERS and look up who owns paths
+    Owners(OwnersArgs),
+
     // ===== Remote Management =====
     /// Manage remotes with URL validation
     #[command(visible_alias = "r")]
     Remote(RemoteArgs),
 
+    /// List the refs of a remote or URL without cloning it
+    #[command(name = "ls-remote")]
+    LsRemote(LsRemoteArgs),
+
+    /// Keep bare mirrors in sync, e.g. on backup servers and CI cache hosts
+    Mirror(MirrorArgs),
+
     // ===== Tag Management =====
     /// Tag operations with GPG signing support
     #[command(visible_alias = "t")]
@@ -152,11 +237,22 @@ pub enum Commands {
     #[command(visible_alias = "s")]
     Stash(StashArgs),
 
+    /// Checkpoint all changes, untracked files included, as a WIP commit
+    Wip(WipArgs),
+
     // ===== Submodule Operations =====
     /// Complete submodule management with health checking
     #[command(visible_alias = "sub")]
     Submodule(SubmoduleArgs),
 
+    /// Vendor other repositories into a directory with subtree merges
+    Subtree(SubtreeArgs),
+
+    // ===== Workspace Operations =====
+    /// Run operations across a set of repositories
+    #[command(visible_alias = "ws")]
+    Workspace(WorkspaceArgs),
+
     // ===== Advanced Git Operations =====
     /// Interactive bisect for bug hunting
     Bisect(BisectArgs),
@@ -164,12 +260,30 @@ pub enum Commands {
     /// Show reference logs with filtering
     Reflog(ReflogArgs),
 
+    /// Find lost commits and recreate branches for them
+    Recover(RecoverArgs),
+
     /// Repository maintenance and optimization
     Gc(GcArgs),
 
     /// File system check with repair options
     Fsck(FsckArgs),
 
+    /// Audit history for unsigned commits, unknown authors, large blobs and force pushes
+    Audit(AuditArgs),
+
+    /// Look for API keys, private keys and other credentials in staged changes
+    Scan(ScanArgs),
+
+    /// Rewrite history to remove paths or blobs, fix emails or strip file modes
+    Filter(FilterArgs),
+
+    /// Import history from a fast-import stream, e.g. from hg-fast-export or svn2git
+    Import(ImportArgs),
+
+    /// Write history as a fast-import stream for mirrors and other tools
+    Export(ExportArgs),
+
     // ===== Ease-of-Use Commands =====
     /// Quick sync (pull + push) with safety checks
     #[command(visible_alias = "sy")]
@@ -189,19 +303,35 @@ pub enum Commands {
     /// Interactive conflict resolution assistant
     Resolve,
 
+    /// Open conflicted files in an external merge tool
+    Mergetool(MergetoolArgs),
+
     /// Backup current repository state
     Backup(BackupArgs),
 
-    /// Restore from backup
+    /// Restore files in the working tree or index from any revision
     Restore(RestoreArgs),
 
     // ===== Utility Commands =====
     /// Repository health check and diagnostics
     #[command(visible_alias = "doc")]
-    Doctor,
+    Doctor(DoctorArgs),
 
     /// Interactive Git tutorials and learning
     Learn(LearnArgs),
+
+    /// Explain an error code like RG-0042: causes and fixes
+    Explain(ExplainArgs),
+
+    /// View and edit rgit and git configuration
+    #[command(visible_alias = "cfg")]
+    Config(ConfigArgs),
+
+    /// Show the identity and signing key commits will use
+    Whoami,
+
+    /// Manage cached HTTPS credentials
+    Credential(CredentialArgs),
 }
 
 // ============================================================================
@@ -246,12 +376,13 @@ pub enum GitignoreTemplate {
 
 #[derive(Args, Debug)]
 pub struct CloneArgs {
-    /// Repository URL to clone
-    #[arg(value_name = "URL", help = "Git repository URL to clone")]
+    /// Repository URL to clone, or a forge shorthand like gh:owner/repo
+    #[arg(value_name = "URL", help = "Git repository URL, or gh:/gl:/cb: owner[/repo] to clone from a forge")]
     pub url: String, // Note: This was the correct field name
 
     /// Directory name (optional, defaults to repository name)
     #[arg(
+        id = "target_directory",
         value_name = "DIRECTORY",
         help = "Directory name for the cloned repository"
     )]
@@ -275,9 +406,18 @@ pub struct CloneArgs {
     pub branch: Option<String>,
 
     /// Clone recursively (including submodules)
-    #[arg(long, help = "Initialize and clone submodules recursively")]
+    #[arg(long = "recurse-submodules", visible_alias = "recursive",
+          help = "Initialize and clone submodules recursively (on by default with submodules.auto_init)")]
     pub recursive: bool,
 
+    /// How many submodules to clone at once
+    #[arg(short, long, value_name = "N", help = "Clone N submodules in parallel (default: submodules.max_jobs)")]
+    pub jobs: Option<usize>,
+
+    /// Download Git LFS files after checkout
+    #[arg(long, help = "Fetch Git LFS objects for the checkout and its submodules (needs git-lfs)")]
+    pub lfs: bool,
+
     /// Use single branch mode
     #[arg(
         long,
@@ -286,7 +426,7 @@ pub struct CloneArgs {
     pub single_branch: bool,
 
     /// Clone with specific protocol
-    #[arg(long, value_enum, help = "Force specific protocol for cloning")]
+    #[arg(long, value_enum, help = "Protocol for forge shorthands (overrides clone.protocol)")]
     pub protocol: Option<Protocol>,
 
     /// Create a bare repository
@@ -294,7 +434,7 @@ pub struct CloneArgs {
     pub bare: bool,
 
     /// Create a mirror repository
-    #[arg(long, help = "Create a mirror repository")]
+    #[arg(long, help = "Create a bare mirror of every ref, kept in sync with rgit mirror update")]
     pub mirror: bool,
 }
 
@@ -326,6 +466,23 @@ pub struct StatusArgs {
     /// Include file modification times
     #[arg(long, help = "Show file modification times")]
     pub timestamps: bool,
+
+    /// Summarize every repository found below a directory
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".",
+          help = "Scan a directory tree for repositories and summarize each one")]
+    pub all_repos: Option<PathBuf>,
+
+    /// Maximum directory depth for --all-repos
+    #[arg(long, default_value = "3", requires = "all_repos", help = "Maximum depth to scan with --all-repos")]
+    pub max_depth: usize,
+
+    /// Number of repositories to inspect in parallel
+    #[arg(short, long, requires = "all_repos", help = "Repositories to inspect in parallel with --all-repos")]
+    pub jobs: Option<usize>,
+
+    /// Skip the automatic fetch configured by fetch.auto
+    #[arg(long, help = "Do not auto-fetch before showing status")]
+    pub no_fetch: bool,
 }
 
 #[derive(Args, Debug)]
@@ -359,6 +516,60 @@ pub struct AddArgs {
     pub intent_to_add: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct MvArgs {
+    /// Sources followed by the destination
+    #[arg(value_name = "SOURCE... DESTINATION", num_args = 2.., required = true,
+          help = "Files, directories or globs to move, then the destination")]
+    pub paths: Vec<String>,
+
+    /// Only update the index (for files already moved on disk)
+    #[arg(long, help = "Update the index only; leave the working tree alone")]
+    pub cached: bool,
+
+    /// Overwrite existing destinations
+    #[arg(short, long, help = "Overwrite the destination if it exists")]
+    pub force: bool,
+
+    /// Show what would be moved
+    #[arg(short = 'n', id = "mv_dry_run", help = "Show what would be moved without moving")]
+    pub dry_run: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct UnstageArgs {
+    /// Files, directories or globs to unstage (interactive selection if none specified)
+    #[arg(value_name = "PATHS", help = "Files, directories or globs to unstage")]
+    pub paths: Vec<String>,
+
+    /// Unstage changes interactively by hunks
+    #[arg(short, long, help = "Interactively select hunks to unstage")]
+    pub patch: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct RmArgs {
+    /// Files, directories or globs to remove
+    #[arg(value_name = "PATHS", required = true, help = "Files, directories or globs to remove")]
+    pub paths: Vec<String>,
+
+    /// Only remove from the index
+    #[arg(long, help = "Remove from the index only; keep the files on disk")]
+    pub cached: bool,
+
+    /// Remove directories recursively
+    #[arg(short, long, help = "Allow removing directories recursively")]
+    pub recursive: bool,
+
+    /// Remove files with uncommitted changes
+    #[arg(short, long, help = "Remove files even if they have uncommitted changes")]
+    pub force: bool,
+
+    /// Show what would be removed
+    #[arg(short = 'n', id = "rm_dry_run", help = "Show what would be removed without removing")]
+    pub dry_run: bool,
+}
+
 #[derive(Args, Debug)]
 pub struct CommitArgs {
     /// Commit message
@@ -374,8 +585,8 @@ pub struct CommitArgs {
     )]
     pub file: Option<PathBuf>,
 
-    /// Amend the last commit
-    #[arg(short, long, help = "Amend the previous commit")]
+    /// Amend the last commit. No short flag: `-a` is `--all`, as in git.
+    #[arg(long, help = "Amend the previous commit")]
     pub amend: bool,
 
     /// Skip pre-commit and commit-msg hooks
@@ -397,6 +608,60 @@ pub struct CommitArgs {
     /// Use commit template
     #[arg(long, help = "Use a commit message template")]
     pub template: bool,
+
+    /// Commit even if the staged changes look like they contain secrets
+    #[arg(long, help = "Skip the scan for credentials in staged changes")]
+    pub allow_secrets: bool,
+
+    /// Commit files above commit.max_file_size
+    #[arg(long, help = "Commit staged files larger than commit.max_file_size")]
+    pub allow_large_files: bool,
+
+    #[command(flatten)]
+    pub trailers: TrailerArgs,
+}
+
+/// Trailers appended to the message by commit and quick-commit
+#[derive(Args, Debug, Default, Clone)]
+pub struct TrailerArgs {
+    /// Credit a co-author
+    #[arg(long = "co-author", value_name = "NAME <EMAIL>", help = "Add a Co-authored-by trailer")]
+    pub co_authors: Vec<String>,
+
+    /// Pick co-authors from recent collaborators
+    #[arg(long, help = "Choose co-authors from people who recently worked on this repository")]
+    pub pick_co_authors: bool,
+
+    /// Arbitrary trailers
+    #[arg(long = "trailer", value_name = "KEY=VALUE", help = "Add a trailer, e.g. Reviewed-by=Name <email>")]
+    pub trailers: Vec<String>,
+
+    /// Certify the commit with Signed-off-by
+    #[arg(short = 's', long, help = "Add a Signed-off-by trailer (always on with commit.signoff)")]
+    pub signoff: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ResetArgs {
+    /// Commit to reset to (default: HEAD)
+    #[arg(value_name = "COMMIT", help = "Commit to move HEAD to (default: HEAD)")]
+    pub commit: Option<String>,
+
+    /// Keep the index and working tree
+    #[arg(long, conflicts_with_all = ["mixed", "hard"], help = "Move HEAD only; keep the index and working tree")]
+    pub soft: bool,
+
+    /// Reset the index but keep the working tree
+    #[arg(long, conflicts_with = "hard", help = "Reset the index but keep the working tree (default)")]
+    pub mixed: bool,
+
+    /// Reset the index and working tree
+    #[arg(long, help = "Reset the index and working tree, discarding uncommitted changes")]
+    pub hard: bool,
+
+    /// Skip the confirmation for a hard reset
+    #[arg(short, long, help = "Don't ask before a hard reset discards changes")]
+    pub force: bool,
 }
 
 #[derive(Args, Debug)]
@@ -410,7 +675,7 @@ pub struct PushArgs {
     pub branch: Option<String>,
 
     /// Set upstream tracking for the branch
-    #[arg(short, long, help = "Set upstream for git pull/status")]
+    #[arg(short, long, short_alias = 'u', help = "Set upstream for git pull/status")]
     pub set_upstream: bool,
 
     /// Force push (use with caution)
@@ -433,9 +698,14 @@ pub struct PushArgs {
     #[arg(long, help = "Push all tags")]
     pub tags: bool,
 
-    /// Delete remote branch
-    #[arg(long, help = "Delete the remote branch")]
-    pub delete: bool,
+    /// Push to every configured remote
+    #[arg(long, conflicts_with = "remote", help = "Push to every remote and summarize the results")]
+    pub all_remotes: bool,
+
+    /// Delete remote branches (interactive selection if none given)
+    #[arg(short = 'd', long, value_name = "BRANCH", num_args = 0..,
+          help = "Delete remote branches, recording their tips for recovery")]
+    pub delete: Option<Vec<String>>,
 }
 
 #[derive(Args, Debug)]
@@ -503,12 +773,16 @@ pub enum SubmoduleCommands {
         rebase: bool,
 
         /// Use remote tracking branch
-        #[arg(long, help = "Update to latest remote commit")]
+        #[arg(long, help = "Fetch and fast-forward to the tracked remote branch")]
         remote: bool,
 
         /// Force update
         #[arg(short, long, help = "Discard local changes when updating")]
         force: bool,
+
+        /// Submodules to update at once (default: submodules.max_jobs)
+        #[arg(short, long, value_name = "N", help = "Update this many submodules in parallel")]
+        jobs: Option<usize>,
     },
 
     /// Show submodule status with health information
@@ -522,6 +796,21 @@ pub enum SubmoduleCommands {
         health: bool,
     },
 
+    /// Set the branch a submodule follows with `update --remote`
+    SetBranch {
+        /// Submodule path
+        #[arg(value_name = "PATH", help = "Submodule to configure")]
+        path: String,
+
+        /// Branch to follow
+        #[arg(short, long, value_name = "BRANCH", required_unless_present = "default", help = "Remote branch to follow")]
+        branch: Option<String>,
+
+        /// Follow the remote's default branch
+        #[arg(short, long, conflicts_with = "branch", help = "Follow the remote's default branch again")]
+        default: bool,
+    },
+
     /// Sync submodule URLs from .gitmodules
     Sync {
         /// Specific submodule paths
@@ -533,6 +822,13 @@ pub enum SubmoduleCommands {
         recursive: bool,
     },
 
+    /// Commit staged work in every submodule, then the new pointers in the superproject
+    CommitAll {
+        /// Message for every commit
+        #[arg(short, long, value_name = "MESSAGE", help = "Commit message")]
+        message: String,
+    },
+
     /// Remove a submodule (deinitialize and remove)
     #[command(visible_alias = "rm")]
     Deinit {
@@ -565,12 +861,241 @@ pub enum SubmoduleCommands {
     },
 }
 
+#[derive(Args, Debug)]
+pub struct SubtreeArgs {
+    #[command(subcommand)]
+    pub action: SubtreeCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SubtreeCommands {
+    /// Add a repository's history under a directory
+    Add {
+        /// Directory to put the repository in
+        #[arg(short = 'P', long, value_name = "DIR", help = "Directory for the subtree")]
+        prefix: String,
+
+        /// Remote name or URL to add
+        #[arg(value_name = "REPOSITORY", help = "Remote name or repository URL")]
+        repository: String,
+
+        /// Branch or tag to add
+        #[arg(value_name = "REF", help = "Branch or tag to add")]
+        reference: String,
+
+        /// Bring in one squashed commit instead of the whole history
+        #[arg(long, help = "Squash upstream history into a single commit")]
+        squash: bool,
+
+        /// Message for the merge commit
+        #[arg(short, long, value_name = "MESSAGE", help = "Merge commit message")]
+        message: Option<String>,
+    },
+
+    /// Merge upstream changes into a subtree
+    Pull {
+        /// Directory of the subtree
+        #[arg(short = 'P', long, value_name = "DIR", help = "Directory of the subtree")]
+        prefix: String,
+
+        /// Remote name or URL (default: the one it was added from)
+        #[arg(value_name = "REPOSITORY", help = "Remote name or repository URL")]
+        repository: Option<String>,
+
+        /// Branch or tag to merge (default: the one it was added from)
+        #[arg(value_name = "REF", requires = "repository", help = "Branch or tag to merge")]
+        reference: Option<String>,
+
+        /// Bring in one squashed commit instead of the whole history
+        #[arg(long, help = "Squash upstream history into a single commit")]
+        squash: bool,
+
+        /// Message for the merge commit
+        #[arg(short, long, value_name = "MESSAGE", help = "Merge commit message")]
+        message: Option<String>,
+    },
+
+    /// Push the subtree's history back to its upstream
+    Push {
+        /// Directory of the subtree
+        #[arg(short = 'P', long, value_name = "DIR", help = "Directory of the subtree")]
+        prefix: String,
+
+        /// Remote name or URL (default: the one it was added from)
+        #[arg(value_name = "REPOSITORY", help = "Remote name or repository URL")]
+        repository: Option<String>,
+
+        /// Branch to push to (default: the one it was added from)
+        #[arg(value_name = "REF", requires = "repository", help = "Branch to push to")]
+        reference: Option<String>,
+    },
+
+    /// Extract the subtree's history as commits rooted at the directory
+    Split {
+        /// Directory of the subtree
+        #[arg(short = 'P', long, value_name = "DIR", help = "Directory of the subtree")]
+        prefix: String,
+
+        /// Create or fast-forward this branch to the split history
+        #[arg(short, long, value_name = "BRANCH", help = "Branch for the split history")]
+        branch: Option<String>,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct WorkspaceArgs {
+    #[command(subcommand)]
+    pub action: WorkspaceCommands,
+
+    /// Maximum number of repositories processed at once
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "JOBS",
+        help = "Number of repositories to process concurrently"
+    )]
+    pub jobs: Option<usize>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum WorkspaceCommands {
+    /// Create a workspace manifest in the current directory
+    Init {
+        /// Register every git repository found directly below the workspace
+        #[arg(long, help = "Register repositories found in subdirectories")]
+        scan: bool,
+    },
+
+    /// Register a repository in the workspace
+    Add {
+        /// Path to the repository
+        #[arg(value_name = "PATH", help = "Repository path to register")]
+        path: PathBuf,
+
+        /// Name used in reports
+        #[arg(long, value_name = "NAME", help = "Name for the repository")]
+        name: Option<String>,
+    },
+
+    /// Unregister a repository from the workspace
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// Name of the repository
+        #[arg(value_name = "NAME", help = "Repository name to unregister")]
+        name: String,
+    },
+
+    /// List registered repositories
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// Show status of every repository
+    #[command(visible_alias = "st")]
+    Status,
+
+    /// Fast-forward every clean repository from its upstream
+    Pull,
+
+    /// Push every repository that has local commits
+    Push,
+
+    /// Run a shell command in every repository
+    Foreach {
+        /// Command to execute
+        #[arg(value_name = "COMMAND", help = "Command to execute")]
+        command: String,
+    },
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: Option<ConfigCommands>,
+
+    /// Operate on git configuration instead of rgit settings
+    #[arg(long, global = true, help = "Operate on git configuration instead of rgit settings")]
+    pub git: bool,
+
+    /// Use the user-wide git configuration
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "local",
+        help = "Use the global git configuration (implies --git)"
+    )]
+    pub global: bool,
+
+    /// Use the repository git configuration
+    #[arg(long, global = true, help = "Use the repository git configuration (implies --git)")]
+    pub local: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Print the value of a configuration key
+    Get {
+        /// Key to read (e.g. ui.theme or user.email)
+        #[arg(value_name = "KEY", help = "Configuration key to read")]
+        key: String,
+    },
+
+    /// Set a configuration key
+    Set {
+        /// Key to write
+        #[arg(value_name = "KEY", help = "Configuration key to write")]
+        key: String,
+
+        /// New value
+        #[arg(value_name = "VALUE", help = "Value to store")]
+        value: String,
+    },
+
+    /// Remove a key (rgit settings are reset to their defaults)
+    Unset {
+        /// Key to remove
+        #[arg(value_name = "KEY", help = "Configuration key to remove")]
+        key: String,
+    },
+
+    /// List configuration values
+    #[command(visible_alias = "ls")]
+    List,
+
+    /// Browse and edit settings interactively
+    Edit,
+
+    /// Show configuration file locations
+    Path,
+}
+
+#[derive(Args, Debug)]
+pub struct CredentialArgs {
+    #[command(subcommand)]
+    pub action: CredentialCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CredentialCommands {
+    /// Forget every cached credential and stop the cache daemon
+    Clear,
+
+    /// Serve the credential cache; rgit starts it when needed
+    #[command(hide = true)]
+    Daemon {
+        /// Socket to listen on
+        #[arg(long, value_name = "PATH")]
+        socket: PathBuf,
+    },
+}
+
 // Additional command argument structs with comprehensive options...
 #[derive(Args, Debug)]
 pub struct PullArgs {
     pub remote: Option<String>,
     pub branch: Option<String>,
-    #[arg(short, long)]
+    /// Replay local commits on top of the fetched ones
+    #[arg(short, long, conflicts_with_all = ["ff_only", "no_rebase"])]
     pub rebase: bool,
     #[arg(long)]
     pub no_edit: bool,
@@ -578,20 +1103,39 @@ pub struct PullArgs {
     pub no_commit: bool,
     #[arg(short, long)]
     pub force: bool,
-    #[arg(long)]
+    /// Only fast-forward; fail if the branches have diverged
+    #[arg(long, conflicts_with = "no_rebase")]
     pub ff_only: bool,
+    /// Merge even when pull.mode says otherwise
+    #[arg(long)]
+    pub no_rebase: bool,
+    /// Stash local changes before pulling and reapply them afterwards
+    #[arg(long)]
+    pub autostash: bool,
 }
-#[derive(Args, Debug)]
+#[derive(Args, Debug, Default)]
 pub struct FetchArgs {
     pub remote: Option<String>,
-    #[arg(long)]
+    /// Refspecs to fetch instead of the remote's configured ones
+    #[arg(value_name = "REFSPEC", requires = "remote")]
+    pub refspecs: Vec<String>,
+    #[arg(long, conflicts_with = "refspecs")]
     pub all: bool,
-    #[arg(long)]
+    /// Delete remote-tracking branches that no longer exist on the remote
+    #[arg(short, long)]
     pub prune: bool,
-    #[arg(long)]
+    /// Also delete local tags that no longer exist on the remote (implies --prune)
+    #[arg(short = 'P', long)]
+    pub prune_tags: bool,
+    /// Show what would change without updating any refs (set by --dry-run)
+    #[arg(skip)]
     pub dry_run: bool,
-    #[arg(long)]
+    /// Fetch every tag, not only tags pointing at fetched commits
+    #[arg(short, long, conflicts_with = "no_tags")]
     pub tags: bool,
+    /// Don't fetch any tags
+    #[arg(long)]
+    pub no_tags: bool,
     #[arg(long)]
     pub depth: Option<u32>,
     #[arg(long)]
@@ -618,6 +1162,21 @@ pub struct BranchArgs {
     pub no_merged: bool,
     #[arg(long)]
     pub remotes: bool,
+    /// Skip the automatic fetch configured by fetch.auto
+    #[arg(long)]
+    pub no_fetch: bool,
+    /// Lay branch names out in columns sized to the terminal
+    #[arg(long)]
+    pub column: bool,
+    /// Sort by refname, version:refname, committerdate, authordate or creatordate; prefix with - to reverse
+    #[arg(long, value_name = "KEY", allow_hyphen_values = true)]
+    pub sort: Option<String>,
+    /// Make the branch (default: current) track UPSTREAM, e.g. origin/main
+    #[arg(short = 'u', long, value_name = "UPSTREAM")]
+    pub set_upstream_to: Option<String>,
+    /// Stop the branch (default: current) tracking its upstream
+    #[arg(long, conflicts_with = "set_upstream_to")]
+    pub unset_upstream: bool,
 }
 #[derive(Args, Debug)]
 pub struct CheckoutArgs {
@@ -626,14 +1185,52 @@ pub struct CheckoutArgs {
     pub new_branch: bool,
     #[arg(short = 'B', long)]
     pub force_new_branch: bool,
+    /// Start TARGET as a new branch with no history and an empty index
+    #[arg(long, conflicts_with_all = ["new_branch", "force_new_branch"])]
+    pub orphan: bool,
     #[arg(short, long)]
     pub force: bool,
+    /// Carry local changes that are in the way over with a three-way merge
+    #[arg(short, long, conflicts_with = "force")]
+    pub merge: bool,
     #[arg(long)]
     pub track: bool,
     #[arg(long)]
     pub no_track: bool,
 }
 #[derive(Args, Debug)]
+pub struct SwitchArgs {
+    /// Branch to switch to (`-` for the previous one), or the start point with -c/--force-create
+    pub target: Option<String>,
+    /// Create a new branch and switch to it
+    #[arg(short = 'c', long, value_name = "NEW_BRANCH", conflicts_with = "force_create")]
+    pub create: Option<String>,
+    /// Create or reset a branch and switch to it (`-C` is taken by the global --directory)
+    #[arg(long, value_name = "NEW_BRANCH")]
+    pub force_create: Option<String>,
+    /// Switch to a commit, leaving HEAD detached
+    #[arg(short, long, conflicts_with_all = ["create", "force_create"])]
+    pub detach: bool,
+    /// Start a new branch with no history, clearing the index and tracked files
+    #[arg(long, value_name = "NEW_BRANCH", conflicts_with_all = ["target", "create", "force_create", "detach"])]
+    pub orphan: Option<String>,
+    /// Throw away local changes that are in the way
+    #[arg(short = 'f', long, alias = "force")]
+    pub discard_changes: bool,
+    /// Carry local changes that are in the way over with a three-way merge
+    #[arg(short, long, conflicts_with = "discard_changes")]
+    pub merge: bool,
+    /// Set the start point as the new branch's upstream
+    #[arg(short, long, conflicts_with = "no_track")]
+    pub track: bool,
+    /// Don't set an upstream for the new branch
+    #[arg(long)]
+    pub no_track: bool,
+    /// Don't create a tracking branch from a same-named remote branch
+    #[arg(long)]
+    pub no_guess: bool,
+}
+#[derive(Args, Debug)]
 pub struct LogArgs {
     #[arg(short, long, default_value = "10")]
     pub limit: usize,
@@ -641,32 +1238,111 @@ pub struct LogArgs {
     pub oneline: bool,
     #[arg(long)]
     pub graph: bool,
+    /// Show commits from all branches, remotes and tags
+    #[arg(long)]
+    pub all: bool,
     #[arg(long)]
     pub decorate: bool,
     #[arg(long)]
     pub stat: bool,
-    pub file: Option<String>,
+    /// Revision or range followed by paths; only commits touching the paths are shown
+    #[arg(value_name = "REVISION|PATHS")]
+    pub paths: Vec<String>,
     #[arg(long)]
     pub since: Option<String>,
     #[arg(long)]
     pub until: Option<String>,
     #[arg(long)]
     pub author: Option<String>,
+    /// Only show commits whose message matches a regular expression
+    #[arg(long, value_name = "REGEX")]
+    pub grep: Option<String>,
+    /// Only show commits that change the number of occurrences of a string
+    #[arg(short = 'S', value_name = "STRING")]
+    pub pickaxe: Option<String>,
+    /// Treat the -S string as a regular expression
+    #[arg(long, requires = "pickaxe")]
+    pub pickaxe_regex: bool,
+    /// Only show commits with added or removed lines matching a regular expression
+    #[arg(short = 'G', value_name = "REGEX", conflicts_with = "pickaxe")]
+    pub diff_grep: Option<String>,
+    /// Select commits interactively and cherry-pick them onto the current branch
+    #[arg(short, long)]
+    pub interactive: bool,
+    /// Revision or range to list commits from (defaults to HEAD)
+    #[arg(long, value_name = "REV")]
+    pub rev: Option<String>,
+    /// Keep listing the history of a single file across renames
+    #[arg(long)]
+    pub follow: bool,
+    #[command(flatten)]
+    pub renames: RenameArgs,
 }
 #[derive(Args, Debug)]
 pub struct DiffArgs {
-    pub target: Option<String>,
-    #[arg(long)]
+    /// Revisions to compare (`A`, `A B`, `A..B` or `A...B`), then paths
+    #[arg(value_name = "REV|PATH")]
+    pub args: Vec<String>,
+    /// Paths to limit the diff to; everything after `--` is a path
+    #[arg(last = true, value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// Show staged changes, against HEAD or the given commit
+    #[arg(long, visible_alias = "cached")]
     pub staged: bool,
     #[arg(long)]
-    pub cached: bool,
-    pub file: Option<String>,
-    #[arg(long)]
     pub word_diff: bool,
-    #[arg(long)]
+    /// Show a histogram of lines changed per file
+    #[arg(long, group = "summary")]
     pub stat: bool,
-    #[arg(long)]
+    /// Show only the totals line of --stat
+    #[arg(long, group = "summary")]
+    pub shortstat: bool,
+    /// Show added and removed line counts per file, tab separated
+    #[arg(long, group = "summary")]
+    pub numstat: bool,
+    /// Show only the names of changed files
+    #[arg(long, group = "summary")]
     pub name_only: bool,
+    /// Show the names and status letters of changed files
+    #[arg(long, group = "summary")]
+    pub name_status: bool,
+    /// Open each changed file in a diff tool (vscode, meld, kdiff3, vimdiff,
+    /// araxis or a configured one); `--tool` alone uses the configured tool
+    #[arg(long, value_name = "TOOL", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+    pub tool: Option<String>,
+    #[command(flatten)]
+    pub renames: RenameArgs,
+    #[command(flatten)]
+    pub content: DiffContentArgs,
+}
+/// Whitespace and context options shared by diff and show
+#[derive(Args, Debug, Default, Clone)]
+pub struct DiffContentArgs {
+    /// Ignore whitespace when comparing lines
+    #[arg(short = 'w', long)]
+    pub ignore_all_space: bool,
+    /// Ignore changes that only add or remove blank lines
+    #[arg(long)]
+    pub ignore_blank_lines: bool,
+    /// Show the whole function around each change as context
+    #[arg(short = 'W', long)]
+    pub function_context: bool,
+}
+/// Rename and copy detection shared by diff, show and log; unset options
+/// fall back to `diff.renames` and `diff.rename_threshold`
+#[derive(Args, Debug, Default, Clone)]
+pub struct RenameArgs {
+    /// Report renamed files, optionally with a similarity threshold (-M=75)
+    #[arg(short = 'M', long, value_name = "PERCENT", num_args = 0..=1, require_equals = true,
+          value_parser = clap::value_parser!(u16).range(0..=100))]
+    pub find_renames: Option<Option<u16>>,
+    /// Report copied files as well as renamed ones (--find-copies=75)
+    #[arg(long, value_name = "PERCENT", num_args = 0..=1, require_equals = true,
+          value_parser = clap::value_parser!(u16).range(0..=100))]
+    pub find_copies: Option<Option<u16>>,
+    /// Show moved files as a deletion and an addition
+    #[arg(long, conflicts_with_all = ["find_renames", "find_copies"])]
+    pub no_renames: bool,
 }
 #[derive(Args, Debug)]
 pub struct SyncArgs {
@@ -678,7 +1354,11 @@ pub struct SyncArgs {
     pub force: bool,
     #[arg(long)]
     pub submodules: bool,
-    #[arg(long)]
+    /// Fast-forward every local branch that is behind its upstream, without pushing
+    #[arg(short, long, conflicts_with_all = ["push_only", "pull_only", "force", "submodules"])]
+    pub all: bool,
+    /// Set by --dry-run
+    #[arg(skip)]
     pub dry_run: bool,
 }
 #[derive(Args, Debug)]
@@ -691,91 +1371,352 @@ pub struct QuickCommitArgs {
     pub push: bool,
     #[arg(long)]
     pub amend: bool,
+    /// Commit even if the staged changes look like they contain secrets
+    #[arg(long)]
+    pub allow_secrets: bool,
+    /// Commit files above commit.max_file_size
+    #[arg(long)]
+    pub allow_large_files: bool,
+    #[command(flatten)]
+    pub trailers: TrailerArgs,
 }
 #[derive(Args, Debug)]
 pub struct UndoArgs {
-    #[arg(short, long, default_value = "1")]
-    pub commits: usize,
-    #[arg(long)]
-    pub operation: Option<String>,
-    #[arg(long)]
+    /// Undo the last N commits on the current branch instead of an operation
+    #[arg(short, long, value_name = "N", conflicts_with_all = ["operation", "interactive", "list"])]
+    pub commits: Option<usize>,
+    /// Undo this operation from `rgit undo --list` (1 is the most recent)
+    #[arg(long, value_name = "NUMBER")]
+    pub operation: Option<usize>,
+    /// Browse the operation journal, preview what each undo changes and pick one
+    #[arg(short, long, conflicts_with = "operation")]
+    pub interactive: bool,
+    /// List the operations that can be undone
+    #[arg(short, long, conflicts_with_all = ["operation", "interactive"])]
+    pub list: bool,
+    /// Move the checked-out branch without touching the index or working tree
+    #[arg(long, conflicts_with = "hard")]
     pub soft: bool,
+    /// Discard uncommitted changes when moving the checked-out branch
     #[arg(long)]
     pub hard: bool,
 }
 #[derive(Args, Debug)]
 pub struct CleanArgs {
+    /// Only clean files under these paths
+    pub paths: Vec<String>,
+    /// Remove without asking which files to keep
     #[arg(short, long)]
     pub force: bool,
-    #[arg(short, long)]
+    /// Also remove ignored files
+    #[arg(short = 'x', long)]
     pub ignored: bool,
-    #[arg(short, long)]
+    /// Remove only ignored files
+    #[arg(short = 'X', long, conflicts_with = "ignored")]
+    pub only_ignored: bool,
+    /// List what would be removed without touching anything
+    #[arg(short = 'n', id = "clean_dry_run")]
     pub dry_run: bool,
+    /// Also remove untracked directories
     #[arg(short, long)]
     pub directories: bool,
+    /// Pick the files to remove from a list, even with --force
     #[arg(short, long)]
     pub interactive: bool,
+    /// Where removed files go, overriding clean.trash
+    #[arg(long, value_enum, value_name = "WHERE")]
+    pub trash: Option<TrashTarget>,
+    /// Put back the files moved to the repository trash by the last clean
+    #[arg(long, conflicts_with_all = ["force", "clean_dry_run", "interactive", "trash"])]
+    pub restore: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrashTarget {
+    /// Keep removed files under .git/rgit/trash
+    Repo,
+    /// Move removed files to the desktop trash
+    System,
+    /// Delete removed files outright
+    Off,
 }
 #[derive(Args, Debug)]
 pub struct MergeArgs {
-    pub branch: String,
-    #[arg(long)]
+    /// Branch or commit to merge into the current branch
+    #[arg(required_unless_present_any = ["continue_merge", "abort", "skip"])]
+    pub branch: Option<String>,
+    /// Always create a merge commit, even when a fast-forward is possible
+    #[arg(long, conflicts_with_all = ["ff_only", "squash"])]
     pub no_ff: bool,
+    /// Only merge when the current branch can be fast-forwarded
+    #[arg(long, conflicts_with = "squash")]
+    pub ff_only: bool,
+    /// Merge but stop before committing
     #[arg(long)]
     pub no_commit: bool,
+    /// Stage the combined changes as one commit's worth, without a merge
     #[arg(long)]
     pub squash: bool,
     #[arg(short, long)]
     pub message: Option<String>,
+    /// Option for the merge machinery: ours, theirs, union, patience, minimal,
+    /// ignore-space-change, ignore-all-space, ignore-space-at-eol,
+    /// find-renames[=<n>], no-renames
+    #[arg(short = 'X', long = "strategy-option", value_name = "OPTION")]
+    pub strategy_options: Vec<String>,
+    /// Conclude a merge that stopped on conflicts
+    #[arg(long = "continue", conflicts_with_all = ["abort", "skip"])]
+    pub continue_merge: bool,
+    /// Give up on the merge and restore the pre-merge state
+    #[arg(long, conflicts_with = "skip")]
+    pub abort: bool,
+    /// Accepted for symmetry with rebase and cherry-pick; a merge has no steps to skip
+    #[arg(long)]
+    pub skip: bool,
+}
+#[derive(Args, Debug)]
+pub struct MergetoolArgs {
+    /// Conflicted files to open; all of them by default
+    pub paths: Vec<String>,
+    /// Merge tool to use instead of the configured one
+    #[arg(short, long)]
+    pub tool: Option<String>,
+    /// List known merge tools and whether they are installed
+    #[arg(long)]
+    pub tool_help: bool,
 }
 #[derive(Args, Debug)]
 pub struct RebaseArgs {
-    pub target: Option<String>,
+    /// Upstream whose commits are left out (defaults to the branch's upstream)
+    pub upstream: Option<String>,
+    /// Branch to rebase instead of the current one
+    pub branch: Option<String>,
+    /// Replay the commits onto this commit instead of the upstream
+    #[arg(long, value_name = "NEWBASE")]
+    pub onto: Option<String>,
     #[arg(short, long)]
     pub interactive: bool,
-    #[arg(long)]
+    /// Resume after resolving conflicts
+    #[arg(long = "continue", conflicts_with_all = ["abort", "skip"])]
     pub continue_rebase: bool,
-    #[arg(long)]
+    /// Stop and restore the branch to where it was
+    #[arg(long, conflicts_with = "skip")]
     pub abort: bool,
+    /// Drop the commit that stopped on conflicts and go on with the rest
     #[arg(long)]
     pub skip: bool,
 }
 #[derive(Args, Debug)]
 pub struct CherryPickArgs {
+    /// Commits or ranges (A..B) to apply, oldest first
     pub commits: Vec<String>,
     #[arg(short, long)]
     pub no_commit: bool,
+    /// Edit each commit message before committing
     #[arg(short, long)]
     pub edit: bool,
-    #[arg(long)]
+    /// Resume after resolving conflicts
+    #[arg(long = "continue", conflicts_with_all = ["abort", "skip"])]
     pub continue_pick: bool,
+    /// Stop and restore the branch to where it was
+    #[arg(long, conflicts_with = "skip")]
+    pub abort: bool,
+    /// Drop the commit that stopped on conflicts and go on with the rest
+    #[arg(long)]
+    pub skip: bool,
+}
+#[derive(Args, Debug)]
+pub struct RevertArgs {
+    /// Commits or ranges to revert (ranges are reverted newest first)
+    pub commits: Vec<String>,
+    /// Parent number (starting at 1) whose side of a merge to keep
+    #[arg(short, long, value_name = "PARENT")]
+    pub mainline: Option<u32>,
+    /// Apply the reverts to the index without committing
+    #[arg(short, long)]
+    pub no_commit: bool,
+    /// Edit each revert message
+    #[arg(short, long)]
+    pub edit: bool,
+    #[arg(long = "continue")]
+    pub continue_revert: bool,
     #[arg(long)]
     pub abort: bool,
 }
 #[derive(Args, Debug)]
 pub struct ShowArgs {
-    pub commit: Option<String>,
+    /// Object to show: a commit, tag, tree or blob (e.g. HEAD:src/main.rs)
+    #[arg(value_name = "OBJECT")]
+    pub object: Option<String>,
     #[arg(long)]
     pub stat: bool,
     #[arg(long)]
     pub name_only: bool,
+    /// Print blobs without syntax highlighting
+    #[arg(long)]
+    pub no_highlight: bool,
+    /// Browse the files of the commit or tree interactively
+    #[arg(long, conflicts_with_all = ["stat", "name_only"])]
+    pub tree: bool,
+    #[command(flatten)]
+    pub renames: RenameArgs,
+    #[command(flatten)]
+    pub content: DiffContentArgs,
+}
+#[derive(Args, Debug)]
+pub struct CatArgs {
+    /// File to print as <rev>:<path>, or a blob id
+    #[arg(value_name = "OBJECT")]
+    pub object: String,
+    /// Print the blob's id, size and text as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+#[derive(Args, Debug)]
+pub struct LsArgs {
+    /// Commit or tree to list
+    #[arg(value_name = "REV", default_value = "HEAD")]
+    pub revision: String,
+    /// Directory or file to list, relative to the current directory
+    #[arg(value_name = "PATH")]
+    pub path: Option<String>,
+    /// List the files in subdirectories too
+    #[arg(short, long)]
+    pub recursive: bool,
+    /// Print only the paths
+    #[arg(long, conflicts_with = "json")]
+    pub name_only: bool,
+    /// Print the entries as a JSON array
+    #[arg(long)]
+    pub json: bool,
+}
+#[derive(Args, Debug)]
+pub struct HashArgs {
+    /// Files to hash
+    #[arg(value_name = "FILE", required_unless_present = "stdin")]
+    pub files: Vec<String>,
+    /// Hash what is read from standard input
+    #[arg(long)]
+    pub stdin: bool,
+    /// Also store the content as a blob in the repository
+    #[arg(short, long)]
+    pub write: bool,
+}
+#[derive(Args, Debug)]
+pub struct ObjectArgs {
+    /// Object id or any revision, e.g. HEAD^{tree} or HEAD:src/main.rs
+    #[arg(value_name = "OBJECT")]
+    pub object: String,
+    /// Print only the object's type
+    #[arg(short = 't', long = "type", conflicts_with_all = ["size", "json"])]
+    pub r#type: bool,
+    /// Print only the content size in bytes
+    #[arg(short, long, conflicts_with = "json")]
+    pub size: bool,
+    /// Print the id, type, size and content as JSON
+    #[arg(long)]
+    pub json: bool,
 }
 #[derive(Args, Debug)]
 pub struct GrepArgs {
     pub pattern: String,
+    /// Only search these paths
     pub files: Vec<String>,
     #[arg(short, long)]
     pub ignore_case: bool,
     #[arg(short, long)]
     pub line_number: bool,
+    /// Only match whole words
+    #[arg(short, long)]
+    pub word_regexp: bool,
+    /// Treat the pattern as a literal string
+    #[arg(short = 'F', long, conflicts_with = "perl_regexp")]
+    pub fixed_strings: bool,
+    /// Use Perl-style regex with lookaround and backreferences
+    #[arg(short = 'P', long)]
+    pub perl_regexp: bool,
+    /// Lines of context to show after each match
+    #[arg(short = 'A', long, value_name = "NUM")]
+    pub after_context: Option<usize>,
+    /// Lines of context to show before each match
+    #[arg(short = 'B', long, value_name = "NUM")]
+    pub before_context: Option<usize>,
+    /// Lines of context to show around each match (-C is --directory)
+    #[arg(long, value_name = "NUM")]
+    pub context: Option<usize>,
+    /// Search the staged contents instead of the working tree
+    #[arg(long, conflicts_with = "tree")]
+    pub cached: bool,
+    /// Search a commit, tag or tree instead of the working tree
+    #[arg(long, value_name = "TREE-ISH")]
+    pub tree: Option<String>,
+    /// Only print the names of files with matches
+    #[arg(long)]
+    pub files_with_matches: bool,
+    /// Print the number of matching lines in each file
+    #[arg(short, long, conflicts_with = "files_with_matches")]
+    pub count: bool,
+    /// Files to scan in parallel (default: advanced.performance.threads)
+    #[arg(short = 'j', long, value_name = "N")]
+    pub threads: Option<usize>,
 }
 #[derive(Args, Debug)]
 pub struct BlameArgs {
     pub file: String,
-    #[arg(short, long)]
+    /// Only blame these lines, e.g. 10,20 or 10,+5
+    #[arg(short, long, value_name = "START,END")]
     pub line_range: Option<String>,
+    /// Find when lines were last present instead of added (not supported yet)
     #[arg(short, long)]
     pub reverse: bool,
+    /// Show lines per author with their share and last change instead of the lines
+    #[arg(short, long)]
+    pub summary: bool,
+    /// Color each line by the age of its commit, newest hottest
+    #[arg(long)]
+    pub heatmap: bool,
+}
+#[derive(Args, Debug)]
+pub struct WhoArgs {
+    /// Files, directories or globs to look up, e.g. src/net or '*.toml'
+    #[arg(required = true, value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// How many committers to list
+    #[arg(short = 'n', long, default_value_t = 5)]
+    pub limit: usize,
+    /// Only count commits after this date
+    #[arg(long, value_name = "DATE", default_value = "1 year ago")]
+    pub since: String,
+}
+#[derive(Args, Debug)]
+pub struct TodosArgs {
+    /// Only scan these files or directories
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// Only list markers added by this author (part of a name or email)
+    #[arg(long, value_name = "AUTHOR")]
+    pub author: Option<String>,
+    /// List at most this many markers
+    #[arg(short = 'n', long)]
+    pub limit: Option<usize>,
+    /// Print the markers as a JSON array
+    #[arg(long)]
+    pub json: bool,
+}
+#[derive(Args, Debug)]
+pub struct OwnersArgs {
+    #[command(subcommand)]
+    pub action: OwnersCommands,
+}
+#[derive(Subcommand, Debug)]
+pub enum OwnersCommands {
+    /// Report invalid lines and patterns that match no tracked files
+    Check,
+    /// Show the owners of each path and the rule that decides them
+    Of {
+        #[arg(required = true, value_name = "PATH")]
+        paths: Vec<String>,
+    },
 }
 #[derive(Args, Debug)]
 pub struct RemoteArgs {
@@ -808,10 +1749,80 @@ pub enum RemoteCommands {
         name: Option<String>,
     },
 }
+
+#[derive(Args, Debug, Default)]
+pub struct LsRemoteArgs {
+    /// Remote name or URL (default: the current branch's remote, or origin)
+    #[arg(value_name = "REPOSITORY")]
+    pub repository: Option<String>,
+    /// Only show refs matching these patterns, e.g. main or refs/tags/v1.*
+    #[arg(value_name = "PATTERN")]
+    pub patterns: Vec<String>,
+    /// Only show branches
+    #[arg(long, visible_alias = "branches")]
+    pub heads: bool,
+    /// Only show tags
+    #[arg(short, long)]
+    pub tags: bool,
+    /// Leave out HEAD and peeled tags
+    #[arg(long)]
+    pub refs: bool,
+    /// Show what symbolic refs such as HEAD point to
+    #[arg(long)]
+    pub symref: bool,
+    /// Fail when no ref matches
+    #[arg(long)]
+    pub exit_code: bool,
+}
+#[derive(Args, Debug)]
+pub struct MirrorArgs {
+    #[command(subcommand)]
+    pub action: MirrorCommands,
+}
+#[derive(Subcommand, Debug)]
+pub enum MirrorCommands {
+    /// Fetch every ref of the mirrored remotes, dropping refs they deleted
+    Update {
+        /// Mirrors to update (default: the current directory)
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+        /// Keep running and update again every INTERVAL, e.g. 15m or 1h
+        #[arg(long, value_name = "INTERVAL")]
+        every: Option<String>,
+        /// Keep refs the remote has deleted
+        #[arg(long)]
+        no_prune: bool,
+    },
+}
 #[derive(Args, Debug)]
 pub struct TagArgs {
     #[command(subcommand)]
     pub action: Option<TagCommands>,
+    /// Listing options used when no action is given
+    #[command(flatten)]
+    pub list: TagListArgs,
+}
+#[derive(Args, Debug, Default, Clone)]
+pub struct TagListArgs {
+    /// Lay tag names out in columns sized to the terminal
+    #[arg(long)]
+    pub column: bool,
+    /// Sort by refname, version:refname, committerdate, authordate or creatordate; prefix with - to reverse
+    /// (-creatordate lists the newest tags first)
+    #[arg(long, value_name = "KEY", allow_hyphen_values = true)]
+    pub sort: Option<String>,
+    /// Only list tags whose commit contains this commit
+    #[arg(long, value_name = "COMMIT")]
+    pub contains: Option<String>,
+    /// Only list tags that point at this object
+    #[arg(long, value_name = "OBJECT")]
+    pub points_at: Option<String>,
+    /// Only list tags reachable from this commit (default: HEAD)
+    #[arg(long, value_name = "COMMIT", num_args = 0..=1, default_missing_value = "HEAD")]
+    pub merged: Option<String>,
+    /// Only list tags not reachable from this commit (default: HEAD)
+    #[arg(long, value_name = "COMMIT", num_args = 0..=1, default_missing_value = "HEAD")]
+    pub no_merged: Option<String>,
 }
 #[derive(Subcommand, Debug)]
 pub enum TagCommands {
@@ -827,7 +1838,10 @@ pub enum TagCommands {
         name: String,
     },
     List {
-        pattern: Option<String>,
+        /// Only list tags matching one of these glob patterns (e.g. 'v1.*')
+        patterns: Vec<String>,
+        #[command(flatten)]
+        options: TagListArgs,
     },
     Show {
         name: String,
@@ -835,21 +1849,27 @@ pub enum TagCommands {
 }
 #[derive(Args, Debug)]
 pub struct StashArgs {
+    /// What to do; stashes all local changes when omitted
     #[command(subcommand)]
     pub action: Option<StashCommands>,
 }
 #[derive(Subcommand, Debug)]
 pub enum StashCommands {
+    /// Stash local changes, optionally only some paths
+    Push(StashPushArgs),
     Save {
         message: Option<String>,
         #[arg(short, long)]
         include_untracked: bool,
     },
     List,
-    Apply {
-        index: Option<usize>,
-    },
-    Pop {
+    /// Restore a stash entry, or chosen files or hunks of it
+    Apply(StashApplyArgs),
+    /// Restore a stash entry and drop what was restored
+    Pop(StashApplyArgs),
+    /// Create a branch where a stash was made and restore it there
+    Branch {
+        name: String,
         index: Option<usize>,
     },
     Drop {
@@ -861,6 +1881,53 @@ pub enum StashCommands {
     Clear,
 }
 #[derive(Args, Debug)]
+pub struct WipArgs {
+    /// What to do; saves a WIP commit when omitted
+    #[command(subcommand)]
+    pub action: Option<WipCommands>,
+    /// Describe the work in progress
+    #[arg(short, long, value_name = "MESSAGE")]
+    pub message: Option<String>,
+}
+#[derive(Subcommand, Debug)]
+pub enum WipCommands {
+    /// Undo the WIP commit at HEAD, restoring what was staged and what was not
+    Pop,
+}
+#[derive(Args, Debug, Default)]
+pub struct StashApplyArgs {
+    /// Stash entry to restore (defaults to the latest)
+    pub index: Option<usize>,
+    /// Choose the files to restore from a list
+    #[arg(short, long)]
+    pub interactive: bool,
+    /// Choose individual hunks to restore
+    #[arg(short, long)]
+    pub patch: bool,
+    /// Restore only these files; the rest stays stashed
+    #[arg(last = true)]
+    pub paths: Vec<String>,
+}
+#[derive(Args, Debug, Default)]
+pub struct StashPushArgs {
+    /// Only stash changes to these paths
+    pub paths: Vec<String>,
+    #[arg(short, long)]
+    pub message: Option<String>,
+    /// Leave staged changes in the index and working tree
+    #[arg(short, long)]
+    pub keep_index: bool,
+    /// Also stash untracked files
+    #[arg(short = 'u', long)]
+    pub include_untracked: bool,
+    /// Also stash untracked and ignored files
+    #[arg(short, long, conflicts_with = "include_untracked")]
+    pub all: bool,
+    /// Choose the files to stash from a list
+    #[arg(short, long, conflicts_with = "paths")]
+    pub interactive: bool,
+}
+#[derive(Args, Debug)]
 pub struct BisectArgs {
     #[command(subcommand)]
     pub action: BisectCommands,
@@ -875,39 +1942,292 @@ pub enum BisectCommands {
 }
 #[derive(Args, Debug)]
 pub struct ReflogArgs {
+    /// Reference whose history to show (default: HEAD)
     pub reference: Option<String>,
-    #[arg(short, long)]
+    /// Show the history of every reference that has one
+    #[arg(short, long, conflicts_with = "interactive")]
     pub all: bool,
+    /// Browse the timeline, then diff, check out, branch from or restore an entry
+    #[arg(short, long)]
+    pub interactive: bool,
+    /// Show at most N entries per reference
+    #[arg(short = 'n', long, value_name = "N")]
+    pub max_count: Option<usize>,
+}
+#[derive(Args, Debug)]
+pub struct RecoverArgs {
+    /// Lost commit to recreate a branch at, instead of choosing from a list
+    pub commit: Option<String>,
+    /// Name for the recreated branch
+    #[arg(short, long)]
+    pub name: Option<String>,
+    /// Show the full diff of each lost line of history
+    #[arg(short, long)]
+    pub patch: bool,
+    /// Only list what can be recovered
+    #[arg(short, long, conflicts_with = "commit")]
+    pub list: bool,
+    /// Skip scanning the object database for dangling commits
+    #[arg(long)]
+    pub no_dangling: bool,
 }
 #[derive(Args, Debug)]
 pub struct GcArgs {
+    /// Recompute every delta with a wider search window (slow, smaller packs)
     #[arg(long)]
     pub aggressive: bool,
+    /// Prune unreachable objects older than EXPIRY (default gc.prune_expiry); `--prune` alone prunes them all
+    #[arg(long, value_name = "EXPIRY", num_args = 0..=1, require_equals = true, default_missing_value = "now")]
+    pub prune: Option<String>,
+    /// Only run when loose objects or packs exceed the gc.auto_* limits
     #[arg(long)]
-    pub prune: bool,
+    pub auto: bool,
+    /// Objects compared when looking for deltas (default 10, 250 with --aggressive)
+    #[arg(long, value_name = "N")]
+    pub window: Option<u32>,
+    /// Longest delta chain allowed (default 50)
+    #[arg(long, value_name = "N")]
+    pub depth: Option<u32>,
 }
 #[derive(Args, Debug)]
 pub struct FsckArgs {
+    /// Walk every tree and blob, not just commits and their root trees
     #[arg(long)]
     pub full: bool,
+    /// Treat warnings, such as dangling symbolic refs, as problems
     #[arg(long)]
     pub strict: bool,
+    /// Fix what can be fixed; all modes when none are listed
+    #[arg(long, value_enum, value_name = "MODE", num_args = 0.., value_delimiter = ',')]
+    pub repair: Option<Vec<RepairMode>>,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    /// Revision or range to audit (default: the last audit.max_commits commits of HEAD)
+    #[arg(value_name = "REVISION")]
+    pub revision: Option<String>,
+    /// Only audit commits and reflog entries after this date
+    #[arg(long, value_name = "DATE")]
+    pub since: Option<String>,
+    /// Also accept these authors (email, name or a pattern like *@example.com)
+    #[arg(long = "allow-author", value_name = "PATTERN")]
+    pub allow_authors: Vec<String>,
+    /// Report blobs larger than this (default: audit.max_blob_size)
+    #[arg(long, value_name = "SIZE")]
+    pub max_blob_size: Option<String>,
+    /// Check signatures with `git verify-commit` instead of only their presence
+    #[arg(long)]
+    pub verify_signatures: bool,
+    /// Report format
+    #[arg(long, value_enum, default_value_t = AuditFormat::Text)]
+    pub format: AuditFormat,
+    /// Write the report to FILE
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScanArgs {
+    /// Only scan these paths
+    #[arg(value_name = "PATH")]
+    pub paths: Vec<String>,
+    /// Scan every file in the index, not just the staged changes
+    #[arg(short, long)]
+    pub all: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FilterArgs {
+    /// Remove a file or directory from every commit
+    #[arg(long = "remove-path", value_name = "PATH")]
+    pub remove_paths: Vec<String>,
+    /// Remove a blob wherever it appears, by id or as REV:PATH
+    #[arg(long = "remove-blob", value_name = "BLOB")]
+    pub remove_blobs: Vec<String>,
+    /// Remove every blob larger than SIZE, e.g. 10MB
+    #[arg(long, value_name = "SIZE")]
+    pub strip_blobs_bigger_than: Option<String>,
+    /// Replace an author or committer email
+    #[arg(long = "email", value_name = "OLD=NEW")]
+    pub emails: Vec<String>,
+    /// Clear the executable bit on every file
+    #[arg(long)]
+    pub strip_modes: bool,
+    /// Only rewrite these branches or tags (default: all of them)
+    #[arg(long = "ref", value_name = "REF")]
+    pub refs: Vec<String>,
+    /// Report what would change without updating any refs
+    #[arg(short = 'n', id = "filter_dry_run")]
+    pub dry_run: bool,
+    /// Rewrite without asking for confirmation
+    #[arg(short, long)]
+    pub force: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ImportArgs {
+    /// Stream to read (default: standard input)
+    #[arg(value_name = "FILE")]
+    pub input: Option<PathBuf>,
+    /// Let refs move to commits that don't descend from where they are now
+    #[arg(short, long)]
+    pub force: bool,
+    /// Load marks saved by an earlier import, to continue from it
+    #[arg(long, value_name = "FILE")]
+    pub import_marks: Option<PathBuf>,
+    /// Save marks for a later incremental import
+    #[arg(long, value_name = "FILE")]
+    pub export_marks: Option<PathBuf>,
+    /// Skip checking the repository once the import is done
+    #[arg(long)]
+    pub no_verify: bool,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ExportArgs {
+    /// Branches and tags to export (default: all of them)
+    #[arg(value_name = "REF")]
+    pub refs: Vec<String>,
+    /// Write a git fast-import stream
+    #[arg(long, required = true)]
+    pub fast_export: bool,
+    /// Write the stream to FILE instead of standard output
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Skip objects in marks saved by an earlier export, to continue from it
+    #[arg(long, value_name = "FILE")]
+    pub import_marks: Option<PathBuf>,
+    /// Save marks for a later incremental export
+    #[arg(long, value_name = "FILE")]
+    pub export_marks: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditFormat {
+    /// Human-readable report
+    Text,
+    /// Machine-readable findings
+    Json,
+    /// Markdown tables for review documents
+    Markdown,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepairMode {
+    /// Point broken refs at their last good reflog entry, or delete them
+    Refs,
+    /// Drop corrupt packed-refs lines and recreate lost refs from reflogs
+    PackedRefs,
+    /// Remove corrupt loose objects that have an intact packed copy
+    Objects,
+    /// Download missing or corrupt objects from the remotes
+    Fetch,
 }
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct BackupArgs {
+    /// What to do; takes a snapshot when omitted
+    #[command(subcommand)]
+    pub action: Option<BackupCommands>,
+    /// Label for the snapshot
     pub name: Option<String>,
+    /// Also capture untracked files
     #[arg(long)]
     pub include_untracked: bool,
 }
+#[derive(Subcommand, Debug)]
+pub enum BackupCommands {
+    /// List snapshots, newest first
+    List,
+    /// Delete automatic snapshots beyond the retention limits
+    Prune {
+        /// Show what would be deleted
+        #[arg(short = 'n', id = "prune_dry_run")]
+        dry_run: bool,
+    },
+    /// Delete a snapshot
+    Drop {
+        id: String,
+    },
+    /// Put back the branch and files from a safety snapshot taken before a
+    /// destructive command (the newest when no id is given)
+    Recover {
+        id: Option<String>,
+    },
+}
 #[derive(Args, Debug)]
 pub struct RestoreArgs {
-    pub name: String,
+    /// Files or directories to restore (globs allowed)
+    #[arg(required_unless_present_any = ["from_backup", "patch"])]
+    pub paths: Vec<String>,
+    /// Restore refs or files from a backup snapshot, chosen from a list
+    /// when no id is given
+    #[arg(long, value_name = "SNAPSHOT", num_args = 0..=1, require_equals = true,
+          default_missing_value = "", conflicts_with = "source")]
+    pub from_backup: Option<String>,
+    /// Revision to take content from (default: the index, or HEAD with --staged)
+    #[arg(short, long, value_name = "REV")]
+    pub source: Option<String>,
+    /// Restore the index
+    #[arg(short = 'S', long)]
+    pub staged: bool,
+    /// Restore the working tree (the default without --staged)
+    #[arg(short = 'W', long)]
+    pub worktree: bool,
+    /// Interactively choose hunks to unstage, like `rgit unstage -p`
+    #[arg(short, long, requires = "staged", conflicts_with_all = ["source", "from_backup", "worktree"])]
+    pub patch: bool,
+    /// Discard local modifications without asking
     #[arg(short, long)]
     pub force: bool,
 }
+#[derive(Args, Debug)]
+pub struct DoctorArgs {
+    /// Report format; json and sarif go to stdout unless --output is given
+    #[arg(long, value_enum, default_value_t = DoctorFormat::Text)]
+    pub format: DoctorFormat,
+    /// Write the report to FILE
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+    /// Show recent warnings and errors from the log files instead of checking the repository
+    #[arg(long, conflicts_with_all = ["format", "output"])]
+    pub logs: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoctorFormat {
+    /// Human-readable report
+    Text,
+    /// Machine-readable list of checks
+    Json,
+    /// SARIF 2.1.0 for code scanning dashboards
+    Sarif,
+}
+
+#[derive(Args, Debug)]
+pub struct ExplainArgs {
+    /// Error code shown with the error, e.g. RG-0042; lists every code when omitted
+    #[arg(value_name = "CODE")]
+    pub code: Option<String>,
+}
+
 #[derive(Args, Debug)]
 pub struct LearnArgs {
     pub topic: Option<String>,
     #[arg(long)]
     pub interactive: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    /// Clap only reports clashing flags when a command is parsed, so check
+    /// every subcommand up front
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+}
@@ -0,0 +1,253 @@
+//! CODEOWNERS files in GitHub's format: one pattern per line, matched like
+//! .gitignore patterns, followed by the users, teams or emails that own the
+//! matching paths. The last matching line wins.
+
+use anyhow::Result;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::is_valid_email;
+
+/// Where a CODEOWNERS file may live, in the order GitHub looks
+pub const LOCATIONS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// A pattern and who owns the paths it matches
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// Line in the file, counting from 1
+    pub line: usize,
+    pub pattern: String,
+    /// Empty when the line leaves matching paths without an owner
+    pub owners: Vec<String>,
+    regex: Regex,
+}
+
+impl Rule {
+    /// Whether the rule applies to a repository-relative path
+    pub fn matches(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+}
+
+/// A line GitHub skips because it can't understand it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem {
+    pub line: usize,
+    pub message: String,
+}
+
+/// The rules of a CODEOWNERS file
+#[derive(Debug, Clone)]
+pub struct CodeOwners {
+    /// Where the file is, relative to the working tree
+    pub path: String,
+    pub rules: Vec<Rule>,
+    /// Lines left out of `rules`
+    pub problems: Vec<Problem>,
+}
+
+impl CodeOwners {
+    /// The CODEOWNERS file of the working tree, if there is one
+    pub fn load(workdir: &Path) -> Result<Option<Self>> {
+        for location in LOCATIONS {
+            let file = workdir.join(location);
+            if file.is_file() {
+                return Ok(Some(Self::parse(location, &fs::read_to_string(file)?)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parse CODEOWNERS content. Like GitHub, lines with invalid syntax are
+    /// skipped; they are listed in `problems`.
+    pub fn parse(path: &str, text: &str) -> Self {
+        let mut rules = Vec::new();
+        let mut problems = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            match parse_line(index + 1, line) {
+                Ok(Some(rule)) => rules.push(rule),
+                Ok(None) => {}
+                Err(message) => problems.push(Problem { line: index + 1, message }),
+            }
+        }
+        Self { path: path.to_string(), rules, problems }
+    }
+
+    /// The rule that decides who owns `path`
+    pub fn rule_for(&self, path: &str) -> Option<&Rule> {
+        self.rules.iter().rev().find(|rule| rule.matches(path))
+    }
+
+    /// Owners of `path`, empty when nobody owns it
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rule_for(path).map(|rule| rule.owners.as_slice()).unwrap_or(&[])
+    }
+
+    /// Rules whose pattern matches none of `files`, usually left behind by
+    /// a move or a typo
+    pub fn unmatched_rules(&self, files: &[String]) -> Vec<&Rule> {
+        self.rules.iter().filter(|rule| !files.iter().any(|file| rule.matches(file))).collect()
+    }
+}
+
+/// The rule on one line, `None` for blank lines and comments
+fn parse_line(number: usize, line: &str) -> std::result::Result<Option<Rule>, String> {
+    // Everything from an unescaped '#' on is a comment
+    let mut fields = line.split_whitespace().take_while(|field| !field.starts_with('#'));
+    let Some(pattern) = fields.next() else {
+        return Ok(None);
+    };
+    let regex = pattern_regex(pattern)?;
+    let owners: Vec<String> = fields.map(str::to_string).collect();
+    if let Some(owner) = owners.iter().find(|owner| !is_valid_owner(owner)) {
+        return Err(format!("'{}' is not a @user, @org/team or email address", owner));
+    }
+    Ok(Some(Rule { line: number, pattern: pattern.to_string(), owners, regex }))
+}
+
+/// Whether `owner` is a GitHub user, a team or an email address
+fn is_valid_owner(owner: &str) -> bool {
+    match owner.strip_prefix('@') {
+        Some(handle) => {
+            let name = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+            match handle.split_once('/') {
+                Some((org, team)) => name(org) && name(team),
+                None => name(handle),
+            }
+        }
+        None => is_valid_email(owner),
+    }
+}
+
+/// Translate a CODEOWNERS pattern into a regex over repository-relative paths
+fn pattern_regex(pattern: &str) -> std::result::Result<Regex, String> {
+    if pattern.starts_with('!') {
+        return Err("negated patterns ('!') are not supported".to_string());
+    }
+    if pattern.contains('[') {
+        return Err("character ranges ('[...]') are not supported".to_string());
+    }
+    let body = pattern.trim_matches('/');
+    if body.is_empty() {
+        return Err("the pattern matches nothing".to_string());
+    }
+
+    // A slash anywhere but the end ties the pattern to the repository root
+    let anchored = pattern.trim_end_matches('/').contains('/');
+    let segments: Vec<&str> = body.split('/').collect();
+    let mut regex = String::from(if anchored { "^" } else { "^(?:.*/)?" });
+    for (index, segment) in segments.iter().enumerate() {
+        let last = index + 1 == segments.len();
+        match *segment {
+            "**" if last => regex.push_str(".*"),
+            "**" => regex.push_str("(?:[^/]*/)*"),
+            segment => {
+                regex.push_str(&glob_segment(segment));
+                if !last {
+                    regex.push('/');
+                }
+            }
+        }
+    }
+    // Unlike .gitignore, `docs/*` only covers files directly in docs; other
+    // patterns naming a directory cover everything below it
+    let last = segments[segments.len() - 1];
+    if pattern.ends_with('/') {
+        regex.push_str("/.*");
+    } else if !last.contains('*') {
+        regex.push_str("(?:/.*)?");
+    }
+    regex.push('$');
+
+    Regex::new(&regex).map_err(|e| e.to_string())
+}
+
+/// Regex for one path segment with `*`, `?` and `\` escapes
+fn glob_segment(segment: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = segment.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+# Default owners
+*           @org/everyone
+
+*.js        @js-owner # inline comment
+/docs/*     docs@example.com
+apps/       @apps-team
+/build/logs/
+**/migrations/** @db-team
+";
+
+    #[test]
+    fn test_parse() {
+        let owners = CodeOwners::parse("CODEOWNERS", SAMPLE);
+        assert_eq!(owners.rules.len(), 6);
+        assert_eq!(owners.rules[1].line, 4);
+        assert_eq!(owners.rules[1].owners, vec!["@js-owner"]);
+        assert!(owners.rules[4].owners.is_empty());
+    }
+
+    #[test]
+    fn test_owners_of() {
+        let owners = CodeOwners::parse("CODEOWNERS", SAMPLE);
+        assert_eq!(owners.owners_of("README.md"), ["@org/everyone"]);
+        assert_eq!(owners.owners_of("src/web/app.js"), ["@js-owner"]);
+        assert_eq!(owners.owners_of("docs/intro.md"), ["docs@example.com"]);
+        // `docs/*` doesn't reach into subdirectories
+        assert_eq!(owners.owners_of("docs/guides/intro.md"), ["@org/everyone"]);
+        assert_eq!(owners.owners_of("apps/web/main.rs"), ["@apps-team"]);
+        assert_eq!(owners.owners_of("src/apps/web/main.rs"), ["@apps-team"]);
+        assert!(owners.owners_of("build/logs/today.log").is_empty());
+        assert_eq!(owners.owners_of("src/db/migrations/001.sql"), ["@db-team"]);
+        assert_eq!(owners.rule_for("build/logs/today.log").map(|rule| rule.line), Some(7));
+    }
+
+    #[test]
+    fn test_problems_and_unmatched_rules() {
+        let owners = CodeOwners::parse("CODEOWNERS", "*.rs @rust\n!vendor/ @a\nsrc/ octocat\n/gone/ @b\n");
+        assert_eq!(owners.rules.len(), 2);
+        assert_eq!(owners.problems.iter().map(|problem| problem.line).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(owners.problems[1].message.contains("octocat"));
+
+        let files = vec!["src/main.rs".to_string()];
+        let unmatched = owners.unmatched_rules(&files);
+        assert_eq!(unmatched.iter().map(|rule| rule.pattern.as_str()).collect::<Vec<_>>(), vec!["/gone/"]);
+    }
+
+    #[test]
+    fn test_is_valid_owner() {
+        assert!(is_valid_owner("@octocat"));
+        assert!(is_valid_owner("@org/team-name"));
+        assert!(is_valid_owner("dev@example.com"));
+        assert!(!is_valid_owner("@"));
+        assert!(!is_valid_owner("@org/"));
+        assert!(!is_valid_owner("octocat"));
+    }
+
+    #[test]
+    fn test_unsupported_patterns() {
+        assert!(pattern_regex("!vendor/").is_err());
+        assert!(pattern_regex("*.[ch]").is_err());
+        assert!(pattern_regex("/").is_err());
+        assert!(pattern_regex("\\#notes").unwrap().is_match("#notes"));
+    }
+}
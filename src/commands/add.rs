@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use colored::*;
-use git2::{DiffOptions, Repository, Status};
+use git2::{Repository, Status};
 use std::collections::HashSet;
 use std::fs;
 use std::io::{self, Write};
@@ -11,8 +11,10 @@ use tracing::{debug, error, info, instrument, warn};
 
 use crate::cli::AddArgs;
 use crate::config::Config;
-use crate::core::{FileStatus, RgitCore};
+use crate::core::{DiffOptionsBuilder, FileStatus, RgitCore};
+use crate::error::RgitError;
 use crate::interactive::{FileItem, FileSelector, InteractivePrompt};
+use crate::timings::{self, Phase};
 
 #[derive(Error, Debug)]
 pub enum AddError {
@@ -62,6 +64,8 @@ pub struct AddConfig {
     pub batch_size: usize,
     pub max_preview_files: usize,
     pub interactive_threshold: usize,
+    /// Add many files without asking first
+    pub assume_yes: bool,
 }
 
 impl Default for AddConfig {
@@ -73,6 +77,7 @@ impl Default for AddConfig {
             batch_size: 100,
             max_preview_files: 10,
             interactive_threshold: 20,
+            assume_yes: false,
         }
     }
 }
@@ -114,17 +119,17 @@ impl ProgressTracker {
         let percentage = (self.completed as f64 / self.total as f64 * 100.0) as u8;
         let elapsed = self.start_time.elapsed();
         
-        print!("\r{} Progress: {}/{} ({}%) - Elapsed: {:?}",
-               "⏳".yellow(),
-               self.completed,
-               self.total,
-               percentage,
-               elapsed);
+        ui_print!("\r{} Progress: {}/{} ({}%) - Elapsed: {:?}",
+                  "⏳".yellow(),
+                  self.completed,
+                  self.total,
+                  percentage,
+                  elapsed);
         io::stdout().flush().unwrap_or(());
     }
     
     fn finish(&self) {
-        println!("\r{} Completed {}/{} files in {:?}",
+        ui_println!("\r{} Completed {}/{} files in {:?}",
                 "✅".green(),
                 self.completed,
                 self.total,
@@ -228,25 +233,383 @@ pub struct DiffLineInfo {
     pub new_lineno: Option<u32>,
 }
 
+/// Which way patch mode moves hunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchMode {
+    /// Copy hunks of working tree changes into the index
+    Stage,
+    /// Take hunks of staged changes back out of the index
+    Unstage,
+}
+
+impl PatchMode {
+    fn verb(self) -> &'static str {
+        match self {
+            PatchMode::Stage => "stage",
+            PatchMode::Unstage => "unstage",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            PatchMode::Stage => "Stage",
+            PatchMode::Unstage => "Unstage",
+        }
+    }
+}
+
+/// Marker libgit2 emits for lines without a trailing newline
+const NO_NEWLINE_MARKER: &str = "\\ No newline at end of file";
+
+impl Hunk {
+    /// Build a hunk from diff lines, deriving the line counts and header
+    fn from_lines(old_start: u32, new_start: u32, lines: Vec<DiffLineInfo>) -> Self {
+        let old_lines = lines.iter().filter(|l| matches!(l.origin, ' ' | '-')).count() as u32;
+        let new_lines = lines.iter().filter(|l| matches!(l.origin, ' ' | '+')).count() as u32;
+
+        Self {
+            header: format!("@@ -{},{} +{},{} @@\n", old_start, old_lines, new_start, new_lines),
+            old_start,
+            old_lines,
+            new_start,
+            new_lines,
+            lines,
+        }
+    }
+
+    fn has_changes(&self) -> bool {
+        self.lines.iter().any(|l| matches!(l.origin, '+' | '-'))
+    }
+
+    /// Split the hunk at the context runs between its changes. Context between
+    /// two changes is shared by both halves, as in `git add -p`. Returns `None`
+    /// when the changes are contiguous.
+    pub fn split(&self) -> Option<Vec<Hunk>> {
+        // Maximal runs of changed lines as half-open ranges into `lines`
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        for (idx, line) in self.lines.iter().enumerate() {
+            if matches!(line.origin, ' ' | '=') {
+                continue;
+            }
+            match groups.last_mut() {
+                Some((_, end)) if *end == idx => *end = idx + 1,
+                _ => groups.push((idx, idx + 1)),
+            }
+        }
+
+        if groups.len() < 2 {
+            return None;
+        }
+
+        // Old and new line numbers at the start of every line
+        let (mut old, mut new) = (self.old_start, self.new_start);
+        let mut positions = Vec::with_capacity(self.lines.len());
+        for line in &self.lines {
+            positions.push((old, new));
+            match line.origin {
+                ' ' => {
+                    old += 1;
+                    new += 1;
+                }
+                '-' => old += 1,
+                '+' => new += 1,
+                _ => {}
+            }
+        }
+
+        let hunks = (0..groups.len())
+            .map(|i| {
+                let from = if i == 0 { 0 } else { groups[i - 1].1 };
+                let to = groups.get(i + 1).map_or(self.lines.len(), |g| g.0);
+                let (old_start, new_start) = positions[from];
+                Hunk::from_lines(old_start, new_start, self.lines[from..to].to_vec())
+            })
+            .collect();
+
+        Some(hunks)
+    }
+
+    /// The same change in the other direction, for applying it in reverse
+    pub fn reversed(&self) -> Hunk {
+        let lines = self.lines.iter()
+            .map(|line| DiffLineInfo {
+                origin: match line.origin {
+                    '+' => '-',
+                    '-' => '+',
+                    '>' => '<',
+                    '<' => '>',
+                    other => other,
+                },
+                content: line.content.clone(),
+                old_lineno: line.new_lineno,
+                new_lineno: line.old_lineno,
+            })
+            .collect();
+
+        Hunk::from_lines(self.new_start, self.old_start, lines)
+    }
+
+    /// Render the hunk as unified diff text
+    pub fn to_patch_text(&self) -> String {
+        let mut text = format!("{}\n", self.header.trim_end());
+        for line in &self.lines {
+            match line.origin {
+                ' ' | '+' | '-' => {
+                    text.push(line.origin);
+                    text.push_str(line.content.trim_end_matches('\n'));
+                    text.push('\n');
+                }
+                _ => {
+                    text.push_str(NO_NEWLINE_MARKER);
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+}
+
+/// Parse a hunk edited by the user back into a [`Hunk`]. Returns `None` when
+/// nothing is left to apply. The side of the hunk the index already has must
+/// be unchanged so it still applies cleanly: the context and removed lines when
+/// staging, the context and added lines when unstaging.
+fn parse_edited_hunk(original: &Hunk, text: &str, mode: PatchMode) -> Result<Option<Hunk>, AddError> {
+    let mut lines: Vec<DiffLineInfo> = Vec::new();
+
+    for raw in text.split('\n') {
+        let raw = raw.strip_suffix('\r').unwrap_or(raw);
+        if raw.starts_with('#') || raw.starts_with("@@") {
+            continue;
+        }
+
+        if raw.starts_with('\\') {
+            // The marker belongs to the line before it
+            if let Some(prev) = lines.last_mut() {
+                prev.content = prev.content.trim_end_matches('\n').to_string();
+                let origin = match prev.origin {
+                    '+' => '>',
+                    '-' => '<',
+                    _ => '=',
+                };
+                lines.push(DiffLineInfo {
+                    origin,
+                    content: format!("\n{}\n", NO_NEWLINE_MARKER),
+                    old_lineno: None,
+                    new_lineno: None,
+                });
+            }
+            continue;
+        }
+
+        // Editors commonly strip the space from empty context lines
+        let (origin, content) = match raw.chars().next() {
+            None => (' ', ""),
+            Some(origin @ (' ' | '+' | '-')) => (origin, &raw[1..]),
+            Some(_) => {
+                return Err(AddError::PatchFailed {
+                    reason: format!("unrecognized line in edited hunk: {}", raw),
+                });
+            }
+        };
+
+        lines.push(DiffLineInfo {
+            origin,
+            content: format!("{}\n", content),
+            old_lineno: None,
+            new_lineno: None,
+        });
+    }
+
+    // Trailing empty lines are editor noise rather than context
+    while lines.last().is_some_and(|l| l.origin == ' ' && l.content == "\n") {
+        lines.pop();
+    }
+
+    let kept = match mode {
+        PatchMode::Stage => '-',
+        PatchMode::Unstage => '+',
+    };
+    let index_side = |lines: &[DiffLineInfo]| -> Vec<String> {
+        lines
+            .iter()
+            .filter(|l| l.origin == ' ' || l.origin == kept)
+            .map(|l| l.content.clone())
+            .collect()
+    };
+    if index_side(&lines) != index_side(&original.lines) {
+        return Err(AddError::PatchFailed {
+            reason: format!(
+                "edited hunk changes context or {} lines and would not apply",
+                if kept == '-' { "removed" } else { "added" }
+            ),
+        });
+    }
+
+    let hunk = Hunk::from_lines(original.old_start, original.new_start, lines);
+    Ok(hunk.has_changes().then_some(hunk))
+}
+
+/// Apply hunks diffed against `base` to it. Hunks must be in file order; context
+/// they share with the hunk before them, as split hunks do, is only emitted once.
+fn apply_hunks(base: &[u8], hunks: &[Hunk]) -> Result<Vec<u8>, AddError> {
+    let base_lines: Vec<&[u8]> = base.split_inclusive(|&b| b == b'\n').collect();
+    let mut output = Vec::with_capacity(base.len());
+    let mut cursor: usize = 0;
+    
+    let mismatch = |hunk: &Hunk| AddError::PatchFailed {
+        reason: format!("hunk {} does not apply to the staged content", hunk.header.trim_end()),
+    };
+    
+    for hunk in hunks {
+        // A hunk without old lines inserts after `old_start` rather than at it
+        let start = if hunk.old_lines == 0 {
+            hunk.old_start as usize
+        } else {
+            (hunk.old_start as usize).saturating_sub(1)
+        };
+        if start > base_lines.len() {
+            return Err(mismatch(hunk));
+        }
+        
+        let mut overlap = cursor.saturating_sub(start);
+        for line in &base_lines[cursor.min(start)..start] {
+            output.extend_from_slice(line);
+        }
+        let mut pos = start;
+        
+        for line in &hunk.lines {
+            match line.origin {
+                ' ' | '-' => {
+                    if base_lines.get(pos) != Some(&line.content.as_bytes()) {
+                        return Err(mismatch(hunk));
+                    }
+                    pos += 1;
+                    
+                    if overlap > 0 {
+                        // Only context may be shared with the previous hunk
+                        if line.origin != ' ' {
+                            return Err(mismatch(hunk));
+                        }
+                        overlap -= 1;
+                    } else if line.origin == ' ' {
+                        output.extend_from_slice(line.content.as_bytes());
+                    }
+                }
+                '+' if overlap > 0 => return Err(mismatch(hunk)),
+                '+' => output.extend_from_slice(line.content.as_bytes()),
+                _ => {}
+            }
+        }
+        
+        cursor = cursor.max(pos);
+    }
+    
+    for line in &base_lines[cursor..] {
+        output.extend_from_slice(line);
+    }
+    
+    Ok(output)
+}
+
+/// Commands offered for a hunk in patch mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PatchAction {
+    Stage,
+    Skip,
+    StageRest,
+    SkipRest,
+    NextUndecided,
+    Next,
+    PreviousUndecided,
+    Previous,
+    Goto,
+    Split,
+    Edit,
+    Quit,
+    Help,
+}
+
+impl PatchAction {
+    fn label(self, mode: PatchMode) -> String {
+        let label = match self {
+            Self::Stage => return format!("{} this hunk [y]", mode.title()),
+            Self::Skip => "Skip this hunk [n]",
+            Self::StageRest => return format!("{} this and all later hunks [a]", mode.title()),
+            Self::SkipRest => "Skip this and all later hunks [d]",
+            Self::NextUndecided => "Leave undecided, next undecided hunk [j]",
+            Self::Next => "Leave undecided, next hunk [J]",
+            Self::PreviousUndecided => "Leave undecided, previous undecided hunk [k]",
+            Self::Previous => "Leave undecided, previous hunk [K]",
+            Self::Goto => "Go to hunk [g]",
+            Self::Split => "Split into smaller hunks [s]",
+            Self::Edit => "Edit this hunk [e]",
+            Self::Quit => "Quit [q]",
+            Self::Help => "Show help [?]",
+        };
+        label.to_string()
+    }
+}
+
+/// First undecided hunk after `current`, wrapping around to the start
+fn next_undecided(decisions: &[Option<bool>], current: usize) -> Option<usize> {
+    (current + 1..decisions.len())
+        .chain(0..current)
+        .find(|&i| decisions[i].is_none())
+}
+
 pub struct PatchProcessor<'repo> {
     repo: &'repo Repository,
     config: AddConfig,
+    mode: PatchMode,
 }
 
 impl<'repo> PatchProcessor<'repo> {
-    fn new(repo: &'repo Repository, config: AddConfig) -> Self {
-        Self { repo, config }
+    pub fn new(repo: &'repo Repository, config: AddConfig) -> Self {
+        Self { repo, config, mode: PatchMode::Stage }
+    }
+    
+    pub fn with_mode(mut self, mode: PatchMode) -> Self {
+        self.mode = mode;
+        self
     }
     
+    /// Let the user pick hunks of `file_path` and apply them to the index.
+    /// Returns the number of hunks applied.
     #[instrument(skip(self))]
-    fn get_file_diff(&self, file_path: &Path) -> Result<Vec<Hunk>, AddError> {
-        let mut diff_opts = DiffOptions::new();
-        diff_opts.pathspec(file_path);
-        diff_opts.context_lines(3);
-        diff_opts.include_untracked(true);
+    pub fn process_file(&self, file_path: &Path) -> Result<usize, AddError> {
+        let hunks = self.get_file_diff(file_path)?;
+        
+        if hunks.is_empty() {
+            debug!("No hunks found for {}", file_path.display());
+            return Ok(0);
+        }
+        
+        let selected_hunks = self.interactive_hunk_selection(file_path, hunks)?;
+        if selected_hunks.is_empty() {
+            return Ok(0);
+        }
+        
+        self.apply_to_index(file_path, &selected_hunks)?;
+        debug!("Applied {} hunks to {}", selected_hunks.len(), file_path.display());
         
-        let diff = self.repo
-            .diff_index_to_workdir(None, Some(&mut diff_opts))?;
+        Ok(selected_hunks.len())
+    }
+    
+    #[instrument(skip(self))]
+    fn get_file_diff(&self, file_path: &Path) -> Result<Vec<Hunk>, AddError> {
+        let mut diff_opts = DiffOptionsBuilder::new()
+            .paths([self.index_path(file_path)?.to_string_lossy().replace('\\', "/")])
+            .context_lines(3)
+            .include_untracked()
+            .build();
+        
+        let diff = match self.mode {
+            PatchMode::Stage => self.repo.diff_index_to_workdir(None, Some(&mut diff_opts))?,
+            PatchMode::Unstage => {
+                let head = self.head_tree();
+                self.repo.diff_tree_to_index(head.as_ref(), None, Some(&mut diff_opts))?
+            }
+        };
         
         // Workaround for borrow checker: collect hunks and lines separately, then combine.
         struct TempHunk {
@@ -257,8 +620,7 @@ impl<'repo> PatchProcessor<'repo> {
             new_lines: u32,
         }
         let mut temp_hunks: Vec<TempHunk> = Vec::new();
-        let mut hunk_lines: Vec<Vec<DiffLineInfo>> = Vec::new();
-        let mut current_hunk_idx: usize = 0;
+        let hunk_lines: std::cell::RefCell<Vec<Vec<DiffLineInfo>>> = std::cell::RefCell::new(Vec::new());
 
         diff.foreach(
             &mut |_delta, _progress| true,
@@ -271,12 +633,12 @@ impl<'repo> PatchProcessor<'repo> {
                     new_start: hunk.new_start(),
                     new_lines: hunk.new_lines(),
                 });
-                hunk_lines.push(Vec::new());
-                current_hunk_idx = hunk_lines.len() - 1;
+                hunk_lines.borrow_mut().push(Vec::new());
                 true
             }),
             Some(&mut |_delta, _hunk, line| {
                 // Always push to the last hunk_lines entry
+                let mut hunk_lines = hunk_lines.borrow_mut();
                 if !hunk_lines.is_empty() {
                     let idx = hunk_lines.len() - 1;
                     let line_info = DiffLineInfo {
@@ -293,7 +655,7 @@ impl<'repo> PatchProcessor<'repo> {
 
         let hunks: Vec<Hunk> = temp_hunks
             .into_iter()
-            .zip(hunk_lines.into_iter())
+            .zip(hunk_lines.into_inner().into_iter())
             .map(|(h, lines)| Hunk {
                 header: h.header,
                 old_start: h.old_start,
@@ -307,140 +669,313 @@ impl<'repo> PatchProcessor<'repo> {
         Ok(hunks)
     }
     
-    #[instrument(skip(self))]
-    fn apply_hunk(&self, file_path: &Path, hunk: &Hunk) -> Result<(), AddError> {
-        // Create a temporary index to apply the hunk
-        let mut index = self.repo.index()?;
+    /// Path of `file_path` relative to the working directory, as the index stores it
+    fn index_path(&self, file_path: &Path) -> Result<PathBuf, AddError> {
+        if file_path.is_relative() {
+            return Ok(file_path.to_path_buf());
+        }
         
-        // Read the current file content
-        let file_content = fs::read_to_string(file_path)?;
+        let workdir = self.repo.workdir()
+            .ok_or_else(|| AddError::Git(git2::Error::from_str("Repository has no working directory")))?;
         
-        let lines: Vec<&str> = file_content.lines().collect();
-        let mut new_content = Vec::new();
+        let canonical = workdir.canonicalize()?;
+        file_path.strip_prefix(workdir)
+            .or_else(|_| file_path.strip_prefix(&canonical))
+            .map(Path::to_path_buf)
+            .map_err(|_| AddError::PathTraversal { path: file_path.display().to_string() })
+    }
+    
+    fn head_tree(&self) -> Option<git2::Tree<'repo>> {
+        self.repo.head().and_then(|h| h.peel_to_tree()).ok()
+    }
+    
+    /// Apply the selected hunks for `file_path` to the index without touching the
+    /// working tree. The hunks are applied to the staged blob, in reverse when
+    /// unstaging, and the result is written straight into the index like
+    /// `git apply --cached`.
+    #[instrument(skip(self, hunks))]
+    fn apply_to_index(&self, file_path: &Path, hunks: &[Hunk]) -> Result<(), AddError> {
+        let path = self.index_path(file_path)?;
+        let path_str = path.to_string_lossy().replace('\\', "/");
         
-        let mut line_idx = 0;
-        let mut hunk_line_idx = 0;
+        let mut index = self.repo.index()?;
+        let staged = index.get_path(&path, 0);
+        let committed = self.head_tree().and_then(|tree| tree.get_path(&path).ok());
         
-        // Apply the hunk line by line
-        while line_idx < lines.len() && hunk_line_idx < hunk.lines.len() {
-            let hunk_line = &hunk.lines[hunk_line_idx];
-            
-            match hunk_line.origin {
-                ' ' => {
-                    // Context line - keep as is
-                    new_content.push(lines[line_idx].to_string());
-                    line_idx += 1;
-                    hunk_line_idx += 1;
-                }
-                '-' => {
-                    // Deleted line - skip from original
-                    line_idx += 1;
-                    hunk_line_idx += 1;
-                }
-                '+' => {
-                    // Added line - add to new content
-                    new_content.push(hunk_line.content.trim_end().to_string());
-                    hunk_line_idx += 1;
-                }
-                _ => {
-                    hunk_line_idx += 1;
-                }
+        let base = match &staged {
+            Some(entry) => self.repo.find_blob(entry.id)?.content().to_vec(),
+            None => Vec::new(),
+        };
+        let content = match self.mode {
+            PatchMode::Stage => apply_hunks(&base, hunks)?,
+            PatchMode::Unstage => {
+                let reversed: Vec<Hunk> = hunks.iter().map(Hunk::reversed).collect();
+                apply_hunks(&base, &reversed)?
             }
-        }
+        };
         
-        // Add remaining lines
-        while line_idx < lines.len() {
-            new_content.push(lines[line_idx].to_string());
-            line_idx += 1;
+        // Unstaging all of a newly added file drops it from the index again
+        if self.mode == PatchMode::Unstage && content.is_empty() && committed.is_none() {
+            index.remove_path(&path)?;
+            timings::time(Phase::IndexWrite, || index.write())?;
+            return Ok(());
         }
         
-        // Write the patched content back
-        let patched_content = new_content.join("\n");
-        fs::write(file_path, patched_content)?;
-        
-        // Add to index
-        index.add_path(file_path.strip_prefix(self.repo.workdir().unwrap()).unwrap())?;
-        index.write()?;
+        let id = self.repo.blob(&content)?;
+        let mode = staged.as_ref()
+            .map(|e| e.mode)
+            .or_else(|| committed.as_ref().map(|e| e.filemode() as u32))
+            .unwrap_or(u32::from(git2::FileMode::Blob));
+        
+        index.add(&git2::IndexEntry {
+            // Zeroed stat data makes the worktree file compare as modified again
+            ctime: git2::IndexTime::new(0, 0),
+            mtime: git2::IndexTime::new(0, 0),
+            dev: 0,
+            ino: 0,
+            mode,
+            uid: 0,
+            gid: 0,
+            file_size: content.len() as u32,
+            id,
+            flags: path_str.len().min(0xfff) as u16,
+            flags_extended: 0,
+            path: path_str.into_bytes(),
+        })?;
+        timings::time(Phase::IndexWrite, || index.write())?;
         
         Ok(())
     }
     
-    #[instrument(skip(self))]
-    fn interactive_hunk_selection(&self, file_path: &Path, hunks: &[Hunk]) -> Result<Vec<usize>, AddError> {
-        let mut selected_hunks = Vec::new();
-        
-        println!("\n{} Processing: {}", 
+    #[instrument(skip(self, hunks))]
+    fn interactive_hunk_selection(&self, file_path: &Path, mut hunks: Vec<Hunk>) -> Result<Vec<Hunk>, AddError> {
+        ui_println!("\n{} Processing: {}", 
                 "📁".blue(), 
                 file_path.display().to_string().yellow());
         
-        for (idx, hunk) in hunks.iter().enumerate() {
-            println!("\n{} Hunk {} of {}:", "🔍".cyan(), idx + 1, hunks.len());
-            println!("{}", hunk.header.dimmed());
-            
-            // Display hunk content with syntax highlighting
-            for line in &hunk.lines {
-                match line.origin {
-                    '+' => println!("{}{}", "+".green(), line.content.green()),
-                    '-' => println!("{}{}", "-".red(), line.content.red()),
-                    ' ' => println!(" {}", line.content),
-                    _ => {}
-                }
-            }
+        let mut decisions: Vec<Option<bool>> = vec![None; hunks.len()];
+        let mut current = 0;
+        
+        loop {
+            ui_println!("\n{} Hunk {} of {}:", "🔍".cyan(), current + 1, hunks.len());
+            self.show_hunk(&hunks[current]);
             
-            // Interactive prompt for this hunk
-            let options = vec![
-                "Add this hunk [y]",
-                "Skip this hunk [n]", 
-                "Add all remaining hunks [a]",
-                "Skip all remaining hunks [d]",
-                "Quit [q]",
-                "Show help [?]",
-            ];
+            let actions = Self::available_actions(&hunks, &decisions, current);
+            let labels: Vec<String> = actions.iter().map(|a| a.label(self.mode)).collect();
             
             let choice = InteractivePrompt::new()
-                .with_message("Add this hunk?")
-                .with_options(&options)
+                .with_message(format!("{} this hunk?", self.mode.title()))
+                .with_options(&labels)
                 .with_default(0)
                 .select()
                 .map_err(|_| AddError::UserCancelled)?;
             
-            match choice {
-                0 => {
-                    selected_hunks.push(idx);
+            match actions[choice] {
+                PatchAction::Stage | PatchAction::Skip => {
+                    decisions[current] = Some(actions[choice] == PatchAction::Stage);
+                }
+                PatchAction::StageRest | PatchAction::SkipRest => {
+                    let stage = actions[choice] == PatchAction::StageRest;
+                    for decision in decisions[current..].iter_mut().filter(|d| d.is_none()) {
+                        *decision = Some(stage);
+                    }
+                    decisions[current] = Some(stage);
+                }
+                PatchAction::NextUndecided => {
+                    current = (current + 1..hunks.len())
+                        .find(|&i| decisions[i].is_none())
+                        .unwrap_or(current);
+                    continue;
                 }
-                1 => {
-                    // Skip this hunk
+                PatchAction::Next => {
+                    current += 1;
+                    continue;
                 }
-                2 => {
-                    // Add all remaining hunks
-                    selected_hunks.extend(idx..hunks.len());
-                    break;
+                PatchAction::PreviousUndecided => {
+                    current = (0..current)
+                        .rev()
+                        .find(|&i| decisions[i].is_none())
+                        .unwrap_or(current);
+                    continue;
                 }
-                3 => {
-                    // Skip all remaining hunks
-                    break;
+                PatchAction::Previous => {
+                    current -= 1;
+                    continue;
                 }
-                4 => {
+                PatchAction::Goto => {
+                    current = self.goto_hunk(&hunks, &decisions, current)?;
+                    continue;
+                }
+                PatchAction::Split => {
+                    if let Some(parts) = hunks[current].split() {
+                        ui_println!("{} Split into {} hunks", "✂️".cyan(), parts.len());
+                        let count = parts.len();
+                        hunks.splice(current..=current, parts);
+                        decisions.splice(current..=current, vec![None; count]);
+                    }
+                    continue;
+                }
+                PatchAction::Edit => {
+                    match self.edit_hunk(&hunks[current]) {
+                        Ok(Some(edited)) => {
+                            hunks[current] = edited;
+                            decisions[current] = Some(true);
+                        }
+                        Ok(None) => {
+                            ui_println!("{} Edited hunk is empty, leaving it unchanged", "⚠️".yellow());
+                            continue;
+                        }
+                        Err(e) => {
+                            ui_println!("{} {}", "❌".red(), e);
+                            continue;
+                        }
+                    }
+                }
+                PatchAction::Quit => {
                     return Err(AddError::UserCancelled);
                 }
-                5 => {
+                PatchAction::Help => {
                     self.show_patch_help();
                     continue; // Re-ask for this hunk
                 }
-                _ => {}
+            }
+            
+            match next_undecided(&decisions, current) {
+                Some(next) => current = next,
+                None => break,
             }
         }
         
-        Ok(selected_hunks)
+        Ok(hunks
+            .into_iter()
+            .zip(decisions)
+            .filter(|(_, decision)| *decision == Some(true))
+            .map(|(hunk, _)| hunk)
+            .collect())
+    }
+    
+    /// Commands that make sense for the hunk at `current`
+    fn available_actions(hunks: &[Hunk], decisions: &[Option<bool>], current: usize) -> Vec<PatchAction> {
+        let mut actions = vec![
+            PatchAction::Stage,
+            PatchAction::Skip,
+            PatchAction::StageRest,
+            PatchAction::SkipRest,
+        ];
+        
+        if decisions[current + 1..].iter().any(|d| d.is_none()) {
+            actions.push(PatchAction::NextUndecided);
+        }
+        if current + 1 < hunks.len() {
+            actions.push(PatchAction::Next);
+        }
+        if decisions[..current].iter().any(|d| d.is_none()) {
+            actions.push(PatchAction::PreviousUndecided);
+        }
+        if current > 0 {
+            actions.push(PatchAction::Previous);
+        }
+        if hunks.len() > 1 {
+            actions.push(PatchAction::Goto);
+        }
+        if hunks[current].split().is_some() {
+            actions.push(PatchAction::Split);
+        }
+        
+        actions.extend([PatchAction::Edit, PatchAction::Quit, PatchAction::Help]);
+        actions
+    }
+    
+    fn show_hunk(&self, hunk: &Hunk) {
+        println!("{}", hunk.header.trim_end().dimmed());
+        
+        // Display hunk content with syntax highlighting
+        for line in &hunk.lines {
+            let content = line.content.trim_end_matches('\n');
+            match line.origin {
+                '+' => println!("{}{}", "+".green(), content.green()),
+                '-' => println!("{}{}", "-".red(), content.red()),
+                ' ' => println!(" {}", content),
+                _ => println!("{}", NO_NEWLINE_MARKER.dimmed()),
+            }
+        }
+    }
+    
+    fn goto_hunk(&self, hunks: &[Hunk], decisions: &[Option<bool>], current: usize) -> Result<usize, AddError> {
+        let options: Vec<String> = hunks
+            .iter()
+            .zip(decisions)
+            .enumerate()
+            .map(|(idx, (hunk, decision))| {
+                let state = match decision {
+                    Some(true) => "+",
+                    Some(false) => "-",
+                    None => " ",
+                };
+                let first_change = hunk
+                    .lines
+                    .iter()
+                    .find(|l| matches!(l.origin, '+' | '-'))
+                    .map(|l| format!("{}{}", l.origin, l.content.trim_end()))
+                    .unwrap_or_default();
+                format!("{:>3}{}: {} {}", idx + 1, state, hunk.header.trim_end(), first_change)
+            })
+            .collect();
+        
+        InteractivePrompt::new()
+            .with_message("Go to which hunk?")
+            .with_options(&options)
+            .with_default(current)
+            .select()
+            .map_err(|_| AddError::UserCancelled)
+    }
+    
+    /// Let the user edit the hunk in `$EDITOR`
+    fn edit_hunk(&self, hunk: &Hunk) -> Result<Option<Hunk>, AddError> {
+        let guide = match self.mode {
+            PatchMode::Stage => "# To remove '-' lines, make them ' ' lines (context).\n\
+                                 # To remove '+' lines, delete them.\n",
+            PatchMode::Unstage => "# To remove '+' lines, make them ' ' lines (context).\n\
+                                   # To remove '-' lines, delete them.\n",
+        };
+        let template = format!(
+            "# Manual hunk edit mode -- see bottom for a quick guide.\n\
+             {}\
+             # ---\n\
+             {}\
+             # Lines starting with # will be removed.\n\
+             # Save an empty hunk to abort the edit.\n",
+            hunk.to_patch_text(),
+            guide
+        );
+        
+        let edited = match InteractivePrompt::new().with_message(template).editor() {
+            Ok(text) => text,
+            // Closing the editor without saving aborts the edit
+            Err(e) if matches!(e.downcast_ref::<RgitError>(), Some(RgitError::OperationCancelled)) => {
+                return Ok(None);
+            }
+            Err(e) => return Err(AddError::PatchFailed { reason: e.to_string() }),
+        };
+        
+        parse_edited_hunk(hunk, &edited, self.mode)
     }
     
     fn show_patch_help(&self) {
-        println!("\n{} Patch mode commands:", "💡".blue().bold());
-        println!("  {} - add this hunk to index", "y".green().bold());
-        println!("  {} - do not add this hunk to index", "n".red().bold());
-        println!("  {} - quit; do not add this hunk or any remaining ones", "q".yellow().bold());
-        println!("  {} - add this hunk and all later hunks in the file", "a".green().bold());
-        println!("  {} - do not add this hunk or any later hunks in the file", "d".red().bold());
+        ui_println!("\n{} Patch mode commands:", "💡".blue().bold());
+        let verb = self.mode.verb();
+        println!("  {} - {} this hunk", "y".green().bold(), verb);
+        println!("  {} - do not {} this hunk", "n".red().bold(), verb);
+        println!("  {} - quit; do not {} this hunk or any remaining ones", "q".yellow().bold(), verb);
+        println!("  {} - {} this hunk and all later hunks in the file", "a".green().bold(), verb);
+        println!("  {} - do not {} this hunk or any later hunks in the file", "d".red().bold(), verb);
+        println!("  {} - leave this hunk undecided, see next undecided hunk", "j".cyan().bold());
+        println!("  {} - leave this hunk undecided, see next hunk", "J".cyan().bold());
+        println!("  {} - leave this hunk undecided, see previous undecided hunk", "k".cyan().bold());
+        println!("  {} - leave this hunk undecided, see previous hunk", "K".cyan().bold());
+        println!("  {} - select a hunk to go to", "g".cyan().bold());
+        println!("  {} - split the current hunk into smaller hunks", "s".magenta().bold());
+        println!("  {} - manually edit the current hunk", "e".magenta().bold());
         println!("  {} - show this help", "?".blue().bold());
         println!();
     }
@@ -681,7 +1216,7 @@ impl<'repo> AddExecutor<'repo> {
             return Ok(());
         }
         
-        println!("{} Interactive patch mode", "🔍".blue().bold());
+        ui_println!("{} Interactive patch mode", "🔍".blue().bold());
         println!("Select hunks to add for each file:\n");
         
         let processor = PatchProcessor::new(&self.rgit.repo, self.config.clone());
@@ -694,7 +1229,7 @@ impl<'repo> AddExecutor<'repo> {
                 continue;
             }
             
-            match self.process_file_patches(&processor, file_path) {
+            match processor.process_file(file_path) {
                 Ok(hunks_added) => {
                     total_hunks_added += hunks_added;
                     if hunks_added > 0 {
@@ -722,29 +1257,6 @@ impl<'repo> AddExecutor<'repo> {
         Ok(())
     }
     
-    #[instrument(skip(self, processor, file_path))]
-    fn process_file_patches(&self, processor: &PatchProcessor, file_path: &Path) -> Result<usize, AddError> {
-        let hunks = processor.get_file_diff(file_path)?;
-        
-        if hunks.is_empty() {
-            debug!("No hunks found for {}", file_path.display());
-            return Ok(0);
-        }
-        
-        let selected_indices = processor.interactive_hunk_selection(file_path, &hunks)?;
-        
-        let mut applied_hunks = 0;
-        for &idx in &selected_indices {
-            if idx < hunks.len() {
-                processor.apply_hunk(file_path, &hunks[idx])?;
-                applied_hunks += 1;
-                debug!("Applied hunk {} for {}", idx, file_path.display());
-            }
-        }
-        
-        Ok(applied_hunks)
-    }
-    
     // Utility methods
     
     fn add_file_batch(&mut self, files: &[PathBuf]) -> Result<(), AddError> {
@@ -762,7 +1274,7 @@ impl<'repo> AddExecutor<'repo> {
         
         let mut index = self.rgit.repo.index()?;
         index.add_path(relative_path)?;
-        index.write()?;
+        timings::time(Phase::IndexWrite, || index.write())?;
         
         debug!("Added file: {}", file_path.display());
         Ok(())
@@ -794,6 +1306,9 @@ impl<'repo> AddExecutor<'repo> {
     }
     
     fn confirm_add_all(&self, total_files: usize) -> Result<bool, AddError> {
+        if self.config.assume_yes {
+            return Ok(true);
+        }
         InteractivePrompt::new()
             .with_message(&format!("Add all {} files?", total_files))
             .confirm()
@@ -804,10 +1319,10 @@ impl<'repo> AddExecutor<'repo> {
         let max_show = self.config.max_preview_files;
         let mut shown = 0;
         
-        println!("{} Files to be added:", "📋".blue());
+        ui_println!("{} Files to be added:", "📋".blue());
         
         for file in unstaged.iter().take(max_show - shown) {
-            println!("  {} {}: {}", 
+            ui_println!("  {} {}: {}", 
                     "○".yellow(), 
                     file.status_symbol(false).yellow(),
                     file.path.white());
@@ -831,19 +1346,19 @@ impl<'repo> AddExecutor<'repo> {
     }
     
     fn show_status_summary(&self, status: &crate::core::RepositoryStatus) {
-        println!("{} Current repository status:", "📋".blue());
-        println!("  {} {} unstaged changes", "📝".yellow(), status.unstaged.len());
-        println!("  {} {} untracked files", "❓".red(), status.untracked.len());
+        ui_println!("{} Current repository status:", "📋".blue());
+        ui_println!("  {} {} unstaged changes", "📝".yellow(), status.unstaged.len());
+        ui_println!("  {} {} untracked files", "❓".red(), status.untracked.len());
         println!();
     }
     
     async fn show_add_summary(&self, operation: &str) -> Result<(), AddError> {
         let status = self.rgit.status()?;
         
-        println!("\n{} {} completed:", "📋".blue(), operation.cyan());
+        ui_println!("\n{} {} completed:", "📋".blue(), operation.cyan());
         
         if !status.staged.is_empty() {
-            println!("  {} {} file{} staged for commit", 
+            ui_println!("  {} {} file{} staged for commit", 
                     "✅".green(),
                     status.staged.len(),
                     if status.staged.len() == 1 { "" } else { "s" });
@@ -851,16 +1366,16 @@ impl<'repo> AddExecutor<'repo> {
         
         if !status.unstaged.is_empty() || !status.untracked.is_empty() {
             let remaining = status.unstaged.len() + status.untracked.len();
-            println!("  {} {} file{} remaining unstaged", 
+            ui_println!("  {} {} file{} remaining unstaged", 
                     "📝".yellow(),
                     remaining,
                     if remaining == 1 { "" } else { "s" });
         }
         
         if !status.staged.is_empty() {
-            println!("\n{} Next steps:", "💡".blue());
-            println!("  • {} - Commit staged changes", "rgit commit".cyan());
-            println!("  • {} - Quick commit workflow", "rgit quick-commit".cyan());
+            ui_println!("\n{} Next steps:", "💡".blue());
+            ui_println!("  • {} - Commit staged changes", "rgit commit".cyan());
+            ui_println!("  • {} - Quick commit workflow", "rgit quick-commit".cyan());
         }
         
         Ok(())
@@ -873,7 +1388,7 @@ impl<'repo> AddExecutor<'repo> {
                  if results.added.len() == 1 { "" } else { "s" });
             
             for file in &results.added {
-                println!("  {} {}", "✓".green(), file.display().to_string().white());
+                ui_println!("  {} {}", "✓".green(), file.display().to_string().white());
             }
         }
         
@@ -894,7 +1409,7 @@ impl<'repo> AddExecutor<'repo> {
                      .collect::<Vec<_>>()
                      .join(", "));
             
-            println!("  💡 Use {} to add ignored files", "--force".cyan());
+            ui_println!("  💡 Use {} to add ignored files", "--force".cyan());
         }
         
         if !results.failed.is_empty() {
@@ -934,7 +1449,7 @@ impl AddResults {
 // Public API
 #[instrument(skip(args, rgit, config))]
 pub async fn execute(args: &AddArgs, rgit: &mut RgitCore, config: &Config) -> Result<()> {
-    let add_config = AddConfig::default();
+    let add_config = AddConfig { assume_yes: config.ui.assume_yes, ..Default::default() };
     let mut executor = AddExecutor::new(rgit, add_config)?;
     
     executor.execute(args).await?;
@@ -974,7 +1489,7 @@ pub async fn stage_files(
             })?;
         
         let mut index = rgit.repo.index()?;
-        if index.add_path(relative_path).is_ok() && index.write().is_ok() {
+        if index.add_path(relative_path).is_ok() && timings::time(Phase::IndexWrite, || index.write()).is_ok() {
             staged.push(file_path.clone());
         }
     }
@@ -990,20 +1505,9 @@ pub fn has_stageable_files(rgit: &RgitCore) -> Result<bool, AddError> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
+    use crate::test_support::create_test_repo;
     use std::fs;
 
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[tokio::test]
     async fn test_add_specific_files() {
         let (temp_dir, repo) = create_test_repo();
@@ -1069,6 +1573,139 @@ mod tests {
         assert!(hunks[0].lines.iter().any(|l| l.content.contains("modified")));
     }
 
+    fn two_change_hunk() -> (tempfile::TempDir, Hunk) {
+        let (temp_dir, repo) = create_test_repo();
+        let file_path = temp_dir.path().join("test.txt");
+        let original: String = (1..=10).map(|i| format!("line{}\n", i)).collect();
+        fs::write(&file_path, &original).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+
+        fs::write(&file_path, original.replace("line2\n", "LINE2\n").replace("line9\n", "LINE9\n")).unwrap();
+
+        let processor = PatchProcessor::new(&repo, AddConfig::default());
+        let mut hunks = processor.get_file_diff(&file_path).unwrap();
+        assert_eq!(hunks.len(), 1);
+        (temp_dir, hunks.remove(0))
+    }
+
+    #[test]
+    fn test_split_hunk_shares_context() {
+        let (_temp_dir, hunk) = two_change_hunk();
+
+        let parts = hunk.split().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].header, "@@ -1,8 +1,8 @@\n");
+        assert_eq!(parts[1].header, "@@ -3,8 +3,8 @@\n");
+        assert!(parts[0].to_patch_text().contains("+LINE2\n"));
+        assert!(!parts[0].to_patch_text().contains("LINE9"));
+        assert!(parts[1].to_patch_text().starts_with("@@ -3,8 +3,8 @@\n line3\n"));
+
+        // A single change cannot be split any further
+        assert!(parts[0].split().is_none());
+    }
+
+    #[test]
+    fn test_parse_edited_hunk() {
+        let (_temp_dir, hunk) = two_change_hunk();
+        let text = hunk.to_patch_text();
+
+        // Dropping one change keeps the hunk applicable
+        let edited = text.replace("-line9\n+LINE9\n", " line9\n");
+        let parsed = parse_edited_hunk(&hunk, &format!("# comment\n{}", edited), PatchMode::Stage).unwrap().unwrap();
+        assert_eq!((parsed.old_lines, parsed.new_lines), (10, 10));
+        assert_eq!(parsed.to_patch_text(), format!("@@ -1,10 +1,10 @@\n{}", &edited[edited.find('\n').unwrap() + 1..]));
+
+        // Added lines can be rewritten freely
+        let parsed = parse_edited_hunk(&hunk, &text.replace("+LINE2", "+Line two"), PatchMode::Stage).unwrap().unwrap();
+        assert!(parsed.lines.iter().any(|l| l.origin == '+' && l.content == "Line two\n"));
+
+        // Touching context makes it inapplicable
+        assert!(parse_edited_hunk(&hunk, &text.replace(" line5\n", ""), PatchMode::Stage).is_err());
+
+        // Nothing left to stage
+        let reverted = text.replace("-line2\n+LINE2\n", " line2\n").replace("-line9\n+LINE9\n", " line9\n");
+        assert!(parse_edited_hunk(&hunk, &reverted, PatchMode::Stage).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_stage_hunks_updates_index_only() {
+        let (temp_dir, repo) = create_test_repo();
+        let file_path = temp_dir.path().join("test.txt");
+        let original: String = (1..=10).map(|i| format!("line{}\n", i)).collect();
+        fs::write(&file_path, &original).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("test.txt")).unwrap();
+        index.write().unwrap();
+
+        let modified = original.replace("line2\n", "LINE2\n").replace("line9\n", "LINE9\n") + "line11";
+        fs::write(&file_path, &modified).unwrap();
+
+        let staged = |repo: &Repository| {
+            let index = repo.index().unwrap();
+            let entry = index.get_path(Path::new("test.txt"), 0).unwrap();
+            String::from_utf8(repo.find_blob(entry.id).unwrap().content().to_vec()).unwrap()
+        };
+
+        let processor = PatchProcessor::new(&repo, AddConfig::default());
+        let hunks = processor.get_file_diff(&file_path).unwrap();
+        let parts = hunks[0].split().unwrap();
+        assert_eq!(parts.len(), 3);
+
+        // Only the first change is staged; the worktree keeps everything
+        processor.apply_to_index(&file_path, &parts[..1]).unwrap();
+        assert_eq!(staged(&repo), original.replace("line2\n", "LINE2\n"));
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), modified);
+
+        // The remaining hunks are diffed against the new staged content
+        let hunks = processor.get_file_diff(&file_path).unwrap();
+        let parts = hunks[0].split().unwrap();
+        processor.apply_to_index(&file_path, &parts).unwrap();
+        assert_eq!(staged(&repo), modified);
+        assert!(processor.get_file_diff(&file_path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unstage_hunks_from_index() {
+        let (temp_dir, repo) = create_test_repo();
+        let file_path = temp_dir.path().join("test.txt");
+        let original: String = (1..=10).map(|i| format!("line{}\n", i)).collect();
+        fs::write(&file_path, &original).unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("test.txt")).unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &sig, &sig, "Initial commit", &tree, &[]).unwrap();
+
+        let modified = original.replace("line2\n", "LINE2\n").replace("line9\n", "LINE9\n");
+        fs::write(&file_path, &modified).unwrap();
+        fs::write(temp_dir.path().join("new.txt"), "new\n").unwrap();
+        index.add_path(Path::new("test.txt")).unwrap();
+        index.add_path(Path::new("new.txt")).unwrap();
+        index.write().unwrap();
+
+        let processor = PatchProcessor::new(&repo, AddConfig::default()).with_mode(PatchMode::Unstage);
+        let hunks = processor.get_file_diff(Path::new("test.txt")).unwrap();
+        let parts = hunks[0].split().unwrap();
+
+        // Unstaging the second change leaves only the first one staged
+        processor.apply_to_index(Path::new("test.txt"), &parts[1..]).unwrap();
+        let index = repo.index().unwrap();
+        let entry = index.get_path(Path::new("test.txt"), 0).unwrap();
+        let staged = repo.find_blob(entry.id).unwrap().content().to_vec();
+        assert_eq!(String::from_utf8(staged).unwrap(), original.replace("line2\n", "LINE2\n"));
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), modified);
+
+        // Unstaging all of a new file drops it from the index
+        let hunks = processor.get_file_diff(Path::new("new.txt")).unwrap();
+        processor.apply_to_index(Path::new("new.txt"), &hunks).unwrap();
+        assert!(repo.index().unwrap().get_path(Path::new("new.txt"), 0).is_none());
+    }
+
     #[tokio::test]
     async fn test_stage_files_utility() {
         let (temp_dir, repo) = create_test_repo();
@@ -0,0 +1,469 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Delta, ObjectType, Oid, Repository, Sort};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::process::Command;
+
+use crate::cli::{AuditArgs, AuditFormat};
+use crate::config::Config;
+use crate::core::{resolve_revision, RgitCore};
+use crate::error::RgitError;
+use crate::output::decoration;
+use crate::utils::{format_date, humanize_size, parse_date_spec, parse_size, shorten_oid};
+
+/// Execute the audit command
+pub async fn execute(args: &AuditArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let options = AuditOptions::from_args(args, config)?;
+    let report = audit(&rgit.repo, &options)?;
+
+    let rendered = match args.format {
+        AuditFormat::Text => render_text(&report),
+        AuditFormat::Json => serde_json::to_string_pretty(&report)?,
+        AuditFormat::Markdown => render_markdown(&report),
+    };
+    match &args.output {
+        Some(path) => {
+            fs::write(path, rendered + "\n")?;
+            ui_println!("{} Report written to {}", "📄".blue(), path.display().to_string().cyan());
+        }
+        None => println!("{}", rendered),
+    }
+
+    if report.findings.is_empty() {
+        return Ok(());
+    }
+    let count = report.findings.len();
+    Err(RgitError::OperationFailed(format!("{} audit finding{}", count, if count == 1 { "" } else { "s" })).into())
+}
+
+// =============================================================================
+// Options
+// =============================================================================
+
+/// What to audit and what counts as a finding
+#[derive(Debug)]
+pub struct AuditOptions {
+    /// Revision or range; HEAD when unset
+    pub revision: Option<String>,
+    /// Commits walked when no range limits the walk
+    pub max_commits: usize,
+    pub since: Option<i64>,
+    /// Authors needing no review; the check is skipped when empty
+    pub allowed_authors: Vec<AuthorPattern>,
+    pub max_blob_size: u64,
+    pub verify_signatures: bool,
+}
+
+impl AuditOptions {
+    fn from_args(args: &AuditArgs, config: &Config) -> Result<Self> {
+        let since = args.since.as_ref()
+            .map(|spec| parse_date_spec(spec)
+                .ok_or_else(|| RgitError::InvalidArgument(format!("Unrecognized date: {}", spec))))
+            .transpose()?;
+        let size = args.max_blob_size.as_deref().unwrap_or(&config.audit.max_blob_size);
+        let max_blob_size = parse_size(size)
+            .ok_or_else(|| RgitError::InvalidArgument(format!("Invalid size: {}", size)))?;
+        let allowed_authors = config.audit.allowed_authors().iter()
+            .chain(&args.allow_authors)
+            .map(|pattern| AuthorPattern::new(pattern))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            revision: args.revision.clone(),
+            max_commits: config.audit.max_commits,
+            since,
+            allowed_authors,
+            max_blob_size,
+            verify_signatures: args.verify_signatures,
+        })
+    }
+}
+
+/// An allowlist entry matched against author emails and names; `*` is a wildcard
+#[derive(Debug)]
+pub struct AuthorPattern(Regex);
+
+impl AuthorPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = format!("(?i)^{}$", regex::escape(pattern.trim()).replace(r"\*", ".*"));
+        Ok(Self(Regex::new(&regex)?))
+    }
+
+    fn matches(&self, name: &str, email: &str) -> bool {
+        self.0.is_match(email) || self.0.is_match(name)
+    }
+}
+
+// =============================================================================
+// Findings
+// =============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FindingKind {
+    Unsigned,
+    BadSignature,
+    UnknownAuthor,
+    LargeBlob,
+    ForcePush,
+}
+
+impl FindingKind {
+    fn title(&self) -> &'static str {
+        match self {
+            FindingKind::Unsigned => "Unsigned commits",
+            FindingKind::BadSignature => "Signatures that failed verification",
+            FindingKind::UnknownAuthor => "Authors outside the allowlist",
+            FindingKind::LargeBlob => "Large blobs",
+            FindingKind::ForcePush => "Force pushes",
+        }
+    }
+}
+
+/// One thing a reviewer should look at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind,
+    /// Commit the finding is about, in full
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Ref whose reflog shows a force push
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub date: String,
+    pub detail: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subject = self.commit.as_deref()
+            .map(|id| id[..8.min(id.len())].to_string())
+            .or_else(|| self.reference.clone())
+            .unwrap_or_default();
+        write!(f, "{} {} ({})", subject, self.detail, self.date)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuditReport {
+    /// What was audited, e.g. `HEAD` or `v1.0..main`
+    pub range: String,
+    pub commits_checked: usize,
+    pub refs_checked: usize,
+    /// Whether authors were checked against an allowlist
+    pub author_check: bool,
+    pub findings: Vec<Finding>,
+}
+
+impl AuditReport {
+    fn count(&self, kind: FindingKind) -> usize {
+        self.findings.iter().filter(|f| f.kind == kind).count()
+    }
+}
+
+// =============================================================================
+// Auditing
+// =============================================================================
+
+/// Walk history and the remote-tracking reflogs for findings
+pub fn audit(repo: &Repository, options: &AuditOptions) -> Result<AuditReport> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+    let range = options.revision.clone().unwrap_or_else(|| "HEAD".to_string());
+    resolve_revision(repo, &range)?.push_to(repo, &mut revwalk)?;
+    // A range bounds the walk itself
+    let limit = if range.contains("..") { usize::MAX } else { options.max_commits };
+
+    let mut findings = Vec::new();
+    let mut commits_checked = 0;
+    for oid in revwalk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        if options.since.is_some_and(|since| commit.time().seconds() < since) {
+            continue;
+        }
+        commits_checked += 1;
+        findings.extend(check_signature(repo, &commit, options.verify_signatures));
+        findings.extend(check_author(&commit, &options.allowed_authors));
+        findings.extend(check_blobs(repo, &commit, options.max_blob_size)?);
+    }
+
+    let (refs_checked, force_pushes) = find_force_pushes(repo, options.since)?;
+    findings.extend(force_pushes);
+    // Group by kind, keeping history order within each group
+    findings.sort_by_key(|finding| finding.kind);
+
+    Ok(AuditReport {
+        range,
+        commits_checked,
+        refs_checked,
+        author_check: !options.allowed_authors.is_empty(),
+        findings,
+    })
+}
+
+fn commit_finding(kind: FindingKind, commit: &Commit, detail: String) -> Finding {
+    Finding {
+        kind,
+        commit: Some(commit.id().to_string()),
+        reference: None,
+        date: format_date(commit.time()),
+        detail,
+    }
+}
+
+fn check_signature(repo: &Repository, commit: &Commit, verify: bool) -> Option<Finding> {
+    let summary = commit.summary().unwrap_or("").to_string();
+    if repo.extract_signature(&commit.id(), None).is_err() {
+        return Some(commit_finding(FindingKind::Unsigned, commit, summary));
+    }
+    if !verify {
+        return None;
+    }
+    match verify_commit(repo, commit.id()) {
+        Ok(()) => None,
+        Err(reason) => Some(commit_finding(FindingKind::BadSignature, commit, format!("{}: {}", summary, reason))),
+    }
+}
+
+/// Check a commit signature with `git verify-commit`, which honors gpg.program
+/// and gpg.ssh.allowedSignersFile
+fn verify_commit(repo: &Repository, id: Oid) -> std::result::Result<(), String> {
+    let output = Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["verify-commit", &id.to_string()])
+        .output()
+        .map_err(|_| "git is not installed".to_string())?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr.lines().map(str::trim).rfind(|line| !line.is_empty()).unwrap_or("verification failed").to_string())
+}
+
+fn check_author(commit: &Commit, allowed: &[AuthorPattern]) -> Option<Finding> {
+    if allowed.is_empty() {
+        return None;
+    }
+    let author = commit.author();
+    let (name, email) = (author.name().unwrap_or(""), author.email().unwrap_or(""));
+    if allowed.iter().any(|pattern| pattern.matches(name, email)) {
+        return None;
+    }
+    Some(commit_finding(FindingKind::UnknownAuthor, commit, format!("{} <{}>", name, email)))
+}
+
+/// Blobs over the limit that a commit adds or changes. Merges are skipped;
+/// their blobs come from commits that are checked on their own.
+fn check_blobs(repo: &Repository, commit: &Commit, max_size: u64) -> Result<Vec<Finding>> {
+    if commit.parent_count() > 1 {
+        return Ok(Vec::new());
+    }
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let odb = repo.odb()?;
+
+    let mut findings = Vec::new();
+    for delta in diff.deltas().filter(|d| matches!(d.status(), Delta::Added | Delta::Modified)) {
+        let file = delta.new_file();
+        let Ok((size, ObjectType::Blob)) = odb.read_header(file.id()) else { continue };
+        if size as u64 > max_size {
+            let path = file.path().map(|p| p.display().to_string()).unwrap_or_default();
+            findings.push(commit_finding(FindingKind::LargeBlob, commit,
+                    format!("{} is {}", path, humanize_size(size as u64))));
+        }
+    }
+    Ok(findings)
+}
+
+/// Non-fast-forward updates in the reflogs of remote-tracking refs: someone
+/// force pushed upstream, or this clone force pushed. Returns the number of
+/// refs checked along with the findings.
+fn find_force_pushes(repo: &Repository, since: Option<i64>) -> Result<(usize, Vec<Finding>)> {
+    let mut findings = Vec::new();
+    let mut checked = HashSet::new();
+
+    for reference in repo.references_glob("refs/remotes/*")? {
+        let reference = reference?;
+        let Some(name) = reference.name().map(str::to_string) else { continue };
+        if reference.symbolic_target().is_some() || !checked.insert(name.clone()) {
+            continue;
+        }
+        let Ok(reflog) = repo.reflog(&name) else { continue };
+
+        for entry in reflog.iter() {
+            let (old, new) = (entry.id_old(), entry.id_new());
+            if old.is_zero() || new.is_zero() || old == new {
+                continue;
+            }
+            let when = entry.committer().when();
+            if since.is_some_and(|since| when.seconds() < since) {
+                continue;
+            }
+            // A missing old commit means history was rewritten and pruned
+            if repo.graph_descendant_of(new, old).unwrap_or(false) {
+                continue;
+            }
+            let message = entry.message().unwrap_or("");
+            let by = if message.contains("push") { "pushed from this clone" } else { "seen on fetch" };
+            findings.push(Finding {
+                kind: FindingKind::ForcePush,
+                commit: None,
+                reference: Some(name.clone()),
+                date: format_date(when),
+                detail: format!("{} -> {} rewrote history ({})", shorten_oid(&old, 8), shorten_oid(&new, 8), by),
+            });
+        }
+    }
+    Ok((checked.len(), findings))
+}
+
+// =============================================================================
+// Rendering
+// =============================================================================
+
+const KINDS: [FindingKind; 5] = [
+    FindingKind::Unsigned,
+    FindingKind::BadSignature,
+    FindingKind::UnknownAuthor,
+    FindingKind::LargeBlob,
+    FindingKind::ForcePush,
+];
+
+/// The report for a terminal; findings quote commits, so only the glyphs are decorations
+fn render_text(report: &AuditReport) -> String {
+    let mut lines = vec![
+        format!("{} Audited {} commit{} of {} and the reflogs of {} remote-tracking ref{}",
+                decoration("🔍").blue(),
+                report.commits_checked, if report.commits_checked == 1 { "" } else { "s" },
+                report.range.cyan(),
+                report.refs_checked, if report.refs_checked == 1 { "" } else { "s" }),
+    ];
+    if !report.author_check {
+        lines.push(format!("{} No author allowlist; set {} to check authors",
+                decoration("💡").blue(), "audit.allowed_authors".cyan()));
+    }
+
+    for kind in KINDS {
+        let findings: Vec<&Finding> = report.findings.iter().filter(|f| f.kind == kind).collect();
+        if findings.is_empty() {
+            continue;
+        }
+        lines.push(String::new());
+        lines.push(format!("{} {} ({})", decoration("⚠️").yellow(), kind.title().bold(), findings.len()));
+        lines.extend(findings.iter().map(|finding| format!("  {} {}", decoration("•"), finding)));
+    }
+
+    lines.push(String::new());
+    if report.findings.is_empty() {
+        lines.push(format!("{} No findings", decoration("✅").green().bold()));
+    } else {
+        lines.push(format!("{} {} finding{} to review", decoration("❌").red(), report.findings.len(),
+                if report.findings.len() == 1 { "" } else { "s" }));
+    }
+    lines.join("\n")
+}
+
+fn render_markdown(report: &AuditReport) -> String {
+    let mut out = vec![
+        "# Repository audit".to_string(),
+        String::new(),
+        format!("- Range: `{}`", report.range),
+        format!("- Commits checked: {}", report.commits_checked),
+        format!("- Remote-tracking refs checked: {}", report.refs_checked),
+        format!("- Author allowlist: {}", if report.author_check { "checked" } else { "not configured" }),
+        format!("- Findings: {}", report.findings.len()),
+        String::new(),
+        "| Check | Findings |".to_string(),
+        "| --- | ---: |".to_string(),
+    ];
+    out.extend(KINDS.iter().map(|kind| format!("| {} | {} |", kind.title(), report.count(*kind))));
+
+    for kind in KINDS {
+        let findings: Vec<&Finding> = report.findings.iter().filter(|f| f.kind == kind).collect();
+        if findings.is_empty() {
+            continue;
+        }
+        out.push(String::new());
+        out.push(format!("## {}", kind.title()));
+        out.push(String::new());
+        out.push("| Commit or ref | Date | Detail |".to_string());
+        out.push("| --- | --- | --- |".to_string());
+        for finding in findings {
+            let subject = finding.commit.as_deref()
+                .map(|id| format!("`{}`", &id[..8.min(id.len())]))
+                .or_else(|| finding.reference.as_ref().map(|r| format!("`{}`", r)))
+                .unwrap_or_default();
+            out.push(format!("| {} | {} | {} |", subject, finding.date, finding.detail.replace('|', "\\|")));
+        }
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn options() -> AuditOptions {
+        AuditOptions {
+            revision: None,
+            max_commits: 100,
+            since: None,
+            allowed_authors: Vec::new(),
+            max_blob_size: 1024,
+            verify_signatures: false,
+        }
+    }
+
+    #[test]
+    fn test_audit_findings() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "hello\n", "Initial commit");
+        repo.commit_file("data.bin", &"x".repeat(2048), "Add data");
+
+        let report = audit(repo.repo(), &options()).unwrap();
+        assert_eq!(report.commits_checked, 2);
+        assert_eq!(report.count(FindingKind::Unsigned), 2);
+        assert_eq!(report.count(FindingKind::UnknownAuthor), 0);
+        let large: Vec<&Finding> = report.findings.iter().filter(|f| f.kind == FindingKind::LargeBlob).collect();
+        assert_eq!(large.len(), 1);
+        assert_eq!(large[0].detail, "data.bin is 2.0 KB");
+
+        let mut strict = options();
+        strict.allowed_authors = vec![AuthorPattern::new("*@elsewhere.org").unwrap()];
+        assert_eq!(audit(repo.repo(), &strict).unwrap().count(FindingKind::UnknownAuthor), 2);
+    }
+
+    #[test]
+    fn test_author_patterns() {
+        let pattern = AuthorPattern::new("*@Example.com").unwrap();
+        assert!(pattern.matches("Someone", "dev@example.com"));
+        assert!(!pattern.matches("Someone", "dev@example.com.evil"));
+        assert!(AuthorPattern::new("Release Bot").unwrap().matches("release bot", "bot@ci"));
+    }
+
+    #[test]
+    fn test_force_push_in_reflog() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "a\n", "First");
+        let second = repo.commit_file("a.txt", "b\n", "Second");
+        let git = repo.repo();
+        git.reference("refs/remotes/origin/main", first, true, "fetch: fast-forward").unwrap();
+        git.reference("refs/remotes/origin/main", second, true, "fetch: fast-forward").unwrap();
+        git.reference("refs/remotes/origin/main", first, true, "update by push").unwrap();
+
+        let (refs, findings) = find_force_pushes(git, None).unwrap();
+        assert_eq!(refs, 1);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].reference.as_deref(), Some("refs/remotes/origin/main"));
+        assert!(findings[0].detail.ends_with("(pushed from this clone)"));
+    }
+}
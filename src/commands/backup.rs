@@ -0,0 +1,593 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, Utc};
+use colored::*;
+use git2::build::TreeUpdateBuilder;
+use git2::{Commit, FileMode, Oid, Repository, Signature, Status, StatusOptions, Tree};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{BackupArgs, BackupCommands};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::safety;
+use crate::timings::{self, Phase};
+use crate::utils::{format_time_ago, shorten_oid};
+
+/// Directory inside `.git` holding one JSON manifest per snapshot
+const BACKUP_DIR: &str = "rgit/backups";
+
+/// Namespace mirroring each snapshot's refs, which keeps their objects alive
+const REF_PREFIX: &str = "refs/rgit/backups/";
+
+/// Ref (under a snapshot's namespace) holding the captured working tree
+const WORKTREE_REF: &str = "WORKTREE";
+
+/// Execute the backup command
+pub async fn execute(args: &BackupArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    match &args.action {
+        None => {
+            let request = SnapshotRequest {
+                label: args.name.clone(),
+                reason: None,
+                untracked: args.include_untracked,
+            };
+            let snapshot = create_snapshot(repo, &request, &snapshot_signature(rgit)?)?;
+            ui_println!("{} Saved snapshot {} ({})", "💾".blue(), snapshot.id.yellow(), snapshot.contents());
+            ui_println!("{} See all snapshots with {}", "💡".blue(), "rgit backup list".cyan());
+            Ok(())
+        }
+        Some(BackupCommands::List) => list(repo),
+        Some(BackupCommands::Prune { dry_run }) => {
+            let expired = prune(repo, config, *dry_run)?;
+            if expired.is_empty() {
+                ui_println!("{} No snapshots past the retention limits", "✅".green());
+                return Ok(());
+            }
+            let verb = if *dry_run { "Would delete" } else { "Deleted" };
+            ui_println!("{} {} {} snapshot{}:", "🧹".blue(), verb, expired.len(), plural(expired.len()));
+            for snapshot in &expired {
+                println!("  {} {} {}", "-".red(), snapshot.id, snapshot.describe().dimmed());
+            }
+            Ok(())
+        }
+        Some(BackupCommands::Drop { id }) => {
+            let snapshot = find_snapshot(repo, id)?;
+            if config.is_interactive() && config.advanced.safety.confirm_destructive {
+                let confirmed = InteractivePrompt::new()
+                    .with_message(format!("Delete snapshot {} ({})?", snapshot.id, snapshot.describe()))
+                    .confirm()?;
+                if !confirmed {
+                    return Err(RgitError::OperationCancelled.into());
+                }
+            }
+            delete_snapshot(repo, &snapshot)?;
+            ui_println!("{} Deleted snapshot {}", "🗑️".red(), snapshot.id);
+            Ok(())
+        }
+        Some(BackupCommands::Recover { id }) => recover(rgit, id.as_deref(), config),
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn list(repo: &Repository) -> Result<()> {
+    let snapshots = list_snapshots(repo)?;
+    let safety_snapshots = safety::list(repo)?;
+    if snapshots.is_empty() && safety_snapshots.is_empty() {
+        ui_println!("{} No snapshots yet; take one with {}", "💡".blue(), "rgit backup".cyan());
+        return Ok(());
+    }
+
+    for snapshot in &snapshots {
+        let head = match (&snapshot.head, &snapshot.head_oid) {
+            (Some(name), _) => name.trim_start_matches("refs/heads/").to_string(),
+            (None, Some(oid)) => Oid::from_str(oid).map(|id| shorten_oid(&id, 8)).unwrap_or_default(),
+            (None, None) => "no commits".to_string(),
+        };
+        println!("{} {:<14} {} {}",
+                snapshot.id.yellow(),
+                snapshot.age().dimmed(),
+                head.cyan(),
+                snapshot.describe());
+        println!("   {}", snapshot.contents().dimmed());
+    }
+
+    if !safety_snapshots.is_empty() {
+        if !snapshots.is_empty() {
+            println!();
+        }
+        ui_println!("{} {}", "🛟".blue(), "Safety snapshots, taken before destructive commands".bold());
+        for snapshot in &safety_snapshots {
+            println!("{} {:<14} {} before {}",
+                    snapshot.id.yellow(),
+                    snapshot.age().dimmed(),
+                    snapshot.head_description().cyan(),
+                    snapshot.operation);
+        }
+        ui_println!("{} Put one back with {}", "💡".blue(), "rgit backup recover [ID]".cyan());
+    }
+    Ok(())
+}
+
+/// Put back a safety snapshot, saving the current state in a new one first
+fn recover(rgit: &RgitCore, id: Option<&str>, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let snapshot = safety::find(repo, id)?;
+    ui_println!("{} Safety snapshot {} taken before {} ({})",
+            "🛟".blue(), snapshot.id.yellow(), snapshot.operation.bold(), snapshot.age());
+    println!("   {} at {}", snapshot.head_description().cyan(), shorten_oid(&snapshot.head, 8).yellow());
+
+    let saved = repo.find_commit(snapshot.commit)?.tree()?;
+    let diff = repo.diff_tree_to_workdir_with_index(Some(&saved), None)?;
+    let paths: Vec<String> = diff.deltas()
+        .filter_map(|delta| delta.old_file().path().or(delta.new_file().path()))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect();
+    if !paths.is_empty() {
+        println!("\n{}", "Files put back:".bold());
+        for path in &paths {
+            println!("  {} {}", "~".yellow(), path);
+        }
+    }
+    println!();
+
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message("Recover this snapshot? Where you are now is saved first")
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    let current = safety::take(repo, "backup recover", &snapshot_signature(rgit)?)?;
+    safety::recover(repo, &snapshot)?;
+    ui_println!("{} Back on {} with the files from before {}",
+            "✅".green().bold(), snapshot.head_description().cyan(), snapshot.operation);
+    if let Some(current) = current {
+        ui_println!("{} To return to where you were: {}", "💡".blue(), format!("rgit backup recover {}", current.id).cyan());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Snapshots
+// =============================================================================
+
+/// A recorded copy of every ref, plus uncommitted changes when there were any
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Snapshot {
+    /// Timestamp-based name, e.g. `20240102-150405`
+    pub id: String,
+    pub created: DateTime<Utc>,
+    /// Label given to a manual snapshot
+    pub label: Option<String>,
+    /// Operation an automatic snapshot was taken before, e.g. "rebase"
+    pub reason: Option<String>,
+    /// Branch HEAD pointed to (None when detached or unborn)
+    pub head: Option<String>,
+    pub head_oid: Option<String>,
+    /// Ref name to object id, as they were
+    pub refs: BTreeMap<String, String>,
+    /// Commit holding the index, when it differed from HEAD
+    pub index: Option<String>,
+    /// Commit holding the working tree (its parent is `index`)
+    pub worktree: Option<String>,
+    /// Whether untracked files were captured in `worktree`
+    pub untracked: bool,
+}
+
+impl Snapshot {
+    /// Automatic snapshots are subject to pruning; manual ones are kept
+    pub fn is_automatic(&self) -> bool {
+        self.reason.is_some()
+    }
+
+    /// Namespace holding this snapshot's refs
+    pub fn ref_namespace(&self) -> String {
+        format!("{}{}/", REF_PREFIX, self.id)
+    }
+
+    pub fn describe(&self) -> String {
+        match (&self.label, &self.reason) {
+            (Some(label), _) => label.clone(),
+            (None, Some(reason)) => format!("before {}", reason),
+            (None, None) => "manual".to_string(),
+        }
+    }
+
+    /// How long ago the snapshot was taken
+    pub fn age(&self) -> String {
+        format_time_ago(git2::Time::new(self.created.timestamp(), 0))
+    }
+
+    /// Summary such as "12 refs, local changes"
+    pub fn contents(&self) -> String {
+        let mut parts = vec![format!("{} ref{}", self.refs.len(), plural(self.refs.len()))];
+        if self.worktree.is_some() {
+            parts.push(if self.untracked { "local and untracked changes" } else { "local changes" }.to_string());
+        }
+        parts.join(", ")
+    }
+}
+
+/// What to capture in a new snapshot
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotRequest {
+    pub label: Option<String>,
+    pub reason: Option<String>,
+    pub untracked: bool,
+}
+
+fn backup_dir(repo: &Repository) -> PathBuf {
+    repo.path().join(BACKUP_DIR)
+}
+
+fn manifest_path(repo: &Repository, id: &str) -> PathBuf {
+    backup_dir(repo).join(format!("{}.json", id))
+}
+
+/// Record every ref and any uncommitted changes under a new snapshot
+pub fn create_snapshot(repo: &Repository, request: &SnapshotRequest, signature: &Signature) -> Result<Snapshot> {
+    let dir = backup_dir(repo);
+    fs::create_dir_all(&dir).with_context(|| format!("Failed to create backup directory: {}", dir.display()))?;
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut n = 2;
+    while manifest_path(repo, &id).exists() {
+        id = format!("{}-{}", stamp, n);
+        n += 1;
+    }
+
+    let head_commit = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let head = repo.find_reference("HEAD").ok()
+        .and_then(|head| head.symbolic_target().map(String::from))
+        .filter(|_| repo.head_detached().ok() == Some(false));
+    let mut snapshot = Snapshot {
+        id,
+        created: Utc::now(),
+        label: request.label.clone(),
+        reason: request.reason.clone(),
+        head,
+        head_oid: head_commit.as_ref().map(|c| c.id().to_string()),
+        refs: BTreeMap::new(),
+        index: None,
+        worktree: None,
+        untracked: request.untracked,
+    };
+
+    for (name, target) in current_refs(repo)? {
+        snapshot.refs.insert(name, target.to_string());
+    }
+
+    let namespace = snapshot.ref_namespace();
+    for (name, target) in &snapshot.refs {
+        let mirror = format!("{}{}", namespace, name.trim_start_matches("refs/"));
+        repo.reference(&mirror, Oid::from_str(target)?, true, "rgit backup")?;
+    }
+    if let Some(commit) = &head_commit {
+        repo.reference(&format!("{}HEAD", namespace), commit.id(), true, "rgit backup")?;
+    }
+    if let Some((index, worktree)) = capture_changes(repo, head_commit.as_ref(), request.untracked, signature)? {
+        repo.reference(&format!("{}{}", namespace, WORKTREE_REF), worktree, true, "rgit backup")?;
+        snapshot.index = Some(index.to_string());
+        snapshot.worktree = Some(worktree.to_string());
+    }
+
+    fs::write(manifest_path(repo, &snapshot.id), serde_json::to_string_pretty(&snapshot)?)?;
+    Ok(snapshot)
+}
+
+/// Direct refs outside rgit's own namespace
+fn current_refs(repo: &Repository) -> Result<BTreeMap<String, Oid>> {
+    let mut refs = BTreeMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        let (Some(name), Some(target)) = (reference.name(), reference.target()) else { continue };
+        if !name.starts_with("refs/rgit/") {
+            refs.insert(name.to_string(), target);
+        }
+    }
+    Ok(refs)
+}
+
+/// Commit the index and the working tree on top of HEAD, stash-style.
+/// Returns `None` when there is nothing uncommitted, in a bare repository,
+/// or while the index has conflicts.
+fn capture_changes(repo: &Repository, head: Option<&Commit>, untracked: bool, signature: &Signature) -> Result<Option<(Oid, Oid)>> {
+    let Some((index_tree, worktree_tree)) = snapshot_trees(repo, untracked)? else { return Ok(None) };
+    if head.map(|c| c.tree_id()) == Some(index_tree.id()) && worktree_tree.id() == index_tree.id() {
+        return Ok(None);
+    }
+
+    let parents: Vec<&Commit> = head.into_iter().collect();
+    let index_commit = repo.commit(None, signature, signature, "rgit backup: index", &index_tree, &parents)?;
+    let index_commit = repo.find_commit(index_commit)?;
+    let worktree = repo.commit(None, signature, signature, "rgit backup: working tree", &worktree_tree, &[&index_commit])?;
+    Ok(Some((index_commit.id(), worktree)))
+}
+
+/// The index as a tree, and the working tree laid over it, with untracked
+/// files when `untracked` is set. `None` in a bare repository or while the
+/// index has conflicts.
+pub fn snapshot_trees(repo: &Repository, untracked: bool) -> Result<Option<(Tree<'_>, Tree<'_>)>> {
+    let Some(workdir) = repo.workdir() else { return Ok(None) };
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        return Ok(None);
+    }
+    let index_tree = repo.find_tree(index.write_tree()?)?;
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(untracked)
+        .recurse_untracked_dirs(true)
+        .exclude_submodules(true);
+    let mut update = TreeUpdateBuilder::new();
+    for entry in timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?.iter() {
+        let status = entry.status();
+        let Some(path) = entry.path() else { continue };
+        if status.contains(Status::WT_DELETED) {
+            update.remove(path);
+        } else if status.intersects(Status::WT_MODIFIED | Status::WT_TYPECHANGE | Status::WT_NEW) {
+            let (blob, mode) = worktree_blob(repo, &workdir.join(path))?;
+            update.upsert(path, blob, mode);
+        }
+    }
+    let worktree_tree = repo.find_tree(update.create_updated(repo, &index_tree)?)?;
+    Ok(Some((index_tree, worktree_tree)))
+}
+
+/// Write a working tree file as a blob, keeping symlinks and the executable bit
+fn worktree_blob(repo: &Repository, path: &Path) -> Result<(Oid, FileMode)> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        let target = fs::read_link(path)?;
+        return Ok((repo.blob(target.to_string_lossy().as_bytes())?, FileMode::Link));
+    }
+
+    #[cfg(unix)]
+    let executable = {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    };
+    #[cfg(not(unix))]
+    let executable = false;
+
+    let mode = if executable { FileMode::BlobExecutable } else { FileMode::Blob };
+    Ok((repo.blob_path(path)?, mode))
+}
+
+/// All snapshots, newest first. Unreadable manifests are skipped.
+pub fn list_snapshots(repo: &Repository) -> Result<Vec<Snapshot>> {
+    let dir = backup_dir(repo);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots: Vec<Snapshot> = fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|text| serde_json::from_str(&text).ok())
+        .collect();
+    snapshots.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| b.id.cmp(&a.id)));
+    Ok(snapshots)
+}
+
+/// A snapshot by id or unique id prefix
+pub fn find_snapshot(repo: &Repository, id: &str) -> Result<Snapshot> {
+    let mut matches: Vec<Snapshot> = list_snapshots(repo)?
+        .into_iter()
+        .filter(|snapshot| snapshot.id.starts_with(id))
+        .collect();
+    if let Some(exact) = matches.iter().position(|snapshot| snapshot.id == id) {
+        return Ok(matches.swap_remove(exact));
+    }
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(RgitError::InvalidArgument(format!("no snapshot named '{}'", id)).into()),
+        _ => Err(RgitError::InvalidArgument(format!("'{}' matches {} snapshots", id, matches.len())).into()),
+    }
+}
+
+/// Remove a snapshot's refs and manifest
+pub fn delete_snapshot(repo: &Repository, snapshot: &Snapshot) -> Result<()> {
+    let namespace = snapshot.ref_namespace();
+    let names: Vec<String> = repo.references()?
+        .filter_map(|reference| reference.ok())
+        .filter_map(|reference| reference.name().map(String::from))
+        .filter(|name| name.starts_with(&namespace))
+        .collect();
+    for name in names {
+        repo.find_reference(&name)?.delete()?;
+    }
+
+    let manifest = manifest_path(repo, &snapshot.id);
+    if manifest.exists() {
+        fs::remove_file(manifest)?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Comparing
+// =============================================================================
+
+/// A ref whose value differs between a snapshot and now
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefChange {
+    pub name: String,
+    /// Value in the snapshot; None when the ref was created since
+    pub snapshot: Option<Oid>,
+    /// Current value; None when the ref was deleted since
+    pub current: Option<Oid>,
+}
+
+impl RefChange {
+    /// Short form of the ref name, e.g. `heads/main`
+    pub fn short_name(&self) -> &str {
+        self.name.trim_start_matches("refs/")
+    }
+}
+
+/// Refs that moved, appeared or disappeared since the snapshot was taken
+pub fn ref_changes(repo: &Repository, snapshot: &Snapshot) -> Result<Vec<RefChange>> {
+    let current = current_refs(repo)?;
+    let mut names: Vec<&String> = snapshot.refs.keys().chain(current.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut changes = Vec::new();
+    for name in names {
+        let then = snapshot.refs.get(name).map(|oid| Oid::from_str(oid)).transpose()?;
+        let now = current.get(name).copied();
+        if then != now {
+            changes.push(RefChange { name: name.clone(), snapshot: then, current: now });
+        }
+    }
+    Ok(changes)
+}
+
+/// Files as the snapshot saw them: the captured working tree, or else the
+/// tree HEAD pointed to. None for a snapshot of an unborn branch.
+pub fn snapshot_tree<'r>(repo: &'r Repository, snapshot: &Snapshot) -> Result<Option<Tree<'r>>> {
+    let Some(commit) = snapshot.worktree.as_ref().or(snapshot.head_oid.as_ref()) else {
+        return Ok(None);
+    };
+    Ok(Some(repo.find_commit(Oid::from_str(commit)?)?.tree()?))
+}
+
+// =============================================================================
+// Retention
+// =============================================================================
+
+/// Automatic snapshots beyond the newest `keep` or older than
+/// `retention_days`; zero disables either limit. `snapshots` must be
+/// newest first.
+pub fn expired_snapshots(snapshots: &[Snapshot], keep: usize, retention_days: u32, now: DateTime<Utc>) -> Vec<Snapshot> {
+    snapshots.iter()
+        .filter(|snapshot| snapshot.is_automatic())
+        .enumerate()
+        .filter(|(n, snapshot)| {
+            (keep > 0 && *n >= keep)
+                || (retention_days > 0 && now - snapshot.created > Duration::days(retention_days.into()))
+        })
+        .map(|(_, snapshot)| snapshot.clone())
+        .collect()
+}
+
+/// Delete automatic snapshots past `backup.keep` and
+/// `advanced.safety.backup_retention`, returning them
+pub fn prune(repo: &Repository, config: &Config, dry_run: bool) -> Result<Vec<Snapshot>> {
+    let expired = expired_snapshots(&list_snapshots(repo)?, config.backup.keep, config.advanced.safety.backup_retention, Utc::now());
+    if !dry_run {
+        for snapshot in &expired {
+            delete_snapshot(repo, snapshot)?;
+        }
+    }
+    Ok(expired)
+}
+
+/// Snapshot the repository before a destructive operation, when
+/// `backup.auto_before_destructive` and `advanced.safety.auto_backup` allow it
+pub fn snapshot_before(rgit: &RgitCore, config: &Config, operation: &str) -> Result<Option<Snapshot>> {
+    if !config.backup.auto_before_destructive || !config.advanced.safety.auto_backup {
+        return Ok(None);
+    }
+
+    let request = SnapshotRequest { reason: Some(operation.to_string()), ..Default::default() };
+    let snapshot = create_snapshot(&rgit.repo, &request, &snapshot_signature(rgit)?)
+        .with_context(|| format!("Failed to snapshot the repository before {}", operation))?;
+    prune(&rgit.repo, config, false)?;
+    ui_println!("{} {}", "💾".blue(), format!("Snapshot {} saved before {}", snapshot.id, operation).dimmed());
+    Ok(Some(snapshot))
+}
+
+/// The user's signature, or a placeholder so snapshots never block on identity
+pub fn snapshot_signature(rgit: &RgitCore) -> Result<Signature<'_>> {
+    match rgit.get_signature() {
+        Ok(signature) => Ok(signature),
+        Err(_) => Ok(Signature::now("rgit", "rgit@localhost")?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn signature() -> Signature<'static> {
+        Signature::now("Test", "test@example.com").unwrap()
+    }
+
+    #[test]
+    fn test_snapshot_records_refs_and_changes() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        repo.branch("feature");
+        repo.write("a.txt", "changed\n").write("new.txt", "new\n");
+
+        let request = SnapshotRequest { label: Some("safe".to_string()), untracked: true, ..Default::default() };
+        let snapshot = create_snapshot(repo.repo(), &request, &signature()).unwrap();
+        assert_eq!(snapshot.head.as_deref(), Some("refs/heads/main"));
+        assert_eq!(snapshot.refs.get("refs/heads/feature"), Some(&first.to_string()));
+
+        let mirrored = repo.repo().refname_to_id(&format!("{}heads/feature", snapshot.ref_namespace())).unwrap();
+        assert_eq!(mirrored, first);
+        let worktree = repo.repo().find_commit(Oid::from_str(snapshot.worktree.as_ref().unwrap()).unwrap()).unwrap();
+        let blob = worktree.tree().unwrap().get_path(Path::new("a.txt")).unwrap().to_object(repo.repo()).unwrap();
+        assert_eq!(blob.as_blob().unwrap().content(), b"changed\n");
+        assert!(worktree.tree().unwrap().get_path(Path::new("new.txt")).is_ok());
+
+        assert_eq!(list_snapshots(repo.repo()).unwrap(), vec![snapshot.clone()]);
+        delete_snapshot(repo.repo(), &snapshot).unwrap();
+        assert!(list_snapshots(repo.repo()).unwrap().is_empty());
+        assert!(repo.repo().refname_to_id(&format!("{}heads/feature", snapshot.ref_namespace())).is_err());
+    }
+
+    #[test]
+    fn test_clean_tree_has_no_changes() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+
+        let snapshot = create_snapshot(repo.repo(), &SnapshotRequest::default(), &signature()).unwrap();
+        assert_eq!(snapshot.worktree, None);
+        assert_eq!(find_snapshot(repo.repo(), &snapshot.id[..8]).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_retention_only_prunes_automatic_snapshots() {
+        let now = Utc::now();
+        let snapshot = |id: &str, days: i64, reason: Option<&str>| Snapshot {
+            id: id.to_string(),
+            created: now - Duration::days(days),
+            label: None,
+            reason: reason.map(String::from),
+            head: None,
+            head_oid: None,
+            refs: BTreeMap::new(),
+            index: None,
+            worktree: None,
+            untracked: false,
+        };
+        let snapshots = vec![
+            snapshot("d", 0, Some("reset")),
+            snapshot("c", 1, Some("rebase")),
+            snapshot("b", 2, None),
+            snapshot("a", 40, Some("clean")),
+        ];
+
+        let ids = |expired: Vec<Snapshot>| expired.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(expired_snapshots(&snapshots, 0, 30, now)), vec!["a"]);
+        assert_eq!(ids(expired_snapshots(&snapshots, 1, 0, now)), vec!["c", "a"]);
+        assert!(expired_snapshots(&snapshots, 0, 0, now).is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use colored::*;
+use git2::{Blame, Oid, Repository, Time};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::cli::BlameArgs;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::TableDisplay;
+use crate::output::decoration;
+use crate::theme::{Element, Themed};
+use crate::utils::{create_progress_bar, current_time, shorten_oid};
+use crate::validation::validate_pathspec;
+
+/// Heatmap colors from the newest commits to the oldest
+const HEAT_COLORS: [Color; 5] = [
+    Color::TrueColor { r: 215, g: 48, b: 39 },
+    Color::TrueColor { r: 252, g: 141, b: 89 },
+    Color::TrueColor { r: 254, g: 224, b: 144 },
+    Color::TrueColor { r: 145, g: 191, b: 219 },
+    Color::TrueColor { r: 69, g: 117, b: 180 },
+];
+
+/// Width of the share bars in `--summary`
+const SHARE_BAR_WIDTH: usize = 15;
+
+/// Author shown for lines changed in the working tree
+pub(crate) const UNCOMMITTED_AUTHOR: &str = "Not Committed Yet";
+
+/// A line of the file and the commit that last changed it
+#[derive(Debug, Clone)]
+pub(crate) struct BlameLine {
+    pub(crate) number: usize,
+    /// Zero for lines changed in the working tree
+    pub(crate) commit: Oid,
+    pub(crate) author: String,
+    pub(crate) email: String,
+    pub(crate) time: Time,
+    pub(crate) content: String,
+}
+
+/// The lines one author last changed
+#[derive(Debug, Clone, PartialEq)]
+struct AuthorShare {
+    author: String,
+    lines: usize,
+    commits: usize,
+    last_touched: Time,
+}
+
+/// Execute the blame command
+pub async fn execute(args: &BlameArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.reverse {
+        return Err(RgitError::InvalidArgument("blame --reverse is not supported yet".to_string()).into());
+    }
+    let range = args.line_range.as_deref()
+        .map(|spec| parse_line_range(spec).ok_or_else(|| {
+            RgitError::InvalidArgument(format!("Invalid line range '{}', expected START,END or START,+COUNT", spec))
+        }))
+        .transpose()?;
+
+    let path = match rgit.repo.workdir() {
+        Some(workdir) => validate_pathspec(workdir, &std::env::current_dir()?, &args.file)?,
+        None => args.file.clone(),
+    };
+    let mut lines = blame_file(&rgit.repo, &path)?;
+    if let Some((start, end)) = range {
+        if start > lines.len() {
+            return Err(RgitError::InvalidArgument(format!("{} has only {} lines", path, lines.len())).into());
+        }
+        lines.retain(|line| (start..=end).contains(&line.number));
+    }
+
+    if args.summary {
+        show_summary(&path, &lines, args.heatmap, config);
+    } else {
+        show_lines(&lines, args.heatmap);
+    }
+    Ok(())
+}
+
+/// Blame every line of `path` as it is in the working tree, or at HEAD in
+/// a bare repository
+pub(crate) fn blame_file(repo: &Repository, path: &str) -> Result<Vec<BlameLine>> {
+    let committed = repo.blame_file(Path::new(path), None)
+        .map_err(|_| RgitError::InvalidArgument(format!("{} is not tracked at HEAD", path)))?;
+
+    let content = match repo.workdir().map(|workdir| workdir.join(path)).filter(|file| file.is_file()) {
+        Some(file) => std::fs::read(file)?,
+        None => {
+            let entry = repo.head()?.peel_to_tree()?.get_path(Path::new(path))?;
+            repo.find_blob(entry.id())?.content().to_vec()
+        }
+    };
+    if content.is_empty() {
+        return Ok(Vec::new());
+    }
+    if content.contains(&0) {
+        return Err(RgitError::InvalidArgument(format!("{} is a binary file", path)).into());
+    }
+    // Blaming the working tree content attributes local edits to nobody yet
+    let blame = committed.blame_buffer(&content)?;
+
+    collect_lines(repo, &blame, &String::from_utf8_lossy(&content))
+}
+
+fn collect_lines(repo: &Repository, blame: &Blame, text: &str) -> Result<Vec<BlameLine>> {
+    let mailmap = repo.mailmap().ok();
+    let mut authors: HashMap<Oid, (String, String, Time)> = HashMap::new();
+    let mut lines = Vec::new();
+
+    for (index, content) in text.lines().enumerate() {
+        let number = index + 1;
+        let commit = blame.get_line(number).map(|hunk| hunk.final_commit_id()).unwrap_or_else(Oid::zero);
+        if let Entry::Vacant(entry) = authors.entry(commit) {
+            let author = if commit.is_zero() {
+                (UNCOMMITTED_AUTHOR.to_string(), String::new(), current_time())
+            } else {
+                let found = repo.find_commit(commit)?;
+                let signature = match &mailmap {
+                    Some(mailmap) => found.author_with_mailmap(mailmap)?,
+                    None => found.author(),
+                };
+                (
+                    signature.name().unwrap_or("unknown").to_string(),
+                    signature.email().unwrap_or("").to_string(),
+                    signature.when(),
+                )
+            };
+            entry.insert(author);
+        }
+        let (author, email, time) = authors[&commit].clone();
+        lines.push(BlameLine { number, commit, author, email, time, content: content.to_string() });
+    }
+
+    Ok(lines)
+}
+
+/// Parse `START,END`, `START,+COUNT` or `START,` (to the end of the file)
+/// into an inclusive range of line numbers
+fn parse_line_range(spec: &str) -> Option<(usize, usize)> {
+    let (start, end) = spec.split_once(',')?;
+    let start = start.trim().parse::<usize>().ok().filter(|&start| start > 0)?;
+    let end = match end.trim() {
+        "" => usize::MAX,
+        end => match end.strip_prefix('+') {
+            Some(count) => start + count.parse::<usize>().ok()?.checked_sub(1)?,
+            None => end.parse().ok()?,
+        },
+    };
+    (end >= start).then_some((start, end))
+}
+
+// =============================================================================
+// Line view
+// =============================================================================
+
+fn show_lines(lines: &[BlameLine], heatmap: bool) {
+    let author_width = lines.iter().map(|line| console::measure_text_width(&line.author)).max().unwrap_or(0);
+    let number_width = lines.last().map(|line| line.number.to_string().len()).unwrap_or(1);
+    let heat = heatmap.then(|| HeatScale::new(lines.iter().filter(|line| !line.commit.is_zero()).map(|line| line.time)));
+
+    if let Some(heat) = &heat {
+        println!("{}", heat.legend());
+    }
+    for line in lines {
+        let commit = if line.commit.is_zero() { "0".repeat(8) } else { shorten_oid(&line.commit, 8) };
+        let padding = " ".repeat(author_width - console::measure_text_width(&line.author));
+        let header = match &heat {
+            Some(heat) => format!("{} ({}{} {}", commit, line.author, padding, day(line.time))
+                .color(heat.color(line.time))
+                .to_string(),
+            None => format!("{} ({}{} {}", commit.as_str().themed(Element::Commit), line.author, padding, day(line.time).dimmed()),
+        };
+        println!("{} {:>width$}) {}", header, line.number, line.content, width = number_width);
+    }
+}
+
+// =============================================================================
+// Summary view
+// =============================================================================
+
+/// Lines per author, most lines first
+fn summarize(lines: &[BlameLine]) -> Vec<AuthorShare> {
+    let mut by_author: HashMap<String, (AuthorShare, Vec<Oid>)> = HashMap::new();
+    for line in lines {
+        // Authors are told apart by email, which outlives name changes
+        let key = if line.email.is_empty() { line.author.clone() } else { line.email.to_lowercase() };
+        let (share, commits) = by_author.entry(key).or_insert_with(|| (
+            AuthorShare { author: line.author.clone(), lines: 0, commits: 0, last_touched: line.time },
+            Vec::new(),
+        ));
+        share.lines += 1;
+        if line.time > share.last_touched {
+            share.last_touched = line.time;
+        }
+        if !commits.contains(&line.commit) {
+            commits.push(line.commit);
+        }
+    }
+
+    let mut shares: Vec<AuthorShare> = by_author.into_values()
+        .map(|(share, commits)| AuthorShare { commits: commits.len(), ..share })
+        .collect();
+    shares.sort_by(|a, b| b.lines.cmp(&a.lines).then_with(|| a.author.cmp(&b.author)));
+    shares
+}
+
+fn show_summary(path: &str, lines: &[BlameLine], heatmap: bool, config: &Config) {
+    let shares = summarize(lines);
+    ui_println!("{} Ownership of {} ({} lines, {} author{})",
+            "📊".blue(), path.cyan(), lines.len(), shares.len(), if shares.len() == 1 { "" } else { "s" });
+    if shares.is_empty() {
+        return;
+    }
+
+    let heat = heatmap.then(|| HeatScale::new(lines.iter().filter(|line| !line.commit.is_zero()).map(|line| line.time)));
+    if let Some(heat) = &heat {
+        println!("{}", heat.legend());
+    }
+    println!();
+
+    let mut table = TableDisplay::new().with_max_width(config.terminal_width()).with_headers(vec![
+        "Author".to_string(),
+        "Lines".to_string(),
+        "Share".to_string(),
+        "Commits".to_string(),
+        "Last touched".to_string(),
+    ]);
+    for share in &shares {
+        let percent = share.lines as f64 * 100.0 / lines.len() as f64;
+        let last_touched = day(share.last_touched);
+        let uncommitted = share.author == UNCOMMITTED_AUTHOR;
+        table.add_row(vec![
+            share.author.clone(),
+            share.lines.to_string(),
+            format!("{} {:>5.1}%", decoration(&create_progress_bar(share.lines, lines.len(), SHARE_BAR_WIDTH)), percent),
+            if uncommitted { "-".to_string() } else { share.commits.to_string() },
+            match &heat {
+                Some(heat) if !uncommitted => last_touched.color(heat.color(share.last_touched)).to_string(),
+                _ => last_touched,
+            },
+        ]);
+    }
+    table.display();
+}
+
+// =============================================================================
+// Heatmap
+// =============================================================================
+
+/// Maps commit times onto [`HEAT_COLORS`] across the span of the file's history
+#[derive(Debug, Clone, Copy)]
+struct HeatScale {
+    oldest: i64,
+    newest: i64,
+}
+
+impl HeatScale {
+    fn new(times: impl Iterator<Item = Time>) -> Self {
+        let seconds: Vec<i64> = times.map(|time| time.seconds()).collect();
+        Self {
+            oldest: seconds.iter().copied().min().unwrap_or(0),
+            newest: seconds.iter().copied().max().unwrap_or(0),
+        }
+    }
+
+    /// Index into [`HEAT_COLORS`], 0 for the newest commits and anything
+    /// newer, like uncommitted lines
+    fn level(&self, time: Time) -> usize {
+        if self.newest <= self.oldest {
+            return 0;
+        }
+        let age = (self.newest - time.seconds()).max(0) as f64 / (self.newest - self.oldest) as f64;
+        ((age * HEAT_COLORS.len() as f64) as usize).min(HEAT_COLORS.len() - 1)
+    }
+
+    fn color(&self, time: Time) -> Color {
+        HEAT_COLORS[self.level(time)]
+    }
+
+    fn legend(&self) -> String {
+        let swatches: String = HEAT_COLORS.iter().map(|&color| "██".color(color).to_string()).collect();
+        format!("{} {} {} {}",
+                day(Time::new(self.newest, 0)).dimmed(), swatches, day(Time::new(self.oldest, 0)).dimmed(),
+                "(newest to oldest)".dimmed())
+    }
+}
+
+/// The local date of `time`
+fn day(time: Time) -> String {
+    let utc = Utc.timestamp_opt(time.seconds(), 0).single().unwrap_or_else(Utc::now);
+    let local: DateTime<Local> = utc.into();
+    local.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_parse_line_range() {
+        assert_eq!(parse_line_range("10,20"), Some((10, 20)));
+        assert_eq!(parse_line_range("10,+5"), Some((10, 14)));
+        assert_eq!(parse_line_range("10,"), Some((10, usize::MAX)));
+        assert_eq!(parse_line_range("20,10"), None);
+        assert_eq!(parse_line_range("0,10"), None);
+        assert_eq!(parse_line_range("10,+0"), None);
+        assert_eq!(parse_line_range("10"), None);
+    }
+
+    #[test]
+    fn test_heat_level() {
+        let scale = HeatScale::new([Time::new(1000, 0), Time::new(2000, 0)].into_iter());
+        assert_eq!(scale.level(Time::new(2000, 0)), 0);
+        assert_eq!(scale.level(Time::new(1000, 0)), HEAT_COLORS.len() - 1);
+        assert_eq!(scale.level(Time::new(1500, 0)), 2);
+        assert_eq!(scale.level(Time::new(3000, 0)), 0);
+
+        let single = HeatScale::new(std::iter::once(Time::new(1000, 0)));
+        assert_eq!(single.level(Time::new(1000, 0)), 0);
+    }
+
+    #[test]
+    fn test_blame_summary() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("notes.txt", "one\ntwo\n", "First");
+        let repo = test_repo.repo();
+        let signature = git2::Signature::now("Other", "other@example.com").unwrap();
+        test_repo.write("notes.txt", "one\nTWO\nthree\n").stage("notes.txt");
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        let parent = repo.head().unwrap().peel_to_commit().unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Second", &tree, &[&parent]).unwrap();
+        // An uncommitted edit on top
+        test_repo.write("notes.txt", "one\nTWO\nthree\nfour\n");
+
+        let lines = blame_file(repo, "notes.txt").unwrap();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[1].author, "Other");
+        assert!(lines[3].commit.is_zero());
+
+        let shares = summarize(&lines);
+        assert_eq!(shares.iter().map(|share| (share.author.as_str(), share.lines)).collect::<Vec<_>>(),
+                   vec![("Other", 2), (UNCOMMITTED_AUTHOR, 1), (lines[0].author.as_str(), 1)]);
+        assert_eq!(shares[0].commits, 1);
+    }
+}
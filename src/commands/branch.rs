@@ -1,12 +1,16 @@
 use anyhow::Result;
 use colored::*;
-use git2::{Branch, BranchType, Repository};
+use git2::{Branch, BranchType, Repository, Time};
+use std::cmp::Ordering;
 
 use crate::cli::BranchArgs;
+use crate::commands::fetch;
 use crate::config::Config;
-use crate::core::RgitCore;
+use crate::core::{resolve_commit, RgitCore};
 use crate::error::RgitError;
 use crate::interactive::InteractivePrompt;
+use crate::utils::{format_columns, format_time_ago, pad_string, TextAlign};
+use crate::validation::validate_branch_name;
 
 /// Execute the branch command
 pub async fn execute(args: &BranchArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
@@ -18,16 +22,22 @@ pub async fn execute(args: &BranchArgs, rgit: &RgitCore, config: &Config) -> Res
         move_branch(repo, args, config).await
     } else if args.copy.is_some() {
         copy_branch(repo, args, config).await
+    } else if args.set_upstream_to.is_some() || args.unset_upstream {
+        change_upstream(repo, args)
     } else if let Some(branch_name) = &args.name {
         create_branch(repo, branch_name, args, config).await
     } else {
-        list_branches(repo, args, config).await
+        if !args.no_fetch {
+            fetch::auto_fetch(rgit, config).await;
+        }
+        list_branches(repo, args, config, rgit.verbose).await
     }
 }
 
 /// List branches
-async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config) -> Result<()> {
-    println!("{} Repository branches:", "🌿".green().bold());
+async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config, verbose: bool) -> Result<()> {
+    let sort = args.sort.as_deref().map(RefSort::parse).transpose()?.unwrap_or_default();
+    ui_println!("{} Repository branches:", "🌿".green().bold());
 
     let branch_type = if args.list {
         None // Show both local and remote when --list is true
@@ -50,12 +60,26 @@ async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config) ->
         collect_branches(repo, BranchType::Remote, &mut branches, &current_branch)?;
     }
 
-    // Sort branches by name
-    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    sort.apply(&mut branches, |b| (&b.name, b.dates));
 
     // Display branches
     if branches.is_empty() {
-        println!("  {} No branches found", "ℹ️".blue());
+        ui_println!("  {} No branches found", "ℹ️".blue());
+        return Ok(());
+    }
+
+    if args.column {
+        let names: Vec<String> = branches.iter().map(|b| format!("{} {}", branch_marker(b), branch_name(b))).collect();
+        for line in format_columns(&names, config.terminal_width()) {
+            println!("{}", line);
+        }
+        return Ok(());
+    }
+    if verbose {
+        let width = branches.iter().map(|b| b.name.chars().count()).max().unwrap_or(0);
+        for branch in &branches {
+            display_branch_verbose(branch, width);
+        }
         return Ok(());
     }
 
@@ -70,7 +94,7 @@ async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config) ->
 
         println!();
         if local_count > 0 {
-            println!(
+            ui_println!(
                 "{} {} local branch{}",
                 "📍".blue(),
                 local_count,
@@ -78,7 +102,7 @@ async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config) ->
             );
         }
         if remote_count > 0 {
-            println!(
+            ui_println!(
                 "{} {} remote branch{}",
                 "🌐".blue(),
                 remote_count,
@@ -87,10 +111,13 @@ async fn list_branches(repo: &Repository, args: &BranchArgs, config: &Config) ->
         }
 
         // Show next steps
-        println!("\n{} Commands:", "💡".blue());
-        println!("  • {} - Create new branch", "rgit branch <name>".cyan());
-        println!("  • {} - Switch to branch", "rgit checkout <name>".cyan());
-        println!("  • {} - Delete branch", "rgit branch -d <name>".cyan());
+        ui_println!("\n{} Commands:", "💡".blue());
+        ui_println!("  • {} - Create new branch", "rgit branch <name>".cyan());
+        ui_println!("  • {} - Switch to branch", "rgit checkout <name>".cyan());
+        ui_println!("  • {} - Delete branch", "rgit branch -d <name>".cyan());
+        if remote_count > 0 {
+            ui_println!("  • {} - Select remote branches to delete", "rgit push --delete".cyan());
+        }
     }
 
     Ok(())
@@ -107,6 +134,7 @@ struct BranchInfo {
     author: String,
     ahead_behind: Option<(usize, usize)>,
     upstream: Option<String>,
+    dates: RefDates,
 }
 
 /// Collect branches of a specific type
@@ -154,6 +182,7 @@ fn collect_branches(
                 author: author_name,
                 ahead_behind,
                 upstream,
+                dates: RefDates::of_commit(&commit),
             });
         }
     }
@@ -161,23 +190,57 @@ fn collect_branches(
     Ok(())
 }
 
-/// Display information for a single branch
-fn display_branch_info(branch: &BranchInfo, config: &Config) -> Result<()> {
-    let prefix = if branch.is_current {
+fn branch_marker(branch: &BranchInfo) -> ColoredString {
+    if branch.is_current {
         "*".green().bold()
     } else {
         " ".normal()
-    };
+    }
+}
 
-    let branch_color = if branch.is_current {
+fn branch_name(branch: &BranchInfo) -> ColoredString {
+    if branch.is_current {
         branch.name.green().bold()
     } else if branch.is_remote {
         branch.name.red()
     } else {
         branch.name.cyan()
+    }
+}
+
+/// One aligned line per branch: tip, upstream with ahead/behind, subject and age
+fn display_branch_verbose(branch: &BranchInfo, width: usize) {
+    let name = pad_string(&branch.name, width, TextAlign::Left);
+    let styled = if branch.is_current {
+        name.green().bold()
+    } else if branch.is_remote {
+        name.red()
+    } else {
+        name.cyan()
     };
 
-    print!("{} {}", prefix, branch_color);
+    let tracking = branch.upstream.as_ref().map(|upstream| {
+        let counts = match branch.ahead_behind {
+            Some((ahead, behind)) if ahead > 0 && behind > 0 => format!(": ahead {}, behind {}", ahead, behind),
+            Some((ahead, 0)) if ahead > 0 => format!(": ahead {}", ahead),
+            Some((0, behind)) if behind > 0 => format!(": behind {}", behind),
+            _ => String::new(),
+        };
+        format!("[{}{}] ", upstream.blue(), counts)
+    }).unwrap_or_default();
+
+    println!("{} {} {} {}{} {}",
+             branch_marker(branch),
+             styled,
+             branch.commit_id.yellow(),
+             tracking,
+             branch.commit_message,
+             format!("({})", format_time_ago(Time::new(branch.dates.committer, 0))).dimmed());
+}
+
+/// Display information for a single branch
+fn display_branch_info(branch: &BranchInfo, config: &Config) -> Result<()> {
+    print!("{} {}", branch_marker(branch), branch_name(branch));
 
     // Show upstream tracking
     if let Some(upstream) = &branch.upstream {
@@ -227,16 +290,14 @@ async fn create_branch(
     args: &BranchArgs,
     config: &Config,
 ) -> Result<()> {
-    println!(
+    ui_println!(
         "{} Creating branch '{}'",
         "🌱".green().bold(),
         branch_name.cyan()
     );
 
     // Validate branch name
-    if !is_valid_branch_name(branch_name) {
-        return Err(RgitError::InvalidBranchName(branch_name.to_string()).into());
-    }
+    validate_branch_name(branch_name)?;
 
     // Check if branch already exists
     if repo.find_branch(branch_name, BranchType::Local).is_ok() {
@@ -249,7 +310,7 @@ async fn create_branch(
     // Create the branch
     let branch = repo.branch(branch_name, &start_point, false)?;
 
-    println!(
+    ui_println!(
         "{} Branch '{}' created successfully",
         "✅".green(),
         branch_name.cyan()
@@ -262,14 +323,14 @@ async fn create_branch(
             .confirm()?;
         if switch {
             checkout_branch(repo, branch_name)?;
-            println!(
+            ui_println!(
                 "{} Switched to branch '{}'",
                 "🔄".green(),
                 branch_name.cyan()
             );
         }
     } else {
-        println!(
+        ui_println!(
             "{} Use 'rgit checkout {}' to switch to the new branch",
             "ℹ️".blue(),
             branch_name.cyan()
@@ -282,7 +343,7 @@ async fn create_branch(
 async fn delete_branch(repo: &Repository, args: &BranchArgs, config: &Config) -> Result<()> {
     let branch_name = args.delete.as_ref().unwrap();
 
-    println!(
+    ui_println!(
         "{} Deleting branch '{}'",
         "🗑️".red().bold(),
         branch_name.red()
@@ -297,7 +358,7 @@ async fn delete_branch(repo: &Repository, args: &BranchArgs, config: &Config) ->
     if args.force_delete.is_none() {
         if !is_branch_merged(repo, &branch)? {
             if config.is_interactive() {
-                println!(
+                ui_println!(
                     "{} Branch '{}' is not fully merged",
                     "⚠️".yellow(),
                     branch_name.yellow()
@@ -308,7 +369,7 @@ async fn delete_branch(repo: &Repository, args: &BranchArgs, config: &Config) ->
                     .confirm()?;
 
                 if !force_delete {
-                    println!("{} Branch deletion cancelled", "❌".red());
+                    ui_println!("{} Branch deletion cancelled", "❌".red());
                     return Ok(());
                 }
             } else {
@@ -325,7 +386,7 @@ async fn delete_branch(repo: &Repository, args: &BranchArgs, config: &Config) ->
     let mut branch = branch;
     branch.delete()?;
 
-    println!(
+    ui_println!(
         "{} Branch '{}' deleted successfully",
         "✅".green(),
         branch_name.cyan()
@@ -346,9 +407,7 @@ async fn move_branch(repo: &Repository, args: &BranchArgs, _config: &Config) ->
     };
 
     // Validate new branch name
-    if !is_valid_branch_name(new_name) {
-        return Err(RgitError::InvalidBranchName(new_name.to_string()).into());
-    }
+    validate_branch_name(new_name)?;
     // Check if target name already exists
     if repo.find_branch(new_name, BranchType::Local).is_ok() {
         return Err(RgitError::BranchAlreadyExists(new_name.to_string()).into());
@@ -359,7 +418,7 @@ async fn move_branch(repo: &Repository, args: &BranchArgs, _config: &Config) ->
         .map_err(|_| RgitError::BranchNotFound(old_name.to_string()))?;
     branch.rename(new_name, false)?;
 
-    println!(
+    ui_println!(
         "{} Branch '{}' renamed to '{}'",
         "✅".green(),
         old_name.cyan(),
@@ -382,7 +441,7 @@ async fn copy_branch(repo: &Repository, args: &BranchArgs, _config: &Config) ->
             .ok_or_else(|| RgitError::OperationFailed("Branch name required".to_string()))?
     };
 
-    println!(
+    ui_println!(
         "{} Copying branch '{}' to '{}'",
         "📋".blue().bold(),
         source_name.cyan(),
@@ -390,9 +449,7 @@ async fn copy_branch(repo: &Repository, args: &BranchArgs, _config: &Config) ->
     );
 
     // Validate new branch name
-    if !is_valid_branch_name(new_name) {
-        return Err(RgitError::InvalidBranchName(new_name.to_string()).into());
-    }
+    validate_branch_name(new_name)?;
 
     // Check if target name already exists
     if repo.find_branch(new_name, BranchType::Local).is_ok() {
@@ -409,11 +466,169 @@ async fn copy_branch(repo: &Repository, args: &BranchArgs, _config: &Config) ->
     // Create new branch at the same commit
     repo.branch(new_name, &commit, false)?;
 
-    println!("{} Branch copied successfully", "✅".green());
+    ui_println!("{} Branch copied successfully", "✅".green());
 
     Ok(())
 }
 
+/// Set or remove the upstream of a branch
+fn change_upstream(repo: &Repository, args: &BranchArgs) -> Result<()> {
+    let current_branch = get_current_branch(repo)?;
+    let name = args.name.as_deref()
+        .or(current_branch.as_deref())
+        .ok_or_else(|| RgitError::OperationFailed("Branch name required".to_string()))?;
+    let mut branch = repo
+        .find_branch(name, BranchType::Local)
+        .map_err(|_| RgitError::BranchNotFound(name.to_string()))?;
+
+    match &args.set_upstream_to {
+        Some(upstream) => {
+            set_branch_upstream(&mut branch, upstream)?;
+            ui_println!("{} Branch '{}' now tracks '{}'", "🔗".blue(), name.cyan(), upstream.cyan());
+            if let Some((ahead, behind)) = calculate_ahead_behind(repo, &branch)? {
+                ui_println!("  {} {} ahead, {} behind", "📊".blue(), ahead, behind);
+            }
+        }
+        None => {
+            let Some(upstream) = get_upstream_branch(repo, name)? else {
+                return Err(RgitError::NoUpstreamBranch.into());
+            };
+            branch.set_upstream(None)?;
+            ui_println!("{} Branch '{}' no longer tracks '{}'", "✅".green(), name.cyan(), upstream.cyan());
+        }
+    }
+    Ok(())
+}
+
+/// Local branches whose upstream has been deleted on the remote, with the
+/// missing upstream's short name
+pub fn gone_upstreams(repo: &Repository) -> Result<Vec<(String, String)>> {
+    let mut gone = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (Some(name), Some(refname)) = (branch.name()?, branch.get().name()) else { continue };
+        // Fails when no upstream is configured
+        let Ok(upstream) = repo.branch_upstream_name(refname) else { continue };
+        let Some(upstream) = upstream.as_str() else { continue };
+        if repo.find_reference(upstream).is_err() {
+            let short = upstream.strip_prefix("refs/remotes/")
+                .or_else(|| upstream.strip_prefix("refs/heads/"))
+                .unwrap_or(upstream);
+            gone.push((name.to_string(), short.to_string()));
+        }
+    }
+    Ok(gone)
+}
+
+// =============================================================================
+// Ref Sorting
+// =============================================================================
+
+/// What branch and tag listings can be sorted by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    RefName,
+    /// Names compared with embedded numbers as numbers (v1.10 after v1.9)
+    Version,
+    CommitterDate,
+    AuthorDate,
+    /// Tagger date for annotated tags, commit date otherwise
+    CreatorDate,
+}
+
+/// A `--sort=[-]KEY` ordering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefSort {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+/// Timestamps a ref can be sorted by, in seconds since the epoch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefDates {
+    pub committer: i64,
+    pub author: i64,
+    pub creator: i64,
+}
+
+impl RefDates {
+    pub fn of_commit(commit: &git2::Commit) -> Self {
+        let committer = commit.committer().when().seconds();
+        Self { committer, author: commit.author().when().seconds(), creator: committer }
+    }
+}
+
+impl RefSort {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (descending, key) = match spec.strip_prefix('-') {
+            Some(key) => (true, key),
+            None => (false, spec),
+        };
+        let key = match key {
+            "refname" => SortKey::RefName,
+            "version:refname" | "v:refname" => SortKey::Version,
+            "committerdate" => SortKey::CommitterDate,
+            "authordate" => SortKey::AuthorDate,
+            "creatordate" | "taggerdate" => SortKey::CreatorDate,
+            _ => return Err(RgitError::InvalidArgument(format!(
+                "Unknown sort key '{}'; use refname, version:refname, committerdate, authordate or creatordate", key
+            )).into()),
+        };
+        Ok(Self { key, descending })
+    }
+
+    /// Sort `items`, breaking date ties by name
+    pub fn apply<T>(&self, items: &mut [T], keys: impl Fn(&T) -> (&str, RefDates)) {
+        items.sort_by(|a, b| {
+            let ((a_name, a_dates), (b_name, b_dates)) = (keys(a), keys(b));
+            let ordering = match self.key {
+                SortKey::RefName => a_name.cmp(b_name),
+                SortKey::Version => compare_versions(a_name, b_name),
+                SortKey::CommitterDate => a_dates.committer.cmp(&b_dates.committer),
+                SortKey::AuthorDate => a_dates.author.cmp(&b_dates.author),
+                SortKey::CreatorDate => a_dates.creator.cmp(&b_dates.creator),
+            }.then_with(|| a_name.cmp(b_name));
+            if self.descending { ordering.reverse() } else { ordering }
+        });
+    }
+}
+
+/// Compare names treating runs of digits as numbers
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn chunks(s: &str) -> Vec<(bool, &str)> {
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut digits = None;
+        for (i, c) in s.char_indices() {
+            let is_digit = c.is_ascii_digit();
+            if digits.is_some_and(|d| d != is_digit) {
+                chunks.push((digits.unwrap_or(false), &s[start..i]));
+                start = i;
+            }
+            digits = Some(is_digit);
+        }
+        if start < s.len() {
+            chunks.push((digits.unwrap_or(false), &s[start..]));
+        }
+        chunks
+    }
+
+    for (x, y) in chunks(a).into_iter().zip(chunks(b)) {
+        let ordering = match (x, y) {
+            ((true, x), (true, y)) => {
+                let (x, y) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            ((_, x), (_, y)) => x.cmp(y),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
 /// Helper functions
 
 fn get_current_branch(repo: &Repository) -> Result<Option<String>> {
@@ -424,19 +639,7 @@ fn get_current_branch(repo: &Repository) -> Result<Option<String>> {
 }
 
 fn resolve_commit_reference<'a>(repo: &'a Repository, reference: &str) -> Result<git2::Commit<'a>> {
-    let obj = repo.revparse_single(reference)?;
-    Ok(obj.peel_to_commit()?)
-}
-
-fn is_valid_branch_name(name: &str) -> bool {
-    // Basic validation - can be extended
-    !name.is_empty()
-        && !name.starts_with('-')
-        && !name.contains("..")
-        && !name.contains(' ')
-        && !name.contains('\t')
-        && !name.contains('\n')
-        && name != "HEAD"
+    resolve_commit(repo, reference)
 }
 
 fn calculate_ahead_behind(repo: &Repository, branch: &Branch) -> Result<Option<(usize, usize)>> {
@@ -470,20 +673,11 @@ fn get_upstream_branch(repo: &Repository, branch_name: &str) -> Result<Option<St
     }
 }
 
-fn set_branch_upstream(repo: &Repository, branch: &Branch, upstream: &str) -> Result<()> {
-    let branch_name = branch.name()?.unwrap();
-    let mut config = repo.config()?;
-
-    // Parse upstream (format: remote/branch)
-    if let Some((remote, branch_ref)) = upstream.split_once('/') {
-        let remote_key = format!("branch.{}.remote", branch_name);
-        let merge_key = format!("branch.{}.merge", branch_name);
-        let merge_ref = format!("refs/heads/{}", branch_ref);
-
-        config.set_str(&remote_key, remote)?;
-        config.set_str(&merge_key, &merge_ref)?;
-    }
-
+fn set_branch_upstream(branch: &mut Branch, upstream: &str) -> Result<()> {
+    // libgit2 works out the remote and merge ref from the remote-tracking
+    // branch (e.g. origin/main), or tracks a local branch directly
+    branch.set_upstream(Some(upstream))
+        .map_err(|_| RgitError::BranchNotFound(upstream.to_string()))?;
     Ok(())
 }
 
@@ -509,30 +703,40 @@ fn is_branch_merged(repo: &Repository, branch: &Branch) -> Result<bool> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
+    use crate::test_support::create_test_repo;
 
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-
-        (temp_dir, repo)
+    #[test]
+    fn test_validate_branch_name() {
+        assert!(validate_branch_name("feature/new-feature").is_ok());
+        assert!(validate_branch_name("main").is_ok());
+        assert!(validate_branch_name("develop").is_ok());
+
+        assert!(validate_branch_name("").is_err());
+        assert!(validate_branch_name("-invalid").is_err());
+        assert!(validate_branch_name("branch..with..dots").is_err());
+        assert!(validate_branch_name("HEAD").is_err());
     }
 
     #[test]
-    fn test_is_valid_branch_name() {
-        assert!(is_valid_branch_name("feature/new-feature"));
-        assert!(is_valid_branch_name("main"));
-        assert!(is_valid_branch_name("develop"));
-
-        assert!(!is_valid_branch_name(""));
-        assert!(!is_valid_branch_name("-invalid"));
-        assert!(!is_valid_branch_name("branch..with..dots"));
-        assert!(!is_valid_branch_name("HEAD"));
+    fn test_gone_upstreams() {
+        use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Initial commit");
+        let _remote = repo.add_remote("origin");
+        repo.branch("feature");
+        let mut feature = repo.repo().find_branch("feature", BranchType::Local).unwrap();
+        assert!(gone_upstreams(repo.repo()).unwrap().is_empty());
+
+        // Tracking a branch that was never fetched counts as gone
+        let mut config = repo.repo().config().unwrap();
+        config.set_str("branch.feature.remote", "origin").unwrap();
+        config.set_str("branch.feature.merge", "refs/heads/feature").unwrap();
+        assert_eq!(gone_upstreams(repo.repo()).unwrap(), [("feature".to_string(), "origin/feature".to_string())]);
+
+        set_branch_upstream(&mut feature, &format!("origin/{}", DEFAULT_BRANCH)).unwrap();
+        assert!(gone_upstreams(repo.repo()).unwrap().is_empty());
+        assert!(set_branch_upstream(&mut feature, "origin/missing").is_err());
     }
 
     #[tokio::test]
@@ -564,11 +768,16 @@ mod tests {
             force_delete: None,
             list: false,
             remotes: false,
+            no_fetch: true,
             rename: None,
             move_to: None,
             copy: None,
             merged: false,
             no_merged: false,
+            column: false,
+            sort: None,
+            set_upstream_to: None,
+            unset_upstream: false,
         };
 
         let result = create_branch(&repo, "test-branch", &args, &config).await;
@@ -577,4 +786,19 @@ mod tests {
         // Verify branch was created
         assert!(repo.find_branch("test-branch", BranchType::Local).is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ref_sort() {
+        let dates = |committer| RefDates { committer, author: 0, creator: committer };
+        let mut refs = vec![("v1.10", dates(3)), ("v1.9", dates(1)), ("v1.2", dates(2))];
+
+        RefSort::parse("version:refname").unwrap().apply(&mut refs, |r| (r.0, r.1));
+        assert_eq!(refs.iter().map(|r| r.0).collect::<Vec<_>>(), ["v1.2", "v1.9", "v1.10"]);
+        RefSort::parse("refname").unwrap().apply(&mut refs, |r| (r.0, r.1));
+        assert_eq!(refs.iter().map(|r| r.0).collect::<Vec<_>>(), ["v1.10", "v1.2", "v1.9"]);
+        RefSort::parse("-committerdate").unwrap().apply(&mut refs, |r| (r.0, r.1));
+        assert_eq!(refs.iter().map(|r| r.0).collect::<Vec<_>>(), ["v1.10", "v1.2", "v1.9"]);
+
+        assert!(RefSort::parse("size").is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::Result;
+use git2::ObjectType;
+use serde::Serialize;
+use std::io::Write;
+
+use crate::cli::CatArgs;
+use crate::config::Config;
+use crate::core::{Revision, RgitCore};
+use crate::error::RgitError;
+
+/// A blob as `cat --json` prints it
+#[derive(Debug, Serialize)]
+struct BlobJson {
+    oid: String,
+    size: usize,
+    binary: bool,
+    /// The text, or `null` for binary blobs
+    content: Option<String>,
+}
+
+/// Execute the cat command
+pub async fn execute(args: &CatArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let Revision::Single(object) = rgit.resolve_revision(&args.object)? else {
+        return Err(RgitError::InvalidArgument(format!("{} is a range, not a file", args.object)).into());
+    };
+    let blob = match object.kind() {
+        Some(ObjectType::Blob) => object.peel_to_blob()?,
+        Some(ObjectType::Tree) => return Err(RgitError::InvalidArgument(
+            format!("{} is a directory; list it with 'rgit ls'", args.object)
+        ).into()),
+        _ => return Err(RgitError::InvalidArgument(
+            format!("{} is not a file; name one as <rev>:<path>, e.g. HEAD:README.md", args.object)
+        ).into()),
+    };
+
+    if args.json {
+        let binary = blob.is_binary();
+        let json = BlobJson {
+            oid: blob.id().to_string(),
+            size: blob.size(),
+            binary,
+            content: (!binary).then(|| String::from_utf8_lossy(blob.content()).into_owned()),
+        };
+        println!("{}", serde_json::to_string_pretty(&json)?);
+        return Ok(());
+    }
+
+    // The exact bytes, so the output can be redirected into a file
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(blob.content())?;
+    stdout.flush()?;
+    Ok(())
+}
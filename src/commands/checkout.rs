@@ -0,0 +1,139 @@
+use anyhow::Result;
+use colored::*;
+use git2::{BranchType, Repository};
+use std::path::Path;
+
+use crate::cli::CheckoutArgs;
+use crate::commands::restore::{restore_paths, RestoreSource, RestoreTargets};
+use crate::commands::rm::index_paths_matching;
+use crate::commands::switch::{
+    confirm_orphan, guess_remote_branch, show_switched, switch, DirtyTreeAction, SwitchOptions, SwitchTarget,
+};
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::validation::validate_pathspec;
+
+/// Execute the checkout command.
+///
+/// Kept for compatibility: branch changes go through `rgit switch` and file
+/// checkouts through `rgit restore`.
+pub async fn execute(args: &CheckoutArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let options = SwitchOptions {
+        discard_changes: args.force,
+        guess: !args.no_track,
+        track: if args.track { Some(true) } else if args.no_track { Some(false) } else { None },
+        dirty: DirtyTreeAction::from_config(args.merge, config),
+    };
+    let cwd = std::env::current_dir()?;
+
+    match classify(repo, &rgit.repo_path, &cwd, args)? {
+        CheckoutKind::Switch(target) => {
+            if let SwitchTarget::Orphan(name) = &target {
+                confirm_orphan(repo, name, &options, config)?;
+            }
+            let outcome = switch(repo, &target, &options)?;
+            show_switched(repo, &outcome);
+        }
+        CheckoutKind::Files(paths) => {
+            let targets = RestoreTargets { index: false, worktree: true };
+            restore_paths(repo, &rgit.repo_path, &paths, &RestoreSource::Index, targets)?;
+            ui_println!("{} Restored {} file{} from the index",
+                    "✅".green().bold(),
+                    paths.len(),
+                    if paths.len() == 1 { "" } else { "s" });
+            ui_println!("{} {} does the same and can also restore from other revisions",
+                    "💡".blue(), format!("rgit restore {}", args.target).cyan());
+        }
+    }
+    Ok(())
+}
+
+/// What a `checkout` argument refers to
+#[derive(Debug, Clone, PartialEq)]
+enum CheckoutKind {
+    Switch(SwitchTarget),
+    /// Tracked files to restore from the index
+    Files(Vec<String>),
+}
+
+/// Decide between branch, commit and path like git: refs win over files
+fn classify(repo: &Repository, workdir: &Path, cwd: &Path, args: &CheckoutArgs) -> Result<CheckoutKind> {
+    let target = &args.target;
+    if args.orphan {
+        return Ok(CheckoutKind::Switch(SwitchTarget::Orphan(target.clone())));
+    }
+    if args.new_branch || args.force_new_branch {
+        return Ok(CheckoutKind::Switch(SwitchTarget::Create {
+            name: target.clone(),
+            start: None,
+            force: args.force_new_branch,
+        }));
+    }
+
+    if target == "-" || repo.find_branch(target, BranchType::Local).is_ok() {
+        return Ok(CheckoutKind::Switch(SwitchTarget::Branch(target.clone())));
+    }
+    if !args.no_track && guess_remote_branch(repo, target)?.is_some() {
+        return Ok(CheckoutKind::Switch(SwitchTarget::Branch(target.clone())));
+    }
+    if resolve_commit(repo, target).is_ok() {
+        return Ok(CheckoutKind::Switch(SwitchTarget::Detach(target.clone())));
+    }
+
+    if let Ok(relative) = validate_pathspec(workdir, cwd, target) {
+        let paths = index_paths_matching(&repo.index()?, &relative)?;
+        if !paths.is_empty() {
+            return Ok(CheckoutKind::Files(paths));
+        }
+    }
+
+    Err(RgitError::CheckoutFailed(
+        format!("'{}' did not match any branch, commit or tracked file", target)
+    ).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn args(target: &str) -> CheckoutArgs {
+        CheckoutArgs {
+            target: target.to_string(),
+            new_branch: false,
+            force_new_branch: false,
+            orphan: false,
+            force: false,
+            merge: false,
+            track: false,
+            no_track: false,
+        }
+    }
+
+    fn kind(repo: &TestRepo, args: &CheckoutArgs) -> Result<CheckoutKind> {
+        classify(repo.repo(), repo.path(), repo.path(), args)
+    }
+
+    #[test]
+    fn test_classify_checkout_target() {
+        let repo = TestRepo::new();
+        let oid = repo.commit_file("docs/guide.md", "guide\n", "Initial");
+        repo.branch("feature").tag("v1.0");
+
+        assert_eq!(kind(&repo, &args("feature")).unwrap(),
+                   CheckoutKind::Switch(SwitchTarget::Branch("feature".to_string())));
+        assert_eq!(kind(&repo, &args("v1.0")).unwrap(),
+                   CheckoutKind::Switch(SwitchTarget::Detach("v1.0".to_string())));
+        assert_eq!(kind(&repo, &args(&oid.to_string())).unwrap(),
+                   CheckoutKind::Switch(SwitchTarget::Detach(oid.to_string())));
+        assert_eq!(kind(&repo, &args("docs")).unwrap(),
+                   CheckoutKind::Files(vec!["docs/guide.md".to_string()]));
+        assert_eq!(kind(&repo, &CheckoutArgs { new_branch: true, ..args("topic") }).unwrap(),
+                   CheckoutKind::Switch(SwitchTarget::Create { name: "topic".to_string(), start: None, force: false }));
+        assert_eq!(kind(&repo, &CheckoutArgs { orphan: true, ..args("gh-pages") }).unwrap(),
+                   CheckoutKind::Switch(SwitchTarget::Orphan("gh-pages".to_string())));
+        assert!(kind(&repo, &args("nothing")).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{Commit, Index, Oid, Repository, RepositoryState, Tree};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::CherryPickArgs;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::operation::OperationKind;
+use crate::sequencer::{self, Action, Sequence};
+use crate::timings::{self, Phase};
+use crate::utils::{check_timestamp, normalize_signature};
+use crate::validation::validate_message;
+
+/// Execute the cherry-pick command
+pub async fn execute(args: &CherryPickArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.abort {
+        return sequencer::abort::<PickOptions>(rgit);
+    }
+    if args.continue_pick {
+        return sequencer::resume::<PickOptions>(rgit, config);
+    }
+    if args.skip {
+        return sequencer::skip::<PickOptions>(rgit, config);
+    }
+
+    if args.commits.is_empty() {
+        return Err(RgitError::InvalidArgument("No commits specified to cherry-pick".to_string()).into());
+    }
+
+    let commits = sequencer::resolve_commits::<PickOptions>(&rgit.repo, &args.commits)?;
+    let options = PickOptions {
+        no_commit: args.no_commit,
+        edit: args.edit,
+    };
+
+    sequencer::start(rgit, commits, options, config)
+}
+
+/// Options that apply to every commit in a sequence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PickOptions {
+    /// Apply changes to the index without committing
+    pub no_commit: bool,
+    /// Edit each commit message before committing
+    pub edit: bool,
+}
+
+/// Persistent state of a multi-commit cherry-pick
+pub type CherryPickSequence = Sequence<PickOptions>;
+
+impl Action for PickOptions {
+    const KIND: OperationKind = OperationKind::CherryPick;
+    const DOING: &'static str = "Cherry-picking";
+    const DONE: &'static str = "Cherry-picked";
+    const OLDEST_FIRST: bool = true;
+
+    fn icon() -> ColoredString {
+        "🍒".red()
+    }
+
+    fn no_commit(&self) -> bool {
+        self.no_commit
+    }
+
+    fn apply(&self, repo: &Repository, commit: &Commit, config: &Config) -> Result<Vec<String>> {
+        if let Some(anomaly) = check_timestamp(commit.author().when().seconds(), chrono::Utc::now().timestamp()) {
+            if config.git.normalize_dates {
+                ui_println!("     {} Author date is {}; normalizing to now", "🕒".yellow(), anomaly);
+            } else {
+                ui_println!("     {} Author date is {} (set {} to normalize)",
+                        "⚠️".yellow(), anomaly, "git.normalize_dates".cyan());
+            }
+        }
+
+        if self.no_commit {
+            return apply_to_index(repo, commit);
+        }
+        let mut opts = git2::CherrypickOptions::new();
+        if commit.parent_count() > 1 {
+            opts.mainline(1);
+        }
+        repo.cherrypick(commit, Some(&mut opts))?;
+        conflicted_paths(&repo.index()?)
+    }
+
+    fn commit(&self, rgit: &RgitCore, commit: &Commit, config: &Config) -> Result<Oid> {
+        commit_pick(rgit, commit, self, config)
+    }
+
+    /// A pick that failed before libgit2 recorded it is applied again
+    fn stopped(&self, repo: &Repository) -> bool {
+        self.no_commit || repo.state() == RepositoryState::CherryPick
+    }
+}
+
+// =============================================================================
+// Picking
+// =============================================================================
+
+/// Commit the staged result of a pick, preserving the original author
+fn commit_pick(rgit: &RgitCore, commit: &Commit, options: &PickOptions, config: &Config) -> Result<Oid> {
+    let repo = &rgit.repo;
+    let mut index = repo.index()?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let mut message = commit.message().unwrap_or("").to_string();
+    if options.edit && config.is_interactive() {
+        message = InteractivePrompt::new()
+            .with_message(message)
+            .editor()?;
+    }
+    validate_message(&message)?;
+
+    let committer = rgit.get_signature()?;
+    let author = if config.git.normalize_dates {
+        normalize_signature(&commit.author(), chrono::Utc::now().timestamp())?
+    } else {
+        commit.author().to_owned()
+    };
+    let oid = repo.commit(Some("HEAD"), &author, &committer, &message, &tree, &[&head])?;
+    Ok(oid)
+}
+
+/// Merge a commit's changes into the current index (for `--no-commit`).
+///
+/// The current index is used as "ours" so several commits can be combined.
+/// Returns the conflicted paths, which are written to the index and working tree.
+fn apply_to_index(repo: &Repository, commit: &Commit) -> Result<Vec<String>> {
+    let base = match commit.parents().next() {
+        Some(parent) => parent.tree()?,
+        None => empty_tree(repo)?,
+    };
+    merge_into_index(repo, &base, &commit.tree()?)
+}
+
+/// Apply the change from `base` to `theirs` on top of the current index and
+/// working tree, returning the conflicted paths
+pub fn merge_into_index(repo: &Repository, base: &Tree, theirs: &Tree) -> Result<Vec<String>> {
+    let mut index = repo.index()?;
+    let ours = repo.find_tree(index.write_tree()?)?;
+
+    let merged = repo.merge_trees(base, &ours, theirs, None)?;
+
+    index.clear()?;
+    for entry in merged.iter() {
+        index.add(&entry)?;
+    }
+    timings::time(Phase::IndexWrite, || index.write())?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force().allow_conflicts(true).conflict_style_merge(true);
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+
+    conflicted_paths(&index)
+}
+
+/// The tree of an empty repository, used as the base of root commits
+pub fn empty_tree(repo: &Repository) -> Result<Tree<'_>> {
+    Ok(repo.find_tree(Index::new()?.write_tree_to(repo)?)?)
+}
+
+/// Paths with unresolved conflicts in an index
+pub fn conflicted_paths(index: &Index) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// Refuse to start when tracked files have uncommitted changes
+pub fn ensure_no_tracked_changes(rgit: &RgitCore) -> Result<()> {
+    let status = rgit.status()?;
+    if !status.staged.is_empty() || !status.unstaged.is_empty() {
+        return Err(RgitError::BranchHasUncommittedChanges.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{create_test_repo, TestRepo};
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_sequence_state_roundtrip() {
+        let (_temp_dir, repo) = create_test_repo();
+
+        assert!(CherryPickSequence::load(&repo).unwrap().is_none());
+
+        let sequence = CherryPickSequence {
+            original_head: "abc".to_string(),
+            todo: vec!["one".to_string(), "two".to_string()],
+            done: vec!["zero".to_string()],
+            skipped: Vec::new(),
+            options: PickOptions::default(),
+        };
+        sequence.save(&repo).unwrap();
+        assert!(CherryPickSequence::path(&repo).exists());
+
+        let loaded = CherryPickSequence::load(&repo).unwrap().unwrap();
+        assert_eq!(loaded.todo, sequence.todo);
+        assert_eq!(loaded.total(), 3);
+
+        CherryPickSequence::clear(&repo).unwrap();
+        assert!(!CherryPickSequence::path(&repo).exists());
+    }
+
+    #[test]
+    fn test_apply_to_index_combines_commits() {
+        let test_repo = TestRepo::new();
+        let base = test_repo.commit_file("base.txt", "base\n", "base");
+        let first = test_repo.commit_file("a.txt", "a\n", "add a");
+        let second = test_repo.commit_file("b.txt", "b\n", "add b");
+        let repo = test_repo.repo();
+
+        // Rewind to base and apply both commits without committing
+        let base_commit = repo.find_commit(base).unwrap();
+        repo.reset(base_commit.as_object(), git2::ResetType::Hard, None).unwrap();
+
+        for oid in [first, second] {
+            let commit = repo.find_commit(oid).unwrap();
+            assert!(apply_to_index(repo, &commit).unwrap().is_empty());
+        }
+
+        assert!(test_repo.path().join("a.txt").exists());
+        assert!(test_repo.path().join("b.txt").exists());
+        let index = repo.index().unwrap();
+        assert!(index.get_path(Path::new("a.txt"), 0).is_some());
+        assert!(index.get_path(Path::new("b.txt"), 0).is_some());
+    }
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.ui.interactive = false;
+        config
+    }
+
+    #[test]
+    fn test_range_is_picked_oldest_first() {
+        let repo = TestRepo::new();
+        repo.commit_file("base.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        let a = repo.commit_file("a.txt", "a\n", "Add a");
+        let b = repo.commit_file("b.txt", "b\n", "Add b");
+        repo.checkout("main");
+
+        let commits = sequencer::resolve_commits::<PickOptions>(repo.repo(), &["main..feature".to_string()]).unwrap();
+        assert_eq!(commits, vec![a, b]);
+        assert!(sequencer::resolve_commits::<PickOptions>(repo.repo(), &["feature..main".to_string()]).is_err());
+
+        sequencer::start(&repo.core(), commits, PickOptions::default(), &config()).unwrap();
+        let head = repo.repo().find_commit(repo.head()).unwrap();
+        assert_eq!(head.summary(), Some("Add b"));
+        assert_eq!(head.parent(0).unwrap().summary(), Some("Add a"));
+    }
+
+    #[test]
+    fn test_skip_conflicting_commit() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        let clash = repo.commit_file("file.txt", "feature\n", "Clash");
+        let extra = repo.commit_file("extra.txt", "extra\n", "Extra");
+        repo.checkout("main");
+        repo.commit_file("file.txt", "main\n", "Main change");
+
+        let error = sequencer::start(&repo.core(), vec![clash, extra], PickOptions::default(), &config()).unwrap_err();
+        assert!(matches!(error.downcast_ref::<RgitError>(), Some(RgitError::MergeConflict(_))));
+
+        sequencer::skip::<PickOptions>(&repo.core(), &config()).unwrap();
+        assert!(CherryPickSequence::load(repo.repo()).unwrap().is_none());
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+        assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), "main\n");
+        assert_eq!(repo.repo().find_commit(repo.head()).unwrap().summary(), Some("Extra"));
+    }
+
+    #[test]
+    fn test_resolve_commits() {
+        let repo = TestRepo::new();
+        let oid = repo.commit_file("file.txt", "content\n", "initial");
+
+        assert_eq!(sequencer::resolve_commits::<PickOptions>(repo.repo(), &["HEAD".to_string()]).unwrap(), vec![oid]);
+        assert!(sequencer::resolve_commits::<PickOptions>(repo.repo(), &["does-not-exist".to_string()]).is_err());
+    }
+}
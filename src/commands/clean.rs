@@ -0,0 +1,537 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use git2::{Repository, Status, StatusOptions};
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::cli::{CleanArgs, TrashTarget};
+use crate::commands::backup;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::{format_size, FileItem, FileSelector, InteractivePrompt};
+use crate::timings::{self, Phase};
+use crate::validation::validate_pathspec;
+
+/// Directory inside `.git` holding cleaned files, one batch per clean
+const TRASH_DIR: &str = "rgit/trash";
+
+/// Paths moved into a trash batch, one per line
+const MANIFEST_FILE: &str = "manifest";
+
+/// Execute the clean command
+pub async fn execute(args: &CleanArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.restore {
+        return restore(rgit);
+    }
+
+    let cwd = std::env::current_dir()?;
+    let paths = args.paths.iter()
+        .map(|spec| validate_pathspec(&rgit.repo_path, &cwd, spec))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let ignored = if args.only_ignored {
+        Ignored::Only
+    } else if args.ignored {
+        Ignored::Include
+    } else {
+        Ignored::Skip
+    };
+    let filter = CleanFilter { directories: args.directories, ignored, paths };
+
+    let (candidates, nested) = find_candidates(&rgit.repo, &rgit.repo_path, &filter)?;
+    for path in &nested {
+        ui_println!("{} Skipping {} (a nested repository)", "⚠️".yellow(), path.yellow());
+    }
+    if candidates.is_empty() {
+        ui_println!("{} Nothing to clean", "✨".green());
+        return Ok(());
+    }
+
+    if args.dry_run {
+        ui_println!("{} Would remove:", "👁️".blue().bold());
+        show_candidates(&candidates);
+        return Ok(());
+    }
+
+    let target = args.trash.unwrap_or_else(|| trash_target(config));
+    let chosen = if args.interactive || !args.force {
+        if !config.is_interactive() {
+            ui_println!("{} These files would be removed:", "🧹".blue());
+            show_candidates(&candidates);
+            ui_println!("\n{} Use {} to remove them or {} to preview",
+                    "💡".blue(), "--force".cyan(), "--dry-run".cyan());
+            return Err(RgitError::InvalidArgument(
+                "refusing to clean without --force outside an interactive terminal".to_string()
+            ).into());
+        }
+        let chosen = choose_candidates(candidates)?;
+        if chosen.is_empty() {
+            ui_println!("{} Nothing selected; nothing removed", "💡".blue());
+            return Ok(());
+        }
+        chosen
+    } else {
+        candidates
+    };
+
+    if target == TrashTarget::Off && config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Permanently delete {} item{}?", chosen.len(), plural(chosen.len())))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    backup::snapshot_before(rgit, config, "clean")?;
+    let removed = remove_candidates(&rgit.repo, &rgit.repo_path, &chosen, target, filter.directories)?;
+    for candidate in &chosen {
+        ui_println!("  {} {}", "🗑️".red(), candidate.display_path());
+    }
+    let size: u64 = chosen.iter().map(|c| c.size).sum();
+    ui_println!("{} Removed {} item{} ({})", "✅".green().bold(), chosen.len(), plural(chosen.len()), format_size(size));
+    match removed {
+        Removed::RepoTrash(batch) => {
+            println!("   {} {}", "kept in".dimmed(), batch.display().to_string().dimmed());
+            ui_println!("{} Bring them back with {}", "💡".blue(), "rgit clean --restore".cyan());
+        }
+        Removed::SystemTrash => println!("   {}", "moved to the system trash".dimmed()),
+        Removed::Deleted => {}
+    }
+    Ok(())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+// =============================================================================
+// Candidates
+// =============================================================================
+
+/// How ignored files are treated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ignored {
+    /// Leave ignored files alone (the default)
+    Skip,
+    /// Clean ignored files as well as untracked ones (`-x`)
+    Include,
+    /// Clean only ignored files (`-X`)
+    Only,
+}
+
+/// What a clean should consider removing
+#[derive(Debug, Clone)]
+pub struct CleanFilter {
+    /// Remove whole untracked directories (`-d`)
+    pub directories: bool,
+    pub ignored: Ignored,
+    /// Pathspecs relative to the working tree; empty means everything
+    pub paths: Vec<String>,
+}
+
+/// An untracked or ignored file or directory that can be removed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// Path relative to the working tree, without a trailing slash
+    pub path: String,
+    pub directory: bool,
+    pub ignored: bool,
+    /// Size on disk, summed over directory contents
+    pub size: u64,
+}
+
+impl Candidate {
+    fn display_path(&self) -> String {
+        if self.directory { format!("{}/", self.path) } else { self.path.clone() }
+    }
+}
+
+/// Files and directories the filter allows removing, plus nested
+/// repositories that were skipped.
+///
+/// Like `git clean`, untracked directories are only removed with `-d`; when
+/// paths are given, the files inside matching directories are listed instead.
+pub fn find_candidates(repo: &Repository, workdir: &Path, filter: &CleanFilter) -> Result<(Vec<Candidate>, Vec<String>)> {
+    let recurse = !filter.paths.is_empty() && !filter.directories;
+    let mut options = StatusOptions::new();
+    options.include_untracked(filter.ignored != Ignored::Only)
+        .recurse_untracked_dirs(recurse)
+        .include_ignored(filter.ignored != Ignored::Skip)
+        .recurse_ignored_dirs(recurse);
+    for path in &filter.paths {
+        options.pathspec(path);
+    }
+
+    let mut candidates = Vec::new();
+    let mut nested = Vec::new();
+    for entry in timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?.iter() {
+        let status = entry.status();
+        let ignored = status.contains(Status::IGNORED);
+        if !ignored && !status.contains(Status::WT_NEW) {
+            continue;
+        }
+        let Some(raw) = entry.path() else { continue };
+        let directory = raw.ends_with('/');
+        let path = raw.trim_end_matches('/').to_string();
+        let full = workdir.join(&path);
+
+        if directory {
+            if full.join(".git").exists() {
+                nested.push(path);
+                continue;
+            }
+            if !filter.directories {
+                continue;
+            }
+        }
+        candidates.push(Candidate { size: disk_size(&full), path, directory, ignored });
+    }
+    Ok((candidates, nested))
+}
+
+fn disk_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+fn show_candidates(candidates: &[Candidate]) {
+    for candidate in candidates {
+        let kind = if candidate.ignored { " (ignored)".dimmed() } else { "".normal() };
+        println!("  {} {}{} {}", "-".red(), candidate.display_path(), kind, format_size(candidate.size).dimmed());
+    }
+    let size: u64 = candidates.iter().map(|c| c.size).sum();
+    println!("  {} item{}, {}", candidates.len(), plural(candidates.len()), format_size(size));
+}
+
+fn choose_candidates(candidates: Vec<Candidate>) -> Result<Vec<Candidate>> {
+    let files = candidates.iter()
+        .map(|candidate| FileItem {
+            path: PathBuf::from(candidate.display_path()),
+            status: if candidate.ignored { "ignored" } else { "untracked" }.to_string(),
+            size: Some(candidate.size),
+            selected: false,
+        })
+        .collect();
+    let chosen = FileSelector::new()
+        .with_prompt("Select files to remove")
+        .with_files(files)
+        .with_details()
+        .select()?;
+
+    Ok(candidates.into_iter()
+        .filter(|candidate| chosen.contains(&PathBuf::from(candidate.display_path())))
+        .collect())
+}
+
+// =============================================================================
+// Removal
+// =============================================================================
+
+/// Where a clean put the files it removed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Removed {
+    /// Batch directory under `.git/rgit/trash`
+    RepoTrash(PathBuf),
+    SystemTrash,
+    Deleted,
+}
+
+fn trash_target(config: &Config) -> TrashTarget {
+    match config.clean.trash.as_str() {
+        "system" => TrashTarget::System,
+        "off" => TrashTarget::Off,
+        _ => TrashTarget::Repo,
+    }
+}
+
+/// Remove the candidates from the working tree. With `prune_dirs`, parent
+/// directories left empty are removed as well.
+pub fn remove_candidates(repo: &Repository, workdir: &Path, candidates: &[Candidate], target: TrashTarget, prune_dirs: bool) -> Result<Removed> {
+    let removed = match target {
+        TrashTarget::Repo => {
+            let batch = new_batch(repo)?;
+            let mut manifest = String::new();
+            for candidate in candidates {
+                move_path(&workdir.join(&candidate.path), &batch.join("files").join(&candidate.path))?;
+                manifest.push_str(&candidate.path);
+                manifest.push('\n');
+            }
+            fs::write(batch.join(MANIFEST_FILE), manifest)?;
+            Removed::RepoTrash(batch)
+        }
+        TrashTarget::System => {
+            for candidate in candidates {
+                move_to_system_trash(&workdir.join(&candidate.path))?;
+            }
+            Removed::SystemTrash
+        }
+        TrashTarget::Off => {
+            for candidate in candidates {
+                let full = workdir.join(&candidate.path);
+                if candidate.directory {
+                    fs::remove_dir_all(&full)
+                } else {
+                    fs::remove_file(&full)
+                }.with_context(|| format!("Failed to remove {}", full.display()))?;
+            }
+            Removed::Deleted
+        }
+    };
+
+    if prune_dirs {
+        for candidate in candidates {
+            prune_empty_parents(workdir, &candidate.path);
+        }
+    }
+    Ok(removed)
+}
+
+/// Remove directories emptied by a clean, stopping at the working tree root
+fn prune_empty_parents(workdir: &Path, path: &str) {
+    let mut parent = Path::new(path).parent();
+    while let Some(dir) = parent.filter(|dir| !dir.as_os_str().is_empty()) {
+        if fs::remove_dir(workdir.join(dir)).is_err() {
+            break;
+        }
+        parent = dir.parent();
+    }
+}
+
+/// Move a file or directory, copying when a rename crosses filesystems
+fn move_path(from: &Path, to: &Path) -> Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    if from.is_dir() {
+        for entry in WalkDir::new(from) {
+            let entry = entry?;
+            let dest = to.join(entry.path().strip_prefix(from)?);
+            if entry.file_type().is_dir() {
+                fs::create_dir_all(&dest)?;
+            } else {
+                fs::copy(entry.path(), &dest)?;
+            }
+        }
+        fs::remove_dir_all(from)?;
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Trash
+// =============================================================================
+
+/// Root of the repository trash
+pub fn trash_dir(repo: &Repository) -> PathBuf {
+    repo.path().join(TRASH_DIR)
+}
+
+/// A fresh batch directory named after the current time
+fn new_batch(repo: &Repository) -> Result<PathBuf> {
+    let root = trash_dir(repo);
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut batch = root.join(&stamp);
+    let mut n = 2;
+    while batch.exists() {
+        batch = root.join(format!("{}-{}", stamp, n));
+        n += 1;
+    }
+    fs::create_dir_all(&batch)
+        .with_context(|| format!("Failed to create trash directory: {}", batch.display()))?;
+    Ok(batch)
+}
+
+/// Trash batches, oldest first
+pub fn trash_batches(repo: &Repository) -> Result<Vec<PathBuf>> {
+    let root = trash_dir(repo);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+    let mut batches: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&root)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join(MANIFEST_FILE).exists())
+        .map(|entry| (entry.metadata().and_then(|m| m.modified()).unwrap_or(std::time::UNIX_EPOCH), entry.path()))
+        .collect();
+    batches.sort();
+    Ok(batches.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Move the files of a trash batch back into the working tree. Paths that
+/// exist again are left in the batch; returns the restored and kept paths.
+pub fn restore_batch(workdir: &Path, batch: &Path) -> Result<(Vec<String>, Vec<String>)> {
+    let manifest = fs::read_to_string(batch.join(MANIFEST_FILE))?;
+    let mut restored = Vec::new();
+    let mut kept = Vec::new();
+    for path in manifest.lines().filter(|line| !line.is_empty()) {
+        let dest = workdir.join(path);
+        if dest.exists() {
+            kept.push(path.to_string());
+            continue;
+        }
+        move_path(&batch.join("files").join(path), &dest)?;
+        restored.push(path.to_string());
+    }
+
+    if kept.is_empty() {
+        fs::remove_dir_all(batch)?;
+    } else {
+        fs::write(batch.join(MANIFEST_FILE), kept.iter().map(|p| format!("{}\n", p)).collect::<String>())?;
+    }
+    Ok((restored, kept))
+}
+
+fn restore(rgit: &RgitCore) -> Result<()> {
+    let Some(batch) = trash_batches(&rgit.repo)?.pop() else {
+        ui_println!("{} The trash is empty", "💡".blue());
+        return Ok(());
+    };
+
+    let (restored, kept) = restore_batch(&rgit.repo_path, &batch)?;
+    for path in &restored {
+        ui_println!("  {} {}", "↩️".green(), path);
+    }
+    ui_println!("{} Restored {} item{} from {}",
+            "✅".green().bold(),
+            restored.len(),
+            plural(restored.len()),
+            batch.file_name().unwrap_or_default().to_string_lossy());
+    if !kept.is_empty() {
+        ui_println!("{} {} path{} already exist again and stayed in {}:",
+                "⚠️".yellow(), kept.len(), plural(kept.len()), batch.display());
+        for path in &kept {
+            ui_println!("  {} {}", "•".yellow(), path);
+        }
+    }
+    Ok(())
+}
+
+/// Move a path to the desktop trash: `~/.Trash` on macOS and the
+/// freedesktop.org trash elsewhere on Unix.
+fn move_to_system_trash(path: &Path) -> Result<()> {
+    let name = path.file_name()
+        .ok_or_else(|| RgitError::InvalidPath(path.to_path_buf()))?
+        .to_string_lossy()
+        .to_string();
+
+    if cfg!(windows) {
+        return Err(RgitError::OperationNotSupported(
+            "the system trash on Windows; use --trash repo instead".to_string()
+        ).into());
+    }
+    if cfg!(target_os = "macos") {
+        let trash = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Cannot find the home directory"))?.join(".Trash");
+        return move_path(path, &unique_name(&trash, &name, ""));
+    }
+
+    let trash = dirs::data_dir().ok_or_else(|| anyhow::anyhow!("Cannot find the data directory"))?.join("Trash");
+    let dest = unique_name(&trash.join("files"), &name, ".trashinfo");
+    let info_name = format!("{}.trashinfo", dest.file_name().unwrap_or_default().to_string_lossy());
+    let absolute = fs::canonicalize(path)?;
+    fs::create_dir_all(trash.join("info"))?;
+    fs::write(trash.join("info").join(info_name), format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        encode_trash_path(&absolute.to_string_lossy()),
+        Local::now().format("%Y-%m-%dT%H:%M:%S"),
+    ))?;
+    move_path(path, &dest)
+}
+
+/// A name in `dir` not used yet, also checking `<name><info_suffix>` in the
+/// sibling `info` directory of a freedesktop trash
+fn unique_name(dir: &Path, name: &str, info_suffix: &str) -> PathBuf {
+    let taken = |candidate: &str| {
+        dir.join(candidate).exists()
+            || (!info_suffix.is_empty()
+                && dir.with_file_name("info").join(format!("{}{}", candidate, info_suffix)).exists())
+    };
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while taken(&candidate) {
+        candidate = format!("{}.{}", name, n);
+        n += 1;
+    }
+    dir.join(candidate)
+}
+
+/// Percent-encode a path for a `.trashinfo` file
+fn encode_trash_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn filter(directories: bool, ignored: Ignored, paths: &[&str]) -> CleanFilter {
+        CleanFilter { directories, ignored, paths: paths.iter().map(|p| p.to_string()).collect() }
+    }
+
+    fn candidate_paths(repo: &TestRepo, filter: &CleanFilter) -> Vec<String> {
+        let (candidates, _) = find_candidates(repo.repo(), repo.path(), filter).unwrap();
+        candidates.into_iter().map(|c| c.display_path()).collect()
+    }
+
+    #[test]
+    fn test_candidates_follow_flags() {
+        let repo = TestRepo::new();
+        repo.commit_file(".gitignore", "*.log\n", "Ignore logs");
+        repo.write("notes.txt", "n\n")
+            .write("debug.log", "d\n")
+            .write("build/out.o", "o\n")
+            .write("build/keep.txt", "k\n");
+
+        assert_eq!(candidate_paths(&repo, &filter(false, Ignored::Skip, &[])), vec!["notes.txt"]);
+        assert_eq!(candidate_paths(&repo, &filter(true, Ignored::Skip, &[])), vec!["build/", "notes.txt"]);
+        assert_eq!(candidate_paths(&repo, &filter(false, Ignored::Include, &[])), vec!["debug.log", "notes.txt"]);
+        assert_eq!(candidate_paths(&repo, &filter(false, Ignored::Only, &[])), vec!["debug.log"]);
+        assert_eq!(candidate_paths(&repo, &filter(false, Ignored::Skip, &["build"])), vec!["build/keep.txt", "build/out.o"]);
+    }
+
+    #[test]
+    fn test_repo_trash_round_trip() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "hello\n", "Initial commit");
+        repo.write("notes.txt", "notes\n").write("tmp/a.txt", "a\n");
+
+        let (candidates, _) = find_candidates(repo.repo(), repo.path(), &filter(true, Ignored::Skip, &[])).unwrap();
+        let removed = remove_candidates(repo.repo(), repo.path(), &candidates, TrashTarget::Repo, true).unwrap();
+        assert!(!repo.path().join("notes.txt").exists());
+        assert!(!repo.path().join("tmp").exists());
+        let Removed::RepoTrash(batch) = removed else { panic!("expected a trash batch") };
+        assert_eq!(trash_batches(repo.repo()).unwrap(), vec![batch.clone()]);
+
+        // A file recreated since the clean is not overwritten
+        repo.write("notes.txt", "new notes\n");
+        let (restored, kept) = restore_batch(repo.path(), &batch).unwrap();
+        assert_eq!(restored, vec!["tmp"]);
+        assert_eq!(kept, vec!["notes.txt"]);
+        assert_eq!(fs::read_to_string(repo.path().join("tmp/a.txt")).unwrap(), "a\n");
+        assert_eq!(fs::read_to_string(repo.path().join("notes.txt")).unwrap(), "new notes\n");
+    }
+
+    #[test]
+    fn test_encode_trash_path() {
+        assert_eq!(encode_trash_path("/home/me/my file.txt"), "/home/me/my%20file.txt");
+    }
+}
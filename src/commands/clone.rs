@@ -1,30 +1,41 @@
 use anyhow::Result;
 use colored::*;
-use git2::{build::RepoBuilder, FetchOptions, Progress, RemoteCallbacks};
-use std::cell::RefCell;
-use std::io::{self, Write};
+use git2::{build::RepoBuilder, Direction, FetchOptions, RemoteCallbacks};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::cli::CloneArgs;
+use crate::cli::{CloneArgs, Protocol};
+use crate::commands::mirror::{BARE_REFSPEC, MIRROR_REFSPEC};
+use crate::commands::submodule::show_health_summary;
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
-use crate::interactive::InteractivePrompt;
+use crate::forge::{self, Shorthand};
+use crate::interactive::{InteractivePrompt, TransferProgress};
+use crate::network;
+use crate::submodule::{SubmoduleIssue, SubmoduleManager, UpdateOptions};
+use crate::timings::{self, Phase};
+use crate::utils::{expand_home, parse_git_url};
+use crate::validation::validate_url;
 
 /// Execute the clone command
-pub async fn execute(args: &CloneArgs, _rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("{} Cloning repository...", "🚀".blue().bold());
+pub async fn execute(args: &CloneArgs, config: &Config) -> Result<()> {
+    let repo_url = &resolve_url(args, config)?;
+    ui_println!("{} Cloning repository...", "🚀".blue().bold());
     
-    let repo_url = &args.url; // Fixed: changed from args.repository to args.url
     let target_dir = args.directory.as_ref()
-        .map(|d| PathBuf::from(d)) // Fixed: convert String to PathBuf
-        .unwrap_or_else(|| PathBuf::from(extract_repo_name(repo_url)));
+        .map(PathBuf::from)
+        .unwrap_or_else(|| default_target(repo_url, config, args.bare || args.mirror));
     
-    // Validate URL
-    if !is_valid_git_url(repo_url) {
-        return Err(RgitError::InvalidRemoteUrl(repo_url.clone().to_owned()).into());
+    // Validate URL (an existing directory is always a valid source)
+    let transfer_url = network::rewrite_url(config, repo_url, Direction::Fetch);
+    if !Path::new(&transfer_url).exists() {
+        validate_url(&transfer_url)?;
+    }
+    if args.lfs {
+        require_lfs()?;
     }
     
     // Check if directory already exists
@@ -36,7 +47,7 @@ pub async fn execute(args: &CloneArgs, _rgit: &RgitCore, config: &Config) -> Res
                     .confirm()?;
                 
                 if !overwrite {
-                    println!("{} Clone cancelled", "❌".red());
+                    ui_println!("{} Clone cancelled", "❌".red());
                     return Ok(());
                 }
             } else {
@@ -47,49 +58,72 @@ pub async fn execute(args: &CloneArgs, _rgit: &RgitCore, config: &Config) -> Res
     }
     
     // Show clone details
-    println!("{} Repository: {}", "📡".blue(), repo_url.cyan());
-    println!("{} Target: {}", "📁".blue(), target_dir.display().to_string().yellow());
+    ui_println!("{} Repository: {}", "📡".blue(), repo_url.cyan());
+    if transfer_url != *repo_url {
+        ui_println!("{} Via: {}", "🔀".blue(), transfer_url.dimmed());
+    }
+    ui_println!("{} Target: {}", "📁".blue(), target_dir.display().to_string().yellow());
     
     if let Some(branch) = &args.branch {
-        println!("{} Branch: {}", "🌿".green(), branch.green());
+        ui_println!("{} Branch: {}", "🌿".green(), branch.green());
     }
     
     if let Some(depth) = args.depth {
-        println!("{} Depth: {} (shallow clone)", "📏".yellow(), depth);
+        ui_println!("{} Depth: {} (shallow clone)", "📏".yellow(), depth);
     }
     
-    // Note: You'll need to add these fields to CloneArgs or remove these checks
-    // if args.bare {
-    //     println!("{} Mode: Bare repository", "📦".blue());
-    // }
-    
-    // if args.mirror {
-    //     println!("{} Mode: Mirror repository", "🪞".blue());
-    // }
+    if args.mirror {
+        ui_println!("{} Mode: Mirror repository", "🪞".blue());
+    } else if args.bare {
+        ui_println!("{} Mode: Bare repository", "📦".blue());
+    }
     
     // Perform the clone
-    println!("\n{} Cloning...", "⏳".yellow());
+    ui_println!("\n{} Cloning...", "⏳".yellow());
     
-    let progress = Arc::new(RefCell::new(CloneProgress::new()));
     let cancelled = Arc::new(AtomicBool::new(false));
     
-    match perform_clone(repo_url, &target_dir, args, progress.clone(), cancelled.clone()).await {
+    match perform_clone(repo_url, &transfer_url, &target_dir, args, config, cancelled.clone()).await {
         Ok(repo) => {
-            println!("\n{} Successfully cloned to {}", 
+            ui_println!("\n{} Successfully cloned to {}", 
                     "✅".green().bold(), 
                     target_dir.display().to_string().cyan());
             
             // Show repository info
             show_repo_info(&repo, config)?;
             
+            // Submodules and LFS objects are fetched into a finished clone, so
+            // failures leave it in place to be retried from inside
+            let has_submodules = !repo.is_bare() && !repo.submodules()?.is_empty();
+            let recurse = has_submodules && (args.recursive || config.submodules.auto_init);
+            let mut failures = Vec::new();
+            if recurse {
+                failures.extend(clone_submodules(&target_dir, args.jobs, config)?);
+            }
+            if args.lfs && !repo.is_bare() {
+                fetch_lfs(&target_dir, recurse)?;
+            }
+            
             // Show next steps
-            println!("\n{} Next steps:", "💡".blue());
-            println!("  • {} - Enter the repository", format!("cd {}", target_dir.display()).cyan());
-            println!("  • {} - Check repository status", "rgit status".cyan());
-            println!("  • {} - View recent commits", "rgit log".cyan());
+            ui_println!("\n{} Next steps:", "💡".blue());
+            if repo.is_bare() {
+                ui_println!("  • {} - Fetch new and deleted refs", format!("rgit mirror update {}", target_dir.display()).cyan());
+                ui_println!("  • {} - Keep it in sync", format!("rgit mirror update --every 15m {}", target_dir.display()).cyan());
+                return Ok(());
+            }
+            ui_println!("  • {} - Enter the repository", format!("cd {}", target_dir.display()).cyan());
+            ui_println!("  • {} - Check repository status", "rgit status".cyan());
+            ui_println!("  • {} - View recent commits", "rgit log".cyan());
+            
+            if has_submodules && (!recurse || !failures.is_empty()) {
+                ui_println!("  • {} - Initialize submodules", "rgit submodule update --init --recursive".cyan());
+            }
+            if !args.lfs && uses_lfs(&target_dir) {
+                ui_println!("  • {} - Download Git LFS files", "git lfs pull".cyan());
+            }
             
-            if args.recursive {
-                println!("  • {} - Initialize submodules", "rgit submodule update --init".cyan());
+            if !failures.is_empty() {
+                return Err(RgitError::SubmoduleOperationFailed(failures.join("; ")).into());
             }
         }
         Err(e) => {
@@ -98,7 +132,7 @@ pub async fn execute(args: &CloneArgs, _rgit: &RgitCore, config: &Config) -> Res
                 let _ = std::fs::remove_dir_all(&target_dir);
             }
             
-            println!("{} Clone failed: {}", "❌".red().bold(), e);
+            ui_println!("{} Clone failed: {}", "❌".red().bold(), e);
             return Err(e);
         }
     }
@@ -106,143 +140,242 @@ pub async fn execute(args: &CloneArgs, _rgit: &RgitCore, config: &Config) -> Res
     Ok(())
 }
 
-/// Progress tracking for clone operations
-struct CloneProgress {
-    total_objects: usize,
-    received_objects: usize,
-    received_bytes: usize,
-    indexed_objects: usize,
-    indexed_deltas: usize,
-    total_deltas: usize,
-}
-
-impl CloneProgress {
-    fn new() -> Self {
-        Self {
-            total_objects: 0,
-            received_objects: 0,
-            received_bytes: 0,
-            indexed_objects: 0,
-            indexed_deltas: 0,
-            total_deltas: 0,
+/// Perform the actual clone operation, transferring from `transfer_url` (the
+/// rewritten `url`) while origin keeps `url`, as git does
+async fn perform_clone(
+    url: &str,
+    transfer_url: &str,
+    target: &Path,
+    args: &CloneArgs,
+    config: &Config,
+    _cancelled: Arc<AtomicBool>,
+) -> Result<git2::Repository> {
+    let bare = args.bare || args.mirror;
+    
+    // libgit2 removes what a failed clone wrote, so every attempt starts
+    // from an empty target with a fresh builder, callbacks and progress
+    let repo = network::with_retries(config, || {
+        let progress = TransferProgress::new(config);
+        let mut builder = RepoBuilder::new();
+        let mut callbacks = RemoteCallbacks::new();
+        progress.attach(&mut callbacks);
+        callbacks.credentials(network::credentials(config));
+        
+        // Set up fetch options
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        
+        if bare {
+            // Branches (for a mirror, every ref) map straight onto the repository's
+            // own, so `rgit mirror update` keeps it identical to its source
+            let mirror = args.mirror;
+            builder.bare(true).remote_create(move |repo, name, url| {
+                let remote = repo.remote_with_fetch(name, url, if mirror { MIRROR_REFSPEC } else { BARE_REFSPEC })?;
+                if mirror {
+                    repo.config()?.set_bool(&format!("remote.{}.mirror", name), true)?;
+                }
+                Ok(remote)
+            });
         }
-    }
-    
-    fn update(&mut self, progress: Progress) {
-        self.total_objects = progress.total_objects();
-        self.received_objects = progress.received_objects();
-        self.received_bytes = progress.received_bytes();
-        self.indexed_objects = progress.indexed_objects();
-        self.indexed_deltas = progress.indexed_deltas();
-        self.total_deltas = progress.total_deltas();
         
-        self.display();
-    }
-    
-    fn display(&self) {
-        if self.total_objects > 0 {
-            let receive_percent = (self.received_objects * 100) / self.total_objects;
-            print!("\r{} Receiving objects: {}% ({}/{}), {} bytes", 
-                   "📥".green(),
-                   receive_percent,
-                   self.received_objects,
-                   self.total_objects,
-                   format_bytes(self.received_bytes));
+        if let Some(branch) = &args.branch {
+            builder.branch(branch);
         }
         
-        if self.total_deltas > 0 && self.indexed_deltas > 0 {
-            let delta_percent = (self.indexed_deltas * 100) / self.total_deltas;
-            print!("\r{} Resolving deltas: {}% ({}/{})", 
-                   "🔧".yellow(),
-                   delta_percent,
-                   self.indexed_deltas,
-                   self.total_deltas);
+        if let Some(depth) = args.depth {
+            fetch_options.depth(depth as i32);
         }
         
-        io::stdout().flush().unwrap();
+        builder.fetch_options(fetch_options);
+        
+        // Perform clone
+        let cloned = timings::time(Phase::Network, || builder.clone(transfer_url, target));
+        progress.finish();
+        cloned
+    }, |e| RgitError::OperationFailed(format!("Clone failed: {}", e.message()))).await?;
+    if transfer_url != url {
+        repo.remote_set_url("origin", url)?;
+    }
+    if bare {
+        // libgit2 records the remote's HEAD as a remote-tracking ref even without a refspec for it
+        if let Ok(mut remote_head) = repo.find_reference("refs/remotes/origin/HEAD") {
+            remote_head.delete()?;
+        }
     }
+    
+    Ok(repo)
 }
 
-/// Perform the actual clone operation
-async fn perform_clone(
-    url: &str,
-    target: &Path,
-    args: &CloneArgs,
-    progress: Arc<RefCell<CloneProgress>>,
-    _cancelled: Arc<AtomicBool>,
-) -> Result<git2::Repository> {
-    let mut builder = RepoBuilder::new();
-    
-    // Set up progress callback
-    let mut callbacks = RemoteCallbacks::new();
-    // Fixed: use correct method name for git2
-    callbacks.transfer_progress(|stats| {
-        progress.borrow_mut().update(stats);
-        true
-    });
-    
-    // Set up fetch options
-    let mut fetch_options = FetchOptions::new();
-    fetch_options.remote_callbacks(callbacks);
-    
-    // Configure clone options - commented out since fields don't exist in CloneArgs
-    // if args.bare {
-    //     builder.bare(true);
-    // }
-    
-    // Note: mirror() method may not exist in git2 - check documentation
-    // if args.mirror {
-    //     builder.mirror(true);
-    // }
-    
-    if let Some(branch) = &args.branch {
-        builder.branch(branch);
+/// Clone every submodule, nested ones included, `jobs` at a time, then
+/// report their health. Returns the submodules that failed.
+fn clone_submodules(target: &Path, jobs: Option<usize>, config: &Config) -> Result<Vec<String>> {
+    let rgit = RgitCore::from_path(target, false)?;
+    let manager = SubmoduleManager::new(&rgit, config);
+    let paths: Vec<PathBuf> = rgit.repo.submodules()?
+        .iter()
+        .map(|submodule| submodule.path().to_path_buf())
+        .collect();
+    let jobs = manager.update_jobs(jobs);
+    ui_println!("\n{} Cloning {} submodule(s), {} at a time...", "🔗".blue(), paths.len(), jobs.min(paths.len()));
+
+    let options = UpdateOptions { init: true, recursive: true, ..Default::default() };
+    let outcomes = manager.update_paths(&paths, options, jobs)?;
+    let mut failures = Vec::new();
+    for outcome in &outcomes {
+        match &outcome.error {
+            None => rgit.success(&format!("Cloned '{}' ({})", outcome.name, outcome.summary)),
+            Some(e) => {
+                rgit.warning(&format!("Failed to clone '{}': {}", outcome.name, e));
+                failures.push(format!("{}: {}", outcome.name, e));
+            }
+        }
     }
-    
-    if let Some(depth) = args.depth {
-        fetch_options.depth(depth as i32);
+
+    // A fresh submodule checkout is detached by design, so that isn't worth reporting
+    let mut health = manager.check_health()?;
+    for status in health.submodules.values_mut() {
+        status.issues.retain(|issue| !matches!(issue, SubmoduleIssue::DetachedHead));
     }
-    
-    builder.fetch_options(fetch_options);
-    
-    // Perform clone
-    let repo = builder.clone(url, target)
-        .map_err(|e| anyhow::anyhow!("Clone failed: {}", e.message()))?;
-    
-    // Handle submodules if requested
-    if args.recursive {
-        println!("\n{} Initializing submodules...", "🔗".blue());
-        init_submodules(&repo)?;
+    println!();
+    show_health_summary(&health, config)?;
+    Ok(failures)
+}
+
+/// Whether the checkout tracks files with Git LFS
+fn uses_lfs(workdir: &Path) -> bool {
+    std::fs::read_to_string(workdir.join(".gitattributes"))
+        .is_ok_and(|attributes| attributes.contains("filter=lfs"))
+}
+
+/// Download the Git LFS files of the checkout, and of its submodules when
+/// they were cloned too, with the git-lfs client
+fn fetch_lfs(target: &Path, submodules: bool) -> Result<()> {
+    let mut workdirs = vec![target.to_path_buf()];
+    if submodules {
+        collect_submodule_workdirs(&git2::Repository::open(target)?, &mut workdirs);
     }
-    
-    println!(); // New line after progress
-    Ok(repo)
+
+    ui_println!("\n{} Fetching Git LFS files...", "📦".blue());
+    for workdir in workdirs.iter().filter(|workdir| uses_lfs(workdir)) {
+        let name = workdir.strip_prefix(target).ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map_or_else(|| ".".to_string(), |relative| relative.display().to_string());
+        // --local installs the filters this checkout needs without touching global config
+        run_lfs(workdir, &["install", "--local"])?;
+        run_lfs(workdir, &["pull"])?;
+        let files = run_lfs(workdir, &["ls-files", "--name-only"])?.lines().count();
+        ui_println!("  {} {}: {} file(s)", "✅".green(), name.cyan(), files);
+    }
+    Ok(())
 }
 
-/// Initialize submodules recursively
-fn init_submodules(repo: &git2::Repository) -> Result<()> {
-    let submodules = repo.submodules()?;
-    
-    for mut submodule in submodules {
-        println!("  {} Initializing submodule: {}", 
-                "🔗".blue(), 
-                submodule.name().unwrap_or("unnamed").cyan());
-        
-        submodule.init(false)?;
-        
-        submodule.update(true, None)?;
-        
-        // Recursively init submodules in submodules
-        let subrepo = submodule.open()?;
-        let sub_submodules = subrepo.submodules();
-        if let Ok(sub_submodules) = &sub_submodules {
-            if !sub_submodules.is_empty() {
-                init_submodules(&subrepo)?;
+/// Fail before cloning when `--lfs` can't be honored
+fn require_lfs() -> Result<()> {
+    if run_lfs(Path::new("."), &["version"]).is_err() {
+        return Err(RgitError::OperationNotSupported(
+            "--lfs needs the git-lfs client; install it from https://git-lfs.com".to_string()
+        ).into());
+    }
+    Ok(())
+}
+
+fn run_lfs(workdir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("lfs").args(args).current_dir(workdir).output()
+        .map_err(|e| RgitError::CommandExecutionFailed(format!("git lfs {}: {}", args.join(" "), e)))?;
+    if !output.status.success() {
+        return Err(RgitError::CommandExecutionFailed(
+            format!("git lfs {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim())
+        ).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Working trees of every checked out submodule below `repo`, nested ones included
+fn collect_submodule_workdirs(repo: &git2::Repository, workdirs: &mut Vec<PathBuf>) {
+    for submodule in repo.submodules().unwrap_or_default() {
+        if let Ok(sub_repo) = submodule.open() {
+            if let Some(workdir) = sub_repo.workdir() {
+                workdirs.push(workdir.to_path_buf());
             }
+            collect_submodule_workdirs(&sub_repo, workdirs);
         }
     }
-    
-    Ok(())
+}
+
+/// The URL to clone. Forge shorthands are expanded with the preferred
+/// protocol, and an owner on its own lists its repositories to pick from.
+fn resolve_url(args: &CloneArgs, config: &Config) -> Result<String> {
+    let Some(shorthand) = Shorthand::parse(&args.url) else {
+        return Ok(args.url.clone());
+    };
+    let forge = shorthand.forge;
+    let protocol = match &args.protocol {
+        Some(Protocol::Https) => "https",
+        Some(Protocol::Ssh) => "ssh",
+        Some(Protocol::Git) => return Err(RgitError::InvalidArgument(
+            format!("{} doesn't serve the git:// protocol; use https or ssh", forge.host())
+        ).into()),
+        None => config.clone.protocol.as_str(),
+    };
+    if let Some(repo) = &shorthand.repo {
+        return Ok(forge.clone_url(&shorthand.owner, repo, protocol));
+    }
+
+    ui_println!("{} Listing repositories of {} on {}...", "🔍".blue(), shorthand.owner.cyan(), forge.host());
+    let repos = forge::list_repos(forge, &shorthand.owner)?;
+    if repos.is_empty() {
+        return Err(RgitError::OperationFailed(
+            format!("{} has no repositories visible on {}", shorthand.owner, forge.host())
+        ).into());
+    }
+
+    if !config.is_interactive() {
+        for repo in &repos {
+            println!("  {}:{}", forge.prefix(), repo.full_name);
+        }
+        return Err(RgitError::InvalidArgument(
+            format!("pick one of the repositories above, e.g. rgit clone {}:{}", forge.prefix(), repos[0].full_name)
+        ).into());
+    }
+
+    let options: Vec<String> = repos.iter()
+        .map(|repo| {
+            let mut label = repo.name.clone();
+            if repo.archived {
+                label.push_str(" (archived)");
+            }
+            if let Some(description) = &repo.description {
+                label.push_str(&format!(" - {}", description));
+            }
+            label
+        })
+        .collect();
+    let choice = InteractivePrompt::new()
+        .with_message(format!("Repository to clone from {}:{}", forge.prefix(), shorthand.owner))
+        .with_options(&options)
+        .fuzzy_search()
+        .select()?;
+    Ok(repos[choice].clone_url(protocol).to_string())
+}
+
+/// Where to clone when no directory is given: the `clone.destination`
+/// template for URLs with a host and owner, otherwise the repository name.
+/// Bare repositories get a `.git` suffix.
+fn default_target(url: &str, config: &Config, bare: bool) -> PathBuf {
+    let parts = parse_git_url(url).and_then(|info| {
+        let (owner, repo) = info.path.rsplit_once('/')?;
+        Some((info.host.clone(), owner.to_string(), repo.to_string()))
+    });
+    let target = match (&config.clone.destination, parts) {
+        (Some(template), Some((host, owner, repo))) => expand_home(&forge::destination(template, &host, &owner, &repo)),
+        _ => PathBuf::from(extract_repo_name(url)),
+    };
+    if !bare {
+        return target;
+    }
+    let mut name = target.into_os_string();
+    name.push(".git");
+    PathBuf::from(name)
 }
 
 /// Extract repository name from URL
@@ -256,40 +389,28 @@ fn extract_repo_name(url: &str) -> String {
         .to_string()
 }
 
-/// Validate if the URL is a valid git repository URL
-fn is_valid_git_url(url: &str) -> bool {
-    // Basic validation - can be extended
-    url.starts_with("http://") 
-        || url.starts_with("https://") 
-        || url.starts_with("git://")
-        || url.starts_with("ssh://")
-        || url.starts_with("git@")
-        || url.ends_with(".git")
-        || std::path::Path::new(url).exists()
-}
-
 /// Show repository information after successful clone
 fn show_repo_info(repo: &git2::Repository, config: &Config) -> Result<()> {
     if !config.ui.interactive {
         return Ok(());
     }
     
-    println!("\n{} Repository Information:", "📊".blue().bold());
+    ui_println!("\n{} Repository Information:", "📊".blue().bold());
     
     // Show HEAD reference
     if let Ok(head) = repo.head() {
         if let Some(name) = head.shorthand() {
-            println!("  {} Current branch: {}", "🌿".green(), name.cyan());
+            ui_println!("  {} Current branch: {}", "🌿".green(), name.cyan());
         }
         
         if let Ok(commit) = head.peel_to_commit() {
             let summary = commit.summary().unwrap_or("No commit message");
             let author = commit.author();
             
-            println!("  {} Latest commit: {}", "📝".yellow(), 
+            ui_println!("  {} Latest commit: {}", "📝".yellow(), 
                     commit.id().to_string()[..8].yellow());
-            println!("    {} {}", "💬".blue(), summary.white());
-            println!("    {} {} <{}>", "👤".blue(), 
+            ui_println!("    {} {}", "💬".blue(), summary.white());
+            ui_println!("    {} {} <{}>", "👤".blue(), 
                     author.name().unwrap_or("Unknown"),
                     author.email().unwrap_or("unknown@example.com"));
         }
@@ -299,11 +420,11 @@ fn show_repo_info(repo: &git2::Repository, config: &Config) -> Result<()> {
     if let Ok(remotes) = repo.remotes() {
         if let Some(remote_names) = remotes.iter().collect::<Option<Vec<_>>>() {
             if !remote_names.is_empty() {
-                println!("  {} Remotes:", "🌐".blue());
+                ui_println!("  {} Remotes:", "🌐".blue());
                 for remote_name in remote_names {
                     if let Ok(remote) = repo.find_remote(remote_name) {
                         if let Some(url) = remote.url() {
-                            println!("    {} {} -> {}", "•".green(), remote_name.cyan(), url.dimmed());
+                            ui_println!("    {} {} -> {}", "•".green(), remote_name.cyan(), url.dimmed());
                         }
                     }
                 }
@@ -315,31 +436,13 @@ fn show_repo_info(repo: &git2::Repository, config: &Config) -> Result<()> {
     if let Ok(index) = repo.index() {
         let file_count = index.len();
         if file_count > 0 {
-            println!("  {} Files: {}", "📁".blue(), file_count.to_string().yellow());
+            ui_println!("  {} Files: {}", "📁".blue(), file_count.to_string().yellow());
         }
     }
     
     Ok(())
 }
 
-/// Format bytes for display
-fn format_bytes(bytes: usize) -> String {
-    const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
-    let mut size = bytes as f64;
-    let mut unit_index = 0;
-    
-    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
-        size /= 1024.0;
-        unit_index += 1;
-    }
-    
-    if unit_index == 0 {
-        format!("{} {}", size as usize, UNITS[unit_index])
-    } else {
-        format!("{:.1} {}", size, UNITS[unit_index])
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -353,19 +456,22 @@ mod tests {
     }
 
     #[test]
-    fn test_is_valid_git_url() {
-        assert!(is_valid_git_url("https://github.com/user/repo.git"));
-        assert!(is_valid_git_url("git@github.com:user/repo.git"));
-        assert!(is_valid_git_url("ssh://git@github.com/user/repo.git"));
-        assert!(is_valid_git_url("file:///local/repo.git"));
-        assert!(!is_valid_git_url("invalid-url"));
+    fn test_validate_url() {
+        assert!(validate_url("https://github.com/user/repo.git").is_ok());
+        assert!(validate_url("git@github.com:user/repo.git").is_ok());
+        assert!(validate_url("ssh://git@github.com/user/repo.git").is_ok());
+        assert!(validate_url("file:///local/repo.git").is_ok());
+        assert!(validate_url("invalid-url").is_err());
     }
 
     #[test]
-    fn test_format_bytes() {
-        assert_eq!(format_bytes(512), "512 B");
-        assert_eq!(format_bytes(1024), "1.0 KB");
-        assert_eq!(format_bytes(1536), "1.5 KB");
-        assert_eq!(format_bytes(1048576), "1.0 MB");
+    fn test_default_target() {
+        let mut config = Config::default();
+        assert_eq!(default_target("https://github.com/me/tool.git", &config, false), PathBuf::from("tool"));
+        assert_eq!(default_target("https://github.com/me/tool.js", &config, true), PathBuf::from("tool.js.git"));
+
+        config.clone.destination = Some("/src/{host}/{owner}/{repo}".to_string());
+        assert_eq!(default_target("git@gitlab.com:group/sub/tool.git", &config, false), PathBuf::from("/src/gitlab.com/group/sub/tool"));
+        assert_eq!(default_target("/local/path/repo", &config, false), PathBuf::from("repo"));
     }
 }
\ No newline at end of file
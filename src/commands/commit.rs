@@ -1,15 +1,28 @@
 use anyhow::Result;
 use colored::*;
-use git2::Oid;
+use git2::{Commit, Delta, Oid, Repository, Sort};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::cli::CommitArgs;
+use crate::cli::{CommitArgs, TrailerArgs};
+use crate::commands::scan;
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::{CommitMessageEditor, InteractivePrompt};
-use crate::utils::{validate_commit_message, shorten_oid};
+use crate::utils::{
+    check_timestamp, format_time, humanize_size, is_valid_email, parse_size, shorten_oid, system_clock_is_implausible, validate_commit_message, TimestampAnomaly,
+};
+
+/// Trailer crediting someone who worked on a commit
+const CO_AUTHOR_TRAILER: &str = "Co-authored-by";
+/// Trailer certifying the Developer Certificate of Origin
+const SIGNOFF_TRAILER: &str = "Signed-off-by";
+/// Commits searched for people to offer as co-authors
+const COLLABORATOR_SCAN_LIMIT: usize = 500;
+/// Co-authors offered by `--pick-co-authors`
+const COLLABORATOR_PICKER_SIZE: usize = 20;
 
 /// Execute the commit command
 pub async fn execute(args: &CommitArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
@@ -18,6 +31,7 @@ pub async fn execute(args: &CommitArgs, rgit: &RgitCore, config: &Config) -> Res
     
     // Get commit message
     let message = get_commit_message(args, rgit, config).await?;
+    let message = append_trailers(&message, &requested_trailers(&args.trailers, rgit, config)?);
     
     // Auto-stage files if requested
     if args.all {
@@ -34,6 +48,13 @@ pub async fn execute(args: &CommitArgs, rgit: &RgitCore, config: &Config) -> Res
         }
     }
     
+    // Refuse to commit credentials
+    if !args.allow_secrets {
+        scan::check_staged_changes(rgit, config)?;
+    }
+    
+    check_large_files(rgit, config, args.allow_large_files)?;
+    
     // Run pre-commit hooks if enabled
     if !args.no_verify && config.integrations.hooks.pre_commit {
         run_pre_commit_hooks(rgit, config).await?;
@@ -83,10 +104,235 @@ async fn perform_pre_commit_checks(
     if args.amend {
         warn_about_amend_published(rgit, config).await?;
     }
+
+    warn_about_commit_times(rgit, args.amend);
     
     Ok(())
 }
 
+/// Warn when the clock or the parent commits would produce misordered history
+fn warn_about_commit_times(rgit: &RgitCore, amend: bool) {
+    let now = chrono::Utc::now().timestamp();
+    if system_clock_is_implausible(now) {
+        ui_println!("{} System clock reads {}; the commit date will be wrong",
+                "⚠️".yellow(), format_time(git2::Time::new(now, 0)).yellow());
+    }
+
+    let head = match rgit.repo.head().and_then(|h| h.peel_to_commit()) {
+        Ok(head) => head,
+        Err(_) => return,
+    };
+    let parents: Vec<Commit> = if amend { head.parents().collect() } else { vec![head] };
+
+    for parent in parents {
+        if let Some(anomaly @ TimestampAnomaly::Future(_)) = check_timestamp(parent.time().seconds(), now) {
+            ui_println!("{} Parent {} is dated {}; this commit will sort before it",
+                    "⚠️".yellow(), shorten_oid(&parent.id(), 8).yellow(), anomaly);
+            println!("   Check the system clock, or run {} for details", "rgit doctor".cyan());
+        }
+    }
+}
+
+/// Warn about staged files above `commit.warn_file_size`, and refuse to
+/// commit files above `commit.max_file_size` unless `allow` is set
+pub fn check_large_files(rgit: &RgitCore, config: &Config, allow: bool) -> Result<()> {
+    let warn_at = parse_size(&config.commit.warn_file_size).unwrap_or(u64::MAX);
+    let block_at = match &config.commit.max_file_size {
+        Some(max) if !allow => parse_size(max),
+        _ => None,
+    };
+    let threshold = block_at.map_or(warn_at, |block| block.min(warn_at));
+
+    let large = staged_files_over(&rgit.repo, threshold)?;
+    if large.is_empty() {
+        return Ok(());
+    }
+
+    ui_println!("{} Large files staged:", "⚠️".yellow());
+    for (path, size) in &large {
+        let shown = humanize_size(*size);
+        let blocked = block_at.is_some_and(|block| *size > block);
+        println!("  {} {}", path.white(), if blocked { shown.red() } else { shown.yellow() });
+    }
+
+    let mut patterns: Vec<String> = large.iter()
+        .map(|(path, _)| match Path::new(path).extension() {
+            Some(ext) => format!("\"*.{}\"", ext.to_string_lossy()),
+            None => format!("\"{}\"", path),
+        })
+        .collect();
+    patterns.sort();
+    patterns.dedup();
+    ui_println!("{} Track them with Git LFS ({}), or add them to .gitignore and {}",
+            "💡".blue(), format!("git lfs track {}", patterns.join(" ")).cyan(), "rgit unstage".cyan());
+
+    if let (Some(block), Some(limit)) = (block_at, &config.commit.max_file_size) {
+        let count = large.iter().filter(|(_, size)| *size > block).count();
+        if count > 0 {
+            return Err(RgitError::StagedFilesTooLarge { count, limit: limit.clone() }.into());
+        }
+    }
+    Ok(())
+}
+
+/// Staged files whose new content is larger than `threshold` bytes
+fn staged_files_over(repo: &Repository, threshold: u64) -> Result<Vec<(String, u64)>> {
+    let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let diff = repo.diff_tree_to_index(head.as_ref(), None, None)?;
+    let odb = repo.odb()?;
+
+    let mut large = Vec::new();
+    for delta in diff.deltas() {
+        let file = delta.new_file();
+        if delta.status() == Delta::Deleted || file.id().is_zero() {
+            continue;
+        }
+        let (size, _) = odb.read_header(file.id())?;
+        if size as u64 > threshold {
+            large.push((file.path().unwrap_or(Path::new("")).to_string_lossy().to_string(), size as u64));
+        }
+    }
+    Ok(large)
+}
+
+/// Trailers asked for with `--co-author`, `--pick-co-authors`, `--trailer`
+/// and `--signoff` or `commit.signoff`, in that order
+pub fn requested_trailers(args: &TrailerArgs, rgit: &RgitCore, config: &Config) -> Result<Vec<(String, String)>> {
+    let mut trailers = Vec::new();
+    for co_author in &args.co_authors {
+        trailers.push((CO_AUTHOR_TRAILER.to_string(), parse_identity(co_author)?));
+    }
+    if args.pick_co_authors {
+        for co_author in pick_co_authors(rgit, config)? {
+            trailers.push((CO_AUTHOR_TRAILER.to_string(), co_author));
+        }
+    }
+    for trailer in &args.trailers {
+        trailers.push(parse_trailer(trailer)?);
+    }
+    if args.signoff || config.commit.signoff {
+        let signature = rgit.get_signature()?;
+        trailers.push((SIGNOFF_TRAILER.to_string(),
+                       format!("{} <{}>", signature.name().unwrap_or(""), signature.email().unwrap_or(""))));
+    }
+    Ok(trailers)
+}
+
+/// Add `trailers` to the end of `message`, joining a trailer block that is
+/// already there and skipping lines the message already has
+pub fn append_trailers(message: &str, trailers: &[(String, String)]) -> String {
+    let mut message = message.trim_end().to_string();
+    let mut lines: Vec<String> = Vec::new();
+    for (key, value) in trailers {
+        let line = format!("{}: {}", key, value);
+        if !message.lines().any(|existing| existing.trim() == line) && !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+    if lines.is_empty() {
+        return message;
+    }
+
+    let has_trailer_block = message.rsplit_once("\n\n")
+        .is_some_and(|(_, last)| last.lines().all(is_trailer_line));
+    message.push_str(if has_trailer_block { "\n" } else { "\n\n" });
+    message.push_str(&lines.join("\n"));
+    message
+}
+
+/// Whether `line` looks like `Token: value`
+fn is_trailer_line(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, value)| is_trailer_key(key) && !value.trim().is_empty())
+}
+
+fn is_trailer_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Parse `key=value` or `key: value` from `--trailer`
+fn parse_trailer(trailer: &str) -> Result<(String, String)> {
+    let (key, value) = trailer.split_once('=')
+        .or_else(|| trailer.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .filter(|(key, value)| is_trailer_key(key) && !value.is_empty())
+        .ok_or_else(|| RgitError::InvalidArgument(format!("Expected a trailer like Reviewed-by=Name <email>, got {:?}", trailer)))?;
+    Ok((key.to_string(), value.to_string()))
+}
+
+/// Normalize `Name <email>`, rejecting anything that isn't an identity
+fn parse_identity(identity: &str) -> Result<String> {
+    let (name, email) = split_identity(identity)
+        .filter(|(name, email)| !name.is_empty() && is_valid_email(email))
+        .ok_or_else(|| RgitError::InvalidArgument(format!("Expected \"Name <email>\", got {:?}", identity)))?;
+    Ok(format!("{} <{}>", name, email))
+}
+
+fn split_identity(identity: &str) -> Option<(&str, &str)> {
+    let (name, rest) = identity.split_once('<')?;
+    let email = rest.trim_end().strip_suffix('>')?;
+    Some((name.trim(), email.trim()))
+}
+
+/// Authors and co-authors of recent commits as `Name <email>`, most recent
+/// first and one entry per email, leaving out `own_email`
+pub fn recent_collaborators(repo: &Repository, own_email: &str, limit: usize) -> Result<Vec<String>> {
+    let mut walk = repo.revwalk()?;
+    if walk.push_head().is_err() {
+        // Nothing committed yet
+        return Ok(Vec::new());
+    }
+    walk.set_sorting(Sort::TIME)?;
+
+    let mut seen = HashSet::from([own_email.to_lowercase()]);
+    let mut people = Vec::new();
+    for oid in walk.take(COLLABORATOR_SCAN_LIMIT) {
+        let commit = repo.find_commit(oid?)?;
+        let author = commit.author();
+        let mut identities = vec![format!("{} <{}>", author.name().unwrap_or(""), author.email().unwrap_or(""))];
+        identities.extend(commit.message().unwrap_or("").lines()
+            .filter_map(|line| line.split_once(':'))
+            .filter(|(key, _)| key.trim().eq_ignore_ascii_case(CO_AUTHOR_TRAILER))
+            .filter_map(|(_, value)| parse_identity(value.trim()).ok()));
+
+        for identity in identities {
+            let email = split_identity(&identity).map(|(_, email)| email.to_lowercase()).unwrap_or_default();
+            if !email.is_empty() && seen.insert(email) {
+                people.push(identity);
+            }
+        }
+        if people.len() >= limit {
+            people.truncate(limit);
+            break;
+        }
+    }
+    Ok(people)
+}
+
+/// Let the user choose co-authors from recent collaborators
+fn pick_co_authors(rgit: &RgitCore, config: &Config) -> Result<Vec<String>> {
+    // Without asking, nobody is added
+    if config.ui.assume_yes {
+        return Ok(Vec::new());
+    }
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+    let own_email = rgit.get_signature().ok()
+        .and_then(|signature| signature.email().map(str::to_string))
+        .unwrap_or_default();
+    let people = recent_collaborators(&rgit.repo, &own_email, COLLABORATOR_PICKER_SIZE)?;
+    if people.is_empty() {
+        ui_println!("{} No other contributors found in recent history", "ℹ️".blue());
+        return Ok(Vec::new());
+    }
+
+    let selected = InteractivePrompt::new()
+        .with_message("Select co-authors")
+        .with_options(&people)
+        .multiselect_prompt()?;
+    Ok(selected.into_iter().map(|i| people[i].clone()).collect())
+}
+
 /// Get commit message from various sources
 async fn get_commit_message(
     args: &CommitArgs, 
@@ -139,6 +385,10 @@ async fn get_message_from_template(rgit: &RgitCore, config: &Config) -> Result<S
 
 /// Get commit message interactively
 async fn get_message_interactively(rgit: &RgitCore, config: &Config) -> Result<String> {
+    // There's no message to take by default, so nothing is committed
+    if config.ui.assume_yes {
+        return Err(RgitError::InvalidArgument("--yes can't write the commit message; pass it with -m".to_string()).into());
+    }
     if !config.is_interactive() {
         return Err(RgitError::NonInteractiveEnvironment.into());
     }
@@ -164,9 +414,9 @@ async fn get_simple_commit_message(rgit: &RgitCore, _config: &Config) -> Result<
     let status = rgit.status()?;
     
     // Show what will be committed
-    println!("{} Files to be committed:", "📦".green());
+    ui_println!("{} Files to be committed:", "📦".green());
     for file in &status.staged {
-        println!("  {} {}: {}", 
+        ui_println!("  {} {}: {}", 
                 "✓".green(), 
                 file.status_symbol(true).green(),
                 file.path.white());
@@ -180,15 +430,15 @@ async fn get_simple_commit_message(rgit: &RgitCore, _config: &Config) -> Result<
             .input()?;
         
         if message.trim().is_empty() {
-            println!("{} Commit message cannot be empty", "❌".red());
+            ui_println!("{} Commit message cannot be empty", "❌".red());
             continue;
         }
         
         // Quick validation
         if let Err(issues) = quick_validate_message(&message) {
-            println!("{} Message issues found:", "⚠️".yellow());
+            ui_println!("{} Message issues found:", "⚠️".yellow());
             for issue in &issues {
-                println!("  • {}", issue.yellow());
+                ui_println!("  • {}", issue.yellow());
             }
             
             if InteractivePrompt::new()
@@ -261,15 +511,15 @@ fn validate_and_improve_message(message: &str, config: &Config) -> Result<String
     // If non-interactive, just warn about issues
     if !config.is_interactive() {
         for issue in &issues {
-            eprintln!("{} {}", "⚠️".yellow(), issue.yellow());
+            ui_eprintln!("{} {}", "⚠️".yellow(), issue.yellow());
         }
         return Ok(message.to_string());
     }
     
     // Show issues and ask for confirmation
-    println!("{} Commit message issues found:", "⚠️".yellow());
+    ui_println!("{} Commit message issues found:", "⚠️".yellow());
     for issue in &issues {
-        println!("  • {}", issue.yellow());
+        ui_println!("  • {}", issue.yellow());
     }
     
     if InteractivePrompt::new()
@@ -304,13 +554,13 @@ async fn auto_stage_files(rgit: &RgitCore, config: &Config) -> Result<()> {
     
     // Show what will be auto-staged
     if config.ui.interactive && unstaged_count > 0 {
-        println!("{} Auto-staging {} modified file{}:", 
+        ui_println!("{} Auto-staging {} modified file{}:", 
                 "📝".yellow(),
                 unstaged_count,
                 if unstaged_count == 1 { "" } else { "s" });
         
         for file in &status.unstaged {
-            println!("  {} {}: {}", 
+            ui_println!("  {} {}: {}", 
                     "○".yellow(), 
                     file.status_symbol(false).yellow(),
                     file.path.white());
@@ -362,7 +612,7 @@ async fn show_commit_summary(
     let first_line = message.lines().next().unwrap_or("").to_string();
     
     if config.ui.interactive {
-        println!("\n{} Commit created successfully!", "🎉".green());
+        ui_println!("\n{} Commit created successfully!", "🎉".green());
         println!("   {} {}", "ID:".bold(), short_id.yellow());
         println!("   {} {}", "Message:".bold(), first_line.white());
         
@@ -440,26 +690,26 @@ async fn show_next_steps(rgit: &RgitCore, config: &Config) -> Result<()> {
     
     let branch_info = rgit.get_branch_info()?;
     
-    println!("\n{} Next steps:", "💡".blue());
+    ui_println!("\n{} Next steps:", "💡".blue());
     
     // Push suggestions
     if branch_info.upstream.is_some() {
         if branch_info.ahead > 0 {
-            println!("  • {} - Share your changes", "rgit push".cyan());
+            ui_println!("  • {} - Share your changes", "rgit push".cyan());
         }
     } else if branch_info.name != "main" && branch_info.name != "master" {
-        println!("  • {} - Set up tracking and push", 
+        ui_println!("  • {} - Set up tracking and push", 
                 format!("rgit push --set-upstream origin {}", branch_info.name).cyan());
     }
     
     // Additional suggestions
-    println!("  • {} - Continue working", "Edit more files".cyan());
-    println!("  • {} - Quick sync workflow", "rgit sync".cyan());
+    ui_println!("  • {} - Continue working", "Edit more files".cyan());
+    ui_println!("  • {} - Quick sync workflow", "rgit sync".cyan());
     
     // Check for remaining changes
     let status = rgit.status()?;
     if !status.is_clean() {
-        println!("  • {} - Stage remaining changes", "rgit add".cyan());
+        ui_println!("  • {} - Stage remaining changes", "rgit add".cyan());
     }
     
     Ok(())
@@ -475,7 +725,7 @@ async fn handle_merge_commit(rgit: &RgitCore, config: &Config) -> Result<()> {
     rgit.log("Merge in progress detected");
     
     if config.ui.interactive {
-        println!("{} Merge in progress", "🔀".blue());
+        ui_println!("{} Merge in progress", "🔀".blue());
         println!("Creating merge commit...");
     }
     
@@ -503,10 +753,10 @@ async fn warn_about_amend_published(rgit: &RgitCore, config: &Config) -> Result<
     let branch_info = rgit.get_branch_info()?;
     
     if branch_info.behind == 0 && branch_info.ahead > 0 {
-        println!("{} {}", "⚠️".yellow(), "Warning: Amending unpushed commit".yellow());
+        ui_println!("{} {}", "⚠️".yellow(), "Warning: Amending unpushed commit".yellow());
         println!("This is safe as the commit hasn't been shared yet.");
     } else if branch_info.upstream.is_some() {
-        println!("{} {}", "⚠️".yellow(), "Warning: Amending potentially published commit".yellow());
+        ui_println!("{} {}", "⚠️".yellow(), "Warning: Amending potentially published commit".yellow());
         println!("This will rewrite history and may cause issues for collaborators.");
         
         if !InteractivePrompt::new()
@@ -540,21 +790,10 @@ pub fn has_staged_changes(rgit: &RgitCore) -> Result<bool> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::{create_test_repo, TestRepo};
     use tempfile::TempDir;
     use std::fs;
 
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[test]
     fn test_read_message_from_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -612,6 +851,64 @@ mod tests {
         assert!(!commit_id.is_zero());
     }
 
+    #[test]
+    fn test_large_file_guard() {
+        let repo = TestRepo::new();
+        repo.commit_file("small.txt", "small\n", "Initial commit");
+        repo.write("big.bin", &"x".repeat(4096)).write("small.txt", "still small\n").stage_all();
+
+        let large = staged_files_over(repo.repo(), 1024).unwrap();
+        assert_eq!(large, vec![("big.bin".to_string(), 4096)]);
+
+        let mut config = Config::default();
+        config.commit.warn_file_size = "1KB".to_string();
+        check_large_files(&repo.core(), &config, false).unwrap();
+
+        config.commit.max_file_size = Some("2KB".to_string());
+        let err = check_large_files(&repo.core(), &config, false).unwrap_err();
+        assert!(matches!(err.downcast_ref::<RgitError>(), Some(RgitError::StagedFilesTooLarge { count: 1, .. })));
+        check_large_files(&repo.core(), &config, true).unwrap();
+    }
+
+    #[test]
+    fn test_append_trailers() {
+        let co_author = ("Co-authored-by".to_string(), "Ada <ada@example.com>".to_string());
+        let reviewed = ("Reviewed-by".to_string(), "Bob <bob@example.com>".to_string());
+
+        assert_eq!(append_trailers("Fix parser\n", std::slice::from_ref(&co_author)),
+                   "Fix parser\n\nCo-authored-by: Ada <ada@example.com>");
+        assert_eq!(append_trailers("Fix parser\n\nCo-authored-by: Ada <ada@example.com>", &[co_author.clone(), reviewed.clone()]),
+                   "Fix parser\n\nCo-authored-by: Ada <ada@example.com>\nReviewed-by: Bob <bob@example.com>");
+        assert_eq!(append_trailers("Fix parser\n\nThe body: explained.", &[reviewed]),
+                   "Fix parser\n\nThe body: explained.\n\nReviewed-by: Bob <bob@example.com>");
+        assert_eq!(append_trailers("Fix parser", &[]), "Fix parser");
+    }
+
+    #[test]
+    fn test_parse_trailers_and_identities() {
+        assert_eq!(parse_trailer("Reviewed-by=Bob <bob@example.com>").unwrap(),
+                   ("Reviewed-by".to_string(), "Bob <bob@example.com>".to_string()));
+        assert_eq!(parse_trailer("Fixes: #12").unwrap(), ("Fixes".to_string(), "#12".to_string()));
+        assert!(parse_trailer("no value=").is_err());
+
+        assert_eq!(parse_identity("  Ada Lovelace <ada@example.com> ").unwrap(), "Ada Lovelace <ada@example.com>");
+        assert!(parse_identity("ada@example.com").is_err());
+        assert!(parse_identity("Ada <not-an-email>").is_err());
+    }
+
+    #[test]
+    fn test_recent_collaborators() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "1\n", "First");
+        repo.commit_file("a.txt", "2\n", "Pair on a\n\nCo-authored-by: Ada <ada@example.com>");
+        repo.commit_file("a.txt", "3\n", "Pair again\n\nCo-authored-by: Bob <bob@example.com>\nCo-authored-by: Ada <ADA@example.com>");
+
+        let own = repo.repo().signature().unwrap().email().unwrap().to_string();
+        let people = recent_collaborators(repo.repo(), &own, 10).unwrap();
+        assert_eq!(people, vec!["Bob <bob@example.com>", "Ada <ADA@example.com>"]);
+        assert_eq!(recent_collaborators(repo.repo(), &own, 1).unwrap().len(), 1);
+    }
+
     #[test]
     fn test_is_merge_in_progress() {
         let (_temp_dir, repo) = create_test_repo();
@@ -0,0 +1,564 @@
+use anyhow::Result;
+use colored::*;
+use git2::{ConfigLevel, Repository};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::cli::{ConfigArgs, ConfigCommands};
+use crate::config::{parse_bool, Config, ConfigKey, CONFIG_KEYS};
+use crate::error::RgitError;
+use crate::interactive::{InteractivePrompt, TableDisplay};
+use crate::utils::is_valid_email;
+
+/// Execute the config command
+pub async fn execute(args: &ConfigArgs, config: &Config) -> Result<()> {
+    let target = ConfigTarget::from_args(args);
+
+    match &args.action {
+        Some(ConfigCommands::Get { key }) => get_value(&target, key, config),
+        Some(ConfigCommands::Set { key, value }) => set_value(&target, key, value),
+        Some(ConfigCommands::Unset { key }) => unset_value(&target, key),
+        Some(ConfigCommands::List) => list_values(&target, config),
+        Some(ConfigCommands::Path) => show_paths(),
+        Some(ConfigCommands::Edit) | None => {
+            if !config.is_interactive() {
+                if args.action.is_some() {
+                    return Err(RgitError::NonInteractiveEnvironment.into());
+                }
+                return list_values(&target, config);
+            }
+            match target {
+                ConfigTarget::Rgit => interactive_rgit_editor(),
+                ConfigTarget::Git(scope) => interactive_git_editor(scope),
+            }
+        }
+    }
+}
+
+/// Which configuration store a command operates on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigTarget {
+    Rgit,
+    Git(GitScope),
+}
+
+/// Scope of git configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GitScope {
+    /// Effective value across all levels (reads) / repository if available (writes)
+    Auto,
+    Local,
+    Global,
+}
+
+impl ConfigTarget {
+    fn from_args(args: &ConfigArgs) -> Self {
+        if args.global {
+            ConfigTarget::Git(GitScope::Global)
+        } else if args.local {
+            ConfigTarget::Git(GitScope::Local)
+        } else if args.git {
+            ConfigTarget::Git(GitScope::Auto)
+        } else {
+            ConfigTarget::Rgit
+        }
+    }
+}
+
+impl GitScope {
+    fn label(&self) -> &'static str {
+        match self {
+            GitScope::Auto => "effective",
+            GitScope::Local => "local",
+            GitScope::Global => "global",
+        }
+    }
+}
+
+// =============================================================================
+// Non-interactive operations
+// =============================================================================
+
+/// Print a single value
+fn get_value(target: &ConfigTarget, key: &str, config: &Config) -> Result<()> {
+    let value = match target {
+        ConfigTarget::Rgit => config.get_value(key)?,
+        ConfigTarget::Git(scope) => {
+            let git_config = open_git_config(*scope, false)?;
+            git_config.get_string(key).ok()
+        }
+    };
+
+    match value {
+        Some(value) => {
+            println!("{}", value);
+            Ok(())
+        }
+        None => Err(RgitError::ConfigurationError(format!("'{}' is not set", key)).into()),
+    }
+}
+
+/// Set a value after validating it
+fn set_value(target: &ConfigTarget, key: &str, value: &str) -> Result<()> {
+    match target {
+        ConfigTarget::Rgit => {
+            let mut updated = stored_config()?;
+            updated.set_value(key, value)?;
+            updated.save()?;
+        }
+        ConfigTarget::Git(scope) => {
+            validate_git_value(key, value)?;
+            let mut git_config = open_git_config(*scope, true)?;
+            git_config.set_str(key, value)?;
+        }
+    }
+
+    ui_println!("{} Set {} = {}", "✅".green(), key.cyan(), value.bold());
+    Ok(())
+}
+
+/// Remove a value (rgit settings return to their defaults)
+fn unset_value(target: &ConfigTarget, key: &str) -> Result<()> {
+    match target {
+        ConfigTarget::Rgit => {
+            let mut updated = stored_config()?;
+            updated.unset_value(key)?;
+            updated.save()?;
+            let default = Config::default_value(key)?.unwrap_or_else(|| "(unset)".to_string());
+            ui_println!("{} Reset {} to default ({})", "✅".green(), key.cyan(), default.dimmed());
+        }
+        ConfigTarget::Git(scope) => {
+            let mut git_config = open_git_config(*scope, true)?;
+            git_config.remove(key).map_err(|_| {
+                RgitError::ConfigurationError(format!("'{}' is not set", key))
+            })?;
+            ui_println!("{} Removed {}", "✅".green(), key.cyan());
+        }
+    }
+
+    Ok(())
+}
+
+/// List all values in a table
+fn list_values(target: &ConfigTarget, config: &Config) -> Result<()> {
+    match target {
+        ConfigTarget::Rgit => list_rgit_values(config),
+        ConfigTarget::Git(scope) => list_git_values(*scope),
+    }
+}
+
+fn list_rgit_values(config: &Config) -> Result<()> {
+    ui_println!("{} rgit configuration", "⚙️".blue().bold());
+    println!();
+
+    let mut table = TableDisplay::new().with_headers(vec![
+        "Key".to_string(),
+        "Value".to_string(),
+        "Default".to_string(),
+    ]);
+
+    for key in CONFIG_KEYS {
+        let (current, default) = current_and_default(config, key)?;
+        let marker = if current != default { " *" } else { "" };
+        table.add_row(vec![
+            format!("{}{}", key.name, marker),
+            current,
+            default,
+        ]);
+    }
+
+    table.display();
+    println!();
+    ui_println!("{} Keys marked with * differ from the default", "💡".blue());
+    Ok(())
+}
+
+fn list_git_values(scope: GitScope) -> Result<()> {
+    let git_config = open_git_config(scope, false)?;
+
+    ui_println!("{} git configuration ({})", "⚙️".blue().bold(), scope.label());
+    println!();
+
+    let mut table = TableDisplay::new().with_headers(vec![
+        "Key".to_string(),
+        "Value".to_string(),
+        "Level".to_string(),
+    ]);
+
+    git_config.entries(None)?.for_each(|entry| {
+        table.add_row(vec![
+            entry.name().unwrap_or("<invalid>").to_string(),
+            entry.value().unwrap_or("").to_string(),
+            match scope {
+                GitScope::Auto => level_name(entry.level()),
+                _ => scope.label(),
+            }.to_string(),
+        ]);
+    })?;
+
+    table.display();
+    Ok(())
+}
+
+/// Show where configuration is stored
+fn show_paths() -> Result<()> {
+    ui_println!("{} Configuration files:", "📁".blue().bold());
+    println!("  {} {}", "rgit:".bold(), Config::get_config_path()?.display());
+
+    if let Ok(path) = global_git_config_path() {
+        println!("  {} {}", "git (global):".bold(), path.display());
+    }
+    if let Ok(repo) = Repository::discover(".") {
+        println!("  {} {}", "git (local):".bold(), repo.path().join("config").display());
+    }
+
+    Ok(())
+}
+
+/// The rgit configuration as stored, without the `RGIT_*` and command-line
+/// overrides the running configuration carries, so saving it changes only
+/// what the user asked for. Invalid values were reported at startup and go
+/// back to their defaults.
+fn stored_config() -> Result<Config> {
+    let mut stored = Config::load_stored()?;
+    stored.reset_invalid_values();
+    Ok(stored)
+}
+
+// =============================================================================
+// Interactive editing
+// =============================================================================
+
+/// Browse rgit settings grouped by section and edit them
+fn interactive_rgit_editor() -> Result<()> {
+    let mut working = stored_config()?;
+    let mut changed = false;
+
+    ui_println!("{} {} Configuration Editor", "⚙️".blue(), "rgit".cyan().bold());
+    println!("{}", "=".repeat(50).dimmed());
+    println!();
+
+    let sections = group_by_section(CONFIG_KEYS);
+    let section_names: Vec<&str> = sections.keys().copied().collect();
+
+    loop {
+        let mut options: Vec<String> = section_names
+            .iter()
+            .map(|name| {
+                let keys = &sections[name];
+                let modified = keys.iter()
+                    .filter(|key| is_modified(&working, key))
+                    .count();
+                if modified > 0 {
+                    format!("{} ({} keys, {} modified)", name, keys.len(), modified)
+                } else {
+                    format!("{} ({} keys)", name, keys.len())
+                }
+            })
+            .collect();
+        options.push("Save and exit".to_string());
+        options.push("Exit without saving".to_string());
+
+        let selection = InteractivePrompt::new()
+            .with_message("Choose a section to edit")
+            .with_options(&options)
+            .select()?;
+
+        if selection == section_names.len() {
+            if changed {
+                working.save()?;
+                ui_println!("{} Configuration saved to {}", "✅".green(),
+                        Config::get_config_path()?.display().to_string().dimmed());
+            } else {
+                ui_println!("{} No changes to save", "ℹ️".blue());
+            }
+            return Ok(());
+        }
+        if selection == section_names.len() + 1 {
+            if changed {
+                ui_println!("{} Changes discarded", "⚠️".yellow());
+            }
+            return Ok(());
+        }
+
+        let keys = &sections[section_names[selection]];
+        changed |= edit_section(&mut working, keys)?;
+    }
+}
+
+/// Edit keys within a single section; returns whether anything changed
+fn edit_section(working: &mut Config, keys: &[&'static ConfigKey]) -> Result<bool> {
+    let mut changed = false;
+
+    loop {
+        let mut options = Vec::with_capacity(keys.len() + 1);
+        for key in keys {
+            let (current, default) = current_and_default(working, key)?;
+            let leaf = key.name.rsplit('.').next().unwrap_or(key.name);
+            if current == default {
+                options.push(format!("{} = {}", leaf, current));
+            } else {
+                options.push(format!("{} = {}  (default: {})", leaf, current, default));
+            }
+        }
+        options.push("← Back".to_string());
+
+        let selection = InteractivePrompt::new()
+            .with_message("Choose a setting")
+            .with_options(&options)
+            .select()?;
+
+        if selection == keys.len() {
+            return Ok(changed);
+        }
+
+        changed |= edit_key(working, keys[selection])?;
+    }
+}
+
+/// Prompt for a new value of one key, re-prompting until it validates
+fn edit_key(working: &mut Config, key: &ConfigKey) -> Result<bool> {
+    let (current, default) = current_and_default(working, key)?;
+
+    println!();
+    println!("{} {}", key.name.cyan().bold(), key.description.dimmed());
+    println!("  Current: {}", current.bold());
+    println!("  Default: {}", default.dimmed());
+
+    let actions = ["Enter a new value", "Reset to default", "Cancel"];
+    let action = InteractivePrompt::new()
+        .with_message("What would you like to do?")
+        .with_options(&actions)
+        .select()?;
+
+    match action {
+        0 => loop {
+            let input: String = InteractivePrompt::new()
+                .with_message(format!("New value ({})", key.kind.hint()))
+                .allow_empty()
+                .input()?;
+
+            match working.set_value(key.name, &input) {
+                Ok(()) => {
+                    ui_println!("{} {} updated", "✅".green(), key.name.cyan());
+                    return Ok(true);
+                }
+                Err(e) => {
+                    ui_println!("{} {}", "❌".red(), e);
+                    if !InteractivePrompt::new()
+                        .with_message("Try again?")
+                        .confirm()? {
+                        return Ok(false);
+                    }
+                }
+            }
+        },
+        1 => {
+            working.unset_value(key.name)?;
+            ui_println!("{} {} reset to default", "✅".green(), key.name.cyan());
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Commonly edited git settings offered by the interactive git editor
+const GIT_KEYS: &[(&str, &str)] = &[
+    ("user.name", "Name recorded in commits"),
+    ("user.email", "Email recorded in commits"),
+    ("init.defaultBranch", "Branch name for new repositories"),
+    ("pull.rebase", "Rebase instead of merge when pulling"),
+    ("push.autoSetupRemote", "Set upstream automatically on first push"),
+    ("fetch.prune", "Prune deleted remote branches on fetch"),
+    ("core.editor", "Editor for commit messages"),
+    ("core.autocrlf", "Line ending conversion"),
+    ("commit.gpgsign", "Sign commits by default"),
+    ("merge.tool", "Tool used for conflict resolution"),
+];
+
+/// Edit common git settings at the selected scope
+fn interactive_git_editor(scope: GitScope) -> Result<()> {
+    let write_scope = if scope == GitScope::Auto { default_write_scope() } else { scope };
+    let mut git_config = open_git_config(write_scope, true)?;
+
+    ui_println!("{} git configuration ({})", "⚙️".blue().bold(), write_scope.label());
+    println!();
+
+    loop {
+        let snapshot = git_config.snapshot()?;
+        let mut options: Vec<String> = GIT_KEYS
+            .iter()
+            .map(|(key, _)| match snapshot.get_str(key) {
+                Ok(value) => format!("{} = {}", key, value),
+                Err(_) => format!("{} {}", key, "(not set)"),
+            })
+            .collect();
+        options.push("Done".to_string());
+
+        let selection = InteractivePrompt::new()
+            .with_message("Choose a setting")
+            .with_options(&options)
+            .select()?;
+
+        if selection == GIT_KEYS.len() {
+            return Ok(());
+        }
+
+        let (key, description) = GIT_KEYS[selection];
+        println!("{} {}", key.cyan().bold(), description.dimmed());
+
+        let input: String = InteractivePrompt::new()
+            .with_message("New value (empty to unset)")
+            .allow_empty()
+            .input()?;
+        let input = input.trim();
+
+        if input.is_empty() {
+            if git_config.remove(key).is_ok() {
+                ui_println!("{} Removed {}", "✅".green(), key.cyan());
+            }
+            continue;
+        }
+
+        match validate_git_value(key, input) {
+            Ok(()) => {
+                git_config.set_str(key, input)?;
+                ui_println!("{} Set {} = {}", "✅".green(), key.cyan(), input.bold());
+            }
+            Err(e) => ui_println!("{} {}", "❌".red(), e),
+        }
+    }
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Group keys by section, preserving a stable order
+fn group_by_section(keys: &'static [ConfigKey]) -> BTreeMap<&'static str, Vec<&'static ConfigKey>> {
+    let mut sections: BTreeMap<&str, Vec<&ConfigKey>> = BTreeMap::new();
+    for key in keys {
+        sections.entry(key.section()).or_default().push(key);
+    }
+    sections
+}
+
+/// Current and default value of a key, formatted for display
+fn current_and_default(config: &Config, key: &ConfigKey) -> Result<(String, String)> {
+    let unset = || "(unset)".to_string();
+    let current = config.get_value(key.name)?.unwrap_or_else(unset);
+    let default = Config::default_value(key.name)?.unwrap_or_else(unset);
+    Ok((current, default))
+}
+
+fn is_modified(config: &Config, key: &ConfigKey) -> bool {
+    current_and_default(config, key)
+        .map(|(current, default)| current != default)
+        .unwrap_or(false)
+}
+
+/// Validate well-known git settings before writing them
+fn validate_git_value(key: &str, value: &str) -> Result<()> {
+    let invalid = || RgitError::InvalidConfigValue {
+        key: key.to_string(),
+        value: value.to_string(),
+    };
+
+    if !key.contains('.') || key.starts_with('.') || key.ends_with('.') {
+        return Err(RgitError::UnknownConfigKey(key.to_string()).into());
+    }
+
+    match key.to_lowercase().as_str() {
+        "user.email" if !is_valid_email(value) => Err(invalid().into()),
+        "user.name" if value.trim().is_empty() => Err(invalid().into()),
+        "pull.rebase" if parse_bool(value).is_none()
+            && !["merges", "interactive"].contains(&value) => Err(invalid().into()),
+        "fetch.prune" | "commit.gpgsign" | "push.autosetupremote"
+            if parse_bool(value).is_none() => Err(invalid().into()),
+        "core.autocrlf" if parse_bool(value).is_none() && value != "input" => Err(invalid().into()),
+        _ => Ok(()),
+    }
+}
+
+/// Scope used for writes when none was given explicitly
+fn default_write_scope() -> GitScope {
+    if Repository::discover(".").is_ok() {
+        GitScope::Local
+    } else {
+        GitScope::Global
+    }
+}
+
+/// Open git configuration for reading or writing at the given scope
+fn open_git_config(scope: GitScope, for_write: bool) -> Result<git2::Config> {
+    let scope = if for_write && scope == GitScope::Auto { default_write_scope() } else { scope };
+
+    match scope {
+        GitScope::Auto => match Repository::discover(".") {
+            Ok(repo) => Ok(repo.config()?),
+            Err(_) => Ok(git2::Config::open_default()?),
+        },
+        GitScope::Local => {
+            let repo = Repository::discover(".").map_err(|_| RgitError::NotInRepository)?;
+            Ok(repo.config()?.open_level(ConfigLevel::Local)?)
+        }
+        GitScope::Global => Ok(git2::Config::open(&global_git_config_path()?)?),
+    }
+}
+
+/// Path of the user-wide git configuration file
+pub fn global_git_config_path() -> Result<PathBuf> {
+    if let Ok(path) = git2::Config::find_global() {
+        return Ok(path);
+    }
+    dirs::home_dir()
+        .map(|home| home.join(".gitconfig"))
+        .ok_or_else(|| RgitError::ConfigurationError("Cannot determine home directory".to_string()).into())
+}
+
+/// Short name of a git configuration level
+pub fn level_name(level: ConfigLevel) -> &'static str {
+    match level {
+        ConfigLevel::ProgramData => "programdata",
+        ConfigLevel::System => "system",
+        ConfigLevel::XDG => "xdg",
+        ConfigLevel::Global => "global",
+        ConfigLevel::Local => "local",
+        ConfigLevel::App => "app",
+        ConfigLevel::Highest => "highest",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_git_value() {
+        assert!(validate_git_value("user.email", "dev@example.com").is_ok());
+        assert!(validate_git_value("user.email", "not-an-email").is_err());
+        assert!(validate_git_value("pull.rebase", "merges").is_ok());
+        assert!(validate_git_value("pull.rebase", "sometimes").is_err());
+        assert!(validate_git_value("core.autocrlf", "input").is_ok());
+        assert!(validate_git_value("nodot", "value").is_err());
+        assert!(validate_git_value("alias.st", "status").is_ok());
+    }
+
+    #[test]
+    fn test_group_by_section() {
+        let sections = group_by_section(CONFIG_KEYS);
+        assert!(sections.contains_key("ui"));
+        assert!(sections.contains_key("advanced.safety"));
+        let total: usize = sections.values().map(|keys| keys.len()).sum();
+        assert_eq!(total, CONFIG_KEYS.len());
+    }
+
+    #[test]
+    fn test_config_target_from_args() {
+        let args = ConfigArgs { action: None, git: false, global: true, local: false };
+        assert_eq!(ConfigTarget::from_args(&args), ConfigTarget::Git(GitScope::Global));
+
+        let args = ConfigArgs { action: None, git: false, global: false, local: false };
+        assert_eq!(ConfigTarget::from_args(&args), ConfigTarget::Rgit);
+    }
+}
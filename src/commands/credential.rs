@@ -0,0 +1,24 @@
+use anyhow::Result;
+use colored::*;
+
+use crate::cli::{CredentialArgs, CredentialCommands};
+use crate::config::Config;
+use crate::credential;
+
+/// Execute the credential command
+pub async fn execute(args: &CredentialArgs, config: &Config) -> Result<()> {
+    match &args.action {
+        CredentialCommands::Clear => {
+            if credential::clear(config)? {
+                ui_println!("{} Cleared cached credentials", "✅".green().bold());
+            } else {
+                ui_println!("{} No credentials are cached", "ℹ️".blue());
+                if !config.credential.cache {
+                    ui_println!("{} Turn caching on with {}", "💡".blue(), "rgit config set credential.cache true".cyan());
+                }
+            }
+            Ok(())
+        }
+        CredentialCommands::Daemon { socket } => credential::serve(socket),
+    }
+}
@@ -0,0 +1,558 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Delta, Diff, DiffDelta, DiffOptions, Repository, Tree};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{DiffArgs, DiffContentArgs, RenameArgs};
+use crate::commands::show::print_patch;
+use crate::config::{parse_bool, Config};
+use crate::core::{resolve_object, resolve_revision, DiffOptionsBuilder, RenameDetection, Revision, RgitCore};
+use crate::error::RgitError;
+use crate::theme::{Element, Themed};
+use crate::tools::{select_tool, Tool, ToolFiles, ToolKind};
+use crate::utils::{FileChangeStats, FileStat};
+use crate::validation::validate_pathspec;
+
+/// Execute the diff command
+pub async fn execute(args: &DiffArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.word_diff {
+        return Err(RgitError::OperationNotSupported("--word-diff".to_string()).into());
+    }
+    let repo = &rgit.repo;
+
+    let (revisions, paths) = split_revisions(repo, &args.args, &args.paths);
+    let cwd = std::env::current_dir()?;
+    let paths = paths.iter()
+        .map(|path| validate_pathspec(&rgit.repo_path, &cwd, path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sides = DiffSides::resolve(repo, &revisions, args.staged)?;
+    let mut options = content_options(&args.content).paths(paths).build();
+    let mut diff = sides.diff(repo, &mut options)?;
+    rename_detection(&args.renames, config).apply(&mut diff)?;
+
+    if let Some(requested) = &args.tool {
+        let tool = select_tool(Some(repo), config, ToolKind::Diff, Some(requested))?;
+        return launch_difftool(repo, &diff, sides.comparison, &tool);
+    }
+
+    if diff.deltas().len() == 0 {
+        return Ok(());
+    }
+    if !(args.stat || args.shortstat || args.numstat || args.name_only || args.name_status) {
+        return print_patch(repo, &diff, config, args.content.function_context);
+    }
+
+    let stats = FileChangeStats::from_diff(&diff)?;
+    if args.stat {
+        print_stat(&stats, config.terminal_width());
+    } else if args.shortstat {
+        println!("{}", stats.format_shortstat());
+    }
+    for file in &stats.per_file {
+        if args.numstat {
+            match file.binary {
+                true => println!("-\t-\t{}", file.display_path()),
+                false => println!("{}\t{}\t{}", file.additions, file.deletions, file.display_path()),
+            }
+        } else if args.name_status {
+            match &file.old_path {
+                Some(old) => println!("{}\t{}\t{}", file.status_letter(), old, file.path),
+                None => println!("{}\t{}", file.status_letter(), file.path),
+            }
+        } else if args.name_only {
+            println!("{}", file.path);
+        }
+    }
+    Ok(())
+}
+
+/// Rename detection asked for on the command line, falling back to
+/// `diff.renames` and `diff.rename_threshold`
+pub fn rename_detection(args: &RenameArgs, config: &Config) -> RenameDetection {
+    if args.no_renames {
+        return RenameDetection::off();
+    }
+    let configured = &config.diff;
+    let copies = configured.renames.eq_ignore_ascii_case("copies") || args.find_copies.is_some();
+    let renames = copies
+        || args.find_renames.is_some()
+        || parse_bool(&configured.renames).unwrap_or(true);
+    let threshold = args.find_copies.flatten()
+        .or(args.find_renames.flatten())
+        .unwrap_or(configured.rename_threshold);
+    RenameDetection { renames, copies, threshold }
+}
+
+// =============================================================================
+// Content Options
+// =============================================================================
+
+/// Context that covers whole files, for `--function-context` to cut down
+const WHOLE_FILE_CONTEXT: u32 = i32::MAX as u32;
+
+/// Diff options for the whitespace and context flags
+pub fn content_options(args: &DiffContentArgs) -> DiffOptionsBuilder {
+    let builder = DiffOptionsBuilder::new()
+        .ignore_whitespace(args.ignore_all_space)
+        .ignore_blank_lines(args.ignore_blank_lines);
+    if args.function_context {
+        builder.context_lines(WHOLE_FILE_CONTEXT)
+    } else {
+        builder
+    }
+}
+
+/// Cut the lines of a whole-file patch down to hunks spanning the functions
+/// that contain changes, like `git diff --function-context`. As in git's
+/// default rule, a function starts at a line beginning with a letter, `_`
+/// or `$`. Lines are `(origin, content)` pairs; hunk headers are rebuilt.
+pub fn function_context_hunks(lines: &[(char, String)]) -> Vec<(char, String)> {
+    let body: Vec<&(char, String)> = lines.iter().filter(|(origin, _)| *origin != 'H').collect();
+    let is_change = |origin: char| matches!(origin, '+' | '-' | '>' | '<');
+    let is_function = |(origin, content): &(char, String)| {
+        matches!(origin, ' ' | '+' | '-') && content.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$')
+    };
+
+    let mut starts = vec![0];
+    starts.extend((1..body.len()).filter(|&i| is_function(body[i])));
+    starts.push(body.len());
+
+    // Functions with changes, merged when they are adjacent
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for bounds in starts.windows(2) {
+        let (start, end) = (bounds[0], bounds[1]);
+        if !body[start..end].iter().any(|(origin, _)| is_change(*origin)) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => ranges.push((start, end)),
+        }
+    }
+
+    // Lines on each side, for the hunk positions
+    let count = |lines: &[&(char, String)]| lines.iter().fold((0, 0), |(old, new), (origin, _)| match origin {
+        ' ' => (old + 1, new + 1),
+        '-' => (old + 1, new),
+        '+' => (old, new + 1),
+        _ => (old, new),
+    });
+    let range = |before: usize, lines: usize| match lines {
+        0 => format!("{},0", before),
+        1 => format!("{}", before + 1),
+        _ => format!("{},{}", before + 1, lines),
+    };
+
+    let mut hunks = Vec::new();
+    for (start, mut end) in ranges {
+        // Blank lines between functions aren't part of either
+        while end > start && body[end - 1].0 == ' ' && body[end - 1].1.trim().is_empty() {
+            end -= 1;
+        }
+        let (old_before, new_before) = count(&body[..start]);
+        let (old_lines, new_lines) = count(&body[start..end]);
+        hunks.push(('H', format!("@@ -{} +{} @@\n", range(old_before, old_lines), range(new_before, new_lines))));
+        hunks.extend(body[start..end].iter().map(|line| (*line).clone()));
+    }
+    hunks
+}
+
+// =============================================================================
+// Statistics
+// =============================================================================
+
+/// Widest the +/- graph of `--stat` gets, as in git
+const MAX_GRAPH_WIDTH: usize = 40;
+
+/// One row of `--stat` output, laid out to fit the terminal
+#[derive(Debug, PartialEq, Eq)]
+struct StatLine {
+    name: String,
+    count: String,
+    plus: usize,
+    minus: usize,
+}
+
+/// Lay out the `--stat` rows for `width` columns. Long names are shortened
+/// from the left and the graph is scaled down when changes don't fit.
+fn stat_lines(stats: &FileChangeStats, width: usize) -> Vec<StatLine> {
+    let names: Vec<String> = stats.per_file.iter().map(FileStat::display_path).collect();
+    let max_change = stats.per_file.iter().map(FileStat::total_changes).max().unwrap_or(0);
+    let count_width = max_change.to_string().len().max(3);
+
+    // " name | count graph"
+    let available = width.saturating_sub(count_width + 5);
+    let longest_name = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    let mut graph_width = max_change.min(MAX_GRAPH_WIDTH);
+    if longest_name + graph_width > available {
+        // Share the room, favouring names but keeping some graph
+        graph_width = graph_width.min((available * 3 / 8).max(available.saturating_sub(longest_name)));
+    }
+    let name_width = available.saturating_sub(graph_width).max(5);
+
+    let scale = |lines: usize| match (lines, max_change > graph_width) {
+        (0, _) => 0,
+        (lines, true) => 1 + lines * graph_width.saturating_sub(1) / max_change,
+        (lines, false) => lines,
+    };
+
+    stats.per_file.iter().zip(names)
+        .map(|(file, name)| {
+            let length = name.chars().count();
+            let name = if length > name_width {
+                let tail: String = name.chars().skip(length - (name_width - 3)).collect();
+                format!("...{}", tail)
+            } else {
+                name
+            };
+            if file.binary {
+                return StatLine { name, count: "Bin".to_string(), plus: 0, minus: 0 };
+            }
+            StatLine {
+                name,
+                count: file.total_changes().to_string(),
+                plus: scale(file.additions),
+                minus: scale(file.deletions),
+            }
+        })
+        .collect()
+}
+
+/// Print per-file change counts with +/- bars and a totals line
+fn print_stat(stats: &FileChangeStats, width: usize) {
+    let lines = stat_lines(stats, width);
+    let name_width = lines.iter().map(|l| l.name.chars().count()).max().unwrap_or(0);
+    let count_width = lines.iter().map(|l| l.count.len()).max().unwrap_or(0);
+
+    for line in &lines {
+        print!(" {:<name_width$} | {:>count_width$}", line.name, line.count);
+        if line.plus + line.minus > 0 {
+            print!(" {}{}", "+".repeat(line.plus).themed(Element::Added), "-".repeat(line.minus).themed(Element::Removed));
+        }
+        println!();
+    }
+    println!("{}", stats.format_shortstat());
+}
+
+/// Split the positional arguments into revisions and paths like git: leading
+/// arguments that name revisions are revisions and the rest are paths.
+/// Everything after `--` is a path.
+fn split_revisions(repo: &Repository, args: &[String], after_dashes: &[String]) -> (Vec<String>, Vec<String>) {
+    let revisions = if after_dashes.is_empty() {
+        args.iter().take_while(|arg| resolve_revision(repo, arg).is_ok()).count()
+    } else {
+        // Explicit paths: anything before `--` must be a revision
+        args.len()
+    };
+    let (revisions, paths) = args.split_at(revisions);
+
+    let mut paths = paths.to_vec();
+    paths.extend(after_dashes.iter().cloned());
+    (revisions.to_vec(), paths)
+}
+
+/// What is compared with what
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    /// Unstaged changes
+    IndexToWorkdir,
+    /// Staged changes, against HEAD or a commit
+    TreeToIndex,
+    /// All changes since a commit
+    TreeToWorkdir,
+    /// Changes between two commits
+    TreeToTree,
+}
+
+impl Comparison {
+    fn new(staged: bool, has_target: bool) -> Self {
+        match (staged, has_target) {
+            (true, _) => Comparison::TreeToIndex,
+            (false, true) => Comparison::TreeToWorkdir,
+            (false, false) => Comparison::IndexToWorkdir,
+        }
+    }
+
+    fn diff<'r>(&self, repo: &'r Repository, old: Option<&Tree<'r>>, new: Option<&Tree<'r>>, options: &mut DiffOptions) -> Result<Diff<'r>> {
+        Ok(match self {
+            Comparison::IndexToWorkdir => repo.diff_index_to_workdir(None, Some(options))?,
+            Comparison::TreeToIndex => {
+                let head = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+                repo.diff_tree_to_index(old.or(head.as_ref()), None, Some(options))?
+            }
+            Comparison::TreeToWorkdir => repo.diff_tree_to_workdir_with_index(old, Some(options))?,
+            Comparison::TreeToTree => repo.diff_tree_to_tree(old, new, Some(options))?,
+        })
+    }
+
+    fn new_side_in_workdir(&self) -> bool {
+        matches!(self, Comparison::IndexToWorkdir | Comparison::TreeToWorkdir)
+    }
+}
+
+/// The comparison and the trees on either side of it
+struct DiffSides<'r> {
+    comparison: Comparison,
+    old: Option<Tree<'r>>,
+    new: Option<Tree<'r>>,
+}
+
+impl<'r> DiffSides<'r> {
+    /// Resolve the revisions given on the command line: none, one commit,
+    /// two commits, or a `A..B`, `A...B` or `A^!` range
+    fn resolve(repo: &'r Repository, revisions: &[String], staged: bool) -> Result<Self> {
+        let between = |old: Option<Tree<'r>>, new: Tree<'r>| -> Result<Self> {
+            if staged {
+                return Err(RgitError::InvalidArgument(
+                    "--staged compares the index with one commit; drop it to compare two commits".to_string()
+                ).into());
+            }
+            Ok(DiffSides { comparison: Comparison::TreeToTree, old, new: Some(new) })
+        };
+
+        match revisions {
+            [] => Ok(DiffSides { comparison: Comparison::new(staged, false), old: None, new: None }),
+            [spec] => match resolve_revision(repo, spec)? {
+                Revision::Single(object) => {
+                    let old = object.peel_to_tree()
+                        .map_err(|_| RgitError::InvalidReference(format!("'{}' does not name a tree", spec)))?;
+                    Ok(DiffSides { comparison: Comparison::new(staged, true), old: Some(old), new: None })
+                }
+                Revision::Range { from, to, symmetric } => {
+                    // `A...B` shows what B did since it diverged from A
+                    let base = if symmetric {
+                        let base = repo.merge_base(from.id(), to.id()).map_err(|_| {
+                            RgitError::InvalidArgument(format!("'{}' has no merge base", spec))
+                        })?;
+                        repo.find_commit(base)?
+                    } else {
+                        from
+                    };
+                    between(Some(base.tree()?), to.tree()?)
+                }
+                Revision::Only(commit) => {
+                    let parent = match commit.parents().next() {
+                        Some(parent) => Some(parent.tree()?),
+                        None => None,
+                    };
+                    between(parent, commit.tree()?)
+                }
+            },
+            [old, new] => between(Some(resolve_tree(repo, old)?), resolve_tree(repo, new)?),
+            _ => Err(RgitError::InvalidArgument(
+                "at most two revisions can be compared".to_string()
+            ).into()),
+        }
+    }
+
+    fn diff(&self, repo: &'r Repository, options: &mut DiffOptions) -> Result<Diff<'r>> {
+        self.comparison.diff(repo, self.old.as_ref(), self.new.as_ref(), options)
+    }
+}
+
+/// Resolve a revision that must name a tree
+fn resolve_tree<'r>(repo: &'r Repository, spec: &str) -> Result<Tree<'r>> {
+    resolve_object(repo, spec)?
+        .peel_to_tree()
+        .map_err(|_| RgitError::InvalidReference(format!("'{}' does not name a tree", spec)).into())
+}
+
+fn delta_path<'a>(delta: &DiffDelta<'a>) -> Option<&'a Path> {
+    delta.new_file().path().or_else(|| delta.old_file().path())
+}
+
+// =============================================================================
+// Diff tool
+// =============================================================================
+
+/// Open each changed file in `tool`, one after another
+fn launch_difftool(repo: &Repository, diff: &Diff, comparison: Comparison, tool: &Tool) -> Result<()> {
+    let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+    let scratch = tempfile::tempdir()?;
+    let deltas: Vec<DiffDelta> = diff.deltas().collect();
+    if deltas.is_empty() {
+        ui_println!("{} No changes to show", "✅".green());
+        return Ok(());
+    }
+
+    for (i, delta) in deltas.iter().enumerate() {
+        let Some(path) = delta_path(delta) else { continue };
+        ui_println!("{} Viewing ({}/{}) {} in {}",
+                "🔍".blue(), i + 1, deltas.len(), path.display().to_string().yellow(), tool.name().cyan());
+
+        let local = side_file(repo, scratch.path(), path, "LOCAL", delta.old_file().id(), delta.status() == Delta::Added)?;
+        let in_workdir = workdir.join(path);
+        let remote = if comparison.new_side_in_workdir() && in_workdir.exists() {
+            in_workdir.clone()
+        } else {
+            side_file(repo, scratch.path(), path, "REMOTE", delta.new_file().id(), delta.status() == Delta::Deleted)?
+        };
+
+        let files = ToolFiles { local: &local, remote: &remote, base: None, merged: &in_workdir };
+        tool.launch(ToolKind::Diff, &files, workdir)?;
+    }
+    Ok(())
+}
+
+/// Write one side of a change to a scratch file; absent sides are empty
+fn side_file(repo: &Repository, scratch: &Path, path: &Path, label: &str, id: git2::Oid, absent: bool) -> Result<PathBuf> {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let file = scratch.join(format!("{}_{}", label, name));
+    let contents = if absent || id.is_zero() {
+        Vec::new()
+    } else {
+        repo.find_blob(id)?.content().to_vec()
+    };
+    fs::write(&file, contents)?;
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn changed_paths(repo: &TestRepo, revisions: &[&str], staged: bool) -> Vec<String> {
+        let revisions: Vec<String> = revisions.iter().map(|r| r.to_string()).collect();
+        let sides = DiffSides::resolve(repo.repo(), &revisions, staged).unwrap();
+        let diff = sides.diff(repo.repo(), &mut DiffOptions::new()).unwrap();
+        diff.deltas().map(|d| delta_path(&d).unwrap().display().to_string()).collect()
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "First");
+        repo.commit_file("b.txt", "b\n", "Second");
+        repo.write("a.txt", "staged\n").stage("a.txt");
+        repo.write("b.txt", "unstaged\n");
+
+        assert_eq!(changed_paths(&repo, &[], false), vec!["b.txt"]);
+        assert_eq!(changed_paths(&repo, &[], true), vec!["a.txt"]);
+        assert_eq!(changed_paths(&repo, &["HEAD~1"], false), vec!["a.txt", "b.txt"]);
+        assert_eq!(changed_paths(&repo, &["HEAD~1"], true), vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_rename_detection_settings() {
+        let mut config = Config::default();
+        let args = RenameArgs::default();
+        assert_eq!(rename_detection(&args, &config), RenameDetection { renames: true, copies: false, threshold: 50 });
+
+        config.diff.renames = "false".to_string();
+        config.diff.rename_threshold = 60;
+        assert!(!rename_detection(&args, &config).is_enabled());
+
+        // Flags override the configuration, and copies imply renames
+        let args = RenameArgs { find_copies: Some(Some(80)), ..Default::default() };
+        assert_eq!(rename_detection(&args, &config), RenameDetection { renames: true, copies: true, threshold: 80 });
+        let args = RenameArgs { find_renames: Some(None), ..Default::default() };
+        assert_eq!(rename_detection(&args, &config).threshold, 60);
+
+        config.diff.renames = "copies".to_string();
+        let args = RenameArgs { no_renames: true, ..Default::default() };
+        assert_eq!(rename_detection(&args, &config), RenameDetection::off());
+    }
+
+    #[test]
+    fn test_revision_ranges() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "First");
+        repo.branch("feature");
+        repo.commit_file("main.txt", "main\n", "On main");
+        repo.checkout("feature");
+        repo.commit_file("feature.txt", "feature\n", "On feature");
+
+        // Two dots compare the tips, three dots start from the merge base
+        assert_eq!(changed_paths(&repo, &["main..feature"], false), vec!["feature.txt", "main.txt"]);
+        assert_eq!(changed_paths(&repo, &["main", "feature"], false), vec!["feature.txt", "main.txt"]);
+        assert_eq!(changed_paths(&repo, &["main...feature"], false), vec!["feature.txt"]);
+        assert_eq!(changed_paths(&repo, &["feature^!"], false), vec!["feature.txt"]);
+        assert!(DiffSides::resolve(repo.repo(), &["main".to_string(), "feature".to_string()], true).is_err());
+    }
+
+    #[test]
+    fn test_function_context_hunks() {
+        let line = |origin: char, text: &str| (origin, format!("{}\n", text));
+        let lines = vec![
+            line('H', "@@ -1,9 +1,9 @@"),
+            line(' ', "fn alpha() {"),
+            line(' ', "}"),
+            line(' ', ""),
+            line(' ', "fn beta() {"),
+            line('-', "    old"),
+            line('+', "    new"),
+            line(' ', "}"),
+            line(' ', ""),
+            line(' ', "fn gamma() {"),
+            line(' ', "}"),
+        ];
+
+        let hunks = function_context_hunks(&lines);
+        let texts: Vec<&str> = hunks.iter().map(|(_, text)| text.trim_end()).collect();
+        assert_eq!(texts, ["@@ -4,3 +4,3 @@", "fn beta() {", "    old", "    new", "}"]);
+        assert_eq!(hunks[0].0, 'H');
+
+        // Nothing changed, nothing to show
+        assert!(function_context_hunks(&lines[..4]).is_empty());
+    }
+
+    #[test]
+    fn test_split_revisions_and_paths() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "First");
+
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(split_revisions(repo.repo(), &args(&["HEAD", "a.txt", "b.txt"]), &[]),
+                   (args(&["HEAD"]), args(&["a.txt", "b.txt"])));
+        assert_eq!(split_revisions(repo.repo(), &args(&["a.txt"]), &[]), (args(&[]), args(&["a.txt"])));
+        assert_eq!(split_revisions(repo.repo(), &args(&["HEAD"]), &args(&["HEAD"])),
+                   (args(&["HEAD"]), args(&["HEAD"])));
+    }
+
+    #[test]
+    fn test_stat_fits_terminal_width() {
+        let repo = TestRepo::new();
+        let long_name = format!("{}/file.txt", "nested".repeat(12));
+        repo.commit_file("small.txt", "a\nb\n", "First");
+        repo.write("small.txt", "a\nc\n");
+        repo.write(&long_name, &"line\n".repeat(200)).stage(&long_name);
+
+        let sides = DiffSides::resolve(repo.repo(), &["HEAD".to_string()], false).unwrap();
+        let diff = sides.diff(repo.repo(), &mut DiffOptions::new()).unwrap();
+        let stats = FileChangeStats::from_diff(&diff).unwrap();
+        assert_eq!((stats.files, stats.additions, stats.deletions), (2, 201, 1));
+        assert_eq!(stats.format_shortstat(), " 2 files changed, 201 insertions(+), 1 deletion(-)");
+
+        let lines = stat_lines(&stats, 80);
+        for line in &lines {
+            let graph = line.plus + line.minus;
+            assert!(1 + line.name.chars().count() + 3 + 3 + 1 + graph <= 80, "{:?}", line);
+        }
+        let big = lines.iter().find(|l| l.count == "200").unwrap();
+        assert!(big.name.starts_with("...") && big.name.ends_with("/file.txt"));
+        assert!(big.plus > 0 && big.plus <= MAX_GRAPH_WIDTH && big.minus == 0);
+
+        // Small changes are drawn one character per line
+        let small = lines.iter().find(|l| l.name == "small.txt").unwrap();
+        assert_eq!((small.plus, small.minus), (1, 1));
+    }
+
+    #[test]
+    fn test_difftool_gets_both_sides() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "old\n", "First");
+        repo.write("a.txt", "new\n");
+
+        let out = repo.path().join(".git").join("seen");
+        let tool = Tool::Custom {
+            name: "record".to_string(),
+            command: format!("cat \"$LOCAL\" \"$REMOTE\" > \"{}\"", out.display()),
+        };
+        let diff = Comparison::IndexToWorkdir.diff(repo.repo(), None, None, &mut DiffOptions::new()).unwrap();
+        launch_difftool(repo.repo(), &diff, Comparison::IndexToWorkdir, &tool).unwrap();
+        assert_eq!(fs::read_to_string(out).unwrap(), "old\nnew\n");
+    }
+}
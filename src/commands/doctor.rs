@@ -4,34 +4,102 @@ use git2::*;
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use serde::Serialize;
+use std::io::Write;
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
+use crate::commands::branch;
+use crate::commands::config::global_git_config_path;
+use crate::commands::gc;
+use crate::commands::owners;
+use crate::commands::whoami;
+use crate::checks::{CheckStatus, CustomChecks, CHECKS_FILE};
+use crate::codeowners::CodeOwners;
+use crate::cli::{DoctorArgs, DoctorFormat};
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::{InteractivePrompt, TableDisplay};
+use crate::logging;
+use crate::network;
+use crate::output::decoration;
 use crate::submodule::SubmoduleManager;
-use crate::utils::{humanize_size, is_valid_email};
+use crate::timings::{self, Phase};
+use crate::utils::{
+    calculate_repo_size, check_timestamp, format_time, humanize_size, is_valid_email, parse_git_url, shorten_oid,
+    system_clock_is_implausible, TimestampAnomaly,
+};
+
+/// Number of commits inspected for implausible dates
+const TIMESTAMP_SCAN_LIMIT: usize = 5000;
+
+/// History length above which a missing commit-graph is worth a warning
+const COMMIT_GRAPH_MIN_COMMITS: usize = 1000;
+
+/// How long a remote gets to list its refs
+const REMOTE_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Remotes slower than this to list refs get a warning
+const SLOW_REMOTE: Duration = Duration::from_secs(3);
+
+/// Log entries shown by `doctor --logs`
+const RECENT_LOG_ENTRIES: usize = 20;
 
 /// Execute the doctor command - comprehensive repository health check
-pub async fn execute(config: &Config) -> Result<()> {
-    println!("{} {} Repository Health Check", "🏥".blue(), "rgit".cyan().bold());
-    println!("{}", "=".repeat(50).dimmed());
-    println!();
+///
+/// Exits with 1 when warnings remain and 2 when errors do, so CI can gate on it.
+pub async fn execute(args: &DoctorArgs, config: &Config) -> Result<()> {
+    if args.logs {
+        return show_recent_logs(config);
+    }
+
+    // A machine-readable report on stdout must be the only thing there
+    let machine_output = args.format != DoctorFormat::Text && args.output.is_none();
+    if !machine_output {
+        ui_println!("{} {} Repository Health Check", "🏥".blue(), "rgit".cyan().bold());
+        println!("{}", "=".repeat(50).dimmed());
+        println!();
+    }
 
     let mut doctor = RepositoryDoctor::new(config);
-    let health_report = doctor.run_full_diagnosis().await?;
-    
-    display_health_report(&health_report, config)?;
+    doctor.quiet = machine_output;
+    let mut health_report = doctor.run_full_diagnosis().await?;
     
-    if health_report.has_issues() {
-        offer_auto_fix(&health_report, config).await?;
-    } else {
-        println!("\n{} Repository is in excellent health! 🎉", "✅".green().bold());
+    if !machine_output {
+        display_health_report(&health_report, config)?;
+        
+        if health_report.has_issues() {
+            if let Some(recheck) = offer_auto_fix(&doctor, &health_report, config).await? {
+                health_report.replace_with(recheck);
+            }
+        } else {
+            ui_println!("\n{} Repository is in excellent health! 🎉", "✅".green().bold());
+        }
+        
+        show_health_recommendations(&health_report, config)?;
+    }
+
+    let rendered = match args.format {
+        DoctorFormat::Text => render_text(&health_report),
+        DoctorFormat::Json => serde_json::to_string_pretty(&render_json(&health_report, doctor.rgit.as_ref()))?,
+        DoctorFormat::Sarif => serde_json::to_string_pretty(&render_sarif(&health_report))?,
+    };
+    match &args.output {
+        Some(path) => {
+            fs::write(path, rendered + "\n")?;
+            ui_println!("{} Report written to {}", "📄".blue(), path.display().to_string().cyan());
+        }
+        None if machine_output => println!("{}", rendered),
+        None => {}
+    }
+
+    let (errors, warnings) = (health_report.error_count(), health_report.warning_count());
+    if errors + warnings > 0 {
+        return Err(RgitError::HealthCheckFailed { errors, warnings }.into());
     }
-    
-    show_health_recommendations(&health_report, config)?;
-    
     Ok(())
 }
 
@@ -39,12 +107,27 @@ pub async fn execute(config: &Config) -> Result<()> {
 struct RepositoryDoctor<'a> {
     config: &'a Config,
     rgit: Option<RgitCore>,
+    /// Hide the per-check progress lines
+    quiet: bool,
 }
 
 impl<'a> RepositoryDoctor<'a> {
     fn new(config: &'a Config) -> Self {
         let rgit = RgitCore::new(false).ok();
-        Self { config, rgit }
+        Self { config, rgit, quiet: false }
+    }
+
+    fn progress(&self, label: &str) {
+        if !self.quiet {
+            ui_print!("{}", label);
+            let _ = std::io::stdout().flush();
+        }
+    }
+
+    fn progress_done(&self, mark: ColoredString) {
+        if !self.quiet {
+            ui_println!("{}", mark);
+        }
     }
 
     /// Run complete diagnosis
@@ -54,6 +137,7 @@ impl<'a> RepositoryDoctor<'a> {
         // Basic environment checks (always run)
         self.check_git_installation(&mut report).await?;
         self.check_git_configuration(&mut report).await?;
+        self.check_signing(&mut report).await?;
         
         // Repository-specific checks (only if in a git repo)
         if let Some(ref rgit) = self.rgit {
@@ -62,9 +146,12 @@ impl<'a> RepositoryDoctor<'a> {
             self.check_working_directory(rgit, &mut report).await?;
             self.check_remotes(rgit, &mut report).await?;
             self.check_branches(rgit, &mut report).await?;
+            self.check_commit_timestamps(rgit, &mut report).await?;
             self.check_submodules(rgit, &mut report).await?;
             self.check_hooks(rgit, &mut report).await?;
             self.check_performance(rgit, &mut report).await?;
+            self.check_code_owners(rgit, &mut report).await?;
+            self.check_custom(rgit, &mut report).await?;
         } else {
             report.add_info("Repository", "Not in a git repository", 
                           "Run 'rgit init' to create a new repository");
@@ -73,16 +160,31 @@ impl<'a> RepositoryDoctor<'a> {
         Ok(report)
     }
 
+    /// Run the checks that report the given categories again
+    async fn recheck(&self, categories: &[&str]) -> Result<HealthReport> {
+        let mut report = HealthReport::new();
+        let needs = |names: &[&str]| names.iter().any(|name| categories.contains(name));
+        if needs(&["User Identity", "Default Branch"]) {
+            self.check_git_configuration(&mut report).await?;
+        }
+        if let Some(ref rgit) = self.rgit {
+            if needs(&["Object Packing", "Commit Graph"]) {
+                self.check_performance(rgit, &mut report).await?;
+            }
+        }
+        Ok(report)
+    }
+
     /// Check Git installation and version
     async fn check_git_installation(&self, report: &mut HealthReport) -> Result<()> {
-        print!("Checking Git installation... ");
+        self.progress("Checking Git installation... ");
         
         match Command::new("git").arg("--version").output() {
             Ok(output) => {
                 if output.status.success() {
                     let version = String::from_utf8_lossy(&output.stdout);
                     let version_line = version.lines().next().unwrap_or("unknown");
-                    println!("{}", "✅".green());
+                    self.progress_done("✅".green());
                     
                     // Parse version and check if it's recent enough
                     if let Some(version_num) = extract_git_version(&version_line) {
@@ -101,14 +203,14 @@ impl<'a> RepositoryDoctor<'a> {
                                       "Version parsing failed");
                     }
                 } else {
-                    println!("{}", "❌".red());
+                    self.progress_done("❌".red());
                     report.add_error("Git Installation", 
                                    "Git command failed",
                                    "Reinstall Git or check PATH");
                 }
             }
             Err(_) => {
-                println!("{}", "❌".red());
+                self.progress_done("❌".red());
                 report.add_error("Git Installation", 
                                "Git not found in PATH",
                                "Install Git or add it to PATH");
@@ -120,7 +222,7 @@ impl<'a> RepositoryDoctor<'a> {
 
     /// Check Git configuration
     async fn check_git_configuration(&self, report: &mut HealthReport) -> Result<()> {
-        print!("Checking Git configuration... ");
+        self.progress("Checking Git configuration... ");
         
         // Check global configuration
         match Repository::open_from_env() {
@@ -128,7 +230,7 @@ impl<'a> RepositoryDoctor<'a> {
                 let config = repo.config()?;
                 self.check_user_identity(&config, report)?;
                 self.check_essential_config(&config, report)?;
-                println!("{}", "✅".green());
+                self.progress_done("✅".green());
             }
             Err(_) => {
                 // Try to check global config
@@ -136,10 +238,10 @@ impl<'a> RepositoryDoctor<'a> {
                     Ok(config) => {
                         self.check_user_identity(&config, report)?;
                         self.check_essential_config(&config, report)?;
-                        println!("{}", "✅".green());
+                        self.progress_done("✅".green());
                     }
                     Err(_) => {
-                        println!("{}", "❌".red());
+                        self.progress_done("❌".red());
                         report.add_error("Git Configuration", 
                                        "Cannot access Git configuration",
                                        "Check Git installation");
@@ -229,9 +331,88 @@ impl<'a> RepositoryDoctor<'a> {
         Ok(())
     }
 
+    /// Check that commits can be signed: the key exists, its agent answers
+    /// and a test signature verifies
+    async fn check_signing(&self, report: &mut HealthReport) -> Result<()> {
+        let git_config = match Repository::open_from_env() {
+            Ok(repo) => repo.config()?,
+            Err(_) => git2::Config::open_default()?,
+        };
+        let enabled = git_config.get_bool("commit.gpgsign").unwrap_or(false)
+            || self.config.git.sign_commits
+            || self.config.integrations.gpg.enabled;
+        let format = git_config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string());
+        let configured_key = git_config.get_string("user.signingkey").ok()
+            .or_else(|| self.config.integrations.gpg.key_id.clone());
+        if !enabled && configured_key.is_none() {
+            return Ok(());
+        }
+        // Without a signing key, gpg picks one by the committer email
+        let key = configured_key
+            .or_else(|| (format != "ssh").then(|| git_config.get_string("user.email").ok()).flatten());
+        let Some(key) = key else {
+            report.add_error("Signing",
+                           "Commit signing is enabled but no signing key is set",
+                           "Set user.signingkey to the key commits should be signed with");
+            return Ok(());
+        };
+
+        self.progress("Checking commit signing... ");
+        // Failures break every commit while signing is on, otherwise only `-S`
+        let add_problem = |report: &mut HealthReport, status: &str, suggestion: &str| {
+            if enabled {
+                report.add_error("Signing", status, suggestion);
+            } else {
+                report.add_warning("Signing", status, suggestion);
+            }
+        };
+        let program = whoami::signing_program(&git_config, &format, self.config);
+
+        match whoami::check_signing_agent(&format) {
+            Ok(status) => report.add_success("Signing Agent", &status, "Signing requests can reach the agent"),
+            // ssh-keygen can sign with a key file on its own
+            Err(reason) if format == "ssh" && whoami::literal_ssh_key(&key).is_none() => {
+                report.add_info("Signing Agent", &reason, "Keys with a passphrase will prompt on every commit");
+            }
+            Err(reason) => {
+                let suggestion = if format == "ssh" {
+                    "Start ssh-agent and add your key with ssh-add"
+                } else {
+                    "Start the agent with 'gpgconf --launch gpg-agent'"
+                };
+                add_problem(report, &reason, suggestion);
+            }
+        }
+
+        if let Err(reason) = whoami::check_signing_key(&format, &key, program.as_deref()) {
+            self.progress_done("❌".red());
+            add_problem(report,
+                        &format!("Signing key '{}' is not usable: {}", key, reason),
+                        "Check user.signingkey and gpg.format, or run 'rgit whoami'");
+            return Ok(());
+        }
+
+        match whoami::sign_roundtrip(&format, &key, program.as_deref()) {
+            Ok(()) => {
+                self.progress_done("✅".green());
+                report.add_success("Signing",
+                                 &format!("Test signature with {} key '{}' verified", format, key),
+                                 "Signed commits will work");
+            }
+            Err(reason) => {
+                self.progress_done("❌".red());
+                add_problem(report,
+                            &format!("Test signature with '{}' failed: {}", key, reason),
+                            "Sign a commit by hand with 'git commit -S' to see the full error");
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check repository structure and basic health
     async fn check_repository_structure(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking repository structure... ");
+        self.progress("Checking repository structure... ");
         
         let git_dir = rgit.git_dir();
         let work_dir = rgit.root_dir();
@@ -264,7 +445,7 @@ impl<'a> RepositoryDoctor<'a> {
         // Check essential Git files
         self.check_git_files(git_dir, report)?;
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 
@@ -295,7 +476,7 @@ impl<'a> RepositoryDoctor<'a> {
 
     /// Check repository integrity
     async fn check_repository_integrity(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking repository integrity... ");
+        self.progress("Checking repository integrity... ");
         
         // Check if repository is bare
         if rgit.repo.is_bare() {
@@ -347,13 +528,13 @@ impl<'a> RepositoryDoctor<'a> {
             }
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 
     /// Check working directory status
     async fn check_working_directory(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking working directory... ");
+        self.progress("Checking working directory... ");
         
         let status = rgit.status()?;
         
@@ -374,7 +555,7 @@ impl<'a> RepositoryDoctor<'a> {
         // Check disk space
         self.check_disk_space(rgit.root_dir(), report)?;
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 
@@ -423,7 +604,7 @@ impl<'a> RepositoryDoctor<'a> {
 
     /// Check remote repositories
     async fn check_remotes(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking remotes... ");
+        self.progress("Checking remotes... ");
         
         let remotes = rgit.list_remotes()?;
         
@@ -433,7 +614,18 @@ impl<'a> RepositoryDoctor<'a> {
                           "Add a remote to sync with other repositories");
         } else {
             for remote_info in &remotes {
-                self.check_remote_connectivity(&remote_info, report).await?;
+                self.check_remote_connectivity(rgit, remote_info, report).await?;
+            }
+
+            let uses_ssh = remotes.iter()
+                .flat_map(|remote| std::iter::once(&remote.url).chain(remote.push_url.as_ref()))
+                .any(|url| parse_git_url(url).is_some_and(|parsed| parsed.protocol == "ssh"));
+            if uses_ssh {
+                match whoami::check_ssh_agent() {
+                    Ok(status) => report.add_success("SSH Agent", &status, "SSH remotes can use agent keys"),
+                    Err(reason) => report.add_info("SSH Agent", &reason,
+                                                 "SSH remotes need a key file without a passphrase, or start ssh-agent"),
+                }
             }
             
             report.add_success("Remotes", 
@@ -441,31 +633,68 @@ impl<'a> RepositoryDoctor<'a> {
                              "Remote repositories available");
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 
-    /// Check connectivity to a remote
-    async fn check_remote_connectivity(&self, remote_info: &crate::core::RemoteInfo, report: &mut HealthReport) -> Result<()> {
-        // In a real implementation, this would test network connectivity
-        // For now, we'll just validate the URL format
-        
-        if remote_info.url.starts_with("http") || remote_info.url.contains("@") {
-            report.add_success(&format!("Remote: {}", remote_info.name), 
-                             &format!("URL: {}", remote_info.url),
-                             "Remote URL format is valid");
-        } else {
-            report.add_warning(&format!("Remote: {}", remote_info.name), 
-                             &format!("URL: {}", remote_info.url),
-                             "Remote URL format may be invalid");
+    /// Check connectivity to a remote by listing its refs, like `git ls-remote`
+    async fn check_remote_connectivity(&self, rgit: &RgitCore, remote_info: &crate::core::RemoteInfo, report: &mut HealthReport) -> Result<()> {
+        let category = format!("Remote: {}", remote_info.name);
+        if self.config.fetch.offline {
+            report.add_info(&category,
+                          &format!("URL: {}", remote_info.url),
+                          "Offline mode: connectivity not tested");
+            return Ok(());
         }
-        
+
+        // Probe the URLs rgit would really use, after any rewrites
+        let remote = rgit.repo.find_remote(&remote_info.name)?;
+        let fetch_url = network::remote_url(self.config, &remote, Direction::Fetch).unwrap_or_else(|| remote_info.url.clone());
+        let push_url = network::remote_url(self.config, &remote, Direction::Push);
+        let mut probes = vec![(fetch_url.clone(), Direction::Fetch)];
+        if let Some(push_url) = push_url.filter(|url| *url != fetch_url) {
+            probes.push((push_url, Direction::Push));
+        }
+
+        for (url, direction) in probes {
+            let label = if direction == Direction::Push { "push" } else { "fetch" };
+            let probe = probe_remote(&url, direction, REMOTE_PROBE_TIMEOUT, self.config);
+            let millis = probe.latency.as_millis();
+            match &probe.connectivity {
+                Connectivity::Reachable { refs } if probe.latency > SLOW_REMOTE => {
+                    report.add_warning(&category,
+                                     &format!("{} reachable but slow: {} ms ({} refs)", label, millis, refs),
+                                     &format!("Check your network or a closer mirror for {}", url));
+                }
+                Connectivity::Reachable { refs } => {
+                    report.add_success(&category,
+                                     &format!("{} reachable in {} ms ({} refs)", label, millis, refs),
+                                     "Remote is responding");
+                }
+                Connectivity::AuthFailed(message) => {
+                    report.add_error(&category,
+                                   &format!("{} authentication failed: {}", label, message),
+                                   "Check your SSH agent keys or credential helper for this host");
+                }
+                Connectivity::NetworkFailed(message) => {
+                    report.add_error(&category,
+                                   &format!("{} unreachable: {}", label, message),
+                                   &format!("Check the URL and your connection: {}", url));
+                }
+                Connectivity::TimedOut => {
+                    report.add_warning(&category,
+                                     &format!("{} gave no answer within {} s", label, REMOTE_PROBE_TIMEOUT.as_secs()),
+                                     "The host may be down, firewalled or behind a proxy");
+                }
+            }
+        }
+
         Ok(())
     }
 
     /// Check branch configuration
     async fn check_branches(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking branches... ");
+        self.progress("Checking branches... ");
         
         let branches = rgit.list_branches()?;
         
@@ -506,15 +735,70 @@ impl<'a> RepositoryDoctor<'a> {
             report.add_success("Branches", 
                              &format!("{} local branches", branches.len()),
                              "Branch structure is healthy");
+
+            let gone = branch::gone_upstreams(&rgit.repo)?;
+            if !gone.is_empty() {
+                let names: Vec<String> = gone.iter().map(|(name, upstream)| format!("{} ({})", name, upstream)).collect();
+                report.add_warning("Gone Upstreams",
+                                 &format!("Upstream deleted for {}", names.join(", ")),
+                                 "Delete merged branches, or run 'rgit branch --unset-upstream <branch>'");
+            }
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
+        Ok(())
+    }
+
+    /// Check for commit dates that break log ordering and time-based caches
+    async fn check_commit_timestamps(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
+        self.progress("Checking commit timestamps... ");
+
+        let now = chrono::Utc::now().timestamp();
+        if system_clock_is_implausible(now) {
+            report.add_error("System Clock",
+                           &format!("Clock reads {}", format_time(Time::new(now, 0))),
+                           "Fix the system time before committing");
+        }
+
+        let mut walk = rgit.repo.revwalk()?;
+        walk.push_glob("refs/heads")?;
+
+        let mut future = Vec::new();
+        let mut ancient = Vec::new();
+        for oid in walk.take(TIMESTAMP_SCAN_LIMIT).flatten() {
+            let commit = match rgit.repo.find_commit(oid) {
+                Ok(commit) => commit,
+                Err(_) => continue,
+            };
+            for seconds in [commit.author().when().seconds(), commit.time().seconds()] {
+                match check_timestamp(seconds, now) {
+                    Some(TimestampAnomaly::Future(_)) => { future.push(oid); break; }
+                    Some(TimestampAnomaly::Ancient) => { ancient.push(oid); break; }
+                    None => {}
+                }
+            }
+        }
+
+        if future.is_empty() && ancient.is_empty() {
+            report.add_success("Commit Dates", "No future or pre-1980 dates", "History orders correctly");
+        }
+        for (oids, label) in [(&future, "in the future"), (&ancient, "dated before 1980")] {
+            if let Some(first) = oids.first() {
+                report.add_warning("Commit Dates",
+                                 &format!("{} commit{} {} (e.g. {})",
+                                         oids.len(), if oids.len() == 1 { "" } else { "s" },
+                                         label, shorten_oid(first, 8)),
+                                 "Set git.normalize_dates to clamp dates when rewriting history");
+            }
+        }
+
+        self.progress_done(if future.is_empty() && ancient.is_empty() { "✅".green() } else { "⚠️".yellow() });
         Ok(())
     }
 
     /// Check submodules
     async fn check_submodules(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking submodules... ");
+        self.progress("Checking submodules... ");
         
         let submodule_manager = SubmoduleManager::new(rgit, self.config);
         let health = submodule_manager.check_health()?;
@@ -534,13 +818,13 @@ impl<'a> RepositoryDoctor<'a> {
                              "Use 'rgit submodule status' for details");
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 
     /// Check Git hooks
     async fn check_hooks(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking hooks... ");
+        self.progress("Checking hooks... ");
         
         let hooks_dir = rgit.git_dir().join("hooks");
         
@@ -548,7 +832,7 @@ impl<'a> RepositoryDoctor<'a> {
             report.add_info("Hooks", 
                           "No hooks directory",
                           "No Git hooks configured");
-            println!("{}", "✅".green());
+            self.progress_done("✅".green());
             return Ok(());
         }
         
@@ -570,13 +854,73 @@ impl<'a> RepositoryDoctor<'a> {
                           "Consider setting up Git hooks");
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
+        Ok(())
+    }
+
+    /// Validate CODEOWNERS, when the repository has one
+    async fn check_code_owners(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
+        let Some(codeowners) = CodeOwners::load(rgit.root_dir())? else {
+            return Ok(());
+        };
+        self.progress("Checking code owners... ");
+
+        for problem in &codeowners.problems {
+            report.add_error("Code Owners",
+                           &format!("{} line {}: {}", codeowners.path, problem.line, problem.message),
+                           "GitHub skips this line; run 'rgit owners check' after fixing it");
+        }
+        let files = owners::tracked_files(&rgit.repo)?;
+        let unmatched = codeowners.unmatched_rules(&files);
+        for rule in &unmatched {
+            report.add_warning("Code Owners",
+                             &format!("{} line {}: '{}' matches no tracked files", codeowners.path, rule.line, rule.pattern),
+                             "Update the pattern to the files' new location or remove it");
+        }
+
+        if codeowners.problems.is_empty() && unmatched.is_empty() {
+            report.add_success("Code Owners",
+                             &format!("{} rules in {}", codeowners.rules.len(), codeowners.path),
+                             "CODEOWNERS is valid");
+            self.progress_done("✅".green());
+        } else {
+            self.progress_done("⚠️".yellow());
+        }
+        Ok(())
+    }
+
+    /// Run the repository's own checks from `.rgit/checks.toml`
+    async fn check_custom(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
+        let checks = match CustomChecks::load(rgit.root_dir()) {
+            Ok(Some(checks)) => checks,
+            Ok(None) => return Ok(()),
+            Err(e) => {
+                report.add_error("Custom Checks",
+                               &format!("{:#}", e),
+                               &format!("Fix {}", CHECKS_FILE));
+                return Ok(());
+            }
+        };
+        self.progress("Checking custom rules... ");
+
+        let mut all_passed = true;
+        for outcome in checks.evaluate(&rgit.repo)? {
+            let category = format!("Custom: {}", outcome.name);
+            match outcome.status {
+                CheckStatus::Pass => report.add_success(&category, &outcome.detail, "Repository rule satisfied"),
+                CheckStatus::Warn => report.add_warning(&category, &outcome.detail, &outcome.hint),
+                CheckStatus::Fail => report.add_error(&category, &outcome.detail, &outcome.hint),
+            }
+            all_passed &= outcome.status == CheckStatus::Pass;
+        }
+
+        self.progress_done(if all_passed { "✅".green() } else { "⚠️".yellow() });
         Ok(())
     }
 
     /// Check repository performance metrics
     async fn check_performance(&self, rgit: &RgitCore, report: &mut HealthReport) -> Result<()> {
-        print!("Checking performance... ");
+        self.progress("Checking performance... ");
         
         // Check repository size
         let repo_size = calculate_repo_size(rgit.git_dir())?;
@@ -591,30 +935,32 @@ impl<'a> RepositoryDoctor<'a> {
                              "Repository size is reasonable");
         }
         
-        // Check for packed objects
-        let objects_dir = rgit.git_dir().join("objects");
-        let pack_dir = objects_dir.join("pack");
-        
-        if pack_dir.exists() {
-            let pack_count = fs::read_dir(&pack_dir)?
-                .filter_map(|entry| entry.ok())
-                .filter(|entry| {
-                    entry.file_name().to_string_lossy().ends_with(".pack")
-                })
-                .count();
-            
-            if pack_count > 10 {
-                report.add_warning("Object Packing", 
-                                 &format!("{} pack files", pack_count),
-                                 "Consider running 'git gc' to optimize");
-            } else {
-                report.add_success("Object Packing", 
-                                 &format!("{} pack files", pack_count),
-                                 "Object database is optimized");
+        // Check how objects are stored, with the same limits as `rgit gc --auto`
+        let stats = gc::ObjectStats::collect(rgit.git_dir())?;
+        let status = format!("{} loose objects, {} pack files", stats.loose_objects, stats.packs);
+        if let Some(reason) = gc::auto_reason(&stats, &self.config.gc) {
+            report.add_warning("Object Packing",
+                             &status,
+                             &format!("Run 'rgit gc --auto' to pack them ({})", reason));
+        } else {
+            report.add_success("Object Packing",
+                             &status,
+                             "Object database is optimized");
+        }
+
+        if gc::has_commit_graph(rgit.git_dir()) {
+            report.add_success("Commit Graph", "Commit-graph present", "History walks use precomputed generations");
+        } else {
+            let mut revwalk = rgit.repo.revwalk()?;
+            let commits = if revwalk.push_head().is_ok() { revwalk.take(COMMIT_GRAPH_MIN_COMMITS + 1).count() } else { 0 };
+            if commits > COMMIT_GRAPH_MIN_COMMITS {
+                report.add_warning("Commit Graph",
+                                 &format!("No commit-graph for {}+ commits", COMMIT_GRAPH_MIN_COMMITS),
+                                 "Run 'rgit gc' to speed up log and branch comparisons");
             }
         }
         
-        println!("{}", "✅".green());
+        self.progress_done("✅".green());
         Ok(())
     }
 }
@@ -671,6 +1017,13 @@ impl HealthReport {
         });
     }
     
+    /// Swap in re-run checks for every category they cover
+    fn replace_with(&mut self, recheck: HealthReport) {
+        let categories: Vec<String> = recheck.checks.iter().map(|c| c.category.clone()).collect();
+        self.checks.retain(|c| !categories.contains(&c.category));
+        self.checks.extend(recheck.checks);
+    }
+    
     fn has_issues(&self) -> bool {
         self.checks.iter().any(|c| matches!(c.level, HealthLevel::Error | HealthLevel::Warning))
     }
@@ -684,7 +1037,7 @@ impl HealthReport {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 struct HealthCheck {
     category: String,
     status: String,
@@ -692,7 +1045,8 @@ struct HealthCheck {
     suggestion: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
 enum HealthLevel {
     Success,
     Info,
@@ -731,18 +1085,18 @@ fn display_health_report(report: &HealthReport, config: &Config) -> Result<()> {
     let error_count = report.error_count();
     let warning_count = report.warning_count();
     
-    println!("{} Health Summary:", "📊".blue().bold());
-    println!("  {} {} total checks", "🔍".blue(), total_checks);
+    ui_println!("{} Health Summary:", "📊".blue().bold());
+    ui_println!("  {} {} total checks", "🔍".blue(), total_checks);
     
     if error_count > 0 {
-        println!("  {} {} errors", "❌".red(), error_count.to_string().red());
+        ui_println!("  {} {} errors", "❌".red(), error_count.to_string().red());
     }
     if warning_count > 0 {
-        println!("  {} {} warnings", "⚠️".yellow(), warning_count.to_string().yellow());
+        ui_println!("  {} {} warnings", "⚠️".yellow(), warning_count.to_string().yellow());
     }
     
     let success_count = total_checks - error_count - warning_count;
-    println!("  {} {} passed", "✅".green(), success_count.to_string().green());
+    ui_println!("  {} {} passed", "✅".green(), success_count.to_string().green());
     
     println!();
     
@@ -758,17 +1112,17 @@ fn display_health_report(report: &HealthReport, config: &Config) -> Result<()> {
 
 /// Display detailed health check results
 fn display_detailed_results(report: &HealthReport) -> Result<()> {
-    println!("{} Detailed Results:", "📋".blue().bold());
+    ui_println!("{} Detailed Results:", "📋".blue().bold());
     println!();
     
     for check in &report.checks {
-        println!("{} {} {}", 
+        ui_println!("{} {} {}", 
                 check.level.icon(),
                 check.category.bold(),
                 check.status);
         
         if !matches!(check.level, HealthLevel::Success) {
-            println!("    {} {}", "💡".blue(), check.suggestion.dimmed());
+            ui_println!("    {} {}", "💡".blue(), check.suggestion.dimmed());
         }
     }
     
@@ -778,6 +1132,7 @@ fn display_detailed_results(report: &HealthReport) -> Result<()> {
 /// Display summary results table
 fn display_summary_results(report: &HealthReport) -> Result<()> {
     let mut table = TableDisplay::new()
+        .with_wrap(true)
         .with_headers(vec![
             "Status".to_string(),
             "Category".to_string(),
@@ -786,7 +1141,7 @@ fn display_summary_results(report: &HealthReport) -> Result<()> {
     
     for check in &report.checks {
         table.add_row(vec![
-            format!("{}", check.level.icon()),
+            decoration(check.level.icon()).into_owned(),
             check.category.clone(),
             check.status.clone(),
         ]);
@@ -797,9 +1152,10 @@ fn display_summary_results(report: &HealthReport) -> Result<()> {
 }
 
 /// Offer automatic fixes for detected issues
-async fn offer_auto_fix(report: &HealthReport, config: &Config) -> Result<()> {
+/// Offer the fixes rgit can make, returning the re-run checks if any were made
+async fn offer_auto_fix(doctor: &RepositoryDoctor<'_>, report: &HealthReport, config: &Config) -> Result<Option<HealthReport>> {
     if !config.is_interactive() {
-        return Ok(());
+        return Ok(None);
     }
     
     let fixable_issues: Vec<&HealthCheck> = report.checks.iter()
@@ -807,82 +1163,371 @@ async fn offer_auto_fix(report: &HealthReport, config: &Config) -> Result<()> {
         .collect();
     
     if fixable_issues.is_empty() {
-        return Ok(());
+        return Ok(None);
     }
     
-    println!("\n{} Auto-fixable Issues Found:", "🔧".blue().bold());
+    ui_println!("\n{} Auto-fixable Issues Found:", "🔧".blue().bold());
     for issue in &fixable_issues {
-        println!("  {} {}: {}", issue.level.icon(), issue.category, issue.suggestion);
+        ui_println!("  {} {}: {}", issue.level.icon(), issue.category, issue.suggestion);
     }
     
     if InteractivePrompt::new()
         .with_message("Would you like rgit to attempt automatic fixes?")
         .confirm()? {
         
-        perform_auto_fixes(&fixable_issues).await?;
+        return Ok(Some(perform_auto_fixes(doctor, &fixable_issues, config).await?));
     }
     
-    Ok(())
+    Ok(None)
 }
 
 /// Check if an issue can be automatically fixed
 fn is_auto_fixable(check: &HealthCheck) -> bool {
     // Define which issues can be automatically fixed
+    !matches!(check.level, HealthLevel::Success) &&
     matches!(check.category.as_str(), 
-        "User Identity" | "Default Branch" | "Object Packing")
+        "User Identity" | "Default Branch" | "Object Packing" | "Commit Graph")
 }
 
-/// Perform automatic fixes
-async fn perform_auto_fixes(issues: &[&HealthCheck]) -> Result<()> {
-    println!("\n{} Performing automatic fixes...", "🔧".blue());
+/// Perform automatic fixes, then re-run the affected checks
+async fn perform_auto_fixes(doctor: &RepositoryDoctor<'_>, issues: &[&HealthCheck], config: &Config) -> Result<HealthReport> {
+    ui_println!("\n{} Performing automatic fixes...", "🔧".blue());
     
     for issue in issues {
-        match issue.category.as_str() {
+        let fixed = match issue.category.as_str() {
             "User Identity" => {
-                println!("  {} Setting up user identity...", "👤".blue());
-                // In real implementation, guide user through identity setup
-                println!("    {} Would guide through user.name and user.email setup", "💡".green());
+                ui_println!("  {} Setting up user identity...", "👤".blue());
+                prompt_identity()
             }
             "Default Branch" => {
-                println!("  {} Setting default branch to 'main'...", "🌿".blue());
-                // In real implementation: git config --global init.defaultBranch main
-                println!("    {} Would set init.defaultBranch = main", "💡".green());
+                let branch = &config.git.default_branch;
+                ui_println!("  {} Setting default branch to '{}'...", "🌿".blue(), branch);
+                open_global_config().and_then(|mut global| set_default_branch(&mut global, branch))
             }
             "Object Packing" => {
-                println!("  {} Optimizing object database...", "📦".blue());
-                // In real implementation: run git gc
-                println!("    {} Would run git gc to optimize repository", "💡".green());
+                ui_println!("  {} Optimizing object database...", "📦".blue());
+                match &doctor.rgit {
+                    Some(rgit) => gc::auto(rgit, config).map(|_| ()),
+                    None => Ok(()),
+                }
+            }
+            "Commit Graph" => {
+                ui_println!("  {} Writing commit-graph...", "📈".blue());
+                match &doctor.rgit {
+                    Some(rgit) => gc::write_commit_graph(rgit),
+                    None => Ok(()),
+                }
             }
-            _ => {}
+            _ => Ok(()),
+        };
+        // One failed fix shouldn't stop the others
+        if let Err(e) = fixed {
+            ui_println!("    {} {}", "❌".red(), e);
+        }
+    }
+    
+    ui_println!("\n{} Re-checking...", "🔍".blue());
+    let categories: Vec<&str> = issues.iter().map(|i| i.category.as_str()).collect();
+    let recheck = doctor.recheck(&categories).await?;
+    let mut remaining = 0;
+    for check in recheck.checks.iter().filter(|c| categories.contains(&c.category.as_str())) {
+        if matches!(check.level, HealthLevel::Success) {
+            ui_println!("  {} {}: {}", "✅".green(), check.category, check.status);
+        } else {
+            remaining += 1;
+            ui_println!("  {} {}: {} — {}", check.level.icon(), check.category, check.status, check.suggestion);
         }
     }
     
-    println!("  {} Automatic fixes completed!", "✅".green());
+    if remaining == 0 {
+        ui_println!("  {} Automatic fixes completed!", "✅".green());
+    } else {
+        ui_println!("  {} {} issue(s) still need attention", "⚠️".yellow(), remaining);
+    }
+    Ok(recheck)
+}
+
+/// Ask for a name and email and save them globally
+fn prompt_identity() -> Result<()> {
+    let mut global = open_global_config()?;
+    let name: String = InteractivePrompt::new()
+        .with_message("Your name")
+        .with_initial_text(global.get_string("user.name").unwrap_or_default())
+        .input()?;
+    let mut email: String = InteractivePrompt::new()
+        .with_message("Your email")
+        .with_initial_text(global.get_string("user.email").unwrap_or_default())
+        .input()?;
+    while !is_valid_email(&email) {
+        ui_println!("    {} '{}' is not a valid email address", "⚠️".yellow(), email);
+        email = InteractivePrompt::new().with_message("Your email").input()?;
+    }
+    set_identity(&mut global, &name, &email)
+}
+
+fn open_global_config() -> Result<git2::Config> {
+    Ok(git2::Config::open(&global_git_config_path()?)?)
+}
+
+/// Set user.name and user.email
+fn set_identity(config: &mut git2::Config, name: &str, email: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(RgitError::InvalidArgument("name cannot be empty".to_string()).into());
+    }
+    if !is_valid_email(email.trim()) {
+        return Err(RgitError::InvalidArgument(format!("'{}' is not a valid email address", email)).into());
+    }
+    config.set_str("user.name", name)?;
+    config.set_str("user.email", email.trim())?;
+    Ok(())
+}
+
+/// Set init.defaultBranch for new repositories
+fn set_default_branch(config: &mut git2::Config, branch: &str) -> Result<()> {
+    if !crate::utils::is_valid_ref_name(&format!("refs/heads/{}", branch)) {
+        return Err(RgitError::InvalidBranchName(branch.to_string()).into());
+    }
+    config.set_str("init.defaultBranch", branch)?;
+    Ok(())
+}
+
+// =============================================================================
+// Report Export
+// =============================================================================
+
+/// Plain-text report for files, one check per line
+/// Print recent warnings and errors from the log files, for bug reports
+fn show_recent_logs(config: &Config) -> Result<()> {
+    let Some(path) = logging::log_path(config) else {
+        ui_println!("{} Logging to a file is off", "ℹ️".blue());
+        println!("   Turn it on with: {}", "rgit config set advanced.logging.to_file true".cyan());
+        return Ok(());
+    };
+
+    let files = logging::log_files(&path);
+    if files.is_empty() {
+        ui_println!("{} No logs yet at {}", "ℹ️".blue(), path.display().to_string().cyan());
+        return Ok(());
+    }
+
+    let problems = logging::recent_problems(&path, RECENT_LOG_ENTRIES);
+    if problems.is_empty() {
+        ui_println!("{} No warnings or errors in {}", "✅".green(), path.display().to_string().cyan());
+    } else {
+        ui_println!("{} Recent problems (rgit {}):", "📜".blue(), env!("CARGO_PKG_VERSION"));
+        println!();
+        for entry in &problems {
+            let level = match entry.level.as_str() {
+                "ERROR" => entry.level.red().bold(),
+                _ => entry.level.yellow().bold(),
+            };
+            println!("{} {} {}", entry.timestamp.dimmed(), level, entry.message);
+            for (name, value) in &entry.fields {
+                println!("    {}: {}", name.dimmed(), value);
+            }
+        }
+    }
+
+    println!();
+    ui_println!("{} When filing a bug report, attach:", "💡".blue());
+    for file in &files {
+        println!("   {}", file.display());
+    }
     Ok(())
 }
 
+fn render_text(report: &HealthReport) -> String {
+    report.checks.iter()
+        .map(|check| match check.level {
+            HealthLevel::Success => format!("[{}] {}: {}", level_name(&check.level), check.category, check.status),
+            _ => format!("[{}] {}: {} ({})", level_name(&check.level), check.category, check.status, check.suggestion),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn level_name(level: &HealthLevel) -> &'static str {
+    match level {
+        HealthLevel::Success => "success",
+        HealthLevel::Info => "info",
+        HealthLevel::Warning => "warning",
+        HealthLevel::Error => "error",
+    }
+}
+
+fn render_json(report: &HealthReport, rgit: Option<&RgitCore>) -> serde_json::Value {
+    let errors = report.error_count();
+    let warnings = report.warning_count();
+    serde_json::json!({
+        "tool": "rgit",
+        "version": env!("CARGO_PKG_VERSION"),
+        "repository": rgit.map(|rgit| rgit.root_dir().display().to_string()),
+        "summary": {
+            "checks": report.checks.len(),
+            "errors": errors,
+            "warnings": warnings,
+            "passed": report.checks.len() - errors - warnings,
+        },
+        "checks": report.checks,
+    })
+}
+
+/// SARIF 2.1.0 log with one rule per check category and a result per issue
+fn render_sarif(report: &HealthReport) -> serde_json::Value {
+    let mut rules: Vec<serde_json::Value> = Vec::new();
+    let mut rule_ids: Vec<String> = Vec::new();
+    let mut results = Vec::new();
+    for check in report.checks.iter().filter(|c| !matches!(c.level, HealthLevel::Success)) {
+        let rule_id = format!("doctor/{}", slugify(&check.category));
+        if !rule_ids.contains(&rule_id) {
+            rules.push(serde_json::json!({
+                "id": rule_id,
+                "name": check.category,
+                "shortDescription": { "text": format!("{} health check", check.category) },
+            }));
+            rule_ids.push(rule_id.clone());
+        }
+        let level = match check.level {
+            HealthLevel::Error => "error",
+            HealthLevel::Warning => "warning",
+            _ => "note",
+        };
+        results.push(serde_json::json!({
+            "ruleId": rule_id,
+            "level": level,
+            "message": { "text": format!("{}. {}", check.status, check.suggestion) },
+        }));
+    }
+
+    serde_json::json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rgit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    })
+}
+
+/// `Remote: origin` -> `remote-origin`
+fn slugify(text: &str) -> String {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// =============================================================================
+// Remote Probes
+// =============================================================================
+
+/// Whether a remote answered and, if not, why
+#[derive(Debug, Clone, PartialEq)]
+pub enum Connectivity {
+    Reachable { refs: usize },
+    AuthFailed(String),
+    NetworkFailed(String),
+    TimedOut,
+}
+
+/// The result of contacting one remote URL
+#[derive(Debug, Clone)]
+pub struct RemoteProbe {
+    pub connectivity: Connectivity,
+    pub latency: Duration,
+}
+
+/// Connect to `url` and list its refs, giving up after `timeout`.
+///
+/// The probe runs on its own thread with a detached remote, so a transport
+/// stuck in connect or an SSH handshake is abandoned rather than waited on.
+pub fn probe_remote(url: &str, direction: Direction, timeout: Duration, config: &Config) -> RemoteProbe {
+    let (sender, receiver) = mpsc::channel();
+    let url = url.to_string();
+    // Nobody answers a prompt from the probe's thread before it times out
+    let mut config = config.clone();
+    config.ui.interactive = false;
+    let started = Instant::now();
+    std::thread::spawn(move || {
+        let _ = sender.send(list_remote_refs(&url, direction, &config));
+    });
+
+    let connectivity = match receiver.recv_timeout(timeout) {
+        Ok(connectivity) => connectivity,
+        Err(_) => Connectivity::TimedOut,
+    };
+    RemoteProbe { connectivity, latency: started.elapsed() }
+}
+
+fn list_remote_refs(url: &str, direction: Direction, config: &Config) -> Connectivity {
+    let asked_for_credentials = Arc::new(AtomicBool::new(false));
+    let asked = Arc::clone(&asked_for_credentials);
+    let mut credentials = network::credentials(config);
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username_from_url, allowed_types| {
+        // libgit2 keeps asking while credentials are rejected; one try is enough
+        if asked.swap(true, Ordering::SeqCst) {
+            return Err(git2::Error::new(ErrorCode::Auth, ErrorClass::Callback, "credentials were rejected"));
+        }
+        credentials(url, username_from_url, allowed_types)
+    });
+
+    let result = network::detached(config, url, direction).and_then(|mut remote| {
+        let connection = timings::time(Phase::Network, || remote.connect_auth(direction, Some(callbacks), None))?;
+        let refs = connection.list()?.len();
+        Ok(refs)
+    });
+    match result {
+        Ok(refs) => Connectivity::Reachable { refs },
+        Err(e) => classify_remote_error(&e, asked_for_credentials.load(Ordering::SeqCst)),
+    }
+}
+
+/// Tell authentication failures from network ones
+fn classify_remote_error(error: &git2::Error, asked_for_credentials: bool) -> Connectivity {
+    let message = error.message().to_string();
+    let lower = message.to_lowercase();
+    let auth_message = ["authentication", "permission denied", "401", "403", "credentials"]
+        .iter()
+        .any(|hint| lower.contains(hint));
+    if error.code() == ErrorCode::Auth || auth_message || (asked_for_credentials && error.class() == ErrorClass::Callback) {
+        Connectivity::AuthFailed(message)
+    } else {
+        Connectivity::NetworkFailed(message)
+    }
+}
+
 /// Show health recommendations
 fn show_health_recommendations(report: &HealthReport, config: &Config) -> Result<()> {
     if !config.ui.interactive {
         return Ok(());
     }
     
-    println!("\n{} Recommendations:", "💡".blue().bold());
+    ui_println!("\n{} Recommendations:", "💡".blue().bold());
     
     // General recommendations based on findings
     if report.error_count() > 0 {
-        println!("  • Address errors immediately to prevent data loss");
+        ui_println!("  • Address errors immediately to prevent data loss");
     }
     
     if report.warning_count() > 0 {
-        println!("  • Review warnings to improve repository health");
+        ui_println!("  • Review warnings to improve repository health");
     }
     
     // Specific recommendations
-    println!("  • Run 'rgit doctor' regularly to monitor repository health");
-    println!("  • Use 'rgit status' to check for uncommitted changes");
-    println!("  • Keep Git updated to the latest version");
-    println!("  • Set up proper backup strategies for important repositories");
+    ui_println!("  • Run 'rgit doctor' regularly to monitor repository health");
+    ui_println!("  • Use 'rgit status' to check for uncommitted changes");
+    ui_println!("  • Keep Git updated to the latest version");
+    ui_println!("  • Set up proper backup strategies for important repositories");
     
     println!();
     Ok(())
@@ -925,25 +1570,6 @@ fn find_large_files(dir: &Path, size_threshold: u64) -> Result<Vec<(PathBuf, u64
     Ok(large_files)
 }
 
-/// Calculate total repository size
-fn calculate_repo_size(git_dir: &Path) -> Result<u64> {
-    fn dir_size(dir: &Path) -> Result<u64> {
-        let mut size = 0;
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                size += entry.metadata()?.len();
-            } else if path.is_dir() {
-                size += dir_size(&path)?;
-            }
-        }
-        Ok(size)
-    }
-    
-    dir_size(git_dir)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -955,6 +1581,72 @@ mod tests {
         assert_eq!(extract_git_version("invalid version"), None);
     }
 
+    #[test]
+    fn test_probe_remote_reaches_local_remote() {
+        let repo = crate::test_support::TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        let remote = repo.add_remote("origin");
+        let url = repo.repo().find_remote("origin").unwrap().url().unwrap().to_string();
+
+        let probe = probe_remote(&url, Direction::Fetch, REMOTE_PROBE_TIMEOUT, &Config::default());
+        assert_eq!(probe.connectivity, Connectivity::Reachable { refs: 2 });
+
+        drop(remote);
+        let probe = probe_remote(&url, Direction::Fetch, REMOTE_PROBE_TIMEOUT, &Config::default());
+        assert!(matches!(probe.connectivity, Connectivity::NetworkFailed(_)));
+    }
+
+    #[test]
+    fn test_identity_and_default_branch_fixes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = git2::Config::open(&dir.path().join("gitconfig")).unwrap();
+
+        assert!(set_identity(&mut config, "Ada", "not-an-email").is_err());
+        set_identity(&mut config, " Ada ", "ada@example.com").unwrap();
+        assert_eq!(config.get_string("user.name").unwrap(), "Ada");
+        assert_eq!(config.get_string("user.email").unwrap(), "ada@example.com");
+
+        assert!(set_default_branch(&mut config, "bad..name").is_err());
+        set_default_branch(&mut config, "trunk").unwrap();
+        let config = config.snapshot().unwrap();
+        let mut report = HealthReport::new();
+        RepositoryDoctor::new(&Config::default()).check_essential_config(&config, &mut report).unwrap();
+        let branch = report.checks.iter().find(|c| c.category == "Default Branch").unwrap();
+        assert!(matches!(branch.level, HealthLevel::Success));
+    }
+
+    #[test]
+    fn test_report_exports() {
+        let mut report = HealthReport::new();
+        report.add_success("Hooks", "2 hooks configured", "Git hooks are available");
+        report.add_warning("Remote: origin", "fetch reachable but slow", "Check your network");
+        report.add_error("User Identity", "No user identity configured", "Set user.name and user.email");
+
+        let json = render_json(&report, None);
+        assert_eq!(json["summary"]["errors"], 1);
+        assert_eq!(json["summary"]["passed"], 1);
+        assert_eq!(json["checks"][1]["level"], "warning");
+
+        let sarif = render_sarif(&report);
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["ruleId"], "doctor/remote-origin");
+        assert_eq!(results[1]["level"], "error");
+        assert_eq!(sarif["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap().len(), 2);
+
+        assert!(render_text(&report).contains("[error] User Identity: No user identity configured"));
+    }
+
+    #[test]
+    fn test_classify_remote_error() {
+        let auth = git2::Error::new(ErrorCode::Auth, ErrorClass::Http, "authentication required but no callback set");
+        assert!(matches!(classify_remote_error(&auth, false), Connectivity::AuthFailed(_)));
+        let denied = git2::Error::new(ErrorCode::GenericError, ErrorClass::Ssh, "Permission denied (publickey)");
+        assert!(matches!(classify_remote_error(&denied, true), Connectivity::AuthFailed(_)));
+        let dns = git2::Error::new(ErrorCode::GenericError, ErrorClass::Net, "failed to resolve address for example.invalid");
+        assert!(matches!(classify_remote_error(&dns, false), Connectivity::NetworkFailed(_)));
+    }
+
     #[test]
     fn test_health_report() {
         let mut report = HealthReport::new();
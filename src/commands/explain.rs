@@ -0,0 +1,46 @@
+use anyhow::Result;
+use colored::*;
+
+use crate::cli::ExplainArgs;
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::error_catalog::{self, ErrorExplanation, CATALOG};
+use crate::t;
+
+/// Execute the explain command: the catalog entry for one error code, or
+/// the list of codes
+pub async fn execute(args: &ExplainArgs, _config: &Config) -> Result<()> {
+    let Some(code) = &args.code else {
+        ui_println!("{} {}", "📖".blue(), t!("explain-codes-heading", rgit = "rgit".cyan().bold()));
+        println!();
+        for entry in CATALOG {
+            println!("  {}  {}", entry.code.yellow(), entry.title);
+        }
+        println!();
+        println!("{}", t!("explain-list-hint", command = "rgit explain <code>".cyan()));
+        return Ok(());
+    };
+
+    let entry = error_catalog::lookup(code)
+        .ok_or_else(|| RgitError::InvalidArgument(t!("explain-unknown-code", code = code)))?;
+    show_explanation(entry);
+    Ok(())
+}
+
+fn show_explanation(entry: &ErrorExplanation) {
+    ui_println!("{} {}: {}", "📖".blue(), entry.code.yellow().bold(), entry.title.bold());
+    println!();
+    println!("{}", entry.explanation);
+
+    println!();
+    println!("{}", t!("explain-causes").cyan().bold());
+    for cause in entry.causes {
+        ui_println!("  • {}", cause);
+    }
+
+    println!();
+    println!("{}", t!("explain-fixes").green().bold());
+    for fix in entry.fixes {
+        ui_println!("  • {}", fix);
+    }
+}
@@ -0,0 +1,431 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Delta, FileMode, ObjectType, Oid, Repository, Signature, Sort, Tag};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+use crate::cli::ExportArgs;
+use crate::commands::import::{format_marks, read_marks};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::ProgressDisplay;
+
+/// Commits exported between refreshes of the progress display
+const PROGRESS_INTERVAL: usize = 100;
+
+/// Execute the export command
+pub async fn execute(args: &ExportArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let refs = refs_to_export(repo, &args.refs)?;
+    if refs.is_empty() {
+        return Err(RgitError::InvalidArgument("no branches or tags to export".to_string()).into());
+    }
+
+    let out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(io::stdout().lock()),
+    };
+    let mut exporter = Exporter::new(repo, BufWriter::new(out));
+    if let Some(path) = &args.import_marks {
+        exporter.load_marks(read_marks(path)?);
+    }
+
+    let bar = config.ui.progress.then(|| ProgressDisplay::new("Exporting").create_progress_bar());
+    let result = exporter.run(&refs, |stats| {
+        if let Some(bar) = &bar {
+            bar.set_message(format!("Exporting: {}", stats));
+        }
+    });
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+    result?;
+
+    if let Some(path) = &args.export_marks {
+        fs::write(path, format_marks(&exporter.marks_by_number()))?;
+    }
+
+    // The stream may be on standard output, so everything else goes to standard error
+    let stats = &exporter.stats;
+    ui_eprintln!("{} Exported {}", "✅".green().bold(), stats);
+    for name in &stats.skipped {
+        ui_eprintln!("  {} skipped {}, which doesn't point at a commit", "⚠️".yellow(), name);
+    }
+    if stats.signed_commits > 0 {
+        ui_eprintln!("  {} {} commit signature{} can't be carried in the stream and {} left out",
+                 "⚠️".yellow(), stats.signed_commits,
+                 if stats.signed_commits == 1 { "" } else { "s" },
+                 if stats.signed_commits == 1 { "was" } else { "were" });
+    }
+    Ok(())
+}
+
+/// Local branches and tags by full name, with what they point at
+fn refs_to_export(repo: &Repository, only: &[String]) -> Result<Vec<(String, Oid)>> {
+    let mut refs = Vec::new();
+    if only.is_empty() {
+        for reference in repo.references()? {
+            let reference = reference?;
+            let (Some(name), Some(target)) = (reference.name(), reference.target()) else { continue };
+            if name.starts_with("refs/heads/") || name.starts_with("refs/tags/") {
+                refs.push((name.to_string(), target));
+            }
+        }
+        refs.sort();
+        return Ok(refs);
+    }
+
+    for name in only {
+        let reference = repo.resolve_reference_from_short_name(name)
+            .map_err(|_| RgitError::InvalidReference(name.clone()))?
+            .resolve()?;
+        let full = reference.name().unwrap_or(name).to_string();
+        let target = reference.target().ok_or_else(|| RgitError::InvalidReference(name.clone()))?;
+        if !refs.iter().any(|(existing, _)| *existing == full) {
+            refs.push((full, target));
+        }
+    }
+    Ok(refs)
+}
+
+/// What an export wrote
+#[derive(Debug, Default)]
+pub struct ExportStats {
+    pub blobs: usize,
+    pub commits: usize,
+    pub tags: usize,
+    /// Refs that don't lead to a commit
+    pub skipped: Vec<String>,
+    /// Commits whose signatures were dropped
+    pub signed_commits: usize,
+}
+
+impl fmt::Display for ExportStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        write!(f, "{} commit{}, {} blob{} and {} tag{}",
+               self.commits, plural(self.commits),
+               self.blobs, plural(self.blobs),
+               self.tags, plural(self.tags))
+    }
+}
+
+/// Writes refs and the history behind them as a fast-import stream
+pub struct Exporter<'r, W: Write> {
+    repo: &'r Repository,
+    out: W,
+    /// Objects already in the stream, or in an earlier one
+    marks: HashMap<Oid, u64>,
+    /// The commits among `marks`, which later refs' walks stop at
+    commits: Vec<Oid>,
+    next_mark: u64,
+    pub stats: ExportStats,
+}
+
+impl<'r, W: Write> Exporter<'r, W> {
+    pub fn new(repo: &'r Repository, out: W) -> Self {
+        Self { repo, out, marks: HashMap::new(), commits: Vec::new(), next_mark: 1, stats: ExportStats::default() }
+    }
+
+    /// Treat the objects of an earlier export as already written
+    pub fn load_marks(&mut self, marks: HashMap<u64, Oid>) {
+        let odb = self.repo.odb().ok();
+        for (mark, oid) in marks {
+            self.next_mark = self.next_mark.max(mark + 1);
+            self.marks.insert(oid, mark);
+            let kind = odb.as_ref().and_then(|odb| odb.read_header(oid).ok()).map(|(_, kind)| kind);
+            if kind == Some(ObjectType::Commit) {
+                self.commits.push(oid);
+            }
+        }
+    }
+
+    pub fn marks_by_number(&self) -> HashMap<u64, Oid> {
+        self.marks.iter().map(|(oid, mark)| (*mark, *oid)).collect()
+    }
+
+    /// Write the stream for `refs`, calling `report` every so often
+    pub fn run(&mut self, refs: &[(String, Oid)], mut report: impl FnMut(&ExportStats)) -> Result<()> {
+        writeln!(self.out, "feature done")?;
+        for (name, target) in refs {
+            self.export_ref(name, *target, &mut report)?;
+        }
+        writeln!(self.out, "done")?;
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn export_ref(&mut self, name: &str, target: Oid, report: &mut impl FnMut(&ExportStats)) -> Result<()> {
+        let object = self.repo.find_object(target, None)?;
+        let tag = object.as_tag().filter(|tag| tag.target_type() == Some(ObjectType::Commit)).cloned();
+        let commit = match (&tag, object.kind()) {
+            (Some(tag), _) => tag.target()?.peel_to_commit()?,
+            (None, Some(ObjectType::Commit)) => object.peel_to_commit()?,
+            _ => {
+                self.stats.skipped.push(name.to_string());
+                return Ok(());
+            }
+        };
+
+        let mut walk = self.repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        walk.push(commit.id())?;
+        for oid in &self.commits {
+            walk.hide(*oid)?;
+        }
+
+        let mut wrote_commits = false;
+        for oid in walk {
+            self.commit(name, &self.repo.find_commit(oid?)?)?;
+            wrote_commits = true;
+            if self.stats.commits.is_multiple_of(PROGRESS_INTERVAL) {
+                report(&self.stats);
+            }
+        }
+
+        match (tag, name.strip_prefix("refs/tags/")) {
+            (Some(tag), Some(short)) => self.tag(short, &tag)?,
+            _ if !wrote_commits => {
+                writeln!(self.out, "reset {}", name)?;
+                writeln!(self.out, "from {}\n", self.object_ref(commit.id()))?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, name: &str, commit: &Commit) -> Result<()> {
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = self.repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+
+        // Deletions go first so a directory can be replaced by a file of the same name
+        let mut deleted = Vec::new();
+        let mut changed = Vec::new();
+        for delta in diff.deltas() {
+            if delta.status() == Delta::Deleted {
+                deleted.extend(delta.old_file().path_bytes().map(<[u8]>::to_vec));
+            } else if let Some(path) = delta.new_file().path_bytes() {
+                changed.push((path.to_vec(), delta.new_file().mode(), delta.new_file().id()));
+            }
+        }
+        for (_, mode, id) in &changed {
+            if *mode != FileMode::Commit && !self.marks.contains_key(id) {
+                self.blob(*id)?;
+            }
+        }
+
+        if commit.parent_count() == 0 {
+            writeln!(self.out, "reset {}", name)?;
+        }
+        let mark = self.mark(commit.id());
+        self.commits.push(commit.id());
+        writeln!(self.out, "commit {}", name)?;
+        writeln!(self.out, "mark :{}", mark)?;
+        self.identity("author", &commit.author())?;
+        self.identity("committer", &commit.committer())?;
+        if let Some(encoding) = commit.message_encoding() {
+            writeln!(self.out, "encoding {}", encoding)?;
+        }
+        self.data(commit.message_raw_bytes())?;
+        for (i, parent) in commit.parent_ids().enumerate() {
+            writeln!(self.out, "{} {}", if i == 0 { "from" } else { "merge" }, self.object_ref(parent))?;
+        }
+        for path in &deleted {
+            self.out.write_all(b"D ")?;
+            self.out.write_all(&quote_path(path))?;
+            writeln!(self.out)?;
+        }
+        for (path, mode, id) in &changed {
+            let dataref = if *mode == FileMode::Commit { id.to_string() } else { self.object_ref(*id) };
+            write!(self.out, "M {:06o} {} ", i32::from(*mode), dataref)?;
+            self.out.write_all(&quote_path(path))?;
+            writeln!(self.out)?;
+        }
+        writeln!(self.out)?;
+
+        if commit.header_field_bytes("gpgsig").is_ok() {
+            self.stats.signed_commits += 1;
+        }
+        self.stats.commits += 1;
+        Ok(())
+    }
+
+    fn blob(&mut self, id: Oid) -> Result<()> {
+        let blob = self.repo.find_blob(id)?;
+        let mark = self.mark(id);
+        writeln!(self.out, "blob\nmark :{}", mark)?;
+        self.data(blob.content())?;
+        self.stats.blobs += 1;
+        Ok(())
+    }
+
+    fn tag(&mut self, name: &str, tag: &Tag) -> Result<()> {
+        writeln!(self.out, "tag {}", name)?;
+        writeln!(self.out, "from {}", self.object_ref(tag.target_id()))?;
+        if let Some(tagger) = tag.tagger() {
+            self.identity("tagger", &tagger)?;
+        }
+        self.data(tag.message_bytes().unwrap_or_default())?;
+        self.stats.tags += 1;
+        Ok(())
+    }
+
+    fn mark(&mut self, id: Oid) -> u64 {
+        let mark = self.next_mark;
+        self.next_mark += 1;
+        self.marks.insert(id, mark);
+        mark
+    }
+
+    /// A mark for objects in the stream, otherwise the id
+    fn object_ref(&self, id: Oid) -> String {
+        match self.marks.get(&id) {
+            Some(mark) => format!(":{}", mark),
+            None => id.to_string(),
+        }
+    }
+
+    fn identity(&mut self, kind: &str, signature: &Signature) -> io::Result<()> {
+        let when = signature.when();
+        let offset = when.offset_minutes().abs();
+        write!(self.out, "{} ", kind)?;
+        self.out.write_all(signature.name_bytes())?;
+        self.out.write_all(b" <")?;
+        self.out.write_all(signature.email_bytes())?;
+        writeln!(self.out, "> {} {}{:02}{:02}", when.seconds(), when.sign(), offset / 60, offset % 60)
+    }
+
+    fn data(&mut self, data: &[u8]) -> io::Result<()> {
+        writeln!(self.out, "data {}", data.len())?;
+        self.out.write_all(data)?;
+        writeln!(self.out)
+    }
+}
+
+/// `path` with C-style quoting when it can't be written as is
+fn quote_path(path: &[u8]) -> Vec<u8> {
+    if !path.iter().any(|byte| matches!(byte, b'"' | b'\\' | b'\n')) {
+        return path.to_vec();
+    }
+    let mut quoted = vec![b'"'];
+    for &byte in path {
+        match byte {
+            b'"' => quoted.extend_from_slice(b"\\\""),
+            b'\\' => quoted.extend_from_slice(b"\\\\"),
+            b'\n' => quoted.extend_from_slice(b"\\n"),
+            _ => quoted.push(byte),
+        }
+    }
+    quoted.push(b'"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::ImportArgs;
+    use crate::commands::import::fast_import;
+    use crate::test_support::TestRepo;
+
+    fn export(repo: &Repository, refs: &[(String, Oid)], marks: HashMap<u64, Oid>) -> (Vec<u8>, HashMap<u64, Oid>) {
+        let mut stream = Vec::new();
+        let mut exporter = Exporter::new(repo, &mut stream);
+        exporter.load_marks(marks);
+        exporter.run(refs, |_| {}).unwrap();
+        let marks = exporter.marks_by_number();
+        (stream, marks)
+    }
+
+    fn import(repo: &TestRepo, stream: &[u8], args: &ImportArgs) {
+        let stats = fast_import(repo.repo(), stream, args, |_| {}).unwrap();
+        assert!(stats.rejected.is_empty());
+    }
+
+    #[test]
+    fn test_export_round_trips_through_import() {
+        let source = TestRepo::new();
+        source.commit_file("a.txt", "one\n", "Initial commit");
+        source.commit_file("dir/quote\"d.txt", "quoted\n", "Add an awkward name");
+        source.branch("topic");
+        source.checkout("topic");
+        source.commit_file("topic.txt", "topic\n", "Topic work");
+        source.checkout(crate::test_support::DEFAULT_BRANCH);
+        source.remove("a.txt");
+        source.stage_all().commit("Remove a");
+        let head = source.repo().head().unwrap().peel_to_commit().unwrap();
+        let topic = source.repo().revparse_single("topic").unwrap().peel_to_commit().unwrap();
+        let signature = Signature::now("Tagger", "tagger@example.com").unwrap();
+        let merge = source.repo().commit(Some("HEAD"), &signature, &signature, "Merge topic",
+                                         &topic.tree().unwrap(), &[&head, &topic]).unwrap();
+        source.repo().tag("v1.0", &source.repo().find_object(merge, None).unwrap(), &signature, "Release\n", false).unwrap();
+        source.tag("light");
+
+        let refs = refs_to_export(source.repo(), &[]).unwrap();
+        let (stream, _) = export(source.repo(), &refs, HashMap::new());
+        let target = TestRepo::new();
+        import(&target, &stream, &ImportArgs::default());
+
+        for (name, oid) in &refs {
+            assert_eq!(target.repo().refname_to_id(name).unwrap(), *oid, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_keeps_non_utf8_messages() {
+        let source = TestRepo::new();
+        let parent = source.commit_file("a.txt", "one\n", "Initial commit");
+        let tree = source.repo().find_commit(parent).unwrap().tree_id();
+        let mut raw = format!(
+            "tree {}\nparent {}\nauthor Ann <ann@example.com> 1700000000 +0000\ncommitter Ann <ann@example.com> 1700000000 +0000\nencoding ISO-8859-1\n\n",
+            tree, parent
+        ).into_bytes();
+        raw.extend_from_slice(b"Caf\xe9 au lait\n");
+        let latin1 = source.repo().odb().unwrap().write(ObjectType::Commit, &raw).unwrap();
+        source.repo().reference("refs/heads/latin1", latin1, true, "test").unwrap();
+
+        let refs = refs_to_export(source.repo(), &[]).unwrap();
+        let (stream, _) = export(source.repo(), &refs, HashMap::new());
+        let target = TestRepo::new();
+        import(&target, &stream, &ImportArgs::default());
+
+        assert_eq!(target.repo().refname_to_id("refs/heads/latin1").unwrap(), latin1);
+        let imported = target.repo().find_commit(latin1).unwrap();
+        assert_eq!(imported.message_encoding(), Some("ISO-8859-1"));
+        assert_eq!(imported.message_raw_bytes(), b"Caf\xe9 au lait\n");
+    }
+
+    #[test]
+    fn test_incremental_export_skips_marked_objects() {
+        let source = TestRepo::new();
+        source.commit_file("a.txt", "one\n", "First");
+        let refs = refs_to_export(source.repo(), &[]).unwrap();
+        let (first, marks) = export(source.repo(), &refs, HashMap::new());
+
+        let second = source.commit_file("b.txt", "two\n", "Second");
+        let refs = refs_to_export(source.repo(), &[]).unwrap();
+        let (stream, _) = export(source.repo(), &refs, marks);
+        let text = String::from_utf8_lossy(&stream);
+        assert_eq!(text.matches("\ncommit ").count(), 1);
+        assert!(text.contains("from :2\n"));
+
+        // Marks saved by the first import let the second one resolve `:2`
+        let target = TestRepo::new();
+        let marks_dir = tempfile::TempDir::new().unwrap();
+        let marks = marks_dir.path().join("marks");
+        import(&target, &first, &ImportArgs { export_marks: Some(marks.clone()), ..Default::default() });
+        import(&target, &stream, &ImportArgs { import_marks: Some(marks), ..Default::default() });
+        assert_eq!(target.repo().refname_to_id(&refs[0].0).unwrap(), second);
+    }
+
+    #[test]
+    fn test_quote_path() {
+        assert_eq!(quote_path(b"plain name.txt"), b"plain name.txt");
+        assert_eq!(quote_path(b"a\"b\\c"), b"\"a\\\"b\\\\c\"");
+    }
+}
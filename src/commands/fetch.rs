@@ -1,21 +1,29 @@
 use anyhow::Result;
 use colored::*;
-use git2::{FetchOptions, RemoteCallbacks, Repository};
-use std::io::{self, Write};
+use git2::{AutotagOption, Direction, FetchOptions, Oid, RemoteCallbacks, Repository};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use tracing::debug;
 
 use crate::cli::FetchArgs;
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
+use crate::interactive::TransferProgress;
+use crate::network;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
 
 /// Execute the fetch command
 pub async fn execute(args: &FetchArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("{} Fetching from remote repositories...", "📥".blue().bold());
+    ui_println!("{} Fetching from remote repositories...", "📥".blue().bold());
     
     let repo = &rgit.repo;
     
     if args.all {
-        fetch_all_remotes(repo, config).await?;
+        fetch_all_remotes(repo, args, config).await?;
     } else {
         let remote_name = args.remote.as_deref().unwrap_or("origin");
         fetch_single_remote(repo, remote_name, args, config).await?;
@@ -24,35 +32,37 @@ pub async fn execute(args: &FetchArgs, rgit: &RgitCore, config: &Config) -> Resu
     // Show fetch results
     show_fetch_summary(repo, args, config)?;
     
-    println!("{} Fetch completed successfully", "✅".green().bold());
+    if args.dry_run {
+        ui_println!("{} Dry run: no refs were changed", "ℹ️".blue());
+    } else {
+        ui_println!("{} Fetch completed successfully", "✅".green().bold());
+    }
     
     Ok(())
 }
 
 /// Fetch from all configured remotes
-async fn fetch_all_remotes(repo: &Repository, config: &Config) -> Result<()> {
+async fn fetch_all_remotes(repo: &Repository, args: &FetchArgs, config: &Config) -> Result<()> {
     let remotes = repo.remotes()?;
     
     if remotes.is_empty() {
-        println!("{} No remotes configured", "ℹ️".blue());
+        ui_println!("{} No remotes configured", "ℹ️".blue());
         return Ok(());
     }
     
-    println!("{} Fetching from {} remote{}", 
+    ui_println!("{} Fetching from {} remote{}", 
             "🌐".blue(), 
             remotes.len(), 
             if remotes.len() == 1 { "" } else { "s" });
     
-    for remote_name in remotes.iter() {
-        if let Some(name) = remote_name {
-            println!("\n{} Fetching from {}", "📡".blue(), name.cyan());
-            
-            match fetch_remote_by_name(repo, name, config).await {
-                Ok(_) => println!("  {} {}", "✅".green(), "Success".green()),
-                Err(e) => {
-                    println!("  {} Failed: {}", "❌".red(), e);
-                    // Continue with other remotes even if one fails
-                }
+    for name in remotes.iter().flatten() {
+        ui_println!("\n{} Fetching from {}", "📡".blue(), name.cyan());
+        
+        match fetch_remote_with_options(repo, name, args, config).await {
+            Ok(report) => show_fetch_report(name, &report, args.dry_run),
+            Err(e) => {
+                ui_println!("  {} Failed: {}", "❌".red(), e);
+                // Continue with other remotes even if one fails
             }
         }
     }
@@ -67,185 +77,272 @@ async fn fetch_single_remote(
     args: &FetchArgs,
     config: &Config,
 ) -> Result<()> {
-    println!("{} Fetching from {}", "📡".blue(), remote_name.cyan());
+    ui_println!("{} Fetching from {}", "📡".blue(), remote_name.cyan());
     
     // Check if remote exists
     if repo.find_remote(remote_name).is_err() {
         return Err(RgitError::RemoteNotFound(remote_name.to_string()).into());
     }
     
-    // Show remote URL
+    // Show remote URL, as rewritten
     if let Ok(remote) = repo.find_remote(remote_name) {
-        if let Some(url) = remote.url() {
-            println!("{} URL: {}", "🌐".blue(), url.dimmed());
+        if let Some(url) = network::remote_url(config, &remote, Direction::Fetch) {
+            ui_println!("{} URL: {}", "🌐".blue(), url.dimmed());
         }
     }
     
     // Perform fetch with specific options
-    fetch_remote_with_options(repo, remote_name, args, config).await?;
+    let report = fetch_remote_with_options(repo, remote_name, args, config).await?;
+    show_fetch_report(remote_name, &report, args.dry_run);
     
     Ok(())
 }
 
-/// Fetch from a remote by name
-async fn fetch_remote_by_name(repo: &Repository, remote_name: &str, config: &Config) -> Result<()> {
-    let args = FetchArgs {
-        remote: Some(remote_name.to_string()),
-        all: false,
-        prune: false,
-        tags: false,
-        depth: None,
-        unshallow: false,
-        dry_run: false,
-    };
-    
-    fetch_remote_with_options(repo, remote_name, &args, config).await
-}
-
-/// Fetch from remote with specific options
-async fn fetch_remote_with_options(
+/// Fetch from remote with specific options, returning how local refs changed
+pub(crate) async fn fetch_remote_with_options(
     repo: &Repository,
     remote_name: &str,
     args: &FetchArgs,
     config: &Config,
-) -> Result<()> {
-    let mut remote = repo.find_remote(remote_name)
+) -> Result<FetchReport> {
+    let remote = repo.find_remote(remote_name)
         .map_err(|_| RgitError::RemoteNotFound(remote_name.to_string()))?;
     
-    // Set up callbacks
-    let mut callbacks = RemoteCallbacks::new();
+    // Determine what to fetch; the refspecs are explicit, so they work
+    // through a rewritten remote too
+    let refspecs = fetch_refspecs(&remote, args)?;
+    let mut remote = network::rewritten(repo, remote, config, Direction::Fetch)?;
+    let prune = args.prune || args.prune_tags;
+    let before = snapshot_refs(repo)?;
     
-    // Progress callback
-    if config.ui.interactive {
-        callbacks.transfer_progress(|stats| {
-            print!(
-                "\r{} Received {}/{} objects, {}/{} bytes",
-                "📦".blue(),
-                stats.received_objects(),
-                stats.total_objects(),
-                stats.received_bytes(),
-                stats.total_deltas()
-            );
-            io::stdout().flush().unwrap();
-            true
-        });
+    if args.dry_run {
+        let advertised = remote_ls(&mut remote)?;
+        return Ok(planned_changes(repo, &refspecs, &advertised, args, &before));
     }
     
-    // Authentication callback
-    callbacks.credentials(|_url, username_from_url, _allowed_types| {
-        git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-    });
-    
-    // Update tips callback
-    callbacks.update_tips(|refname, old_oid, new_oid| {
-        if config.ui.interactive {
-            let old_short = old_oid.to_string()[..8].to_string();
-            let new_short = new_oid.to_string()[..8].to_string();
-            println!("\r{} {}: {} -> {}", 
-                    "🔄".yellow(), 
-                    refname.cyan(),
-                    if old_oid.is_zero() { "new".green() } else { old_short.yellow() },
-                    new_short.green());
+    // Perform the fetch, with fresh callbacks and progress for each attempt
+    let refspec_slices: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
+    network::with_retries(config, || {
+        let progress = TransferProgress::new(config);
+        let mut callbacks = RemoteCallbacks::new();
+        progress.attach(&mut callbacks);
+        
+        // Authentication callback
+        callbacks.credentials(network::credentials(config));
+        
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        
+        // Configure fetch options
+        if let Some(depth) = args.depth {
+            fetch_options.depth(depth as i32);
         }
-        true
-    });
-    
-    let mut fetch_options = FetchOptions::new();
-    fetch_options.remote_callbacks(callbacks);
-    
-    // Configure fetch options
-    if let Some(depth) = args.depth {
-        fetch_options.depth(depth as i32);
-    }
+        
+        if args.unshallow {
+            fetch_options.depth(i32::MAX); // Effectively unshallow
+        }
+        
+        if args.tags {
+            fetch_options.download_tags(AutotagOption::All);
+        } else if args.no_tags {
+            fetch_options.download_tags(AutotagOption::None);
+        }
+        
+        let fetched = timings::time(Phase::Network, || remote.fetch(&refspec_slices, Some(&mut fetch_options), None));
+        progress.finish();
+        fetched
+    }, |e| RgitError::FetchFailed(e.message().to_string())).await?;
     
-    if args.unshallow {
-        fetch_options.depth(i32::MAX); // Effectively unshallow
+    // Handle pruning
+    if prune {
+        prune_remote_refs(repo, remote_name, &refspecs, config)?;
     }
     
-    // Determine what to fetch
-    let refspecs: Vec<String> = if args.tags {
-        vec!["refs/tags/*:refs/tags/*".to_string()]
-    } else {
+    let after = snapshot_refs(repo)?;
+    Ok(diff_refs(repo, &before, &after))
+}
+
+/// Refspecs for a fetch: the ones given on the command line, or the remote's
+/// configured ones, plus every tag when pruning tags
+fn fetch_refspecs(remote: &git2::Remote, args: &FetchArgs) -> Result<Vec<String>> {
+    let mut refspecs = if args.refspecs.is_empty() {
         // Use default refspecs from remote configuration
-        let refspecs = remote.fetch_refspecs()?;
-        let owned: Vec<String> = refspecs
+        let configured = remote.fetch_refspecs()?;
+        configured
             .iter()
             .map(|s| s.map(|s| s.to_string()))
             .collect::<Option<Vec<String>>>()
-            .ok_or_else(|| RgitError::InvalidReference("Failed to get refspecs".to_string()))?;
-        owned
+            .ok_or_else(|| RgitError::InvalidReference("Failed to get refspecs".to_string()))?
+    } else {
+        args.refspecs.clone()
     };
-    
-    // Perform the fetch
-    let refspec_slices: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
-    remote.fetch(&refspec_slices, Some(&mut fetch_options), None)
-        .map_err(|e| RgitError::FetchFailed(e.message().to_string()))?;
-    
-    // Handle pruning
-    if args.prune {
-        prune_remote_refs(repo, remote_name, config)?;
+    if args.prune_tags {
+        refspecs.push("refs/tags/*:refs/tags/*".to_string());
     }
-    
-    if config.ui.interactive {
-        println!(); // New line after progress
+    Ok(refspecs)
+}
+
+// =============================================================================
+// Ref Changes
+// =============================================================================
+
+/// How a local ref changed in a fetch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefChange {
+    Created(Oid),
+    Updated { old: Oid, new: Oid, forced: bool },
+    Deleted(Oid),
+}
+
+/// Ref changes by full ref name
+pub type FetchReport = BTreeMap<String, RefChange>;
+
+/// A fetch refspec such as `+refs/heads/*:refs/remotes/origin/*`, for working
+/// out which local refs a fetch touches
+#[derive(Debug, Clone)]
+struct FetchSpec {
+    src: String,
+    dst: String,
+}
+
+impl FetchSpec {
+    /// Parse a refspec; negative refspecs don't map refs and are skipped
+    fn parse(spec: &str) -> Option<Self> {
+        if spec.starts_with('^') {
+            return None;
+        }
+        let spec = spec.trim_start_matches('+');
+        let (src, dst) = spec.split_once(':').unwrap_or((spec, ""));
+        let qualify = |name: &str| {
+            if name.is_empty() || name.starts_with("refs/") {
+                name.to_string()
+            } else {
+                format!("refs/heads/{}", name)
+            }
+        };
+        Some(Self { src: qualify(src), dst: qualify(dst) })
+    }
+
+    /// The local ref a remote ref is fetched into
+    fn transform(&self, remote_ref: &str) -> Option<String> {
+        map_ref(&self.src, &self.dst, remote_ref)
+    }
+
+    /// The remote ref a local ref is fetched from
+    fn reverse(&self, local_ref: &str) -> Option<String> {
+        map_ref(&self.dst, &self.src, local_ref)
     }
-    
-    Ok(())
 }
 
-/// Prune remote tracking branches that no longer exist on remote
-fn prune_remote_refs(repo: &Repository, remote_name: &str, config: &Config) -> Result<()> {
-    println!("{} Pruning remote tracking branches", "✂️".yellow());
-    
-    let remote_prefix = format!("refs/remotes/{}/", remote_name);
-    let mut pruned_count = 0;
-    
-    // Get list of remote refs
-    let mut remote = repo.find_remote(remote_name)?;
-    let remote_refs = remote_ls(&mut remote)?;
-    
-    // Build set of existing remote branch names
-    let mut remote_branches = std::collections::HashSet::new();
-    for remote_ref in &remote_refs {
-        if let Some(name) = remote_ref.0.strip_prefix("refs/heads/") {
-            remote_branches.insert(name.to_string());
+/// Map `name` from one side of a refspec to the other, expanding one `*`
+fn map_ref(from: &str, to: &str, name: &str) -> Option<String> {
+    if from.is_empty() || to.is_empty() {
+        return None;
+    }
+    match from.split_once('*') {
+        Some((prefix, suffix)) => {
+            let middle = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some(to.replacen('*', middle, 1))
         }
+        None => (name == from).then(|| to.to_string()),
     }
-    
-    // Check local remote tracking branches
-    let references = repo.references_glob(&format!("{}*", remote_prefix))?;
-    
-    for reference in references {
-        let mut reference = reference?;
-        if let Some(name) = reference.name() {
-            if let Some(branch_name) = name.strip_prefix(&remote_prefix) {
-                if !remote_branches.contains(branch_name) {
-                    // This remote tracking branch no longer exists on remote
-                    if config.ui.interactive {
-                        println!("  {} Pruning {}", "✂️".red(), name.red());
-                    }
-                    reference.delete()?;
-                    pruned_count += 1;
-                }
+}
+
+/// Every direct ref in the repository and where it points
+fn snapshot_refs(repo: &Repository) -> Result<BTreeMap<String, Oid>> {
+    let mut refs = BTreeMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+            refs.insert(name.to_string(), oid);
+        }
+    }
+    Ok(refs)
+}
+
+/// Compare two ref snapshots
+fn diff_refs(repo: &Repository, before: &BTreeMap<String, Oid>, after: &BTreeMap<String, Oid>) -> FetchReport {
+    let mut report = FetchReport::new();
+    for (name, &old) in before {
+        match after.get(name) {
+            None => {
+                report.insert(name.clone(), RefChange::Deleted(old));
             }
+            Some(&new) if new != old => {
+                // Unknown objects (in a dry run) count as fast-forwards
+                let forced = !repo.graph_descendant_of(new, old).unwrap_or(true);
+                report.insert(name.clone(), RefChange::Updated { old, new, forced });
+            }
+            Some(_) => {}
         }
     }
-    
-    if pruned_count > 0 {
-        println!("{} Pruned {} remote tracking branch{}", 
-                "✅".green(), 
-                pruned_count, 
-                if pruned_count == 1 { "" } else { "es" });
-    } else if config.ui.interactive {
-        println!("  {} No branches to prune", "ℹ️".blue());
+    for (name, &new) in after {
+        if !before.contains_key(name) {
+            report.insert(name.clone(), RefChange::Created(new));
+        }
     }
-    
-    Ok(())
+    report
+}
+
+/// Local refs a refspec maps from a remote ref that the remote no longer has
+fn stale_refs(specs: &[FetchSpec], advertised: &[(String, Oid)], local: &BTreeMap<String, Oid>) -> Vec<String> {
+    local.keys()
+        .filter(|name| {
+            let sources: Vec<String> = specs.iter().filter_map(|spec| spec.reverse(name)).collect();
+            !sources.is_empty() && !sources.iter().any(|src| advertised.iter().any(|(remote_ref, _)| remote_ref == src))
+        })
+        .cloned()
+        .collect()
+}
+
+/// What a fetch would change, from the refs the remote advertises
+fn planned_changes(
+    repo: &Repository,
+    refspecs: &[String],
+    advertised: &[(String, Oid)],
+    args: &FetchArgs,
+    before: &BTreeMap<String, Oid>,
+) -> FetchReport {
+    let specs: Vec<FetchSpec> = refspecs.iter().filter_map(|spec| FetchSpec::parse(spec)).collect();
+    let mut after = before.clone();
+    for (name, oid) in advertised {
+        // Skip peeled tag entries
+        if name.ends_with("^{}") {
+            continue;
+        }
+        for spec in &specs {
+            if let Some(local) = spec.transform(name) {
+                after.insert(local, *oid);
+            }
+        }
+        if args.tags && name.starts_with("refs/tags/") {
+            after.entry(name.clone()).or_insert(*oid);
+        }
+    }
+    if args.prune || args.prune_tags {
+        for name in stale_refs(&specs, advertised, before) {
+            after.remove(&name);
+        }
+    }
+    diff_refs(repo, before, &after)
+}
+
+/// Delete local refs whose remote counterpart is gone, returning their names
+fn prune_remote_refs(repo: &Repository, remote_name: &str, refspecs: &[String], config: &Config) -> Result<Vec<String>> {
+    let mut remote = network::rewritten(repo, repo.find_remote(remote_name)?, config, Direction::Fetch)?;
+    let advertised = remote_ls(&mut remote)?;
+    let specs: Vec<FetchSpec> = refspecs.iter().filter_map(|spec| FetchSpec::parse(spec)).collect();
+    
+    let stale = stale_refs(&specs, &advertised, &snapshot_refs(repo)?);
+    for name in &stale {
+        repo.find_reference(name)?.delete()?;
+    }
+    Ok(stale)
 }
 
 /// Helper to list remote refs using remote_ls
 fn remote_ls(remote: &mut git2::Remote) -> Result<Vec<(String, git2::Oid)>> {
-    remote.connect(git2::Direction::Fetch)?;
+    timings::time(Phase::Network, || remote.connect(git2::Direction::Fetch))?;
     let refs = remote.list()?;
     
     // Extract the data we need before disconnecting
@@ -257,35 +354,82 @@ fn remote_ls(remote: &mut git2::Remote) -> Result<Vec<(String, git2::Oid)>> {
     Ok(ref_data)
 }
 
+/// Short display name for a ref
+fn short_ref_name(name: &str) -> &str {
+    ["refs/remotes/", "refs/tags/", "refs/heads/"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name)
+}
+
+/// List new, updated and deleted refs after fetching from a remote
+fn show_fetch_report(remote_name: &str, report: &FetchReport, dry_run: bool) {
+    if report.is_empty() {
+        ui_println!("  {} {} is up to date", "ℹ️".blue(), remote_name.cyan());
+        return;
+    }
+
+    ui_println!("\n{} {} from {}:",
+            "📋".blue().bold(),
+            if dry_run { "Would update" } else { "Updated refs" },
+            remote_name.cyan());
+    for (name, change) in report {
+        let short = short_ref_name(name);
+        match *change {
+            RefChange::Created(_) => {
+                let kind = if name.starts_with("refs/tags/") {
+                    "[new tag]"
+                } else if name.starts_with("refs/remotes/") || name.starts_with("refs/heads/") {
+                    "[new branch]"
+                } else {
+                    "[new ref]"
+                };
+                ui_println!("  {} {} {}", "✨".green(), kind.green(), short.cyan());
+            }
+            RefChange::Updated { old, new, forced } => {
+                let range = format!("{}{}{}", shorten_oid(&old, 8), if forced { "..." } else { ".." }, shorten_oid(&new, 8));
+                ui_println!("  {} {} {}{}",
+                        "🔄".yellow(),
+                        short.cyan(),
+                        range.yellow(),
+                        if forced { " (forced update)".red().to_string() } else { String::new() });
+            }
+            RefChange::Deleted(old) => {
+                ui_println!("  {} {} {} (was {})", "🗑️".red(), "[deleted]".red(), short.cyan(), shorten_oid(&old, 8).dimmed());
+            }
+        }
+    }
+}
+
 /// Show fetch summary
 fn show_fetch_summary(repo: &Repository, args: &FetchArgs, config: &Config) -> Result<()> {
     if !config.ui.interactive {
         return Ok(());
     }
     
-    println!("\n{} Fetch Summary:", "📊".blue().bold());
+    ui_println!("\n{} Fetch Summary:", "📊".blue().bold());
     
     // Show what was fetched
     if args.all {
         let remotes = repo.remotes()?;
-        println!("  {} Fetched from {} remote{}", 
+        ui_println!("  {} Fetched from {} remote{}", 
                 "📡".blue(), 
                 remotes.len(),
                 if remotes.len() == 1 { "" } else { "s" });
     } else {
         let remote_name = args.remote.as_deref().unwrap_or("origin");
-        println!("  {} Fetched from {}", "📡".blue(), remote_name.cyan());
+        ui_println!("  {} Fetched from {}", "📡".blue(), remote_name.cyan());
     }
     
     // Show remote tracking branch status
     show_tracking_status(repo)?;
     
     // Show next steps
-    println!("\n{} Next steps:", "💡".blue());
-    println!("  • {} - Check for new commits", "rgit log --oneline".cyan());
-    println!("  • {} - View all branches", "rgit branch -a".cyan());
-    println!("  • {} - Merge or rebase changes", "rgit pull".cyan());
-    println!("  • {} - Check status", "rgit status".cyan());
+    ui_println!("\n{} Next steps:", "💡".blue());
+    ui_println!("  • {} - Check for new commits", "rgit log --oneline".cyan());
+    ui_println!("  • {} - View all branches", "rgit branch -a".cyan());
+    ui_println!("  • {} - Merge or rebase changes", "rgit pull".cyan());
+    ui_println!("  • {} - Check status", "rgit status".cyan());
     
     Ok(())
 }
@@ -310,7 +454,7 @@ fn show_tracking_status(repo: &Repository) -> Result<()> {
         let upstream_oid = upstream_ref.target().unwrap();
         
         if head_oid == upstream_oid {
-            println!("  {} {} is up to date with {}", 
+            ui_println!("  {} {} is up to date with {}", 
                     "✅".green(), 
                     current_branch.cyan(), 
                     upstream_name.yellow());
@@ -319,20 +463,20 @@ fn show_tracking_status(repo: &Repository) -> Result<()> {
             let (ahead, behind) = repo.graph_ahead_behind(head_oid, upstream_oid)?;
             
             if ahead > 0 && behind > 0 {
-                println!("  {} {} is {} ahead, {} behind {}", 
+                ui_println!("  {} {} is {} ahead, {} behind {}", 
                         "↕️".yellow(), 
                         current_branch.cyan(),
                         ahead.to_string().green(),
                         behind.to_string().red(),
                         upstream_name.yellow());
             } else if ahead > 0 {
-                println!("  {} {} is {} ahead of {}", 
+                ui_println!("  {} {} is {} ahead of {}", 
                         "⬆️".green(), 
                         current_branch.cyan(),
                         ahead.to_string().green(),
                         upstream_name.yellow());
             } else if behind > 0 {
-                println!("  {} {} is {} behind {}", 
+                ui_println!("  {} {} is {} behind {}", 
                         "⬇️".red(), 
                         current_branch.cyan(),
                         behind.to_string().red(),
@@ -340,7 +484,7 @@ fn show_tracking_status(repo: &Repository) -> Result<()> {
             }
         }
     } else {
-        println!("  {} {} has no upstream branch", 
+        ui_println!("  {} {} has no upstream branch", 
                 "⚠️".yellow(), 
                 current_branch.cyan());
     }
@@ -348,23 +492,146 @@ fn show_tracking_status(repo: &Repository) -> Result<()> {
     Ok(())
 }
 
+// =============================================================================
+// Automatic Fetch
+// =============================================================================
+
+/// Stamp file (inside `.git`) recording the last automatic fetch attempt
+const AUTO_FETCH_STAMP: &str = "rgit/last-auto-fetch";
+
+/// Stop waiting for an automatic fetch after this long
+const AUTO_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often to check whether a waited-for automatic fetch has finished
+const AUTO_FETCH_POLL: Duration = Duration::from_millis(50);
+
+/// Opportunistically fetch the current branch's remote before a read-only view.
+///
+/// Runs at most once per `fetch.auto` interval, never in offline mode, and
+/// never fails the calling command. The fetch runs in a detached `rgit fetch`
+/// that the view waits up to ten seconds for; a slower fetch carries on after
+/// the view shows the refs as they were, and the next one picks up the result.
+pub async fn auto_fetch(rgit: &RgitCore, config: &Config) {
+    let Some(remote_name) = claim_auto_fetch(rgit, config) else { return };
+
+    rgit.log(&format!("Auto-fetching {}", remote_name));
+    let mut child = match spawn_fetch(rgit, &remote_name) {
+        Ok(child) => child,
+        Err(e) => {
+            debug!("Failed to start auto-fetch: {}", e);
+            return;
+        }
+    };
+    let started = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() {
+                    debug!("Auto-fetch failed: {}", status);
+                }
+                break;
+            }
+            Ok(None) if started.elapsed() >= AUTO_FETCH_TIMEOUT => {
+                debug!("Auto-fetch still running after {:?}; not waiting for it", AUTO_FETCH_TIMEOUT);
+                break;
+            }
+            Ok(None) => tokio::time::sleep(AUTO_FETCH_POLL).await,
+            Err(e) => {
+                debug!("Auto-fetch failed: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// Start the same throttled fetch when `fetch.background` is on, without
+/// waiting for it.
+///
+/// Read commands call this so remote-tracking refs stay fresh and the next
+/// `status` shows true ahead/behind counts without fetching itself.
+pub fn auto_fetch_in_background(rgit: &RgitCore, config: &Config) {
+    if !config.fetch.background {
+        return;
+    }
+    let Some(remote_name) = claim_auto_fetch(rgit, config) else { return };
+
+    match spawn_fetch(rgit, &remote_name) {
+        Ok(child) => debug!("Background fetch of {} started (pid {})", remote_name, child.id()),
+        Err(e) => debug!("Failed to start background fetch: {}", e),
+    }
+}
+
+/// Run `rgit fetch <remote>` as a separate process that outlives this one,
+/// so exiting never has to wait for the network
+fn spawn_fetch(rgit: &RgitCore, remote_name: &str) -> std::io::Result<std::process::Child> {
+    let mut command = std::process::Command::new(std::env::current_exe()?);
+    command
+        .args(["--no-pager", "--no-color", "fetch", remote_name])
+        .current_dir(rgit.repo.workdir().unwrap_or_else(|| rgit.git_dir()))
+        // A fetch nobody is watching mustn't stop to ask for credentials
+        .env("RGIT_NONINTERACTIVE", "1")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    // Keep Ctrl-C in the pager from killing the fetch
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    command.spawn()
+}
+
+/// The remote to auto-fetch if a fetch is due, recording the attempt first
+/// so an unreachable remote isn't retried on every command
+fn claim_auto_fetch(rgit: &RgitCore, config: &Config) -> Option<String> {
+    let interval = config.fetch.auto_interval()?;
+
+    let stamp = rgit.git_dir().join(AUTO_FETCH_STAMP);
+    if !auto_fetch_due(&stamp, interval) {
+        return None;
+    }
+    if let Err(e) = touch_stamp(&stamp) {
+        debug!("Failed to record auto-fetch time: {}", e);
+        return None;
+    }
+
+    let remote_name = upstream_remote(&rgit.repo).unwrap_or_else(|| config.git.default_remote.clone());
+    rgit.repo.find_remote(&remote_name).ok()?;
+    Some(remote_name)
+}
+
+/// Whether the last automatic fetch is older than `interval`
+fn auto_fetch_due(stamp: &Path, interval: Duration) -> bool {
+    let elapsed = fs::metadata(stamp)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+
+    match elapsed {
+        Some(elapsed) => elapsed >= interval,
+        None => true,
+    }
+}
+
+fn touch_stamp(stamp: &Path) -> Result<()> {
+    if let Some(parent) = stamp.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(stamp, chrono::Utc::now().to_rfc3339())?;
+    Ok(())
+}
+
+/// Remote configured as upstream for the current branch
+fn upstream_remote(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    let branch = head.shorthand()?;
+    repo.config().ok()?.get_string(&format!("branch.{}.remote", branch)).ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::create_test_repo;
     use tempfile::TempDir;
 
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[test]
     fn test_show_tracking_status() {
         let (_temp_dir, repo) = create_test_repo();
@@ -380,17 +647,110 @@ mod tests {
         let config = Config::minimal();
         
         // Should handle repo with no remotes
-        let result = fetch_all_remotes(&repo, &config).await;
+        let result = fetch_all_remotes(&repo, &FetchArgs::default(), &config).await;
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_auto_fetch_due() {
+        let temp_dir = TempDir::new().unwrap();
+        let stamp = temp_dir.path().join("rgit").join("last-auto-fetch");
+
+        assert!(auto_fetch_due(&stamp, Duration::from_secs(60)));
+        touch_stamp(&stamp).unwrap();
+        assert!(!auto_fetch_due(&stamp, Duration::from_secs(60)));
+        assert!(auto_fetch_due(&stamp, Duration::ZERO));
+    }
+
     #[test]
     fn test_prune_remote_refs() {
         let (_temp_dir, repo) = create_test_repo();
-        let config = Config::minimal();
         
         // Should handle repo with no remotes gracefully
-        let result = prune_remote_refs(&repo, "origin", &config);
+        let result = prune_remote_refs(&repo, "origin", &[], &Config::minimal());
         assert!(result.is_err()); // Expected since no remote exists
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_fetch_spec_mapping() {
+        let spec = FetchSpec::parse("+refs/heads/*:refs/remotes/origin/*").unwrap();
+        assert_eq!(spec.transform("refs/heads/feature/x").as_deref(), Some("refs/remotes/origin/feature/x"));
+        assert_eq!(spec.transform("refs/tags/v1"), None);
+        assert_eq!(spec.reverse("refs/remotes/origin/main").as_deref(), Some("refs/heads/main"));
+
+        let spec = FetchSpec::parse("main:mirror").unwrap();
+        assert_eq!(spec.transform("refs/heads/main").as_deref(), Some("refs/heads/mirror"));
+        assert!(FetchSpec::parse("main").unwrap().transform("refs/heads/main").is_none());
+        assert!(FetchSpec::parse("^refs/heads/tmp/*").is_none());
+    }
+
+    #[test]
+    fn test_fetch_reports_and_prunes_changes() {
+        use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "a\n", "First");
+        let remote = repo.add_remote("origin");
+        repo.branch("old").tag("v1");
+        {
+            let mut origin = repo.repo().find_remote("origin").unwrap();
+            origin.push(&["refs/heads/old:refs/heads/old", "refs/tags/v1:refs/tags/v1"], None).unwrap();
+            origin.fetch(&["refs/heads/old:refs/remotes/origin/old"], None, None).unwrap();
+        }
+
+        // Someone else moves main, adds a branch and deletes one and the tag
+        let second = repo.commit_file("a.txt", "b\n", "Second");
+        let remote_repo = remote.repo();
+        let mut origin = repo.repo().find_remote("origin").unwrap();
+        let main = format!("refs/heads/{0}:refs/heads/{0}", DEFAULT_BRANCH);
+        let new = format!("refs/heads/{}:refs/heads/new", DEFAULT_BRANCH);
+        origin.push(&[main.as_str(), new.as_str()], None).unwrap();
+        remote_repo.find_reference("refs/heads/old").unwrap().delete().unwrap();
+        remote_repo.find_reference("refs/tags/v1").unwrap().delete().unwrap();
+        // Our pushes updated the tracking refs; undo that so the fetch has something to report
+        repo.repo().reference(&format!("refs/remotes/origin/{}", DEFAULT_BRANCH), first, true, "").unwrap();
+        repo.repo().find_reference("refs/remotes/origin/new").unwrap().delete().unwrap();
+
+        let args = FetchArgs { prune: true, prune_tags: true, dry_run: true, ..Default::default() };
+        let refspecs = fetch_refspecs(&origin, &args).unwrap();
+        let before = snapshot_refs(repo.repo()).unwrap();
+        let advertised = remote_ls(&mut origin).unwrap();
+        let report = planned_changes(repo.repo(), &refspecs, &advertised, &args, &before);
+
+        assert_eq!(report.get(&format!("refs/remotes/origin/{}", DEFAULT_BRANCH)),
+                   Some(&RefChange::Updated { old: first, new: second, forced: false }));
+        assert_eq!(report.get("refs/remotes/origin/new"), Some(&RefChange::Created(second)));
+        assert_eq!(report.get("refs/remotes/origin/old"), Some(&RefChange::Deleted(first)));
+        assert_eq!(report.get("refs/tags/v1"), Some(&RefChange::Deleted(first)));
+        assert_eq!(report.len(), 4);
+
+        let pruned = prune_remote_refs(repo.repo(), "origin", &refspecs, &Config::minimal()).unwrap();
+        assert_eq!(pruned, ["refs/remotes/origin/old", "refs/tags/v1"]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_through_rewritten_url() {
+        use crate::config::UrlRewrite;
+        use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "First");
+        let remote = repo.add_remote("origin");
+        let second = repo.commit_file("a.txt", "b\n", "Second");
+        let main = format!("refs/heads/{0}:refs/heads/{0}", DEFAULT_BRANCH);
+        repo.repo().remote_anonymous(&remote.url()).unwrap().push(&[main.as_str()], None).unwrap();
+
+        // origin now points nowhere unless the rewrite applies
+        repo.repo().remote_set_url("origin", "https://example.invalid/tool.git").unwrap();
+        let mut config = Config::minimal();
+        config.url.insert(remote.url(), UrlRewrite {
+            instead_of: vec!["https://example.invalid/tool.git".to_string()],
+            push_instead_of: vec![],
+        });
+
+        let report = fetch_remote_with_options(repo.repo(), "origin", &FetchArgs::default(), &config).await.unwrap();
+        let tracking = format!("refs/remotes/origin/{}", DEFAULT_BRANCH);
+        assert!(matches!(report.get(&tracking), Some(RefChange::Updated { new, .. }) if *new == second));
+        assert_eq!(repo.repo().find_remote("origin").unwrap().url(), Some("https://example.invalid/tool.git"));
+    }
+}
@@ -0,0 +1,671 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, FileMode, ObjectType, Oid, Repository, ResetType, Signature, Sort, StatusOptions, Tree};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::cli::FilterArgs;
+use crate::commands::backup::{self, SnapshotRequest};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::timings::{self, Phase};
+use crate::utils::{check_timestamp, humanize_size, normalize_signature, parse_size, shorten_oid};
+
+/// Execute the filter command
+pub async fn execute(args: &FilterArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let filter = HistoryFilter::from_args(repo, args, config)?;
+    let refs = refs_to_rewrite(repo, &args.refs)?;
+    if refs.is_empty() {
+        ui_println!("{} No branches or tags to rewrite", "ℹ️".blue());
+        return Ok(());
+    }
+
+    if !args.dry_run {
+        ensure_clean(repo)?;
+        confirm(&filter, refs.len(), args.force, config)?;
+    }
+
+    let snapshot = if args.dry_run {
+        None
+    } else {
+        let request = SnapshotRequest { label: Some("before filter".to_string()), ..Default::default() };
+        Some(backup::create_snapshot(repo, &request, &backup::snapshot_signature(rgit)?)?)
+    };
+
+    let mut rewriter = Rewriter::new(repo, &filter);
+    let updates = rewriter.rewrite(&refs)?;
+    if args.dry_run {
+        show_report(repo, &rewriter.stats, &updates, None);
+        ui_println!("{} Dry run: no refs were changed", "ℹ️".blue());
+        return Ok(());
+    }
+
+    apply_updates(repo, &updates)?;
+    show_report(repo, &rewriter.stats, &updates, snapshot.as_ref().map(|s| s.id.as_str()));
+    Ok(())
+}
+
+// =============================================================================
+// Filter
+// =============================================================================
+
+/// What to take out of or change in every commit
+#[derive(Debug, Default)]
+pub struct HistoryFilter {
+    /// Files or directories to drop, relative to the root
+    pub paths: Vec<String>,
+    pub blobs: HashSet<Oid>,
+    /// Blobs larger than this are dropped
+    pub max_blob_size: Option<u64>,
+    /// Lowercased old email to its replacement
+    pub emails: HashMap<String, String>,
+    /// Turn executable files into regular ones
+    pub strip_modes: bool,
+    /// Clamp implausible author and committer dates on rewritten commits to
+    /// now, as `git.normalize_dates` does for cherry-picks
+    pub normalize_dates: bool,
+}
+
+impl HistoryFilter {
+    fn from_args(repo: &Repository, args: &FilterArgs, config: &Config) -> Result<Self> {
+        let paths = args.remove_paths.iter()
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+        let blobs = args.remove_blobs.iter()
+            .map(|spec| {
+                let object = repo.revparse_single(spec)
+                    .map_err(|_| RgitError::InvalidReference(spec.clone()))?;
+                match object.kind() {
+                    Some(ObjectType::Blob) => Ok(object.id()),
+                    _ => Err(RgitError::InvalidArgument(format!("{} is not a blob", spec)).into()),
+                }
+            })
+            .collect::<Result<_>>()?;
+        let max_blob_size = args.strip_blobs_bigger_than.as_ref()
+            .map(|size| parse_size(size).ok_or_else(|| RgitError::InvalidArgument(format!("Invalid size: {}", size))))
+            .transpose()?;
+        let emails = args.emails.iter()
+            .map(|mapping| {
+                mapping.split_once('=')
+                    .map(|(old, new)| (old.trim().to_lowercase(), new.trim().to_string()))
+                    .filter(|(old, new)| !old.is_empty() && !new.is_empty())
+                    .ok_or_else(|| RgitError::InvalidArgument(format!("Expected OLD=NEW, got {}", mapping)).into())
+            })
+            .collect::<Result<_>>()?;
+
+        let filter = Self {
+            paths,
+            blobs,
+            max_blob_size,
+            emails,
+            strip_modes: args.strip_modes,
+            normalize_dates: config.git.normalize_dates,
+        };
+        if filter.is_empty() {
+            return Err(RgitError::InvalidArgument(
+                "nothing to filter; use --remove-path, --remove-blob, --strip-blobs-bigger-than, --email or --strip-modes".to_string()
+            ).into());
+        }
+        Ok(filter)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.blobs.is_empty() && self.max_blob_size.is_none()
+            && self.emails.is_empty() && !self.strip_modes
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self.paths.iter().map(|path| format!("remove {}", path)).collect();
+        lines.extend(self.blobs.iter().map(|blob| format!("remove blob {}", shorten_oid(blob, 8))));
+        if let Some(size) = self.max_blob_size {
+            lines.push(format!("remove blobs larger than {}", humanize_size(size)));
+        }
+        lines.extend(self.emails.iter().map(|(old, new)| format!("replace email {} with {}", old, new)));
+        if self.strip_modes {
+            lines.push("clear executable bits".to_string());
+        }
+        if self.normalize_dates {
+            lines.push("set implausible dates on rewritten commits to now (git.normalize_dates)".to_string());
+        }
+        lines
+    }
+}
+
+/// Local branches and tags to rewrite, with the commits they lead to
+fn refs_to_rewrite(repo: &Repository, only: &[String]) -> Result<Vec<(String, Oid)>> {
+    let mut refs = Vec::new();
+    if only.is_empty() {
+        for reference in repo.references()? {
+            let reference = reference?;
+            let Some(name) = reference.name() else { continue };
+            if !(name.starts_with("refs/heads/") || name.starts_with("refs/tags/")) {
+                continue;
+            }
+            if let Ok(commit) = reference.peel_to_commit() {
+                refs.push((name.to_string(), commit.id()));
+            }
+        }
+    } else {
+        for name in only {
+            let reference = repo.resolve_reference_from_short_name(name)
+                .map_err(|_| RgitError::InvalidReference(name.clone()))?;
+            let full = reference.name().unwrap_or(name).to_string();
+            if !(full.starts_with("refs/heads/") || full.starts_with("refs/tags/")) {
+                return Err(RgitError::InvalidArgument(format!("{} is not a branch or tag", name)).into());
+            }
+            refs.push((full, reference.peel_to_commit()?.id()));
+        }
+    }
+    Ok(refs)
+}
+
+/// Rewriting resets the working tree, so uncommitted work must not be in the way
+fn ensure_clean(repo: &Repository) -> Result<()> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).exclude_submodules(true);
+    if !timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?.is_empty() {
+        return Err(RgitError::BranchHasUncommittedChanges.into());
+    }
+    Ok(())
+}
+
+fn confirm(filter: &HistoryFilter, ref_count: usize, force: bool, config: &Config) -> Result<()> {
+    ui_println!("{} Rewriting {} ref{} to:", "✂️".yellow(), ref_count, if ref_count == 1 { "" } else { "s" });
+    for line in filter.describe() {
+        ui_println!("  {} {}", "•".yellow(), line);
+    }
+    if force {
+        return Ok(());
+    }
+    if !config.is_interactive() {
+        return Err(RgitError::InvalidArgument("rewriting history needs --force without a terminal".to_string()).into());
+    }
+    let proceed = InteractivePrompt::new()
+        .with_message("Rewrite history? A backup snapshot is taken first")
+        .confirm()?;
+    if !proceed {
+        return Err(RgitError::OperationCancelled.into());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Rewriting
+// =============================================================================
+
+/// What a rewrite changed
+#[derive(Debug, Default)]
+pub struct FilterStats {
+    pub commits_rewritten: usize,
+    /// Commits whose only changes were filtered away
+    pub commits_dropped: usize,
+    pub paths_removed: BTreeSet<String>,
+    /// Removed blobs and their sizes
+    pub blobs_removed: HashMap<Oid, u64>,
+    pub emails_changed: usize,
+    /// Commits whose author or committer date was normalized
+    pub dates_normalized: usize,
+}
+
+/// A ref and where it points after the rewrite; `None` when every commit
+/// it reached was dropped
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefUpdate {
+    pub name: String,
+    pub old: Oid,
+    pub new: Option<Oid>,
+}
+
+struct Rewriter<'r> {
+    repo: &'r Repository,
+    filter: &'r HistoryFilter,
+    /// Rewritten trees by original id and path (the path only matters when removing paths);
+    /// `None` for trees left empty
+    trees: HashMap<(Oid, String), Option<Oid>>,
+    sizes: HashMap<Oid, u64>,
+    commits: HashMap<Oid, Option<Oid>>,
+    /// What implausible dates are normalized to
+    now: i64,
+    stats: FilterStats,
+}
+
+impl<'r> Rewriter<'r> {
+    fn new(repo: &'r Repository, filter: &'r HistoryFilter) -> Self {
+        Self {
+            repo,
+            filter,
+            trees: HashMap::new(),
+            sizes: HashMap::new(),
+            commits: HashMap::new(),
+            now: chrono::Utc::now().timestamp(),
+            stats: FilterStats::default(),
+        }
+    }
+
+    /// Rewrite every commit reachable from `refs`, oldest first
+    fn rewrite(&mut self, refs: &[(String, Oid)]) -> Result<Vec<RefUpdate>> {
+        let mut walk = self.repo.revwalk()?;
+        walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+        for (_, tip) in refs {
+            walk.push(*tip)?;
+        }
+        for oid in walk {
+            let oid = oid?;
+            let rewritten = self.rewrite_commit(&self.repo.find_commit(oid)?)?;
+            self.commits.insert(oid, rewritten);
+        }
+
+        Ok(refs.iter()
+            .map(|(name, tip)| RefUpdate { name: name.clone(), old: *tip, new: self.commits[tip] })
+            .collect())
+    }
+
+    fn rewrite_commit(&mut self, commit: &Commit) -> Result<Option<Oid>> {
+        let mut parents: Vec<Oid> = Vec::new();
+        for parent in commit.parent_ids() {
+            if let Some(Some(new)) = self.commits.get(&parent) {
+                if !parents.contains(new) {
+                    parents.push(*new);
+                }
+            }
+        }
+
+        let empty_tree = self.repo.treebuilder(None)?.write()?;
+        let tree = self.rewrite_tree(&commit.tree()?, "")?.unwrap_or(empty_tree);
+
+        // Drop commits whose only changes were filtered away
+        if commit.parent_count() <= 1 {
+            let old_base = commit.parent(0).map(|p| p.tree_id()).unwrap_or(empty_tree);
+            let new_base = match parents.first() {
+                Some(parent) => self.repo.find_commit(*parent)?.tree_id(),
+                None => empty_tree,
+            };
+            if tree == new_base && commit.tree_id() != old_base {
+                self.stats.commits_dropped += 1;
+                return Ok(parents.first().copied());
+            }
+        }
+
+        let author = self.rewrite_signature(&commit.author())?;
+        let committer = self.rewrite_signature(&commit.committer())?;
+        let unchanged = tree == commit.tree_id()
+            && parents == commit.parent_ids().collect::<Vec<_>>()
+            && author.is_none()
+            && committer.is_none();
+        if unchanged {
+            return Ok(Some(commit.id()));
+        }
+        let (original_author, original_committer) = (commit.author(), commit.committer());
+        if self.new_email(&original_author).is_some() || self.new_email(&original_committer).is_some() {
+            self.stats.emails_changed += 1;
+        }
+        if self.implausible_date(&original_author) || self.implausible_date(&original_committer) {
+            self.stats.dates_normalized += 1;
+        }
+
+        let parent_commits = parents.iter()
+            .map(|oid| self.repo.find_commit(*oid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let parent_refs: Vec<&Commit> = parent_commits.iter().collect();
+        let new = self.repo.commit(
+            None,
+            author.as_ref().unwrap_or(&commit.author()),
+            committer.as_ref().unwrap_or(&commit.committer()),
+            commit.message_raw().unwrap_or(""),
+            &self.repo.find_tree(tree)?,
+            &parent_refs,
+        )?;
+        self.stats.commits_rewritten += 1;
+        Ok(Some(new))
+    }
+
+    /// `signature` with its email replaced and its date normalized, or `None`
+    /// when it stays the same
+    fn rewrite_signature(&self, signature: &Signature) -> Result<Option<Signature<'static>>> {
+        let email = self.new_email(signature);
+        let normalize = self.implausible_date(signature);
+        if email.is_none() && !normalize {
+            return Ok(None);
+        }
+
+        let signature = if normalize {
+            normalize_signature(signature, self.now)?
+        } else {
+            signature.to_owned()
+        };
+        let email = email.unwrap_or(signature.email().unwrap_or(""));
+        Ok(Some(Signature::new(signature.name().unwrap_or(""), email, &signature.when())?))
+    }
+
+    /// The replacement for a signature's email, if the filter changes it
+    fn new_email(&self, signature: &Signature) -> Option<&'r str> {
+        let email = signature.email().unwrap_or("").to_lowercase();
+        self.filter.emails.get(&email).map(String::as_str)
+    }
+
+    /// Whether a signature's date is normalized under `git.normalize_dates`
+    fn implausible_date(&self, signature: &Signature) -> bool {
+        self.filter.normalize_dates && check_timestamp(signature.when().seconds(), self.now).is_some()
+    }
+
+    /// The filtered tree at `prefix`, or `None` when nothing is left of it
+    fn rewrite_tree(&mut self, tree: &Tree, prefix: &str) -> Result<Option<Oid>> {
+        let key_path = if self.filter.paths.is_empty() { String::new() } else { prefix.to_string() };
+        if let Some(cached) = self.trees.get(&(tree.id(), key_path.clone())) {
+            return Ok(*cached);
+        }
+
+        let mut builder = self.repo.treebuilder(None)?;
+        let mut changed = false;
+        for entry in tree.iter() {
+            let name = String::from_utf8_lossy(entry.name_bytes()).to_string();
+            let path = if prefix.is_empty() { name.clone() } else { format!("{}/{}", prefix, name) };
+            if self.filter.paths.contains(&path) {
+                self.stats.paths_removed.insert(path);
+                changed = true;
+                continue;
+            }
+
+            match entry.kind() {
+                Some(ObjectType::Tree) => {
+                    let subtree = self.repo.find_tree(entry.id())?;
+                    match self.rewrite_tree(&subtree, &path)? {
+                        Some(id) => {
+                            changed |= id != entry.id();
+                            builder.insert(entry.name_bytes(), id, entry.filemode())?;
+                        }
+                        None => changed = true,
+                    }
+                }
+                Some(ObjectType::Blob) => {
+                    if self.drops_blob(entry.id())? {
+                        changed = true;
+                        continue;
+                    }
+                    let mut mode = entry.filemode();
+                    if self.filter.strip_modes && mode == i32::from(FileMode::BlobExecutable) {
+                        mode = FileMode::Blob.into();
+                        changed = true;
+                    }
+                    builder.insert(entry.name_bytes(), entry.id(), mode)?;
+                }
+                // Submodule commits and anything else are kept as they are
+                _ => {
+                    builder.insert(entry.name_bytes(), entry.id(), entry.filemode())?;
+                }
+            }
+        }
+
+        let rewritten = match (changed, builder.len()) {
+            (false, _) => Some(tree.id()),
+            (true, 0) => None,
+            (true, _) => Some(builder.write()?),
+        };
+        self.trees.insert((tree.id(), key_path), rewritten);
+        Ok(rewritten)
+    }
+
+    fn drops_blob(&mut self, blob: Oid) -> Result<bool> {
+        let explicit = self.filter.blobs.contains(&blob);
+        if !explicit && self.filter.max_blob_size.is_none() {
+            return Ok(false);
+        }
+        let size = match self.sizes.get(&blob) {
+            Some(size) => *size,
+            None => {
+                let (size, _) = self.repo.odb()?.read_header(blob)?;
+                self.sizes.insert(blob, size as u64);
+                size as u64
+            }
+        };
+        let drop = explicit || self.filter.max_blob_size.is_some_and(|max| size > max);
+        if drop {
+            self.stats.blobs_removed.insert(blob, size);
+        }
+        Ok(drop)
+    }
+}
+
+// =============================================================================
+// Applying
+// =============================================================================
+
+/// Move the refs, recreate annotated tags and reset the working tree
+fn apply_updates(repo: &Repository, updates: &[RefUpdate]) -> Result<()> {
+    let journal = Journal::for_repo(repo);
+    let head_name = repo.head().ok().and_then(|head| head.name().map(String::from));
+
+    for update in updates {
+        if update.new == Some(update.old) {
+            continue;
+        }
+        let mut reference = repo.find_reference(&update.name)?;
+        let entry = JournalEntry::new("filter", &update.name).with_old_oid(update.old);
+        let Some(new) = update.new else {
+            reference.delete()?;
+            journal.record(&entry)?;
+            continue;
+        };
+
+        let tag = reference.target().and_then(|target| repo.find_tag(target).ok());
+        match (tag, update.name.strip_prefix("refs/tags/")) {
+            (Some(tag), Some(short)) => {
+                let tagger = tag.tagger().unwrap_or(Signature::now("rgit", "rgit@localhost")?);
+                repo.tag(short, &repo.find_object(new, None)?, &tagger, tag.message().unwrap_or(""), true)?;
+            }
+            _ => {
+                reference.set_target(new, "rgit filter")?;
+            }
+        }
+        journal.record(&entry.with_new_oid(new))?;
+    }
+
+    if let Some(head) = head_name.and_then(|name| updates.iter().find(|u| u.name == name)) {
+        if let Some(new) = head.new.filter(|new| *new != head.old) {
+            repo.reset(&repo.find_object(new, None)?, ResetType::Hard, None)?;
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+fn show_report(repo: &Repository, stats: &FilterStats, updates: &[RefUpdate], snapshot: Option<&str>) {
+    ui_println!("{} Rewrote {} commit{}{}",
+            "✅".green().bold(),
+            stats.commits_rewritten,
+            if stats.commits_rewritten == 1 { "" } else { "s" },
+            if stats.commits_dropped > 0 { format!(", dropped {} left empty", stats.commits_dropped) } else { String::new() });
+    for path in &stats.paths_removed {
+        ui_println!("  {} removed {}", "•".green(), path);
+    }
+    if !stats.blobs_removed.is_empty() {
+        let total: u64 = stats.blobs_removed.values().sum();
+        ui_println!("  {} removed {} blob{} ({})", "•".green(), stats.blobs_removed.len(),
+                if stats.blobs_removed.len() == 1 { "" } else { "s" }, humanize_size(total));
+    }
+    if stats.emails_changed > 0 {
+        ui_println!("  {} changed emails in {} commit{}", "•".green(), stats.emails_changed,
+                if stats.emails_changed == 1 { "" } else { "s" });
+    }
+    if stats.dates_normalized > 0 {
+        ui_println!("  {} normalized implausible dates in {} commit{}", "•".green(), stats.dates_normalized,
+                if stats.dates_normalized == 1 { "" } else { "s" });
+    }
+
+    let changed: Vec<&RefUpdate> = updates.iter().filter(|u| u.new != Some(u.old)).collect();
+    if changed.is_empty() {
+        ui_println!("{} No refs changed", "ℹ️".blue());
+        return;
+    }
+    ui_println!("\n{} Refs:", "🔀".blue());
+    for update in &changed {
+        let new = update.new.map(|oid| shorten_oid(&oid, 8)).unwrap_or_else(|| "deleted".to_string());
+        ui_println!("  {} {} → {}", short_ref(&update.name).cyan(), shorten_oid(&update.old, 8).dimmed(), new.yellow());
+    }
+
+    let Some(snapshot) = snapshot else { return };
+    ui_println!("\n{} Backup snapshot {}; undo with {}",
+            "💾".blue(), snapshot.cyan(), format!("rgit restore --from-backup={}", snapshot).cyan());
+
+    let pushes = force_pushes(repo, &changed);
+    if !pushes.is_empty() {
+        ui_println!("\n{} Remotes still have the old history. Once you're happy with the result:", "⚠️".yellow());
+        for command in &pushes {
+            println!("   {}", command.cyan());
+        }
+        println!("   Collaborators need to re-clone or rebase their work onto the new commits.");
+    }
+    ui_println!("{} The old objects stay in this repository until you run {} and {}",
+            "💡".blue(), format!("rgit backup drop {}", snapshot).cyan(), "rgit gc --prune".cyan());
+    println!("   Commit signatures on rewritten commits are not carried over.");
+}
+
+/// Commands that publish the rewritten refs to the remotes that have them
+fn force_pushes(repo: &Repository, changed: &[&RefUpdate]) -> Vec<String> {
+    let remotes: Vec<String> = repo.remotes().map(|r| r.iter().flatten().map(String::from).collect()).unwrap_or_default();
+    let mut commands = Vec::new();
+    for update in changed {
+        if let Some(branch) = update.name.strip_prefix("refs/heads/") {
+            for remote in &remotes {
+                if repo.find_reference(&format!("refs/remotes/{}/{}", remote, branch)).is_ok() {
+                    commands.push(format!("rgit push {} {} --force-with-lease", remote, branch));
+                }
+            }
+        } else if let Some(tag) = update.name.strip_prefix("refs/tags/") {
+            for remote in &remotes {
+                commands.push(format!("git push --force {} refs/tags/{}", remote, tag));
+            }
+        }
+    }
+    commands
+}
+
+fn short_ref(name: &str) -> &str {
+    name.strip_prefix("refs/heads/")
+        .or_else(|| name.strip_prefix("refs/tags/"))
+        .unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use std::path::Path;
+
+    fn rewrite(repo: &TestRepo, filter: &HistoryFilter) -> (FilterStats, Vec<RefUpdate>) {
+        let refs = refs_to_rewrite(repo.repo(), &[]).unwrap();
+        let mut rewriter = Rewriter::new(repo.repo(), filter);
+        let updates = rewriter.rewrite(&refs).unwrap();
+        apply_updates(repo.repo(), &updates).unwrap();
+        (rewriter.stats, updates)
+    }
+
+    fn head(repo: &TestRepo) -> Commit<'_> {
+        repo.repo().head().unwrap().peel_to_commit().unwrap()
+    }
+
+    #[test]
+    fn test_remove_path_from_history() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "hello\n", "Initial commit");
+        repo.commit_file("config/secrets.env", "TOKEN=abc\n", "Add secrets");
+        repo.commit_file("README.md", "hello again\n", "Update readme");
+        repo.tag("v1.0");
+
+        let filter = HistoryFilter { paths: vec!["config".to_string()], ..Default::default() };
+        let (stats, _) = rewrite(&repo, &filter);
+
+        assert_eq!(stats.commits_dropped, 1);
+        assert!(stats.paths_removed.contains("config"));
+        let head = head(&repo);
+        assert_eq!(head.summary(), Some("Update readme"));
+        assert_eq!(head.parent(0).unwrap().summary(), Some("Initial commit"));
+        assert!(head.tree().unwrap().get_path(Path::new("config")).is_err());
+        assert!(!repo.path().join("config/secrets.env").exists());
+
+        let tag = repo.repo().revparse_single("v1.0^{commit}").unwrap();
+        assert_eq!(tag.id(), head.id());
+    }
+
+    #[test]
+    fn test_large_blobs_and_emails() {
+        let repo = TestRepo::new();
+        repo.commit_file("big.bin", &"x".repeat(2048), "Add big file");
+        repo.commit_file("small.txt", "small\n", "Add small file");
+
+        let own = head(&repo).author().email().unwrap().to_lowercase();
+        let filter = HistoryFilter {
+            max_blob_size: Some(1024),
+            emails: HashMap::from([(own, "new@example.com".to_string())]),
+            ..Default::default()
+        };
+        let (stats, updates) = rewrite(&repo, &filter);
+
+        assert_eq!(stats.blobs_removed.values().copied().collect::<Vec<_>>(), vec![2048]);
+        assert_eq!(updates.len(), 1);
+        let head = head(&repo);
+        assert_eq!(head.author().email(), Some("new@example.com"));
+        assert_eq!(head.committer().email(), Some("new@example.com"));
+        assert!(head.tree().unwrap().get_path(Path::new("big.bin")).is_err());
+        // The first commit only added the big file, so nothing is left of it
+        assert_eq!(stats.commits_dropped, 1);
+        assert_eq!(head.parent_count(), 0);
+    }
+
+    #[test]
+    fn test_normalizes_implausible_dates() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Initial commit");
+        let now = chrono::Utc::now().timestamp();
+        let future = Signature::new("Time Traveller", "tt@example.com", &git2::Time::new(now + 86400 * 365, 60)).unwrap();
+        let tree = head(&repo).tree().unwrap();
+        repo.repo().commit(Some("HEAD"), &future, &future, "From the future", &tree, &[&head(&repo)]).unwrap();
+
+        // Left alone unless git.normalize_dates is set
+        let (stats, _) = rewrite(&repo, &HistoryFilter::default());
+        assert_eq!(stats.dates_normalized, 0);
+        assert_eq!(head(&repo).author().when().seconds(), now + 86400 * 365);
+
+        let filter = HistoryFilter { normalize_dates: true, ..Default::default() };
+        let (stats, _) = rewrite(&repo, &filter);
+        assert_eq!((stats.dates_normalized, stats.emails_changed), (1, 0));
+        let head = head(&repo);
+        assert_eq!(head.summary(), Some("From the future"));
+        assert!(head.author().when().seconds() - now < 60);
+        assert_eq!(head.committer().when().offset_minutes(), 60);
+        assert_eq!(head.author().email(), Some("tt@example.com"));
+    }
+
+    #[test]
+    fn test_strip_modes() {
+        let repo = TestRepo::new();
+        repo.commit_file("run.sh", "echo hi\n", "Initial commit");
+        let blob = head(&repo).tree().unwrap().get_name("run.sh").unwrap().id();
+        let mut builder = repo.repo().treebuilder(None).unwrap();
+        builder.insert("run.sh", blob, FileMode::BlobExecutable.into()).unwrap();
+        let tree = repo.repo().find_tree(builder.write().unwrap()).unwrap();
+
+        let filter = HistoryFilter { strip_modes: true, ..Default::default() };
+        let mut rewriter = Rewriter::new(repo.repo(), &filter);
+        let stripped = rewriter.rewrite_tree(&tree, "").unwrap().unwrap();
+        assert_eq!(stripped, head(&repo).tree_id());
+    }
+
+    #[test]
+    fn test_filter_requires_an_action() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Initial commit");
+        assert!(HistoryFilter::from_args(repo.repo(), &FilterArgs::default(), &Config::default()).is_err());
+
+        let args = FilterArgs { remove_blobs: vec!["HEAD:a.txt".to_string()], ..Default::default() };
+        let filter = HistoryFilter::from_args(repo.repo(), &args, &Config::default()).unwrap();
+        assert_eq!(filter.blobs.len(), 1);
+    }
+}
@@ -0,0 +1,754 @@
+use anyhow::Result;
+use colored::*;
+use flate2::read::ZlibDecoder;
+use git2::{Direction, FetchOptions, ObjectType, Oid, RemoteCallbacks, Repository};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+use crate::cli::{FsckArgs, RepairMode};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::network;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+
+/// Rounds of fetching objects reachable from ones fetched the round before
+const MAX_FETCH_ROUNDS: usize = 10;
+
+/// Execute the fsck command
+pub async fn execute(args: &FsckArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let report = check(repo, args.full)?;
+    show_report(&report);
+
+    let failing = report.problems.len() + if args.strict { report.warnings.len() } else { 0 };
+    let Some(modes) = &args.repair else {
+        if failing == 0 {
+            ui_println!("{} No problems found", "✅".green().bold());
+            return Ok(());
+        }
+        if !report.problems.is_empty() {
+            ui_println!("{} Try {} to fix what can be fixed", "💡".blue(), "rgit fsck --repair".cyan());
+        }
+        return Err(RgitError::OperationFailed(format!("{} problem{} found", failing, plural(failing))).into());
+    };
+
+    if report.problems.is_empty() {
+        ui_println!("{} Nothing to repair", "✅".green().bold());
+        return Ok(());
+    }
+    let modes = if modes.is_empty() { RepairMode::ALL.to_vec() } else { modes.clone() };
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Repair {} problem{}?", report.problems.len(), plural(report.problems.len())))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    let fixes = repair(repo, &report, &modes, config)?;
+    println!();
+    for fix in &fixes {
+        ui_println!("  {} {}", "🔧".green(), fix);
+    }
+
+    // Check again with a fresh handle so cached objects don't hide anything
+    let fresh = Repository::open(repo.path())?;
+    let remaining = check(&fresh, args.full)?.problems;
+    if remaining.is_empty() {
+        ui_println!("{} Repository repaired", "✅".green().bold());
+        return Ok(());
+    }
+    ui_println!("\n{} Still broken:", "⚠️".yellow());
+    for problem in &remaining {
+        ui_println!("  {} {}", "•".red(), problem);
+    }
+    Err(RgitError::OperationFailed(format!("{} problem{} could not be repaired", remaining.len(), plural(remaining.len()))).into())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn show_report(report: &FsckReport) {
+    ui_println!("{} Checked {} ref{}, {} object{} and {} loose object{}",
+            "🔍".blue(),
+            report.refs_checked, plural(report.refs_checked),
+            report.objects_checked, plural(report.objects_checked),
+            report.loose_checked, plural(report.loose_checked));
+    for problem in &report.problems {
+        ui_println!("  {} {}", "❌".red(), problem);
+    }
+    for warning in &report.warnings {
+        ui_println!("  {} {}", "⚠️".yellow(), warning);
+    }
+}
+
+impl RepairMode {
+    /// Every mode, in the order repairs run: objects first so refs can be
+    /// pointed at anything fetched
+    const ALL: [RepairMode; 4] = [RepairMode::Objects, RepairMode::Fetch, RepairMode::PackedRefs, RepairMode::Refs];
+}
+
+// =============================================================================
+// Checking
+// =============================================================================
+
+/// Something wrong with the repository
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// A loose ref file holding neither an object id nor a symbolic target
+    UnreadableRef { name: String },
+    /// A ref pointing at an object that isn't in the repository
+    RefToMissingObject { name: String, target: Oid },
+    /// A malformed line in `packed-refs` (1-based)
+    CorruptPackedRef { line: usize, text: String },
+    /// A ref that disappeared while its reflog survived
+    MissingRef { name: String, last: Oid },
+    /// A loose object whose content doesn't match its id
+    CorruptLooseObject { id: Oid, path: PathBuf, packed_copy: bool },
+    /// An object referenced from history that isn't in the repository
+    MissingObject { id: Oid, referenced_by: String },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::UnreadableRef { name } => write!(f, "{} is unreadable", name),
+            Problem::RefToMissingObject { name, target } => {
+                write!(f, "{} points to missing object {}", name, shorten_oid(target, 8))
+            }
+            Problem::CorruptPackedRef { line, text } => write!(f, "packed-refs line {} is malformed: {}", line, text),
+            Problem::MissingRef { name, last } => {
+                write!(f, "{} is gone but its reflog remains (last at {})", name, shorten_oid(last, 8))
+            }
+            Problem::CorruptLooseObject { id, packed_copy, .. } => {
+                write!(f, "loose object {} is corrupt{}", shorten_oid(id, 8),
+                       if *packed_copy { " (an intact packed copy exists)" } else { "" })
+            }
+            Problem::MissingObject { id, referenced_by } => {
+                write!(f, "object {} is missing (referenced by {})", shorten_oid(id, 8), referenced_by)
+            }
+        }
+    }
+}
+
+/// The outcome of a check
+#[derive(Debug, Clone, Default)]
+pub struct FsckReport {
+    pub problems: Vec<Problem>,
+    pub warnings: Vec<String>,
+    pub refs_checked: usize,
+    pub objects_checked: usize,
+    pub loose_checked: usize,
+}
+
+/// A ref read straight from disk
+#[derive(Debug, Clone, PartialEq)]
+enum RawRef {
+    Direct(Oid),
+    Symbolic(String),
+}
+
+/// Check refs, reflogs, loose objects and the history reachable from refs.
+///
+/// Refs are read from disk rather than through libgit2 so a corrupt ref
+/// can't stop the check. `full` walks every tree and blob; otherwise only
+/// commits, tags and root trees are checked.
+pub fn check(repo: &Repository, full: bool) -> Result<FsckReport> {
+    let mut report = FsckReport::default();
+    let odb = repo.odb()?;
+    let (refs, unreadable) = read_refs(repo.path(), &mut report)?;
+    report.refs_checked = refs.len() + unreadable.len();
+    report.problems.extend(unreadable.into_iter().map(|name| Problem::UnreadableRef { name }));
+
+    let mut roots = Vec::new();
+    for (name, raw) in &refs {
+        match raw {
+            RawRef::Direct(target) if odb.exists(*target) => roots.push((*target, name.clone())),
+            RawRef::Direct(target) => report.problems.push(Problem::RefToMissingObject { name: name.clone(), target: *target }),
+            RawRef::Symbolic(target) => {
+                // HEAD on an unborn branch is normal
+                if !refs.contains_key(target) && name != "HEAD" {
+                    report.warnings.push(format!("{} points to {}, which doesn't exist", name, target));
+                }
+            }
+        }
+    }
+
+    for (name, last) in orphaned_reflogs(repo.path(), &refs)? {
+        report.problems.push(Problem::MissingRef { name, last });
+    }
+
+    let corrupt = corrupt_loose_objects(repo, &mut report.loose_checked)?;
+    let corrupt_ids: HashSet<Oid> = corrupt.iter().map(|(id, _)| *id).collect();
+    for (id, path) in corrupt {
+        let packed_copy = odb.read(id)
+            .map(|object| Oid::hash_object(object.kind(), object.data()).ok() == Some(id))
+            .unwrap_or(false);
+        report.problems.push(Problem::CorruptLooseObject { id, path, packed_copy });
+    }
+
+    let (checked, missing) = walk_history(repo, &roots, full, &corrupt_ids)?;
+    report.objects_checked = checked;
+    report.problems.extend(missing);
+    Ok(report)
+}
+
+/// Loose and packed refs plus HEAD, and the names of unreadable loose refs
+fn read_refs(git_dir: &Path, report: &mut FsckReport) -> Result<(BTreeMap<String, RawRef>, Vec<String>)> {
+    let mut refs = BTreeMap::new();
+    let mut unreadable = Vec::new();
+
+    let packed = git_dir.join("packed-refs");
+    if let Ok(text) = fs::read_to_string(&packed) {
+        for (n, line) in text.lines().enumerate() {
+            match parse_packed_line(line) {
+                Some(Some((name, id))) => {
+                    refs.insert(name, RawRef::Direct(id));
+                }
+                Some(None) => {}
+                None => report.problems.push(Problem::CorruptPackedRef { line: n + 1, text: line.to_string() }),
+            }
+        }
+    }
+
+    let mut loose = vec![git_dir.join("HEAD")];
+    loose.extend(WalkDir::new(git_dir.join("refs"))
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.extension().is_none_or(|ext| ext != "lock")));
+    for path in loose {
+        let Ok(relative) = path.strip_prefix(git_dir) else { continue };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        match fs::read_to_string(&path).ok().and_then(|text| parse_loose_ref(&text)) {
+            Some(raw) => {
+                refs.insert(name, raw);
+            }
+            None if path.exists() => unreadable.push(name),
+            None => {}
+        }
+    }
+
+    // An unreadable loose ref hides any packed value
+    for name in &unreadable {
+        refs.remove(name);
+    }
+    Ok((refs, unreadable))
+}
+
+fn parse_loose_ref(text: &str) -> Option<RawRef> {
+    let text = text.trim();
+    if let Some(target) = text.strip_prefix("ref: ") {
+        return Some(RawRef::Symbolic(target.trim().to_string()));
+    }
+    parse_oid(text).map(RawRef::Direct)
+}
+
+/// A full-length hex object id
+fn parse_oid(text: &str) -> Option<Oid> {
+    if (text.len() == 40 || text.len() == 64) && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+        Oid::from_str(text).ok()
+    } else {
+        None
+    }
+}
+
+/// A `packed-refs` line: `Some(Some(ref))` for an entry, `Some(None)` for
+/// a header, peel line or blank, `None` when malformed
+fn parse_packed_line(line: &str) -> Option<Option<(String, Oid)>> {
+    if line.is_empty() || line.starts_with('#') {
+        return Some(None);
+    }
+    if let Some(peeled) = line.strip_prefix('^') {
+        return parse_oid(peeled).map(|_| None);
+    }
+    let (id, name) = line.split_once(' ')?;
+    let id = parse_oid(id)?;
+    if !name.starts_with("refs/") || name.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(Some((name.to_string(), id)))
+}
+
+/// Reflogs under `logs/refs` whose ref no longer exists, with the last
+/// value each recorded. Reflogs ending in a deletion are left alone.
+fn orphaned_reflogs(git_dir: &Path, refs: &BTreeMap<String, RawRef>) -> Result<Vec<(String, Oid)>> {
+    let logs = git_dir.join("logs");
+    let mut orphans = Vec::new();
+    for entry in WalkDir::new(logs.join("refs")).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(&logs) else { continue };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        if refs.contains_key(&name) || git_dir.join(&name).exists() {
+            continue;
+        }
+        if let Some(last) = reflog_values(entry.path()).last() {
+            if !last.is_zero() {
+                orphans.push((name, *last));
+            }
+        }
+    }
+    Ok(orphans)
+}
+
+/// The new value of each entry in a reflog file, oldest first
+fn reflog_values(path: &Path) -> Vec<Oid> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.split(' ').nth(1).and_then(parse_oid))
+        .collect()
+}
+
+/// Loose objects whose content doesn't hash to their name
+fn corrupt_loose_objects(repo: &Repository, checked: &mut usize) -> Result<Vec<(Oid, PathBuf)>> {
+    let objects = repo.path().join("objects");
+    let mut corrupt = Vec::new();
+    for dir in fs::read_dir(&objects)?.filter_map(|e| e.ok()) {
+        let prefix = dir.file_name().to_string_lossy().to_string();
+        if prefix.len() != 2 || !prefix.bytes().all(|b| b.is_ascii_hexdigit()) {
+            continue;
+        }
+        for file in fs::read_dir(dir.path())?.filter_map(|e| e.ok()) {
+            let Some(id) = parse_oid(&format!("{}{}", prefix, file.file_name().to_string_lossy())) else { continue };
+            *checked += 1;
+            if !loose_object_is_intact(&file.path(), id) {
+                corrupt.push((id, file.path()));
+            }
+        }
+    }
+    Ok(corrupt)
+}
+
+/// Inflate a loose object and check its header and hash
+fn loose_object_is_intact(path: &Path, id: Oid) -> bool {
+    let Ok(compressed) = fs::read(path) else { return false };
+    let mut data = Vec::new();
+    if ZlibDecoder::new(&compressed[..]).read_to_end(&mut data).is_err() {
+        return false;
+    }
+    let Some(nul) = data.iter().position(|b| *b == 0) else { return false };
+    let header = String::from_utf8_lossy(&data[..nul]).to_string();
+    let Some((kind, size)) = header.split_once(' ') else { return false };
+    let (Some(kind), Ok(size)) = (ObjectType::from_str(kind), size.parse::<usize>()) else { return false };
+    let body = &data[nul + 1..];
+    body.len() == size && Oid::hash_object(kind, body).ok() == Some(id)
+}
+
+/// Walk history from `roots`, returning how many objects were visited and
+/// the missing ones. Parents of shallow commits are not followed, and
+/// corrupt objects are left to the loose object check.
+fn walk_history(repo: &Repository, roots: &[(Oid, String)], full: bool, corrupt: &HashSet<Oid>) -> Result<(usize, Vec<Problem>)> {
+    let odb = repo.odb()?;
+    let shallow: HashSet<Oid> = fs::read_to_string(repo.path().join("shallow"))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(parse_oid)
+        .collect();
+
+    let mut stack: Vec<(Oid, String)> = roots.to_vec();
+    let mut visited = HashSet::new();
+    let mut missing = Vec::new();
+    while let Some((id, referenced_by)) = stack.pop() {
+        if !visited.insert(id) || corrupt.contains(&id) {
+            continue;
+        }
+        if !odb.exists(id) {
+            missing.push(Problem::MissingObject { id, referenced_by });
+            continue;
+        }
+        let Ok(object) = repo.find_object(id, None) else { continue };
+        let label = format!("{} {}", object.kind().map(|k| k.str()).unwrap_or("object"), shorten_oid(&id, 8));
+        if let Some(commit) = object.as_commit() {
+            stack.push((commit.tree_id(), label.clone()));
+            if !shallow.contains(&id) {
+                stack.extend(commit.parent_ids().map(|parent| (parent, label.clone())));
+            }
+        } else if let Some(tag) = object.as_tag() {
+            stack.push((tag.target_id(), label));
+        } else if let (Some(tree), true) = (object.as_tree(), full) {
+            for entry in tree.iter() {
+                // Submodule commits live in another repository
+                if entry.kind() != Some(ObjectType::Commit) {
+                    stack.push((entry.id(), label.clone()));
+                }
+            }
+        }
+    }
+    Ok((visited.len(), missing))
+}
+
+// =============================================================================
+// Repairing
+// =============================================================================
+
+/// Apply the chosen repairs, returning a description of each fix
+pub fn repair(repo: &Repository, report: &FsckReport, modes: &[RepairMode], config: &Config) -> Result<Vec<String>> {
+    let mut fixes = Vec::new();
+    let journal = Journal::for_repo(repo);
+    let mut rechecked: Option<FsckReport> = None;
+
+    for mode in RepairMode::ALL.iter().filter(|mode| modes.contains(mode)) {
+        let report = rechecked.as_ref().unwrap_or(report);
+        match mode {
+            RepairMode::Objects => {
+                for problem in &report.problems {
+                    if let Problem::CorruptLooseObject { id, path, packed_copy: true } = problem {
+                        remove_object_file(path)?;
+                        fixes.push(format!("removed the corrupt loose copy of {}", shorten_oid(id, 8)));
+                    }
+                }
+            }
+            RepairMode::Fetch => {
+                let recovered = fetch_missing(repo, report, config)?;
+                if !recovered.is_empty() {
+                    fixes.push(format!("fetched {} missing object{} from the remotes", recovered.len(), plural(recovered.len())));
+                    // Refs to the fetched objects are whole again and must not be reset or deleted below
+                    rechecked = Some(check(&Repository::open(repo.path())?, true)?);
+                }
+            }
+            RepairMode::PackedRefs => {
+                let lines: Vec<usize> = report.problems.iter()
+                    .filter_map(|p| match p { Problem::CorruptPackedRef { line, .. } => Some(*line), _ => None })
+                    .collect();
+                if !lines.is_empty() {
+                    drop_packed_lines(repo.path(), &lines)?;
+                    fixes.push(format!("dropped {} malformed packed-refs line{}", lines.len(), plural(lines.len())));
+                }
+                let odb = repo.odb()?;
+                for problem in &report.problems {
+                    if let Problem::MissingRef { name, last } = problem {
+                        if odb.exists(*last) {
+                            write_loose_ref(repo.path(), name, *last)?;
+                            journal.record(&JournalEntry::new("fsck --repair", name).with_new_oid(last))?;
+                            fixes.push(format!("recreated {} at {} from its reflog", name, shorten_oid(last, 8)));
+                        }
+                    }
+                }
+            }
+            RepairMode::Refs => {
+                for problem in &report.problems {
+                    let (name, old) = match problem {
+                        Problem::UnreadableRef { name } => (name, None),
+                        Problem::RefToMissingObject { name, target } => (name, Some(*target)),
+                        _ => continue,
+                    };
+                    fixes.push(repair_ref(repo, name, old, config)?);
+                }
+            }
+        }
+    }
+    Ok(fixes)
+}
+
+/// Point a broken ref at the newest reflog entry that still exists. HEAD
+/// falls back to the default branch; other refs without a usable entry are deleted.
+fn repair_ref(repo: &Repository, name: &str, old: Option<Oid>, config: &Config) -> Result<String> {
+    let odb = repo.odb()?;
+    let journal = Journal::for_repo(repo);
+    let good = reflog_values(&repo.path().join("logs").join(name))
+        .into_iter()
+        .rev()
+        .find(|id| !id.is_zero() && odb.exists(*id));
+
+    let mut entry = JournalEntry::new("fsck --repair", name);
+    if let Some(old) = old {
+        entry = entry.with_old_oid(old);
+    }
+    if let Some(good) = good {
+        journal.record(&entry.with_new_oid(good))?;
+        write_loose_ref(repo.path(), name, good)?;
+        return Ok(format!("reset {} to {} from its reflog", name, shorten_oid(&good, 8)));
+    }
+
+    if name == "HEAD" {
+        let branch = format!("refs/heads/{}", default_branch(repo, config));
+        fs::write(repo.path().join("HEAD"), format!("ref: {}\n", branch))?;
+        journal.record(&entry.with_detail(format!("HEAD now points to {}", branch)))?;
+        return Ok(format!("pointed HEAD at {}", branch));
+    }
+
+    journal.record(&entry.with_detail("deleted: no usable reflog entry"))?;
+    let loose = repo.path().join(name);
+    if loose.exists() {
+        fs::remove_file(loose)?;
+    }
+    let packed = repo.path().join("packed-refs");
+    if let Ok(text) = fs::read_to_string(&packed) {
+        let lines: Vec<usize> = text.lines().enumerate()
+            .filter(|(_, line)| line.split_once(' ').map(|(_, n)| n) == Some(name))
+            .map(|(n, _)| n + 1)
+            .collect();
+        drop_packed_lines(repo.path(), &lines)?;
+    }
+    Ok(format!("deleted {} (no usable reflog entry)", name))
+}
+
+/// The branch a repaired HEAD points at: what the remotes call their default
+/// branch, then `init.defaultBranch`, then `git.default_branch`, preferring
+/// one that exists here
+fn default_branch(repo: &Repository, config: &Config) -> String {
+    let mut remotes: Vec<String> = repo.remotes()
+        .map(|names| names.iter().flatten().map(String::from).collect())
+        .unwrap_or_default();
+    remotes.sort_by_key(|remote| *remote != config.git.default_remote);
+
+    let mut candidates: Vec<String> = remotes.iter()
+        .filter_map(|remote| {
+            let head = repo.find_reference(&format!("refs/remotes/{}/HEAD", remote)).ok()?;
+            let target = head.symbolic_target()?;
+            target.strip_prefix(&format!("refs/remotes/{}/", remote)).map(String::from)
+        })
+        .collect();
+    candidates.extend(repo.config().and_then(|c| c.get_string("init.defaultBranch")).ok());
+    candidates.push(config.git.default_branch.clone());
+    candidates.retain(|branch| !branch.is_empty());
+
+    candidates.iter()
+        .find(|branch| repo.find_reference(&format!("refs/heads/{}", branch)).is_ok())
+        .or(candidates.first())
+        .cloned()
+        .unwrap_or_else(|| "main".to_string())
+}
+
+fn write_loose_ref(git_dir: &Path, name: &str, id: Oid) -> Result<()> {
+    let path = git_dir.join(name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, format!("{}\n", id))?;
+    Ok(())
+}
+
+/// Rewrite `packed-refs` without the given 1-based lines and the peel
+/// lines that belong to them
+fn drop_packed_lines(git_dir: &Path, lines: &[usize]) -> Result<()> {
+    let path = git_dir.join("packed-refs");
+    let text = fs::read_to_string(&path)?;
+    let mut kept = String::new();
+    let mut dropping = false;
+    for (n, line) in text.lines().enumerate() {
+        if line.starts_with('^') && dropping {
+            continue;
+        }
+        dropping = lines.contains(&(n + 1));
+        if !dropping {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    fs::write(&path, kept)?;
+    Ok(())
+}
+
+/// Loose object files are read-only
+fn remove_object_file(path: &Path) -> Result<()> {
+    let mut permissions = fs::metadata(path)?.permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(path, permissions)?;
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+/// Fetch every remote into a scratch repository and copy over the objects
+/// the report found missing or corrupt, plus anything those lead to.
+///
+/// A scratch repository is used because a fetch into this one would tell
+/// the server we already have everything our refs point to.
+fn fetch_missing(repo: &Repository, report: &FsckReport, config: &Config) -> Result<Vec<Oid>> {
+    let mut wanted: Vec<Oid> = report.problems.iter()
+        .filter_map(|problem| match problem {
+            Problem::MissingObject { id, .. } => Some(*id),
+            Problem::RefToMissingObject { target, .. } => Some(*target),
+            Problem::CorruptLooseObject { id, packed_copy: false, .. } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if wanted.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut scratch: Vec<(TempDir, Repository)> = Vec::new();
+    for name in repo.remotes()?.iter().flatten() {
+        let Some(url) = network::remote_url(config, &repo.find_remote(name)?, Direction::Fetch) else { continue };
+        let dir = tempfile::tempdir()?;
+        let copy = Repository::init_bare(dir.path())?;
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(network::credentials(config));
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        let fetched = copy.remote_anonymous(&url)
+            .and_then(|mut remote| timings::time(Phase::Network, || remote.fetch(&["+refs/*:refs/*"], Some(&mut options), None)));
+        match fetched {
+            Ok(()) => scratch.push((dir, copy)),
+            Err(e) => ui_println!("  {} Couldn't fetch {}: {}", "⚠️".yellow(), name, e.message()),
+        }
+    }
+
+    let target = repo.odb()?;
+    let mut recovered = Vec::new();
+    for _ in 0..MAX_FETCH_ROUNDS {
+        let mut copied = false;
+        for id in wanted.drain(..) {
+            let found = scratch.iter().find_map(|(_, copy)| {
+                let odb = copy.odb().ok()?;
+                let object = odb.read(id).ok()?;
+                Some((object.kind(), object.data().to_vec()))
+            });
+            let Some((kind, data)) = found else { continue };
+            let loose = repo.path().join("objects").join(&id.to_string()[..2]).join(&id.to_string()[2..]);
+            if loose.exists() {
+                remove_object_file(&loose)?;
+            }
+            target.write(kind, &data)?;
+            recovered.push(id);
+            copied = true;
+        }
+        if !copied {
+            break;
+        }
+
+        // Objects just copied may reference others that are missing too
+        let fresh = Repository::open(repo.path())?;
+        wanted = check(&fresh, true)?.problems.into_iter()
+            .filter_map(|problem| match problem {
+                Problem::MissingObject { id, .. } => Some(id),
+                _ => None,
+            })
+            .collect();
+    }
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use std::io::Write;
+
+    fn object_path(repo: &Repository, id: Oid) -> PathBuf {
+        let hex = id.to_string();
+        repo.path().join("objects").join(&hex[..2]).join(&hex[2..])
+    }
+
+    fn fresh(repo: &TestRepo) -> Repository {
+        Repository::open(repo.path()).unwrap()
+    }
+
+    #[test]
+    fn test_repairs_refs_from_reflogs() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        repo.branch("feature");
+        let second = repo.commit_file("a.txt", "two\n", "Second");
+        assert!(check(repo.repo(), true).unwrap().problems.is_empty());
+
+        // main points nowhere, feature's file is gone but its reflog remains
+        let missing = Oid::from_str("1111111111111111111111111111111111111111").unwrap();
+        fs::write(repo.repo().path().join("refs/heads/main"), format!("{}\n", missing)).unwrap();
+        fs::remove_file(repo.repo().path().join("refs/heads/feature")).unwrap();
+        fs::write(repo.repo().path().join("refs/heads/junk"), "not a ref\n").unwrap();
+
+        let report = check(&fresh(&repo), false).unwrap();
+        assert!(report.problems.contains(&Problem::RefToMissingObject { name: "refs/heads/main".to_string(), target: missing }));
+        assert!(report.problems.contains(&Problem::MissingRef { name: "refs/heads/feature".to_string(), last: first }));
+        assert!(report.problems.contains(&Problem::UnreadableRef { name: "refs/heads/junk".to_string() }));
+
+        repair(&fresh(&repo), &report, &RepairMode::ALL, &Config::default()).unwrap();
+        let repaired = fresh(&repo);
+        assert!(check(&repaired, false).unwrap().problems.is_empty());
+        assert_eq!(repaired.refname_to_id("refs/heads/main").unwrap(), second);
+        assert_eq!(repaired.refname_to_id("refs/heads/feature").unwrap(), first);
+        assert!(repaired.find_reference("refs/heads/junk").is_err());
+    }
+
+    #[test]
+    fn test_removes_corrupt_loose_object_with_packed_copy() {
+        let repo = TestRepo::new();
+        let head = repo.commit_file("a.txt", "one\n", "First");
+
+        // Pack the commit, then corrupt its loose copy
+        let mut builder = repo.repo().packbuilder().unwrap();
+        builder.insert_commit(head).unwrap();
+        let mut buf = git2::Buf::new();
+        builder.write_buf(&mut buf).unwrap();
+        let odb = repo.repo().odb().unwrap();
+        let mut writer = odb.packwriter().unwrap();
+        writer.write_all(&buf).unwrap();
+        writer.commit().unwrap();
+
+        let path = object_path(repo.repo(), head);
+        remove_object_file(&path).unwrap();
+        fs::write(&path, b"garbage").unwrap();
+
+        let report = check(&fresh(&repo), true).unwrap();
+        assert_eq!(report.problems, vec![Problem::CorruptLooseObject { id: head, path: path.clone(), packed_copy: true }]);
+        repair(&fresh(&repo), &report, &[RepairMode::Objects], &Config::default()).unwrap();
+        assert!(!path.exists());
+        assert!(check(&fresh(&repo), true).unwrap().problems.is_empty());
+    }
+
+    #[test]
+    fn test_fetches_missing_objects_from_remote() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        let _remote = repo.add_remote("origin");
+        let blob = repo.repo().revparse_single("HEAD:a.txt").unwrap().id();
+        remove_object_file(&object_path(repo.repo(), blob)).unwrap();
+
+        let report = check(&fresh(&repo), true).unwrap();
+        assert!(matches!(report.problems.as_slice(), [Problem::MissingObject { id, .. }] if *id == blob));
+        repair(&fresh(&repo), &report, &[RepairMode::Fetch], &Config::default()).unwrap();
+        assert!(check(&fresh(&repo), true).unwrap().problems.is_empty());
+    }
+
+    #[test]
+    fn test_fetched_objects_keep_their_refs() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        repo.branch("feature").checkout("feature");
+        let feature = repo.commit_file("b.txt", "two\n", "Feature");
+        let _remote = repo.add_remote("origin");
+        repo.checkout("main");
+
+        // feature's commit is lost and so is its reflog, but the remote has it
+        remove_object_file(&object_path(repo.repo(), feature)).unwrap();
+        fs::remove_file(repo.repo().path().join("logs/refs/heads/feature")).unwrap();
+
+        let report = check(&fresh(&repo), true).unwrap();
+        assert!(report.problems.contains(&Problem::RefToMissingObject { name: "refs/heads/feature".to_string(), target: feature }));
+        repair(&fresh(&repo), &report, &RepairMode::ALL, &Config::default()).unwrap();
+        assert_eq!(fresh(&repo).refname_to_id("refs/heads/feature").unwrap(), feature);
+        assert!(check(&fresh(&repo), true).unwrap().problems.is_empty());
+    }
+
+    #[test]
+    fn test_head_falls_back_to_the_default_branch() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        repo.branch("trunk");
+        repo.repo().config().unwrap().set_str("init.defaultBranch", "trunk").unwrap();
+        fs::write(repo.repo().path().join("HEAD"), "garbage\n").unwrap();
+        fs::remove_file(repo.repo().path().join("logs/HEAD")).unwrap();
+
+        let report = check(&fresh(&repo), false).unwrap();
+        assert!(report.problems.contains(&Problem::UnreadableRef { name: "HEAD".to_string() }));
+        repair(&fresh(&repo), &report, &[RepairMode::Refs], &Config::default()).unwrap();
+        assert_eq!(fs::read_to_string(repo.repo().path().join("HEAD")).unwrap(), "ref: refs/heads/trunk\n");
+    }
+}
@@ -0,0 +1,397 @@
+use anyhow::Result;
+use colored::*;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use crate::cli::GcArgs;
+use crate::config::{Config, GcConfig};
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::utils::{calculate_repo_size, humanize_size};
+
+/// Delta search window git uses by default
+const DEFAULT_WINDOW: u32 = 10;
+/// Delta search window for `--aggressive`
+const AGGRESSIVE_WINDOW: u32 = 250;
+/// Longest delta chain git allows by default
+const DEFAULT_DEPTH: u32 = 50;
+/// Commit-graph chunks libgit2 can read
+const LIBGIT2_GRAPH_CHUNKS: [&[u8]; 6] = [b"OIDF", b"OIDL", b"CDAT", b"EDGE", b"BIDX", b"BDAT"];
+
+/// Execute the gc command
+pub async fn execute(args: &GcArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let expiry = args.prune.clone().unwrap_or_else(|| config.gc.prune_expiry.clone());
+    if !GcConfig::is_valid_expiry(&expiry) {
+        return Err(RgitError::InvalidArgument(format!("'{}' is not a prune expiry; try now, never or 2.weeks.ago", expiry)).into());
+    }
+
+    let stats = ObjectStats::collect(rgit.git_dir())?;
+    if args.auto {
+        match auto_reason(&stats, &config.gc) {
+            Some(reason) => ui_println!("{} Running maintenance: {}", "🧹".blue(), reason),
+            None => {
+                ui_println!("{} Nothing to do: {} loose object{} and {} pack{}",
+                        "✅".green(),
+                        stats.loose_objects, plural(stats.loose_objects),
+                        stats.packs, plural(stats.packs));
+                return Ok(());
+            }
+        }
+    }
+
+    let plan = GcPlan {
+        aggressive: args.aggressive,
+        expiry: Some(expiry).filter(|e| !is_never(e)),
+        window: args.window,
+        depth: args.depth,
+        ..GcPlan::from_config(&config.gc)
+    };
+    run(rgit, &plan)?;
+    Ok(())
+}
+
+/// Run `gc --auto` from other commands: returns whether anything ran
+pub fn auto(rgit: &RgitCore, config: &Config) -> Result<bool> {
+    let stats = ObjectStats::collect(rgit.git_dir())?;
+    let Some(reason) = auto_reason(&stats, &config.gc) else {
+        return Ok(false);
+    };
+    ui_println!("{} Running maintenance: {}", "🧹".blue(), reason);
+    run(rgit, &GcPlan::from_config(&config.gc))?;
+    Ok(true)
+}
+
+/// Write just the commit-graph, without repacking
+pub fn write_commit_graph(rgit: &RgitCore) -> Result<()> {
+    run_steps(rgit, &[Step::commit_graph()])
+}
+
+/// Whether a commit-graph libgit2 can use has been written. libgit2 only
+/// reads a single `objects/info/commit-graph`, not a split chain, and
+/// ignores one with chunks it doesn't know, like git's generation data.
+pub fn has_commit_graph(git_dir: &Path) -> bool {
+    let Ok(graph) = fs::read(git_dir.join("objects").join("info").join("commit-graph")) else {
+        return false;
+    };
+    // "CGPH", version 1, hash version 1, chunk count, base graph count, then 12-byte chunk entries
+    if graph.len() < 8 || &graph[..4] != b"CGPH" || graph[4] != 1 || graph[5] != 1 {
+        return false;
+    }
+    (0..graph[6] as usize).all(|n| {
+        graph.get(8 + n * 12..12 + n * 12).is_some_and(|id| LIBGIT2_GRAPH_CHUNKS.contains(&id))
+    })
+}
+
+fn is_never(expiry: &str) -> bool {
+    expiry.trim().eq_ignore_ascii_case("never")
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+// =============================================================================
+// Object Statistics
+// =============================================================================
+
+/// How the object database is stored
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ObjectStats {
+    pub loose_objects: usize,
+    pub loose_size: u64,
+    pub packs: usize,
+    pub pack_size: u64,
+}
+
+impl ObjectStats {
+    pub fn collect(git_dir: &Path) -> Result<Self> {
+        let objects = git_dir.join("objects");
+        let mut stats = Self::default();
+        for dir in fs::read_dir(&objects)?.filter_map(|e| e.ok()) {
+            let name = dir.file_name().to_string_lossy().to_string();
+            if name.len() != 2 || !name.bytes().all(|b| b.is_ascii_hexdigit()) {
+                continue;
+            }
+            for file in fs::read_dir(dir.path())?.filter_map(|e| e.ok()) {
+                stats.loose_objects += 1;
+                stats.loose_size += file.metadata().map(|m| m.len()).unwrap_or(0);
+            }
+        }
+
+        if let Ok(packs) = fs::read_dir(objects.join("pack")) {
+            for pack in packs.filter_map(|e| e.ok()) {
+                if pack.path().extension().is_some_and(|ext| ext == "pack") {
+                    stats.packs += 1;
+                    stats.pack_size += pack.metadata().map(|m| m.len()).unwrap_or(0);
+                }
+            }
+        }
+        Ok(stats)
+    }
+
+    fn describe(&self) -> String {
+        format!("{} loose object{} ({}), {} pack{} ({})",
+                self.loose_objects, plural(self.loose_objects), humanize_size(self.loose_size),
+                self.packs, plural(self.packs), humanize_size(self.pack_size))
+    }
+}
+
+/// Why the repository needs maintenance, or `None` if it doesn't.
+/// A limit of 0 disables that check.
+pub fn auto_reason(stats: &ObjectStats, config: &GcConfig) -> Option<String> {
+    if config.auto_loose_objects > 0 && stats.loose_objects > config.auto_loose_objects {
+        Some(format!("{} loose objects (limit {})", stats.loose_objects, config.auto_loose_objects))
+    } else if config.auto_pack_limit > 0 && stats.packs > config.auto_pack_limit {
+        Some(format!("{} packs (limit {})", stats.packs, config.auto_pack_limit))
+    } else {
+        None
+    }
+}
+
+// =============================================================================
+// Running
+// =============================================================================
+
+/// What a gc run does
+#[derive(Debug, Clone, PartialEq)]
+pub struct GcPlan {
+    pub aggressive: bool,
+    /// Age at which unreachable objects are deleted; `None` keeps them all
+    pub expiry: Option<String>,
+    pub window: Option<u32>,
+    pub depth: Option<u32>,
+    /// Write a commit-graph so history walks skip parsing commits
+    pub commit_graph: bool,
+    /// Write a multi-pack-index so lookups search one index instead of every pack
+    pub multi_pack_index: bool,
+}
+
+/// One git maintenance command
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    label: &'static str,
+    args: Vec<String>,
+    /// `-c` settings for this command only
+    config: Vec<&'static str>,
+}
+
+impl Step {
+    fn new(label: &'static str, args: &[&str]) -> Self {
+        Self { label, args: args.iter().map(|a| a.to_string()).collect(), config: Vec::new() }
+    }
+
+    /// libgit2 rejects a graph with chunks it doesn't know, so the graph
+    /// keeps to version 1 generations; git otherwise adds generation-data
+    /// chunks. Changed-path filters are included for the git CLI's
+    /// path-limited log; libgit2 accepts and ignores them.
+    fn commit_graph() -> Self {
+        Self {
+            config: vec!["commitGraph.generationVersion=1"],
+            ..Self::new("Writing commit-graph", &["commit-graph", "write", "--reachable", "--changed-paths"])
+        }
+    }
+}
+
+impl GcPlan {
+    /// What `rgit gc` does without options
+    pub fn from_config(config: &GcConfig) -> Self {
+        Self {
+            aggressive: false,
+            expiry: Some(config.prune_expiry.clone()).filter(|e| !is_never(e)),
+            window: None,
+            depth: None,
+            commit_graph: config.commit_graph,
+            multi_pack_index: config.multi_pack_index,
+        }
+    }
+
+    /// The git commands to run, in order.
+    ///
+    /// libgit2 can't repack with delta options, so this mirrors `git gc`
+    /// step by step to report progress between them.
+    ///
+    /// libgit2 (1.7, vendored by git2) picks both files up without any
+    /// setup. Every revwalk and merge-base parses commits through
+    /// `git_commit_list_parse`, which takes parents, dates and generations
+    /// from `objects/info/commit-graph` (loaded in `odb.c`), so log, who
+    /// and ahead/behind counts skip reading commit objects. The pack
+    /// backend finds objects through `objects/pack/multi-pack-index`
+    /// (`refresh_multi_pack_index` in `odb_pack.c`), which speeds up every
+    /// object read, blame's included; blame walks parents itself and gets
+    /// nothing from the commit-graph. The graph is written whole, never
+    /// `--split`, because libgit2 can't read a chain.
+    fn steps(&self) -> Vec<Step> {
+        let step = Step::new;
+        let mut steps = vec![
+            step("Packing refs", &["pack-refs", "--all", "--prune"]),
+            step("Expiring reflogs", &["reflog", "expire", "--all"]),
+        ];
+
+        let mut repack = step("Repacking objects", &["repack", "-d", "-l", "-q"]);
+        match self.expiry.as_deref() {
+            // Unreachable objects would be pruned straight away, so drop them now
+            Some("now") => repack.args.push("-a".to_string()),
+            Some(expiry) => repack.args.extend(["-A".to_string(), format!("--unpack-unreachable={}", expiry)]),
+            None => repack.args.push("-A".to_string()),
+        }
+        if self.aggressive {
+            repack.args.push("-f".to_string());
+        }
+        let default_window = if self.aggressive { AGGRESSIVE_WINDOW } else { DEFAULT_WINDOW };
+        repack.args.push(format!("--window={}", self.window.unwrap_or(default_window)));
+        repack.args.push(format!("--depth={}", self.depth.unwrap_or(DEFAULT_DEPTH)));
+        steps.push(repack);
+
+        if let Some(expiry) = &self.expiry {
+            steps.push(step("Pruning unreachable objects", &["prune", "--expire", expiry]));
+        }
+        steps.push(step("Pruning worktrees", &["worktree", "prune"]));
+        if self.commit_graph {
+            steps.push(Step::commit_graph());
+        }
+        if self.multi_pack_index {
+            steps.push(step("Writing multi-pack-index", &["multi-pack-index", "write"]));
+        }
+        steps
+    }
+}
+
+/// Sizes before and after a run
+#[derive(Debug, Clone, Copy)]
+pub struct GcOutcome {
+    pub before: u64,
+    pub after: u64,
+}
+
+/// Run the plan, printing each step and the space reclaimed
+pub fn run(rgit: &RgitCore, plan: &GcPlan) -> Result<GcOutcome> {
+    let git_dir = rgit.git_dir();
+    let before = calculate_repo_size(git_dir)?;
+    ui_println!("{} Before: {} — {}", "📦".blue(), humanize_size(before).bold(), ObjectStats::collect(git_dir)?.describe());
+
+    run_steps(rgit, &plan.steps())?;
+
+    let after = calculate_repo_size(git_dir)?;
+    ui_println!("{} After:  {} — {}", "📦".blue(), humanize_size(after).bold(), ObjectStats::collect(git_dir)?.describe());
+    if after < before {
+        ui_println!("{} Reclaimed {}", "✅".green().bold(), humanize_size(before - after).green());
+    } else {
+        ui_println!("{} Repository is already compact", "✅".green().bold());
+    }
+    Ok(GcOutcome { before, after })
+}
+
+fn run_steps(rgit: &RgitCore, steps: &[Step]) -> Result<()> {
+    let git_dir = rgit.git_dir();
+    for (n, step) in steps.iter().enumerate() {
+        print!("  [{}/{}] {}... ", n + 1, steps.len(), step.label);
+        std::io::stdout().flush()?;
+        let started = Instant::now();
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(git_dir)
+            .args(step.config.iter().flat_map(|setting| ["-c", setting]))
+            .args(&step.args)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| RgitError::CommandExecutionFailed(format!("gc needs git installed: {}", e)))?;
+        if !output.status.success() {
+            ui_println!("{}", "❌".red());
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().rev().find(|l| !l.trim().is_empty()).unwrap_or("unknown error");
+            return Err(RgitError::CommandExecutionFailed(format!("git {}: {}", step.args[0], reason.trim())).into());
+        }
+        ui_println!("{} {}", "✅".green(), format!("{:.1}s", started.elapsed().as_secs_f64()).dimmed());
+    }
+
+    // Pick up the new packs, commit-graph and multi-pack-index
+    rgit.repo.odb()?.refresh()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_steps_follow_prune_expiry_and_delta_options() {
+        let plan = GcPlan { aggressive: true, expiry: Some("now".to_string()), depth: Some(20), ..GcPlan::from_config(&GcConfig::default()) };
+        let steps = plan.steps();
+        let repack = steps.iter().find(|s| s.args[0] == "repack").unwrap();
+        assert!(repack.args.contains(&"-a".to_string()));
+        assert!(repack.args.contains(&"-f".to_string()));
+        assert!(repack.args.contains(&"--window=250".to_string()));
+        assert!(repack.args.contains(&"--depth=20".to_string()));
+        assert!(steps.iter().any(|s| s.args == ["prune", "--expire", "now"]));
+
+        let keep = GcPlan { expiry: None, window: Some(5), commit_graph: false, ..GcPlan::from_config(&GcConfig::default()) };
+        let steps = keep.steps();
+        let repack = steps.iter().find(|s| s.args[0] == "repack").unwrap();
+        assert!(repack.args.contains(&"-A".to_string()));
+        assert!(!repack.args.iter().any(|a| a.starts_with("--unpack-unreachable")));
+        assert!(repack.args.contains(&"--window=5".to_string()));
+        assert!(!steps.iter().any(|s| s.args[0] == "prune"));
+        assert!(!steps.iter().any(|s| s.args[0] == "commit-graph"));
+    }
+
+    #[test]
+    fn test_auto_reason_uses_limits() {
+        let config = GcConfig { auto_loose_objects: 10, auto_pack_limit: 2, ..GcConfig::default() };
+        let quiet = ObjectStats { loose_objects: 10, packs: 2, ..ObjectStats::default() };
+        assert_eq!(auto_reason(&quiet, &config), None);
+        assert!(auto_reason(&ObjectStats { loose_objects: 11, ..quiet }, &config).unwrap().contains("11 loose objects"));
+        assert!(auto_reason(&ObjectStats { packs: 3, ..quiet }, &config).unwrap().contains("3 packs"));
+
+        let disabled = GcConfig { auto_loose_objects: 0, auto_pack_limit: 0, ..config };
+        assert_eq!(auto_reason(&ObjectStats { loose_objects: 1000, packs: 1000, ..quiet }, &disabled), None);
+    }
+
+    #[test]
+    fn test_run_packs_loose_objects() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        repo.commit_file("a.txt", "two\n", "Second");
+        assert!(ObjectStats::collect(repo.repo().path()).unwrap().loose_objects > 0);
+
+        let rgit = repo.core();
+        let plan = GcPlan { expiry: Some("now".to_string()), ..GcPlan::from_config(&GcConfig::default()) };
+        run(&rgit, &plan).unwrap();
+
+        let stats = ObjectStats::collect(repo.repo().path()).unwrap();
+        assert_eq!((stats.loose_objects, stats.packs), (0, 1));
+        assert!(has_commit_graph(repo.repo().path()));
+        assert!(repo.repo().path().join("objects/pack/multi-pack-index").exists());
+    }
+
+    #[test]
+    fn test_revwalk_reads_the_commit_graph() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        let second = repo.commit_file("a.txt", "two\n", "Second");
+        repo.commit_file("a.txt", "three\n", "Third");
+
+        // git's own default adds generation data, which libgit2 refuses
+        run_steps(&repo.core(), &[Step::new("Writing commit-graph", &["commit-graph", "write", "--reachable"])]).unwrap();
+        assert!(!has_commit_graph(repo.repo().path()));
+        write_commit_graph(&repo.core()).unwrap();
+        assert!(has_commit_graph(repo.repo().path()));
+
+        // With the middle commit gone, only the commit-graph knows its parents
+        let hex = second.to_string();
+        fs::remove_file(repo.repo().path().join("objects").join(&hex[..2]).join(&hex[2..])).unwrap();
+        let walk = |repo: &TestRepo| {
+            let fresh = git2::Repository::open(repo.path()).unwrap();
+            let mut revwalk = fresh.revwalk().unwrap();
+            revwalk.push_head().unwrap();
+            revwalk.collect::<Result<Vec<_>, _>>().map(|commits| commits.len())
+        };
+        assert_eq!(walk(&repo).unwrap(), 3);
+
+        fs::remove_file(repo.repo().path().join("objects/info/commit-graph")).unwrap();
+        assert!(walk(&repo).is_err());
+    }
+}
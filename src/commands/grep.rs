@@ -0,0 +1,406 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Oid, Pathspec, PathspecFlags, Repository, TreeWalkMode, TreeWalkResult};
+use rayon::prelude::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::GrepArgs;
+use crate::config::Config;
+use crate::core::{resolve_object, RgitCore};
+use crate::error::RgitError;
+use crate::validation::validate_pathspec;
+
+/// Bytes inspected for a NUL when deciding whether a file is binary
+const BINARY_PROBE_LEN: usize = 8000;
+
+/// Index entries with this mode are submodules, which have no content to search
+const GITLINK_MODE: u32 = 0o160000;
+
+/// Execute the grep command
+pub async fn execute(args: &GrepArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let matcher = Matcher::new(&args.pattern, &MatchOptions::from(args))?;
+    let source = match &args.tree {
+        Some(tree) => Source::Tree(tree.clone()),
+        None if args.cached => Source::Index,
+        None => Source::Worktree,
+    };
+
+    let cwd = std::env::current_dir()?;
+    let pathspecs = args.files.iter()
+        .map(|file| validate_pathspec(&rgit.repo_path, &cwd, file))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let candidates = collect_candidates(repo, &source, &pathspecs)?;
+    let threads = args.threads.unwrap_or(config.advanced.performance.threads).max(1);
+    let context = Context {
+        before: args.before_context.or(args.context).unwrap_or(0),
+        after: args.after_context.or(args.context).unwrap_or(0),
+    };
+    let results = search(repo.path(), &candidates, &matcher, context, threads)?;
+
+    if results.is_empty() {
+        ui_println!("{} No matches for {}", "🔍".blue(), args.pattern.yellow());
+        return Ok(());
+    }
+
+    let prefix = match &source {
+        Source::Tree(tree) => format!("{}:", tree),
+        _ => String::new(),
+    };
+    let mut first_group = true;
+    for file in &results {
+        let path = format!("{}{}", prefix, file.path);
+        if args.files_with_matches {
+            println!("{}", path.magenta());
+        } else if args.count {
+            println!("{}{}{}", path.magenta(), ":".cyan(), file.count);
+        } else if file.binary {
+            println!("Binary file {} matches", path.magenta());
+        } else {
+            for group in &file.groups {
+                if !context.is_empty() && !first_group {
+                    println!("{}", "--".cyan());
+                }
+                first_group = false;
+                for line in group {
+                    print_line(&path, line, args.line_number);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+/// How the pattern is interpreted
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MatchOptions {
+    pub ignore_case: bool,
+    pub word: bool,
+    pub fixed: bool,
+    pub perl: bool,
+}
+
+impl From<&GrepArgs> for MatchOptions {
+    fn from(args: &GrepArgs) -> Self {
+        Self {
+            ignore_case: args.ignore_case,
+            word: args.word_regexp,
+            fixed: args.fixed_strings,
+            perl: args.perl_regexp,
+        }
+    }
+}
+
+/// A compiled pattern; `Perl` supports lookaround and backreferences at
+/// the cost of backtracking
+pub enum Matcher {
+    Standard(regex::Regex),
+    Perl(fancy_regex::Regex),
+}
+
+impl Matcher {
+    pub fn new(pattern: &str, options: &MatchOptions) -> Result<Self> {
+        let mut expr = if options.fixed { regex::escape(pattern) } else { pattern.to_string() };
+        if options.word {
+            expr = format!(r"\b(?:{})\b", expr);
+        }
+        if options.ignore_case {
+            expr = format!("(?i){}", expr);
+        }
+
+        let invalid = |e: &dyn std::fmt::Display| RgitError::InvalidArgument(format!("Invalid pattern '{}': {}", pattern, e));
+        Ok(if options.perl {
+            Matcher::Perl(fancy_regex::Regex::new(&expr).map_err(|e| invalid(&e))?)
+        } else {
+            Matcher::Standard(regex::Regex::new(&expr).map_err(|e| invalid(&e))?)
+        })
+    }
+
+    /// Byte ranges of every match in `line`; empty when the line doesn't match
+    pub fn find_ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        match self {
+            Matcher::Standard(re) => re.find_iter(line).map(|m| (m.start(), m.end())).collect(),
+            // A backtracking limit error counts as no match rather than aborting the search
+            Matcher::Perl(re) => re.find_iter(line).map_while(|m| m.ok()).map(|m| (m.start(), m.end())).collect(),
+        }
+    }
+
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            Matcher::Standard(re) => re.is_match(text),
+            Matcher::Perl(re) => re.is_match(text).unwrap_or(false),
+        }
+    }
+}
+
+/// Lines of context around each match
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Context {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl Context {
+    fn is_empty(&self) -> bool {
+        self.before == 0 && self.after == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// 1-based line number
+    pub number: usize,
+    pub text: String,
+    /// Match ranges; empty for context lines
+    pub ranges: Vec<(usize, usize)>,
+    pub is_match: bool,
+}
+
+/// Matches in one file, as runs of adjacent lines
+#[derive(Debug, Clone)]
+pub struct FileMatches {
+    pub path: String,
+    pub binary: bool,
+    pub count: usize,
+    pub groups: Vec<Vec<Line>>,
+}
+
+/// Find matching lines in `text`, merging overlapping context into groups
+pub fn match_lines(text: &str, matcher: &Matcher, context: Context) -> (usize, Vec<Vec<Line>>) {
+    let lines: Vec<&str> = text.lines().collect();
+    let ranges: Vec<Vec<(usize, usize)>> = lines.iter().map(|line| matcher.find_ranges(line)).collect();
+    let matches: Vec<usize> = (0..lines.len()).filter(|&i| !ranges[i].is_empty()).collect();
+
+    // Merge the context window of each match with the previous one when they touch
+    let mut windows: Vec<(usize, usize)> = Vec::new();
+    for &i in &matches {
+        let start = i.saturating_sub(context.before);
+        let end = (i + context.after).min(lines.len() - 1);
+        match windows.last_mut() {
+            Some(last) if start <= last.1 + 1 => last.1 = end,
+            _ => windows.push((start, end)),
+        }
+    }
+
+    let groups = windows.into_iter()
+        .map(|(start, end)| (start..=end).map(|i| Line {
+            number: i + 1,
+            text: lines[i].to_string(),
+            ranges: ranges[i].clone(),
+            is_match: !ranges[i].is_empty(),
+        }).collect())
+        .collect();
+    (matches.len(), groups)
+}
+
+fn search_content(path: &str, content: &[u8], matcher: &Matcher, context: Context) -> Option<FileMatches> {
+    let text = String::from_utf8_lossy(content);
+    if content[..content.len().min(BINARY_PROBE_LEN)].contains(&0) {
+        return matcher.is_match(&text).then(|| FileMatches {
+            path: path.to_string(),
+            binary: true,
+            count: text.lines().filter(|line| matcher.is_match(line)).count(),
+            groups: Vec::new(),
+        });
+    }
+
+    let (count, groups) = match_lines(&text, matcher, context);
+    (count > 0).then(|| FileMatches { path: path.to_string(), binary: false, count, groups })
+}
+
+// =============================================================================
+// Sources
+// =============================================================================
+
+/// Where file contents are read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// Tracked files as they are on disk
+    Worktree,
+    /// Staged contents
+    Index,
+    /// Contents of a commit, tag or tree
+    Tree(String),
+}
+
+/// A file to search and where to read it from
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: String,
+    content: Content,
+}
+
+#[derive(Debug, Clone)]
+enum Content {
+    Disk(PathBuf),
+    Blob(Oid),
+}
+
+/// List the files to search, limited to `pathspecs` when any are given
+pub fn collect_candidates(repo: &Repository, source: &Source, pathspecs: &[String]) -> Result<Vec<Candidate>> {
+    let specs: Vec<&String> = pathspecs.iter().filter(|spec| !spec.is_empty()).collect();
+    let filter = if specs.is_empty() { None } else { Some(Pathspec::new(specs)?) };
+    let wanted = |path: &str| filter.as_ref()
+        .is_none_or(|spec| spec.matches_path(Path::new(path), PathspecFlags::DEFAULT));
+
+    let mut candidates = Vec::new();
+    match source {
+        Source::Worktree | Source::Index => {
+            let workdir = repo.workdir();
+            let index = repo.index()?;
+            for entry in index.iter() {
+                if entry.mode == GITLINK_MODE {
+                    continue;
+                }
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+                // Conflicted paths have one entry per stage
+                if candidates.last().is_some_and(|last: &Candidate| last.path == path) || !wanted(&path) {
+                    continue;
+                }
+                let content = match (source, workdir) {
+                    (Source::Worktree, Some(workdir)) => {
+                        let file = workdir.join(&path);
+                        if !file.is_file() {
+                            continue;
+                        }
+                        Content::Disk(file)
+                    }
+                    (Source::Worktree, None) => return Err(RgitError::InvalidRepositoryState(
+                        "bare repository has no working tree; use --cached or --tree".to_string()
+                    ).into()),
+                    _ => Content::Blob(entry.id),
+                };
+                candidates.push(Candidate { path, content });
+            }
+        }
+        Source::Tree(spec) => {
+            let tree = resolve_object(repo, spec)?.peel_to_tree()
+                .map_err(|_| RgitError::InvalidArgument(format!("'{}' does not point to a tree", spec)))?;
+            tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+                if entry.kind() == Some(git2::ObjectType::Blob) {
+                    let path = format!("{}{}", root, entry.name().unwrap_or_default());
+                    if wanted(&path) {
+                        candidates.push(Candidate { path, content: Content::Blob(entry.id()) });
+                    }
+                }
+                TreeWalkResult::Ok
+            })?;
+        }
+    }
+    Ok(candidates)
+}
+
+/// Search `candidates` on `threads` workers, keeping their order
+pub fn search(
+    git_dir: &Path,
+    candidates: &[Candidate],
+    matcher: &Matcher,
+    context: Context,
+    threads: usize,
+) -> Result<Vec<FileMatches>> {
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build()?;
+    let results = pool.install(|| {
+        candidates.par_iter()
+            // Repositories can't be shared between threads, so each worker opens its own
+            .map_init(|| Repository::open(git_dir).ok(), |repo, candidate| {
+                let content = match &candidate.content {
+                    Content::Disk(path) => fs::read(path).ok()?,
+                    Content::Blob(oid) => repo.as_ref()?.find_blob(*oid).ok()?.content().to_vec(),
+                };
+                search_content(&candidate.path, &content, matcher, context)
+            })
+            .collect::<Vec<_>>()
+    });
+    Ok(results.into_iter().flatten().collect())
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+fn print_line(path: &str, line: &Line, line_number: bool) {
+    let separator = if line.is_match { ":".cyan() } else { "-".cyan() };
+    let mut prefix = format!("{}{}", path.magenta(), separator);
+    if line_number {
+        prefix.push_str(&format!("{}{}", line.number.to_string().green(), separator));
+    }
+    println!("{}{}", prefix, highlight(&line.text, &line.ranges));
+}
+
+fn highlight(text: &str, ranges: &[(usize, usize)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    for &(start, end) in ranges {
+        if start < last || start == end {
+            continue;
+        }
+        out.push_str(&text[last..start]);
+        out.push_str(&text[start..end].red().bold().to_string());
+        last = end;
+    }
+    out.push_str(&text[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn matcher(pattern: &str, options: MatchOptions) -> Matcher {
+        Matcher::new(pattern, &options).unwrap()
+    }
+
+    #[test]
+    fn test_pattern_flavors() {
+        let fixed = matcher("a.b", MatchOptions { fixed: true, ..Default::default() });
+        assert_eq!(fixed.find_ranges("axb a.b"), vec![(4, 7)]);
+
+        let word = matcher("cat", MatchOptions { word: true, ignore_case: true, ..Default::default() });
+        assert_eq!(word.find_ranges("Cat concatenate CAT"), vec![(0, 3), (16, 19)]);
+
+        let perl = matcher(r"foo(?=bar)", MatchOptions { perl: true, ..Default::default() });
+        assert_eq!(perl.find_ranges("foobaz foobar"), vec![(7, 10)]);
+        assert!(Matcher::new(r"foo(?=bar)", &MatchOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_context_windows_merge() {
+        let text = "1\nhit\n3\n4\nhit\n6\n7\n8\nhit\n";
+        let (count, groups) = match_lines(text, &matcher("hit", MatchOptions::default()), Context { before: 1, after: 1 });
+        assert_eq!(count, 3);
+        let numbers: Vec<Vec<usize>> = groups.iter().map(|g| g.iter().map(|l| l.number).collect()).collect();
+        assert_eq!(numbers, vec![vec![1, 2, 3, 4, 5, 6], vec![8, 9]]);
+        assert!(groups[1][1].is_match && !groups[1][0].is_match);
+    }
+
+    #[test]
+    fn test_searches_worktree_index_and_tree() {
+        let repo = TestRepo::new();
+        repo.commit_file("src/lib.rs", "fn old() {}\n", "Add lib");
+        repo.commit_file("README.md", "old docs\n", "Add readme");
+        repo.write("src/lib.rs", "fn staged() {}\n").stage("src/lib.rs");
+        repo.write("src/lib.rs", "fn unstaged() {}\n");
+
+        let paths = |source: Source, pattern: &str, specs: &[&str]| -> Vec<String> {
+            let specs: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+            let candidates = collect_candidates(repo.repo(), &source, &specs).unwrap();
+            let matcher = matcher(pattern, MatchOptions::default());
+            search(repo.repo().path(), &candidates, &matcher, Context::default(), 2).unwrap()
+                .into_iter().map(|m| m.path).collect()
+        };
+
+        assert_eq!(paths(Source::Worktree, "unstaged", &[]), vec!["src/lib.rs"]);
+        assert_eq!(paths(Source::Index, "staged", &[]), vec!["src/lib.rs"]);
+        assert!(paths(Source::Index, "unstaged", &[]).is_empty());
+        assert_eq!(paths(Source::Tree("HEAD".into()), "old", &[]), vec!["README.md", "src/lib.rs"]);
+        assert_eq!(paths(Source::Tree("HEAD~1".into()), "old", &["src"]), vec!["src/lib.rs"]);
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::Result;
+use git2::{ObjectType, Oid, Repository};
+use std::io::Read;
+use std::path::Path;
+
+use crate::cli::HashArgs;
+use crate::config::Config;
+use crate::error::RgitError;
+
+/// Execute the hash command
+pub async fn execute(args: &HashArgs, _config: &Config) -> Result<()> {
+    // Only storing the blob needs a repository
+    let repo = if args.write {
+        Some(Repository::discover(".").map_err(|_| RgitError::NotInRepository)?)
+    } else {
+        None
+    };
+
+    if args.stdin {
+        let mut content = Vec::new();
+        std::io::stdin().read_to_end(&mut content)?;
+        println!("{}", hash_blob(repo.as_ref(), &content)?);
+    }
+    for file in &args.files {
+        let content = std::fs::read(file).map_err(|_| RgitError::FileNotFound(Path::new(file).to_path_buf()))?;
+        println!("{}", hash_blob(repo.as_ref(), &content)?);
+    }
+    Ok(())
+}
+
+/// The id `content` has as a blob, stored in `repo` when one is given.
+/// Like `git hash-object --no-filters`, the bytes are hashed exactly as they
+/// are, without line-ending conversion.
+fn hash_blob(repo: Option<&Repository>, content: &[u8]) -> Result<Oid> {
+    Ok(match repo {
+        Some(repo) => repo.blob(content)?,
+        None => Oid::hash_object(ObjectType::Blob, content)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_hash_blob() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("README.md", "hello\n", "Add readme");
+        let repo = test_repo.repo();
+        let committed = repo.head().unwrap().peel_to_tree().unwrap().get_name("README.md").unwrap().id();
+
+        assert_eq!(hash_blob(None, b"hello\n").unwrap(), committed);
+        assert_eq!(committed.to_string(), "ce013625030ba8dba906f756967f9e9ca394464a");
+
+        let oid = hash_blob(None, b"not stored yet\n").unwrap();
+        assert!(repo.find_blob(oid).is_err());
+        assert_eq!(hash_blob(Some(repo), b"not stored yet\n").unwrap(), oid);
+        assert!(repo.find_blob(oid).is_ok());
+    }
+}
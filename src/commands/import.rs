@@ -0,0 +1,407 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{Oid, Repository};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::cli::ImportArgs;
+use crate::commands::{backup, fsck};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::ProgressDisplay;
+use crate::journal::{Journal, JournalEntry};
+use crate::utils::shorten_oid;
+
+/// Execute the import command
+pub async fn execute(args: &ImportArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let input: Box<dyn Read + Send> = match &args.input {
+        Some(path) if path != Path::new("-") => {
+            Box::new(File::open(path).map_err(|_| RgitError::FileNotFound(path.clone()))?)
+        }
+        _ => {
+            if atty::is(atty::Stream::Stdin) {
+                return Err(RgitError::InvalidArgument(
+                    "pipe a fast-import stream into rgit import, or give the file to read".to_string()
+                ).into());
+            }
+            Box::new(io::stdin())
+        }
+    };
+
+    if args.force {
+        backup::snapshot_before(rgit, config, "import")?;
+    }
+    let unborn = repo.head().is_err();
+
+    let bar = config.ui.progress.then(|| {
+        let bar = ProgressDisplay::new("Importing").create_progress_bar();
+        bar.set_message("Importing");
+        bar.enable_steady_tick(std::time::Duration::from_millis(120));
+        bar
+    });
+    let result = fast_import(repo, input, args, |message| match &bar {
+        Some(bar) if !bar.is_hidden() => bar.println(message),
+        _ => println!("{}", message),
+    });
+    if let Some(bar) = &bar {
+        bar.finish_and_clear();
+    }
+    let stats = result?;
+    show_summary(&stats);
+
+    if unborn && repo.head().is_ok() {
+        match repo.checkout_head(Some(CheckoutBuilder::new().safe())) {
+            Ok(()) => ui_println!("{} Checked out {}", "📂".blue(), repo.head()?.shorthand().unwrap_or("HEAD").cyan()),
+            Err(e) => ui_println!("{} Could not check out the imported branch: {}", "⚠️".yellow(), e.message()),
+        }
+    }
+
+    if !args.no_verify {
+        verify(repo)?;
+    }
+    if !stats.rejected.is_empty() {
+        let count = stats.rejected.len();
+        return Err(RgitError::OperationFailed(format!("{} ref{} not updated", count, if count == 1 { " was" } else { "s were" })).into());
+    }
+    Ok(())
+}
+
+/// Check everything the import wrote with a fresh handle, so nothing cached hides a problem
+fn verify(repo: &Repository) -> Result<()> {
+    let report = fsck::check(&Repository::open(repo.path())?, true)?;
+    if report.problems.is_empty() {
+        ui_println!("{} Verified {} objects", "✅".green(), report.objects_checked);
+        return Ok(());
+    }
+    ui_println!("{} The repository has problems after the import:", "❌".red().bold());
+    for problem in &report.problems {
+        ui_println!("  {} {}", "•".red(), problem);
+    }
+    ui_println!("{} Try {} to fix what can be fixed", "💡".blue(), "rgit fsck --repair".cyan());
+    Err(RgitError::OperationFailed(format!("{} problem(s) found after import", report.problems.len())).into())
+}
+
+fn show_summary(stats: &ImportStats) {
+    ui_println!("{} Imported {}", "✅".green().bold(), stats);
+    if !stats.updated.is_empty() {
+        ui_println!("\n{} Refs:", "🔀".blue());
+        for (name, (old, new)) in &stats.updated {
+            let old = old.map(|oid| shorten_oid(&oid, 8)).unwrap_or_else(|| "new".to_string());
+            ui_println!("  {} {} → {}", name.cyan(), old.dimmed(), shorten_oid(new, 8).yellow());
+        }
+    }
+    if !stats.rejected.is_empty() {
+        ui_println!("\n{} Not updated because they would lose commits (use {} to update them anyway):",
+                "⚠️".yellow(), "--force".cyan());
+        for name in &stats.rejected {
+            ui_println!("  {} {}", "•".yellow(), name);
+        }
+    }
+}
+
+// =============================================================================
+// Importing
+// =============================================================================
+
+/// What an import created
+#[derive(Debug, Default)]
+pub struct ImportStats {
+    pub blobs: usize,
+    pub commits: usize,
+    pub tags: usize,
+    /// Refs written, with where they pointed before
+    pub updated: BTreeMap<String, (Option<Oid>, Oid)>,
+    /// Refs left alone because the new commit doesn't descend from the old one
+    pub rejected: Vec<String>,
+}
+
+impl fmt::Display for ImportStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        write!(f, "{} commit{}, {} blob{} and {} tag{}",
+               self.commits, plural(self.commits),
+               self.blobs, plural(self.blobs),
+               self.tags, plural(self.tags))
+    }
+}
+
+/// Feed a fast-import stream to `git fast-import`, then record the refs it
+/// moved in the journal. `report` gets the stream's `progress` messages as
+/// they arrive.
+///
+/// Like git, refs only move forward unless `--force` is given; the ones left
+/// alone end up in `rejected` rather than failing the import.
+pub fn fast_import(
+    repo: &Repository,
+    mut input: impl Read + Send,
+    args: &ImportArgs,
+    mut report: impl FnMut(&str),
+) -> Result<ImportStats> {
+    let before = ref_targets(repo)?;
+
+    let mut command = Command::new("git");
+    command.arg("--git-dir").arg(repo.path()).arg("fast-import");
+    if args.force {
+        command.arg("--force");
+    }
+    if let Some(path) = &args.import_marks {
+        if !path.is_file() {
+            return Err(RgitError::FileNotFound(path.clone()).into());
+        }
+        let mut arg = OsString::from("--import-marks=");
+        arg.push(path);
+        command.arg(arg);
+    }
+    if let Some(path) = &args.export_marks {
+        let mut arg = OsString::from("--export-marks=");
+        arg.push(path);
+        command.arg(arg);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| RgitError::CommandExecutionFailed(format!("import needs git installed: {}", e)))?;
+
+    let mut stdin = child.stdin.take().expect("piped stdin");
+    let stdout = child.stdout.take().expect("piped stdout");
+    let mut stderr = child.stderr.take().expect("piped stderr");
+    let (fed, diagnostics) = std::thread::scope(|scope| -> Result<_> {
+        let feeder = scope.spawn(move || io::copy(&mut input, &mut stdin));
+        let collector = scope.spawn(move || {
+            let mut text = String::new();
+            stderr.read_to_string(&mut text).map(|_| text)
+        });
+        // fast-import echoes the stream's progress commands on standard output
+        for line in BufReader::new(stdout).lines() {
+            if let Some(message) = line?.strip_prefix("progress ") {
+                report(message);
+            }
+        }
+        let fed = feeder.join().unwrap_or_else(|_| Err(io::Error::other("writing the stream failed")));
+        let diagnostics = collector.join().unwrap_or_else(|_| Err(io::Error::other("reading git's output failed")))?;
+        Ok((fed, diagnostics))
+    })?;
+    let status = child.wait()?;
+
+    let rejected: Vec<String> = diagnostics.lines()
+        .filter_map(|line| line.strip_prefix("warning: Not updating "))
+        .filter_map(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+        .collect();
+    let fatal = diagnostics.lines().find_map(|line| line.strip_prefix("fatal: "));
+    if !status.success() && (fatal.is_some() || rejected.is_empty()) {
+        let reason = fatal
+            .or_else(|| diagnostics.lines().rev().find(|line| !line.trim().is_empty()))
+            .unwrap_or("unknown error");
+        return Err(RgitError::CommandExecutionFailed(format!("git fast-import: {}", reason.trim())).into());
+    }
+    // git took the whole stream if it finished without complaint
+    fed?;
+
+    let mut stats = ImportStats {
+        blobs: object_count(&diagnostics, "blobs"),
+        commits: object_count(&diagnostics, "commits"),
+        tags: object_count(&diagnostics, "tags"),
+        rejected,
+        ..Default::default()
+    };
+    let journal = Journal::for_repo(repo);
+    for (name, new) in ref_targets(repo)? {
+        let old = before.get(&name).copied();
+        if old == Some(new) {
+            continue;
+        }
+        let mut entry = JournalEntry::new("import", &name).with_new_oid(new);
+        if let Some(old) = old {
+            entry = entry.with_old_oid(old);
+        }
+        journal.record(&entry)?;
+        stats.updated.insert(name, (old, new));
+    }
+    Ok(stats)
+}
+
+/// Where every direct ref points
+fn ref_targets(repo: &Repository) -> Result<HashMap<String, Oid>> {
+    let mut targets = HashMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+            targets.insert(name.to_string(), oid);
+        }
+    }
+    Ok(targets)
+}
+
+/// New objects of one kind from fast-import's statistics, e.g. the 3 in
+/// `      commits:            3 (         0 duplicates  ...)`
+fn object_count(diagnostics: &str, kind: &str) -> usize {
+    diagnostics.lines()
+        .filter_map(|line| line.trim_start().strip_prefix(kind))
+        .filter_map(|rest| rest.trim_start().strip_prefix(':'))
+        .find_map(|rest| rest.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0)
+}
+
+// =============================================================================
+// Helpers
+// =============================================================================
+
+/// Marks from a file of `:<mark> <id>` lines, as git and rgit write them
+pub fn read_marks(path: &Path) -> Result<HashMap<u64, Oid>> {
+    let content = fs::read_to_string(path).map_err(|_| RgitError::FileNotFound(path.to_path_buf()))?;
+    content.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split_once(' ')
+                .and_then(|(mark, oid)| Some((parse_mark(mark)?, Oid::from_str(oid.trim()).ok()?)))
+                .ok_or_else(|| RgitError::ParseError(format!("invalid mark in {}: {}", path.display(), line)).into())
+        })
+        .collect()
+}
+
+/// `marks` as `:<mark> <id>` lines in mark order
+pub fn format_marks(marks: &HashMap<u64, Oid>) -> String {
+    let mut marks: Vec<_> = marks.iter().collect();
+    marks.sort();
+    marks.into_iter().map(|(mark, oid)| format!(":{} {}\n", mark, oid)).collect()
+}
+
+fn parse_mark(spec: &str) -> Option<u64> {
+    spec.trim().strip_prefix(':')?.parse().ok().filter(|&mark| mark > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use git2::FileMode;
+
+    const STREAM: &str = "\
+feature done
+blob
+mark :1
+data 6
+hello
+
+commit refs/heads/main
+mark :2
+author Ann <ann@example.com> 1700000000 +0100
+committer Ann <ann@example.com> 1700000000 +0100
+data <<EOF
+Initial import
+EOF
+M 644 :1 README.md
+M 755 inline \"bin/run me.sh\"
+data 8
+echo hi
+
+progress halfway
+commit refs/heads/main
+committer Bob <bob@example.com> 1700000100 -0500
+data 7
+Rename
+R \"bin/run me.sh\" tools/run.sh
+D README.md
+
+commit refs/heads/topic
+committer Bob <bob@example.com> 1700000200 +0000
+data 5
+Side
+from :2
+M 100644 :1 notes.txt
+
+tag v1.0
+from refs/heads/main
+tagger Ann <ann@example.com> 1700000300 +0100
+data 8
+Release
+
+done
+";
+
+    fn import(repo: &TestRepo, stream: &str, force: bool) -> Result<ImportStats> {
+        let args = ImportArgs { force, ..Default::default() };
+        let mut messages = Vec::new();
+        let stats = fast_import(repo.repo(), stream.as_bytes(), &args, |message| messages.push(message.to_string()))?;
+        assert!(messages.iter().all(|m| m == "halfway"));
+        Ok(stats)
+    }
+
+    #[test]
+    fn test_import_stream() {
+        let repo = TestRepo::new();
+        let stats = import(&repo, STREAM, false).unwrap();
+        assert_eq!((stats.commits, stats.blobs, stats.tags), (3, 2, 1));
+        assert_eq!(stats.updated.len(), 3);
+
+        let main = repo.repo().revparse_single("refs/heads/main").unwrap().peel_to_commit().unwrap();
+        assert_eq!(main.summary(), Some("Rename"));
+        assert_eq!(main.committer().when().offset_minutes(), -300);
+        let tree = main.tree().unwrap();
+        let script = tree.get_path(Path::new("tools/run.sh")).unwrap();
+        assert_eq!(script.filemode(), i32::from(FileMode::BlobExecutable));
+        assert!(tree.get_path(Path::new("README.md")).is_err());
+        assert!(tree.get_path(Path::new("bin")).is_err());
+
+        let first = main.parent(0).unwrap();
+        assert_eq!(first.author().name(), Some("Ann"));
+        assert_eq!(first.message(), Some("Initial import\n"));
+        let topic = repo.repo().revparse_single("refs/heads/topic").unwrap().peel_to_commit().unwrap();
+        assert_eq!(topic.parent_id(0).unwrap(), first.id());
+
+        let tag = repo.repo().revparse_single("v1.0").unwrap().peel_to_tag().unwrap();
+        assert_eq!(tag.target_id(), main.id());
+
+        // Notes are imported too
+        let notes = "commit refs/notes/commits\ncommitter Ann <ann@example.com> 1700000400 +0000\ndata 6\nNotes\nN inline refs/heads/main\ndata 9\nReviewed\n";
+        import(&repo, notes, false).unwrap();
+        assert_eq!(repo.repo().find_note(None, main.id()).unwrap().message(), Some("Reviewed\n"));
+    }
+
+    #[test]
+    fn test_refuses_to_move_refs_backwards() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Existing");
+        let branch = repo.repo().head().unwrap().name().unwrap().to_string();
+        let existing = repo.head();
+        let stream = format!("commit {}\ncommitter A <a@example.com> 0 +0000\ndata 3\nNew\nM 644 inline b.txt\ndata 2\nb\n", branch);
+
+        let stats = import(&repo, &stream, false).unwrap();
+        assert_eq!(stats.rejected, vec![branch.clone()]);
+        assert_eq!(repo.head(), existing);
+
+        // Continuing from the branch is a fast-forward
+        let stream = stream.replace("data 3\nNew\n", &format!("data 3\nNew\nfrom {}^0\n", branch));
+        let stats = import(&repo, &stream, false).unwrap();
+        assert!(stats.rejected.is_empty());
+        assert_eq!(repo.repo().head().unwrap().peel_to_commit().unwrap().parent_id(0).unwrap(), existing);
+    }
+
+    #[test]
+    fn test_stream_errors() {
+        let repo = TestRepo::new();
+        assert!(import(&repo, "feature done\nblob\ndata 3\nabc\n", false).is_err());
+        let err = import(&repo, "commit refs/heads/x\ndata 2\nhi\n", false).unwrap_err();
+        assert!(err.to_string().contains("git fast-import:"));
+        assert!(repo.repo().find_reference("refs/heads/x").is_err());
+    }
+
+    #[test]
+    fn test_object_count() {
+        let diagnostics = "Total objects:            6 (         0 duplicates                  )\n      blobs  :            2 (         1 duplicates          0 deltas of          0 attempts)\n      commits:            3 (         0 duplicates          0 deltas of          2 attempts)\n";
+        assert_eq!(object_count(diagnostics, "blobs"), 2);
+        assert_eq!(object_count(diagnostics, "commits"), 3);
+        assert_eq!(object_count(diagnostics, "tags"), 0);
+    }
+}
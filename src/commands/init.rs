@@ -19,7 +19,7 @@ pub async fn execute(args: &InitArgs, config: &Config) -> Result<()> {
     // Confirm if directory exists and is not empty
     if target_path.exists() && !is_directory_empty(&target_path)? {
         if !confirm_init_existing_directory(&target_path, config)? {
-            println!("{} Initialization cancelled", "ℹ️".blue());
+            ui_println!("{} Initialization cancelled", "ℹ️".blue());
             return Ok(());
         }
     }
@@ -68,7 +68,7 @@ fn show_init_preview(target_path: &Path, args: &InitArgs, config: &Config) -> Re
         return Ok(());
     }
     
-    println!("{} Git Repository Initialization", "🎯".blue().bold());
+    ui_println!("{} Git Repository Initialization", "🎯".blue().bold());
     println!();
     
     println!("  {} {}", "Directory:".bold(), target_path.display().to_string().cyan());
@@ -117,9 +117,9 @@ fn confirm_init_existing_directory(path: &Path, config: &Config) -> Result<bool>
         .take(5) // Show first 5 entries
         .collect();
     
-    println!("{} Directory is not empty:", "⚠️".yellow());
+    ui_println!("{} Directory is not empty:", "⚠️".yellow());
     for entry in &entries {
-        println!("  {} {}", "•".dimmed(), entry.file_name().to_string_lossy().white());
+        ui_println!("  {} {}", "•".dimmed(), entry.file_name().to_string_lossy().white());
     }
     
     let entry_count = fs::read_dir(path)?.count();
@@ -147,7 +147,7 @@ fn create_repository(path: &Path, args: &InitArgs) -> Result<Repository> {
         Repository::init(path)?
     };
     
-    println!("{} Initialized {} Git repository in {}", 
+    ui_println!("{} Initialized {} Git repository in {}", 
             "✅".green(),
             if args.bare { "bare" } else { "empty" },
             repo.path().display().to_string().cyan());
@@ -162,11 +162,11 @@ fn setup_initial_config(repo: &Repository, args: &InitArgs, config: &Config) ->
     // Set initial branch name if specified
     if let Some(ref branch_name) = args.initial_branch {
         repo_config.set_str("init.defaultBranch", branch_name)?;
-        println!("  {} Set initial branch to '{}'", "🌿".green(), branch_name.cyan());
+        ui_println!("  {} Set initial branch to '{}'", "🌿".green(), branch_name.cyan());
     } else if let Ok(global_config) = git2::Config::open_default() {
         // Check if global default branch is set
         if let Ok(default_branch) = global_config.get_string("init.defaultBranch") {
-            println!("  {} Using default branch '{}'", "🌿".blue(), default_branch.cyan());
+            ui_println!("  {} Using default branch '{}'", "🌿".blue(), default_branch.cyan());
         }
     }
     
@@ -189,31 +189,31 @@ fn apply_template_config(repo: &Repository, template: &GitignoreTemplate, _confi
         GitignoreTemplate::Rust => {
             // Rust-specific configuration
             repo_config.set_str("core.autocrlf", "false")?;
-            println!("  {} Applied Rust project configuration", "🦀".yellow());
+            ui_println!("  {} Applied Rust project configuration", "🦀".yellow());
         }
         GitignoreTemplate::Node => {
             // Node.js-specific configuration
             repo_config.set_str("core.ignorecase", "true")?;
-            println!("  {} Applied Node.js project configuration", "📦".green());
+            ui_println!("  {} Applied Node.js project configuration", "📦".green());
         }
         GitignoreTemplate::Python => {
             // Python-specific configuration
             repo_config.set_str("core.autocrlf", "false")?;
-            println!("  {} Applied Python project configuration", "🐍".blue());
+            ui_println!("  {} Applied Python project configuration", "🐍".blue());
         }
         GitignoreTemplate::Go => {
             // Go-specific configuration
             repo_config.set_str("core.autocrlf", "false")?;
-            println!("  {} Applied Go project configuration", "🔵".cyan());
+            ui_println!("  {} Applied Go project configuration", "🔵".cyan());
         }
         GitignoreTemplate::Java => {
             // Java-specific configuration
             repo_config.set_str("core.autocrlf", "true")?;
-            println!("  {} Applied Java project configuration", "☕".yellow());
+            ui_println!("  {} Applied Java project configuration", "☕".yellow());
         }
         GitignoreTemplate::Default => {
             // Default configuration
-            println!("  {} Applied default configuration", "⚙️".blue());
+            ui_println!("  {} Applied default configuration", "⚙️".blue());
         }
     }
     
@@ -235,7 +235,7 @@ fn setup_recommended_config(repo: &Repository, _config: &Config) -> Result<()> {
         repo_config.set_str("core.autocrlf", "input")?;
     }
     
-    println!("  {} Applied recommended Git configuration", "⚙️".blue());
+    ui_println!("  {} Applied recommended Git configuration", "⚙️".blue());
     Ok(())
 }
 
@@ -246,7 +246,7 @@ fn create_gitignore_file(path: &Path, template: Option<&GitignoreTemplate>, conf
     // Don't overwrite existing .gitignore
     if gitignore_path.exists() {
         if config.ui.interactive {
-            println!("  {} .gitignore already exists, skipping", "ℹ️".blue());
+            ui_println!("  {} .gitignore already exists, skipping", "ℹ️".blue());
         }
         return Ok(());
     }
@@ -258,7 +258,7 @@ fn create_gitignore_file(path: &Path, template: Option<&GitignoreTemplate>, conf
         .map(|t| format!("{:?}", t).to_lowercase())
         .unwrap_or_else(|| "default".to_string());
     
-    println!("  {} Created .gitignore with {} template", "📝".green(), template_name.cyan());
+    ui_println!("  {} Created .gitignore with {} template", "📝".green(), template_name.cyan());
     
     Ok(())
 }
@@ -335,7 +335,7 @@ Add your license information here.
     fs::write(&readme_path, readme_content)?;
     
     if config.ui.interactive {
-        println!("  {} Created README.md", "📖".green());
+        ui_println!("  {} Created README.md", "📖".green());
     }
     
     Ok(())
@@ -382,7 +382,7 @@ fn create_rust_structure(path: &Path, config: &Config) -> Result<()> {
 "#)?;
         
         if config.ui.interactive {
-            println!("  {} Created Rust project structure", "🦀".yellow());
+            ui_println!("  {} Created Rust project structure", "🦀".yellow());
         }
     }
     
@@ -433,7 +433,7 @@ fn create_node_structure(path: &Path, config: &Config) -> Result<()> {
         fs::write(&package_json, package_content)?;
         
         if config.ui.interactive {
-            println!("  {} Created Node.js project structure", "📦".green());
+            ui_println!("  {} Created Node.js project structure", "📦".green());
         }
     }
     
@@ -462,7 +462,7 @@ if __name__ == "__main__":
 "#)?;
         
         if config.ui.interactive {
-            println!("  {} Created Python project structure", "🐍".blue());
+            ui_println!("  {} Created Python project structure", "🐍".blue());
         }
     }
     
@@ -496,7 +496,7 @@ func main() {{
         fs::write(&main_go, main_content)?;
         
         if config.ui.interactive {
-            println!("  {} Created Go project structure", "🔵".cyan());
+            ui_println!("  {} Created Go project structure", "🔵".cyan());
         }
     }
     
@@ -535,7 +535,7 @@ fn create_java_structure(path: &Path, config: &Config) -> Result<()> {
 "#)?;
         
         if config.ui.interactive {
-            println!("  {} Created Java project structure", "☕".yellow());
+            ui_println!("  {} Created Java project structure", "☕".yellow());
         }
     }
     
@@ -551,17 +551,17 @@ fn create_java_structure(path: &Path, config: &Config) -> Result<()> {
 /// Show initialization success message and next steps
 fn show_init_success(path: &Path, args: &InitArgs, config: &Config) -> Result<()> {
     println!();
-    println!("{} Repository initialized successfully!", "🎉".green().bold());
+    ui_println!("{} Repository initialized successfully!", "🎉".green().bold());
     
     if !config.ui.interactive {
         return Ok(());
     }
     
-    println!("\n{} Next steps:", "💡".blue().bold());
+    ui_println!("\n{} Next steps:", "💡".blue().bold());
     
     // Change directory if not current directory
     if path != &std::env::current_dir().unwrap_or_default() {
-        println!("  • {} - Navigate to your repository", 
+        ui_println!("  • {} - Navigate to your repository", 
                 format!("cd {}", path.display()).cyan());
     }
     
@@ -569,23 +569,23 @@ fn show_init_success(path: &Path, args: &InitArgs, config: &Config) -> Result<()
         // Standard repository next steps
         if path.join("README.md").exists() || path.join("main.rs").exists() || 
            path.join("index.js").exists() || path.join("main.py").exists() {
-            println!("  • {} - Add files to staging area", "rgit add .".cyan());
-            println!("  • {} - Make your first commit", "rgit commit -m \"Initial commit\"".cyan());
+            ui_println!("  • {} - Add files to staging area", "rgit add .".cyan());
+            ui_println!("  • {} - Make your first commit", "rgit commit -m \"Initial commit\"".cyan());
         } else {
-            println!("  • {} - Create your project files", "Create files and directories".cyan());
-            println!("  • {} - Add files when ready", "rgit add <files>".cyan());
+            ui_println!("  • {} - Create your project files", "Create files and directories".cyan());
+            ui_println!("  • {} - Add files when ready", "rgit add <files>".cyan());
         }
         
-        println!("  • {} - Add a remote repository", "rgit remote add origin <url>".cyan());
-        println!("  • {} - Push to remote", "rgit push -u origin main".cyan());
+        ui_println!("  • {} - Add a remote repository", "rgit remote add origin <url>".cyan());
+        ui_println!("  • {} - Push to remote", "rgit push -u origin main".cyan());
     } else {
         // Bare repository next steps
-        println!("  • {} - Clone this repository to start working", "git clone <path>".cyan());
-        println!("  • {} - Configure as remote for existing repository", "rgit remote add origin <path>".cyan());
+        ui_println!("  • {} - Clone this repository to start working", "git clone <path>".cyan());
+        ui_println!("  • {} - Configure as remote for existing repository", "rgit remote add origin <path>".cyan());
     }
     
-    println!("  • {} - Check repository status", "rgit status".cyan());
-    println!("  • {} - Get help with commands", "rgit --help".cyan());
+    ui_println!("  • {} - Check repository status", "rgit status".cyan());
+    ui_println!("  • {} - Get help with commands", "rgit --help".cyan());
     
     // Template-specific next steps
     if let Some(ref template) = args.template {
@@ -598,33 +598,33 @@ fn show_init_success(path: &Path, args: &InitArgs, config: &Config) -> Result<()
 
 /// Show template-specific next steps
 fn show_template_next_steps(template: &GitignoreTemplate) -> Result<()> {
-    println!("\n{} Template-specific tips:", "📚".blue().bold());
+    ui_println!("\n{} Template-specific tips:", "📚".blue().bold());
     
     match template {
         GitignoreTemplate::Rust => {
-            println!("  • {} - Build your project", "cargo build".cyan());
-            println!("  • {} - Run your project", "cargo run".cyan());
-            println!("  • {} - Add dependencies in Cargo.toml", "Edit Cargo.toml".cyan());
+            ui_println!("  • {} - Build your project", "cargo build".cyan());
+            ui_println!("  • {} - Run your project", "cargo run".cyan());
+            ui_println!("  • {} - Add dependencies in Cargo.toml", "Edit Cargo.toml".cyan());
         }
         GitignoreTemplate::Node => {
-            println!("  • {} - Install dependencies", "npm install".cyan());
-            println!("  • {} - Run your project", "node index.js".cyan());
-            println!("  • {} - Add dependencies", "npm install <package>".cyan());
+            ui_println!("  • {} - Install dependencies", "npm install".cyan());
+            ui_println!("  • {} - Run your project", "node index.js".cyan());
+            ui_println!("  • {} - Add dependencies", "npm install <package>".cyan());
         }
         GitignoreTemplate::Python => {
-            println!("  • {} - Create virtual environment", "python -m venv venv".cyan());
-            println!("  • {} - Activate virtual environment", "source venv/bin/activate".cyan());
-            println!("  • {} - Install dependencies", "pip install -r requirements.txt".cyan());
+            ui_println!("  • {} - Create virtual environment", "python -m venv venv".cyan());
+            ui_println!("  • {} - Activate virtual environment", "source venv/bin/activate".cyan());
+            ui_println!("  • {} - Install dependencies", "pip install -r requirements.txt".cyan());
         }
         GitignoreTemplate::Go => {
-            println!("  • {} - Build your project", "go build".cyan());
-            println!("  • {} - Run your project", "go run main.go".cyan());
-            println!("  • {} - Add dependencies", "go get <package>".cyan());
+            ui_println!("  • {} - Build your project", "go build".cyan());
+            ui_println!("  • {} - Run your project", "go run main.go".cyan());
+            ui_println!("  • {} - Add dependencies", "go get <package>".cyan());
         }
         GitignoreTemplate::Java => {
-            println!("  • {} - Compile your project", "javac src/main/java/Main.java".cyan());
-            println!("  • {} - Run your project", "java -cp src/main/java Main".cyan());
-            println!("  • {} - Consider using Maven or Gradle", "Build tools".cyan());
+            ui_println!("  • {} - Compile your project", "javac src/main/java/Main.java".cyan());
+            ui_println!("  • {} - Run your project", "java -cp src/main/java Main".cyan());
+            ui_println!("  • {} - Consider using Maven or Gradle", "Build tools".cyan());
         }
         GitignoreTemplate::Default => {
             // No specific tips for default template
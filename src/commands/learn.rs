@@ -6,6 +6,7 @@ use crate::cli::LearnArgs;
 use crate::config::Config;
 use crate::error::RgitError;
 use crate::interactive::{InteractivePrompt, TableDisplay};
+use crate::output::decoration;
 
 /// Execute the learn command - interactive Git tutorials
 pub async fn execute(args: &LearnArgs, config: &Config) -> Result<()> {
@@ -13,7 +14,7 @@ pub async fn execute(args: &LearnArgs, config: &Config) -> Result<()> {
         return Err(RgitError::NonInteractiveEnvironment.into());
     }
 
-    println!("{} {} Interactive Git Learning", "🎓".blue(), "rgit".cyan().bold());
+    ui_println!("{} {} Interactive Git Learning", "🎓".blue(), "rgit".cyan().bold());
     println!("{}", "Welcome to the rgit learning experience!".green());
     println!();
 
@@ -70,7 +71,7 @@ impl TutorialManager {
                 .select()?;
 
             if selection == menu_options.len() - 1 {
-                println!("\n{} Happy learning! 🎉", "✅".green());
+                ui_println!("\n{} Happy learning! 🎉", "✅".green());
                 break;
             }
 
@@ -101,7 +102,7 @@ impl TutorialManager {
         if let Some(tutorial) = tutorial_key.and_then(|key| self.tutorials.get(key)) {
             self.run_tutorial(tutorial, config).await
         } else {
-            println!("{} Tutorial '{}' not found", "❌".red(), name.red());
+            ui_println!("{} Tutorial '{}' not found", "❌".red(), name.red());
             self.suggest_similar_tutorials(name)?;
             Ok(())
         }
@@ -122,10 +123,11 @@ impl TutorialManager {
 
     /// Show tutorial overview
     fn show_tutorial_overview(&self) -> Result<()> {
-        println!("{} Available Tutorials:", "📚".blue().bold());
+        ui_println!("{} Available Tutorials:", "📚".blue().bold());
         println!();
 
         let mut table = TableDisplay::new()
+            .with_wrap(true)
             .with_headers(vec![
                 "Tutorial".to_string(),
                 "Level".to_string(),
@@ -156,9 +158,9 @@ impl TutorialManager {
 
     /// Run a specific tutorial
     async fn run_tutorial(&self, tutorial: &Tutorial, config: &Config) -> Result<()> {
-        println!("\n{} {}", "🎯".blue().bold(), tutorial.title.cyan().bold());
+        ui_println!("\n{} {}", "🎯".blue().bold(), tutorial.title.cyan().bold());
         println!("{}", tutorial.description.dimmed());
-        println!("⏱️  Duration: {} | 📊 Level: {:?}", tutorial.duration, tutorial.level);
+        ui_println!("⏱️  Duration: {} | 📊 Level: {:?}", tutorial.duration, tutorial.level);
         println!();
 
         if !InteractivePrompt::new()
@@ -169,11 +171,11 @@ impl TutorialManager {
 
         // Run tutorial sections
         for (i, section) in tutorial.sections.iter().enumerate() {
-            println!("\n{} Section {}: {}", 
+            ui_println!("\n{} Section {}: {}", 
                     "📖".blue(), 
                     i + 1, 
                     section.title.cyan().bold());
-            println!("{}", "─".repeat(50).dimmed());
+            ui_println!("{}", "─".repeat(50).dimmed());
             
             self.run_tutorial_section(section, config).await?;
             
@@ -197,25 +199,25 @@ impl TutorialManager {
     async fn run_tutorial_section(&self, section: &TutorialSection, _config: &Config) -> Result<()> {
         // Show explanation
         for line in &section.explanation {
-            println!("{}", line);
+            println!("{}", decoration(line));
         }
         println!();
 
         // Show examples
         if !section.examples.is_empty() {
-            println!("{} Examples:", "💡".yellow().bold());
+            ui_println!("{} Examples:", "💡".yellow().bold());
             for example in &section.examples {
-                println!("  {} {}", "•".green(), example.cyan());
+                ui_println!("  {} {}", "•".green(), example.cyan());
             }
             println!();
         }
 
         // Interactive exercises
         if !section.exercises.is_empty() {
-            println!("{} Try it yourself:", "🏃".green().bold());
+            ui_println!("{} Try it yourself:", "🏃".green().bold());
             
             for (i, exercise) in section.exercises.iter().enumerate() {
-                println!("\n{} Exercise {}: {}", "📝".blue(), i + 1, exercise.description);
+                ui_println!("\n{} Exercise {}: {}", "📝".blue(), i + 1, exercise.description);
                 
                 if !exercise.command.is_empty() {
                     println!("   Try running: {}", exercise.command.cyan().bold());
@@ -225,7 +227,7 @@ impl TutorialManager {
                     if InteractivePrompt::new()
                         .with_message("Need a hint?")
                         .confirm()? {
-                        println!("   💡 {}", exercise.hint.yellow());
+                        ui_println!("   💡 {}", exercise.hint.yellow());
                     }
                 }
 
@@ -238,11 +240,11 @@ impl TutorialManager {
 
         // Quiz questions
         if !section.quiz.is_empty() {
-            println!("\n{} Quick Quiz:", "🧠".purple().bold());
+            ui_println!("\n{} Quick Quiz:", "🧠".purple().bold());
             let mut correct_answers = 0;
             
             for (i, question) in section.quiz.iter().enumerate() {
-                println!("\n{} Question {}: {}", "❓".blue(), i + 1, question.question);
+                ui_println!("\n{} Question {}: {}", "❓".blue(), i + 1, question.question);
                 
                 let answer = InteractivePrompt::new()
                     .with_message("Your answer")
@@ -250,15 +252,15 @@ impl TutorialManager {
                     .select()?;
 
                 if answer == question.correct_answer {
-                    println!("   {} Correct!", "✅".green());
+                    ui_println!("   {} Correct!", "✅".green());
                     correct_answers += 1;
                 } else {
-                    println!("   {} Not quite. {}", "❌".red(), question.explanation);
+                    ui_println!("   {} Not quite. {}", "❌".red(), question.explanation);
                 }
             }
             
             let percentage = (correct_answers * 100) / section.quiz.len();
-            println!("\n📊 Quiz Score: {}/{} ({}%)", 
+            ui_println!("\n📊 Quiz Score: {}/{} ({}%)", 
                     correct_answers, 
                     section.quiz.len(), 
                     percentage);
@@ -269,20 +271,20 @@ impl TutorialManager {
 
     /// Show tutorial completion
     fn show_tutorial_completion(&self, tutorial: &Tutorial) -> Result<()> {
-        println!("\n{} Tutorial Complete! 🎉", "🏆".yellow().bold());
+        ui_println!("\n{} Tutorial Complete! 🎉", "🏆".yellow().bold());
         println!("You've successfully completed: {}", tutorial.title.cyan().bold());
         
         if !tutorial.next_steps.is_empty() {
-            println!("\n{} Next Steps:", "🚀".blue().bold());
+            ui_println!("\n{} Next Steps:", "🚀".blue().bold());
             for step in &tutorial.next_steps {
-                println!("  • {}", step.green());
+                ui_println!("  • {}", step.green());
             }
         }
 
         if !tutorial.related_tutorials.is_empty() {
-            println!("\n{} Related Tutorials:", "🔗".blue().bold());
+            ui_println!("\n{} Related Tutorials:", "🔗".blue().bold());
             for related in &tutorial.related_tutorials {
-                println!("  • {}", related.cyan());
+                ui_println!("  • {}", related.cyan());
             }
         }
 
@@ -297,13 +299,13 @@ impl TutorialManager {
             .collect();
 
         if !similar.is_empty() {
-            println!("\n{} Did you mean:", "💡".blue());
+            ui_println!("\n{} Did you mean:", "💡".blue());
             for suggestion in similar {
-                println!("  • {}", suggestion.cyan());
+                ui_println!("  • {}", suggestion.cyan());
             }
         }
 
-        println!("\n{} Use {} to see all available tutorials", 
+        ui_println!("\n{} Use {} to see all available tutorials", 
                 "ℹ️".blue(), 
                 "rgit learn".cyan());
 
@@ -577,7 +579,44 @@ impl Tutorial {
             description: "Advanced Git techniques and power-user features".to_string(),
             level: TutorialLevel::Advanced,
             duration: "30 minutes".to_string(),
-            sections: vec![],
+            sections: vec![
+                TutorialSection {
+                    title: "Inside the Object Database".to_string(),
+                    explanation: vec![
+                        "Git stores everything as objects named by the hash of their content:".to_string(),
+                        "• A blob holds the content of one file, without its name".to_string(),
+                        "• A tree lists the names, modes and objects of one directory".to_string(),
+                        "• A commit points at the top tree, its parents and its message".to_string(),
+                        "• An annotated tag points at another object with a message".to_string(),
+                        "Identical content always gets the same id, so it is stored only once".to_string(),
+                    ],
+                    examples: vec![
+                        "rgit hash README.md        # The blob id of a file's content".to_string(),
+                        "rgit object HEAD           # The raw commit: tree, parents, author".to_string(),
+                        "rgit object HEAD^{tree}    # The entries of the top directory".to_string(),
+                        "rgit object -t <oid>       # Only the type of an object".to_string(),
+                    ],
+                    exercises: vec![
+                        Exercise {
+                            description: "Follow HEAD down to a file's blob".to_string(),
+                            command: "rgit object HEAD && rgit object HEAD^{tree}".to_string(),
+                            hint: "The ids in the tree listing match 'rgit hash' of the committed files".to_string(),
+                        }
+                    ],
+                    quiz: vec![
+                        QuizQuestion {
+                            question: "Two files in different directories have the same content. How many blobs are stored?".to_string(),
+                            options: vec![
+                                "One".to_string(),
+                                "Two".to_string(),
+                                "One per commit that contains them".to_string(),
+                            ],
+                            correct_answer: 0,
+                            explanation: "Blobs are named by their content, and names live in trees, so both entries point at one blob".to_string(),
+                        }
+                    ],
+                },
+            ],
             next_steps: vec![],
             related_tutorials: vec!["submodules".to_string(), "troubleshooting".to_string()],
         }
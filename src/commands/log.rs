@@ -0,0 +1,930 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Delta, Diff, Oid, Patch, Repository, Sort};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::cli::LogArgs;
+use crate::commands::cherry_pick::PickOptions;
+use crate::config::Config;
+use crate::commands::diff::rename_detection;
+use crate::commands::show::describe_move;
+use crate::core::{resolve_revision, DiffOptionsBuilder, RenameDetection, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::output;
+use crate::sequencer;
+use crate::utils::{format_date, format_time_ago, parse_date_spec, shorten_oid};
+use crate::validation::validate_pathspec;
+
+/// Execute the log command
+pub async fn execute(args: &LogArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.interactive {
+        return interactive_pick(args, rgit, config);
+    }
+
+    let (rev, paths) = revision_and_paths(&rgit.repo, args)?;
+    let mut filter = CommitFilter::from_args(args, &rgit.repo, &paths, config)?;
+    let commits = collect_commits(&rgit.repo, args, rev.as_deref(), &mut filter, false)?;
+    if commits.is_empty() {
+        ui_println!("{} No commits to show", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let decorations = if args.decorate { collect_decorations(&rgit.repo)? } else { HashMap::new() };
+    let visible: HashSet<Oid> = commits.iter().map(|c| c.id()).collect();
+    let mut graph = args.graph.then(|| {
+        GraphRenderer::new(if config.ui.icons { &UNICODE_GLYPHS } else { &ASCII_GLYPHS })
+    });
+
+    for commit in &commits {
+        let decoration = decorations.get(&commit.id())
+            .map(|names| format!(" ({})", names.join(", ")).yellow().to_string())
+            .unwrap_or_default();
+        let lines = if args.oneline {
+            vec![oneline(commit, &decoration)]
+        } else {
+            let mut lines = commit_lines(commit, &decoration);
+            if let Some(summary) = filter.follow.as_ref().and_then(|f| f.moves.get(&commit.id())) {
+                lines.push(format!("    {}", summary.yellow()));
+                lines.push(String::new());
+            }
+            if let Some(pickaxe) = &filter.pickaxe {
+                lines.extend(pickaxe.context(&rgit.repo, commit, &filter.paths_at(commit.id()))?);
+            }
+            lines
+        };
+
+        let Some(graph) = graph.as_mut() else {
+            for line in lines {
+                println!("{}", line);
+            }
+            continue;
+        };
+
+        // Filtered history skips commits, so connect rails to the nearest shown ancestor
+        let parents = if filter.is_active() {
+            visible_parents(&rgit.repo, commit, &visible)
+        } else {
+            commit.parent_ids().collect()
+        };
+        let rows = graph.render(commit.id(), &parents);
+
+        if let Some(before) = rows.before {
+            println!("{}", output::decoration(&before));
+        }
+        let mut lines = lines.into_iter();
+        println!("{} {}", output::decoration(&rows.commit), lines.next().unwrap_or_default());
+        if let Some(after) = rows.after {
+            println!("{}", output::decoration(&after));
+        }
+        let rails = graph.rails();
+        for line in lines {
+            println!("{} {}", output::decoration(&rails), line);
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Commit Collection
+// =============================================================================
+
+/// Tokens that only appear in revspecs, never in ordinary paths
+const REVISION_SYNTAX: &[&str] = &["..", "@{", "^", "~", ":/"];
+
+/// Restrictions applied while walking history
+#[derive(Debug, Default)]
+struct CommitFilter {
+    author: Option<String>,
+    since: Option<i64>,
+    until: Option<i64>,
+    grep: Option<Regex>,
+    /// Repository-relative paths
+    paths: Vec<String>,
+    pickaxe: Option<Pickaxe>,
+    follow: Option<Follow>,
+}
+
+impl CommitFilter {
+    fn from_args(args: &LogArgs, repo: &Repository, paths: &[String], config: &Config) -> Result<Self> {
+        let parse = |spec: &Option<String>| -> Result<Option<i64>> {
+            spec.as_ref()
+                .map(|s| parse_date_spec(s).ok_or_else(|| {
+                    RgitError::InvalidArgument(format!("Unrecognized date: {}", s)).into()
+                }))
+                .transpose()
+        };
+
+        let grep = args.grep.as_ref()
+            .map(|pattern| Regex::new(pattern)
+                .map_err(|e| RgitError::InvalidArgument(format!("Invalid --grep pattern: {}", e))))
+            .transpose()?;
+
+        let compile = |pattern: &str, flag: &str| Regex::new(pattern)
+            .map_err(|e| RgitError::InvalidArgument(format!("Invalid {} pattern: {}", flag, e)));
+        let pickaxe = match (&args.pickaxe, &args.diff_grep) {
+            (Some(needle), _) if !needle.is_empty() => {
+                let pattern = if args.pickaxe_regex { needle.clone() } else { regex::escape(needle) };
+                Some(Pickaxe::Occurrences(compile(&pattern, "-S")?))
+            }
+            (_, Some(pattern)) if !pattern.is_empty() => Some(Pickaxe::Lines(compile(pattern, "-G")?)),
+            _ => None,
+        };
+
+        let follow = match (args.follow, paths.len()) {
+            (false, _) => None,
+            (true, 1) => Some(Follow::new(rename_detection(&args.renames, config))),
+            (true, _) => return Err(RgitError::InvalidArgument("--follow requires exactly one path".to_string()).into()),
+        };
+
+        Ok(Self {
+            author: args.author.as_ref().map(|a| a.to_lowercase()),
+            since: parse(&args.since)?,
+            until: parse(&args.until)?,
+            grep,
+            paths: paths.iter().map(|p| repo_relative_path(repo, p)).collect::<Result<_>>()?,
+            pickaxe,
+            follow,
+        })
+    }
+
+    /// Paths the filter selected in `commit`, which differ from the current
+    /// ones for commits before a followed rename
+    fn paths_at(&self, commit: Oid) -> Vec<String> {
+        self.follow.as_ref()
+            .and_then(|follow| follow.paths.get(&commit))
+            .map(|path| vec![path.clone()])
+            .unwrap_or_else(|| self.paths.clone())
+    }
+
+    /// With `--follow`, switch to the old name of the file when `commit`
+    /// created it by renaming or copying another one
+    fn track_renames(&mut self, repo: &Repository, commit: &Commit) -> Result<()> {
+        let Some(follow) = self.follow.as_mut() else { return Ok(()) };
+        let Some(path) = self.paths.first().cloned() else { return Ok(()) };
+        follow.paths.insert(commit.id(), path.clone());
+
+        let touched = first_parent_diff(repo, commit, &self.paths)?;
+        let added = touched.deltas().any(|delta| delta.status() == Delta::Added);
+        if !added || !follow.detection.is_enabled() {
+            return Ok(());
+        }
+
+        // Only the whole tree shows where the file came from
+        let mut diff = first_parent_diff(repo, commit, &[])?;
+        follow.detection.apply(&mut diff)?;
+        let source = diff.deltas().find(|delta| {
+            matches!(delta.status(), Delta::Renamed | Delta::Copied)
+                && delta.new_file().path() == Some(Path::new(&path))
+        });
+        if let Some(delta) = source {
+            if let (Some(summary), Some(old)) = (describe_move(&delta), delta.old_file().path()) {
+                follow.moves.insert(commit.id(), summary);
+                self.paths = vec![old.to_string_lossy().to_string()];
+            }
+        }
+        Ok(())
+    }
+
+    fn is_active(&self) -> bool {
+        self.author.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.grep.is_some()
+            || !self.paths.is_empty()
+            || self.pickaxe.is_some()
+    }
+
+    fn matches(&self, repo: &Repository, commit: &Commit) -> Result<bool> {
+        let time = commit.time().seconds();
+        if self.since.is_some_and(|since| time < since) || self.until.is_some_and(|until| time > until) {
+            return Ok(false);
+        }
+
+        if let Some(author) = &self.author {
+            let sig = commit.author();
+            let name = sig.name().unwrap_or("").to_lowercase();
+            let email = sig.email().unwrap_or("").to_lowercase();
+            if !name.contains(author) && !email.contains(author) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(grep) = &self.grep {
+            if !grep.is_match(commit.message().unwrap_or("")) {
+                return Ok(false);
+            }
+        }
+
+        // Content checks need the diff, so they run last
+        if self.paths.is_empty() && self.pickaxe.is_none() {
+            return Ok(true);
+        }
+
+        let diff = first_parent_diff(repo, commit, &self.paths)?;
+        if diff.deltas().len() == 0 {
+            return Ok(false);
+        }
+
+        match &self.pickaxe {
+            Some(pickaxe) => Ok(!pickaxe.matching_deltas(repo, &diff)?.is_empty()),
+            None => Ok(true),
+        }
+    }
+}
+
+/// State of `--follow` while walking history
+#[derive(Debug)]
+struct Follow {
+    detection: RenameDetection,
+    /// Name of the file in each commit walked
+    paths: HashMap<Oid, String>,
+    /// `renamed: old -> new` for the commits that moved the file
+    moves: HashMap<Oid, String>,
+}
+
+impl Follow {
+    fn new(detection: RenameDetection) -> Self {
+        Self { detection, paths: HashMap::new(), moves: HashMap::new() }
+    }
+}
+
+/// Content search over the changes a commit makes
+#[derive(Debug)]
+enum Pickaxe {
+    /// `-S`: the number of occurrences in a file changes
+    Occurrences(Regex),
+    /// `-G`: an added or removed line matches
+    Lines(Regex),
+}
+
+impl Pickaxe {
+    fn pattern(&self) -> &Regex {
+        match self {
+            Pickaxe::Occurrences(pattern) | Pickaxe::Lines(pattern) => pattern,
+        }
+    }
+
+    /// Indices of the deltas in `diff` that this search selects
+    fn matching_deltas(&self, repo: &Repository, diff: &Diff) -> Result<Vec<usize>> {
+        let mut matching = Vec::new();
+        for (index, delta) in diff.deltas().enumerate() {
+            let selected = match self {
+                Pickaxe::Occurrences(pattern) => {
+                    count_occurrences(repo, delta.old_file().id(), pattern)
+                        != count_occurrences(repo, delta.new_file().id(), pattern)
+                }
+                Pickaxe::Lines(pattern) => match Patch::from_diff(diff, index)? {
+                    Some(patch) => changed_lines(&patch)?.iter().any(|line| pattern.is_match(line)),
+                    None => false,
+                },
+            };
+            if selected {
+                matching.push(index);
+            }
+        }
+        Ok(matching)
+    }
+
+    /// The hunks of `commit` that contain a matching changed line, as log lines
+    fn context(&self, repo: &Repository, commit: &Commit, paths: &[String]) -> Result<Vec<String>> {
+        let diff = first_parent_diff(repo, commit, paths)?;
+        let pattern = self.pattern();
+        let mut lines = Vec::new();
+
+        for index in self.matching_deltas(repo, &diff)? {
+            let Some(patch) = Patch::from_diff(&diff, index)? else { continue };
+            let delta = patch.delta();
+            let path = delta.new_file().path().or_else(|| delta.old_file().path())
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            let mut header_shown = false;
+
+            for hunk_index in 0..patch.num_hunks() {
+                let mut hunk_lines = Vec::new();
+                let mut relevant = false;
+                for line_index in 0..patch.num_lines_in_hunk(hunk_index)? {
+                    let line = patch.line_in_hunk(hunk_index, line_index)?;
+                    let content = String::from_utf8_lossy(line.content()).trim_end_matches(['\n', '\r']).to_string();
+                    hunk_lines.push(match line.origin() {
+                        origin @ ('+' | '-') if pattern.is_match(&content) => {
+                            relevant = true;
+                            let color = if origin == '+' { Color::Green } else { Color::Red };
+                            format!("    {}{}", origin.to_string().color(color), highlight(&content, pattern, color))
+                        }
+                        '+' => format!("    {}", format!("+{}", content).green()),
+                        '-' => format!("    {}", format!("-{}", content).red()),
+                        _ => format!("    {}", format!(" {}", content).dimmed()),
+                    });
+                }
+                if !relevant {
+                    continue;
+                }
+                if !header_shown {
+                    lines.push(format!("    {}", path.bold()));
+                    header_shown = true;
+                }
+                let (hunk, _) = patch.hunk(hunk_index)?;
+                lines.push(format!("    {}", String::from_utf8_lossy(hunk.header()).trim_end().cyan()));
+                lines.extend(hunk_lines);
+            }
+        }
+
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        Ok(lines)
+    }
+}
+
+/// Occurrences of `pattern` in a blob; binary and missing blobs count as none
+fn count_occurrences(repo: &Repository, id: Oid, pattern: &Regex) -> usize {
+    if id.is_zero() {
+        return 0;
+    }
+    repo.find_blob(id)
+        .ok()
+        .filter(|blob| !blob.is_binary())
+        .map(|blob| pattern.find_iter(&String::from_utf8_lossy(blob.content())).count())
+        .unwrap_or(0)
+}
+
+/// Added and removed lines of a patch
+fn changed_lines(patch: &Patch) -> Result<Vec<String>> {
+    let mut changed = Vec::new();
+    for hunk in 0..patch.num_hunks() {
+        for index in 0..patch.num_lines_in_hunk(hunk)? {
+            let line = patch.line_in_hunk(hunk, index)?;
+            if matches!(line.origin(), '+' | '-') {
+                changed.push(String::from_utf8_lossy(line.content()).into_owned());
+            }
+        }
+    }
+    Ok(changed)
+}
+
+/// Color a changed line, making the matches stand out
+fn highlight(text: &str, pattern: &Regex, color: Color) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for found in pattern.find_iter(text) {
+        out.push_str(&text[last..found.start()].color(color).to_string());
+        out.push_str(&found.as_str().color(color).bold().reversed().to_string());
+        last = found.end();
+    }
+    out.push_str(&text[last..].color(color).to_string());
+    out
+}
+
+/// Diff of a commit against its first parent (or the empty tree), limited to `paths`
+fn first_parent_diff<'r>(repo: &'r Repository, commit: &Commit, paths: &[String]) -> Result<Diff<'r>> {
+    let tree = commit.tree()?;
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+
+    let mut opts = DiffOptionsBuilder::new().paths(paths.iter().cloned()).build();
+    Ok(repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut opts))?)
+}
+
+/// Convert a path given relative to the current directory into a repository path
+fn repo_relative_path(repo: &Repository, path: &str) -> Result<String> {
+    match repo.workdir() {
+        Some(workdir) => Ok(validate_pathspec(workdir, &std::env::current_dir()?, path)?),
+        None => Ok(path.to_string()),
+    }
+}
+
+/// Walk history according to the log arguments.
+///
+/// With `exclude_head`, commits reachable from HEAD are hidden so only
+/// commits that could be picked onto the current branch remain; without an
+/// explicit revision every local branch is walked instead.
+fn collect_commits<'r>(
+    repo: &'r Repository,
+    args: &LogArgs,
+    rev: Option<&str>,
+    filter: &mut CommitFilter,
+    exclude_head: bool,
+) -> Result<Vec<Commit<'r>>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    match rev {
+        Some(rev) => resolve_revision(repo, rev)?.push_to(repo, &mut revwalk)?,
+        None if exclude_head || args.all => revwalk.push_glob("refs/heads/*")?,
+        None => revwalk.push_head()?,
+    }
+    if args.all {
+        revwalk.push_glob("refs/remotes/*")?;
+        revwalk.push_glob("refs/tags/*")?;
+        if !exclude_head {
+            // HEAD may be detached from every branch
+            let _ = revwalk.push_head();
+        }
+    }
+    if exclude_head {
+        revwalk.hide_head()?;
+    }
+
+    let mut commits = Vec::new();
+    for oid in revwalk {
+        if commits.len() >= args.limit {
+            break;
+        }
+        let commit = repo.find_commit(oid?)?;
+        let matched = filter.matches(repo, &commit)?;
+        filter.track_renames(repo, &commit)?;
+        if matched {
+            commits.push(commit);
+        }
+    }
+
+    Ok(commits)
+}
+
+/// Split `--rev` and positional arguments into a revision and paths.
+///
+/// Like git, a leading positional argument that is not a file in the working
+/// tree but resolves as a revision (`rgit log main..feature src/`) is taken as
+/// the revision.
+fn revision_and_paths(repo: &Repository, args: &LogArgs) -> Result<(Option<String>, Vec<String>)> {
+    if args.rev.is_some() {
+        return Ok((args.rev.clone(), args.paths.clone()));
+    }
+
+    let Some((first, rest)) = args.paths.split_first() else {
+        return Ok((None, Vec::new()));
+    };
+    if Path::new(first).exists() {
+        return Ok((None, args.paths.clone()));
+    }
+
+    match resolve_revision(repo, first) {
+        Ok(_) => Ok((Some(first.clone()), rest.to_vec())),
+        // Revision syntax that fails to resolve is an error, not a missing path
+        Err(e) if REVISION_SYNTAX.iter().any(|token| first.contains(token)) => Err(e),
+        Err(_) => Ok((None, args.paths.clone())),
+    }
+}
+
+/// Nearest shown ancestor along each parent's first-parent chain
+fn visible_parents(repo: &Repository, commit: &Commit, visible: &HashSet<Oid>) -> Vec<Oid> {
+    const MAX_STEPS: usize = 1000;
+    let mut parents = Vec::new();
+
+    for parent in commit.parent_ids() {
+        let mut current = Some(parent);
+        for _ in 0..MAX_STEPS {
+            let Some(oid) = current else { break };
+            if visible.contains(&oid) {
+                if !parents.contains(&oid) {
+                    parents.push(oid);
+                }
+                break;
+            }
+            current = repo.find_commit(oid).ok().and_then(|c| c.parent_id(0).ok());
+        }
+    }
+
+    parents
+}
+
+/// Map commit ids to the ref names pointing at them, with `HEAD -> branch` first
+pub fn collect_decorations(repo: &Repository) -> Result<HashMap<Oid, Vec<String>>> {
+    let mut decorations: HashMap<Oid, Vec<String>> = HashMap::new();
+    let head = repo.head().ok();
+    let head_branch = head.as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(str::to_string));
+
+    for reference in repo.references()? {
+        let reference = reference?;
+        if !(reference.is_branch() || reference.is_remote() || reference.is_tag()) {
+            continue;
+        }
+        let Some(name) = reference.shorthand() else { continue };
+        let Ok(commit) = reference.peel_to_commit() else { continue };
+
+        let names = decorations.entry(commit.id()).or_default();
+        if reference.is_tag() {
+            names.push(format!("tag: {}", name));
+        } else if reference.is_branch() && head_branch.as_deref() == Some(name) {
+            names.insert(0, format!("HEAD -> {}", name));
+        } else {
+            names.push(name.to_string());
+        }
+    }
+
+    if head_branch.is_none() {
+        if let Some(oid) = head.and_then(|h| h.target()) {
+            decorations.entry(oid).or_default().insert(0, "HEAD".to_string());
+        }
+    }
+
+    Ok(decorations)
+}
+
+fn oneline(commit: &Commit, decoration: &str) -> String {
+    format!("{}{} {}",
+            shorten_oid(&commit.id(), 8).yellow(),
+            decoration,
+            commit.summary().unwrap_or(""))
+}
+
+/// Header and message lines for a commit in the full log format
+pub fn commit_lines(commit: &Commit, decoration: &str) -> Vec<String> {
+    let author = commit.author();
+    let mut lines = vec![
+        format!("{} {}{}", "commit".yellow(), commit.id().to_string().yellow(), decoration),
+        format!("Author: {} <{}>", author.name().unwrap_or("Unknown"), author.email().unwrap_or("")),
+        format!("Date:   {} ({})", format_date(commit.time()), format_time_ago(commit.time()).dimmed()),
+        String::new(),
+    ];
+    for line in commit.message().unwrap_or("").trim_end().lines() {
+        lines.push(format!("    {}", line));
+    }
+    lines.push(String::new());
+    lines
+}
+
+// =============================================================================
+// Graph Rendering
+// =============================================================================
+
+/// Characters used to draw the commit graph
+struct GraphGlyphs {
+    commit: char,
+    rail: char,
+    horizontal: char,
+    cross: char,
+    tee_right: char,
+    tee_left: char,
+    tee_both: char,
+    join_end: char,
+    join_mid: char,
+    fork_right: char,
+    fork_left: char,
+    fork_mid: char,
+}
+
+const UNICODE_GLYPHS: GraphGlyphs = GraphGlyphs {
+    commit: '●',
+    rail: '│',
+    horizontal: '─',
+    cross: '┼',
+    tee_right: '├',
+    tee_left: '┤',
+    tee_both: '┼',
+    join_end: '╯',
+    join_mid: '┴',
+    fork_right: '╮',
+    fork_left: '╭',
+    fork_mid: '┬',
+};
+
+const ASCII_GLYPHS: GraphGlyphs = GraphGlyphs {
+    commit: '*',
+    rail: '|',
+    horizontal: '-',
+    cross: '+',
+    tee_right: '|',
+    tee_left: '|',
+    tee_both: '+',
+    join_end: '\'',
+    join_mid: '\'',
+    fork_right: '.',
+    fork_left: '.',
+    fork_mid: '.',
+};
+
+/// Colors cycled through for graph lanes
+const LANE_COLORS: [Color; 6] = [
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+];
+
+/// Graph output for a single commit
+#[derive(Debug)]
+struct GraphRows {
+    /// Lanes converging into the commit's lane
+    before: Option<String>,
+    /// The commit itself
+    commit: String,
+    /// Lanes forking to the commit's additional parents
+    after: Option<String>,
+}
+
+/// Lane-based graph renderer.
+///
+/// Each lane remembers the commit it is waiting for. Commits must be fed in
+/// topological order (children before parents).
+struct GraphRenderer {
+    lanes: Vec<Option<Oid>>,
+    glyphs: &'static GraphGlyphs,
+}
+
+impl GraphRenderer {
+    fn new(glyphs: &'static GraphGlyphs) -> Self {
+        Self { lanes: Vec::new(), glyphs }
+    }
+
+    /// Render one commit and advance the lanes to its parents
+    fn render(&mut self, oid: Oid, parents: &[Oid]) -> GraphRows {
+        let g = self.glyphs;
+        let column = match self.lanes.iter().position(|lane| *lane == Some(oid)) {
+            Some(column) => column,
+            None => self.free_lane(0),
+        };
+
+        // Lanes to the right that were also waiting for this commit end here
+        let merging: Vec<usize> = self.lanes.iter().enumerate()
+            .filter(|(i, lane)| *i != column && **lane == Some(oid))
+            .map(|(i, _)| i)
+            .collect();
+
+        let before = merging.last().map(|&last| {
+            let row = self.connector_row(column, last, |i| {
+                if i == column {
+                    Some(g.tee_right)
+                } else if merging.contains(&i) {
+                    Some(if i == last { g.join_end } else { g.join_mid })
+                } else {
+                    None
+                }
+            });
+            for &i in &merging {
+                self.lanes[i] = None;
+            }
+            row
+        });
+
+        self.lanes[column] = Some(oid);
+        let commit = self.row(|i, lane| if i == column { Some(g.commit) } else { lane.map(|_| g.rail) }, None);
+
+        // The first parent continues the lane; other parents join or open lanes
+        self.lanes[column] = parents.first().copied();
+        let mut targets: Vec<(usize, bool)> = Vec::new();
+        for &parent in parents.iter().skip(1) {
+            match self.lanes.iter().position(|lane| *lane == Some(parent)) {
+                Some(existing) if existing != column => targets.push((existing, false)),
+                Some(_) => {}
+                None => {
+                    let lane = self.free_lane(column + 1);
+                    self.lanes[lane] = Some(parent);
+                    targets.push((lane, true));
+                }
+            }
+        }
+
+        let after = if targets.is_empty() {
+            None
+        } else {
+            let lo = targets.iter().map(|t| t.0).fold(column, usize::min);
+            let hi = targets.iter().map(|t| t.0).fold(column, usize::max);
+            Some(self.connector_row(lo, hi, |i| {
+                if i == column {
+                    return Some(match (lo < column, hi > column) {
+                        (true, true) => g.tee_both,
+                        (true, false) => g.tee_left,
+                        _ => g.tee_right,
+                    });
+                }
+                let &(_, new) = targets.iter().find(|t| t.0 == i)?;
+                let outer = i == lo || i == hi;
+                Some(match (new, outer, i > column) {
+                    (true, true, true) => g.fork_right,
+                    (true, true, false) => g.fork_left,
+                    (true, false, _) => g.fork_mid,
+                    (false, true, true) => g.tee_left,
+                    (false, true, false) => g.tee_right,
+                    (false, false, _) => g.cross,
+                })
+            }))
+        };
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+
+        GraphRows { before, commit, after }
+    }
+
+    /// Rails for detail lines printed below the current commit
+    fn rails(&self) -> String {
+        self.row(|_, lane| lane.map(|_| self.glyphs.rail), None)
+    }
+
+    /// First empty lane at or after `start`, growing the lane list if needed
+    fn free_lane(&mut self, start: usize) -> usize {
+        if let Some(offset) = self.lanes.iter().skip(start).position(Option::is_none) {
+            return start + offset;
+        }
+        if self.lanes.len() < start {
+            self.lanes.resize(start, None);
+        }
+        self.lanes.push(None);
+        self.lanes.len() - 1
+    }
+
+    /// A row with a horizontal connection spanning lanes `lo..=hi`
+    fn connector_row(&self, lo: usize, hi: usize, special: impl Fn(usize) -> Option<char>) -> String {
+        let g = self.glyphs;
+        self.row(|i, lane| {
+            if let Some(c) = special(i) {
+                Some(c)
+            } else if i > lo && i < hi {
+                Some(if lane.is_some() { g.cross } else { g.horizontal })
+            } else {
+                lane.map(|_| g.rail)
+            }
+        }, Some((lo, hi)))
+    }
+
+    /// Build a row from per-lane glyphs, filling the gaps inside `span` horizontally
+    fn row(&self, glyph: impl Fn(usize, Option<Oid>) -> Option<char>, span: Option<(usize, usize)>) -> String {
+        let mut out = String::new();
+        for (i, lane) in self.lanes.iter().enumerate() {
+            let color = LANE_COLORS[i % LANE_COLORS.len()];
+            let c = glyph(i, *lane).unwrap_or(' ');
+            out.push_str(&c.to_string().color(color).to_string());
+
+            match span {
+                Some((lo, hi)) if i >= lo && i < hi => {
+                    out.push_str(&self.glyphs.horizontal.to_string().color(color).to_string());
+                }
+                _ => out.push(' '),
+            }
+        }
+        out.trim_end().to_string()
+    }
+}
+
+// =============================================================================
+// Interactive Cherry-Pick
+// =============================================================================
+
+/// Let the user pick commits from the log and cherry-pick them in order
+fn interactive_pick(args: &LogArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+
+    // Commits already on HEAD cannot be picked onto it
+    let (rev, paths) = revision_and_paths(&rgit.repo, args)?;
+    let mut filter = CommitFilter::from_args(args, &rgit.repo, &paths, config)?;
+    let commits = collect_commits(&rgit.repo, args, rev.as_deref(), &mut filter, true)?;
+    if commits.is_empty() {
+        ui_println!("{} No commits outside the current branch to cherry-pick", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let options: Vec<String> = commits.iter()
+        .map(|commit| format!("{} {} ({}, {})",
+                shorten_oid(&commit.id(), 8),
+                commit.summary().unwrap_or(""),
+                commit.author().name().unwrap_or("Unknown"),
+                format_time_ago(commit.time())))
+        .collect();
+
+    let selected = InteractivePrompt::new()
+        .with_message("Select commits to cherry-pick onto the current branch")
+        .with_options(&options)
+        .multiselect_prompt()?;
+
+    if selected.is_empty() {
+        ui_println!("{} No commits selected", "ℹ️".blue());
+        return Ok(());
+    }
+
+    // The log lists newest first; apply oldest first
+    let mut picks: Vec<Oid> = selected.iter().map(|&i| commits[i].id()).collect();
+    picks.reverse();
+
+    sequencer::start(rgit, picks, PickOptions::default(), config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn render(graph: &mut GraphRenderer, commit: u8, parents: &[u8]) -> (Option<String>, String, Option<String>) {
+        let parents: Vec<Oid> = parents.iter().map(|&p| oid(p)).collect();
+        let rows = graph.render(oid(commit), &parents);
+        let strip = |s: &str| console::strip_ansi_codes(s).to_string();
+        (rows.before.as_deref().map(strip), strip(&rows.commit), rows.after.as_deref().map(strip))
+    }
+
+    #[test]
+    fn test_linear_history_uses_one_lane() {
+        let mut graph = GraphRenderer::new(&ASCII_GLYPHS);
+
+        assert_eq!(render(&mut graph, 3, &[2]), (None, "*".to_string(), None));
+        assert_eq!(render(&mut graph, 2, &[1]), (None, "*".to_string(), None));
+        assert_eq!(render(&mut graph, 1, &[]), (None, "*".to_string(), None));
+        assert!(graph.lanes.is_empty());
+    }
+
+    #[test]
+    fn test_merge_opens_and_closes_lane() {
+        let mut graph = GraphRenderer::new(&UNICODE_GLYPHS);
+
+        // 4 merges 3 (main) and 2 (feature); both descend from 1
+        assert_eq!(render(&mut graph, 4, &[3, 2]), (None, "●".to_string(), Some("├─╮".to_string())));
+        assert_eq!(render(&mut graph, 3, &[1]).1, "● │");
+        assert_eq!(render(&mut graph, 2, &[1]).1, "│ ●");
+
+        let (before, commit, after) = render(&mut graph, 1, &[]);
+        assert_eq!(before.as_deref(), Some("├─╯"));
+        assert_eq!(commit, "●");
+        assert!(after.is_none());
+        assert!(graph.lanes.is_empty());
+    }
+
+    #[test]
+    fn test_rails_reflect_open_lanes() {
+        let mut graph = GraphRenderer::new(&ASCII_GLYPHS);
+
+        // Two branch tips that have not met yet
+        render(&mut graph, 5, &[3]);
+        assert_eq!(graph.rails(), "|");
+        render(&mut graph, 4, &[2]);
+        assert_eq!(graph.rails(), "| |");
+    }
+
+    #[test]
+    fn test_content_filters() {
+        let test_repo = TestRepo::new();
+        let repo = test_repo.repo();
+
+        let add = test_repo.commit_file("src/lib.rs", "fn helper() {}\n", "feat: add helper");
+        let docs = test_repo.commit_file("README.md", "helper docs\n", "docs: describe helper");
+        let reword = test_repo.commit_file("src/lib.rs", "fn helper() {} // tweak\n", "fix: tweak helper");
+
+        let matching = |filter: &CommitFilter| -> Vec<Oid> {
+            [add, docs, reword].into_iter()
+                .filter(|oid| filter.matches(repo, &repo.find_commit(*oid).unwrap()).unwrap())
+                .collect()
+        };
+
+        let grep = CommitFilter { grep: Some(Regex::new("^(feat|fix):").unwrap()), ..Default::default() };
+        assert_eq!(matching(&grep), vec![add, reword]);
+
+        let paths = CommitFilter { paths: vec!["src".to_string()], ..Default::default() };
+        assert_eq!(matching(&paths), vec![add, reword]);
+
+        // Only commits that change how often the string occurs
+        let pickaxe = CommitFilter { pickaxe: Some(Pickaxe::Occurrences(Regex::new("fn helper").unwrap())), ..Default::default() };
+        assert_eq!(matching(&pickaxe), vec![add]);
+
+        let combined = CommitFilter {
+            pickaxe: Some(Pickaxe::Occurrences(Regex::new("helper").unwrap())),
+            paths: vec!["README.md".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(matching(&combined), vec![docs]);
+        assert!(combined.is_active());
+    }
+
+    #[test]
+    fn test_diff_grep_and_pickaxe_context() {
+        let test_repo = TestRepo::new();
+        let repo = test_repo.repo();
+
+        let add = test_repo.commit_file("src/lib.rs", "fn helper() {}\nfn other() {}\n", "Add helpers");
+        let rename = test_repo.commit_file("src/lib.rs", "fn assist() {}\nfn other() {}\n", "Rename helper");
+        let remove = test_repo.commit_file("src/lib.rs", "fn other() {}\n", "Remove assist");
+
+        let filter = CommitFilter { pickaxe: Some(Pickaxe::Lines(Regex::new(r"fn \w+\(").unwrap())), ..Default::default() };
+        let matching: Vec<Oid> = [add, rename, remove].into_iter()
+            .filter(|oid| filter.matches(repo, &repo.find_commit(*oid).unwrap()).unwrap())
+            .collect();
+        assert_eq!(matching, vec![add, rename, remove]);
+
+        // The rename leaves "fn other" alone, so -S doesn't select it
+        let pickaxe = Pickaxe::Occurrences(Regex::new("fn other").unwrap());
+        let diff = first_parent_diff(repo, &repo.find_commit(rename).unwrap(), &[]).unwrap();
+        assert!(pickaxe.matching_deltas(repo, &diff).unwrap().is_empty());
+
+        // The hunk that removed the function is shown with its context
+        let pickaxe = Pickaxe::Occurrences(Regex::new("assist").unwrap());
+        let lines: Vec<String> = pickaxe.context(repo, &repo.find_commit(remove).unwrap(), &[]).unwrap()
+            .iter().map(|line| console::strip_ansi_codes(line).to_string()).collect();
+        assert_eq!(lines[0], "    src/lib.rs");
+        assert!(lines[1].starts_with("    @@ -1,2 +1 @@"));
+        assert_eq!(&lines[2..4], ["    -fn assist() {}", "     fn other() {}"]);
+    }
+}
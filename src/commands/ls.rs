@@ -0,0 +1,130 @@
+use anyhow::Result;
+use git2::{ObjectType, Odb, Repository, Tree, TreeEntry, TreeWalkMode, TreeWalkResult};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::cli::LsArgs;
+use crate::config::Config;
+use crate::core::{Revision, RgitCore};
+use crate::error::RgitError;
+use crate::validation::validate_pathspec;
+
+/// One line of the listing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Entry {
+    /// Octal file mode, e.g. 100644
+    mode: String,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    oid: String,
+    /// Blob size in bytes; `null` for trees and submodules
+    size: Option<usize>,
+    path: String,
+}
+
+/// Execute the ls command
+pub async fn execute(args: &LsArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let Revision::Single(object) = rgit.resolve_revision(&args.revision)? else {
+        return Err(RgitError::InvalidArgument(format!("{} is a range, not a commit or tree", args.revision)).into());
+    };
+    let tree = object.peel_to_tree()
+        .map_err(|_| RgitError::InvalidObjectId(format!("{} is not a commit or tree", args.revision)))?;
+
+    // Like git ls-tree, paths are relative to the current directory
+    let path = match (&args.path, rgit.repo.workdir()) {
+        (Some(path), Some(workdir)) => validate_pathspec(workdir, &std::env::current_dir()?, path)?,
+        (Some(path), None) => path.trim_matches('/').to_string(),
+        (None, _) => String::new(),
+    };
+    let entries = list(&rgit.repo, &tree, &path, args.recursive)?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else if args.name_only {
+        for entry in &entries {
+            println!("{}", entry.path);
+        }
+    } else {
+        for entry in &entries {
+            let size = entry.size.map(|size| size.to_string()).unwrap_or_else(|| "-".to_string());
+            println!("{} {} {} {:>8}\t{}", entry.mode, entry.kind, entry.oid, size, entry.path);
+        }
+    }
+    Ok(())
+}
+
+/// Entries of the directory at `path` in `tree`, or the single entry when
+/// `path` is a file. Recursive listings leave out the directories themselves.
+fn list(repo: &Repository, tree: &Tree, path: &str, recursive: bool) -> Result<Vec<Entry>> {
+    let odb = repo.odb()?;
+    let dir = if path.is_empty() {
+        tree.clone()
+    } else {
+        let entry = tree.get_path(Path::new(path))
+            .map_err(|_| RgitError::InvalidArgument(format!("'{}' does not exist in that revision", path)))?;
+        if entry.kind() != Some(ObjectType::Tree) {
+            return Ok(vec![to_entry(&odb, &entry, path.to_string())]);
+        }
+        entry.to_object(repo)?.peel_to_tree()?
+    };
+    let prefix = if path.is_empty() { String::new() } else { format!("{}/", path) };
+
+    let mut entries = Vec::new();
+    if recursive {
+        dir.walk(TreeWalkMode::PreOrder, |parent, entry| {
+            if entry.kind() != Some(ObjectType::Tree) {
+                let name = entry.name().unwrap_or("");
+                entries.push(to_entry(&odb, entry, format!("{}{}{}", prefix, parent, name)));
+            }
+            TreeWalkResult::Ok
+        })?;
+    } else {
+        for entry in dir.iter() {
+            let name = entry.name().unwrap_or("");
+            entries.push(to_entry(&odb, &entry, format!("{}{}", prefix, name)));
+        }
+    }
+    Ok(entries)
+}
+
+fn to_entry(odb: &Odb, entry: &TreeEntry, path: String) -> Entry {
+    let (kind, size) = match entry.kind() {
+        Some(ObjectType::Tree) => ("tree", None),
+        Some(ObjectType::Commit) => ("commit", None),
+        _ => ("blob", odb.read_header(entry.id()).ok().map(|(size, _)| size)),
+    };
+    Entry {
+        mode: format!("{:06o}", entry.filemode()),
+        kind,
+        oid: entry.id().to_string(),
+        size,
+        path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_list() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("src/net/client.rs", "fn a() {}\n", "Add client");
+        test_repo.commit_file("README.md", "hello\n", "Add readme");
+        let repo = test_repo.repo();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        let paths = |entries: Vec<Entry>| entries.into_iter().map(|entry| entry.path).collect::<Vec<_>>();
+
+        let top = list(repo, &tree, "", false).unwrap();
+        assert_eq!(top[0].mode, "100644");
+        assert_eq!(top[0].size, Some(6));
+        assert_eq!(top[1].kind, "tree");
+        assert_eq!(paths(top), vec!["README.md", "src"]);
+
+        assert_eq!(paths(list(repo, &tree, "", true).unwrap()), vec!["README.md", "src/net/client.rs"]);
+        assert_eq!(paths(list(repo, &tree, "src", false).unwrap()), vec!["src/net"]);
+        assert_eq!(paths(list(repo, &tree, "src/net/client.rs", false).unwrap()), vec!["src/net/client.rs"]);
+        assert!(list(repo, &tree, "missing", false).is_err());
+    }
+}
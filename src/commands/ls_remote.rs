@@ -0,0 +1,160 @@
+use anyhow::Result;
+use git2::{Direction, Oid, Pathspec, PathspecFlags, Remote, RemoteCallbacks, Repository};
+use std::path::Path;
+
+use crate::cli::LsRemoteArgs;
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::network;
+use crate::timings::{self, Phase};
+
+/// Suffix the server adds to a tag name for the commit it points to
+const PEELED_SUFFIX: &str = "^{}";
+
+/// Execute the ls-remote command
+pub async fn execute(args: &LsRemoteArgs, config: &Config) -> Result<()> {
+    let repo = Repository::discover(".").ok();
+    let repository = match (&args.repository, &repo) {
+        (Some(repository), _) => repository.clone(),
+        (None, Some(repo)) => default_remote(repo),
+        (None, None) => return Err(RgitError::InvalidArgument(
+            "give a remote URL, or run inside a repository to use its remotes".to_string()
+        ).into()),
+    };
+
+    let mut remote = match &repo {
+        Some(repo) => {
+            let remote = match repo.find_remote(&repository) {
+                Ok(remote) => remote,
+                Err(_) => repo.remote_anonymous(&repository)
+                    .map_err(|_| RgitError::RemoteNotFound(repository.clone()))?,
+            };
+            network::rewritten(repo, remote, config, Direction::Fetch)?
+        }
+        None => network::detached(config, &repository, Direction::Fetch)
+            .map_err(|_| RgitError::InvalidRemoteUrl(repository.clone()))?,
+    };
+
+    let refs = list(&mut remote, config).await?;
+    let filter = RefFilter::new(args)?;
+    let shown: Vec<&RemoteRef> = refs.iter().filter(|r| filter.matches(r)).collect();
+    for remote_ref in &shown {
+        if let (true, Some(target)) = (args.symref, &remote_ref.symref) {
+            println!("ref: {}\t{}", target, remote_ref.name);
+        }
+        println!("{}\t{}", remote_ref.oid, remote_ref.name);
+    }
+
+    if shown.is_empty() && args.exit_code {
+        return Err(RgitError::OperationFailed(format!("no matching refs in {}", repository)).into());
+    }
+    Ok(())
+}
+
+/// The current branch's remote, or origin
+fn default_remote(repo: &Repository) -> String {
+    let branch = repo.head().ok().filter(|head| head.is_branch()).and_then(|head| head.shorthand().map(String::from));
+    branch.and_then(|branch| repo.config().ok()?.get_string(&format!("branch.{}.remote", branch)).ok())
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+/// A ref as the remote advertises it
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteRef {
+    pub name: String,
+    pub oid: Oid,
+    /// What a symbolic ref like HEAD points to
+    pub symref: Option<String>,
+}
+
+/// Every ref `remote` advertises, in the order it sends them
+pub async fn list(remote: &mut Remote<'_>, config: &Config) -> Result<Vec<RemoteRef>> {
+    let refs = network::with_retries(config, || {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(network::credentials(config));
+
+        let connection = timings::time(Phase::Network, || remote.connect_auth(Direction::Fetch, Some(callbacks), None))?;
+        Ok(connection.list()?
+            .iter()
+            .map(|head| RemoteRef {
+                name: head.name().to_string(),
+                oid: head.oid(),
+                symref: head.symref_target().map(String::from),
+            })
+            .collect())
+    }, |e| RgitError::NetworkError(e.message().to_string())).await?;
+    Ok(refs)
+}
+
+/// Which advertised refs to show
+struct RefFilter {
+    patterns: Option<Pathspec>,
+    heads: bool,
+    tags: bool,
+    refs_only: bool,
+}
+
+impl RefFilter {
+    fn new(args: &LsRemoteArgs) -> Result<Self> {
+        // Like git, a pattern matches the end of a ref name: `main` matches refs/heads/main
+        let patterns = if args.patterns.is_empty() {
+            None
+        } else {
+            let specs: Vec<String> = args.patterns.iter()
+                .flat_map(|pattern| [pattern.clone(), format!("*/{}", pattern)])
+                .collect();
+            Some(Pathspec::new(specs)?)
+        };
+        Ok(Self { patterns, heads: args.heads, tags: args.tags, refs_only: args.refs })
+    }
+
+    fn matches(&self, remote_ref: &RemoteRef) -> bool {
+        let name = remote_ref.name.as_str();
+        let peeled = name.ends_with(PEELED_SUFFIX);
+        if self.refs_only && (peeled || !name.starts_with("refs/")) {
+            return false;
+        }
+        if (self.heads || self.tags)
+            && !(self.heads && name.starts_with("refs/heads/") || self.tags && name.starts_with("refs/tags/")) {
+            return false;
+        }
+        let base = name.strip_suffix(PEELED_SUFFIX).unwrap_or(name);
+        self.patterns.as_ref()
+            .is_none_or(|patterns| patterns.matches_path(Path::new(base), PathspecFlags::DEFAULT))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    fn shown(refs: &[RemoteRef], args: LsRemoteArgs) -> Vec<&str> {
+        let filter = RefFilter::new(&args).unwrap();
+        refs.iter().filter(|r| filter.matches(r)).map(|r| r.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_and_filter_remote_refs() {
+        let local = TestRepo::new();
+        local.commit_file("a.txt", "a\n", "Initial commit");
+        let upstream = local.add_remote("origin");
+        let head = upstream.repo().refname_to_id("HEAD").unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        upstream.repo().tag("v1.0", &upstream.repo().find_object(head, None).unwrap(), &signature, "Release", false).unwrap();
+        upstream.repo().reference("refs/heads/feature/login", head, false, "test").unwrap();
+
+        let refs = list(&mut Remote::create_detached(upstream.url()).unwrap(), &Config::default()).await.unwrap();
+        let advertised_head = refs.iter().find(|r| r.name == "HEAD").unwrap();
+        assert_eq!(advertised_head.symref.as_deref(), Some(format!("refs/heads/{}", DEFAULT_BRANCH).as_str()));
+        let peeled = refs.iter().find(|r| r.name == "refs/tags/v1.0^{}").unwrap();
+        assert_eq!(peeled.oid, head);
+
+        assert_eq!(shown(&refs, LsRemoteArgs { tags: true, ..Default::default() }), ["refs/tags/v1.0", "refs/tags/v1.0^{}"]);
+        assert_eq!(shown(&refs, LsRemoteArgs { tags: true, refs: true, ..Default::default() }), ["refs/tags/v1.0"]);
+        assert_eq!(shown(&refs, LsRemoteArgs { patterns: vec!["login".to_string()], ..Default::default() }),
+                   ["refs/heads/feature/login"]);
+        assert_eq!(shown(&refs, LsRemoteArgs { patterns: vec!["refs/heads/*".to_string()], ..Default::default() }).len(), 2);
+        assert!(shown(&refs, LsRemoteArgs { heads: true, ..Default::default() }).iter().all(|name| name.starts_with("refs/heads/")));
+    }
+}
@@ -0,0 +1,697 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{
+    AnnotatedCommit, BranchType, Commit, FileFavor, MergeAnalysis, MergeOptions, MergePreference,
+    Oid, Repository, RepositoryState, Signature, Sort, Status, StatusOptions,
+};
+use std::fs;
+
+use crate::cli::MergeArgs;
+use crate::commands::cherry_pick::conflicted_paths;
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+use crate::validation::validate_message;
+
+/// Most commits listed in the preview
+const PREVIEW_COMMITS: usize = 10;
+
+/// Execute the merge command
+pub async fn execute(args: &MergeArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    if args.abort {
+        return abort(repo);
+    }
+    if args.continue_merge {
+        return continue_merge(rgit);
+    }
+    if args.skip {
+        return Err(RgitError::InvalidArgument(
+            "a merge is a single step with nothing to skip; use 'rgit merge --abort' to give it up".to_string()
+        ).into());
+    }
+    if repo.state() != RepositoryState::Clean {
+        return Err(RgitError::InvalidRepositoryState(
+            "another operation is in progress; finish or abort it before merging".to_string()
+        ).into());
+    }
+    if let Some(message) = &args.message {
+        validate_message(message)?;
+    }
+    let strategy = StrategyOptions::parse(&args.strategy_options)?;
+    ensure_clean_tracked_files(repo)?;
+
+    let branch = args.branch.as_deref().ok_or_else(|| RgitError::InvalidArgument("nothing to merge".to_string()))?;
+    let source = MergeSource::resolve(repo, branch)?;
+    let preview = MergePreview::new(repo, &source)?;
+    let how = match MergeHow::choose(args, &preview)? {
+        Some(how) => how,
+        None => {
+            ui_println!("{} Already up to date with '{}'", "✅".green(), source.label.cyan());
+            return Ok(());
+        }
+    };
+
+    show_preview(&source, &preview, how);
+    if config.is_interactive() {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("{} '{}'?", how.verb(), source.label))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    let signature = rgit.get_signature()?;
+    let outcome = merge(repo, &source, how, &strategy, args.message.as_deref(), &signature)?;
+    show_outcome(&source, &outcome)
+}
+
+/// Refuse to merge over uncommitted changes to tracked files
+fn ensure_clean_tracked_files(repo: &Repository) -> Result<()> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let dirty = timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?
+        .iter()
+        .any(|s| s.status() != Status::CURRENT);
+    if dirty {
+        return Err(RgitError::MergeWorkingTreeDirty.into());
+    }
+    Ok(())
+}
+
+// =============================================================================
+// What to merge
+// =============================================================================
+
+/// The commit being merged, with the name the user gave it
+struct MergeSource<'r> {
+    label: String,
+    commit: Commit<'r>,
+    annotated: AnnotatedCommit<'r>,
+    /// Default message for a merge commit
+    message: String,
+}
+
+impl<'r> MergeSource<'r> {
+    fn resolve(repo: &'r Repository, spec: &str) -> Result<Self> {
+        let commit = resolve_commit(repo, spec)?;
+        let (annotated, message) = if let Ok(branch) = repo.find_branch(spec, BranchType::Local) {
+            (repo.reference_to_annotated_commit(branch.get())?, format!("Merge branch '{}'", spec))
+        } else if let Ok(branch) = repo.find_branch(spec, BranchType::Remote) {
+            (repo.reference_to_annotated_commit(branch.get())?, format!("Merge remote-tracking branch '{}'", spec))
+        } else {
+            (repo.find_annotated_commit(commit.id())?, format!("Merge commit '{}'", spec))
+        };
+
+        Ok(Self { label: spec.to_string(), commit, annotated, message })
+    }
+}
+
+/// How the merge will be recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MergeHow {
+    /// Move the current branch forward
+    FastForward,
+    /// Create a merge commit
+    Commit,
+    /// Merge into the index and working tree, leaving the commit to the user
+    NoCommit,
+    /// Apply the combined changes without recording a merge
+    Squash,
+}
+
+impl MergeHow {
+    /// Pick the merge style from the flags, the analysis and `merge.ff`;
+    /// None when there is nothing to merge
+    fn choose(args: &MergeArgs, preview: &MergePreview) -> Result<Option<Self>> {
+        let analysis = preview.analysis;
+        if analysis.is_up_to_date() {
+            return Ok(None);
+        }
+        if args.squash {
+            return Ok(Some(MergeHow::Squash));
+        }
+
+        let ff_only = args.ff_only || preview.preference.is_fastforward_only();
+        let no_ff = args.no_ff || (!ff_only && preview.preference.is_no_fast_forward());
+        let can_fast_forward = analysis.is_fast_forward() || analysis.is_unborn();
+
+        if can_fast_forward && (!no_ff || analysis.is_unborn()) && !args.no_commit {
+            return Ok(Some(MergeHow::FastForward));
+        }
+        if ff_only {
+            ui_println!("{} The branches have diverged, so a fast-forward is not possible", "⚠️".yellow());
+            ui_println!("{} Rebase onto it ({}) or merge without --ff-only",
+                    "💡".blue(), format!("rgit rebase {}", args.branch.as_deref().unwrap_or("<branch>")).cyan());
+            return Err(RgitError::MergeNotPossible.into());
+        }
+        Ok(Some(if args.no_commit { MergeHow::NoCommit } else { MergeHow::Commit }))
+    }
+
+    fn verb(&self) -> &'static str {
+        match self {
+            MergeHow::FastForward => "Fast-forward to",
+            MergeHow::Commit => "Merge",
+            MergeHow::NoCommit => "Merge without committing",
+            MergeHow::Squash => "Squash-merge",
+        }
+    }
+}
+
+/// `-X` options passed to the merge machinery
+#[derive(Debug, Clone, Default, PartialEq)]
+struct StrategyOptions {
+    favor: Option<Favor>,
+    patience: bool,
+    minimal: bool,
+    ignore_space_change: bool,
+    ignore_all_space: bool,
+    ignore_space_at_eol: bool,
+    find_renames: Option<bool>,
+    rename_threshold: Option<u32>,
+}
+
+/// Which side wins conflicting hunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Favor {
+    Ours,
+    Theirs,
+    Union,
+}
+
+impl StrategyOptions {
+    fn parse(options: &[String]) -> Result<Self> {
+        let mut parsed = Self::default();
+        for option in options {
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (option.as_str(), None),
+            };
+            match (name, value) {
+                ("ours", None) => parsed.favor = Some(Favor::Ours),
+                ("theirs", None) => parsed.favor = Some(Favor::Theirs),
+                ("union", None) => parsed.favor = Some(Favor::Union),
+                ("patience", None) | ("diff-algorithm", Some("patience")) => parsed.patience = true,
+                ("diff-algorithm", Some("minimal")) => parsed.minimal = true,
+                ("diff-algorithm", Some("myers" | "default")) => {}
+                ("ignore-space-change", None) => parsed.ignore_space_change = true,
+                ("ignore-all-space", None) => parsed.ignore_all_space = true,
+                ("ignore-space-at-eol", None) => parsed.ignore_space_at_eol = true,
+                ("no-renames", None) => parsed.find_renames = Some(false),
+                ("find-renames" | "rename-threshold", threshold) => {
+                    parsed.find_renames = Some(true);
+                    if let Some(threshold) = threshold {
+                        let percent = threshold.trim_end_matches('%').parse::<u32>().ok()
+                            .filter(|p| *p <= 100)
+                            .ok_or_else(|| RgitError::InvalidArgument(
+                                format!("invalid rename threshold in -X {}", option)
+                            ))?;
+                        parsed.rename_threshold = Some(percent);
+                    }
+                }
+                _ => return Err(RgitError::InvalidArgument(
+                    format!("unknown strategy option -X {}", option)
+                ).into()),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn merge_options(&self) -> MergeOptions {
+        let mut options = MergeOptions::new();
+        if let Some(favor) = self.favor {
+            options.file_favor(match favor {
+                Favor::Ours => FileFavor::Ours,
+                Favor::Theirs => FileFavor::Theirs,
+                Favor::Union => FileFavor::Union,
+            });
+        }
+        options.patience(self.patience)
+            .minimal(self.minimal)
+            .ignore_whitespace_change(self.ignore_space_change)
+            .ignore_whitespace(self.ignore_all_space)
+            .ignore_whitespace_eol(self.ignore_space_at_eol);
+        if let Some(find) = self.find_renames {
+            options.find_renames(find);
+        }
+        if let Some(threshold) = self.rename_threshold {
+            options.rename_threshold(threshold);
+        }
+        options
+    }
+}
+
+// =============================================================================
+// Preview
+// =============================================================================
+
+/// What merging the source would bring in
+struct MergePreview {
+    analysis: MergeAnalysis,
+    preference: MergePreference,
+    /// Incoming commits, newest first: `(id, summary)`
+    commits: Vec<(Oid, String)>,
+    total_commits: usize,
+    files_changed: usize,
+}
+
+impl MergePreview {
+    fn new(repo: &Repository, source: &MergeSource) -> Result<Self> {
+        let (analysis, preference) = repo.merge_analysis(&[&source.annotated])?;
+        let head = repo.head().ok().and_then(|h| h.target());
+
+        let mut revwalk = repo.revwalk()?;
+        revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+        revwalk.push(source.commit.id())?;
+        if let Some(head) = head {
+            revwalk.hide(head)?;
+        }
+        let ids: Vec<Oid> = revwalk.collect::<std::result::Result<_, _>>()?;
+        let commits = ids.iter()
+            .take(PREVIEW_COMMITS)
+            .map(|id| {
+                let summary = repo.find_commit(*id).ok()
+                    .and_then(|c| c.summary().map(String::from))
+                    .unwrap_or_default();
+                (*id, summary)
+            })
+            .collect();
+
+        let base_tree = match head.and_then(|h| repo.merge_base(h, source.commit.id()).ok()) {
+            Some(base) => Some(repo.find_commit(base)?.tree()?),
+            None => None,
+        };
+        let diff = repo.diff_tree_to_tree(base_tree.as_ref(), Some(&source.commit.tree()?), None)?;
+
+        Ok(Self {
+            analysis,
+            preference,
+            commits,
+            total_commits: ids.len(),
+            files_changed: diff.deltas().len(),
+        })
+    }
+}
+
+fn show_preview(source: &MergeSource, preview: &MergePreview, how: MergeHow) {
+    ui_println!("{} {} '{}': {} commit{}, {} file{} changed",
+            "🔀".blue().bold(),
+            how.verb(),
+            source.label.cyan(),
+            preview.total_commits,
+            plural(preview.total_commits),
+            preview.files_changed,
+            plural(preview.files_changed));
+
+    for (id, summary) in &preview.commits {
+        println!("  {} {}", shorten_oid(id, 8).yellow(), summary);
+    }
+    if preview.total_commits > preview.commits.len() {
+        println!("  {}", format!("... and {} more", preview.total_commits - preview.commits.len()).dimmed());
+    }
+    println!();
+}
+
+// =============================================================================
+// Merging
+// =============================================================================
+
+/// How a merge ended
+#[derive(Debug, Clone, PartialEq)]
+enum MergeOutcome {
+    FastForward(Oid),
+    Committed(Oid),
+    /// Merged into the index and working tree without committing
+    Staged(MergeHow),
+    /// Stopped with these conflicted paths
+    Conflicts(MergeHow, Vec<String>),
+}
+
+/// Merge `source` into HEAD.
+///
+/// The previous HEAD goes to the undo journal and `ORIG_HEAD` whenever HEAD moves.
+fn merge(
+    repo: &Repository,
+    source: &MergeSource,
+    how: MergeHow,
+    strategy: &StrategyOptions,
+    message: Option<&str>,
+    signature: &Signature,
+) -> Result<MergeOutcome> {
+    let head = repo.head().ok().and_then(|h| h.target());
+    if let Some(head) = head {
+        repo.reference("ORIG_HEAD", head, true, "rgit: merge")?;
+    }
+
+    if how == MergeHow::FastForward {
+        fast_forward(repo, source)?;
+        record(repo, head, source.commit.id())?;
+        return Ok(MergeOutcome::FastForward(source.commit.id()));
+    }
+
+    let mut options = strategy.merge_options();
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe().allow_conflicts(true).conflict_style_merge(true);
+    if let Err(e) = repo.merge(&[&source.annotated], Some(&mut options), Some(&mut checkout)) {
+        repo.cleanup_state()?;
+        return Err(e.into());
+    }
+
+    let message = message.map(String::from).unwrap_or_else(|| source.message.clone());
+    if how == MergeHow::Squash {
+        // A squash leaves no trace of the merged commit, only the message
+        repo.cleanup_state()?;
+        fs::write(repo.path().join("SQUASH_MSG"), squash_message(repo, source, head)?)?;
+    } else {
+        fs::write(repo.path().join("MERGE_MSG"), format!("{}\n", message))?;
+    }
+
+    let mut index = repo.index()?;
+    let conflicts = conflicted_paths(&index)?;
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicts(how, conflicts));
+    }
+    if how != MergeHow::Commit {
+        return Ok(MergeOutcome::Staged(how));
+    }
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head_commit = repo.head()?.peel_to_commit()?;
+    let oid = repo.commit(Some("HEAD"), signature, signature, &message, &tree, &[&head_commit, &source.commit])?;
+    repo.cleanup_state()?;
+    record(repo, head, oid)?;
+    Ok(MergeOutcome::Committed(oid))
+}
+
+/// `--continue`: commit the resolved merge with the prepared message
+fn continue_merge(rgit: &RgitCore) -> Result<()> {
+    let repo = &rgit.repo;
+    ensure_merging(repo)?;
+    let conflicts = conflicted_paths(&repo.index()?)?;
+    if !conflicts.is_empty() {
+        ui_println!("{} Resolve these conflicts and stage the files first:", "⚠️".yellow());
+        for path in &conflicts {
+            ui_println!("  {} {}", "•".red(), path);
+        }
+        return Err(RgitError::MergeConflict(conflicts).into());
+    }
+
+    let message = fs::read_to_string(repo.path().join("MERGE_MSG")).unwrap_or_default();
+    let message = message.trim();
+    let message = if message.is_empty() { "Merge" } else { message };
+    let head = repo.head()?.target();
+    let oid = rgit.commit(message, false)?;
+    record(repo, head, oid)?;
+    ui_println!("{} Merge concluded in {}", "✅".green().bold(), shorten_oid(&oid, 8).yellow());
+    Ok(())
+}
+
+/// `--abort`: drop the merge result and return to HEAD
+fn abort(repo: &Repository) -> Result<()> {
+    ensure_merging(repo)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    repo.reset(head.as_object(), git2::ResetType::Hard, Some(&mut checkout))?;
+    repo.cleanup_state()?;
+    let _ = fs::remove_file(repo.path().join("MERGE_MSG"));
+    ui_println!("{} Merge aborted; back at {}", "↩️".yellow(), shorten_oid(&head.id(), 8).yellow());
+    Ok(())
+}
+
+fn ensure_merging(repo: &Repository) -> Result<()> {
+    if repo.state() != RepositoryState::Merge {
+        return Err(RgitError::InvalidRepositoryState("no merge in progress".to_string()).into());
+    }
+    Ok(())
+}
+
+/// Move the current branch (or detached HEAD) to the source commit
+fn fast_forward(repo: &Repository, source: &MergeSource) -> Result<()> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(source.commit.as_object(), Some(&mut checkout))?;
+
+    let log_message = format!("merge {}: Fast-forward", source.label);
+    match repo.head() {
+        Ok(mut head) => {
+            head.set_target(source.commit.id(), &log_message)?;
+        }
+        Err(_) => {
+            // Unborn branch: create it where HEAD points
+            let head = repo.find_reference("HEAD")?;
+            let branch = head.symbolic_target()
+                .ok_or_else(|| RgitError::InvalidRepositoryState("HEAD is not a branch".to_string()))?;
+            repo.reference(branch, source.commit.id(), false, &log_message)?;
+        }
+    }
+    Ok(())
+}
+
+fn record(repo: &Repository, old: Option<Oid>, new: Oid) -> Result<()> {
+    let head = repo.head()?;
+    let reference = if head.is_branch() { head.name().unwrap_or("HEAD") } else { "HEAD" };
+    let mut entry = JournalEntry::new("merge", reference).with_new_oid(new);
+    if let Some(old) = old {
+        entry = entry.with_old_oid(old);
+    }
+    Journal::for_repo(repo).record(&entry)
+}
+
+/// `SQUASH_MSG` listing the squashed commits, like git
+fn squash_message(repo: &Repository, source: &MergeSource, head: Option<Oid>) -> Result<String> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(source.commit.id())?;
+    if let Some(head) = head {
+        revwalk.hide(head)?;
+    }
+
+    let mut message = String::from("Squashed commit of the following:\n");
+    for id in revwalk {
+        let commit = repo.find_commit(id?)?;
+        message.push_str(&format!("\ncommit {}\n", commit.id()));
+        message.push_str(&format!("Author: {} <{}>\n\n",
+                                  commit.author().name().unwrap_or(""),
+                                  commit.author().email().unwrap_or("")));
+        for line in commit.message().unwrap_or("").lines() {
+            message.push_str(&format!("    {}\n", line));
+        }
+    }
+    Ok(message)
+}
+
+fn show_outcome(source: &MergeSource, outcome: &MergeOutcome) -> Result<()> {
+    match outcome {
+        MergeOutcome::FastForward(oid) => {
+            ui_println!("{} Fast-forwarded to {} ({})",
+                    "✅".green().bold(), shorten_oid(oid, 8).yellow(), source.label.cyan());
+        }
+        MergeOutcome::Committed(oid) => {
+            ui_println!("{} Merged '{}' in {}",
+                    "✅".green().bold(), source.label.cyan(), shorten_oid(oid, 8).yellow());
+        }
+        MergeOutcome::Staged(MergeHow::Squash) => {
+            ui_println!("{} Squashed changes from '{}' are staged", "✅".green().bold(), source.label.cyan());
+            ui_println!("{} Record them with {}", "💡".blue(), "rgit commit".cyan());
+        }
+        MergeOutcome::Staged(_) => {
+            ui_println!("{} Merged '{}' without committing", "✅".green().bold(), source.label.cyan());
+            ui_println!("{} Review the result, then conclude with {}", "💡".blue(), "rgit commit".cyan());
+        }
+        MergeOutcome::Conflicts(how, conflicts) => {
+            ui_println!("{} Conflicts while merging '{}':", "⚠️".yellow().bold(), source.label.cyan());
+            for path in conflicts {
+                ui_println!("  {} {}", "•".red(), path);
+            }
+            println!();
+            ui_println!("{} Next steps:", "💡".blue());
+            ui_println!("  • {} - Resolve the conflicts", "rgit resolve".cyan());
+            ui_println!("  • {} - Mark them resolved", "rgit add <file>".cyan());
+            if *how == MergeHow::Squash {
+                ui_println!("  • {} - Record the squashed changes", "rgit commit".cyan());
+            } else {
+                ui_println!("  • {} - Conclude the merge", "rgit merge --continue".cyan());
+                ui_println!("  • {} - Give up and restore the pre-merge state", "rgit merge --abort".cyan());
+            }
+            return Err(RgitError::MergeConflict(conflicts.clone()).into());
+        }
+    }
+    Ok(())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    fn args(branch: &str) -> MergeArgs {
+        MergeArgs {
+            branch: Some(branch.to_string()),
+            no_ff: false,
+            ff_only: false,
+            no_commit: false,
+            squash: false,
+            message: None,
+            strategy_options: Vec::new(),
+            continue_merge: false,
+            abort: false,
+            skip: false,
+        }
+    }
+
+    fn signature() -> Signature<'static> {
+        Signature::now("Test", "test@example.com").unwrap()
+    }
+
+    /// Run a merge the way `execute` does, minus prompts and output
+    fn run(repo: &TestRepo, args: &MergeArgs) -> Result<Option<MergeOutcome>> {
+        let source = MergeSource::resolve(repo.repo(), args.branch.as_deref().unwrap())?;
+        let preview = MergePreview::new(repo.repo(), &source)?;
+        let Some(how) = MergeHow::choose(args, &preview)? else { return Ok(None) };
+        let strategy = StrategyOptions::parse(&args.strategy_options)?;
+        merge(repo.repo(), &source, how, &strategy, args.message.as_deref(), &signature()).map(Some)
+    }
+
+    /// `main` and `feature` both change `file.txt` from "base"
+    fn diverged(main: &str, feature: &str) -> TestRepo {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("file.txt", feature, "Feature change");
+        repo.checkout(DEFAULT_BRANCH);
+        repo.commit_file("file.txt", main, "Main change");
+        repo
+    }
+
+    #[test]
+    fn test_fast_forward_and_no_ff() {
+        let repo = TestRepo::new();
+        let base = repo.commit_file("a.txt", "a\n", "Base");
+        repo.branch("feature").checkout("feature");
+        let tip = repo.commit_file("b.txt", "b\n", "Feature");
+        repo.checkout(DEFAULT_BRANCH);
+
+        let source = MergeSource::resolve(repo.repo(), "feature").unwrap();
+        let preview = MergePreview::new(repo.repo(), &source).unwrap();
+        assert_eq!((preview.total_commits, preview.files_changed), (1, 1));
+
+        let mut no_ff = args("feature");
+        no_ff.no_ff = true;
+        let outcome = run(&repo, &no_ff).unwrap().unwrap();
+        let MergeOutcome::Committed(merge_commit) = outcome else { panic!("expected a merge commit") };
+        let parents: Vec<Oid> = repo.repo().find_commit(merge_commit).unwrap().parent_ids().collect();
+        assert_eq!(parents, vec![base, tip]);
+        assert_eq!(repo.repo().refname_to_id("ORIG_HEAD").unwrap(), base);
+
+        repo.checkout("feature");
+        assert_eq!(run(&repo, &args(DEFAULT_BRANCH)).unwrap(), Some(MergeOutcome::FastForward(merge_commit)));
+        assert_eq!(run(&repo, &args(DEFAULT_BRANCH)).unwrap(), None);
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_ff_only_refuses_diverged_branches() {
+        let repo = diverged("main\n", "feature\n");
+        let mut ff_only = args("feature");
+        ff_only.ff_only = true;
+        assert!(run(&repo, &ff_only).is_err());
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_squash_and_no_commit() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Base");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.commit_file("c.txt", "c\n", "Add c");
+        repo.checkout(DEFAULT_BRANCH);
+        let head = repo.head();
+
+        let mut squash = args("feature");
+        squash.squash = true;
+        assert_eq!(run(&repo, &squash).unwrap(), Some(MergeOutcome::Staged(MergeHow::Squash)));
+        assert_eq!(repo.head(), head);
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+        let message = fs::read_to_string(repo.repo().path().join("SQUASH_MSG")).unwrap();
+        assert!(message.contains("Add b") && message.contains("Add c"));
+        assert!(repo.path().join("c.txt").exists());
+
+        let mut no_commit = args("feature");
+        no_commit.no_commit = true;
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Base");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.checkout(DEFAULT_BRANCH);
+        assert_eq!(run(&repo, &no_commit).unwrap(), Some(MergeOutcome::Staged(MergeHow::NoCommit)));
+        assert_eq!(repo.repo().state(), RepositoryState::Merge);
+    }
+
+    #[test]
+    fn test_strategy_options_resolve_conflicts() {
+        let repo = diverged("ours\n", "theirs\n");
+        let outcome = run(&repo, &args("feature")).unwrap().unwrap();
+        assert_eq!(outcome, MergeOutcome::Conflicts(MergeHow::Commit, vec!["file.txt".to_string()]));
+        assert_eq!(repo.repo().state(), RepositoryState::Merge);
+        assert!(repo.core().commit("Merge feature", false).is_err());
+
+        repo.write("file.txt", "both\n").stage("file.txt");
+        let merged = repo.core().commit("Merge feature", false).unwrap();
+        assert_eq!(repo.repo().find_commit(merged).unwrap().parent_count(), 2);
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+
+        for (option, expected) in [("ours", "ours\n"), ("theirs", "theirs\n")] {
+            let repo = diverged("ours\n", "theirs\n");
+            let mut with_option = args("feature");
+            with_option.strategy_options = vec![option.to_string()];
+            assert!(matches!(run(&repo, &with_option).unwrap(), Some(MergeOutcome::Committed(_))));
+            assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_continue_and_abort() {
+        let repo = diverged("ours\n", "theirs\n");
+        let head = repo.head();
+        run(&repo, &args("feature")).unwrap();
+        assert!(continue_merge(&repo.core()).is_err());
+        abort(repo.repo()).unwrap();
+        assert_eq!((repo.head(), repo.repo().state()), (head, RepositoryState::Clean));
+        assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), "ours\n");
+        assert!(abort(repo.repo()).is_err());
+
+        run(&repo, &args("feature")).unwrap();
+        repo.write("file.txt", "both\n").stage("file.txt");
+        continue_merge(&repo.core()).unwrap();
+        let merged = repo.repo().find_commit(repo.head()).unwrap();
+        assert_eq!(merged.parent_count(), 2);
+        assert_eq!(merged.summary(), Some("Merge branch 'feature'"));
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+    }
+
+    #[test]
+    fn test_parse_strategy_options() {
+        let parse = |options: &[&str]| {
+            StrategyOptions::parse(&options.iter().map(|o| o.to_string()).collect::<Vec<_>>())
+        };
+        let parsed = parse(&["theirs", "diff-algorithm=patience", "find-renames=40%", "ignore-space-change"]).unwrap();
+        assert_eq!(parsed.favor, Some(Favor::Theirs));
+        assert!(parsed.patience && parsed.ignore_space_change);
+        assert_eq!((parsed.find_renames, parsed.rename_threshold), (Some(true), Some(40)));
+
+        assert_eq!(parse(&["no-renames"]).unwrap().find_renames, Some(false));
+        assert!(parse(&["subtree"]).is_err());
+        assert!(parse(&["find-renames=150"]).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Pathspec, PathspecFlags};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::MergetoolArgs;
+use crate::commands::resolve::continue_command;
+use crate::config::Config;
+use crate::conflict::{conflict_sides, has_conflict_markers, mark_resolved, run_mergetool, ConflictSides};
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::tools::{select_tool, KNOWN_TOOLS, ToolKind};
+use crate::validation::validate_pathspec;
+
+/// Execute the mergetool command
+pub async fn execute(args: &MergetoolArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.tool_help {
+        list_tools(config);
+        return Ok(());
+    }
+
+    let repo = &rgit.repo;
+    let conflicts = select_conflicts(conflict_sides(repo)?, &rgit.repo_path, &args.paths)?;
+    if conflicts.is_empty() {
+        ui_println!("{} No conflicts to resolve", "✅".green());
+        return Ok(());
+    }
+
+    let tool = select_tool(Some(repo), config, ToolKind::Merge, args.tool.as_deref())?;
+    let mut remaining = Vec::new();
+    for (i, conflict) in conflicts.iter().enumerate() {
+        ui_println!("{} Merging ({}/{}) {} with {}",
+                "🔧".blue(), i + 1, conflicts.len(), conflict.path.yellow(), tool.name().cyan());
+
+        if conflict.ours.is_none() || conflict.theirs.is_none() {
+            ui_println!("  {} One side deleted this file; use {} to choose", "⚠️".yellow(), "rgit resolve".cyan());
+            remaining.push(&conflict.path);
+            continue;
+        }
+
+        run_mergetool(repo, conflict, &tool)?;
+        let merged = fs::read_to_string(rgit.repo_path.join(&conflict.path)).unwrap_or_default();
+        if has_conflict_markers(&merged) {
+            ui_println!("  {} Conflict markers remain; left unresolved", "⚠️".yellow());
+            remaining.push(&conflict.path);
+        } else {
+            mark_resolved(repo, &conflict.path)?;
+            ui_println!("  {} Resolved and staged", "✅".green());
+        }
+    }
+
+    println!();
+    if remaining.is_empty() {
+        ui_println!("{} All conflicts resolved!", "🎉".green());
+        if let Some(next) = continue_command(repo) {
+            ui_println!("{} Finish with {}", "💡".blue(), next.cyan());
+        }
+    } else {
+        ui_println!("{} {} file{} still conflicted: {}",
+                "💡".blue(),
+                remaining.len(),
+                if remaining.len() == 1 { "" } else { "s" },
+                remaining.iter().map(|p| p.as_str()).collect::<Vec<_>>().join(", "));
+    }
+    Ok(())
+}
+
+/// Conflicts under the given pathspecs, or all of them
+fn select_conflicts(conflicts: Vec<ConflictSides>, workdir: &Path, specs: &[String]) -> Result<Vec<ConflictSides>> {
+    if specs.is_empty() {
+        return Ok(conflicts);
+    }
+
+    let cwd = std::env::current_dir()?;
+    let relative = specs.iter()
+        .map(|spec| validate_pathspec(workdir, &cwd, spec))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    let pathspec = Pathspec::new(relative.iter())?;
+    let selected: Vec<ConflictSides> = conflicts.into_iter()
+        .filter(|c| pathspec.matches_path(Path::new(&c.path), PathspecFlags::DEFAULT))
+        .collect();
+    if selected.is_empty() {
+        return Err(RgitError::InvalidArgument(
+            format!("no conflicted files match {}", specs.join(" "))
+        ).into());
+    }
+    Ok(selected)
+}
+
+/// Print the known tools and which are installed
+pub fn list_tools(config: &Config) {
+    ui_println!("{} Known merge and diff tools:", "🔧".blue().bold());
+    for tool in KNOWN_TOOLS {
+        match tool.installed_program() {
+            Some(program) => ui_println!("  {} {:<10} {}", "✅".green(), tool.name, program.display().to_string().dimmed()),
+            None => ui_println!("  {} {:<10} {}", "❌".red(), tool.name,
+                            format!("not found ({})", tool.programs.join(", ")).dimmed()),
+        }
+    }
+
+    if !config.integrations.tools.is_empty() {
+        println!();
+        ui_println!("{} Custom tools:", "⚙️".blue().bold());
+        let mut names: Vec<&String> = config.integrations.tools.keys().collect();
+        names.sort();
+        for name in names {
+            ui_println!("  {} {}", "•".cyan(), name);
+        }
+    }
+
+    println!();
+    ui_println!("{} Choose one with {} or {}",
+            "💡".blue(),
+            "rgit config set integrations.merge_tool <name>".cyan(),
+            "rgit mergetool --tool <name>".cyan());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_select_conflicts_by_path() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "base\n", "Base");
+        repo.conflict("a.txt");
+        let conflicts = conflict_sides(repo.repo()).unwrap();
+
+        let all = select_conflicts(conflicts.clone(), repo.path(), &[]).unwrap();
+        assert_eq!(all.len(), 1);
+        let abs = repo.path().join("a.txt").display().to_string();
+        assert_eq!(select_conflicts(conflicts.clone(), repo.path(), &[abs]).unwrap()[0].path, "a.txt");
+        let other = repo.path().join("b.txt").display().to_string();
+        assert!(select_conflicts(conflicts, repo.path(), &[other]).is_err());
+    }
+}
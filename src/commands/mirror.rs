@@ -0,0 +1,219 @@
+use anyhow::Result;
+use chrono::Local;
+use colored::*;
+use git2::{AutotagOption, Direction, FetchOptions, FetchPrune, Oid, RemoteCallbacks, Repository};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+
+use crate::cli::{MirrorArgs, MirrorCommands};
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::network;
+use crate::timings::{self, Phase};
+use crate::utils::parse_duration_spec;
+
+/// Fetch refspec of a mirror: every ref maps onto itself
+pub const MIRROR_REFSPEC: &str = "+refs/*:refs/*";
+
+/// Fetch refspec of a bare clone: branches map onto its own, tags follow automatically
+pub const BARE_REFSPEC: &str = "+refs/heads/*:refs/heads/*";
+
+/// Held while a mirror updates, so overlapping scheduled runs skip instead of racing
+const LOCK_FILE: &str = "rgit-mirror.lock";
+
+/// Execute the mirror command
+pub async fn execute(args: &MirrorArgs, config: &Config) -> Result<()> {
+    match &args.action {
+        MirrorCommands::Update { paths, every, no_prune } => {
+            let paths = if paths.is_empty() { vec![PathBuf::from(".")] } else { paths.clone() };
+            let interval = every.as_deref()
+                .map(|spec| parse_duration_spec(spec).filter(|interval| !interval.is_zero())
+                    .ok_or_else(|| RgitError::InvalidArgument(format!("invalid interval '{}', expected e.g. 15m or 1h", spec))))
+                .transpose()?;
+
+            loop {
+                let failed = update_all(&paths, !no_prune, config).await;
+                let Some(interval) = interval else {
+                    if failed > 0 {
+                        return Err(RgitError::OperationFailed(
+                            format!("{} of {} mirror(s) failed to update", failed, paths.len())
+                        ).into());
+                    }
+                    return Ok(());
+                };
+
+                // A failed round is retried on the next one rather than ending the schedule
+                let next = Local::now() + chrono::Duration::from_std(interval)?;
+                ui_println!("{} Next update at {}", "⏰".blue(), next.format("%Y-%m-%d %H:%M:%S").to_string().dimmed());
+                tokio::time::sleep(interval).await;
+            }
+        }
+    }
+}
+
+/// Update each mirror in turn, reporting as it goes. Returns how many failed.
+async fn update_all(paths: &[PathBuf], prune: bool, config: &Config) -> usize {
+    let mut failed = 0;
+    for path in paths {
+        let stamp = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let result = match Repository::open_bare(path) {
+            Ok(repo) => update(&repo, prune, config).await,
+            Err(_) => Err(RgitError::InvalidArgument(format!(
+                "{} is not a bare repository; mirror update works on clones made with --mirror or --bare",
+                path.display()
+            )).into()),
+        };
+        match result {
+            Ok(changes) => ui_println!("{} {} {} {}", stamp.dimmed(), "✅".green(), path.display().to_string().cyan(), changes),
+            Err(e) => {
+                ui_println!("{} {} {} {}", stamp.dimmed(), "❌".red(), path.display().to_string().cyan(), e);
+                failed += 1;
+            }
+        }
+    }
+    failed
+}
+
+/// How a mirror's refs changed in an update
+#[derive(Debug, Default, PartialEq)]
+pub struct MirrorChanges {
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+impl MirrorChanges {
+    fn between(before: &BTreeMap<String, Oid>, after: &BTreeMap<String, Oid>) -> Self {
+        let mut changes = Self::default();
+        for (name, oid) in after {
+            match before.get(name) {
+                None => changes.created += 1,
+                Some(old) if old != oid => changes.updated += 1,
+                Some(_) => {}
+            }
+        }
+        changes.deleted = before.keys().filter(|name| !after.contains_key(*name)).count();
+        changes
+    }
+}
+
+impl std::fmt::Display for MirrorChanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self == Self::default() {
+            return write!(f, "up to date");
+        }
+        write!(f, "{} new, {} updated, {} deleted ref(s)", self.created, self.updated, self.deleted)
+    }
+}
+
+/// Fetch every mirrored remote of a bare repository. Remotes marked
+/// `remote.<name>.mirror` are used when there are any, otherwise all of them.
+pub async fn update(repo: &Repository, prune: bool, config: &Config) -> Result<MirrorChanges> {
+    let remotes = mirrored_remotes(repo)?;
+    if remotes.is_empty() {
+        return Err(RgitError::OperationFailed("no remotes to update from".to_string()).into());
+    }
+
+    let _lock = UpdateLock::acquire(repo.path())?;
+    let before = ref_targets(repo)?;
+    for name in &remotes {
+        let remote = repo.find_remote(name)?;
+        // A rewritten remote is anonymous, so the refspecs are passed explicitly
+        let refspecs: Vec<String> = remote.fetch_refspecs()?.iter().flatten().map(String::from).collect();
+        let mut remote = network::rewritten(repo, remote, config, Direction::Fetch)?;
+        network::with_retries(config, || {
+            let mut callbacks = RemoteCallbacks::new();
+            callbacks.credentials(network::credentials(config));
+            let mut options = FetchOptions::new();
+            options.remote_callbacks(callbacks);
+            options.prune(if prune { FetchPrune::On } else { FetchPrune::Off });
+            options.download_tags(AutotagOption::All);
+            timings::time(Phase::Network, || remote.fetch(&refspecs, Some(&mut options), Some("mirror update")))
+        }, |e| RgitError::FetchFailed(format!("{}: {}", name, e.message()))).await?;
+    }
+    Ok(MirrorChanges::between(&before, &ref_targets(repo)?))
+}
+
+fn mirrored_remotes(repo: &Repository) -> Result<Vec<String>> {
+    let config = repo.config()?;
+    let names: Vec<String> = repo.remotes()?.iter().flatten().map(String::from).collect();
+    let mirrors: Vec<String> = names.iter()
+        .filter(|name| config.get_bool(&format!("remote.{}.mirror", name)).unwrap_or(false))
+        .cloned()
+        .collect();
+    Ok(if mirrors.is_empty() { names } else { mirrors })
+}
+
+fn ref_targets(repo: &Repository) -> Result<BTreeMap<String, Oid>> {
+    let mut targets = BTreeMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+            targets.insert(name.to_string(), oid);
+        }
+    }
+    Ok(targets)
+}
+
+/// Lock file in the repository, removed when dropped
+struct UpdateLock(PathBuf);
+
+impl UpdateLock {
+    fn acquire(git_dir: &Path) -> Result<Self> {
+        let path = git_dir.join(LOCK_FILE);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(_) => Ok(Self(path)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => Err(RgitError::OperationFailed(format!(
+                "another update is running (remove {} if it is stale)", path.display()
+            )).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl Drop for UpdateLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use git2::build::RepoBuilder;
+
+    #[tokio::test]
+    async fn test_update_mirrors_new_and_deleted_refs() {
+        let source = TestRepo::new();
+        source.commit_file("a.txt", "a\n", "Initial commit");
+        source.branch("old");
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut builder = RepoBuilder::new();
+        builder.bare(true).remote_create(|repo, name, url| {
+            let remote = repo.remote_with_fetch(name, url, MIRROR_REFSPEC)?;
+            repo.config()?.set_bool(&format!("remote.{}.mirror", name), true)?;
+            Ok(remote)
+        });
+        let mirror = builder.clone(&source.url(), &dir.path().join("mirror.git")).unwrap();
+        assert!(mirror.find_reference("refs/heads/old").is_ok());
+
+        assert_eq!(update(&mirror, true, &Config::default()).await.unwrap(), MirrorChanges::default());
+
+        source.commit_file("b.txt", "b\n", "Second commit");
+        source.tag("v1.0");
+        source.repo().find_branch("old", git2::BranchType::Local).unwrap().delete().unwrap();
+        let changes = update(&mirror, true, &Config::default()).await.unwrap();
+        assert_eq!(changes, MirrorChanges { created: 1, updated: 1, deleted: 1 });
+        assert_eq!(mirror.refname_to_id("refs/tags/v1.0").unwrap(), source.head());
+        assert!(!mirror.path().join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_update_refuses_to_overlap() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let _lock = UpdateLock::acquire(dir.path()).unwrap();
+        assert!(UpdateLock::acquire(dir.path()).is_err());
+    }
+}
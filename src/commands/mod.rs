@@ -2,13 +2,18 @@ use anyhow::Result;
 
 use crate::config::Config;
 use crate::core::RgitCore;
+use crate::output::decoration;
 
 // Core commands
 pub mod init;
 pub mod clone;
 pub mod status;
 pub mod add;
+pub mod unstage;
+pub mod mv;
+pub mod rm;
 pub mod commit;
+pub mod reset;
 pub mod push;
 pub mod pull;
 pub mod fetch;
@@ -16,34 +21,56 @@ pub mod fetch;
 // Branch management
 pub mod branch;
 pub mod checkout;
+pub mod switch;
 pub mod merge;
 pub mod rebase;
 pub mod cherry_pick;
+pub mod revert;
 
 // History and information
 pub mod log;
 pub mod diff;
 pub mod show;
+pub mod cat;
+pub mod ls;
+pub mod hash;
+pub mod object;
 pub mod blame;
+pub mod who;
+pub mod todos;
+pub mod owners;
 pub mod grep;
 
 // Remote management
 pub mod remote;
+pub mod ls_remote;
+pub mod mirror;
 
 // Tag management
 pub mod tag;
 
 // Stash operations
 pub mod stash;
+pub mod wip;
 
-// Submodule operations
+// Submodule and subtree operations
 pub mod submodule;
+pub mod subtree;
+
+// Multi-repository operations
+pub mod workspace;
 
 // Advanced operations
 pub mod bisect;
 pub mod reflog;
+pub mod recover;
 pub mod gc;
 pub mod fsck;
+pub mod audit;
+pub mod scan;
+pub mod filter;
+pub mod import;
+pub mod export;
 
 // Ease-of-use commands
 pub mod sync;
@@ -51,12 +78,17 @@ pub mod quick_commit;
 pub mod undo;
 pub mod clean;
 pub mod resolve;
+pub mod mergetool;
 pub mod backup;
 pub mod restore;
 
 // Utility commands
 pub mod doctor;
 pub mod learn;
+pub mod explain;
+pub mod config;
+pub mod whoami;
+pub mod credential;
 
 /// Trait for command implementations
 pub trait Command {
@@ -167,6 +199,10 @@ pub struct CommandResult {
     pub exit_code: i32,
     /// Execution time in milliseconds
     pub execution_time: u64,
+    /// Time spent in each phase that ran
+    pub phases: Vec<crate::timings::PhaseTiming>,
+    /// Why the command failed
+    pub error: Option<anyhow::Error>,
     /// Additional result data
     pub data: std::collections::HashMap<String, serde_json::Value>,
 }
@@ -177,6 +213,8 @@ impl CommandResult {
             success: true,
             exit_code: 0,
             execution_time: 0,
+            phases: Vec::new(),
+            error: None,
             data: std::collections::HashMap::new(),
         }
     }
@@ -186,6 +224,8 @@ impl CommandResult {
             success: false,
             exit_code,
             execution_time: 0,
+            phases: Vec::new(),
+            error: None,
             data: std::collections::HashMap::new(),
         }
     }
@@ -195,10 +235,25 @@ impl CommandResult {
         self
     }
     
+    pub fn with_phases(mut self, phases: Vec<crate::timings::PhaseTiming>) -> Self {
+        self.phases = phases;
+        self
+    }
+
+    pub fn with_error(mut self, error: anyhow::Error) -> Self {
+        self.error = Some(error);
+        self
+    }
+
     pub fn with_data(mut self, key: String, value: serde_json::Value) -> Self {
         self.data.insert(key, value);
         self
     }
+
+    /// The command's own outcome, for callers that report errors themselves
+    pub fn into_result(self) -> Result<()> {
+        self.error.map_or(Ok(()), Err)
+    }
 }
 
 /// Utility functions for command implementations
@@ -206,10 +261,14 @@ pub mod utils {
     use super::*;
     use crate::error::RgitError;
     use crate::interactive::InteractivePrompt;
+    use crate::t;
     use colored::*;
     use std::time::Instant;
+    use tracing::debug;
 
-    /// Execute a command with timing and error handling
+    /// Execute a command with timing and error handling. The result holds
+    /// the time spent in each phase and, when the command failed, its error,
+    /// which is left to the caller to report.
     pub async fn execute_with_timing<F, Fut>(
         command_name: &str,
         operation: F,
@@ -218,22 +277,35 @@ pub mod utils {
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<()>>,
     {
+        crate::timings::enable();
         let start = Instant::now();
         
-        match operation().await {
-            Ok(()) => {
-                let duration = start.elapsed().as_millis() as u64;
-                Ok(CommandResult::success().with_execution_time(duration))
-            }
+        let outcome = operation().await;
+        let duration = start.elapsed().as_millis() as u64;
+        let result = match outcome {
+            Ok(()) => CommandResult::success(),
             Err(e) => {
-                let duration = start.elapsed().as_millis() as u64;
-                eprintln!("{} Command '{}' failed: {}", 
-                         "❌".red(), 
-                         command_name.cyan(), 
-                         e);
-                Ok(CommandResult::failure(1).with_execution_time(duration))
+                debug!("Command '{}' failed after {}", command_name, format_execution_time(duration));
+                let exit_code = e.downcast_ref::<RgitError>().map(RgitError::exit_code).unwrap_or(1);
+                CommandResult::failure(exit_code).with_error(e)
             }
+        };
+        Ok(result.with_execution_time(duration).with_phases(crate::timings::take()))
+    }
+
+    /// Print how long a command took, phase by phase
+    pub fn show_timings(command_name: &str, result: &CommandResult) {
+        ui_eprintln!("\n{} {} {}", "⏱️".blue(), format!("rgit {}", command_name).cyan().bold(),
+                     t!("timings-took", duration = format_execution_time(result.execution_time)).bold());
+        let mut accounted = 0;
+        for timing in &result.phases {
+            let ms = timing.duration.as_millis() as u64;
+            accounted += ms;
+            let shown = if ms == 0 { "<1ms".to_string() } else { format_execution_time(ms) };
+            let count = if timing.count > 1 { format!(" ({}{})", timing.count, decoration("×")) } else { String::new() };
+            eprintln!("   {:<12} {:>8}{}", timing.phase.label(), shown, count.dimmed());
         }
+        eprintln!("   {:<12} {:>8}", t!("timings-other"), format_execution_time(result.execution_time.saturating_sub(accounted)).dimmed());
     }
     
     /// Confirm destructive operation
@@ -245,12 +317,16 @@ pub mod utils {
         if !config.advanced.safety.confirm_destructive {
             return Ok(true);
         }
+        // Without asking, a destructive operation takes the default answer: no
+        if config.ui.assume_yes {
+            return Ok(false);
+        }
         
         if !config.is_interactive() {
             return Err(RgitError::NonInteractiveEnvironment.into());
         }
         
-        let mut message = format!("Are you sure you want to {}?", operation);
+        let mut message = t!("confirm-destructive", operation = operation);
         if let Some(details) = details {
             message.push_str(&format!("\n{}", details));
         }
@@ -275,9 +351,9 @@ pub mod utils {
         config: &Config,
     ) {
         if config.ui.interactive && !changes.is_empty() {
-            println!("\n{} {} summary:", "📋".blue(), operation.cyan().bold());
+            ui_println!("\n{} {}", "📋".blue(), t!("operation-summary", operation = operation.cyan().bold()));
             for change in changes {
-                println!("  {} {}", "•".green(), change);
+                ui_println!("  {} {}", "•".green(), change);
             }
             println!();
         }
@@ -466,6 +542,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_destructive_operations_are_declined_without_asking() {
+        let mut config = Config::default();
+        config.ui.assume_yes = true;
+        assert!(!utils::confirm_destructive_operation("reset --hard", None, &config).unwrap());
+
+        config.advanced.safety.confirm_destructive = false;
+        assert!(utils::confirm_destructive_operation("reset --hard", None, &config).unwrap());
+    }
+
     #[test]
     fn test_command_registry() {
         let mut registry = CommandRegistry::new();
@@ -0,0 +1,282 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Index, Repository};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::MvArgs;
+use crate::commands::rm::{index_paths_matching, is_glob, remove_empty_parents};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::timings::{self, Phase};
+use crate::validation::validate_pathspec;
+
+/// Execute the mv command
+pub async fn execute(args: &MvArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let (destination, sources) = args.paths.split_last()
+        .ok_or_else(|| RgitError::InvalidArgument("Missing destination".to_string()))?;
+    let cwd = std::env::current_dir()?;
+    let moves = plan_moves(&rgit.repo, &rgit.repo_path, &cwd, sources, destination, args)?;
+
+    if args.dry_run {
+        ui_println!("{} Would move:", "👁️".blue().bold());
+        for mv in &moves {
+            ui_println!("  {} {} {}", mv.from.yellow(), "→".dimmed(), mv.to.green());
+        }
+        return Ok(());
+    }
+
+    apply_moves(&rgit.repo, &rgit.repo_path, &moves, args.cached)?;
+
+    for mv in &moves {
+        ui_println!("  {} {} {} {}", "📦".blue(), mv.from.yellow(), "→".dimmed(), mv.to.green());
+    }
+    ui_println!("{} Moved {} path{}{}",
+            "✅".green().bold(),
+            moves.len(),
+            if moves.len() == 1 { "" } else { "s" },
+            if args.cached { " in the index (working tree untouched)" } else { "" });
+    ui_println!("{} Use {} to record the rename", "💡".blue(), "rgit commit".cyan());
+    Ok(())
+}
+
+/// A file or directory to move, with the index entries it carries
+#[derive(Debug, Clone, PartialEq)]
+struct Move {
+    from: String,
+    to: String,
+    /// `(old, new)` paths of every tracked file below `from`
+    entries: Vec<(String, String)>,
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+/// Work out where each source goes, refusing moves that would lose data
+fn plan_moves(
+    repo: &Repository,
+    workdir: &Path,
+    cwd: &Path,
+    sources: &[String],
+    destination: &str,
+    args: &MvArgs,
+) -> Result<Vec<Move>> {
+    let index = repo.index()?;
+    let destination = validate_pathspec(workdir, cwd, destination)?;
+    let into_directory = destination.is_empty()
+        || workdir.join(&destination).is_dir()
+        || tracked_below(&index, &destination);
+
+    let mut expanded = Vec::new();
+    for spec in sources {
+        let relative = validate_pathspec(workdir, cwd, spec)?;
+        if is_glob(&relative) {
+            let matched = index_paths_matching(&index, &relative)?;
+            if matched.is_empty() {
+                return Err(RgitError::InvalidArgument(
+                    format!("pathspec '{}' did not match any tracked files", spec)
+                ).into());
+            }
+            expanded.extend(matched);
+        } else {
+            expanded.push(relative);
+        }
+    }
+
+    if expanded.len() > 1 && !into_directory {
+        return Err(RgitError::InvalidArgument(
+            format!("destination '{}' is not a directory", destination)
+        ).into());
+    }
+
+    let mut moves = Vec::new();
+    for from in expanded {
+        let to = if into_directory {
+            let name = Path::new(&from).file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .ok_or_else(|| RgitError::InvalidArgument(format!("cannot move '{}'", from)))?;
+            join(&destination, &name)
+        } else {
+            destination.clone()
+        };
+
+        check_move(&index, workdir, &from, &to, args)?;
+        let entries = entries_below(&index, &from)
+            .into_iter()
+            .map(|old| {
+                let new = format!("{}{}", to, &old[from.len()..]);
+                (old, new)
+            })
+            .collect();
+        moves.push(Move { from, to, entries });
+    }
+    Ok(moves)
+}
+
+fn check_move(index: &Index, workdir: &Path, from: &str, to: &str, args: &MvArgs) -> Result<()> {
+    let fail = |reason: &str| -> Result<()> {
+        Err(RgitError::InvalidArgument(format!("cannot move '{}' to '{}': {}", from, to, reason)).into())
+    };
+
+    if from.is_empty() || from == to {
+        return fail("source and destination are the same");
+    }
+    if to.starts_with(&format!("{}/", from)) {
+        return fail("cannot move a directory into itself");
+    }
+    if entries_below(index, from).is_empty() {
+        return fail("source is not under version control");
+    }
+    if index.iter().any(|e| e.path == from.as_bytes() && (e.flags >> 12) & 0x3 != 0) {
+        return fail("source has unresolved conflicts");
+    }
+    if !args.force {
+        if !args.cached && workdir.join(to).symlink_metadata().is_ok() {
+            return fail("destination exists (use --force to overwrite)");
+        }
+        if index.get_path(Path::new(to), 0).is_some() {
+            return fail("destination is tracked (use --force to overwrite)");
+        }
+    }
+    Ok(())
+}
+
+/// Tracked files at `path` or below it
+fn entries_below(index: &Index, path: &str) -> Vec<String> {
+    let prefix = format!("{}/", path);
+    index.iter()
+        .map(|e| String::from_utf8_lossy(&e.path).to_string())
+        .filter(|p| *p == path || p.starts_with(&prefix))
+        .collect()
+}
+
+fn tracked_below(index: &Index, path: &str) -> bool {
+    let prefix = format!("{}/", path);
+    index.iter().any(|e| e.path.starts_with(prefix.as_bytes()))
+}
+
+fn join(directory: &str, name: &str) -> String {
+    if directory.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", directory, name)
+    }
+}
+
+// =============================================================================
+// Moving
+// =============================================================================
+
+/// Move files on disk (unless `cached`) and rename their index entries,
+/// keeping staged content as it is
+fn apply_moves(repo: &Repository, workdir: &Path, moves: &[Move], cached: bool) -> Result<()> {
+    let mut index = repo.index()?;
+
+    for mv in moves {
+        if !cached {
+            let from = workdir.join(&mv.from);
+            let to = workdir.join(&mv.to);
+            if let Some(parent) = to.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            if to.is_file() {
+                fs::remove_file(&to)?;
+            }
+            fs::rename(&from, &to)?;
+            remove_empty_parents(workdir, &from);
+        }
+
+        for (old, new) in &mv.entries {
+            if let Some(mut entry) = index.get_path(Path::new(old), 0) {
+                index.remove_path(Path::new(old))?;
+                entry.path = new.as_bytes().to_vec();
+                index.add(&entry)?;
+            }
+        }
+    }
+
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn args() -> MvArgs {
+        MvArgs { paths: Vec::new(), cached: false, force: false, dry_run: false }
+    }
+
+    fn plan(repo: &TestRepo, sources: &[&str], destination: &str, args: &MvArgs) -> Result<Vec<Move>> {
+        let sources: Vec<String> = sources.iter().map(|s| s.to_string()).collect();
+        plan_moves(repo.repo(), repo.path(), repo.path(), &sources, destination, args)
+    }
+
+    fn index_paths(repo: &TestRepo) -> Vec<String> {
+        let mut index = repo.repo().index().unwrap();
+        index.read(true).unwrap();
+        index.iter().map(|e| String::from_utf8_lossy(&e.path).to_string()).collect()
+    }
+
+    #[test]
+    fn test_plan_moves() {
+        let repo = TestRepo::new();
+        repo.write("a.txt", "a\n").write("b.txt", "b\n").write("src/lib.rs", "lib\n").write("docs/x.md", "x\n");
+        repo.commit("Initial");
+
+        let moves = plan(&repo, &["a.txt"], "renamed.txt", &args()).unwrap();
+        assert_eq!(moves[0].to, "renamed.txt");
+
+        let moves = plan(&repo, &["*.txt"], "docs", &args()).unwrap();
+        let targets: Vec<&str> = moves.iter().map(|m| m.to.as_str()).collect();
+        assert_eq!(targets, vec!["docs/a.txt", "docs/b.txt"]);
+
+        let moves = plan(&repo, &["src"], "code", &args()).unwrap();
+        assert_eq!(moves[0].entries, vec![("src/lib.rs".to_string(), "code/lib.rs".to_string())]);
+
+        assert!(plan(&repo, &["a.txt", "b.txt"], "new.txt", &args()).is_err());
+        assert!(plan(&repo, &["a.txt"], "b.txt", &args()).is_err());
+        assert!(plan(&repo, &["a.txt"], "b.txt", &MvArgs { force: true, ..args() }).is_ok());
+        assert!(plan(&repo, &["untracked.txt"], "x.txt", &args()).is_err());
+        assert!(plan(&repo, &["src"], "src/inner", &args()).is_err());
+    }
+
+    #[test]
+    fn test_apply_moves() {
+        let repo = TestRepo::new();
+        repo.write("src/lib.rs", "lib\n").write("notes.txt", "notes\n");
+        repo.commit("Initial");
+        // Staged content must travel with the rename
+        repo.write("notes.txt", "staged\n").stage("notes.txt");
+
+        let moves = plan(&repo, &["src", "notes.txt"], "", &args());
+        assert!(moves.is_err(), "moving onto the root keeps the same paths");
+
+        let mut moves = plan(&repo, &["src"], "code", &args()).unwrap();
+        moves.extend(plan(&repo, &["notes.txt"], "NOTES.txt", &args()).unwrap());
+        apply_moves(repo.repo(), repo.path(), &moves, false).unwrap();
+
+        assert!(repo.path().join("code/lib.rs").exists());
+        assert!(!repo.path().join("src").exists());
+        assert_eq!(index_paths(&repo), vec!["NOTES.txt", "code/lib.rs"]);
+
+        let statuses = repo.repo().statuses(None).unwrap();
+        assert!(statuses.iter().all(|s| !s.status().is_wt_new() && !s.status().is_wt_modified()));
+    }
+
+    #[test]
+    fn test_cached_move_records_manual_rename() {
+        let repo = TestRepo::new();
+        repo.commit_file("old.txt", "content\n", "Initial");
+        fs::rename(repo.path().join("old.txt"), repo.path().join("new.txt")).unwrap();
+
+        let moves = plan(&repo, &["old.txt"], "new.txt", &MvArgs { cached: true, ..args() }).unwrap();
+        apply_moves(repo.repo(), repo.path(), &moves, true).unwrap();
+
+        assert_eq!(index_paths(&repo), vec!["new.txt"]);
+        assert!(repo.repo().statuses(None).unwrap().iter().all(|s| !s.status().is_wt_new()));
+    }
+}
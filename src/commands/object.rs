@@ -0,0 +1,120 @@
+use anyhow::Result;
+use colored::*;
+use git2::{ObjectType, Repository, Tree};
+use serde::Serialize;
+use std::io::Write;
+
+use crate::cli::ObjectArgs;
+use crate::config::Config;
+use crate::core::{Revision, RgitCore};
+use crate::error::RgitError;
+
+/// An object as git stores it
+#[derive(Debug, Serialize)]
+struct RawObject {
+    oid: String,
+    #[serde(rename = "type")]
+    kind: String,
+    /// Size of the content in bytes, without the object header
+    size: usize,
+    /// The content as `git cat-file -p` shows it, `null` for binary blobs
+    content: Option<String>,
+    /// The exact bytes behind `content`
+    #[serde(skip)]
+    data: Vec<u8>,
+}
+
+/// Execute the object command
+pub async fn execute(args: &ObjectArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let Revision::Single(object) = rgit.resolve_revision(&args.object)? else {
+        return Err(RgitError::InvalidArgument(format!("{} is a range, not an object", args.object)).into());
+    };
+    let raw = read_object(&rgit.repo, object.id())?;
+
+    if args.r#type {
+        println!("{}", raw.kind);
+    } else if args.size {
+        println!("{}", raw.size);
+    } else if args.json {
+        println!("{}", serde_json::to_string_pretty(&raw)?);
+    } else {
+        println!("{} {}  {}", raw.kind.bold(), raw.oid.yellow(), format!("{} bytes", raw.size).dimmed());
+        println!();
+        match &raw.content {
+            // The exact bytes, so commits in other encodings come out unchanged
+            Some(_) => {
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(&raw.data)?;
+                stdout.flush()?;
+            }
+            None => ui_println!("{} Binary content; save it with {}",
+                    "💡".blue(), format!("rgit cat {} > file", &raw.oid[..12]).cyan()),
+        }
+    }
+    Ok(())
+}
+
+/// Type, size and readable content of the object `oid`
+fn read_object(repo: &Repository, oid: git2::Oid) -> Result<RawObject> {
+    let odb = repo.odb()?;
+    let odb_object = odb.read(oid)?;
+    let kind = odb_object.kind();
+    let (content, data) = match kind {
+        // Trees are stored as binary entries; list them like git does
+        ObjectType::Tree => {
+            let listing = tree_listing(&repo.find_tree(oid)?);
+            (Some(listing.clone()), listing.into_bytes())
+        }
+        ObjectType::Blob if repo.find_blob(oid)?.is_binary() => (None, odb_object.data().to_vec()),
+        _ => (Some(String::from_utf8_lossy(odb_object.data()).into_owned()), odb_object.data().to_vec()),
+    };
+    Ok(RawObject {
+        oid: oid.to_string(),
+        kind: kind.str().to_string(),
+        size: odb_object.len(),
+        content,
+        data,
+    })
+}
+
+/// One `<mode> <type> <oid>\t<name>` line per entry
+fn tree_listing(tree: &Tree) -> String {
+    tree.iter()
+        .map(|entry| format!("{:06o} {} {}\t{}\n",
+                entry.filemode(),
+                entry.kind().map(|kind| kind.str()).unwrap_or("unknown"),
+                entry.id(),
+                entry.name().unwrap_or("")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_read_object() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("README.md", "hello\n", "Add readme");
+        let repo = test_repo.repo();
+        let commit = repo.head().unwrap().peel_to_commit().unwrap();
+
+        let raw = read_object(repo, commit.id()).unwrap();
+        assert_eq!(raw.kind, "commit");
+        let content = raw.content.unwrap();
+        assert!(content.starts_with(&format!("tree {}\n", commit.tree_id())));
+        assert!(content.ends_with("Add readme"));
+
+        let tree = read_object(repo, commit.tree_id()).unwrap();
+        assert_eq!(tree.kind, "tree");
+        assert_eq!(tree.content.unwrap(), "100644 blob ce013625030ba8dba906f756967f9e9ca394464a\tREADME.md\n");
+
+        let latin1 = read_object(repo, repo.blob(b"caf\xe9\n").unwrap()).unwrap();
+        assert_eq!(latin1.content.as_deref(), Some("caf\u{fffd}\n"));
+        assert_eq!(latin1.data, b"caf\xe9\n");
+
+        let blob = read_object(repo, repo.blob(b"\0\x01binary").unwrap()).unwrap();
+        assert_eq!((blob.kind.as_str(), blob.size, blob.content), ("blob", 8, None));
+    }
+}
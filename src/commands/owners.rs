@@ -0,0 +1,71 @@
+use anyhow::Result;
+use colored::*;
+use git2::Repository;
+use std::path::PathBuf;
+
+use crate::cli::{OwnersArgs, OwnersCommands};
+use crate::codeowners::{CodeOwners, LOCATIONS};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::validation::validate_pathspec;
+
+/// Execute the owners command
+pub async fn execute(args: &OwnersArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let workdir = rgit.repo.workdir().ok_or(RgitError::NotInRepository)?;
+    let codeowners = CodeOwners::load(workdir)?
+        .ok_or_else(|| RgitError::FileNotFound(PathBuf::from(LOCATIONS.join(", "))))?;
+
+    match &args.action {
+        OwnersCommands::Check => check(&rgit.repo, &codeowners),
+        OwnersCommands::Of { paths } => {
+            let cwd = std::env::current_dir()?;
+            for path in paths {
+                let relative = validate_pathspec(workdir, &cwd, path)?;
+                match codeowners.rule_for(&relative) {
+                    Some(rule) if !rule.owners.is_empty() => println!("{}  {}  {}",
+                            relative.bold(),
+                            rule.owners.join(" ").green(),
+                            format!("({}, line {})", rule.pattern, rule.line).dimmed()),
+                    Some(rule) => println!("{}  {}  {}",
+                            relative.bold(),
+                            "no owner".yellow(),
+                            format!("(cleared by {}, line {})", rule.pattern, rule.line).dimmed()),
+                    None => println!("{}  {}", relative.bold(), "no owner".yellow()),
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Report lines GitHub skips and rules that match nothing
+fn check(repo: &Repository, codeowners: &CodeOwners) -> Result<()> {
+    ui_println!("{} Checking {} ({} rules)", "🔍".blue(), codeowners.path.cyan(), codeowners.rules.len());
+
+    for problem in &codeowners.problems {
+        ui_println!("  {} line {}: {}", "❌".red(), problem.line, problem.message);
+    }
+    let files = tracked_files(repo)?;
+    let unmatched = codeowners.unmatched_rules(&files);
+    for rule in &unmatched {
+        ui_println!("  {} line {}: '{}' matches no tracked files", "⚠️".yellow(), rule.line, rule.pattern);
+    }
+
+    let unowned = files.iter().filter(|file| codeowners.owners_of(file).is_empty()).count();
+    if unowned > 0 {
+        ui_println!("  {} {} of {} tracked files have no owner", "ℹ️".blue(), unowned, files.len());
+    }
+
+    let count = codeowners.problems.len() + unmatched.len();
+    if count == 0 {
+        ui_println!("{} CODEOWNERS is valid", "✅".green().bold());
+        return Ok(());
+    }
+    Err(RgitError::OperationFailed(format!("{} CODEOWNERS problem{}", count, if count == 1 { "" } else { "s" })).into())
+}
+
+/// Paths of every file in the index
+pub fn tracked_files(repo: &Repository) -> Result<Vec<String>> {
+    Ok(repo.index()?.iter().map(|entry| String::from_utf8_lossy(&entry.path).into_owned()).collect())
+}
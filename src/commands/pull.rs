@@ -1,40 +1,87 @@
 use anyhow::Result;
 use colored::*;
-use git2::{Repository, AnnotatedCommit, FetchOptions, RemoteCallbacks};
-use std::io::{self, Write};
+use git2::{Repository, AnnotatedCommit, Direction, FetchOptions, Oid, RemoteCallbacks};
 
 use crate::cli::PullArgs;
+use crate::commands::cherry_pick::conflicted_paths;
+use crate::commands::rebase;
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
-use crate::interactive::InteractivePrompt;
+use crate::interactive::{InteractivePrompt, TransferProgress};
+use crate::network;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+
+/// Most incoming commits listed before integrating them
+const INCOMING_SHOWN: usize = 10;
+
+/// How fetched commits are brought into the current branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PullMode {
+    Merge,
+    Rebase,
+    FastForwardOnly,
+}
+
+impl PullMode {
+    /// Parse a `pull.mode` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "merge" => Some(PullMode::Merge),
+            "rebase" => Some(PullMode::Rebase),
+            "ff-only" => Some(PullMode::FastForwardOnly),
+            _ => None,
+        }
+    }
+
+    /// Flags win over `pull.mode`, which falls back to `git.pull_rebase`
+    pub fn resolve(args: &PullArgs, config: &Config) -> Self {
+        if args.rebase {
+            PullMode::Rebase
+        } else if args.ff_only {
+            PullMode::FastForwardOnly
+        } else if args.no_rebase {
+            PullMode::Merge
+        } else if let Some(mode) = config.pull.mode.as_deref().and_then(Self::parse) {
+            mode
+        } else if config.git.pull_rebase {
+            PullMode::Rebase
+        } else {
+            PullMode::Merge
+        }
+    }
+}
 
 /// Execute the pull command
 pub async fn execute(args: &PullArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("{} Pulling changes...", "🔄".blue().bold());
+    ui_println!("{} Pulling changes...", "🔄".blue().bold());
     
     let repo = &rgit.repo;
+    let mode = PullMode::resolve(args, config);
     
     // Check for uncommitted changes
     let status = rgit.status()?;
-    if !status.is_clean() && !args.force {
-        println!("{} You have uncommitted changes:", "⚠️".yellow().bold());
+    let has_tracked_changes = !status.staged.is_empty() || !status.unstaged.is_empty();
+    let autostash = args.autostash && has_tracked_changes;
+    if !status.is_clean() && !args.force && !autostash {
+        ui_println!("{} You have uncommitted changes:", "⚠️".yellow().bold());
         
         if !status.staged.is_empty() {
-            println!("  {} {} staged files", "📝".green(), status.staged.len());
+            ui_println!("  {} {} staged files", "📝".green(), status.staged.len());
         }
         if !status.unstaged.is_empty() {
-            println!("  {} {} unstaged files", "📝".yellow(), status.unstaged.len());
+            ui_println!("  {} {} unstaged files", "📝".yellow(), status.unstaged.len());
         }
         if !status.untracked.is_empty() {
-            println!("  {} {} untracked files", "❓".red(), status.untracked.len());
+            ui_println!("  {} {} untracked files", "❓".red(), status.untracked.len());
         }
         
         if config.is_interactive() {
             println!("\nOptions:");
-            println!("  • {} - Stash changes and pull", "rgit stash && rgit pull".cyan());
-            println!("  • {} - Commit changes and pull", "rgit commit && rgit pull".cyan());
-            println!("  • {} - Force pull (may lose changes)", "rgit pull --force".red());
+            ui_println!("  • {} - Stash changes, pull and reapply them", "rgit pull --autostash".cyan());
+            ui_println!("  • {} - Commit changes and pull", "rgit commit && rgit pull".cyan());
+            ui_println!("  • {} - Force pull (may lose changes)", "rgit pull --force".red());
             
             let continue_anyway = InteractivePrompt::new()
                 .with_message("Continue with pull anyway?")
@@ -51,20 +98,40 @@ pub async fn execute(args: &PullArgs, rgit: &RgitCore, config: &Config) -> Resul
     // Determine remote and branch
     let (remote_name, branch_name) = determine_pull_source(repo, args)?;
     
-    println!("{} Remote: {}", "📡".blue(), remote_name.cyan());
-    println!("{} Branch: {}", "🌿".green(), branch_name.yellow());
+    ui_println!("{} Remote: {}", "📡".blue(), remote_name.cyan());
+    ui_println!("{} Branch: {}", "🌿".green(), branch_name.yellow());
     
     // Fetch first
     let fetch_head = perform_fetch(repo, &remote_name, &branch_name, config).await?;
+    let upstream_label = format!("{}/{}", remote_name, branch_name);
+    let head_id = repo.head()?.peel_to_commit()?.id();
+    let incoming = commits_between(repo, head_id, fetch_head.id())?;
+    show_incoming(repo, &incoming, &upstream_label)?;
     
-    // Determine merge strategy
-    if args.rebase {
-        perform_rebase(repo, &fetch_head, config).await?;
-    } else {
-        perform_merge(repo, &fetch_head, args, config).await?;
+    if autostash {
+        stash_changes(repo, &upstream_label)?;
     }
     
-    println!("{} Pull completed successfully", "✅".green().bold());
+    // Integrate using the chosen strategy
+    let integrated = integrate(repo, &fetch_head, mode, &upstream_label, rgit, config).await;
+    
+    if autostash {
+        // Put local changes back even when integrating failed, unless a
+        // merge or rebase was left half done for the user to finish
+        if integrated.is_ok() || repo.state() == git2::RepositoryState::Clean {
+            restore_stash(repo)?;
+        } else {
+            ui_println!("{} Your local changes are saved in the stash; run {} once you're done",
+                    "📦".blue(), "rgit stash pop".cyan());
+        }
+    }
+    let strategy = integrated?;
+    
+    ui_println!("{} Pull completed successfully ({}, {} new commit{})",
+            "✅".green().bold(),
+            strategy,
+            incoming.len(),
+            if incoming.len() == 1 { "" } else { "s" });
     
     // Show summary
     show_pull_summary(repo, &remote_name, &branch_name, config)?;
@@ -72,6 +139,108 @@ pub async fn execute(args: &PullArgs, rgit: &RgitCore, config: &Config) -> Resul
     Ok(())
 }
 
+/// Bring the fetched commits into the current branch, returning the strategy used
+async fn integrate<'a>(
+    repo: &'a Repository,
+    fetch_head: &AnnotatedCommit<'a>,
+    mode: PullMode,
+    upstream_label: &str,
+    rgit: &RgitCore,
+    config: &Config,
+) -> Result<&'static str> {
+    let (analysis, _) = repo.merge_analysis(&[fetch_head])?;
+    
+    if analysis.is_up_to_date() {
+        ui_println!("{} Already up to date", "✅".green());
+        Ok("already up to date")
+    } else if analysis.is_fast_forward() {
+        ui_println!("{} Strategy: fast-forward", "⚡".yellow());
+        perform_fast_forward_merge(repo, fetch_head)?;
+        Ok("fast-forward")
+    } else if analysis.is_normal() {
+        match mode {
+            PullMode::FastForwardOnly => {
+                ui_println!("{} Your branch and {} have diverged", "⚠️".yellow(), upstream_label.cyan());
+                ui_println!("{} Use {} or {} to combine them", "💡".blue(),
+                        "rgit pull --rebase".cyan(), "rgit pull --no-rebase".cyan());
+                Err(RgitError::FastForwardNotPossible.into())
+            }
+            PullMode::Merge => {
+                ui_println!("{} Strategy: merge", "🔀".blue());
+                perform_normal_merge(repo, fetch_head, upstream_label, config).await?;
+                Ok("merge")
+            }
+            PullMode::Rebase => {
+                ui_println!("{} Strategy: rebase onto {}", "🔄".blue(), upstream_label.cyan());
+                perform_rebase(repo, fetch_head, rgit).await?;
+                Ok("rebase")
+            }
+        }
+    } else {
+        Err(RgitError::MergeNotPossible.into())
+    }
+}
+
+/// Commits reachable from `tip` but not from `base`, newest first
+fn commits_between(repo: &Repository, base: Oid, tip: Oid) -> Result<Vec<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(tip)?;
+    revwalk.hide(base)?;
+    Ok(revwalk.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+/// List the commits a pull brings in
+fn show_incoming(repo: &Repository, incoming: &[Oid], upstream_label: &str) -> Result<()> {
+    if incoming.is_empty() {
+        return Ok(());
+    }
+    ui_println!("{} {} new commit{} from {}:",
+            "📥".blue(),
+            incoming.len(),
+            if incoming.len() == 1 { "" } else { "s" },
+            upstream_label.cyan());
+    for id in incoming.iter().take(INCOMING_SHOWN) {
+        let commit = repo.find_commit(*id)?;
+        println!("  {} {}", shorten_oid(id, 8).yellow(), commit.summary().unwrap_or(""));
+    }
+    if incoming.len() > INCOMING_SHOWN {
+        ui_println!("  {} and {} more", "…".dimmed(), incoming.len() - INCOMING_SHOWN);
+    }
+    Ok(())
+}
+
+/// Stash tracked changes so the pull can run on a clean tree
+fn stash_changes(repo: &Repository, upstream_label: &str) -> Result<()> {
+    let mut stash_repo = Repository::open(repo.path())?;
+    let signature = stash_repo.signature()
+        .or_else(|_| git2::Signature::now("rgit", "rgit@localhost"))?;
+    stash_repo.stash_save(&signature, &format!("rgit autostash before pulling {}", upstream_label), None)?;
+    ui_println!("{} Stashed local changes", "📦".blue());
+    Ok(())
+}
+
+/// Reapply the autostash, keeping it if that conflicts
+fn restore_stash(repo: &Repository) -> Result<()> {
+    let mut stash_repo = Repository::open(repo.path())?;
+    if let Err(e) = stash_repo.stash_apply(0, None) {
+        ui_println!("{} Could not reapply local changes: {}", "⚠️".yellow(), e.message());
+        ui_println!("{} They are still in the stash; see {}", "💡".blue(), "rgit stash list".cyan());
+        return Ok(());
+    }
+
+    let mut index = stash_repo.index()?;
+    index.read(true)?;
+    let conflicts = conflicted_paths(&index)?;
+    if conflicts.is_empty() {
+        stash_repo.stash_drop(0)?;
+        ui_println!("{} Reapplied your local changes", "♻️".green());
+    } else {
+        ui_println!("{} Reapplying local changes conflicted in: {}", "⚠️".yellow(), conflicts.join(", "));
+        ui_println!("{} The changes are also kept in the stash", "💡".blue());
+    }
+    Ok(())
+}
+
 /// Determine what remote and branch to pull from
 fn determine_pull_source(repo: &Repository, args: &PullArgs) -> Result<(String, String)> {
     let remote_name = args.remote.clone()
@@ -135,42 +304,31 @@ async fn perform_fetch<'a>(
     branch_name: &str,
     config: &Config,
 ) -> Result<AnnotatedCommit<'a>> {
-    println!("{} Fetching from {}/{}", "📥".blue(), remote_name.cyan(), branch_name.yellow());
+    ui_println!("{} Fetching from {}/{}", "📥".blue(), remote_name.cyan(), branch_name.yellow());
     
-    let mut remote = repo.find_remote(remote_name)
+    let remote = repo.find_remote(remote_name)
         .map_err(|_| RgitError::RemoteNotFound(remote_name.to_string()))?;
+    let mut remote = network::rewritten(repo, remote, config, Direction::Fetch)?;
     
-    // Set up callbacks
-    let mut callbacks = RemoteCallbacks::new();
-    
-    if config.ui.interactive {
-        callbacks.pack_progress(|_stage, current, total| {
-            if total > 0 {
-                let percentage = (current * 100) / total;
-                print!("\r{} Progress: {}%", "📦".blue(), percentage);
-                let _ = io::stdout().flush();
-            }
-        });
-    }
-    
-    // Set up authentication
-    callbacks.credentials(|_url, username_from_url, _allowed_types| {
-        git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-    });
-    
-    let mut fetch_options = FetchOptions::new();
-    fetch_options.remote_callbacks(callbacks);
-    
-    // Perform fetch
+    // Perform fetch, with fresh callbacks and progress for each attempt
     let refspec = format!("refs/heads/{}:refs/remotes/{}/{}", 
                          branch_name, remote_name, branch_name);
     
-    remote.fetch(&[&refspec], Some(&mut fetch_options), None)
-        .map_err(|e| RgitError::FetchFailed(e.message().to_string()))?;
-    
-    if config.ui.interactive {
-        println!(); // New line after progress
-    }
+    network::with_retries(config, || {
+        let progress = TransferProgress::new(config);
+        let mut callbacks = RemoteCallbacks::new();
+        progress.attach(&mut callbacks);
+        
+        // Set up authentication
+        callbacks.credentials(network::credentials(config));
+        
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        
+        let fetched = timings::time(Phase::Network, || remote.fetch(&[&refspec], Some(&mut fetch_options), None));
+        progress.finish();
+        fetched
+    }, |e| RgitError::FetchFailed(e.message().to_string())).await?;
     
     // Get the fetched commit
     let fetch_head_ref = format!("refs/remotes/{}/{}", remote_name, branch_name);
@@ -180,7 +338,7 @@ async fn perform_fetch<'a>(
     let fetch_commit = repo.find_commit(fetch_oid)?;
     let fetch_head = repo.reference_to_annotated_commit(&repo.find_reference(&fetch_head_ref)?)?;
     
-    println!("{} Fetched {} ({})", 
+    ui_println!("{} Fetched {} ({})", 
             "✅".green(), 
             fetch_commit.id().to_string()[..8].yellow(),
             fetch_commit.summary().unwrap_or("No message").white());
@@ -188,35 +346,6 @@ async fn perform_fetch<'a>(
     Ok(fetch_head)
 }
 
-/// Perform merge operation
-async fn perform_merge<'a>(
-    repo: &'a Repository,
-    fetch_head: &AnnotatedCommit<'a>,
-    args: &PullArgs,
-    config: &Config,
-) -> Result<()> {
-    let analysis = repo.merge_analysis(&[fetch_head])?;
-    
-    if analysis.0.is_fast_forward() {
-        println!("{} Fast-forward merge", "⚡".yellow());
-        perform_fast_forward_merge(repo, fetch_head)?;
-    } else if analysis.0.is_normal() {
-        // Check if ff_only is set
-        if args.ff_only {
-            return Err(RgitError::FastForwardNotPossible.into());
-        }
-        
-        println!("{} Creating merge commit", "🔀".blue());
-        perform_normal_merge(repo, fetch_head, config).await?;
-    } else if analysis.0.is_up_to_date() {
-        println!("{} Already up to date", "✅".green());
-    } else {
-        return Err(RgitError::MergeNotPossible.into());
-    }
-    
-    Ok(())
-}
-
 /// Perform fast-forward merge
 fn perform_fast_forward_merge(repo: &Repository, fetch_head: &AnnotatedCommit) -> Result<()> {
     let target_oid = fetch_head.id();
@@ -236,6 +365,7 @@ fn perform_fast_forward_merge(repo: &Repository, fetch_head: &AnnotatedCommit) -
 async fn perform_normal_merge<'a>(
     repo: &'a Repository,
     fetch_head: &AnnotatedCommit<'a>,
+    upstream_label: &str,
     config: &Config,
 ) -> Result<()> {
     // Check for merge conflicts first
@@ -254,9 +384,7 @@ async fn perform_normal_merge<'a>(
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
     
-    let message = format!("Merge branch '{}' into {}", 
-                         fetch_commit.summary().unwrap_or("unknown"),
-                         head_commit.summary().unwrap_or("HEAD"));
+    let message = format!("Merge remote-tracking branch '{}'", upstream_label);
     
     repo.commit(
         Some("HEAD"),
@@ -275,18 +403,18 @@ async fn perform_normal_merge<'a>(
 
 /// Handle merge conflicts
 async fn handle_merge_conflicts<'a>(repo: &'a Repository, config: &Config) -> Result<()> {
-    println!("{} Merge conflicts detected!", "⚠️".red().bold());
+    ui_println!("{} Merge conflicts detected!", "⚠️".red().bold());
     
     let index = repo.index()?;
     let conflicts: Vec<_> = index.conflicts()?.collect();
     
-    println!("{} Conflicted files:", "📝".yellow());
+    ui_println!("{} Conflicted files:", "📝".yellow());
     let mut conflict_files = Vec::new();
     for conflict in &conflicts {
         if let Ok(index_conflict) = conflict {
             if let Some(our_entry) = &index_conflict.our {
                 if let Ok(path) = std::str::from_utf8(&our_entry.path) {
-                    println!("  {} {}", "⚡".red(), path.yellow());
+                    ui_println!("  {} {}", "⚡".red(), path.yellow());
                     conflict_files.push(path.to_string());
                 }
             }
@@ -294,11 +422,11 @@ async fn handle_merge_conflicts<'a>(repo: &'a Repository, config: &Config) -> Re
     }
     
     if config.is_interactive() {
-        println!("\n{} Resolution options:", "💡".blue());
-        println!("  • Manually resolve conflicts in your editor");
-        println!("  • {} - Mark files as resolved", "rgit add <file>".cyan());
-        println!("  • {} - Complete the merge", "rgit commit".cyan());
-        println!("  • {} - Abort the merge", "rgit merge --abort".red());
+        ui_println!("\n{} Resolution options:", "💡".blue());
+        ui_println!("  • Manually resolve conflicts in your editor");
+        ui_println!("  • {} - Mark files as resolved", "rgit add <file>".cyan());
+        ui_println!("  • {} - Complete the merge", "rgit commit".cyan());
+        ui_println!("  • {} - Abort the merge", "rgit merge --abort".red());
         
         InteractivePrompt::new()
             .with_message("Resolve conflicts manually, then continue")
@@ -311,49 +439,17 @@ async fn handle_merge_conflicts<'a>(repo: &'a Repository, config: &Config) -> Re
     Ok(())
 }
 
-/// Perform rebase operation
+/// Replay local commits on top of the fetched ones
 async fn perform_rebase<'a>(
     repo: &'a Repository,
     fetch_head: &AnnotatedCommit<'a>,
-    _config: &Config,
+    rgit: &RgitCore,
 ) -> Result<()> {
-    println!("{} Rebasing current branch", "🔄".blue());
-    
-    let signature = get_signature(repo)?;
-    
-    // Get the current branch
     let head = repo.head()?;
     let head_annotated = repo.reference_to_annotated_commit(&head)?;
     
-    // Start rebase
-    let mut rebase = repo.rebase(
-        Some(&head_annotated),
-        None,  // Use None for upstream base
-        Some(fetch_head),
-        None,
-    )?;
-    
-    // Process rebase operations
-    while let Some(operation) = rebase.next() {
-        match operation {
-            Ok(op) => {
-                println!("  {} Applying: {}", "✅".green(), 
-                        repo.find_commit(op.id())?.summary().unwrap_or("No message"));
-                rebase.commit(None, &signature, None)?;
-            }
-            Err(e) => {
-                println!("{} Rebase conflict: {}", "⚠️".red(), e.message());
-                return Err(RgitError::RebaseConflict(e.message().to_string()).into());
-            }
-        }
-    }
-    
-    // Finish rebase
-    rebase.finish(Some(&signature))?;
-    
-    println!("{} Rebase completed", "✅".green());
-    
-    Ok(())
+    let mut rebase = repo.rebase(Some(&head_annotated), Some(fetch_head), None, None)?;
+    rebase::replay(repo, &mut rebase, &rgit.get_signature()?)
 }
 
 /// Get git signature for commits
@@ -378,28 +474,28 @@ fn show_pull_summary(
         return Ok(());
     }
     
-    println!("\n{} Pull Summary:", "📊".blue().bold());
+    ui_println!("\n{} Pull Summary:", "📊".blue().bold());
     
     // Show current HEAD
     if let Ok(head) = repo.head() {
         if let Ok(commit) = head.peel_to_commit() {
-            println!("  {} Current commit: {}", "📝".yellow(), 
+            ui_println!("  {} Current commit: {}", "📝".yellow(), 
                     commit.id().to_string()[..8].yellow());
             
             if let Some(summary) = commit.summary() {
-                println!("    {} {}", "💬".blue(), summary.white());
+                ui_println!("    {} {}", "💬".blue(), summary.white());
             }
         }
     }
     
     // Show remote tracking
-    println!("  {} Tracking: {}/{}", "🔗".green(), remote_name.cyan(), branch_name.cyan());
+    ui_println!("  {} Tracking: {}/{}", "🔗".green(), remote_name.cyan(), branch_name.cyan());
     
     // Show next steps
-    println!("\n{} Next steps:", "💡".blue());
-    println!("  • {} - View recent changes", "rgit log".cyan());
-    println!("  • {} - Check repository status", "rgit status".cyan());
-    println!("  • {} - Push changes if any", "rgit push".cyan());
+    ui_println!("\n{} Next steps:", "💡".blue());
+    ui_println!("  • {} - View recent changes", "rgit log".cyan());
+    ui_println!("  • {} - Check repository status", "rgit status".cyan());
+    ui_println!("  • {} - Push changes if any", "rgit push".cyan());
     
     Ok(())
 }
@@ -407,19 +503,7 @@ fn show_pull_summary(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
+    use crate::test_support::create_test_repo;
 
     #[test]
     fn test_get_current_branch_name() {
@@ -457,6 +541,30 @@ mod tests {
         assert_eq!(signature.email().unwrap(), "test@example.com");
     }
 
+    #[test]
+    fn test_pull_mode_resolution() {
+        let args = |rebase: bool, ff_only: bool, no_rebase: bool| PullArgs {
+            remote: None,
+            branch: None,
+            rebase,
+            no_edit: false,
+            no_commit: false,
+            force: false,
+            ff_only,
+            no_rebase,
+            autostash: false,
+        };
+        let mut config = Config::minimal();
+        assert_eq!(PullMode::resolve(&args(false, false, false), &config), PullMode::Merge);
+        config.git.pull_rebase = true;
+        assert_eq!(PullMode::resolve(&args(false, false, false), &config), PullMode::Rebase);
+        config.pull.mode = Some("ff-only".to_string());
+        assert_eq!(PullMode::resolve(&args(false, false, false), &config), PullMode::FastForwardOnly);
+        assert_eq!(PullMode::resolve(&args(true, false, false), &config), PullMode::Rebase);
+        assert_eq!(PullMode::resolve(&args(false, false, true), &config), PullMode::Merge);
+        assert_eq!(PullMode::parse("squash"), None);
+    }
+
     #[test]
     fn test_determine_pull_source() {
         let (_temp_dir, repo) = create_test_repo();
@@ -469,6 +577,8 @@ mod tests {
             no_commit: false,
             force: false,
             ff_only: false,
+            no_rebase: false,
+            autostash: false,
         };
         
         let (remote, branch) = determine_pull_source(&repo, &args).unwrap();
@@ -1,22 +1,36 @@
 use anyhow::Result;
 use colored::*;
-use git2::{PushOptions, RemoteCallbacks, Repository};
-use std::io::{self, Write};
+use git2::{Direction, FetchOptions, Oid, PushOptions, RemoteCallbacks, Repository};
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::cli::PushArgs;
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
-use crate::interactive::InteractivePrompt;
+use crate::interactive::{InteractivePrompt, TableDisplay, TransferProgress};
+use crate::network;
+use crate::journal::{Journal, JournalEntry};
+use crate::policy::{Policy, Rule, POLICY_FILE};
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+use crate::validation::{validate_branch_name, validate_remote_name};
 
-/// Execute the push command
+/// Execute the push command.
+///
+/// Push refspecs configured for a remote (`git config --add remote.<name>.push
+/// refs/heads/main:refs/heads/production`) decide where a branch lands there;
+/// a branch with several matching refspecs goes to each destination.
 pub async fn execute(args: &PushArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("{} Pushing changes...", "🚀".blue().bold());
+    if let Some(branches) = &args.delete {
+        return delete_remote_branches(rgit, args, branches, config);
+    }
+
+    ui_println!("{} Pushing changes...", "🚀".blue().bold());
     
     // Check if we have any commits to push
     let status = rgit.status()?;
     if !status.staged.is_empty() {
-        println!("{} You have staged changes that haven't been committed:", "⚠️".yellow());
+        ui_println!("{} You have staged changes that haven't been committed:", "⚠️".yellow());
         println!("  Run {} first", "rgit commit".cyan());
         
         if config.is_interactive() {
@@ -33,69 +47,315 @@ pub async fn execute(args: &PushArgs, rgit: &RgitCore, config: &Config) -> Resul
     let repo = &rgit.repo;
     
     // Determine what to push
-    let (remote_name, branch_specs) = determine_push_target(repo, args, config)?;
-    
-    // Get the remote
-    let mut remote = repo.find_remote(&remote_name)
-        .map_err(|_| RgitError::RemoteNotFound(remote_name.clone()))?;
-    
-    // Show push details
-    println!("{} Remote: {}", "📡".blue(), remote_name.cyan());
-    if let Some(url) = remote.url() {
-        println!("{} URL: {}", "🌐".blue(), url.dimmed());
-    }
-    
-    for spec in &branch_specs {
-        println!("{} Pushing: {}", "🌿".green(), spec.yellow());
-    }
-    
-    // Check if we need to set upstream
     let current_branch = get_current_branch(repo)?;
-    let needs_upstream = should_set_upstream(repo, &current_branch, &remote_name)?;
-    
-    if needs_upstream && !args.set_upstream {
-        if config.is_interactive() {
-            let set_upstream = InteractivePrompt::new()
-                .with_message(&format!("Set '{}' as upstream for '{}'?", remote_name, current_branch))
-                .confirm()?;
-            
-            if set_upstream {
-                println!("{} Setting upstream branch", "🔗".blue());
+    let mut set_upstream = args.set_upstream;
+    let mut remotes = push_remotes(repo, args)?;
+    let default_specs = branch_refspecs(repo, args)?;
+    let mut outcomes = Vec::new();
+
+    // A branch pushed for the first time gets help choosing where it should track
+    let plain_push = !args.all_remotes && !args.all && args.remote.is_none() && args.branch.is_none();
+    if plain_push && !set_upstream && get_default_remote(repo).is_none() {
+        if let Some((remote_name, track)) = choose_upstream_remote(repo, &current_branch, config)? {
+            remotes = vec![remote_name];
+            set_upstream = track;
+        }
+    }
+    enforce_push_policy(repo, &remotes, &default_specs, config)?;
+
+    for remote_name in &remotes {
+        // Get the remote
+        validate_remote_name(remote_name)?;
+        let remote = repo.find_remote(remote_name)
+            .map_err(|_| RgitError::RemoteNotFound(remote_name.clone()))?;
+        let branch_specs = apply_configured_refspecs(&remote, &default_specs);
+        let mut remote = network::rewritten(repo, remote, config, Direction::Push)?;
+
+        // Show push details
+        ui_println!("{} Remote: {}", "📡".blue(), remote_name.cyan());
+        if let Some(url) = remote.pushurl().or(remote.url()) {
+            ui_println!("{} URL: {}", "🌐".blue(), url.dimmed());
+        }
+
+        for spec in &branch_specs {
+            ui_println!("{} Pushing: {}", "🌿".green(), spec.yellow());
+        }
+
+        // Perform the push
+        let before = remote_tips(repo, remote_name, &branch_specs);
+        match perform_push(&mut remote, &branch_specs, args, config).await {
+            Ok(()) => {
+                ui_println!("{} Successfully pushed to {}", "✅".green().bold(), remote_name.cyan());
+                if remote.name().is_none() {
+                    update_tracking_refs(repo, remote_name, &branch_specs)?;
+                }
+                if set_upstream && remotes.len() == 1 {
+                    track_pushed_branches(repo, remote_name, &branch_specs)?;
+                }
+                for (spec, old) in branch_specs.iter().zip(before) {
+                    let (destination, result) = describe_update(repo, spec, old);
+                    outcomes.push(PushOutcome { remote: remote_name.clone(), destination, result: Ok(result) });
+                }
             }
+            // With several remotes, one failing shouldn't stop the others
+            Err(e) if remotes.len() > 1 => {
+                ui_println!("{} Push to {} failed: {}", "❌".red(), remote_name.cyan(), e);
+                for spec in &branch_specs {
+                    let (_, destination) = split_refspec(spec);
+                    outcomes.push(PushOutcome {
+                        remote: remote_name.clone(),
+                        destination: destination.to_string(),
+                        result: Err(e.to_string()),
+                    });
+                }
+            }
+            Err(e) => return Err(e),
         }
     }
-    
-    // Perform the push
-    perform_push(&mut remote, &branch_specs, args, config).await?;
-    
-    println!("{} Successfully pushed to {}", "✅".green().bold(), remote_name.cyan());
-    
-    // Show post-push information
-    show_push_summary(repo, &remote_name, &current_branch, config)?;
-    
+
+    if remotes.len() == 1 {
+        // Show post-push information
+        return show_push_summary(repo, &remotes[0], &current_branch, config);
+    }
+
+    show_push_results(&outcomes);
+    let failed: BTreeSet<&str> = outcomes.iter().filter(|o| o.result.is_err()).map(|o| o.remote.as_str()).collect();
+    if !failed.is_empty() {
+        return Err(RgitError::OperationFailed(format!(
+            "Push failed for {} of {} remotes", failed.len(), remotes.len()
+        )).into());
+    }
     Ok(())
 }
 
-/// Determine what remote and branches to push
-fn determine_push_target(
-    repo: &Repository,
-    args: &PushArgs,
-    _config: &Config,
-) -> Result<(String, Vec<String>)> {
+/// Refuse the push, before anything is sent to any remote, when it breaks
+/// the repository's push policy
+fn enforce_push_policy(repo: &Repository, remotes: &[String], default_specs: &[String], config: &Config) -> Result<()> {
+    let Some(policy) = repo.workdir().map(Policy::load).transpose()?.flatten() else {
+        return Ok(());
+    };
+    if !config.push.enforce_policy {
+        ui_println!("{} Not checking the push policy in {}", "⚠️".yellow(), POLICY_FILE);
+        return Ok(());
+    }
+
+    let mut violations = Vec::new();
+    for remote_name in remotes {
+        // Unknown remotes are reported when the push gets to them
+        let Ok(remote) = repo.find_remote(remote_name) else { continue };
+        let specs = apply_configured_refspecs(&remote, default_specs);
+        violations.extend(policy.push.evaluate(repo, remote_name, &specs)?);
+    }
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    ui_println!("{} Push blocked by {}", "🛑".red().bold(), POLICY_FILE.cyan());
+    let mut rules: Vec<Rule> = violations.iter().map(|violation| violation.rule).collect();
+    rules.sort();
+    rules.dedup();
+    for rule in rules {
+        ui_println!("  {} {}", "❌".red(), rule.title().bold());
+        for violation in violations.iter().filter(|violation| violation.rule == rule) {
+            ui_println!("     • {}", violation);
+        }
+        ui_println!("     {} {}", "💡".blue(), rule.hint());
+    }
+    println!();
+    ui_println!("{} To push anyway this once, run {}", "💡".blue(), "RGIT_SKIP_PUSH_POLICY=1 rgit push".cyan());
+    println!("   or stop checking with {}", "rgit config set push.enforce_policy false".cyan());
+
+    Err(RgitError::OperationFailed(format!(
+        "{} push policy violation{}", violations.len(), if violations.len() == 1 { "" } else { "s" }
+    )).into())
+}
+
+/// Remotes to push to: every remote with --all-remotes, otherwise the one
+/// given, the branch's push remote, its upstream remote or origin
+fn push_remotes(repo: &Repository, args: &PushArgs) -> Result<Vec<String>> {
+    if args.all_remotes {
+        let remotes: Vec<String> = repo.remotes()?.iter().flatten().map(str::to_string).collect();
+        if remotes.is_empty() {
+            return Err(RgitError::RemoteNotFound("no remotes are configured".to_string()).into());
+        }
+        return Ok(remotes);
+    }
+
     let remote_name = args.remote.clone()
+        .or_else(|| get_push_remote(repo))
         .or_else(|| get_default_remote(repo))
         .unwrap_or_else(|| "origin".to_string());
-    
-    let branch_specs = if let Some(ref branch) = args.branch {
-        // Push specific branch
-        vec![format!("refs/heads/{}:refs/heads/{}", branch, branch)]
+    Ok(vec![remote_name])
+}
+
+/// Ask which remote a branch without an upstream should go to, and whether to
+/// track it there from now on
+fn choose_upstream_remote(repo: &Repository, branch: &str, config: &Config) -> Result<Option<(String, bool)>> {
+    let remotes: Vec<String> = repo.remotes()?.iter().flatten().map(str::to_string).collect();
+    if remotes.is_empty() {
+        return Ok(None);
+    }
+    if !config.is_interactive() {
+        ui_println!("{} '{}' has no upstream; run {} to track it",
+                    "💡".blue(), branch.cyan(), format!("rgit push -u {} {}", remotes[0], branch).cyan());
+        return Ok(None);
+    }
+
+    let remote_name = if remotes.len() == 1 {
+        remotes[0].clone()
     } else {
-        // Push current branch
-        let current_branch = get_current_branch(repo)?;
-        vec![format!("refs/heads/{}:refs/heads/{}", current_branch, current_branch)]
+        let default = remotes.iter().position(|r| r == "origin").unwrap_or(0);
+        let choice = InteractivePrompt::new()
+            .with_message(format!("'{}' has no upstream yet. Push it to which remote?", branch))
+            .with_options(&remotes)
+            .with_default(default)
+            .select()?;
+        remotes[choice].clone()
     };
-    
-    Ok((remote_name, branch_specs))
+
+    let track = should_set_upstream(repo, branch, &remote_name)?
+        && InteractivePrompt::new()
+            .with_message(format!("Track '{}/{}' as the upstream of '{}'?", remote_name, branch, branch))
+            .confirm()?;
+    Ok(Some((remote_name, track)))
+}
+
+/// Make each pushed branch track the ref it was pushed to, like `git push -u`
+fn track_pushed_branches(repo: &Repository, remote_name: &str, specs: &[String]) -> Result<()> {
+    let mut config = repo.config()?;
+    let mut tracked = BTreeSet::new();
+    for spec in specs {
+        let (source, destination) = split_refspec(spec);
+        let Some(branch) = source.strip_prefix("refs/heads/") else { continue };
+        if !destination.starts_with("refs/heads/") || !tracked.insert(branch) {
+            continue;
+        }
+        config.set_str(&format!("branch.{}.remote", branch), remote_name)?;
+        config.set_str(&format!("branch.{}.merge", branch), destination)?;
+        ui_println!("{} '{}' now tracks '{}/{}'",
+                    "🔗".blue(), branch.cyan(), remote_name.cyan(),
+                    destination.trim_start_matches("refs/heads/").cyan());
+    }
+    Ok(())
+}
+
+/// Refspecs for the branches (and tags) to push, before remote configuration
+fn branch_refspecs(repo: &Repository, args: &PushArgs) -> Result<Vec<String>> {
+    let mut branch_specs = if args.all {
+        get_all_local_branches(repo)?
+    } else {
+        // Push a specific branch, or the current one
+        let branch = match &args.branch {
+            Some(branch) => branch.clone(),
+            None => get_current_branch(repo)?,
+        };
+        vec![format!("refs/heads/{}:refs/heads/{}", branch, branch)]
+    };
+    if args.tags {
+        branch_specs.extend(get_all_tags(repo)?);
+    }
+    Ok(branch_specs)
+}
+
+/// Replace refspecs whose source matches push refspecs configured for `remote`
+fn apply_configured_refspecs(remote: &git2::Remote, specs: &[String]) -> Vec<String> {
+    let configured: Vec<git2::Refspec> = remote.refspecs()
+        .filter(|refspec| refspec.direction() == Direction::Push)
+        .collect();
+
+    let mut resolved = Vec::new();
+    for spec in specs {
+        let (source, _) = spec.split_once(':').unwrap_or((spec, spec));
+        let mut matched = false;
+        for refspec in configured.iter().filter(|refspec| refspec.src_matches(source)) {
+            let Ok(destination) = refspec.transform(source) else { continue };
+            let Some(destination) = destination.as_str() else { continue };
+            resolved.push(format!("{}{}:{}", if refspec.is_force() { "+" } else { "" }, source, destination));
+            matched = true;
+        }
+        if !matched {
+            resolved.push(spec.clone());
+        }
+    }
+    resolved
+}
+
+/// Source and destination of a `[+]src:dst` refspec
+fn split_refspec(spec: &str) -> (&str, &str) {
+    let spec = spec.trim_start_matches('+');
+    spec.split_once(':').unwrap_or((spec, spec))
+}
+
+/// The remote-tracking ref that mirrors `destination` on `remote`
+fn tracking_ref(remote: &str, destination: &str) -> Option<String> {
+    destination.strip_prefix("refs/heads/").map(|branch| format!("refs/remotes/{}/{}", remote, branch))
+}
+
+/// Point the remote-tracking refs of pushed branches at what was pushed,
+/// which libgit2 only does itself for pushes through a named remote
+fn update_tracking_refs(repo: &Repository, remote: &str, specs: &[String]) -> Result<()> {
+    for spec in specs {
+        let (source, destination) = split_refspec(spec);
+        let Some(name) = tracking_ref(remote, destination) else { continue };
+        match repo.refname_to_id(source) {
+            Ok(oid) => { repo.reference(&name, oid, true, "rgit: push")?; }
+            Err(_) if source.is_empty() => {
+                if let Ok(mut reference) = repo.find_reference(&name) {
+                    reference.delete()?;
+                }
+            }
+            Err(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// What the remote had for each refspec's destination before pushing
+fn remote_tips(repo: &Repository, remote: &str, specs: &[String]) -> Vec<Option<Oid>> {
+    specs.iter()
+        .map(|spec| {
+            let (_, destination) = split_refspec(spec);
+            tracking_ref(remote, destination).and_then(|name| repo.refname_to_id(&name).ok())
+        })
+        .collect()
+}
+
+/// Destination and a short description of what a successful push did to it
+fn describe_update(repo: &Repository, spec: &str, old: Option<Oid>) -> (String, String) {
+    let (source, destination) = split_refspec(spec);
+    let short = |oid: Oid| shorten_oid(&oid, 8);
+    let result = match (old, repo.refname_to_id(source).ok()) {
+        (_, None) => "pushed".to_string(),
+        (None, Some(_)) if destination.starts_with("refs/tags/") => "new tag".to_string(),
+        (None, Some(_)) => "new branch".to_string(),
+        (Some(old), Some(new)) if old == new => "up to date".to_string(),
+        (Some(old), Some(new)) => format!("{}..{}", short(old), short(new)),
+    };
+    let destination = destination.strip_prefix("refs/heads/").unwrap_or(destination);
+    (destination.to_string(), result)
+}
+
+/// Result of pushing one ref to one remote
+#[derive(Debug, Clone)]
+struct PushOutcome {
+    remote: String,
+    destination: String,
+    result: Result<String, String>,
+}
+
+/// Combined table for pushes to several remotes
+fn show_push_results(outcomes: &[PushOutcome]) {
+    ui_println!("\n{} Push Results:", "📊".blue().bold());
+    let mut table = TableDisplay::new()
+        .with_headers(vec!["Remote".to_string(), "Ref".to_string(), "Result".to_string()]);
+    for outcome in outcomes {
+        let result = match &outcome.result {
+            Ok(result) => format!("ok: {}", result),
+            Err(e) => format!("failed: {}", e),
+        };
+        table.add_row(vec![outcome.remote.clone(), outcome.destination.clone(), result]);
+    }
+    table.display();
 }
 
 /// Get the current branch name
@@ -108,6 +368,15 @@ fn get_current_branch(repo: &Repository) -> Result<String> {
     }
 }
 
+/// The push remote configured for the current branch, or the repository default
+fn get_push_remote(repo: &Repository) -> Option<String> {
+    let config = repo.config().ok()?;
+    let branch = repo.head().ok()?.shorthand()?.to_string();
+    config.get_string(&format!("branch.{}.pushRemote", branch))
+        .or_else(|_| config.get_string("remote.pushDefault"))
+        .ok()
+}
+
 /// Get the default remote for the current branch
 fn get_default_remote(repo: &Repository) -> Option<String> {
     if let Ok(head) = repo.head() {
@@ -172,87 +441,66 @@ async fn perform_push(
     args: &PushArgs,
     config: &Config,
 ) -> Result<()> {
-    let mut callbacks = RemoteCallbacks::new();
-    
-    // Set up progress callback
-    if config.ui.interactive {
-        callbacks.pack_progress(|_stage, current, total| {
-            if total > 0 {
-                let percentage = (current * 100) / total;
-                print!("\r{} Progress: {}% ({}/{})", "📤".blue(), percentage, current, total);
-                io::stdout().flush().unwrap();
-            }
-            ()
-        });
-    }
-    
-    // Set up push progress callback
-    callbacks.push_update_reference(|refname, status| {
-        if let Some(msg) = status {
-            println!("\r{} Failed to push {}: {}", "❌".red(), refname, msg);
-            return Err(git2::Error::from_str("Push rejected"));
-        }
-        
-        if config.ui.interactive {
-            println!("\r{} Updated {}", "✅".green(), refname);
-        }
-        
-        Ok(())
-    });
-    
-    // Set up authentication callback if needed
-    callbacks.credentials(|_url, username_from_url, _allowed_types| {
-        git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-    });
-    
-    let mut push_options = PushOptions::new();
-    push_options.remote_callbacks(callbacks);
-    
     // Convert refspecs to the format git2 expects
     let refspec_refs: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
     
-    // Perform the push
-    match remote.push(&refspec_refs, Some(&mut push_options)) {
-        Ok(_) => {
+    // Perform the push, with fresh callbacks and progress for each attempt
+    let pushed = network::with_retries(config, || {
+        let progress = TransferProgress::new(config);
+        let mut callbacks = RemoteCallbacks::new();
+        progress.attach(&mut callbacks);
+        
+        // Report each ref as the remote accepts or rejects it
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(msg) = status {
+                progress.suspend(|| ui_println!("{} Failed to push {}: {}", "❌".red(), refname, msg));
+                return Err(git2::Error::from_str("Push rejected"));
+            }
+            
             if config.ui.interactive {
-                println!(); // New line after progress
+                progress.suspend(|| ui_println!("{} Updated {}", "✅".green(), refname));
             }
-        }
-        Err(e) => {
-            if e.message().contains("non-fast-forward") {
-                println!("\n{} Push rejected (non-fast-forward)", "❌".red().bold());
-                println!("{} The remote contains work that you do not have locally.", "💡".blue());
-                
-                if args.force {
-                    println!("{} Force pushing...", "⚠️".yellow().bold());
-                    force_push(remote, refspecs)?;
-                } else {
-                    println!("Suggestions:");
-                    println!("  • {} - Fetch and merge remote changes", "rgit pull".cyan());
-                    println!("  • {} - Force push (destructive!)", "rgit push --force".red());
-                    return Err(anyhow::anyhow!("Push rejected: {}", e.message()).into());
-                }
+            
+            Ok(())
+        });
+        
+        // Set up authentication callback if needed
+        callbacks.credentials(network::credentials(config));
+        
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        
+        let pushed = timings::time(Phase::Network, || remote.push(&refspec_refs, Some(&mut push_options)));
+        progress.finish();
+        pushed
+    }, |e| RgitError::PushRejected(e.message().to_string())).await;
+    match pushed {
+        Ok(()) => {}
+        Err(RgitError::PushRejected(message)) if message.contains("non-fast-forward") => {
+            ui_println!("\n{} Push rejected (non-fast-forward)", "❌".red().bold());
+            ui_println!("{} The remote contains work that you do not have locally.", "💡".blue());
+            
+            if args.force {
+                ui_println!("{} Force pushing...", "⚠️".yellow().bold());
+                force_push(remote, refspecs, config).await?;
             } else {
-                return Err(anyhow::anyhow!("Push failed: {}", e.message()).into());
+                println!("Suggestions:");
+                ui_println!("  • {} - Fetch and merge remote changes", "rgit pull".cyan());
+                ui_println!("  • {} - Force push (destructive!)", "rgit push --force".red());
+                return Err(anyhow::anyhow!("Push rejected: {}", message).into());
             }
         }
+        Err(RgitError::PushRejected(message)) => {
+            return Err(anyhow::anyhow!("Push failed: {}", message).into());
+        }
+        Err(e) => return Err(e.into()),
     }
     
     Ok(())
 }
 
 /// Force push (dangerous operation)
-fn force_push(remote: &mut git2::Remote, refspecs: &[String]) -> Result<()> {
-    let mut callbacks = RemoteCallbacks::new();
-    
-    // Set up authentication
-    callbacks.credentials(|_url, username_from_url, _allowed_types| {
-        git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
-    });
-    
-    let mut push_options = PushOptions::new();
-    push_options.remote_callbacks(callbacks);
-    
+async fn force_push(remote: &mut git2::Remote<'_>, refspecs: &[String], config: &Config) -> Result<()> {
     // Force push by adding + prefix to refspecs
     let force_refspecs: Vec<String> = refspecs.iter()
         .map(|spec| format!("+{}", spec))
@@ -260,12 +508,194 @@ fn force_push(remote: &mut git2::Remote, refspecs: &[String]) -> Result<()> {
     
     let refspec_refs: Vec<&str> = force_refspecs.iter().map(|s| s.as_str()).collect();
     
-    remote.push(&refspec_refs, Some(&mut push_options))
-        .map_err(|e| anyhow::anyhow!("Force push failed: {}", e.message()))?;
+    network::with_retries(config, || {
+        let mut callbacks = RemoteCallbacks::new();
+        
+        // Set up authentication
+        callbacks.credentials(network::credentials(config));
+        
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        timings::time(Phase::Network, || remote.push(&refspec_refs, Some(&mut push_options)))
+    }, |e| RgitError::PushRejected(e.message().to_string())).await?;
     
     Ok(())
 }
 
+// =============================================================================
+// Remote Branch Deletion
+// =============================================================================
+
+/// Namespace for refs that keep deleted remote tips reachable locally
+const DELETED_REFS: &str = "refs/rgit/deleted";
+
+/// Delete branches on a remote.
+///
+/// Each branch's remote tip is recorded in the journal and pinned under
+/// `refs/rgit/deleted/` (fetching it first when it isn't local) before the
+/// push, so every deletion can be undone with a single command.
+fn delete_remote_branches(rgit: &RgitCore, args: &PushArgs, branches: &[String], config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let remote_name = args.remote.clone()
+        .or_else(|| get_default_remote(repo))
+        .unwrap_or_else(|| "origin".to_string());
+    validate_remote_name(&remote_name)?;
+    for branch in branches {
+        validate_branch_name(branch)?;
+    }
+    let remote = repo.find_remote(&remote_name)
+        .map_err(|_| RgitError::RemoteNotFound(remote_name.clone()))?;
+    let mut remote = network::rewritten(repo, remote, config, Direction::Push)?;
+
+    let tips = list_remote_heads(&mut remote, config)?;
+
+    let branches = if branches.is_empty() {
+        select_remote_branches(&remote_name, &tips, config)?
+    } else {
+        branches.to_vec()
+    };
+    if branches.is_empty() {
+        ui_println!("{} No branches selected", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let mut targets = Vec::with_capacity(branches.len());
+    for branch in &branches {
+        let oid = tips.get(branch)
+            .ok_or_else(|| RgitError::BranchNotFound(format!("{}/{}", remote_name, branch)))?;
+        targets.push((branch.clone(), *oid));
+    }
+    let refspecs: Vec<String> = targets.iter()
+        .map(|(branch, _)| format!(":refs/heads/{}", branch))
+        .collect();
+    enforce_push_policy(repo, std::slice::from_ref(&remote_name), &refspecs, config)?;
+
+    ui_println!("{} Deleting from {}:", "🗑️".red().bold(), remote_name.cyan());
+    for (branch, oid) in &targets {
+        ui_println!("  {} {} ({})", "•".red(), branch.yellow(), shorten_oid(oid, 8).dimmed());
+    }
+
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Delete {} remote branch{}?", targets.len(), if targets.len() == 1 { "" } else { "es" }))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    // Keep every tip locally before anything is destroyed, fetching the ones
+    // we don't have, so the deletion can always be undone
+    let missing: Vec<String> = targets.iter()
+        .filter(|(_, oid)| repo.find_commit(*oid).is_err())
+        .map(|(branch, _)| format!("+refs/heads/{}:{}/{}/{}", branch, DELETED_REFS, remote_name, branch))
+        .collect();
+    if !missing.is_empty() {
+        fetch_refspecs(&mut remote, &missing, config)?;
+    }
+
+    let journal = Journal::for_repo(repo);
+    for (branch, oid) in &targets {
+        journal.record(&JournalEntry::new("push --delete", &format!("refs/heads/{}", branch))
+            .with_old_oid(oid)
+            .with_detail(remote_name.clone()))?;
+
+        let keep_ref = format!("{}/{}/{}", DELETED_REFS, remote_name, branch);
+        repo.reference(&keep_ref, *oid, true, "rgit: remote branch deleted")?;
+    }
+
+    push_refspecs(&mut remote, &refspecs, config)?;
+
+    // Drop the now-stale remote-tracking refs
+    for (branch, _) in &targets {
+        let tracking = format!("refs/remotes/{}/{}", remote_name, branch);
+        if let Ok(mut reference) = repo.find_reference(&tracking) {
+            reference.delete()?;
+        }
+    }
+
+    ui_println!("{} Deleted {} remote branch{}",
+            "✅".green().bold(),
+            targets.len(),
+            if targets.len() == 1 { "" } else { "es" });
+    ui_println!("\n{} To restore: {} (or pick it from {} later)", "💡".blue(), "rgit undo".cyan(), "rgit undo --list".cyan());
+
+    Ok(())
+}
+
+/// Branch tips advertised by a remote, keyed by branch name
+fn list_remote_heads(remote: &mut git2::Remote, config: &Config) -> Result<BTreeMap<String, Oid>> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+
+    let connection = timings::time(Phase::Network, || remote.connect_auth(Direction::Fetch, Some(callbacks), None))
+        .map_err(|e| anyhow::anyhow!("Failed to connect to remote: {}", e.message()))?;
+
+    Ok(connection.list()?
+        .iter()
+        .filter_map(|head| {
+            head.name().strip_prefix("refs/heads/").map(|name| (name.to_string(), head.oid()))
+        })
+        .collect())
+}
+
+/// Let the user pick remote branches to delete
+fn select_remote_branches(remote_name: &str, tips: &BTreeMap<String, Oid>, config: &Config) -> Result<Vec<String>> {
+    // Without asking, nothing is picked for deletion
+    if config.ui.assume_yes {
+        return Ok(Vec::new());
+    }
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+    if tips.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let names: Vec<&String> = tips.keys().collect();
+    let options: Vec<String> = tips.iter()
+        .map(|(name, oid)| format!("{}/{} ({})", remote_name, name, shorten_oid(oid, 8)))
+        .collect();
+
+    let selected = InteractivePrompt::new()
+        .with_message("Select remote branches to delete")
+        .with_options(&options)
+        .multiselect_prompt()?;
+
+    Ok(selected.into_iter().map(|i| names[i].clone()).collect())
+}
+
+/// Push refspecs without the interactive progress display
+pub fn push_refspecs(remote: &mut git2::Remote, refspecs: &[String], config: &Config) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+    callbacks.push_update_reference(|refname, status| match status {
+        Some(msg) => Err(git2::Error::from_str(&format!("{} rejected: {}", refname, msg))),
+        None => Ok(()),
+    });
+
+    let mut push_options = PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+
+    let refspec_refs: Vec<&str> = refspecs.iter().map(|s| s.as_str()).collect();
+    timings::time(Phase::Network, || remote.push(&refspec_refs, Some(&mut push_options)))
+        .map_err(|e| anyhow::anyhow!("Push failed: {}", e.message()))?;
+    Ok(())
+}
+
+/// Fetch refspecs without touching the remote-tracking refs
+fn fetch_refspecs(remote: &mut git2::Remote, refspecs: &[String], config: &Config) -> Result<()> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+
+    timings::time(Phase::Network, || remote.fetch(refspecs, Some(&mut fetch_options), None))
+        .map_err(|e| anyhow::anyhow!("Failed to fetch the branches before deleting them: {}", e.message()))?;
+    Ok(())
+}
+
 /// Show summary after successful push
 fn show_push_summary(
     repo: &Repository,
@@ -277,28 +707,28 @@ fn show_push_summary(
         return Ok(());
     }
     
-    println!("\n{} Push Summary:", "📊".blue().bold());
+    ui_println!("\n{} Push Summary:", "📊".blue().bold());
     
     // Show what was pushed
     if let Ok(head) = repo.head() {
         if let Ok(commit) = head.peel_to_commit() {
-            println!("  {} Latest commit: {}", "📝".yellow(), 
+            ui_println!("  {} Latest commit: {}", "📝".yellow(), 
                     commit.id().to_string()[..8].yellow());
             
             if let Some(summary) = commit.summary() {
-                println!("    {} {}", "💬".blue(), summary.white());
+                ui_println!("    {} {}", "💬".blue(), summary.white());
             }
         }
     }
     
     // Show remote tracking information
-    println!("  {} Remote branch: {}/{}", "🌿".green(), remote_name.cyan(), branch_name.cyan());
+    ui_println!("  {} Remote branch: {}/{}", "🌿".green(), remote_name.cyan(), branch_name.cyan());
     
     // Show next steps
-    println!("\n{} Next steps:", "💡".blue());
-    println!("  • {} - View remote repository", "Open in browser".cyan());
-    println!("  • {} - Check for new activity", "rgit fetch".cyan());
-    println!("  • {} - View commit history", "rgit log".cyan());
+    ui_println!("\n{} Next steps:", "💡".blue());
+    ui_println!("  • {} - View remote repository", "Open in browser".cyan());
+    ui_println!("  • {} - Check for new activity", "rgit fetch".cyan());
+    ui_println!("  • {} - View commit history", "rgit log".cyan());
     
     Ok(())
 }
@@ -306,19 +736,7 @@ fn show_push_summary(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
+    use crate::test_support::create_test_repo;
 
     #[test]
     fn test_get_current_branch() {
@@ -355,6 +773,28 @@ mod tests {
         assert!(needs_upstream.is_ok());
     }
 
+    #[test]
+    fn test_apply_configured_refspecs() {
+        let (_temp_dir, repo) = create_test_repo();
+        let mut remote = repo.remote("backup", "https://example.com/repo.git").unwrap();
+        let specs = vec![
+            "refs/heads/main:refs/heads/main".to_string(),
+            "refs/heads/dev:refs/heads/dev".to_string(),
+        ];
+        assert_eq!(apply_configured_refspecs(&remote, &specs), specs);
+
+        let mut config = repo.config().unwrap();
+        config.set_multivar("remote.backup.push", "^$", "refs/heads/main:refs/heads/production").unwrap();
+        config.set_multivar("remote.backup.push", "^$", "+refs/heads/main:refs/heads/staging").unwrap();
+        remote = repo.find_remote("backup").unwrap();
+        assert_eq!(apply_configured_refspecs(&remote, &specs), [
+            "refs/heads/main:refs/heads/production",
+            "+refs/heads/main:refs/heads/staging",
+            "refs/heads/dev:refs/heads/dev",
+        ]);
+        assert_eq!(split_refspec("+refs/heads/a:refs/heads/b"), ("refs/heads/a", "refs/heads/b"));
+    }
+
     #[test]
     fn test_get_all_tags() {
         let (_temp_dir, repo) = create_test_repo();
@@ -7,11 +7,11 @@ use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::{InteractivePrompt, CommitMessageEditor};
 use crate::submodule::SubmoduleManager;
-use crate::commands::{add, commit, status};
+use crate::commands::{commit, scan, status};
 
 /// Execute the quick-commit command - streamlined commit workflow
 pub async fn execute(args: &QuickCommitArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("{} {} Quick Commit Workflow", "⚡".yellow(), "rgit".cyan().bold());
+    ui_println!("{} {} Quick Commit Workflow", "⚡".yellow(), "rgit".cyan().bold());
     println!();
 
     // Step 1: Check repository state and submodules
@@ -28,8 +28,14 @@ pub async fn execute(args: &QuickCommitArgs, rgit: &RgitCore, config: &Config) -
         return Ok(());
     }
 
+    if !args.allow_secrets {
+        scan::check_staged_changes(rgit, config)?;
+    }
+    commit::check_large_files(rgit, config, args.allow_large_files)?;
+
     // Step 4: Get commit message
     let message = get_quick_commit_message(args, config).await?;
+    let message = commit::append_trailers(&message, &commit::requested_trailers(&args.trailers, rgit, config)?);
 
     // Step 5: Create the commit
     let commit_id = create_quick_commit(rgit, &message).await?;
@@ -75,7 +81,7 @@ async fn validate_quick_commit_preconditions(rgit: &RgitCore, config: &Config) -
 async fn show_quick_commit_status(rgit: &RgitCore, config: &Config) -> Result<()> {
     let status_summary = status::quick_status_check(rgit)?;
 
-    println!("{} Current Status:", "📊".blue().bold());
+    ui_println!("{} Current Status:", "📊".blue().bold());
     println!("  {} {}", "Branch:".bold(), status_summary.branch_name.cyan());
     println!("  {} {}", "Status:".bold(), status_summary.format_summary());
 
@@ -88,11 +94,11 @@ async fn show_quick_commit_status(rgit: &RgitCore, config: &Config) -> Result<()
     }
 
     if status_summary.needs_pull() {
-        println!("  {} {} commits behind remote", "⬇️".blue(), status_summary.behind);
+        ui_println!("  {} {} commits behind remote", "⬇️".blue(), status_summary.behind);
     }
 
     if status_summary.needs_push() {
-        println!("  {} {} commits ahead of remote", "⬆️".blue(), status_summary.ahead);
+        ui_println!("  {} {} commits ahead of remote", "⬆️".blue(), status_summary.ahead);
     }
 
     println!();
@@ -128,7 +134,7 @@ async fn stage_all_changes(rgit: &RgitCore, config: &Config) -> Result<()> {
         return Ok(());
     }
 
-    println!("{} Auto-staging {} file{}...", 
+    ui_println!("{} Auto-staging {} file{}...", 
             "📦".blue(),
             total_to_stage,
             if total_to_stage == 1 { "" } else { "s" });
@@ -136,7 +142,7 @@ async fn stage_all_changes(rgit: &RgitCore, config: &Config) -> Result<()> {
     // Show what will be staged
     if config.ui.interactive && total_to_stage <= 10 {
         for file in status.unstaged.iter().take(5) {
-            println!("  {} {}: {}", 
+            ui_println!("  {} {}: {}", 
                     "○".yellow(), 
                     file.status_symbol(false).yellow(),
                     file.path.white());
@@ -162,7 +168,7 @@ async fn stage_all_changes(rgit: &RgitCore, config: &Config) -> Result<()> {
 /// Stage files interactively
 async fn stage_files_interactively(rgit: &RgitCore, config: &Config) -> Result<()> {
     if !config.is_interactive() {
-        println!("{} No files staged and not in interactive mode", "ℹ️".blue());
+        ui_println!("{} No files staged and not in interactive mode", "ℹ️".blue());
         return Ok(());
     }
 
@@ -170,11 +176,11 @@ async fn stage_files_interactively(rgit: &RgitCore, config: &Config) -> Result<(
     let stageable_count = status.unstaged.len() + status.untracked.len();
 
     if stageable_count == 0 {
-        println!("{} No files to stage", "ℹ️".blue());
+        ui_println!("{} No files to stage", "ℹ️".blue());
         return Ok(());
     }
 
-    println!("{} {} file{} available for staging:", 
+    ui_println!("{} {} file{} available for staging:", 
             "📝".yellow(),
             stageable_count,
             if stageable_count == 1 { "" } else { "s" });
@@ -182,7 +188,7 @@ async fn stage_files_interactively(rgit: &RgitCore, config: &Config) -> Result<(
     // Quick preview
     let preview_count = stageable_count.min(3);
     for file in status.unstaged.iter().take(preview_count) {
-        println!("  {} {}: {}", 
+        ui_println!("  {} {}: {}", 
                 "○".yellow(), 
                 file.status_symbol(false).yellow(),
                 file.path.white());
@@ -219,7 +225,7 @@ async fn stage_files_interactively(rgit: &RgitCore, config: &Config) -> Result<(
         }
         2 => {
             // Continue without staging
-            println!("{} Continuing without staging new files", "ℹ️".blue());
+            ui_println!("{} Continuing without staging new files", "ℹ️".blue());
         }
         _ => {}
     }
@@ -231,7 +237,7 @@ async fn stage_files_interactively(rgit: &RgitCore, config: &Config) -> Result<(
 async fn run_interactive_add(rgit: &RgitCore, config: &Config) -> Result<()> {
     // This would call the interactive add functionality
     // For now, simulate the process
-    println!("{} Interactive file selection...", "🎯".blue());
+    ui_println!("{} Interactive file selection...", "🎯".blue());
     
     // In real implementation, this would call:
     // add::interactive_add(rgit, config).await?;
@@ -246,6 +252,10 @@ async fn get_quick_commit_message(args: &QuickCommitArgs, config: &Config) -> Re
         return Ok(message.clone());
     }
 
+    // There's no message to take by default, so nothing is committed
+    if config.ui.assume_yes {
+        return Err(RgitError::InvalidArgument("--yes can't write the commit message; pass it with -m".to_string()).into());
+    }
     if !config.is_interactive() {
         return Err(RgitError::NonInteractiveEnvironment.into());
     }
@@ -256,10 +266,10 @@ async fn get_quick_commit_message(args: &QuickCommitArgs, config: &Config) -> Re
 
 /// Get a simple commit message for quick workflow
 async fn get_simple_commit_message(config: &Config) -> Result<String> {
-    println!("{} Quick commit message:", "💬".blue());
+    ui_println!("{} Quick commit message:", "💬".blue());
     
     // Provide some helpful examples
-    println!("  {} Examples: 'Fix bug in authentication', 'Add user profile page', 'Update dependencies'", "💡".dimmed());
+    ui_println!("  {} Examples: 'Fix bug in authentication', 'Add user profile page', 'Update dependencies'", "💡".dimmed());
     
     loop {
         let message: String = InteractivePrompt::new()
@@ -269,13 +279,13 @@ async fn get_simple_commit_message(config: &Config) -> Result<String> {
         let trimmed = message.trim();
         
         if trimmed.is_empty() {
-            println!("{} Commit message cannot be empty", "❌".red());
+            ui_println!("{} Commit message cannot be empty", "❌".red());
             continue;
         }
 
         // Basic validation
         if trimmed.len() > 72 {
-            println!("{} Message is quite long ({}). Consider a shorter summary.", 
+            ui_println!("{} Message is quite long ({}). Consider a shorter summary.", 
                     "⚠️".yellow(), trimmed.len());
             
             if InteractivePrompt::new()
@@ -292,7 +302,7 @@ async fn get_simple_commit_message(config: &Config) -> Result<String> {
 
 /// Create the quick commit
 async fn create_quick_commit(rgit: &RgitCore, message: &str) -> Result<git2::Oid> {
-    println!("{} Creating commit...", "📝".blue());
+    ui_println!("{} Creating commit...", "📝".blue());
     
     let commit_id = rgit.commit(message, false)?;
     
@@ -308,7 +318,7 @@ async fn create_quick_commit(rgit: &RgitCore, message: &str) -> Result<git2::Oid
 
 /// Handle push after quick commit
 async fn handle_quick_push(rgit: &RgitCore, config: &Config) -> Result<()> {
-    println!("\n{} Pushing to remote...", "⬆️".blue());
+    ui_println!("\n{} Pushing to remote...", "⬆️".blue());
     
     let branch_info = rgit.get_branch_info()?;
     
@@ -328,7 +338,7 @@ async fn handle_no_upstream_push(rgit: &RgitCore, config: &Config, branch_name:
         return Err(RgitError::RemoteNotFound("upstream".to_string()).into());
     }
 
-    println!("{} No upstream configured for branch '{}'", "⚠️".yellow(), branch_name);
+    ui_println!("{} No upstream configured for branch '{}'", "⚠️".yellow(), branch_name);
     
     let options = vec![
         format!("Set upstream and push to origin/{}", branch_name),
@@ -344,7 +354,7 @@ async fn handle_no_upstream_push(rgit: &RgitCore, config: &Config, branch_name:
     match choice {
         0 => {
             // Set upstream and push
-            println!("  {} Setting upstream to origin/{}", "🔗".blue(), branch_name);
+            ui_println!("  {} Setting upstream to origin/{}", "🔗".blue(), branch_name);
             // In real implementation: set upstream and push
             rgit.success("Pushed and set upstream");
         }
@@ -353,7 +363,7 @@ async fn handle_no_upstream_push(rgit: &RgitCore, config: &Config, branch_name:
             perform_quick_push(rgit, config).await?;
         }
         2 => {
-            println!("  {} Skipping push", "⏭️".blue());
+            ui_println!("  {} Skipping push", "⏭️".blue());
         }
         _ => {}
     }
@@ -384,33 +394,33 @@ async fn show_quick_commit_success(
         return Ok(());
     }
 
-    println!("\n{} Quick commit completed successfully! 🎉", "✅".green().bold());
+    ui_println!("\n{} Quick commit completed successfully! 🎉", "✅".green().bold());
     
     let short_id = crate::utils::shorten_oid(&commit_id, 8);
-    println!("   {} Commit: {}", "📝".blue(), short_id.yellow());
+    ui_println!("   {} Commit: {}", "📝".blue(), short_id.yellow());
     
     if pushed {
-        println!("   {} Changes pushed to remote", "⬆️".green());
+        ui_println!("   {} Changes pushed to remote", "⬆️".green());
     }
 
     // Show updated status
     let final_status = status::quick_status_check(rgit)?;
-    println!("   {} Status: {}", "📊".blue(), final_status.format_summary());
+    ui_println!("   {} Status: {}", "📊".blue(), final_status.format_summary());
 
     // Show next steps
-    println!("\n{} What's next:", "💡".blue().bold());
+    ui_println!("\n{} What's next:", "💡".blue().bold());
     
     if !pushed {
-        println!("  • {} - Share your changes", "rgit push".cyan());
+        ui_println!("  • {} - Share your changes", "rgit push".cyan());
     }
     
     if final_status.has_changes() {
-        println!("  • {} - Stage more changes", "rgit add".cyan());
-        println!("  • {} - Make another quick commit", "rgit quick-commit".cyan());
+        ui_println!("  • {} - Stage more changes", "rgit add".cyan());
+        ui_println!("  • {} - Make another quick commit", "rgit quick-commit".cyan());
     }
     
-    println!("  • {} - Continue working on your project", "Edit files".cyan());
-    println!("  • {} - View commit history", "rgit log".cyan());
+    ui_println!("  • {} - Continue working on your project", "Edit files".cyan());
+    ui_println!("  • {} - View commit history", "rgit log".cyan());
     
     Ok(())
 }
@@ -442,7 +452,7 @@ pub async fn smart_quick_commit(
     // Stage all changes for smart commit
     if status.staged.is_empty() {
         // Auto-stage everything for smart commit
-        println!("{} Auto-staging all changes for smart commit", "📦".blue());
+        ui_println!("{} Auto-staging all changes for smart commit", "📦".blue());
         // In real implementation: stage all changes
     }
 
@@ -499,20 +509,9 @@ fn generate_smart_commit_message(status: &crate::core::RepositoryStatus) -> Resu
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
+    use crate::test_support::create_test_repo;
     use std::fs;
 
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[test]
     fn test_generate_smart_commit_message() {
         use crate::core::{RepositoryStatus, FileStatus};
@@ -0,0 +1,469 @@
+use anyhow::Result;
+use colored::*;
+use git2::{
+    AnnotatedCommit, BranchType, Commit, ErrorCode, Oid, Rebase, RebaseOptions, Repository,
+    RepositoryState, Signature, Sort,
+};
+
+use crate::cli::RebaseArgs;
+use crate::commands::backup;
+use crate::commands::cherry_pick::{conflicted_paths, ensure_no_tracked_changes};
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::output::decoration;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+
+/// Most commits drawn per side of the preview graph
+const PREVIEW_COMMITS: usize = 8;
+
+/// Execute the rebase command
+pub async fn execute(args: &RebaseArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    if args.abort {
+        return abort(repo);
+    }
+    if args.continue_rebase || args.skip {
+        let mut rebase = open_rebase(repo)?;
+        if args.skip {
+            skip_current(repo, &mut rebase)?;
+        } else {
+            commit_resolved(repo, &mut rebase, &rgit.get_signature()?)?;
+        }
+        return replay(repo, &mut rebase, &rgit.get_signature()?);
+    }
+    if args.interactive {
+        return Err(RgitError::OperationNotSupported("interactive rebase".to_string()).into());
+    }
+
+    if repo.state() != RepositoryState::Clean {
+        return Err(RgitError::InvalidRepositoryState(
+            "another operation is in progress; finish or abort it before rebasing".to_string()
+        ).into());
+    }
+    ensure_no_tracked_changes(rgit)?;
+
+    let plan = RebasePlan::new(repo, args)?;
+    if plan.is_up_to_date() {
+        ui_println!("{} '{}' is already based on {}", "✅".green(), plan.branch_label(), plan.onto_label.cyan());
+        return Ok(());
+    }
+
+    for line in plan.preview_lines(repo)? {
+        println!("{}", line);
+    }
+    println!();
+    if config.is_interactive() {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Rebase {} commit{} onto {}?", plan.replay.len(), plural(plan.replay.len()), plan.onto_label))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    backup::snapshot_before(rgit, config, "rebase")?;
+    let branch = plan.branch_commit(repo)?;
+    let upstream = repo.find_annotated_commit(plan.upstream)?;
+    let onto = repo.find_annotated_commit(plan.onto.id())?;
+    let mut options = RebaseOptions::new();
+    let mut rebase = repo.rebase(Some(&branch), Some(&upstream), Some(&onto), Some(&mut options))?;
+    replay(repo, &mut rebase, &rgit.get_signature()?)
+}
+
+// =============================================================================
+// Planning and preview
+// =============================================================================
+
+/// What a rebase will do, worked out before anything moves
+struct RebasePlan<'r> {
+    /// Local branch being rebased; None for a detached HEAD
+    branch: Option<String>,
+    tip: Commit<'r>,
+    upstream: Oid,
+    onto: Commit<'r>,
+    onto_label: String,
+    /// Commits replayed, oldest first (merges are flattened away)
+    replay: Vec<Oid>,
+    /// Parent of the oldest replayed commit
+    old_base: Option<Oid>,
+    /// Commits that leave the branch's history because they are only
+    /// reachable through the old base (`--onto` surgery)
+    dropped: Vec<Oid>,
+}
+
+impl<'r> RebasePlan<'r> {
+    fn new(repo: &'r Repository, args: &RebaseArgs) -> Result<Self> {
+        let branch = match &args.branch {
+            Some(name) => {
+                repo.find_branch(name, BranchType::Local)
+                    .map_err(|_| RgitError::BranchNotFound(name.clone()))?;
+                Some(name.clone())
+            }
+            None => {
+                let head = repo.head()?;
+                if head.is_branch() { head.shorthand().map(String::from) } else { None }
+            }
+        };
+        let tip = match &branch {
+            Some(name) => repo.find_branch(name, BranchType::Local)?.get().peel_to_commit()?,
+            None => repo.head()?.peel_to_commit()?,
+        };
+
+        let (upstream_label, upstream) = match &args.upstream {
+            Some(spec) => (spec.clone(), resolve_commit(repo, spec)?),
+            None => {
+                let name = branch.as_ref().ok_or(RgitError::NoUpstreamBranch)?;
+                let upstream = repo.find_branch(name, BranchType::Local)?
+                    .upstream()
+                    .map_err(|_| RgitError::NoUpstreamBranch)?;
+                let label = upstream.name()?.unwrap_or("upstream").to_string();
+                (label, upstream.get().peel_to_commit()?)
+            }
+        };
+        let (onto_label, onto) = match &args.onto {
+            Some(spec) => (spec.clone(), resolve_commit(repo, spec)?),
+            None => (upstream_label, upstream.clone()),
+        };
+
+        let replay = walk(repo, tip.id(), &[upstream.id()], true)?
+            .into_iter()
+            .filter(|id| repo.find_commit(*id).map(|c| c.parent_count() <= 1).unwrap_or(true))
+            .collect::<Vec<_>>();
+        let old_base = match replay.first() {
+            Some(first) => repo.find_commit(*first)?.parent_id(0).ok(),
+            None => None,
+        };
+        let dropped = walk(repo, tip.id(), &[onto.id()], false)?
+            .into_iter()
+            .filter(|id| !replay.contains(id))
+            .collect();
+
+        Ok(Self { branch, tip, upstream: upstream.id(), onto, onto_label, replay, old_base, dropped })
+    }
+
+    fn branch_label(&self) -> String {
+        self.branch.clone().unwrap_or_else(|| "HEAD".to_string())
+    }
+
+    /// Nothing would change: the replayed commits already sit on `onto`
+    fn is_up_to_date(&self) -> bool {
+        self.dropped.is_empty() && match self.replay.first() {
+            Some(_) => self.old_base == Some(self.onto.id()),
+            None => self.tip.id() == self.onto.id(),
+        }
+    }
+
+    /// The branch to rebase; a named branch is checked out by the rebase
+    fn branch_commit(&self, repo: &'r Repository) -> Result<AnnotatedCommit<'r>> {
+        Ok(match &self.branch {
+            Some(name) => repo.reference_to_annotated_commit(repo.find_branch(name, BranchType::Local)?.get())?,
+            None => repo.find_annotated_commit(self.tip.id())?,
+        })
+    }
+
+    /// A before/after sketch of the branch's history; the lines carry commit
+    /// summaries, so their glyphs are already decorations
+    fn preview_lines(&self, repo: &Repository) -> Result<Vec<String>> {
+        let label = self.branch_label();
+        let mut lines = vec![format!("{} Rebase {} commit{} of '{}' onto {} ({})",
+                                     decoration("📋").blue().bold(),
+                                     self.replay.len(),
+                                     plural(self.replay.len()),
+                                     label.cyan(),
+                                     self.onto_label.cyan(),
+                                     shorten_oid(&self.onto.id(), 8).yellow())];
+
+        lines.push(format!("  {}", "After:".bold()));
+        for (i, id) in self.replay.iter().rev().take(PREVIEW_COMMITS).enumerate() {
+            let marker = if i == 0 { format!("  {} {}", decoration("←"), label).green().to_string() } else { String::new() };
+            lines.push(format!("    {} {} {}{}", decoration("●").green(), "new".dimmed(), summary(repo, *id), marker));
+        }
+        if self.replay.len() > PREVIEW_COMMITS {
+            lines.push(format!("    {} {}", decoration("┆").dimmed(), format!("{} more", self.replay.len() - PREVIEW_COMMITS).dimmed()));
+        }
+        lines.push(format!("    {} {} {}  {}",
+                           decoration("◆").cyan(),
+                           shorten_oid(&self.onto.id(), 8).yellow(),
+                           self.onto.summary().unwrap_or(""),
+                           format!("{} {}", decoration("←"), self.onto_label).cyan()));
+
+        lines.push(format!("  {}", "Before:".bold()));
+        for (i, id) in self.replay.iter().rev().take(PREVIEW_COMMITS).enumerate() {
+            let marker = if i == 0 { format!("  {} {}", decoration("←"), label).dimmed().to_string() } else { String::new() };
+            lines.push(format!("    {} {} {}{}", decoration("○").dimmed(), shorten_oid(id, 8).yellow(), summary(repo, *id), marker));
+        }
+        if let Some(base) = self.old_base {
+            lines.push(format!("    {} {} {}", decoration("◇").dimmed(), shorten_oid(&base, 8).yellow(), "old base".dimmed()));
+        }
+
+        if !self.dropped.is_empty() {
+            lines.push(format!("  {} {} commit{} between the old base and {} will no longer be on '{}':",
+                               decoration("✂️").yellow(),
+                               self.dropped.len(),
+                               plural(self.dropped.len()),
+                               self.onto_label,
+                               label));
+            for id in self.dropped.iter().take(PREVIEW_COMMITS) {
+                lines.push(format!("    {} {} {}", "-".red(), shorten_oid(id, 8).yellow(), summary(repo, *id)));
+            }
+        }
+        Ok(lines)
+    }
+}
+
+/// Commits reachable from `tip` but not from `hide`
+fn walk(repo: &Repository, tip: Oid, hide: &[Oid], oldest_first: bool) -> Result<Vec<Oid>> {
+    let mut revwalk = repo.revwalk()?;
+    let mut sorting = Sort::TOPOLOGICAL | Sort::TIME;
+    if oldest_first {
+        sorting |= Sort::REVERSE;
+    }
+    revwalk.set_sorting(sorting)?;
+    revwalk.push(tip)?;
+    for id in hide {
+        revwalk.hide(*id)?;
+    }
+    Ok(revwalk.collect::<std::result::Result<_, _>>()?)
+}
+
+fn summary(repo: &Repository, id: Oid) -> String {
+    repo.find_commit(id).ok()
+        .and_then(|c| c.summary().map(String::from))
+        .unwrap_or_default()
+}
+
+// =============================================================================
+// Replaying
+// =============================================================================
+
+fn open_rebase(repo: &Repository) -> Result<Rebase<'_>> {
+    repo.open_rebase(None).map_err(|_| RgitError::InvalidRepositoryState("no rebase in progress".to_string()).into())
+}
+
+/// Apply the remaining operations, stopping at the first conflict
+pub fn replay(repo: &Repository, rebase: &mut Rebase, signature: &Signature) -> Result<()> {
+    let total = rebase.len();
+    while let Some(operation) = rebase.next() {
+        let id = operation?.id();
+        let step = rebase.operation_current().map(|i| i + 1).unwrap_or(total);
+        ui_println!("  {} [{}/{}] {} {}", "🔁".blue(), step, total, shorten_oid(&id, 8).yellow(), summary(repo, id));
+
+        let conflicts = conflicted_paths(&repo.index()?)?;
+        if !conflicts.is_empty() {
+            show_conflict_help(id, &conflicts);
+            return Err(RgitError::RebaseConflict(conflicts.join(", ")).into());
+        }
+        commit_step(rebase, signature)?;
+    }
+
+    let old = rebase.orig_head_id();
+    let reference = rebase.orig_head_name().unwrap_or("HEAD").to_string();
+    rebase.finish(Some(signature))?;
+    let new = repo.head()?.peel_to_commit()?.id();
+
+    if let Some(old) = old {
+        repo.reference("ORIG_HEAD", old, true, "rgit: rebase")?;
+        Journal::for_repo(repo).record(&JournalEntry::new("rebase", &reference).with_old_oid(old).with_new_oid(new))?;
+    }
+
+    println!();
+    ui_println!("{} Rebased {} to {}",
+            "✅".green().bold(),
+            reference.trim_start_matches("refs/heads/").cyan(),
+            shorten_oid(&new, 8).yellow());
+    ui_println!("{} Changed your mind? {} puts it back", "💡".blue(), "rgit reset --hard ORIG_HEAD".cyan());
+    Ok(())
+}
+
+/// Commit the current operation, keeping its author and message. Changes
+/// already upstream leave nothing to commit and are dropped.
+fn commit_step(rebase: &mut Rebase, signature: &Signature) -> Result<()> {
+    match rebase.commit(None, signature, None) {
+        Ok(_) => Ok(()),
+        Err(e) if e.code() == ErrorCode::Applied => {
+            println!("     {}", "already applied upstream; dropped".dimmed());
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `--continue`: commit the step that stopped once its conflicts are resolved
+fn commit_resolved(repo: &Repository, rebase: &mut Rebase, signature: &Signature) -> Result<()> {
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let conflicts = conflicted_paths(&index)?;
+    if !conflicts.is_empty() {
+        ui_println!("{} Resolve these conflicts and stage the files first:", "⚠️".yellow());
+        for path in &conflicts {
+            ui_println!("  {} {}", "•".red(), path);
+        }
+        return Err(RgitError::RebaseConflict(conflicts.join(", ")).into());
+    }
+
+    if rebase.operation_current().is_some() {
+        commit_step(rebase, signature)?;
+    }
+    Ok(())
+}
+
+/// `--skip`: throw away the step that stopped and move on
+fn skip_current(repo: &Repository, rebase: &mut Rebase) -> Result<()> {
+    if let Some(current) = rebase.operation_current() {
+        if let Some(operation) = rebase.nth(current) {
+            let id = operation.id();
+            ui_println!("  {} Skipped {} {}", "⏭️".yellow(), shorten_oid(&id, 8).yellow(), summary(repo, id).dimmed());
+        }
+    }
+    let mut checkout = git2::build::CheckoutBuilder::new();
+    checkout.force();
+    repo.checkout_head(Some(&mut checkout))?;
+    let head = repo.head()?.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read_tree(&head)?;
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
+fn abort(repo: &Repository) -> Result<()> {
+    let mut rebase = open_rebase(repo)?;
+    rebase.abort()?;
+    let head = repo.head()?;
+    ui_println!("{} Rebase aborted; {} restored to {}",
+            "↩️".yellow(),
+            head.shorthand().unwrap_or("HEAD").cyan(),
+            shorten_oid(&head.peel_to_commit()?.id(), 8).yellow());
+    Ok(())
+}
+
+fn show_conflict_help(id: Oid, conflicts: &[String]) {
+    println!();
+    ui_println!("{} Conflicts while replaying {}:", "⚠️".yellow().bold(), shorten_oid(&id, 8).yellow());
+    for path in conflicts {
+        ui_println!("  {} {}", "•".red(), path);
+    }
+    println!();
+    ui_println!("{} Next steps:", "💡".blue());
+    ui_println!("  • {} - Resolve the conflicts, then {} the files", "rgit resolve".cyan(), "rgit add".cyan());
+    ui_println!("  • {} - Replay the remaining commits", "rgit rebase --continue".cyan());
+    ui_println!("  • {} - Drop this commit and go on", "rgit rebase --skip".cyan());
+    ui_println!("  • {} - Restore the branch to where it was", "rgit rebase --abort".cyan());
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    fn args(upstream: &str) -> RebaseArgs {
+        RebaseArgs {
+            upstream: Some(upstream.to_string()),
+            branch: None,
+            onto: None,
+            interactive: false,
+            continue_rebase: false,
+            abort: false,
+            skip: false,
+        }
+    }
+
+    /// Plan and run a rebase without prompts
+    fn rebase(repo: &TestRepo, args: &RebaseArgs) -> Result<()> {
+        let plan = RebasePlan::new(repo.repo(), args)?;
+        let branch = plan.branch_commit(repo.repo())?;
+        let upstream = repo.repo().find_annotated_commit(plan.upstream)?;
+        let onto = repo.repo().find_annotated_commit(plan.onto.id())?;
+        let mut rebase = repo.repo().rebase(Some(&branch), Some(&upstream), Some(&onto), None)?;
+        replay(repo.repo(), &mut rebase, &Signature::now("Test", "test@example.com")?)
+    }
+
+    fn summaries(repo: &TestRepo, count: usize) -> Vec<String> {
+        let mut commit = repo.repo().find_commit(repo.head()).unwrap();
+        let mut out = vec![commit.summary().unwrap().to_string()];
+        while out.len() < count {
+            commit = commit.parent(0).unwrap();
+            out.push(commit.summary().unwrap().to_string());
+        }
+        out
+    }
+
+    #[test]
+    fn test_rebase_onto_upstream() {
+        let repo = TestRepo::new();
+        repo.commit_file("base.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        let old_tip = repo.commit_file("feature.txt", "feature\n", "Feature");
+        repo.checkout(DEFAULT_BRANCH);
+        repo.commit_file("main.txt", "main\n", "Main");
+        repo.checkout("feature");
+
+        let plan = RebasePlan::new(repo.repo(), &args(DEFAULT_BRANCH)).unwrap();
+        assert_eq!(plan.replay, vec![old_tip]);
+        assert!(!plan.is_up_to_date());
+
+        rebase(&repo, &args(DEFAULT_BRANCH)).unwrap();
+        assert_eq!(summaries(&repo, 3), vec!["Feature", "Main", "Base"]);
+        assert_eq!(repo.repo().head().unwrap().shorthand(), Some("feature"));
+        assert_eq!(repo.repo().refname_to_id("ORIG_HEAD").unwrap(), old_tip);
+        let entry = Journal::for_repo(repo.repo()).entries().unwrap().pop().unwrap();
+        assert_eq!((entry.operation.as_str(), entry.reference.as_str()), ("rebase", "refs/heads/feature"));
+
+        assert!(RebasePlan::new(repo.repo(), &args(DEFAULT_BRANCH)).unwrap().is_up_to_date());
+    }
+
+    #[test]
+    fn test_onto_transplants_only_the_topic() {
+        // main - next - topic: move topic's own commits onto main
+        let repo = TestRepo::new();
+        repo.commit_file("base.txt", "base\n", "Base");
+        repo.branch("next").checkout("next");
+        let next_only = repo.commit_file("next.txt", "next\n", "Next work");
+        repo.branch("topic").checkout("topic");
+        let topic = repo.commit_file("topic.txt", "topic\n", "Topic work");
+
+        let mut onto = args("next");
+        onto.onto = Some(DEFAULT_BRANCH.to_string());
+        let plan = RebasePlan::new(repo.repo(), &onto).unwrap();
+        assert_eq!(plan.replay, vec![topic]);
+        assert_eq!(plan.dropped, vec![next_only]);
+        assert!(plan.preview_lines(repo.repo()).unwrap().iter().any(|l| l.contains("Next work")));
+
+        rebase(&repo, &onto).unwrap();
+        assert_eq!(summaries(&repo, 2), vec!["Topic work", "Base"]);
+        assert!(!repo.path().join("next.txt").exists());
+    }
+
+    #[test]
+    fn test_conflict_continue_and_abort() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        let old_tip = repo.commit_file("file.txt", "feature\n", "Feature");
+        repo.checkout(DEFAULT_BRANCH);
+        repo.commit_file("file.txt", "main\n", "Main");
+        repo.checkout("feature");
+
+        assert!(rebase(&repo, &args(DEFAULT_BRANCH)).is_err());
+        assert_eq!(repo.repo().state(), RepositoryState::RebaseMerge);
+        abort(repo.repo()).unwrap();
+        assert_eq!(repo.head(), old_tip);
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+
+        assert!(rebase(&repo, &args(DEFAULT_BRANCH)).is_err());
+        repo.write("file.txt", "both\n").stage("file.txt");
+        let signature = Signature::now("Test", "test@example.com").unwrap();
+        let mut open = open_rebase(repo.repo()).unwrap();
+        commit_resolved(repo.repo(), &mut open, &signature).unwrap();
+        replay(repo.repo(), &mut open, &signature).unwrap();
+        assert_eq!(summaries(&repo, 3), vec!["Feature", "Main", "Base"]);
+        assert_eq!(repo.repo().state(), RepositoryState::Clean);
+    }
+}
@@ -0,0 +1,388 @@
+use anyhow::Result;
+use colored::*;
+use git2::{BranchType, Commit, DiffStatsFormat, ObjectType, Oid, Repository, Revwalk, Time};
+use std::collections::{HashMap, HashSet};
+
+use crate::cli::RecoverArgs;
+use crate::commands::backup;
+use crate::commands::show::print_patch;
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::utils::{format_time_ago, shorten_oid};
+use crate::validation::validate_branch_name;
+
+/// Commits listed per lost line before the rest are summarised
+const PREVIEW_COMMITS: usize = 5;
+
+/// Execute the recover command
+pub async fn execute(args: &RecoverArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let lines = find_lost(repo, !args.no_dangling)?;
+
+    if let Some(spec) = &args.commit {
+        let commit = resolve_commit(repo, spec)?;
+        let hint = lines.iter().find(|line| line.commits.contains(&commit.id())).and_then(|line| line.branch.clone());
+        let name = match &args.name {
+            Some(name) => name.clone(),
+            None => default_name(repo, hint.as_deref(), commit.id()),
+        };
+        recreate_branch(repo, &name, &commit)?;
+        ui_println!("{} Created branch {} at {} {}",
+                "✅".green().bold(), name.cyan(), shorten_oid(&commit.id(), 8).yellow(), commit.summary().unwrap_or(""));
+        return Ok(());
+    }
+
+    if lines.is_empty() {
+        ui_println!("{} No lost commits found", "✨".green());
+        return Ok(());
+    }
+
+    ui_println!("{} Found {} lost line{} of history\n", "🔍".blue(), lines.len(), plural(lines.len()));
+    for (n, line) in lines.iter().enumerate() {
+        show_line(repo, n + 1, line, args.patch, config)?;
+    }
+
+    if args.list || !config.is_interactive() {
+        ui_println!("{} Recreate one with {}", "💡".blue(), "rgit recover <commit> --name <branch>".cyan());
+        return Ok(());
+    }
+
+    let items: Vec<String> = lines.iter().enumerate()
+        .map(|(n, line)| format!("{}. {} {}", n + 1, line.label(), line.tip_summary(repo)))
+        .collect();
+    let chosen = InteractivePrompt::new()
+        .with_message("Select the history to bring back")
+        .with_options(&items)
+        .multiselect_prompt()?;
+    if chosen.is_empty() {
+        ui_println!("{} Nothing selected; nothing recovered", "💡".blue());
+        return Ok(());
+    }
+
+    for n in chosen {
+        let line = &lines[n];
+        let name: String = InteractivePrompt::new()
+            .with_message(format!("Branch name for {}", shorten_oid(&line.tip, 8)))
+            .with_initial_text(default_name(repo, line.branch.as_deref(), line.tip))
+            .input()?;
+        let commit = repo.find_commit(line.tip)?;
+        recreate_branch(repo, name.trim(), &commit)?;
+        ui_println!("{} Created branch {} with {} commit{}",
+                "✅".green().bold(), name.trim().cyan(), line.commits.len(), plural(line.commits.len()));
+    }
+    Ok(())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn show_line(repo: &Repository, number: usize, line: &LostLine, patch: bool, config: &Config) -> Result<()> {
+    println!("{} {} {} {}",
+            format!("{}.", number).bold(),
+            line.label().cyan().bold(),
+            format!("({}, {})", line.source.describe(), format_time_ago(line.time)).dimmed(),
+            format!("{} commit{}", line.commits.len(), plural(line.commits.len())).dimmed());
+
+    for id in line.commits.iter().take(PREVIEW_COMMITS) {
+        let commit = repo.find_commit(*id)?;
+        println!("   {} {}", shorten_oid(id, 8).yellow(), commit.summary().unwrap_or(""));
+    }
+    if line.commits.len() > PREVIEW_COMMITS {
+        ui_println!("   {} {}", "…".dimmed(), format!("and {} more", line.commits.len() - PREVIEW_COMMITS).dimmed());
+    }
+
+    let tip = repo.find_commit(line.tip)?.tree()?;
+    let base = match line.base {
+        Some(base) => Some(repo.find_commit(base)?.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(base.as_ref(), Some(&tip), None)?;
+    if patch {
+        print_patch(repo, &diff, config, false)?;
+    } else {
+        let stats = diff.stats()?.to_buf(DiffStatsFormat::SHORT, config.terminal_width())?;
+        print!("   {}", stats.as_str().unwrap_or("").trim_start());
+    }
+    println!();
+    Ok(())
+}
+
+// =============================================================================
+// Finding lost commits
+// =============================================================================
+
+/// Where a lost commit was found, most telling first
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LostSource {
+    /// rgit's journal recorded the ref moving away from it
+    Journal,
+    /// A branch's own reflog
+    BranchReflog,
+    /// HEAD's reflog
+    HeadReflog,
+    /// An unreferenced commit in the object database
+    Dangling,
+}
+
+impl LostSource {
+    fn describe(&self) -> &'static str {
+        match self {
+            LostSource::Journal => "rgit journal",
+            LostSource::BranchReflog => "branch reflog",
+            LostSource::HeadReflog => "HEAD reflog",
+            LostSource::Dangling => "dangling",
+        }
+    }
+}
+
+/// A run of commits no longer reachable from any branch, tag or stash
+#[derive(Debug, Clone)]
+pub struct LostLine {
+    /// Newest lost commit
+    pub tip: Oid,
+    /// Lost commits from the tip back to the reachable history, newest first
+    pub commits: Vec<Oid>,
+    /// First reachable ancestor, where the line forked off
+    pub base: Option<Oid>,
+    /// Branch the commits most likely belonged to
+    pub branch: Option<String>,
+    pub source: LostSource,
+    pub time: Time,
+}
+
+impl LostLine {
+    fn label(&self) -> String {
+        self.branch.clone().unwrap_or_else(|| "(no branch)".to_string())
+    }
+
+    fn tip_summary(&self, repo: &Repository) -> String {
+        let summary = repo.find_commit(self.tip).ok()
+            .and_then(|c| c.summary().map(String::from))
+            .unwrap_or_default();
+        format!("{} {}", shorten_oid(&self.tip, 8), summary)
+    }
+}
+
+/// Lost lines of history, newest first. Candidates come from the journal,
+/// every reflog and, with `dangling`, a scan of the object database.
+pub fn find_lost(repo: &Repository, dangling: bool) -> Result<Vec<LostLine>> {
+    let tips = live_tips(repo)?;
+    let mut walk = repo.revwalk()?;
+    for tip in &tips {
+        walk.push(*tip)?;
+    }
+    let mut reachable: HashSet<Oid> = walk.filter_map(|id| id.ok()).collect();
+    // Working trees captured by snapshots are rgit's own bookkeeping
+    for snapshot in backup::list_snapshots(repo)? {
+        for oid in [&snapshot.index, &snapshot.worktree].into_iter().flatten() {
+            reachable.extend(Oid::from_str(oid).ok());
+        }
+    }
+
+    let mut candidates: HashMap<Oid, (LostSource, Option<String>)> = HashMap::new();
+    let mut offer = |id: Oid, source: LostSource, branch: Option<String>| {
+        if id.is_zero() || reachable.contains(&id) {
+            return;
+        }
+        let entry = candidates.entry(id).or_insert((source.clone(), branch.clone()));
+        if source < entry.0 {
+            *entry = (source, branch.or(entry.1.take()));
+        } else if entry.1.is_none() {
+            entry.1 = branch;
+        }
+    };
+
+    for entry in Journal::for_repo(repo).entries()? {
+        let branch = entry.reference.strip_prefix("refs/heads/").map(String::from);
+        for oid in [&entry.old_oid, &entry.new_oid].into_iter().flatten() {
+            if let Ok(id) = Oid::from_str(oid) {
+                offer(id, LostSource::Journal, branch.clone());
+            }
+        }
+    }
+    for name in repo.references()?.filter_map(|r| r.ok()).filter_map(|r| r.name().map(String::from)) {
+        let Some(branch) = name.strip_prefix("refs/heads/") else { continue };
+        if let Ok(reflog) = repo.reflog(&name) {
+            for entry in reflog.iter() {
+                offer(entry.id_old(), LostSource::BranchReflog, Some(branch.to_string()));
+                offer(entry.id_new(), LostSource::BranchReflog, Some(branch.to_string()));
+            }
+        }
+    }
+    for (id, branch) in head_reflog_commits(repo)? {
+        offer(id, LostSource::HeadReflog, branch);
+    }
+    if dangling {
+        for id in unreachable_commits(repo, &reachable)? {
+            offer(id, LostSource::Dangling, None);
+        }
+    }
+
+    // Only commits still in the object database can be recovered
+    let mut candidates: Vec<(Oid, LostSource, Option<String>)> = candidates.into_iter()
+        .filter(|(id, _)| repo.find_commit(*id).is_ok())
+        .map(|(id, (source, branch))| (id, source, branch))
+        .collect();
+
+    let mut lines = Vec::new();
+    for (tip, source, branch) in candidates.drain(..) {
+        let commits = lost_commits(repo.revwalk()?, tip, &tips)?;
+        let oldest = repo.find_commit(*commits.last().unwrap_or(&tip))?;
+        let base = oldest.parent_ids().next();
+        let time = repo.find_commit(tip)?.time();
+        lines.push(LostLine { tip, commits, base, branch, source, time });
+    }
+
+    // A tip inside another line's history is part of that line
+    lines.sort_by(|a, b| b.commits.len().cmp(&a.commits.len()).then(a.source.cmp(&b.source)));
+    let mut kept: Vec<LostLine> = Vec::new();
+    for line in lines {
+        match kept.iter_mut().find(|k| k.commits.contains(&line.tip)) {
+            Some(owner) => {
+                if owner.branch.is_none() {
+                    owner.branch = line.branch;
+                }
+            }
+            None => kept.push(line),
+        }
+    }
+    kept.sort_by(|a, b| b.time.seconds().cmp(&a.time.seconds()).then(a.tip.cmp(&b.tip)));
+    Ok(kept)
+}
+
+/// Commits kept alive by refs. rgit's own snapshot refs don't count, so
+/// history only they remember still shows up as lost.
+fn live_tips(repo: &Repository) -> Result<Vec<Oid>> {
+    let mut tips = Vec::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if reference.name().is_some_and(|name| name.starts_with("refs/rgit/")) {
+            continue;
+        }
+        if let Ok(commit) = reference.peel_to_commit() {
+            tips.push(commit.id());
+        }
+    }
+    if let Ok(head) = repo.head().and_then(|h| h.peel_to_commit()) {
+        tips.push(head.id());
+    }
+    // Older stash entries only live in the stash reflog
+    if let Ok(stashes) = repo.reflog("refs/stash") {
+        tips.extend(stashes.iter().map(|entry| entry.id_new()));
+    }
+    Ok(tips)
+}
+
+/// Commits HEAD pointed to, with the branch checked out at the time
+fn head_reflog_commits(repo: &Repository) -> Result<Vec<(Oid, Option<String>)>> {
+    let Ok(reflog) = repo.reflog("HEAD") else { return Ok(Vec::new()) };
+    let mut branch: Option<String> = None;
+    let mut commits = Vec::new();
+
+    // Oldest first, so checkouts tell which branch later commits were on
+    for entry in reflog.iter().collect::<Vec<_>>().into_iter().rev() {
+        let message = entry.message().unwrap_or("");
+        if let Some(moved) = message.strip_prefix("checkout: moving from ") {
+            commits.push((entry.id_old(), branch.clone()));
+            branch = moved.rsplit(" to ").next()
+                .filter(|to| Oid::from_str(to).is_err())
+                .map(String::from);
+        } else {
+            commits.push((entry.id_old(), branch.clone()));
+        }
+        commits.push((entry.id_new(), branch.clone()));
+    }
+    Ok(commits)
+}
+
+/// Commits in the object database not reachable from any live ref
+fn unreachable_commits(repo: &Repository, reachable: &HashSet<Oid>) -> Result<Vec<Oid>> {
+    let odb = repo.odb()?;
+    let mut commits = Vec::new();
+    odb.foreach(|id| {
+        if !reachable.contains(id) && odb.read_header(*id).map(|(_, kind)| kind == ObjectType::Commit).unwrap_or(false) {
+            commits.push(*id);
+        }
+        true
+    })?;
+    Ok(commits)
+}
+
+/// Commits reachable from `tip` but from none of `live`, newest first
+fn lost_commits(mut walk: Revwalk, tip: Oid, live: &[Oid]) -> Result<Vec<Oid>> {
+    walk.push(tip)?;
+    for id in live {
+        walk.hide(*id)?;
+    }
+    Ok(walk.filter_map(|id| id.ok()).collect())
+}
+
+// =============================================================================
+// Recreating branches
+// =============================================================================
+
+/// The likely branch name, or a fresh `recovered-<id>` name if it's taken
+fn default_name(repo: &Repository, branch: Option<&str>, tip: Oid) -> String {
+    let taken = |name: &str| repo.find_branch(name, BranchType::Local).is_ok();
+    match branch {
+        Some(name) if !taken(name) => name.to_string(),
+        Some(name) if !taken(&format!("{}-recovered", name)) => format!("{}-recovered", name),
+        _ => format!("recovered-{}", shorten_oid(&tip, 8)),
+    }
+}
+
+fn recreate_branch(repo: &Repository, name: &str, commit: &Commit) -> Result<()> {
+    validate_branch_name(name)?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(RgitError::BranchAlreadyExists(name.to_string()).into());
+    }
+    repo.branch(name, commit, false)?;
+    Journal::for_repo(repo).record(&JournalEntry::new("recover", &format!("refs/heads/{}", name))
+        .with_new_oid(commit.id()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    #[test]
+    fn test_finds_deleted_branch_from_head_reflog() {
+        let repo = TestRepo::new();
+        let base = repo.commit_file("a.txt", "one\n", "Base");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("b.txt", "b\n", "Feature one");
+        let tip = repo.commit_file("b.txt", "bb\n", "Feature two");
+        repo.checkout(DEFAULT_BRANCH);
+        repo.repo().find_branch("feature", BranchType::Local).unwrap().delete().unwrap();
+
+        let lines = find_lost(repo.repo(), true).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].tip, tip);
+        assert_eq!(lines[0].commits.len(), 2);
+        assert_eq!(lines[0].base, Some(base));
+        assert_eq!(lines[0].branch.as_deref(), Some("feature"));
+
+        recreate_branch(repo.repo(), "feature", &repo.repo().find_commit(tip).unwrap()).unwrap();
+        assert!(find_lost(repo.repo(), true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_finds_commits_dropped_by_reset() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        let second = repo.commit_file("a.txt", "two\n", "Second");
+        let commit = repo.repo().find_commit(first).unwrap();
+        repo.repo().reset(commit.as_object(), git2::ResetType::Hard, None).unwrap();
+
+        let lines = find_lost(repo.repo(), false).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].tip, second);
+        assert_eq!(default_name(repo.repo(), lines[0].branch.as_deref(), second), "main-recovered");
+    }
+}
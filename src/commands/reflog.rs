@@ -0,0 +1,302 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Oid, Repository, Time};
+
+use crate::cli::{ReflogArgs, ResetArgs};
+use crate::commands::reset;
+use crate::commands::show::print_patch;
+use crate::commands::switch::{self, DirtyTreeAction, SwitchOptions, SwitchTarget};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::output::decoration;
+use crate::utils::{format_time_ago, shorten_oid};
+
+/// Execute the reflog command
+pub async fn execute(args: &ReflogArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    if args.interactive {
+        return browse(args, rgit, config).await;
+    }
+
+    let names = if args.all {
+        logged_refs(repo)?
+    } else {
+        vec![full_ref_name(repo, args.reference.as_deref())?]
+    };
+    for (n, name) in names.iter().enumerate() {
+        if n > 0 {
+            println!();
+        }
+        let entries = read_reflog(repo, name, args.max_count)?;
+        print_timeline(name, &entries);
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Reading the reflog
+// =============================================================================
+
+/// One move of a reference, newest first like `HEAD@{0}`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReflogEntry {
+    /// `<ref>@{n}` selector for the entry
+    pub selector: String,
+    pub old: Oid,
+    pub new: Oid,
+    pub message: String,
+    pub time: Time,
+}
+
+impl ReflogEntry {
+    /// What moved the reference, e.g. "commit", "rebase" or "checkout"
+    pub fn operation(&self) -> String {
+        operation(&self.message)
+    }
+}
+
+/// The operation a reflog message records. Multi-step operations like
+/// "rebase (pick)" and "rebase -i (finish)" all count as "rebase", and rgit's
+/// own "rgit: <operation>" entries as that operation.
+pub fn operation(message: &str) -> String {
+    let message = message.strip_prefix("rgit: ").unwrap_or(message);
+    let prefix = message.split_once(':').map_or(message, |(prefix, _)| prefix);
+    let word = prefix.split([' ', '(']).next().unwrap_or_default();
+    match word {
+        "" => "other".to_string(),
+        "switch" => "checkout".to_string(),
+        word => word.to_lowercase(),
+    }
+}
+
+fn icon(operation: &str) -> &'static str {
+    match operation {
+        "commit" => "📝",
+        "rebase" => "🔀",
+        "checkout" => "🔄",
+        "reset" => "⏪",
+        "merge" | "pull" => "🔗",
+        "cherry-pick" => "🍒",
+        "revert" => "↩️",
+        "branch" | "clone" => "🌱",
+        _ => "•",
+    }
+}
+
+/// Full name of `reference` (default HEAD), so `main` reads refs/heads/main's reflog
+fn full_ref_name(repo: &Repository, reference: Option<&str>) -> Result<String> {
+    let reference = reference.unwrap_or("HEAD");
+    // Look up the exact name first, so HEAD stays HEAD instead of the branch it points at
+    let resolved = repo.find_reference(reference)
+        .or_else(|_| repo.resolve_reference_from_short_name(reference))
+        .map_err(|_| RgitError::InvalidReference(reference.to_string()))?;
+    Ok(resolved.name().unwrap_or(reference).to_string())
+}
+
+/// HEAD and every reference with a reflog
+fn logged_refs(repo: &Repository) -> Result<Vec<String>> {
+    let mut names = vec!["HEAD".to_string()];
+    for reference in repo.references()? {
+        if let Some(name) = reference?.name() {
+            if repo.reflog(name).map(|log| !log.is_empty()).unwrap_or(false) {
+                names.push(name.to_string());
+            }
+        }
+    }
+    Ok(names)
+}
+
+/// The reflog of `name`, newest first, at most `limit` entries
+pub fn read_reflog(repo: &Repository, name: &str, limit: Option<usize>) -> Result<Vec<ReflogEntry>> {
+    let short = name.strip_prefix("refs/heads/").unwrap_or(name);
+    let reflog = repo.reflog(name)?;
+    Ok(reflog.iter()
+        .take(limit.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(n, entry)| ReflogEntry {
+            selector: format!("{}@{{{}}}", short, n),
+            old: entry.id_old(),
+            new: entry.id_new(),
+            message: entry.message().unwrap_or_default().to_string(),
+            time: entry.committer().when(),
+        })
+        .collect())
+}
+
+/// Runs of consecutive entries made by the same operation, newest first
+pub fn group_by_operation(entries: &[ReflogEntry]) -> Vec<(String, &[ReflogEntry])> {
+    let mut groups: Vec<(String, &[ReflogEntry])> = Vec::new();
+    let mut start = 0;
+    for n in 1..=entries.len() {
+        let operation = entries[start].operation();
+        if n == entries.len() || entries[n].operation() != operation {
+            groups.push((operation, &entries[start..n]));
+            start = n;
+        }
+    }
+    groups
+}
+
+// =============================================================================
+// Timeline
+// =============================================================================
+
+fn print_timeline(name: &str, entries: &[ReflogEntry]) {
+    if entries.is_empty() {
+        ui_println!("{} No reflog entries for {}", "ℹ️".blue(), name.cyan());
+        return;
+    }
+    ui_println!("{} {} {}", "📜".blue(), name.cyan().bold(),
+            format!("({} entr{})", entries.len(), if entries.len() == 1 { "y" } else { "ies" }).dimmed());
+
+    for (operation, group) in group_by_operation(entries) {
+        let count = if group.len() > 1 { format!(" {}{}", decoration("×"), group.len()) } else { String::new() };
+        ui_println!("\n{} {}{} {}", icon(&operation), operation.bold(), count, format_time_ago(group[0].time).dimmed());
+        for entry in group {
+            println!("   {} {} {}", entry.selector.cyan(), shorten_oid(&entry.new, 8).yellow(), entry.message);
+        }
+    }
+}
+
+fn entry_label(entry: &ReflogEntry) -> String {
+    format!("{} {:<12} {} {} ({})",
+            icon(&entry.operation()),
+            entry.selector,
+            shorten_oid(&entry.new, 8),
+            entry.message,
+            format_time_ago(entry.time))
+}
+
+// =============================================================================
+// Browser
+// =============================================================================
+
+/// Pick an entry from the timeline, then act on it with one key
+async fn browse(args: &ReflogArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+
+    let repo = &rgit.repo;
+    let name = full_ref_name(repo, args.reference.as_deref())?;
+    let entries = read_reflog(repo, &name, args.max_count)?;
+    if entries.is_empty() {
+        ui_println!("{} No reflog entries for {}", "ℹ️".blue(), name.cyan());
+        return Ok(());
+    }
+
+    let mut options: Vec<String> = entries.iter().map(entry_label).collect();
+    options.push("Done".to_string());
+    let mut selected = 0;
+    loop {
+        selected = InteractivePrompt::new()
+            .with_message(format!("History of {}", name))
+            .with_options(&options)
+            .with_default(selected)
+            .fuzzy_search()
+            .select()?;
+        let Some(entry) = entries.get(selected) else { return Ok(()) };
+        let Ok(commit) = repo.find_commit(entry.new) else {
+            ui_println!("{} {} doesn't point at a commit that still exists", "⚠️".yellow(), entry.selector);
+            continue;
+        };
+
+        ui_println!("\n{} {} {} {}", icon(&entry.operation()), entry.selector.cyan().bold(),
+                shorten_oid(&commit.id(), 8).yellow(), commit.summary().unwrap_or(""));
+        let key = InteractivePrompt::new()
+            .with_message("What now?")
+            .keypress(&[
+                ('d', "diff against HEAD"),
+                ('c', "check out"),
+                ('b', "branch from here"),
+                ('r', "restore branch to here"),
+                ('q', "back"),
+            ])?;
+        let spec = commit.id().to_string();
+        match key {
+            'd' => diff_against_head(repo, entry, config)?,
+            'c' => {
+                let outcome = switch::switch(repo, &SwitchTarget::Detach(spec), &switch_options(config))?;
+                switch::show_switched(repo, &outcome);
+                return Ok(());
+            }
+            'b' => {
+                let branch: String = InteractivePrompt::new()
+                    .with_message(format!("Branch name for {}", entry.selector))
+                    .with_initial_text(format!("restore-{}", shorten_oid(&commit.id(), 7)))
+                    .input()?;
+                let target = SwitchTarget::Create { name: branch.trim().to_string(), start: Some(spec), force: false };
+                let outcome = switch::switch(repo, &target, &switch_options(config))?;
+                switch::show_switched(repo, &outcome);
+                return Ok(());
+            }
+            'r' => {
+                // The guided reset picks the mode and snapshots the current state first
+                let reset_args = ResetArgs { commit: Some(spec), soft: false, mixed: false, hard: false, force: false };
+                return reset::execute(&reset_args, rgit, config).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn switch_options(config: &Config) -> SwitchOptions {
+    SwitchOptions { dirty: DirtyTreeAction::from_config(false, config), ..Default::default() }
+}
+
+/// What changed between an entry and HEAD
+fn diff_against_head(repo: &Repository, entry: &ReflogEntry, config: &Config) -> Result<()> {
+    let then = repo.find_commit(entry.new)?.tree()?;
+    let now = repo.head()?.peel_to_tree()?;
+    let diff = repo.diff_tree_to_tree(Some(&then), Some(&now), None)?;
+    if diff.deltas().len() == 0 {
+        ui_println!("{} {} has the same files as HEAD\n", "✨".green(), entry.selector);
+        return Ok(());
+    }
+    ui_println!("{} Changes from {} to HEAD\n", "📊".blue(), entry.selector.cyan());
+    print_patch(repo, &diff, config, false)?;
+    println!();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_operation() {
+        assert_eq!(operation("commit (initial): Initial commit"), "commit");
+        assert_eq!(operation("rebase -i (pick): Fix typo"), "rebase");
+        assert_eq!(operation("checkout: moving from main to feature"), "checkout");
+        assert_eq!(operation("merge feature: Fast-forward"), "merge");
+        assert_eq!(operation("rgit: reset"), "reset");
+        assert_eq!(operation(""), "other");
+    }
+
+    #[test]
+    fn test_timeline_groups_consecutive_operations() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Initial commit");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("c.txt", "c\n", "Add c");
+
+        let entries = read_reflog(repo.repo(), "HEAD", None).unwrap();
+        assert_eq!(entries[0].selector, "HEAD@{0}");
+        assert_eq!(entries[0].new, repo.head());
+        let groups: Vec<(String, usize)> = group_by_operation(&entries).into_iter()
+            .map(|(operation, group)| (operation, group.len()))
+            .collect();
+        assert_eq!(groups, [("commit".to_string(), 1), ("checkout".to_string(), 1), ("commit".to_string(), 2)]);
+
+        assert_eq!(read_reflog(repo.repo(), "HEAD", Some(2)).unwrap().len(), 2);
+        let name = full_ref_name(repo.repo(), Some("feature")).unwrap();
+        assert_eq!(name, "refs/heads/feature");
+        assert_eq!(read_reflog(repo.repo(), &name, None).unwrap()[0].selector, "feature@{0}");
+        assert_eq!(full_ref_name(repo.repo(), None).unwrap(), "HEAD");
+        assert!(full_ref_name(repo.repo(), Some("missing")).is_err());
+    }
+}
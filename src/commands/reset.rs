@@ -0,0 +1,313 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Oid, Repository, ResetType, Status, StatusOptions};
+
+use crate::cli::ResetArgs;
+use crate::commands::backup;
+use crate::commands::utils::confirm_destructive_operation;
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+
+/// Execute the reset command
+pub async fn execute(args: &ResetArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let head = repo.head()
+        .and_then(|h| h.peel_to_commit())
+        .map_err(|_| RgitError::InvalidCommit("HEAD (no commits yet)".to_string()))?;
+    let target = resolve_commit(repo, args.commit.as_deref().unwrap_or("HEAD"))?;
+    let preview = ResetPreview::new(repo, &head, &target)?;
+
+    let mode = match ResetMode::from_args(args) {
+        Some(mode) => mode,
+        None if config.is_interactive() => choose_mode(&preview)?,
+        None => ResetMode::Mixed,
+    };
+
+    if mode == ResetMode::Hard && !args.force {
+        if config.is_interactive() {
+            show_effects(mode, &preview);
+        }
+        let operation = format!("hard reset to {}", shorten_oid(&target.id(), 8));
+        if !confirm_destructive_operation(&operation, None, config)? {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    backup::snapshot_before(rgit, config, "reset")?;
+    let old_head = reset(repo, &target, mode)?;
+    show_result(&target, old_head, mode, &preview);
+    Ok(())
+}
+
+// =============================================================================
+// Modes
+// =============================================================================
+
+/// How much of the repository a reset rewinds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetMode {
+    /// Move HEAD only
+    Soft,
+    /// Move HEAD and reset the index
+    Mixed,
+    /// Move HEAD and reset the index and working tree
+    Hard,
+}
+
+impl ResetMode {
+    const ALL: [ResetMode; 3] = [ResetMode::Soft, ResetMode::Mixed, ResetMode::Hard];
+
+    fn from_args(args: &ResetArgs) -> Option<Self> {
+        if args.soft {
+            Some(ResetMode::Soft)
+        } else if args.mixed {
+            Some(ResetMode::Mixed)
+        } else if args.hard {
+            Some(ResetMode::Hard)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResetMode::Soft => "soft",
+            ResetMode::Mixed => "mixed",
+            ResetMode::Hard => "hard",
+        }
+    }
+
+    fn reset_type(&self) -> ResetType {
+        match self {
+            ResetMode::Soft => ResetType::Soft,
+            ResetMode::Mixed => ResetType::Mixed,
+            ResetMode::Hard => ResetType::Hard,
+        }
+    }
+
+    /// What happens to the index, given the current state
+    fn index_effect(&self, preview: &ResetPreview) -> String {
+        match self {
+            ResetMode::Soft if preview.commits_undone > 0 => format!(
+                "kept; changes from {} undone commit{} become staged",
+                preview.commits_undone, plural(preview.commits_undone)),
+            ResetMode::Soft => "kept".to_string(),
+            _ if preview.staged == 0 => "reset to the target commit".to_string(),
+            ResetMode::Mixed => format!(
+                "reset; {} staged file{} will be unstaged",
+                preview.staged, plural(preview.staged)),
+            ResetMode::Hard => format!(
+                "reset; {} staged file{} will be discarded",
+                preview.staged, plural(preview.staged)),
+        }
+    }
+
+    /// What happens to the working tree, given the current state
+    fn worktree_effect(&self, preview: &ResetPreview) -> String {
+        match self {
+            ResetMode::Soft | ResetMode::Mixed => "kept".to_string(),
+            ResetMode::Hard if preview.changed_files == 0 => "reset to the target commit".to_string(),
+            ResetMode::Hard => format!(
+                "reset; uncommitted changes to {} file{} are LOST",
+                preview.changed_files, plural(preview.changed_files)),
+        }
+    }
+}
+
+// =============================================================================
+// Preview
+// =============================================================================
+
+/// What a reset would affect
+#[derive(Debug, Clone, Default)]
+struct ResetPreview {
+    /// Branch being moved, or None when HEAD is detached
+    branch: Option<String>,
+    /// Commits on HEAD that the target does not contain
+    commits_undone: usize,
+    /// Commits on the target that HEAD does not contain
+    commits_gained: usize,
+    /// Files with staged changes
+    staged: usize,
+    /// Tracked files with staged or unstaged changes
+    changed_files: usize,
+}
+
+impl ResetPreview {
+    fn new(repo: &Repository, head: &Commit, target: &Commit) -> Result<Self> {
+        let branch = repo.head().ok()
+            .filter(|h| h.is_branch())
+            .and_then(|h| h.shorthand().map(String::from));
+        let (commits_undone, commits_gained) = repo.graph_ahead_behind(head.id(), target.id())?;
+
+        let mut options = StatusOptions::new();
+        options.include_untracked(false).include_ignored(false);
+        let statuses = timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?;
+        let staged_flags = Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED
+            | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE;
+
+        let staged = statuses.iter().filter(|s| s.status().intersects(staged_flags)).count();
+        let changed_files = statuses.iter().filter(|s| !s.status().is_ignored()).count();
+
+        Ok(Self { branch, commits_undone, commits_gained, staged, changed_files })
+    }
+}
+
+/// Explain each mode and let the user pick one
+fn choose_mode(preview: &ResetPreview) -> Result<ResetMode> {
+    ui_println!("{} Reset {}", "🔄".blue().bold(), describe_move(preview));
+    for mode in ResetMode::ALL {
+        show_effects(mode, preview);
+    }
+    println!();
+
+    let options: Vec<String> = ResetMode::ALL.iter()
+        .map(|mode| match mode {
+            ResetMode::Mixed => "mixed (default)".to_string(),
+            mode => mode.name().to_string(),
+        })
+        .collect();
+    let selection = InteractivePrompt::new()
+        .with_message("Reset mode")
+        .with_options(&options)
+        .with_default(1)
+        .select()?;
+
+    Ok(ResetMode::ALL[selection])
+}
+
+fn show_effects(mode: ResetMode, preview: &ResetPreview) {
+    let name = format!("--{}", mode.name());
+    let name = if mode == ResetMode::Hard { name.red().bold() } else { name.cyan().bold() };
+    println!("\n  {}", name);
+    println!("    {} {}", "index:".dimmed(), mode.index_effect(preview));
+    println!("    {} {}", "working tree:".dimmed(), mode.worktree_effect(preview));
+}
+
+fn describe_move(preview: &ResetPreview) -> String {
+    let subject = preview.branch.as_deref()
+        .map(|b| b.cyan().to_string())
+        .unwrap_or_else(|| "detached HEAD".yellow().to_string());
+    match (preview.commits_undone, preview.commits_gained) {
+        (0, 0) => format!("{} (HEAD stays on the same commit)", subject),
+        (undone, 0) => format!("{} back {} commit{}", subject, undone, plural(undone)),
+        (0, gained) => format!("{} forward {} commit{}", subject, gained, plural(gained)),
+        (undone, gained) => format!("{} to a diverged commit ({} undone, {} gained)", subject, undone, gained),
+    }
+}
+
+// =============================================================================
+// Reset
+// =============================================================================
+
+/// Reset HEAD to `target`, returning the previous HEAD.
+///
+/// The previous HEAD is recorded in the undo journal and in `ORIG_HEAD`
+/// before anything changes.
+fn reset(repo: &Repository, target: &Commit, mode: ResetMode) -> Result<Oid> {
+    let head = repo.head()?;
+    let old_head = head.peel_to_commit()?.id();
+    let reference = if head.is_branch() { head.name().unwrap_or("HEAD") } else { "HEAD" };
+
+    Journal::for_repo(repo).record(&JournalEntry::new(&format!("reset --{}", mode.name()), reference)
+        .with_old_oid(old_head)
+        .with_new_oid(target.id()))?;
+    repo.reference("ORIG_HEAD", old_head, true, "rgit: reset")?;
+
+    repo.reset(target.as_object(), mode.reset_type(), None)?;
+    Ok(old_head)
+}
+
+fn show_result(target: &Commit, old_head: Oid, mode: ResetMode, preview: &ResetPreview) {
+    ui_println!("{} HEAD is now at {} {}",
+            "✅".green().bold(),
+            shorten_oid(&target.id(), 8).yellow(),
+            target.summary().unwrap_or(""));
+
+    if old_head == target.id() {
+        return;
+    }
+    ui_println!("\n{} To undo: {}",
+            "💡".blue(),
+            format!("rgit reset --{} {}", mode.name(), shorten_oid(&old_head, 8)).cyan());
+    if mode == ResetMode::Hard && preview.changed_files > 0 {
+        println!("   {}", "Discarded uncommitted changes cannot be restored".yellow());
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_reset_modes() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        let target = repo.repo().find_commit(first).unwrap();
+
+        reset(repo.repo(), &target, ResetMode::Soft).unwrap();
+        assert_eq!(repo.head(), first);
+        assert!(repo.repo().statuses(None).unwrap().iter().any(|s| s.status().is_index_modified()));
+
+        let original = repo.repo().find_commit(second).unwrap();
+        reset(repo.repo(), &original, ResetMode::Mixed).unwrap();
+        repo.write("file.txt", "three\n").stage("file.txt");
+        reset(repo.repo(), &target, ResetMode::Mixed).unwrap();
+        let status = repo.repo().status_file(std::path::Path::new("file.txt")).unwrap();
+        assert_eq!(status, Status::WT_MODIFIED);
+
+        reset(repo.repo(), &target, ResetMode::Hard).unwrap();
+        assert!(repo.repo().statuses(None).unwrap().is_empty());
+        assert_eq!(std::fs::read_to_string(repo.path().join("file.txt")).unwrap(), "one\n");
+    }
+
+    #[test]
+    fn test_reset_records_previous_head() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        let target = repo.repo().find_commit(first).unwrap();
+
+        assert_eq!(reset(repo.repo(), &target, ResetMode::Hard).unwrap(), second);
+
+        let entries = Journal::for_repo(repo.repo()).entries().unwrap();
+        let entry = entries.last().unwrap();
+        assert_eq!(entry.operation, "reset --hard");
+        assert_eq!(entry.reference, "refs/heads/main");
+        assert_eq!(entry.old_oid, Some(second.to_string()));
+        assert_eq!(entry.new_oid, Some(first.to_string()));
+        assert_eq!(repo.repo().refname_to_id("ORIG_HEAD").unwrap(), second);
+    }
+
+    #[test]
+    fn test_preview_effects() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "a\n", "First");
+        repo.commit_file("b.txt", "b\n", "Second");
+        repo.write("a.txt", "changed\n").stage("a.txt");
+        repo.write("b.txt", "changed\n");
+
+        let head = repo.repo().find_commit(repo.head()).unwrap();
+        let target = repo.repo().find_commit(first).unwrap();
+        let preview = ResetPreview::new(repo.repo(), &head, &target).unwrap();
+
+        assert_eq!(preview.branch.as_deref(), Some("main"));
+        assert_eq!((preview.commits_undone, preview.commits_gained), (1, 0));
+        assert_eq!((preview.staged, preview.changed_files), (1, 2));
+        assert!(ResetMode::Soft.index_effect(&preview).contains("1 undone commit "));
+        assert!(ResetMode::Mixed.index_effect(&preview).contains("1 staged file will be unstaged"));
+        assert_eq!(ResetMode::Mixed.worktree_effect(&preview), "kept");
+        assert!(ResetMode::Hard.worktree_effect(&preview).contains("2 files are LOST"));
+    }
+}
@@ -0,0 +1,51 @@
+use anyhow::Result;
+use colored::*;
+use git2::Repository;
+
+use crate::config::Config;
+use crate::conflict::conflict_sides;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::ConflictResolver;
+use crate::operation::InProgress;
+use crate::tools::{select_tool, ToolKind};
+
+/// Execute the resolve command
+pub async fn execute(rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let conflicts = conflict_sides(repo)?;
+    if conflicts.is_empty() {
+        ui_println!("{} No conflicts to resolve", "✅".green());
+        return Ok(());
+    }
+
+    if !config.is_interactive() {
+        ui_println!("{} Conflicted files:", "⚔️".red());
+        for conflict in &conflicts {
+            ui_println!("  {} {}", "•".red(), conflict.path);
+        }
+        ui_println!("{} Edit them and mark each resolved with {}", "💡".blue(), "rgit add <file>".cyan());
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+
+    let tool = select_tool(Some(repo), config, ToolKind::Merge, None).ok();
+    let summary = ConflictResolver::new(repo, conflicts).with_tool(tool).resolve()?;
+    println!();
+    if summary.remaining.is_empty() {
+        if let Some(next) = continue_command(repo) {
+            ui_println!("{} Finish with {}", "💡".blue(), next.cyan());
+        }
+    } else {
+        ui_println!("{} {} file{} still conflicted; run {} again when ready",
+                "💡".blue(),
+                summary.remaining.len(),
+                if summary.remaining.len() == 1 { "" } else { "s" },
+                "rgit resolve".cyan());
+    }
+    Ok(())
+}
+
+/// The command that concludes the operation that produced the conflicts
+pub fn continue_command(repo: &Repository) -> Option<String> {
+    InProgress::detect(repo).ok().flatten().map(|op| op.kind.continue_command())
+}
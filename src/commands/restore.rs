@@ -0,0 +1,573 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{
+    Delta, Index, IndexEntry, IndexTime, ObjectType, Pathspec, PathspecFlags, Repository, ResetType, Status, Tree,
+    TreeWalkMode, TreeWalkResult,
+};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use crate::cli::RestoreArgs;
+use crate::commands::backup::{self, RefChange, Snapshot};
+use crate::commands::cherry_pick::empty_tree;
+use crate::commands::rm::{index_paths_matching, remove_empty_parents};
+use crate::commands::unstage;
+use crate::config::Config;
+use crate::core::{resolve_object, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+use crate::validation::validate_pathspec;
+
+/// Execute the restore command
+pub async fn execute(args: &RestoreArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.patch {
+        return unstage::unstage_hunks(rgit, &args.paths, config);
+    }
+
+    let repo = &rgit.repo;
+    let targets = RestoreTargets { index: args.staged, worktree: args.worktree || !args.staged };
+    let source = match &args.from_backup {
+        Some(id) => {
+            let snapshot = pick_snapshot(repo, id, config)?;
+            if args.paths.is_empty() {
+                return restore_snapshot(rgit, &snapshot, args.force, config);
+            }
+            let tree = backup::snapshot_tree(repo, &snapshot)?.ok_or_else(|| {
+                RgitError::InvalidArgument(format!("snapshot {} has no files", snapshot.id))
+            })?;
+            RestoreSource::Tree { label: format!("snapshot {}", snapshot.id), tree }
+        }
+        None => RestoreSource::resolve(repo, args.source.as_deref(), targets)?,
+    };
+    let cwd = std::env::current_dir()?;
+    let paths = plan_restore(repo, &rgit.repo_path, &cwd, &args.paths, &source)?;
+
+    if targets.worktree && !args.force && config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let modified = locally_modified(repo, &paths)?;
+        if !modified.is_empty() {
+            ui_println!("{} Local modifications to these files will be discarded:", "⚠️".yellow());
+            for path in &modified {
+                ui_println!("  {} {}", "•".red(), path);
+            }
+            let confirmed = InteractivePrompt::new()
+                .with_message("Discard these changes?")
+                .confirm()?;
+            if !confirmed {
+                return Err(RgitError::OperationCancelled.into());
+            }
+        }
+    }
+
+    restore_paths(repo, &rgit.repo_path, &paths, &source, targets)?;
+
+    for path in &paths {
+        ui_println!("  {} {}", "↩️".blue(), path);
+    }
+    ui_println!("{} Restored {} file{} in {} from {}",
+            "✅".green().bold(),
+            paths.len(),
+            if paths.len() == 1 { "" } else { "s" },
+            targets.describe(),
+            source.label().cyan());
+    Ok(())
+}
+
+/// Which copies of the files get restored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestoreTargets {
+    pub index: bool,
+    pub worktree: bool,
+}
+
+impl RestoreTargets {
+    fn describe(&self) -> &'static str {
+        match (self.index, self.worktree) {
+            (true, true) => "the index and working tree",
+            (true, false) => "the index",
+            _ => "the working tree",
+        }
+    }
+}
+
+/// Where restored content comes from
+pub enum RestoreSource<'r> {
+    /// The staged version of each file
+    Index,
+    /// A tree, labelled with the revision it was named by
+    Tree { label: String, tree: Tree<'r> },
+}
+
+impl<'r> RestoreSource<'r> {
+    /// Resolve `--source`, defaulting like git: the index for the working
+    /// tree, HEAD when the index is restored
+    pub fn resolve(repo: &'r Repository, spec: Option<&str>, targets: RestoreTargets) -> Result<Self> {
+        match spec {
+            Some(spec) => {
+                let tree = resolve_object(repo, spec)?
+                    .peel_to_tree()
+                    .map_err(|_| RgitError::InvalidReference(format!("'{}' does not name a tree", spec)))?;
+                Ok(RestoreSource::Tree { label: spec.to_string(), tree })
+            }
+            None if targets.index => {
+                // Nothing is committed yet: restoring the index unstages everything
+                let tree = match repo.head().and_then(|h| h.peel_to_tree()) {
+                    Ok(tree) => tree,
+                    Err(_) => empty_tree(repo)?,
+                };
+                Ok(RestoreSource::Tree { label: "HEAD".to_string(), tree })
+            }
+            None => Ok(RestoreSource::Index),
+        }
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            RestoreSource::Index => "the index",
+            RestoreSource::Tree { label, .. } => label,
+        }
+    }
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+/// Files to restore: everything the pathspecs match in the index or the source
+pub fn plan_restore(repo: &Repository, workdir: &Path, cwd: &Path, specs: &[String], source: &RestoreSource) -> Result<Vec<String>> {
+    let index = repo.index()?;
+    let mut paths = BTreeSet::new();
+
+    for spec in specs {
+        let relative = validate_pathspec(workdir, cwd, spec)?;
+        let mut matched = index_paths_matching(&index, &relative)?;
+        if let RestoreSource::Tree { tree, .. } = source {
+            matched.extend(tree_paths_matching(tree, &relative)?);
+        }
+
+        if matched.is_empty() {
+            return Err(RgitError::InvalidArgument(
+                format!("pathspec '{}' did not match any file known to git", spec)
+            ).into());
+        }
+        paths.extend(matched);
+    }
+
+    if let RestoreSource::Index = source {
+        let unmerged: Vec<&String> = paths.iter().filter(|p| is_unmerged(&index, p)).collect();
+        if !unmerged.is_empty() {
+            return Err(RgitError::InvalidArgument(format!(
+                "{} unmerged; resolve first or restore with --source",
+                unmerged.iter().map(|p| format!("'{}'", p)).collect::<Vec<_>>().join(", ")
+            )).into());
+        }
+    }
+
+    Ok(paths.into_iter().collect())
+}
+
+/// Blob paths in `tree` matched by `spec` (already relative to the working tree)
+fn tree_paths_matching(tree: &Tree, spec: &str) -> Result<Vec<String>> {
+    let pathspec = Pathspec::new([spec])?;
+    let mut paths = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            let path = format!("{}{}", root, entry.name().unwrap_or(""));
+            if spec.is_empty() || pathspec.matches_path(Path::new(&path), PathspecFlags::DEFAULT) {
+                paths.push(path);
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+    Ok(paths)
+}
+
+fn is_unmerged(index: &Index, path: &str) -> bool {
+    index.get_path(Path::new(path), 0).is_none()
+        && (1..=3).any(|stage| index.get_path(Path::new(path), stage).is_some())
+}
+
+/// Files whose working tree content differs from the index
+fn locally_modified(repo: &Repository, paths: &[String]) -> Result<Vec<String>> {
+    let mut modified = Vec::new();
+    for path in paths {
+        let status = repo.status_file(Path::new(path)).unwrap_or(Status::CURRENT);
+        if status.intersects(Status::WT_MODIFIED | Status::WT_TYPECHANGE | Status::WT_DELETED) {
+            modified.push(path.clone());
+        }
+    }
+    Ok(modified)
+}
+
+// =============================================================================
+// Restoring
+// =============================================================================
+
+/// Make `paths` in the selected targets match `source`.
+///
+/// Paths missing from the source are removed from the targets, as git does.
+pub fn restore_paths(
+    repo: &Repository,
+    workdir: &Path,
+    paths: &[String],
+    source: &RestoreSource,
+    targets: RestoreTargets,
+) -> Result<()> {
+    if targets.index {
+        if let RestoreSource::Tree { tree, .. } = source {
+            restore_index(repo, tree, paths)?;
+        }
+    }
+    if !targets.worktree {
+        return Ok(());
+    }
+
+    let mut present = Vec::new();
+    let mut missing = Vec::new();
+    let mut index = repo.index()?;
+    for path in paths {
+        let exists = match source {
+            RestoreSource::Index => index.get_path(Path::new(path), 0).is_some(),
+            RestoreSource::Tree { tree, .. } => tree.get_path(Path::new(path)).is_ok(),
+        };
+        if exists { present.push(path) } else { missing.push(path) }
+    }
+
+    if !present.is_empty() {
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force().update_index(false);
+        for path in &present {
+            checkout.path(path.as_str());
+        }
+        match source {
+            RestoreSource::Index => repo.checkout_index(Some(&mut index), Some(&mut checkout))?,
+            RestoreSource::Tree { tree, .. } => repo.checkout_tree(tree.as_object(), Some(&mut checkout))?,
+        }
+    }
+
+    for path in missing {
+        let full = workdir.join(path);
+        if full.symlink_metadata().is_ok() {
+            fs::remove_file(&full)?;
+            remove_empty_parents(workdir, &full);
+        }
+    }
+    Ok(())
+}
+
+/// Point the index entries for `paths` at their blobs in `tree`
+fn restore_index(repo: &Repository, tree: &Tree, paths: &[String]) -> Result<()> {
+    let mut index = repo.index()?;
+    for path in paths {
+        // Also drops any conflict stages for the path
+        let _ = index.remove_path(Path::new(path));
+        if let Ok(entry) = tree.get_path(Path::new(path)) {
+            let size = repo.find_blob(entry.id()).map(|b| b.size() as u32).unwrap_or(0);
+            index.add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: entry.filemode() as u32,
+                uid: 0,
+                gid: 0,
+                file_size: size,
+                id: entry.id(),
+                flags: path.len().min(0xfff) as u16,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            })?;
+        }
+    }
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
+// =============================================================================
+// Backups
+// =============================================================================
+
+/// The snapshot named by `--from-backup`, or one chosen from a list
+fn pick_snapshot(repo: &Repository, id: &str, config: &Config) -> Result<Snapshot> {
+    if !id.is_empty() {
+        return backup::find_snapshot(repo, id);
+    }
+
+    let snapshots = backup::list_snapshots(repo)?;
+    if snapshots.is_empty() {
+        return Err(RgitError::InvalidArgument("there are no backup snapshots; take one with 'rgit backup'".to_string()).into());
+    }
+    let items: Vec<String> = snapshots.iter()
+        .map(|s| format!("{}  {}  {} ({})", s.id, s.age(), s.describe(), s.contents()))
+        .collect();
+    if !config.is_interactive() {
+        ui_println!("{} Snapshots:", "💾".blue());
+        for item in &items {
+            println!("  {}", item);
+        }
+        return Err(RgitError::InvalidArgument("name a snapshot with --from-backup=<SNAPSHOT>".to_string()).into());
+    }
+
+    let choice = InteractivePrompt::new()
+        .with_message("Restore from which snapshot?")
+        .with_options(&items)
+        .select()?;
+    Ok(snapshots[choice].clone())
+}
+
+/// Files the working tree changed since the snapshot: those it can put
+/// back, and those added since (which a restore leaves alone)
+fn snapshot_file_changes(repo: &Repository, tree: &Tree) -> Result<(Vec<String>, Vec<String>)> {
+    let diff = repo.diff_tree_to_workdir_with_index(Some(tree), None)?;
+    let mut restorable = Vec::new();
+    let mut added = Vec::new();
+    for delta in diff.deltas() {
+        match delta.status() {
+            Delta::Modified | Delta::Deleted | Delta::Typechange => {
+                restorable.extend(delta.old_file().path().map(|p| p.to_string_lossy().replace('\\', "/")));
+            }
+            Delta::Added => {
+                added.extend(delta.new_file().path().map(|p| p.to_string_lossy().replace('\\', "/")));
+            }
+            _ => {}
+        }
+    }
+    Ok((restorable, added))
+}
+
+fn describe_ref_change(change: &RefChange) -> String {
+    let short = |oid: Option<git2::Oid>| oid.map(|id| shorten_oid(&id, 8)).unwrap_or_default();
+    match (change.snapshot, change.current) {
+        (Some(_), Some(_)) => format!("{} {} {} {}", "~".yellow(), change.short_name(), short(change.snapshot).yellow(),
+                                      format!("(now {})", short(change.current)).dimmed()),
+        (Some(_), None) => format!("{} {} {} {}", "-".red(), change.short_name(), short(change.snapshot).yellow(),
+                                   "(deleted since)".dimmed()),
+        _ => format!("{} {} {}", "+".green(), change.short_name(), "(created since; left alone)".dimmed()),
+    }
+}
+
+/// Show how the repository differs from a snapshot and put back the refs
+/// and files the user picks
+fn restore_snapshot(rgit: &RgitCore, snapshot: &Snapshot, force: bool, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let changes = backup::ref_changes(repo, snapshot)?;
+    let tree = backup::snapshot_tree(repo, snapshot)?;
+    let (files, added) = match &tree {
+        Some(tree) => snapshot_file_changes(repo, tree)?,
+        None => (Vec::new(), Vec::new()),
+    };
+
+    ui_println!("{} Snapshot {} ({}, {})", "💾".blue(), snapshot.id.yellow(), snapshot.describe(), snapshot.age());
+    if !changes.is_empty() {
+        println!("\n{}", "Refs:".bold());
+        for change in &changes {
+            ui_println!("  {}", describe_ref_change(change));
+        }
+    }
+    if !files.is_empty() || !added.is_empty() {
+        println!("\n{}", "Files (snapshot vs working tree):".bold());
+        for path in &files {
+            println!("  {} {}", "~".yellow(), path);
+        }
+        for path in &added {
+            println!("  {} {} {}", "+".green(), path, "(new since; left alone)".dimmed());
+        }
+    }
+
+    let restorable: Vec<&RefChange> = changes.iter().filter(|c| c.snapshot.is_some()).collect();
+    if restorable.is_empty() && files.is_empty() {
+        ui_println!("{} The repository already matches this snapshot", "✅".green());
+        return Ok(());
+    }
+    println!();
+
+    let (refs, restore_files) = if force {
+        (restorable, !files.is_empty())
+    } else if config.is_interactive() {
+        let mut items: Vec<String> = restorable.iter().map(|c| describe_ref_change(c)).collect();
+        if !files.is_empty() {
+            items.push(format!("{} file{} in the working tree", files.len(), if files.len() == 1 { "" } else { "s" }));
+        }
+        let chosen = InteractivePrompt::new()
+            .with_message("Select what to restore")
+            .with_options(&items)
+            .multiselect_prompt()?;
+        let refs = restorable.iter().enumerate()
+            .filter(|(n, _)| chosen.contains(n))
+            .map(|(_, c)| *c)
+            .collect();
+        (refs, !files.is_empty() && chosen.contains(&restorable.len()))
+    } else if config.ui.assume_yes {
+        // Without asking, nothing is picked
+        (Vec::new(), false)
+    } else {
+        ui_println!("{} Use {} to restore everything listed, or {} for single files",
+                "💡".blue(), "--force".cyan(), "rgit restore --from-backup=<SNAPSHOT> <path>".cyan());
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    };
+    let files = if restore_files { files } else { Vec::new() };
+    if refs.is_empty() && files.is_empty() {
+        ui_println!("{} Nothing selected; nothing restored", "💡".blue());
+        return Ok(());
+    }
+
+    backup::snapshot_before(rgit, config, "restore")?;
+    apply_snapshot(repo, &rgit.repo_path, snapshot, &refs, &files)?;
+
+    for change in &refs {
+        ui_println!("  {} {}", "↩️".blue(), change.short_name());
+    }
+    for path in &files {
+        ui_println!("  {} {}", "↩️".blue(), path);
+    }
+    ui_println!("{} Restored {} ref{} and {} file{} from snapshot {}",
+            "✅".green().bold(),
+            refs.len(), if refs.len() == 1 { "" } else { "s" },
+            files.len(), if files.len() == 1 { "" } else { "s" },
+            snapshot.id.cyan());
+    Ok(())
+}
+
+/// Point `refs` back at their snapshot values and check out `files` from
+/// the snapshot. The checked-out branch moves with a hard reset so the
+/// working tree follows it.
+fn apply_snapshot(repo: &Repository, workdir: &Path, snapshot: &Snapshot, refs: &[&RefChange], files: &[String]) -> Result<()> {
+    let head_branch = repo.head().ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.name().map(String::from));
+    let journal = Journal::for_repo(repo);
+
+    for change in refs {
+        let Some(target) = change.snapshot else { continue };
+        let mut entry = JournalEntry::new("restore --from-backup", &change.name)
+            .with_new_oid(target)
+            .with_detail(format!("snapshot {}", snapshot.id));
+        if let Some(current) = change.current {
+            entry = entry.with_old_oid(current);
+        }
+        journal.record(&entry)?;
+
+        if head_branch.as_deref() == Some(change.name.as_str()) {
+            if let Some(current) = change.current {
+                repo.reference("ORIG_HEAD", current, true, "rgit: restore")?;
+            }
+            repo.reset(repo.find_commit(target)?.as_object(), ResetType::Hard, None)?;
+        } else {
+            repo.reference(&change.name, target, true, &format!("rgit: restore from snapshot {}", snapshot.id))?;
+        }
+    }
+
+    if !files.is_empty() {
+        let tree = backup::snapshot_tree(repo, snapshot)?
+            .ok_or_else(|| RgitError::InvalidArgument(format!("snapshot {} has no files", snapshot.id)))?;
+        let source = RestoreSource::Tree { label: snapshot.id.clone(), tree };
+        restore_paths(repo, workdir, files, &source, RestoreTargets { index: false, worktree: true })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    const WORKTREE: RestoreTargets = RestoreTargets { index: false, worktree: true };
+    const STAGED: RestoreTargets = RestoreTargets { index: true, worktree: false };
+    const BOTH: RestoreTargets = RestoreTargets { index: true, worktree: true };
+
+    fn restore(repo: &TestRepo, specs: &[&str], source: Option<&str>, targets: RestoreTargets) -> Result<Vec<String>> {
+        let specs: Vec<String> = specs.iter().map(|s| s.to_string()).collect();
+        let source = RestoreSource::resolve(repo.repo(), source, targets)?;
+        let paths = plan_restore(repo.repo(), repo.path(), repo.path(), &specs, &source)?;
+        restore_paths(repo.repo(), repo.path(), &paths, &source, targets)?;
+        Ok(paths)
+    }
+
+    fn read(repo: &TestRepo, path: &str) -> String {
+        fs::read_to_string(repo.path().join(path)).unwrap()
+    }
+
+    fn status(repo: &TestRepo, path: &str) -> Status {
+        repo.repo().status_file(Path::new(path)).unwrap()
+    }
+
+    #[test]
+    fn test_restore_worktree_from_index() {
+        let repo = TestRepo::new();
+        repo.write("a.txt", "one\n").write("src/b.rs", "b\n");
+        repo.commit("Initial");
+        repo.write("a.txt", "staged\n").stage("a.txt").write("a.txt", "unstaged\n");
+        repo.write("src/b.rs", "changed\n");
+
+        assert_eq!(restore(&repo, &["a.txt", "src"], None, WORKTREE).unwrap(), vec!["a.txt", "src/b.rs"]);
+        assert_eq!(read(&repo, "a.txt"), "staged\n");
+        assert_eq!(read(&repo, "src/b.rs"), "b\n");
+        assert_eq!(status(&repo, "a.txt"), Status::INDEX_MODIFIED);
+
+        assert!(restore(&repo, &["untracked.txt"], None, WORKTREE).is_err());
+    }
+
+    #[test]
+    fn test_restore_staged_unstages() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "Initial");
+        repo.write("a.txt", "two\n").write("new.txt", "new\n").stage("a.txt").stage("new.txt");
+
+        restore(&repo, &["*.txt"], None, STAGED).unwrap();
+        assert_eq!(status(&repo, "a.txt"), Status::WT_MODIFIED);
+        assert_eq!(status(&repo, "new.txt"), Status::WT_NEW);
+        assert_eq!(read(&repo, "a.txt"), "two\n");
+    }
+
+    #[test]
+    fn test_restore_from_revision() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        repo.write("a.txt", "two\n").write("later.txt", "later\n");
+        repo.commit("Second");
+
+        // Worktree only: the index keeps HEAD's content
+        restore(&repo, &["a.txt"], Some(&first.to_string()), WORKTREE).unwrap();
+        assert_eq!(read(&repo, "a.txt"), "one\n");
+        assert_eq!(status(&repo, "a.txt"), Status::WT_MODIFIED);
+
+        // Both targets: files missing from the source go away
+        restore(&repo, &["."], Some("HEAD~1"), BOTH).unwrap();
+        assert!(!repo.path().join("later.txt").exists());
+        assert_eq!(status(&repo, "a.txt"), Status::INDEX_MODIFIED);
+        assert_eq!(status(&repo, "later.txt"), Status::INDEX_DELETED);
+    }
+
+    #[test]
+    fn test_restore_refs_and_files_from_snapshot() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        repo.branch("feature");
+        repo.write("a.txt", "draft\n");
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let snapshot = backup::create_snapshot(repo.repo(), &backup::SnapshotRequest::default(), &signature).unwrap();
+
+        // Lose the draft and move both branches on
+        repo.write("a.txt", "two\n").write("b.txt", "b\n");
+        let second = repo.commit("Second");
+        repo.repo().reference("refs/heads/feature", second, true, "test").unwrap();
+
+        let changes = backup::ref_changes(repo.repo(), &snapshot).unwrap();
+        assert_eq!(changes.len(), 2);
+        let tree = backup::snapshot_tree(repo.repo(), &snapshot).unwrap().unwrap();
+        let (files, added) = snapshot_file_changes(repo.repo(), &tree).unwrap();
+        assert_eq!(files, vec!["a.txt"]);
+        assert_eq!(added, vec!["b.txt"]);
+
+        let main: Vec<&RefChange> = changes.iter().filter(|c| c.name == "refs/heads/main").collect();
+        apply_snapshot(repo.repo(), repo.path(), &snapshot, &main, &files).unwrap();
+        assert_eq!(repo.head(), first);
+        assert_eq!(repo.repo().refname_to_id("refs/heads/feature").unwrap(), second);
+        assert_eq!(read(&repo, "a.txt"), "draft\n");
+        assert!(!repo.path().join("b.txt").exists());
+    }
+}
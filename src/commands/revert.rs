@@ -0,0 +1,254 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::RevertArgs;
+use crate::commands::cherry_pick::{empty_tree, merge_into_index};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::operation::OperationKind;
+use crate::sequencer::{self, Action, Sequence};
+use crate::utils::shorten_oid;
+use crate::validation::validate_message;
+
+/// Execute the revert command
+pub async fn execute(args: &RevertArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.abort {
+        return sequencer::abort::<RevertOptions>(rgit);
+    }
+    if args.continue_revert {
+        return sequencer::resume::<RevertOptions>(rgit, config);
+    }
+
+    if args.commits.is_empty() {
+        return Err(RgitError::InvalidArgument("No commits specified to revert".to_string()).into());
+    }
+
+    let options = RevertOptions {
+        no_commit: args.no_commit,
+        edit: args.edit,
+        mainline: args.mainline,
+    };
+    let commits = sequencer::resolve_commits::<RevertOptions>(&rgit.repo, &args.commits)?;
+    for oid in &commits {
+        check_mainline(&rgit.repo.find_commit(*oid)?, options.mainline)?;
+    }
+
+    sequencer::start(rgit, commits, options, config)
+}
+
+/// Options that apply to every commit in a sequence
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RevertOptions {
+    /// Apply the reverts to the index without committing
+    pub no_commit: bool,
+    /// Edit each revert message before committing
+    pub edit: bool,
+    /// Parent (1-based) whose side of a merge is kept
+    pub mainline: Option<u32>,
+}
+
+/// Persistent state of a multi-commit revert
+pub type RevertSequence = Sequence<RevertOptions>;
+
+impl Action for RevertOptions {
+    const KIND: OperationKind = OperationKind::Revert;
+    const DOING: &'static str = "Reverting";
+    const DONE: &'static str = "Reverted";
+    const OLDEST_FIRST: bool = false;
+
+    fn icon() -> ColoredString {
+        "⏪".blue()
+    }
+
+    fn no_commit(&self) -> bool {
+        self.no_commit
+    }
+
+    fn apply(&self, repo: &Repository, commit: &Commit, _config: &Config) -> Result<Vec<String>> {
+        apply_revert(repo, commit, self.mainline)
+    }
+
+    fn commit(&self, rgit: &RgitCore, commit: &Commit, config: &Config) -> Result<Oid> {
+        commit_revert(rgit, commit, self, config)
+    }
+}
+
+// =============================================================================
+// Reverting
+// =============================================================================
+
+/// Apply the inverse of a commit to the index and working tree, returning the
+/// conflicted paths
+fn apply_revert(repo: &Repository, commit: &Commit, mainline: Option<u32>) -> Result<Vec<String>> {
+    let parent = match mainline {
+        Some(n) => Some(commit.parent(n as usize - 1)?),
+        None => commit.parents().next(),
+    };
+    let target = match &parent {
+        Some(parent) => parent.tree()?,
+        None => empty_tree(repo)?,
+    };
+
+    merge_into_index(repo, &commit.tree()?, &target)
+}
+
+/// Commit the staged result of a revert
+fn commit_revert(rgit: &RgitCore, commit: &Commit, options: &RevertOptions, config: &Config) -> Result<Oid> {
+    let repo = &rgit.repo;
+    let mut index = repo.index()?;
+    index.read(false)?;
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let head = repo.head()?.peel_to_commit()?;
+
+    let mut message = revert_message(commit, options.mainline);
+    if options.edit && config.is_interactive() {
+        message = InteractivePrompt::new()
+            .with_message(message)
+            .editor()?;
+    }
+    validate_message(&message)?;
+
+    let signature = rgit.get_signature()?;
+    Ok(repo.commit(Some("HEAD"), &signature, &signature, &message, &tree, &[&head])?)
+}
+
+/// The message git uses for reverts
+fn revert_message(commit: &Commit, mainline: Option<u32>) -> String {
+    let summary = commit.summary().unwrap_or("");
+    let parent = mainline.and_then(|n| commit.parent_id(n as usize - 1).ok());
+
+    match parent {
+        Some(parent) => format!(
+            "Revert \"{}\"\n\nThis reverts commit {}, reversing\nchanges made to {}.\n",
+            summary, commit.id(), parent
+        ),
+        None => format!("Revert \"{}\"\n\nThis reverts commit {}.\n", summary, commit.id()),
+    }
+}
+
+/// Merges need `-m` to say which side to keep; other commits must not have it
+fn check_mainline(commit: &Commit, mainline: Option<u32>) -> Result<()> {
+    let id = shorten_oid(&commit.id(), 8);
+    let parents = commit.parent_count();
+
+    match mainline {
+        None if parents > 1 => Err(RgitError::InvalidArgument(format!(
+            "{} is a merge commit; use -m <parent> to choose the side to keep (1 to {})", id, parents
+        )).into()),
+        Some(_) if parents <= 1 => Err(RgitError::InvalidArgument(format!(
+            "-m was given but {} is not a merge commit", id
+        )).into()),
+        Some(n) if n == 0 || n as usize > parents => Err(RgitError::InvalidArgument(format!(
+            "{} has {} parents; -m must be between 1 and {}", id, parents, parents
+        )).into()),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use git2::BranchType;
+    use std::fs;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.ui.interactive = false;
+        config
+    }
+
+    #[test]
+    fn test_revert_commits() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        let third = repo.commit_file("other.txt", "other\n", "Third");
+
+        sequencer::start(&repo.core(), vec![third, second], RevertOptions::default(), &config()).unwrap();
+
+        let head = repo.repo().find_commit(repo.head()).unwrap();
+        assert_eq!(head.summary(), Some("Revert \"Second\""));
+        assert!(head.message().unwrap().contains(&format!("This reverts commit {}.", second)));
+        assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), "one\n");
+        assert!(!repo.path().join("other.txt").exists());
+        assert!(RevertSequence::load(repo.repo()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_no_commit_accumulates_reverts() {
+        let repo = TestRepo::new();
+        repo.commit_file("base.txt", "base\n", "Base");
+        let a = repo.commit_file("a.txt", "a\n", "Add a");
+        let b = repo.commit_file("b.txt", "b\n", "Add b");
+
+        let options = RevertOptions { no_commit: true, ..Default::default() };
+        sequencer::start(&repo.core(), vec![b, a], options, &config()).unwrap();
+
+        assert_eq!(repo.head(), b);
+        assert!(!repo.path().join("a.txt").exists());
+        assert!(!repo.path().join("b.txt").exists());
+        let mut index = repo.repo().index().unwrap();
+        index.read(true).unwrap();
+        assert!(index.get_path(std::path::Path::new("a.txt"), 0).is_none());
+    }
+
+    #[test]
+    fn test_merge_commit_needs_mainline() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+        repo.branch("feature").checkout("feature");
+        repo.commit_file("feature.txt", "feature\n", "Feature");
+        repo.checkout("main");
+        repo.commit_file("main.txt", "main\n", "Main");
+
+        // Record a merge of feature into main
+        let feature = repo.repo().find_branch("feature", BranchType::Local).unwrap();
+        let theirs = feature.get().peel_to_commit().unwrap();
+        let ours = repo.repo().find_commit(repo.head()).unwrap();
+        let mut index = repo.repo().merge_commits(&ours, &theirs, None).unwrap();
+        let tree = repo.repo().find_tree(index.write_tree_to(repo.repo()).unwrap()).unwrap();
+        let sig = repo.repo().signature().unwrap();
+        let merge = repo.repo().commit(Some("HEAD"), &sig, &sig, "Merge feature", &tree, &[&ours, &theirs]).unwrap();
+        repo.repo().checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+
+        let merge_commit = repo.repo().find_commit(merge).unwrap();
+        assert!(check_mainline(&merge_commit, None).is_err());
+        assert!(check_mainline(&merge_commit, Some(3)).is_err());
+        assert!(check_mainline(&ours, Some(1)).is_err());
+
+        let options = RevertOptions { mainline: Some(1), ..Default::default() };
+        sequencer::start(&repo.core(), vec![merge], options, &config()).unwrap();
+
+        assert!(!repo.path().join("feature.txt").exists());
+        assert!(repo.path().join("main.txt").exists());
+        let head = repo.repo().find_commit(repo.head()).unwrap();
+        assert!(head.message().unwrap().contains(&format!("changes made to {}.", ours.id())));
+    }
+
+    #[test]
+    fn test_conflict_then_continue() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        repo.commit_file("file.txt", "three\n", "Third");
+
+        let error = sequencer::start(&repo.core(), vec![second], RevertOptions::default(), &config()).unwrap_err();
+        assert!(matches!(error.downcast_ref::<RgitError>(), Some(RgitError::MergeConflict(_))));
+        assert!(RevertSequence::load(repo.repo()).unwrap().is_some());
+
+        // Continuing with conflicts still present is refused
+        assert!(sequencer::resume::<RevertOptions>(&repo.core(), &config()).is_err());
+
+        repo.write("file.txt", "resolved\n").stage("file.txt");
+        sequencer::resume::<RevertOptions>(&repo.core(), &config()).unwrap();
+
+        let head = repo.repo().find_commit(repo.head()).unwrap();
+        assert_eq!(head.summary(), Some("Revert \"Second\""));
+        assert!(RevertSequence::load(repo.repo()).unwrap().is_none());
+    }
+}
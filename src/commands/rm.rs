@@ -0,0 +1,225 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Index, Pathspec, PathspecFlags, Repository, Status};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::RmArgs;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::timings::{self, Phase};
+use crate::validation::validate_pathspec;
+
+/// Execute the rm command
+pub async fn execute(args: &RmArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let cwd = std::env::current_dir()?;
+    let paths = plan_removal(repo, &rgit.repo_path, &cwd, &args.paths, args.recursive)?;
+
+    let problems = removal_problems(repo, &paths, args.cached)?;
+    if !problems.is_empty() && !args.force {
+        ui_println!("{} These files have changes that would be lost:", "⚠️".yellow());
+        for (path, problem) in &problems {
+            ui_println!("  {} {} ({})", "•".red(), path, problem);
+        }
+        ui_println!("\n{} Use {} to keep the files on disk, or {} to remove them anyway",
+                "💡".blue(), "--cached".cyan(), "--force".cyan());
+        return Err(RgitError::InvalidArgument(
+            format!("refusing to remove {} file{} with changes", problems.len(), plural(problems.len()))
+        ).into());
+    }
+
+    if args.dry_run {
+        ui_println!("{} Would remove:", "👁️".blue().bold());
+        for path in &paths {
+            println!("  {} {}{}", "-".red(), path, if args.cached { " (index only)".dimmed() } else { "".normal() });
+        }
+        return Ok(());
+    }
+
+    remove_paths(repo, &rgit.repo_path, &paths, args.cached)?;
+
+    for path in &paths {
+        ui_println!("  {} {}", "🗑️".red(), path);
+    }
+    ui_println!("{} Removed {} file{}{}",
+            "✅".green().bold(),
+            paths.len(),
+            plural(paths.len()),
+            if args.cached { " from the index (kept on disk)" } else { "" });
+    ui_println!("{} Use {} to record the removal", "💡".blue(), "rgit commit".cyan());
+    Ok(())
+}
+
+// =============================================================================
+// Matching
+// =============================================================================
+
+/// Whether a pathspec uses glob syntax
+pub fn is_glob(spec: &str) -> bool {
+    spec.contains(['*', '?', '['])
+}
+
+/// Index paths matched by `spec` (already relative to the working tree).
+///
+/// Globs match like `git ls-files`; a plain path matches the file itself or,
+/// for a directory, every tracked file below it.
+pub fn index_paths_matching(index: &Index, spec: &str) -> Result<Vec<String>> {
+    if spec.is_empty() {
+        // The working tree root
+        return Ok(index.iter().map(|e| String::from_utf8_lossy(&e.path).to_string()).collect());
+    }
+
+    let pathspec = Pathspec::new([spec])?;
+    let matches = pathspec.match_index(index, PathspecFlags::DEFAULT)?;
+    Ok(matches.entries().map(|p| String::from_utf8_lossy(p).to_string()).collect())
+}
+
+/// Tracked files to remove for the given command-line paths
+fn plan_removal(repo: &Repository, workdir: &Path, cwd: &Path, specs: &[String], recursive: bool) -> Result<Vec<String>> {
+    let index = repo.index()?;
+    let mut paths = Vec::new();
+
+    for spec in specs {
+        let relative = validate_pathspec(workdir, cwd, spec)?;
+        let matched = index_paths_matching(&index, &relative)?;
+        if matched.is_empty() {
+            return Err(RgitError::InvalidArgument(
+                format!("pathspec '{}' did not match any tracked files", spec)
+            ).into());
+        }
+        if !recursive && !is_glob(&relative) && !matched.contains(&relative) {
+            return Err(RgitError::InvalidArgument(
+                format!("not removing '{}' recursively without -r", spec)
+            ).into());
+        }
+        paths.extend(matched);
+    }
+
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Files whose removal would lose changes, with the reason
+fn removal_problems(repo: &Repository, paths: &[String], cached: bool) -> Result<Vec<(String, &'static str)>> {
+    let staged = Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_TYPECHANGE | Status::INDEX_RENAMED;
+    let modified = Status::WT_MODIFIED | Status::WT_TYPECHANGE;
+    let mut problems = Vec::new();
+
+    for path in paths {
+        let status = repo.status_file(Path::new(path))?;
+        let problem = if status.intersects(staged) && status.intersects(modified) {
+            Some("staged content differs from both the file and HEAD")
+        } else if cached {
+            None
+        } else if status.intersects(staged) {
+            Some("has changes staged in the index")
+        } else if status.intersects(modified) {
+            Some("has local modifications")
+        } else {
+            None
+        };
+
+        if let Some(problem) = problem {
+            problems.push((path.clone(), problem));
+        }
+    }
+    Ok(problems)
+}
+
+/// Remove paths from the index and, unless `cached`, from the working tree
+fn remove_paths(repo: &Repository, workdir: &Path, paths: &[String], cached: bool) -> Result<()> {
+    let mut index = repo.index()?;
+    for path in paths {
+        index.remove_path(Path::new(path))?;
+
+        if !cached {
+            let full = workdir.join(path);
+            if full.symlink_metadata().is_ok() {
+                fs::remove_file(&full)?;
+            }
+            remove_empty_parents(workdir, &full);
+        }
+    }
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
+/// Delete directories left empty by a removal, stopping at the working tree root
+pub fn remove_empty_parents(workdir: &Path, path: &Path) {
+    let mut dir: Option<PathBuf> = path.parent().map(Path::to_path_buf);
+    while let Some(current) = dir {
+        if current == workdir || !current.starts_with(workdir) || fs::remove_dir(&current).is_err() {
+            break;
+        }
+        dir = current.parent().map(Path::to_path_buf);
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn specs(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn test_plan_removal() {
+        let repo = TestRepo::new();
+        repo.write("src/a.rs", "a\n").write("src/b.rs", "b\n").write("src/c.txt", "c\n").write("README.md", "r\n");
+        repo.commit("Initial");
+        let root = repo.path();
+
+        assert_eq!(plan_removal(repo.repo(), root, root, &specs(&["src/*.rs"]), false).unwrap(),
+                   vec!["src/a.rs", "src/b.rs"]);
+        assert_eq!(plan_removal(repo.repo(), root, &root.join("src"), &specs(&["c.txt"]), false).unwrap(),
+                   vec!["src/c.txt"]);
+        assert_eq!(plan_removal(repo.repo(), root, root, &specs(&["src"]), true).unwrap().len(), 3);
+        assert!(plan_removal(repo.repo(), root, root, &specs(&["src"]), false).is_err());
+        assert!(plan_removal(repo.repo(), root, root, &specs(&["missing.txt"]), false).is_err());
+    }
+
+    #[test]
+    fn test_remove_paths() {
+        let repo = TestRepo::new();
+        repo.write("dir/a.txt", "a\n").write("b.txt", "b\n");
+        repo.commit("Initial");
+
+        remove_paths(repo.repo(), repo.path(), &specs(&["dir/a.txt"]), false).unwrap();
+        assert!(!repo.path().join("dir").exists());
+
+        remove_paths(repo.repo(), repo.path(), &specs(&["b.txt"]), true).unwrap();
+        assert!(repo.path().join("b.txt").exists());
+
+        let mut index = repo.repo().index().unwrap();
+        index.read(true).unwrap();
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_removal_problems() {
+        let repo = TestRepo::new();
+        repo.write("clean.txt", "clean\n").write("modified.txt", "one\n").write("staged.txt", "one\n");
+        repo.commit("Initial");
+        repo.write("modified.txt", "two\n");
+        repo.write("staged.txt", "two\n").stage("staged.txt").write("staged.txt", "three\n");
+
+        let paths = specs(&["clean.txt", "modified.txt", "staged.txt"]);
+        let problems = removal_problems(repo.repo(), &paths, false).unwrap();
+        let flagged: Vec<&str> = problems.iter().map(|(p, _)| p.as_str()).collect();
+        assert_eq!(flagged, vec!["modified.txt", "staged.txt"]);
+
+        // Keeping the file on disk only loses content staged apart from both sides
+        let problems = removal_problems(repo.repo(), &paths, true).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].0, "staged.txt");
+    }
+}
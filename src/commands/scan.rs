@@ -0,0 +1,84 @@
+use anyhow::Result;
+use colored::*;
+
+use crate::cli::ScanArgs;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::secrets::{SecretFinding, SecretScanner, ALLOW_MARKER};
+use crate::validation::validate_pathspec;
+
+/// Execute the scan command
+pub async fn execute(args: &ScanArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let cwd = std::env::current_dir()?;
+    let mut specs = args.paths.iter()
+        .map(|spec| validate_pathspec(&rgit.repo_path, &cwd, spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    if specs.iter().any(|spec| spec.is_empty()) {
+        // The working tree root
+        specs.clear();
+    }
+
+    let scanner = SecretScanner::new(&config.secrets)?;
+    let findings = scanner.scan_index(&rgit.repo, args.all, &specs)?;
+    if findings.is_empty() {
+        let scope = if args.all { "tracked files" } else { "staged changes" };
+        ui_println!("{} No secrets found in {}", "✅".green(), scope);
+        return Ok(());
+    }
+
+    print_findings(&findings);
+    Err(RgitError::SecretsDetected(findings.len()).into())
+}
+
+/// Refuse to commit when the staged changes look like they hold secrets.
+/// Used by `commit` and `quick-commit` unless `--allow-secrets` is given.
+pub fn check_staged_changes(rgit: &RgitCore, config: &Config) -> Result<()> {
+    if !config.secrets.scan_on_commit {
+        return Ok(());
+    }
+    rgit.log("Scanning staged changes for secrets...");
+
+    let findings = SecretScanner::new(&config.secrets)?.scan_index(&rgit.repo, false, &[])?;
+    if findings.is_empty() {
+        return Ok(());
+    }
+
+    print_findings(&findings);
+    ui_println!("{} Commit with {} if these are not secrets, or mark the lines with {}",
+            "💡".blue(), "--allow-secrets".cyan(), ALLOW_MARKER.cyan());
+    Err(RgitError::SecretsDetected(findings.len()).into())
+}
+
+fn print_findings(findings: &[SecretFinding]) {
+    ui_println!("{} Possible secrets:", "🔑".red().bold());
+    for finding in findings {
+        println!("  {}:{} {}", finding.path.white(), finding.line, format!("[{}]", finding.rule).yellow());
+        println!("      {}", finding.excerpt.dimmed());
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_check_staged_changes() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "hello\n", "Initial commit");
+        repo.write(".env", "STRIPE_SECRET=sk_live_0123456789abcdefgh\n").stage(".env");
+
+        let mut config = Config::default();
+        let err = check_staged_changes(&repo.core(), &config).unwrap_err();
+        assert!(matches!(err.downcast_ref::<RgitError>(), Some(RgitError::SecretsDetected(1))));
+
+        config.secrets.scan_on_commit = false;
+        check_staged_changes(&repo.core(), &config).unwrap();
+
+        config.secrets.scan_on_commit = true;
+        config.secrets.allow_paths = Some(".env".to_string());
+        check_staged_changes(&repo.core(), &config).unwrap();
+    }
+}
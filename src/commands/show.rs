@@ -0,0 +1,667 @@
+use anyhow::Result;
+use colored::*;
+use git2::{
+    Blob, Commit, Delta, DiffDelta, DiffFile, DiffFormat, DiffStatsFormat, Object, ObjectType, Oid, Patch, Repository, Sort, Tag, Tree,
+};
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+use crate::binary::{self, PreviewProtocol};
+use crate::cli::ShowArgs;
+use crate::commands::diff::{content_options, function_context_hunks, rename_detection};
+use crate::commands::log::{collect_decorations, commit_lines};
+use crate::config::Config;
+use crate::core::{Revision, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::output::decoration;
+use crate::pager;
+use crate::theme::{self, Element, Themed};
+use crate::utils::{format_date, humanize_size, shorten_oid};
+
+/// Blobs larger than this are printed without highlighting
+const MAX_HIGHLIGHT_SIZE: usize = 1024 * 1024;
+
+/// Markers that start the signature block of a signed tag
+const SIGNATURE_MARKERS: &[&str] = &[
+    "-----BEGIN PGP SIGNATURE-----",
+    "-----BEGIN SSH SIGNATURE-----",
+    "-----BEGIN SIGNED MESSAGE-----",
+];
+
+/// Execute the show command
+pub async fn execute(args: &ShowArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let spec = args.object.as_deref().unwrap_or("HEAD");
+    if args.tree {
+        return browse_tree(rgit, spec, config);
+    }
+
+    match rgit.resolve_revision(spec)? {
+        Revision::Single(object) => show_object(&rgit.repo, &object, spec, args, config),
+        revision => {
+            // Ranges show every commit they select, newest first
+            let mut revwalk = rgit.repo.revwalk()?;
+            revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+            revision.push_to(&rgit.repo, &mut revwalk)?;
+            for oid in revwalk {
+                show_commit(&rgit.repo, &rgit.repo.find_commit(oid?)?, args, config)?;
+                println!();
+            }
+            Ok(())
+        }
+    }
+}
+
+fn show_object(repo: &Repository, object: &Object, spec: &str, args: &ShowArgs, config: &Config) -> Result<()> {
+    if let Some(tag) = object.as_tag() {
+        show_tag(repo, tag)?;
+        // Like git, follow the tag to what it points at
+        let target = tag.target()?;
+        return show_object(repo, &target, spec, args, config);
+    }
+    if let Some(commit) = object.as_commit() {
+        return show_commit(repo, commit, args, config);
+    }
+    if let Some(tree) = object.as_tree() {
+        return show_tree(repo, tree, spec);
+    }
+    if let Some(blob) = object.as_blob() {
+        return show_blob(blob, blob_path(spec), args, config);
+    }
+
+    Err(RgitError::InvalidObjectId(format!("{} is not a commit, tag, tree or blob", spec)).into())
+}
+
+// =============================================================================
+// Commits
+// =============================================================================
+
+fn show_commit(repo: &Repository, commit: &Commit, args: &ShowArgs, config: &Config) -> Result<()> {
+    let decoration = collect_decorations(repo)?
+        .remove(&commit.id())
+        .map(|names| format!(" ({})", names.join(", ")).yellow().to_string())
+        .unwrap_or_default();
+
+    for line in commit_lines(commit, &decoration) {
+        println!("{}", line);
+    }
+
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    if commit.parent_count() > 1 {
+        println!("{}", format!("Merge of {} parents; changes shown against the first parent", commit.parent_count()).dimmed());
+        println!();
+    }
+
+    let mut options = content_options(&args.content).build();
+    let mut diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut options))?;
+    rename_detection(&args.renames, config).apply(&mut diff)?;
+
+    if args.name_only {
+        for delta in diff.deltas() {
+            if let Some(path) = delta.new_file().path().or_else(|| delta.old_file().path()) {
+                println!("{}", path.display());
+            }
+        }
+    } else if args.stat {
+        let stats = diff.stats()?.to_buf(DiffStatsFormat::FULL, 80)?;
+        print!("{}", stats.as_str().unwrap_or(""));
+    } else {
+        print_patch(repo, &diff, config, args.content.function_context)?;
+    }
+
+    Ok(())
+}
+
+/// Print a diff as a colored unified patch. Binary files get a summary of
+/// their size and image dimensions instead of "Binary files differ", and
+/// files whose only changes were ignored (e.g. whitespace with `-w`) are left
+/// out. With `function_context` the diff must have whole-file context, which
+/// is cut down to the functions containing changes.
+pub fn print_patch(repo: &Repository, diff: &git2::Diff, config: &Config, function_context: bool) -> Result<()> {
+    let preview = PreviewProtocol::from_setting(&config.diff.image_preview);
+    let mut stdout = std::io::stdout().lock();
+    let mut current: Option<FilePatch> = None;
+
+    diff.print(DiffFormat::Patch, |delta, _hunk, line| {
+        let content = String::from_utf8_lossy(line.content()).into_owned();
+        match line.origin() {
+            'F' => match current.replace(FilePatch::new(&delta, content)) {
+                Some(file) => file.write(&mut stdout, function_context).is_ok(),
+                None => true,
+            },
+            origin => {
+                if let Some(file) = current.as_mut() {
+                    let text = if origin == 'B' { binary_summary(repo, &delta, preview) } else { content };
+                    file.lines.push((origin, text));
+                }
+                true
+            }
+        }
+    })?;
+    if let Some(file) = current {
+        file.write(&mut stdout, function_context)?;
+    }
+    Ok(())
+}
+
+/// One file of a patch, held back until all of its lines are known
+struct FilePatch {
+    header: String,
+    /// `renamed: old -> new` for moved files
+    moved: Option<String>,
+    /// Only the content changed, so without lines there is nothing to show
+    content_only: bool,
+    /// Hunk headers and lines by their origin
+    lines: Vec<(char, String)>,
+}
+
+impl FilePatch {
+    fn new(delta: &DiffDelta, header: String) -> Self {
+        Self {
+            header,
+            moved: describe_move(delta),
+            content_only: delta.status() == Delta::Modified && delta.old_file().mode() == delta.new_file().mode(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn write(self, out: &mut impl Write, function_context: bool) -> std::io::Result<()> {
+        if self.content_only && self.lines.is_empty() {
+            return Ok(());
+        }
+        if let Some(moved) = &self.moved {
+            writeln!(out, "{}", moved.themed(Element::Warning))?;
+        }
+        write!(out, "{}", self.header.bold())?;
+
+        let lines = if function_context { function_context_hunks(&self.lines) } else { self.lines };
+        for (origin, content) in &lines {
+            let text = match origin {
+                '+' => format!("+{}", content).themed(Element::Added),
+                '-' => format!("-{}", content).themed(Element::Removed),
+                ' ' => format!(" {}", content).normal(),
+                'H' => content.themed(Element::Hunk),
+                // Binary summaries are already formatted
+                'B' => content.normal(),
+                _ => content.dimmed(),
+            };
+            write!(out, "{}", text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sizes and image dimensions of a changed binary file, and a preview of
+/// the new image when the terminal can draw one
+fn binary_summary(repo: &Repository, delta: &DiffDelta, preview: Option<PreviewProtocol>) -> String {
+    let old = diff_file_content(repo, &delta.old_file());
+    let new = diff_file_content(repo, &delta.new_file());
+    let path = delta.new_file().path().or_else(|| delta.old_file().path())
+        .map(|p| p.display().to_string())
+        .unwrap_or_default();
+
+    let mut text = format!("{} {}\n", decoration("📦").blue(), binary::summary(&path, old.as_deref(), new.as_deref()));
+    if let Some(image) = preview.zip(new.as_deref()).and_then(|(protocol, data)| protocol.render(data)) {
+        text.push_str(&image);
+    }
+    text
+}
+
+/// Content of one side of a delta, from the object database or, for files
+/// that were never hashed, the working tree
+fn diff_file_content(repo: &Repository, file: &DiffFile) -> Option<Vec<u8>> {
+    if !file.exists() {
+        return None;
+    }
+    if let Ok(blob) = repo.find_blob(file.id()) {
+        return Some(blob.content().to_vec());
+    }
+    std::fs::read(repo.workdir()?.join(file.path()?)).ok()
+}
+
+/// `renamed: old -> new` or `copied: old -> new` for a file that moved
+pub fn describe_move(delta: &DiffDelta) -> Option<String> {
+    let action = match delta.status() {
+        Delta::Renamed => "renamed",
+        Delta::Copied => "copied",
+        _ => return None,
+    };
+    let old = delta.old_file().path()?;
+    let new = delta.new_file().path()?;
+    Some(format!("{}: {} -> {}", action, old.display(), new.display()))
+}
+
+// =============================================================================
+// Tags
+// =============================================================================
+
+/// Result of verifying a tag signature
+#[derive(Debug, Clone, PartialEq)]
+enum SignatureStatus {
+    Unsigned,
+    Good(String),
+    Bad(String),
+    /// Signed, but no tool was available to check it
+    Unverified(String),
+}
+
+fn show_tag(repo: &Repository, tag: &Tag) -> Result<()> {
+    println!("{} {}", "tag".yellow(), tag.name().unwrap_or("").yellow().bold());
+    if let Some(tagger) = tag.tagger() {
+        println!("Tagger: {} <{}>", tagger.name().unwrap_or("Unknown"), tagger.email().unwrap_or(""));
+        println!("Date:   {}", format_date(tagger.when()));
+    }
+
+    let (body, signature) = split_signature(tag.message().unwrap_or(""));
+    println!();
+    for line in body.trim_end().lines() {
+        println!("    {}", line);
+    }
+    println!();
+
+    let status = match signature {
+        Some(_) => verify_tag(repo, tag.id()),
+        None => SignatureStatus::Unsigned,
+    };
+    match status {
+        SignatureStatus::Unsigned => ui_println!("{} {}", "🔓".dimmed(), "Unsigned tag".dimmed()),
+        SignatureStatus::Good(signer) => ui_println!("{} Good signature: {}", "🔏".green(), signer.green()),
+        SignatureStatus::Bad(reason) => ui_println!("{} Bad signature: {}", "❌".red(), reason.red()),
+        SignatureStatus::Unverified(reason) => ui_println!("{} Signed, not verified: {}", "⚠️".yellow(), reason),
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Split a tag message into its text and trailing signature block
+fn split_signature(message: &str) -> (&str, Option<&str>) {
+    SIGNATURE_MARKERS.iter()
+        .filter_map(|marker| message.find(marker))
+        .min()
+        .map(|start| (&message[..start], Some(&message[start..])))
+        .unwrap_or((message, None))
+}
+
+/// Verify a signed tag with `git verify-tag`, which honors gpg.program and gpg.ssh.allowedSignersFile
+fn verify_tag(repo: &Repository, id: Oid) -> SignatureStatus {
+    let output = match Command::new("git")
+        .arg("--git-dir")
+        .arg(repo.path())
+        .args(["verify-tag", &id.to_string()])
+        .output()
+    {
+        Ok(output) => output,
+        Err(_) => return SignatureStatus::Unverified("git is not installed".to_string()),
+    };
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<&str> = stderr.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+
+    if output.status.success() {
+        let signer = lines.iter()
+            .find(|line| line.contains("Good"))
+            .or(lines.last())
+            .map(|line| line.trim_start_matches("gpg: ").to_string())
+            .unwrap_or_else(|| "verified".to_string());
+        return SignatureStatus::Good(signer);
+    }
+
+    let reason = lines.last()
+        .map(|line| line.trim_start_matches("gpg: ").to_string())
+        .unwrap_or_else(|| "verification failed".to_string());
+    if lines.iter().any(|line| line.contains("BAD signature") || line.contains("Could not verify signature")) {
+        SignatureStatus::Bad(reason)
+    } else {
+        SignatureStatus::Unverified(reason)
+    }
+}
+
+// =============================================================================
+// Trees
+// =============================================================================
+
+/// One entry of a tree listing
+#[derive(Debug, Clone, PartialEq)]
+struct TreeRow {
+    mode: i32,
+    kind: Option<ObjectType>,
+    id: Oid,
+    /// Blob size in bytes
+    size: Option<usize>,
+    name: String,
+}
+
+fn tree_rows(repo: &Repository, tree: &Tree) -> Result<Vec<TreeRow>> {
+    let odb = repo.odb()?;
+    Ok(tree.iter()
+        .map(|entry| {
+            let kind = entry.kind();
+            let size = match kind {
+                Some(ObjectType::Blob) => odb.read_header(entry.id()).ok().map(|(size, _)| size),
+                _ => None,
+            };
+            TreeRow {
+                mode: entry.filemode(),
+                kind,
+                id: entry.id(),
+                size,
+                name: entry.name().unwrap_or("").to_string(),
+            }
+        })
+        .collect())
+}
+
+fn show_tree(repo: &Repository, tree: &Tree, spec: &str) -> Result<()> {
+    let rows = tree_rows(repo, tree)?;
+    println!("{} {} {}", "tree".yellow(), spec.yellow().bold(), format!("({} entries)", rows.len()).dimmed());
+    println!();
+
+    let mut total = 0;
+    for row in &rows {
+        let (kind, name) = match row.kind {
+            Some(ObjectType::Tree) => ("tree", format!("{}/", row.name).blue().bold()),
+            Some(ObjectType::Commit) => ("commit", format!("{} (submodule)", row.name).magenta()),
+            _ => ("blob", row.name.normal()),
+        };
+        let size = row.size.map(|s| humanize_size(s as u64)).unwrap_or_else(|| "-".to_string());
+        total += row.size.unwrap_or(0);
+
+        println!("{:06o} {:<6} {} {:>10}  {}",
+                row.mode, kind, shorten_oid(&row.id, 8).dimmed(), size, name);
+    }
+
+    println!();
+    ui_println!("{} {} in files at this level", "📦".blue(), humanize_size(total as u64));
+    Ok(())
+}
+
+// =============================================================================
+// Tree browser
+// =============================================================================
+
+/// Browse the files of a commit or tree: open directories, read files as
+/// they were then and compare them with the working tree
+fn browse_tree(rgit: &RgitCore, spec: &str, config: &Config) -> Result<()> {
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+    let Revision::Single(object) = rgit.resolve_revision(spec)? else {
+        return Err(RgitError::InvalidArgument("--tree needs a single commit or tree, not a range".to_string()).into());
+    };
+    let root = object.peel_to_tree()
+        .map_err(|_| RgitError::InvalidObjectId(format!("{} is not a commit or tree", spec)))?;
+    // `<rev>:<dir>` starts inside a directory
+    let base = blob_path(spec).unwrap_or("").trim_end_matches('/').to_string();
+
+    match object.peel_to_commit() {
+        Ok(commit) => ui_println!("{} Browsing {} {} {}", "🌳".green(), spec.yellow().bold(),
+                shorten_oid(&commit.id(), 8).dimmed(), commit.summary().unwrap_or("").dimmed()),
+        Err(_) => ui_println!("{} Browsing {}", "🌳".green(), spec.yellow().bold()),
+    }
+
+    let repo = &rgit.repo;
+    let mut dirs: Vec<String> = Vec::new();
+    loop {
+        let dir = dirs.join("/");
+        let tree = if dir.is_empty() {
+            root.clone()
+        } else {
+            root.get_path(Path::new(&dir))?.to_object(repo)?.peel_to_tree()?
+        };
+        let rows = browsing_order(tree_rows(repo, &tree)?);
+
+        let mut options: Vec<String> = Vec::new();
+        if !dirs.is_empty() {
+            options.push("⬆️  ..".to_string());
+        }
+        options.extend(rows.iter().map(|row| match row.kind {
+            Some(ObjectType::Tree) => format!("📁 {}/", row.name),
+            Some(ObjectType::Commit) => format!("📦 {} (submodule at {})", row.name, shorten_oid(&row.id, 8)),
+            // Plain text, so fuzzy search doesn't match color codes
+            _ => format!("📄 {} ({})", row.name, row.size.map(|size| humanize_size(size as u64)).unwrap_or_default()),
+        }));
+        options.push("✖  Quit".to_string());
+
+        let location = join_path(&base, &dir);
+        let choice = InteractivePrompt::new()
+            .with_message(format!("{}/{}", spec.split(':').next().unwrap_or(spec), location))
+            .with_options(&options)
+            .fuzzy_search()
+            .select()?;
+
+        let choice = match (dirs.is_empty(), choice) {
+            (false, 0) => {
+                dirs.pop();
+                continue;
+            }
+            (false, choice) => choice - 1,
+            (true, choice) => choice,
+        };
+        let Some(row) = rows.get(choice) else {
+            return Ok(());
+        };
+        match row.kind {
+            Some(ObjectType::Tree) => dirs.push(row.name.clone()),
+            Some(ObjectType::Blob) => browse_file(repo, &repo.find_blob(row.id)?, &join_path(&location, &row.name), config)?,
+            _ => ui_println!("{} {} is a submodule; browse it from inside its directory", "ℹ️".blue(), row.name),
+        }
+    }
+}
+
+/// Directories first, then files, each by name
+fn browsing_order(mut rows: Vec<TreeRow>) -> Vec<TreeRow> {
+    rows.sort_by_key(|row| (row.kind != Some(ObjectType::Tree), row.name.clone()));
+    rows
+}
+
+fn join_path(dir: &str, name: &str) -> String {
+    if dir.is_empty() { name.to_string() } else { format!("{}/{}", dir, name) }
+}
+
+/// Offer to view a file or compare it with the working tree until the user goes back
+fn browse_file(repo: &Repository, blob: &Blob, path: &str, config: &Config) -> Result<()> {
+    let mut keys = vec![('v', "view")];
+    if repo.workdir().is_some() {
+        keys.push(('d', "diff with working tree"));
+    }
+    keys.push(('b', "back"));
+
+    loop {
+        match InteractivePrompt::new().with_message(path).keypress(&keys)? {
+            'v' => {
+                let _pager = pager::start(config);
+                print_blob_text(blob, path, config);
+            }
+            'd' => {
+                let _pager = pager::start(config);
+                print_working_tree_diff(repo, blob, path)?;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
+
+fn print_blob_text(blob: &Blob, path: &str, config: &Config) {
+    if blob.is_binary() {
+        ui_println!("{} {}", "📦".blue(), binary::summary(path, None, Some(blob.content())));
+        return;
+    }
+    let text = String::from_utf8_lossy(blob.content());
+    let highlighted = (config.ui.colors && blob.size() <= MAX_HIGHLIGHT_SIZE)
+        .then(|| highlight(&text, path, theme::current().name))
+        .flatten();
+    print!("{}", highlighted.as_deref().unwrap_or(&text));
+    if !text.ends_with('\n') {
+        println!();
+    }
+}
+
+/// Patch from the file at the browsed revision to the file in the working tree
+fn print_working_tree_diff(repo: &Repository, blob: &Blob, path: &str) -> Result<()> {
+    let Some(file) = repo.workdir().map(|workdir| workdir.join(path)).filter(|file| file.is_file()) else {
+        ui_println!("{} {} is not in the working tree", "ℹ️".blue(), path);
+        return Ok(());
+    };
+    let current = std::fs::read(file)?;
+    if current == blob.content() {
+        ui_println!("{} {} is the same in the working tree", "✅".green(), path);
+        return Ok(());
+    }
+    if blob.is_binary() || current.contains(&0) {
+        ui_println!("{} {}", "📦".blue(), binary::summary(path, Some(blob.content()), Some(&current)));
+        return Ok(());
+    }
+
+    let mut patch = Patch::from_blob_and_buffer(blob, Some(Path::new(path)), &current, Some(Path::new(path)), None)?;
+    let mut file_patch: Option<FilePatch> = None;
+    patch.print(&mut |delta, _hunk, line| {
+        let content = String::from_utf8_lossy(line.content()).into_owned();
+        match (line.origin(), file_patch.as_mut()) {
+            ('F', _) => file_patch = Some(FilePatch::new(&delta, content)),
+            (origin, Some(file)) => file.lines.push((origin, content)),
+            _ => {}
+        }
+        true
+    })?;
+    if let Some(file_patch) = file_patch {
+        file_patch.write(&mut std::io::stdout().lock(), false)?;
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Blobs
+// =============================================================================
+
+/// Path part of a `<rev>:<path>` spec, used to pick a syntax
+fn blob_path(spec: &str) -> Option<&str> {
+    // Skip over reflog selectors like `main@{2024-01-01 10:00}`
+    let start = spec.rfind('}').map_or(0, |i| i + 1);
+    spec[start..].find(':')
+        .map(|i| &spec[start + i + 1..])
+        .filter(|path| !path.is_empty())
+}
+
+fn show_blob(blob: &Blob, path: Option<&str>, args: &ShowArgs, config: &Config) -> Result<()> {
+    let content = blob.content();
+    let terminal = atty::is(atty::Stream::Stdout);
+
+    // Pipes get the exact bytes
+    if !terminal {
+        std::io::stdout().write_all(content)?;
+        return Ok(());
+    }
+
+    if blob.is_binary() {
+        ui_println!("{} Binary blob {} ({})",
+                "📦".blue(), path.unwrap_or(&shorten_oid(&blob.id(), 8)).cyan(), humanize_size(content.len() as u64));
+        ui_println!("{} Redirect the output to save it: {}", "💡".blue(),
+                format!("rgit show {} > file", args.object.as_deref().unwrap_or("<blob>")).cyan());
+        return Ok(());
+    }
+
+    let text = String::from_utf8_lossy(content);
+    let highlighted = (!args.no_highlight && config.ui.colors && content.len() <= MAX_HIGHLIGHT_SIZE)
+        .then(|| path.and_then(|p| highlight(&text, p, theme::current().name)))
+        .flatten();
+
+    match highlighted {
+        Some(output) => print!("{}", output),
+        None => print!("{}", text),
+    }
+    if !text.ends_with('\n') {
+        println!();
+    }
+    Ok(())
+}
+
+/// Highlight text for the terminal; None when the file type is not recognized
+/// or the theme is monochrome
+fn highlight(text: &str, path: &str, theme: &str) -> Option<String> {
+    let theme = match theme {
+        "monochrome" => return None,
+        "light" => "InspiredGitHub",
+        "solarized" => "Solarized (dark)",
+        _ => "base16-ocean.dark",
+    };
+    let syntaxes = SyntaxSet::load_defaults_newlines();
+    let path = Path::new(path);
+    let syntax = path.extension()
+        .and_then(|ext| syntaxes.find_syntax_by_extension(&ext.to_string_lossy()))
+        .or_else(|| path.file_name().and_then(|name| syntaxes.find_syntax_by_extension(&name.to_string_lossy())))
+        .or_else(|| text.lines().next().and_then(|line| syntaxes.find_syntax_by_first_line(line)))?;
+
+    let themes = ThemeSet::load_defaults();
+    let theme = &themes.themes[theme];
+    let mut highlighter = HighlightLines::new(syntax, theme);
+
+    let mut output = String::with_capacity(text.len() * 2);
+    for line in LinesWithEndings::from(text) {
+        let ranges = highlighter.highlight_line(line, &syntaxes).ok()?;
+        output.push_str(&as_24_bit_terminal_escaped(&ranges, false));
+    }
+    output.push_str("\x1b[0m");
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_blob_path() {
+        assert_eq!(blob_path("HEAD:src/main.rs"), Some("src/main.rs"));
+        assert_eq!(blob_path(":README.md"), Some("README.md"));
+        assert_eq!(blob_path("main@{2024-01-01 10:00:00}:Cargo.toml"), Some("Cargo.toml"));
+        assert_eq!(blob_path("HEAD~2"), None);
+        assert_eq!(blob_path("HEAD:"), None);
+    }
+
+    #[test]
+    fn test_split_signature() {
+        let message = "Release 1.0\n\nNotes\n-----BEGIN PGP SIGNATURE-----\nabc\n-----END PGP SIGNATURE-----\n";
+        let (body, signature) = split_signature(message);
+        assert_eq!(body, "Release 1.0\n\nNotes\n");
+        assert!(signature.unwrap().starts_with("-----BEGIN PGP SIGNATURE-----"));
+
+        assert_eq!(split_signature("plain tag\n"), ("plain tag\n", None));
+    }
+
+    #[test]
+    fn test_tree_rows_include_sizes() {
+        let repo = TestRepo::new();
+        repo.commit_file("src/lib.rs", "pub fn a() {}\n", "lib");
+        repo.commit_file("README.md", "hello\n", "readme");
+        let tree = repo.repo().head().unwrap().peel_to_tree().unwrap();
+
+        let rows = tree_rows(repo.repo(), &tree).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].name, "README.md");
+        assert_eq!(rows[0].size, Some(6));
+        assert_eq!(rows[1].name, "src");
+        assert_eq!(rows[1].kind, Some(ObjectType::Tree));
+        assert_eq!(rows[1].size, None);
+
+        let names: Vec<String> = browsing_order(rows).into_iter().map(|row| row.name).collect();
+        assert_eq!(names, vec!["src", "README.md"]);
+    }
+
+    #[test]
+    fn test_highlight_known_and_unknown_types() {
+        let highlighted = highlight("fn main() {}\n", "src/main.rs", "dark").unwrap();
+        assert!(highlighted.contains("\x1b["));
+        assert_eq!(console::strip_ansi_codes(&highlighted), "fn main() {}\n");
+
+        assert!(highlight("just words\n", "notes.unknownext", "dark").is_none());
+    }
+}
@@ -0,0 +1,937 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, ApplyOptions, BranchType, Commit, Delta, Diff, DiffDelta, DiffStatsFormat,
+    ErrorCode, Index, Oid, Patch, Pathspec, PathspecFlags, Repository, Signature, StashApplyOptions,
+    StashFlags, StashSaveOptions, Status, StatusOptions, Tree,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{StashApplyArgs, StashArgs, StashCommands, StashPushArgs};
+use crate::commands::cherry_pick::conflicted_paths;
+use crate::config::Config;
+use crate::core::{DiffOptionsBuilder, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::{FileItem, FileSelector, InteractivePrompt};
+use crate::timings::{self, Phase};
+use crate::utils::{format_time_ago, shorten_oid};
+use crate::validation::{validate_branch_name, validate_pathspec};
+
+/// Execute the stash command
+pub async fn execute(args: &StashArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    match &args.action {
+        None => push(rgit, &StashPushArgs::default(), config),
+        Some(StashCommands::Push(push_args)) => push(rgit, push_args, config),
+        Some(StashCommands::Save { message, include_untracked }) => {
+            let push_args = StashPushArgs {
+                message: message.clone(),
+                include_untracked: *include_untracked,
+                ..Default::default()
+            };
+            push(rgit, &push_args, config)
+        }
+        Some(StashCommands::List) => list(&rgit.repo),
+        Some(StashCommands::Show { index }) => show(&rgit.repo, index.unwrap_or(0), config),
+        Some(StashCommands::Apply(apply_args)) => restore(rgit, apply_args, false, config),
+        Some(StashCommands::Pop(apply_args)) => restore(rgit, apply_args, true, config),
+        Some(StashCommands::Branch { name, index }) => branch(&rgit.repo, name, index.unwrap_or(0)),
+        Some(StashCommands::Drop { index }) => drop(&rgit.repo, index.unwrap_or(0), config),
+        Some(StashCommands::Clear) => clear(&rgit.repo, config),
+    }
+}
+
+// =============================================================================
+// Saving
+// =============================================================================
+
+/// Which files beyond tracked changes go into a stash
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Untracked {
+    Exclude,
+    Include,
+    /// Untracked and ignored files
+    All,
+}
+
+/// Everything that shapes a new stash entry
+#[derive(Debug, Clone)]
+pub struct StashRequest {
+    pub message: Option<String>,
+    /// Repository-relative pathspecs; empty means every change
+    pub paths: Vec<String>,
+    pub keep_index: bool,
+    pub untracked: Untracked,
+}
+
+impl StashRequest {
+    fn flags(&self) -> StashFlags {
+        let mut flags = StashFlags::DEFAULT;
+        if self.keep_index {
+            flags |= StashFlags::KEEP_INDEX;
+        }
+        match self.untracked {
+            Untracked::Exclude => {}
+            Untracked::Include => flags |= StashFlags::INCLUDE_UNTRACKED,
+            Untracked::All => flags |= StashFlags::INCLUDE_UNTRACKED | StashFlags::INCLUDE_IGNORED,
+        }
+        flags
+    }
+}
+
+fn push(rgit: &RgitCore, args: &StashPushArgs, config: &Config) -> Result<()> {
+    let untracked = if args.all {
+        Untracked::All
+    } else if args.include_untracked {
+        Untracked::Include
+    } else {
+        Untracked::Exclude
+    };
+
+    let paths = if args.interactive {
+        // Without asking, nothing is picked
+        let chosen = if config.ui.assume_yes {
+            Vec::new()
+        } else if config.is_interactive() {
+            choose_paths(&rgit.repo, untracked)?
+        } else {
+            return Err(RgitError::NonInteractiveEnvironment.into());
+        };
+        if chosen.is_empty() {
+            ui_println!("{} Nothing selected; nothing stashed", "💡".blue());
+            return Ok(());
+        }
+        chosen
+    } else {
+        let cwd = std::env::current_dir()?;
+        args.paths.iter()
+            .map(|spec| validate_pathspec(&rgit.repo_path, &cwd, spec))
+            .collect::<std::result::Result<Vec<_>, _>>()?
+    };
+
+    let request = StashRequest { message: args.message.clone(), paths, keep_index: args.keep_index, untracked };
+    let oid = stash_push(&rgit.repo, &request, &rgit.get_signature()?)?;
+    let stash = rgit.repo.find_commit(oid)?;
+
+    ui_println!("{} Saved {} {}", "📦".blue(), "stash@{0}".yellow(), stash.summary().unwrap_or(""));
+    if !request.paths.is_empty() {
+        println!("   {} {}", "only:".dimmed(), request.paths.join(", "));
+    }
+    if request.keep_index {
+        println!("   {}", "staged changes were kept in place".dimmed());
+    }
+    ui_println!("{} Bring the changes back with {}", "💡".blue(), "rgit stash pop".cyan());
+    Ok(())
+}
+
+/// Save a stash entry for the request, returning the new stash commit
+pub fn stash_push(repo: &Repository, request: &StashRequest, signature: &Signature) -> Result<Oid> {
+    let mut stash_repo = Repository::open(repo.path())?;
+    let saved = if request.paths.is_empty() {
+        stash_repo.stash_save2(signature, request.message.as_deref(), Some(request.flags()))
+    } else {
+        // libgit2 records only the given paths but then resets the whole
+        // working tree, so keep everything and put back just those paths
+        let mut options = StashSaveOptions::new(signature.to_owned());
+        options.flags(Some(request.flags() | StashFlags::KEEP_ALL));
+        for path in &request.paths {
+            options.pathspec(path.as_str());
+        }
+        stash_repo.stash_save_ext(Some(&mut options))
+    };
+
+    let oid = saved.map_err(|e| -> anyhow::Error {
+        if e.code() == ErrorCode::NotFound { RgitError::NothingToStash.into() } else { e.into() }
+    })?;
+    if request.paths.is_empty() {
+        return Ok(oid);
+    }
+
+    let rebuilt = rebuild_path_limited(&stash_repo, &stash_repo.find_commit(oid)?, request)?;
+    let Some(oid) = rebuilt else {
+        stash_repo.stash_drop(0)?;
+        return Err(RgitError::NothingToStash.into());
+    };
+    reset_stashed_paths(&stash_repo, &stash_repo.find_commit(oid)?, request)?;
+    Ok(oid)
+}
+
+/// libgit2 records path-limited stashes differently from git: untracked files
+/// under the paths land in the working-tree commit, every untracked file lands
+/// in the untracked commit, and the options have no message field. Replace
+/// `stash@{0}` with the entry git would have made; None if nothing is left.
+fn rebuild_path_limited(repo: &Repository, stash: &Commit, request: &StashRequest) -> Result<Option<Oid>> {
+    let head_tree = stash.parent(0)?.tree()?;
+    let index_tree = stash.parent(1)?.tree()?;
+    let tree = tree_without(repo, &stash.tree()?, |path| index_tree.get_path(path).is_err())?;
+
+    let mut parents = vec![stash.parent(0)?, stash.parent(1)?];
+    if let Ok(untracked) = stash.parent(2) {
+        let pathspec = Pathspec::new(request.paths.iter())?;
+        let kept = tree_without(repo, &untracked.tree()?, |path| !pathspec.matches_path(path, PathspecFlags::DEFAULT))?;
+        if !kept.is_empty() {
+            let message = untracked.message().unwrap_or("");
+            let id = repo.commit(None, &untracked.author(), &untracked.committer(), message, &kept, &[])?;
+            parents.push(repo.find_commit(id)?);
+        }
+    }
+    if parents.len() == 2 && tree.id() == head_tree.id() && index_tree.id() == head_tree.id() {
+        return Ok(None);
+    }
+
+    let message = match &request.message {
+        Some(message) => {
+            let head = repo.head()?;
+            let branch = if head.is_branch() { head.shorthand().unwrap_or("HEAD") } else { "(no branch)" };
+            format!("On {}: {}", branch, message)
+        }
+        None => stash.message().unwrap_or("").to_string(),
+    };
+    let parents: Vec<&Commit> = parents.iter().collect();
+    let rebuilt = repo.commit(None, &stash.author(), &stash.committer(), &message, &tree, &parents)?;
+    if rebuilt == stash.id() {
+        return Ok(Some(rebuilt));
+    }
+    repo.reference("refs/stash", rebuilt, true, &message)?;
+
+    // The reference move logged the new entry; forget the original
+    let mut reflog = repo.reflog("refs/stash")?;
+    reflog.remove(1, true)?;
+    reflog.write()?;
+    Ok(Some(rebuilt))
+}
+
+/// A copy of `tree` without the files `drop` picks
+fn tree_without<'r>(repo: &'r Repository, tree: &Tree, drop: impl Fn(&Path) -> bool) -> Result<Tree<'r>> {
+    let mut index = Index::new()?;
+    index.read_tree(tree)?;
+    let dropped: Vec<PathBuf> = index.iter()
+        .map(|entry| PathBuf::from(String::from_utf8_lossy(&entry.path).to_string()))
+        .filter(|path| drop(path))
+        .collect();
+    for path in &dropped {
+        index.remove_path(path)?;
+    }
+    Ok(repo.find_tree(index.write_tree_to(repo)?)?)
+}
+
+/// Undo the stashed changes under the request's paths: back to HEAD, or to
+/// the index with `keep_index`, removing stashed untracked files
+fn reset_stashed_paths(repo: &Repository, stash: &Commit, request: &StashRequest) -> Result<()> {
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    for path in &request.paths {
+        checkout.path(path.as_str());
+    }
+    if request.keep_index {
+        repo.checkout_index(None, Some(&mut checkout))?;
+    } else {
+        repo.checkout_tree(repo.head()?.peel_to_tree()?.as_object(), Some(&mut checkout))?;
+    }
+
+    if let Some(untracked) = untracked_files(stash)? {
+        let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+        for path in untracked {
+            fs::remove_file(workdir.join(path))?;
+        }
+    }
+    Ok(())
+}
+
+/// Offer the changed files for stashing and return the chosen ones
+fn choose_paths(repo: &Repository, untracked: Untracked) -> Result<Vec<String>> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(untracked != Untracked::Exclude)
+        .recurse_untracked_dirs(true)
+        .include_ignored(untracked == Untracked::All);
+
+    let files: Vec<FileItem> = timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?
+        .iter()
+        .filter(|entry| entry.status() != Status::CURRENT)
+        .filter_map(|entry| {
+            let status = entry.status();
+            let label = if status.is_wt_new() || status.is_index_new() {
+                "new"
+            } else if status.is_wt_deleted() || status.is_index_deleted() {
+                "deleted"
+            } else if status.is_ignored() {
+                "ignored"
+            } else {
+                "modified"
+            };
+            entry.path().map(|path| FileItem {
+                path: PathBuf::from(path),
+                status: label.to_string(),
+                size: None,
+                selected: false,
+            })
+        })
+        .collect();
+    if files.is_empty() {
+        return Err(RgitError::NothingToStash.into());
+    }
+
+    let chosen = FileSelector::new()
+        .with_files(files)
+        .with_prompt("Select files to stash")
+        .select()?;
+    Ok(chosen.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+// =============================================================================
+// Inspecting
+// =============================================================================
+
+/// A stash entry as listed by `stash list`
+#[derive(Debug, Clone)]
+pub struct StashEntry {
+    pub index: usize,
+    pub message: String,
+    pub oid: Oid,
+}
+
+/// All stash entries, newest first
+pub fn stash_entries(repo: &Repository) -> Result<Vec<StashEntry>> {
+    let mut stash_repo = Repository::open(repo.path())?;
+    let mut entries = Vec::new();
+    stash_repo.stash_foreach(|index, message, oid| {
+        entries.push(StashEntry { index, message: message.to_string(), oid: *oid });
+        true
+    })?;
+    Ok(entries)
+}
+
+fn find_entry(repo: &Repository, index: usize) -> Result<StashEntry> {
+    let entries = stash_entries(repo)?;
+    if entries.is_empty() {
+        return Err(RgitError::NoStashEntries.into());
+    }
+    entries.into_iter()
+        .find(|entry| entry.index == index)
+        .ok_or_else(|| RgitError::StashIndexOutOfRange(index).into())
+}
+
+fn list(repo: &Repository) -> Result<()> {
+    let entries = stash_entries(repo)?;
+    if entries.is_empty() {
+        ui_println!("{} No stash entries", "📭".dimmed());
+        return Ok(());
+    }
+    for entry in entries {
+        let when = repo.find_commit(entry.oid)
+            .map(|c| format_time_ago(c.time()))
+            .unwrap_or_default();
+        println!("{} {} {}",
+                format!("stash@{{{}}}", entry.index).yellow(),
+                entry.message,
+                format!("({})", when).dimmed());
+    }
+    Ok(())
+}
+
+fn show(repo: &Repository, index: usize, config: &Config) -> Result<()> {
+    let entry = find_entry(repo, index)?;
+    let stash = repo.find_commit(entry.oid)?;
+    println!("{} {} {}", format!("stash@{{{}}}", index).yellow(), entry.message, format_time_ago(stash.time()).dimmed());
+
+    let diff = repo.diff_tree_to_tree(Some(&stash.parent(0)?.tree()?), Some(&stash.tree()?), None)?;
+    let stats = diff.stats()?.to_buf(DiffStatsFormat::FULL, config.terminal_width())?;
+    print!("{}", stats.as_str().unwrap_or(""));
+
+    if let Some(untracked) = untracked_files(&stash)? {
+        ui_println!("{} {} untracked file{}: {}",
+                "➕".green(),
+                untracked.len(),
+                if untracked.len() == 1 { "" } else { "s" },
+                untracked.join(", "));
+    }
+    Ok(())
+}
+
+/// Paths kept in the stash's untracked-files commit, if it has one
+fn untracked_files(stash: &Commit) -> Result<Option<Vec<String>>> {
+    let Ok(untracked) = stash.parent(2) else { return Ok(None) };
+    let mut paths = Vec::new();
+    untracked.tree()?.walk(git2::TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(git2::ObjectType::Blob) {
+            paths.push(format!("{}{}", dir, entry.name().unwrap_or("")));
+        }
+        git2::TreeWalkResult::Ok
+    })?;
+    Ok(Some(paths))
+}
+
+// =============================================================================
+// Restoring and removing
+// =============================================================================
+
+/// Restore a whole stash entry, or only chosen files or hunks of it
+fn restore(rgit: &RgitCore, args: &StashApplyArgs, pop: bool, config: &Config) -> Result<()> {
+    let index = args.index.unwrap_or(0);
+    if !args.interactive && !args.patch && args.paths.is_empty() {
+        return apply(&rgit.repo, index, pop);
+    }
+    if (args.interactive || args.patch) && !config.is_interactive() {
+        // Without asking, nothing is picked
+        if config.ui.assume_yes {
+            ui_println!("{} Nothing chosen; {} is unchanged", "💡".blue(), format!("stash@{{{}}}", index).yellow());
+            return Ok(());
+        }
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+
+    let repo = &rgit.repo;
+    let entry = find_entry(repo, index)?;
+    let stash = repo.find_commit(entry.oid)?;
+    let files = stashed_files(repo, &stash)?;
+
+    let chosen: Vec<&StashedFile> = if args.interactive {
+        let items = files.iter().map(|file| FileItem {
+            path: PathBuf::from(&file.path),
+            status: file.status.to_string(),
+            size: None,
+            selected: false,
+        }).collect();
+        let picked = FileSelector::new()
+            .with_files(items)
+            .with_prompt("Select files to restore")
+            .select()?;
+        files.iter().filter(|file| picked.contains(&PathBuf::from(&file.path))).collect()
+    } else if args.paths.is_empty() {
+        files.iter().collect()
+    } else {
+        let cwd = std::env::current_dir()?;
+        let specs = args.paths.iter()
+            .map(|spec| validate_pathspec(&rgit.repo_path, &cwd, spec))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let pathspec = Pathspec::new(specs.iter())?;
+        files.iter().filter(|file| pathspec.matches_path(Path::new(&file.path), PathspecFlags::DEFAULT)).collect()
+    };
+    if chosen.is_empty() {
+        ui_println!("{} Nothing chosen; {} is unchanged", "💡".blue(), format!("stash@{{{}}}", index).yellow());
+        return Ok(());
+    }
+
+    let diff = stash_diff(repo, &stash)?;
+    let mut picks = Picks::new();
+    for file in &chosen {
+        let pick = if args.patch && !file.untracked {
+            choose_hunks(&diff, &file.path)?
+        } else {
+            Pick::Whole
+        };
+        picks.insert(file.path.clone(), pick);
+    }
+
+    let restored = restore_picks(repo, &stash, &diff, &picks)?;
+    ui_println!("{} Restored {} file{} from {}",
+            "✅".green(),
+            restored,
+            if restored == 1 { "" } else { "s" },
+            format!("stash@{{{}}}", index).yellow());
+
+    if pop {
+        match keep_remainder(repo, &stash, index, &diff, &picks)? {
+            Some(_) => ui_println!("{} The rest stays stashed as {}", "📦".blue(), "stash@{0}".yellow()),
+            None => ui_println!("{} Nothing left in the stash; dropped it", "🗑️".red()),
+        }
+    }
+    Ok(())
+}
+
+/// A file with changes in a stash entry
+#[derive(Debug, Clone)]
+struct StashedFile {
+    path: String,
+    status: &'static str,
+    /// Kept in the stash's untracked-files commit
+    untracked: bool,
+}
+
+/// Working-tree changes recorded by a stash, relative to where it was made
+fn stash_diff<'r>(repo: &'r Repository, stash: &Commit) -> Result<Diff<'r>> {
+    let mut options = DiffOptionsBuilder::new().show_binary().build();
+    Ok(repo.diff_tree_to_tree(Some(&stash.parent(0)?.tree()?), Some(&stash.tree()?), Some(&mut options))?)
+}
+
+fn stashed_files(repo: &Repository, stash: &Commit) -> Result<Vec<StashedFile>> {
+    let mut files: Vec<StashedFile> = stash_diff(repo, stash)?.deltas()
+        .filter_map(|delta| {
+            let status = match delta.status() {
+                Delta::Added => "new",
+                Delta::Deleted => "deleted",
+                _ => "modified",
+            };
+            delta.new_file().path().or_else(|| delta.old_file().path())
+                .map(|path| StashedFile { path: path.to_string_lossy().to_string(), status, untracked: false })
+        })
+        .collect();
+    for path in untracked_files(stash)?.unwrap_or_default() {
+        files.push(StashedFile { path, status: "untracked", untracked: true });
+    }
+    Ok(files)
+}
+
+/// What to take from a stashed file
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pick {
+    Whole,
+    /// One flag per hunk, in diff order
+    Hunks(Vec<bool>),
+}
+
+type Picks = HashMap<String, Pick>;
+
+/// Decides, delta by delta and hunk by hunk, what a patch application takes:
+/// the picked changes, or (`rest`) everything that was not picked
+struct PickFilter<'a> {
+    picks: &'a Picks,
+    rest: bool,
+    current: RefCell<Option<Vec<bool>>>,
+    hunk: Cell<usize>,
+}
+
+impl<'a> PickFilter<'a> {
+    fn new(picks: &'a Picks, rest: bool) -> Self {
+        Self { picks, rest, current: RefCell::new(None), hunk: Cell::new(0) }
+    }
+
+    fn delta(&self, delta: Option<DiffDelta>) -> bool {
+        let path = delta.as_ref()
+            .and_then(|d| d.new_file().path().or_else(|| d.old_file().path()))
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.hunk.set(0);
+        let (take, hunks) = match (self.picks.get(&path), self.rest) {
+            (None, false) => (false, None),
+            (None, true) => (true, None),
+            (Some(Pick::Whole), rest) => (!rest, None),
+            (Some(Pick::Hunks(flags)), false) => (flags.iter().any(|f| *f), Some(flags.clone())),
+            (Some(Pick::Hunks(flags)), true) => {
+                let rest: Vec<bool> = flags.iter().map(|f| !f).collect();
+                (rest.iter().any(|f| *f), Some(rest))
+            }
+        };
+        *self.current.borrow_mut() = hunks;
+        take
+    }
+
+    fn hunk(&self) -> bool {
+        let index = self.hunk.get();
+        self.hunk.set(index + 1);
+        self.current.borrow().as_ref().map(|flags| flags.get(index).copied().unwrap_or(false)).unwrap_or(true)
+    }
+}
+
+/// Ask about each hunk of one stashed file
+fn choose_hunks(diff: &Diff, path: &str) -> Result<Pick> {
+    let position = diff.deltas().position(|d| {
+        d.new_file().path().or_else(|| d.old_file().path()).map(|p| p.to_string_lossy() == path).unwrap_or(false)
+    });
+    let Some(patch) = position.map(|i| Patch::from_diff(diff, i)).transpose()?.flatten() else {
+        return Ok(Pick::Whole);
+    };
+    if patch.num_hunks() <= 1 && patch.delta().status() != Delta::Modified {
+        return Ok(Pick::Whole);
+    }
+
+    const CHOICES: [&str; 4] = [
+        "Restore this hunk",
+        "Keep this hunk stashed",
+        "Restore this and the rest of the file",
+        "Keep the rest of the file stashed",
+    ];
+    let total = patch.num_hunks();
+    let mut flags = vec![false; total];
+    let mut i = 0;
+    while i < total {
+        let (hunk, lines) = patch.hunk(i)?;
+        println!();
+        ui_println!("{} {} ({}/{})", "📄".blue(), path.yellow(), i + 1, total);
+        println!("{}", String::from_utf8_lossy(hunk.header()).trim_end().cyan());
+        for l in 0..lines {
+            let line = patch.line_in_hunk(i, l)?;
+            let text = format!("{}{}", line.origin(), String::from_utf8_lossy(line.content()).trim_end_matches('\n'));
+            match line.origin() {
+                '+' => println!("{}", text.green()),
+                '-' => println!("{}", text.red()),
+                _ => println!("{}", text.dimmed()),
+            }
+        }
+
+        let choice = InteractivePrompt::new()
+            .with_message("Restore this hunk?")
+            .with_options(&CHOICES)
+            .with_default(0)
+            .select()?;
+        match choice {
+            0 => flags[i] = true,
+            1 => {}
+            2 => flags[i..].iter_mut().for_each(|f| *f = true),
+            _ => {}
+        }
+        if choice >= 2 {
+            break;
+        }
+        i += 1;
+    }
+    Ok(if flags.iter().all(|f| *f) { Pick::Whole } else { Pick::Hunks(flags) })
+}
+
+/// Write the picked changes into the working tree; returns the file count
+fn restore_picks(repo: &Repository, stash: &Commit, diff: &Diff, picks: &Picks) -> Result<usize> {
+    let filter = PickFilter::new(picks, false);
+    let mut options = ApplyOptions::new();
+    options.delta_callback(|delta| filter.delta(delta));
+    options.hunk_callback(|_| filter.hunk());
+    repo.apply(diff, ApplyLocation::WorkDir, Some(&mut options)).map_err(|e| {
+        ui_println!("{} The stashed changes no longer apply to the working tree", "⚠️".yellow());
+        anyhow::Error::from(e)
+    })?;
+
+    if let Ok(untracked) = stash.parent(2) {
+        let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+        let tree = untracked.tree()?;
+        for path in picks.keys() {
+            let Ok(entry) = tree.get_path(Path::new(path)) else { continue };
+            let target = workdir.join(path);
+            if target.exists() {
+                return Err(RgitError::InvalidArgument(format!("{} already exists in the working tree", path)).into());
+            }
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&target, repo.find_blob(entry.id())?.content())?;
+        }
+    }
+    Ok(picks.values().filter(|pick| match pick {
+        Pick::Whole => true,
+        Pick::Hunks(flags) => flags.iter().any(|f| *f),
+    }).count())
+}
+
+/// Replace `stash@{index}` with what was not restored. The remainder is
+/// pushed as `stash@{0}` with its changes unstaged; None when nothing is left.
+fn keep_remainder(repo: &Repository, stash: &Commit, index: usize, diff: &Diff, picks: &Picks) -> Result<Option<Oid>> {
+    let base = stash.parent(0)?;
+    let filter = PickFilter::new(picks, true);
+    let mut options = ApplyOptions::new();
+    options.delta_callback(|delta| filter.delta(delta));
+    options.hunk_callback(|_| filter.hunk());
+    let mut remaining = repo.apply_to_tree(&base.tree()?, diff, Some(&mut options))?;
+    let tree = repo.find_tree(remaining.write_tree_to(repo)?)?;
+
+    let untracked = match stash.parent(2) {
+        Ok(untracked) => {
+            let kept = tree_without(repo, &untracked.tree()?, |path| picks.contains_key(&path.to_string_lossy().to_string()))?;
+            if kept.is_empty() { None } else { Some((untracked, kept)) }
+        }
+        Err(_) => None,
+    };
+
+    Repository::open(repo.path())?.stash_drop(index)?;
+    if tree.id() == base.tree_id() && untracked.is_none() {
+        return Ok(None);
+    }
+
+    let author = stash.author();
+    let committer = stash.committer();
+    let index_commit = repo.commit(None, &author, &committer, &format!("index on {}", base.summary().unwrap_or("")),
+                                   &base.tree()?, &[&base])?;
+    let mut parents = vec![base.clone(), repo.find_commit(index_commit)?];
+    if let Some((untracked, kept)) = untracked {
+        let id = repo.commit(None, &untracked.author(), &untracked.committer(), untracked.message().unwrap_or(""), &kept, &[])?;
+        parents.push(repo.find_commit(id)?);
+    }
+    let message = stash.message().unwrap_or("").trim_end().to_string();
+    let parents: Vec<&Commit> = parents.iter().collect();
+    let remainder = repo.commit(None, &author, &committer, &message, &tree, &parents)?;
+    repo.reference_ensure_log("refs/stash")?;
+    repo.reference("refs/stash", remainder, true, &message)?;
+    Ok(Some(remainder))
+}
+
+/// Reapply a stash entry; `pop` drops it unless the result has conflicts
+fn apply(repo: &Repository, index: usize, pop: bool) -> Result<()> {
+    let entry = find_entry(repo, index)?;
+    let mut stash_repo = Repository::open(repo.path())?;
+    if let Err(e) = stash_repo.stash_apply(index, None) {
+        if e.code() == ErrorCode::Conflict {
+            ui_println!("{} Local changes to the same files are in the way", "⚠️".yellow());
+            ui_println!("{} Commit or stash them first, then try again", "💡".blue());
+        }
+        return Err(e.into());
+    }
+
+    let label = format!("stash@{{{}}}", index);
+    let mut index_file = stash_repo.index()?;
+    index_file.read(true)?;
+    let conflicts = conflicted_paths(&index_file)?;
+    if !conflicts.is_empty() {
+        ui_println!("{} Applied {} with conflicts:", "⚠️".yellow(), label.yellow());
+        for path in &conflicts {
+            ui_println!("  {} {}", "•".red(), path);
+        }
+        ui_println!("{} Resolve them with {}; the stash entry was kept", "💡".blue(), "rgit resolve".cyan());
+        return Err(RgitError::MergeConflict(conflicts).into());
+    }
+
+    if pop {
+        stash_repo.stash_drop(index)?;
+        ui_println!("{} Restored and dropped {} ({})", "✅".green(), label.yellow(), shorten_oid(&entry.oid, 8).dimmed());
+    } else {
+        ui_println!("{} Applied {}", "✅".green(), label.yellow());
+    }
+    Ok(())
+}
+
+/// Create `name` at the commit a stash was made on, switch to it and restore
+/// the stash there, where it applies cleanly; the entry is dropped afterwards
+fn branch(repo: &Repository, name: &str, index: usize) -> Result<()> {
+    validate_branch_name(name)?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(RgitError::BranchAlreadyExists(name.to_string()).into());
+    }
+    let entry = find_entry(repo, index)?;
+    let base = repo.find_commit(entry.oid)?.parent(0)?;
+
+    let mut created = repo.branch(name, &base, false)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    if let Err(e) = repo.checkout_tree(base.as_object(), Some(&mut checkout)) {
+        created.delete()?;
+        return Err(e.into());
+    }
+    repo.set_head(&format!("refs/heads/{}", name))?;
+    ui_println!("{} Switched to a new branch '{}' at {}", "🌿".green(), name.cyan(), shorten_oid(&base.id(), 8).yellow());
+
+    let mut stash_repo = Repository::open(repo.path())?;
+    let mut options = StashApplyOptions::new();
+    options.reinstantiate_index();
+    stash_repo.stash_apply(index, Some(&mut options))?;
+    stash_repo.stash_drop(index)?;
+    ui_println!("{} Restored and dropped {} ({})", "✅".green(), format!("stash@{{{}}}", index).yellow(), shorten_oid(&entry.oid, 8).dimmed());
+    Ok(())
+}
+
+fn drop(repo: &Repository, index: usize, config: &Config) -> Result<()> {
+    let entry = find_entry(repo, index)?;
+    let label = format!("stash@{{{}}}", index);
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Drop {} ({})?", label, entry.message))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    Repository::open(repo.path())?.stash_drop(index)?;
+    ui_println!("{} Dropped {} ({})", "🗑️".red(), label.yellow(), entry.oid);
+    Ok(())
+}
+
+fn clear(repo: &Repository, config: &Config) -> Result<()> {
+    let entries = stash_entries(repo)?;
+    if entries.is_empty() {
+        ui_println!("{} No stash entries", "📭".dimmed());
+        return Ok(());
+    }
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Drop all {} stash entries?", entries.len()))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+
+    let mut stash_repo = Repository::open(repo.path())?;
+    for _ in &entries {
+        stash_repo.stash_drop(0)?;
+    }
+    ui_println!("{} Cleared {} stash entr{}", "🗑️".red(), entries.len(), if entries.len() == 1 { "y" } else { "ies" });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn request(paths: &[&str]) -> StashRequest {
+        StashRequest {
+            message: None,
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+            keep_index: false,
+            untracked: Untracked::Exclude,
+        }
+    }
+
+    fn signature() -> Signature<'static> {
+        Signature::now("Test", "test@example.com").unwrap()
+    }
+
+    fn read(repo: &TestRepo, path: &str) -> String {
+        fs::read_to_string(repo.path().join(path)).unwrap()
+    }
+
+    #[test]
+    fn test_stash_only_selected_paths() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Add a");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.write("a.txt", "a changed\n").write("b.txt", "b changed\n");
+
+        stash_push(repo.repo(), &request(&["a.txt"]), &signature()).unwrap();
+        assert_eq!(read(&repo, "a.txt"), "a\n");
+        assert_eq!(read(&repo, "b.txt"), "b changed\n");
+
+        assert!(stash_push(repo.repo(), &request(&["a.txt"]), &signature()).is_err());
+        apply(repo.repo(), 0, true).unwrap();
+        assert_eq!(read(&repo, "a.txt"), "a changed\n");
+        assert!(stash_entries(repo.repo()).unwrap().is_empty());
+
+        let mut named = request(&["b.txt"]);
+        named.message = Some("just b".to_string());
+        let oid = stash_push(repo.repo(), &named, &signature()).unwrap();
+        let entries = stash_entries(repo.repo()).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].oid, entries[0].message.as_str()), (oid, "On main: just b"));
+        assert_eq!(read(&repo, "b.txt"), "b\n");
+        apply(repo.repo(), 0, false).unwrap();
+        assert_eq!(read(&repo, "b.txt"), "b changed\n");
+    }
+
+    #[test]
+    fn test_keep_index() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Add a");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.write("a.txt", "staged\n").stage("a.txt");
+        repo.write("b.txt", "unstaged\n");
+
+        let mut keep = request(&[]);
+        keep.keep_index = true;
+        keep.message = Some("half done".to_string());
+        stash_push(repo.repo(), &keep, &signature()).unwrap();
+
+        assert_eq!(read(&repo, "a.txt"), "staged\n");
+        assert_eq!(read(&repo, "b.txt"), "b\n");
+        let status = repo.repo().status_file(std::path::Path::new("a.txt")).unwrap();
+        assert!(status.is_index_modified());
+        let entries = stash_entries(repo.repo()).unwrap();
+        assert!(entries[0].message.ends_with("half done"));
+    }
+
+    #[test]
+    fn test_untracked_and_ignored() {
+        let repo = TestRepo::new();
+        repo.commit_file(".gitignore", "*.log\n", "Ignore logs");
+        repo.write("new.txt", "new\n").write("debug.log", "log\n");
+
+        assert!(stash_push(repo.repo(), &request(&[]), &signature()).is_err());
+
+        let mut untracked = request(&[]);
+        untracked.untracked = Untracked::Include;
+        stash_push(repo.repo(), &untracked, &signature()).unwrap();
+        assert!(!repo.path().join("new.txt").exists());
+        assert!(repo.path().join("debug.log").exists());
+        let stash = repo.repo().find_commit(stash_entries(repo.repo()).unwrap()[0].oid).unwrap();
+        assert_eq!(untracked_files(&stash).unwrap(), Some(vec!["new.txt".to_string()]));
+
+        let mut all = request(&[]);
+        all.untracked = Untracked::All;
+        stash_push(repo.repo(), &all, &signature()).unwrap();
+        assert!(!repo.path().join("debug.log").exists());
+        assert_eq!(stash_entries(repo.repo()).unwrap().len(), 2);
+
+        repo.write("keep.txt", "keep\n").write("other.txt", "other\n");
+        let mut one = request(&["other.txt"]);
+        one.untracked = Untracked::Include;
+        stash_push(repo.repo(), &one, &signature()).unwrap();
+        assert!(!repo.path().join("other.txt").exists());
+        assert!(repo.path().join("keep.txt").exists());
+        apply(repo.repo(), 0, true).unwrap();
+        assert_eq!(read(&repo, "other.txt"), "other\n");
+
+        // Untracked files need -u even when named
+        assert!(stash_push(repo.repo(), &request(&["keep.txt"]), &signature()).is_err());
+        assert!(repo.path().join("keep.txt").exists());
+        assert_eq!(stash_entries(repo.repo()).unwrap().len(), 2);
+    }
+
+    /// Restore `picks` from stash@{0} and keep the rest, as `pop` does
+    fn pop_picks(repo: &TestRepo, picks: &[(&str, Pick)]) -> Option<Oid> {
+        let picks: Picks = picks.iter().map(|(p, pick)| (p.to_string(), pick.clone())).collect();
+        let stash = repo.repo().find_commit(stash_entries(repo.repo()).unwrap()[0].oid).unwrap();
+        let diff = stash_diff(repo.repo(), &stash).unwrap();
+        restore_picks(repo.repo(), &stash, &diff, &picks).unwrap();
+        keep_remainder(repo.repo(), &stash, 0, &diff, &picks).unwrap()
+    }
+
+    #[test]
+    fn test_partial_pop_keeps_the_rest() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "a\n", "Add a");
+        repo.commit_file("b.txt", "b\n", "Add b");
+        repo.write("a.txt", "a changed\n").write("b.txt", "b changed\n").write("c.txt", "new\n");
+        let mut all = request(&[]);
+        all.untracked = Untracked::Include;
+        stash_push(repo.repo(), &all, &signature()).unwrap();
+
+        let stash = repo.repo().find_commit(stash_entries(repo.repo()).unwrap()[0].oid).unwrap();
+        let files: Vec<String> = stashed_files(repo.repo(), &stash).unwrap().into_iter().map(|f| f.path).collect();
+        assert_eq!(files, vec!["a.txt", "b.txt", "c.txt"]);
+
+        assert!(pop_picks(&repo, &[("a.txt", Pick::Whole), ("c.txt", Pick::Whole)]).is_some());
+        assert_eq!(read(&repo, "a.txt"), "a changed\n");
+        assert_eq!(read(&repo, "b.txt"), "b\n");
+        assert_eq!(read(&repo, "c.txt"), "new\n");
+
+        let stash = repo.repo().find_commit(stash_entries(repo.repo()).unwrap()[0].oid).unwrap();
+        let files: Vec<String> = stashed_files(repo.repo(), &stash).unwrap().into_iter().map(|f| f.path).collect();
+        assert_eq!(files, vec!["b.txt"]);
+        assert_eq!(pop_picks(&repo, &[("b.txt", Pick::Whole)]), None);
+        assert_eq!(read(&repo, "b.txt"), "b changed\n");
+        assert!(stash_entries(repo.repo()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_partial_pop_by_hunk() {
+        let original: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let changed = original.replace("line 2\n", "line two\n").replace("line 19\n", "line nineteen\n");
+        let repo = TestRepo::new();
+        repo.commit_file("f.txt", &original, "Add f");
+        repo.write("f.txt", &changed);
+        stash_push(repo.repo(), &request(&[]), &signature()).unwrap();
+
+        pop_picks(&repo, &[("f.txt", Pick::Hunks(vec![true, false]))]).unwrap();
+        assert_eq!(read(&repo, "f.txt"), original.replace("line 2\n", "line two\n"));
+
+        // The rest patches in on top of the restored hunk
+        assert_eq!(pop_picks(&repo, &[("f.txt", Pick::Whole)]), None);
+        assert_eq!(read(&repo, "f.txt"), changed);
+    }
+
+    #[test]
+    fn test_stash_branch() {
+        let repo = TestRepo::new();
+        let base = repo.commit_file("a.txt", "a\n", "Add a");
+        repo.write("a.txt", "stashed\n").stage("a.txt");
+        stash_push(repo.repo(), &request(&[]), &signature()).unwrap();
+        repo.commit_file("a.txt", "moved on\n", "Move on");
+
+        branch(repo.repo(), "wip", 0).unwrap();
+        assert_eq!(repo.repo().head().unwrap().shorthand(), Some("wip"));
+        assert_eq!(repo.head(), base);
+        assert_eq!(read(&repo, "a.txt"), "stashed\n");
+        assert!(repo.repo().status_file(Path::new("a.txt")).unwrap().is_index_modified());
+        assert!(stash_entries(repo.repo()).unwrap().is_empty());
+        assert!(branch(repo.repo(), "wip", 0).is_err());
+    }
+
+}
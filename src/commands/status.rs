@@ -1,14 +1,25 @@
 use anyhow::Result;
 use colored::*;
+use std::time::Instant;
 
 use crate::cli::StatusArgs;
+use crate::commands::fetch;
+use crate::commands::workspace::{display_report_table, show_report_summary};
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::status::StatusDisplay;
 use crate::submodule::SubmoduleManager;
+use crate::t;
+use crate::theme::{Element, Themed};
+use crate::workspace::{discover_repositories, run_on_repos, WorkspaceOperation};
 
 /// Execute the status command
 pub async fn execute(args: &StatusArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    // Refresh remote-tracking refs so ahead/behind counts aren't stale
+    if !args.no_fetch {
+        fetch::auto_fetch(rgit, config).await;
+    }
+
     // Create status display with options from arguments
     let display = StatusDisplay::from_args(
         args.short,
@@ -34,6 +45,42 @@ pub async fn execute(args: &StatusArgs, rgit: &RgitCore, config: &Config) -> Res
     Ok(())
 }
 
+/// Summarize every repository found below the `--all-repos` directory
+pub async fn execute_all_repos(args: &StatusArgs, config: &Config) -> Result<()> {
+    let root = args.all_repos.as_deref().unwrap_or(std::path::Path::new("."));
+    let root = dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+
+    ui_println!("{} {}", "🔍".blue(),
+            t!("status-scanning", path = root.display().to_string().cyan(), depth = args.max_depth));
+
+    let repos: Vec<_> = discover_repositories(&root, args.max_depth)
+        .into_iter()
+        .map(|path| {
+            let name = path.strip_prefix(&root)
+                .ok()
+                .filter(|relative| !relative.as_os_str().is_empty())
+                .map(|relative| relative.display().to_string())
+                .unwrap_or_else(|| ".".to_string());
+            (name, path)
+        })
+        .collect();
+
+    if repos.is_empty() {
+        ui_println!("{} {}", "ℹ️".blue(), t!("status-no-repositories"));
+        ui_println!("{} {}", "💡".blue(), t!("status-scan-deeper", option = "--max-depth <N>".themed(Element::Hint)));
+        return Ok(());
+    }
+
+    let jobs = args.jobs.unwrap_or(config.advanced.performance.threads);
+    let start = Instant::now();
+    let reports = run_on_repos(repos, WorkspaceOperation::Status, jobs, config).await;
+
+    println!();
+    display_report_table(&reports, config.terminal_width());
+    show_report_summary(&reports, start.elapsed());
+    Ok(())
+}
+
 /// Show submodule status information
 async fn show_submodule_status(rgit: &RgitCore, config: &Config, detailed: bool) -> Result<()> {
     let submodule_manager = SubmoduleManager::new(rgit, config);
@@ -41,7 +88,7 @@ async fn show_submodule_status(rgit: &RgitCore, config: &Config, detailed: bool)
 
     if health.submodules.is_empty() {
         if detailed {
-            println!("{} No submodules found", "ℹ️".blue());
+            ui_println!("{} {}", "ℹ️".blue(), t!("status-no-submodules"));
         }
         return Ok(());
     }
@@ -51,16 +98,13 @@ async fn show_submodule_status(rgit: &RgitCore, config: &Config, detailed: bool)
         submodule_manager.display_detailed_status(&health)?;
     } else if !health.is_healthy() {
         // Show summary of issues if there are any
-        println!("\n{} {} submodule{} with issues:", 
-                "⚠️".yellow(), 
-                health.total_issues(),
-                if health.total_issues() == 1 { "" } else { "s" });
+        ui_println!("\n{} {}", "⚠️".yellow(), t!("status-submodules-with-issues", count = health.total_issues()));
         
         for name in health.unhealthy_submodules() {
-            println!("  {} {}", "📦".red(), name.yellow());
+            ui_println!("  {} {}", "📦".red(), name.yellow());
         }
         
-        println!("  💡 Use \"{}\" for details", "rgit submodule status".cyan());
+        ui_println!("  💡 {}", t!("status-submodule-details", command = "rgit submodule status".themed(Element::Hint)));
     }
 
     Ok(())
@@ -83,33 +127,31 @@ async fn show_status_hints(rgit: &RgitCore, config: &Config) -> Result<()> {
 async fn show_clean_repository_hints(rgit: &RgitCore, config: &Config) -> Result<()> {
     let branch_info = rgit.get_branch_info()?;
     
-    println!("\n{} {} Repository is clean!", "✨".green(), "Tip:".bold());
+    ui_println!("\n{} {} {}", "✨".green(), t!("status-tip").bold(), t!("status-clean-repository"));
     
     // Suggest next actions based on branch state
     if branch_info.behind > 0 {
-        println!("  • {} - Get latest changes", "rgit pull".cyan());
+        ui_println!("  • {} - {}", "rgit pull".themed(Element::Hint), t!("status-hint-pull"));
     }
     
     if branch_info.ahead > 0 {
-        println!("  • {} - Share your changes", "rgit push".cyan());
+        ui_println!("  • {} - {}", "rgit push".themed(Element::Hint), t!("status-hint-push"));
     }
     
     if branch_info.upstream.is_none() && branch_info.name != "main" && branch_info.name != "master" {
-        println!("  • {} - Set up tracking", 
-                format!("rgit push --set-upstream origin {}", branch_info.name).cyan());
+        ui_println!("  • {} - {}", 
+                format!("rgit push --set-upstream origin {}", branch_info.name).themed(Element::Hint),
+                t!("status-hint-set-upstream"));
     }
     
     // Suggest common development actions
-    println!("  • {} - Quick workflow for changes", "rgit quick-commit".cyan());
-    println!("  • {} - Sync with remote", "rgit sync".cyan());
+    ui_println!("  • {} - {}", "rgit quick-commit".themed(Element::Hint), t!("status-hint-quick-commit"));
+    ui_println!("  • {} - {}", "rgit sync".themed(Element::Hint), t!("status-hint-sync"));
     
     // Check if there are stashes
     if let Ok(stash_count) = count_stash_entries(rgit) {
         if stash_count > 0 {
-            println!("  • {} - Review {} stashed change{}", 
-                    "rgit stash list".cyan(),
-                    stash_count,
-                    if stash_count == 1 { "" } else { "s" });
+            ui_println!("  • {} - {}", "rgit stash list".themed(Element::Hint), t!("status-hint-stash-list", count = stash_count));
         }
     }
 
@@ -121,23 +163,23 @@ async fn show_dirty_repository_hints(
     status: &crate::core::RepositoryStatus, 
     _config: &Config
 ) -> Result<()> {
-    println!("\n{} {} Next steps:", "💡".blue(), "Tip:".bold());
+    ui_println!("\n{} {} {}", "💡".blue(), t!("status-tip").bold(), t!("status-next-steps"));
     
     if !status.untracked.is_empty() || !status.unstaged.is_empty() {
-        println!("  • {} - Select files to stage", "rgit add".cyan());
+        ui_println!("  • {} - {}", "rgit add".themed(Element::Hint), t!("status-hint-add"));
         if status.untracked.len() + status.unstaged.len() > 3 {
-            println!("  • {} - Stage all changes", "rgit add --all".cyan());
+            ui_println!("  • {} - {}", "rgit add --all".themed(Element::Hint), t!("status-hint-add-all"));
         }
     }
     
     if !status.staged.is_empty() {
-        println!("  • {} - Commit staged changes", "rgit commit".cyan());
-        println!("  • {} - Quick commit workflow", "rgit quick-commit".cyan());
+        ui_println!("  • {} - {}", "rgit commit".themed(Element::Hint), t!("status-hint-commit"));
+        ui_println!("  • {} - {}", "rgit quick-commit".themed(Element::Hint), t!("status-hint-quick-commit-staged"));
     }
     
     if !status.is_clean() {
-        println!("  • {} - Sync when ready", "rgit sync".cyan());
-        println!("  • {} - Temporarily save changes", "rgit stash save".cyan());
+        ui_println!("  • {} - {}", "rgit sync".themed(Element::Hint), t!("status-hint-sync-when-ready"));
+        ui_println!("  • {} - {}", "rgit stash save".themed(Element::Hint), t!("status-hint-stash"));
     }
 
     Ok(())
@@ -157,16 +199,13 @@ pub async fn show_status_summary(rgit: &RgitCore, config: &Config) -> Result<()>
     let status = rgit.status()?;
     
     if status.is_clean() {
-        println!("{} Working tree clean", "✅".green());
+        ui_println!("{} {}", "✅".green(), t!("status-working-tree-clean"));
     } else {
-        let total = status.total_changes();
-        println!("{} {} change{} ({} staged, {} unstaged, {} untracked)", 
-                "📊".blue(),
-                total,
-                if total == 1 { "" } else { "s" },
-                status.staged.len(),
-                status.unstaged.len(),
-                status.untracked.len());
+        ui_println!("{} {}", "📊".blue(), t!("status-changes-breakdown",
+                total = status.total_changes(),
+                staged = status.staged.len(),
+                unstaged = status.unstaged.len(),
+                untracked = status.untracked.len()));
     }
     
     // Show branch status
@@ -231,23 +270,20 @@ impl StatusSummary {
     
     pub fn format_summary(&self) -> String {
         if self.is_clean && self.is_in_sync() {
-            "Clean and up to date".green().to_string()
+            t!("status-clean-and-up-to-date").themed(Element::Success).to_string()
         } else if self.is_clean {
             match (self.ahead, self.behind) {
-                (0, behind) if behind > 0 => format!("Clean, {} behind", behind.to_string().red()),
-                (ahead, 0) if ahead > 0 => format!("Clean, {} ahead", ahead.to_string().green()),
+                (0, behind) if behind > 0 => t!("status-clean-behind", behind = behind.to_string().themed(Element::Removed)),
+                (ahead, 0) if ahead > 0 => t!("status-clean-ahead", ahead = ahead.to_string().themed(Element::Added)),
                 (ahead, behind) if ahead > 0 && behind > 0 => {
-                    format!("Clean, {} ahead, {} behind", 
-                           ahead.to_string().green(), 
-                           behind.to_string().red())
+                    t!("status-clean-ahead-behind",
+                       ahead = ahead.to_string().themed(Element::Added),
+                       behind = behind.to_string().themed(Element::Removed))
                 }
-                _ => "Clean".green().to_string(),
+                _ => t!("status-clean").themed(Element::Success).to_string(),
             }
         } else {
-            let changes = self.total_changes();
-            format!("{} change{}", 
-                   changes.to_string().yellow(), 
-                   if changes == 1 { "" } else { "s" })
+            t!("status-changes", count = self.total_changes())
         }
     }
 }
@@ -276,15 +312,18 @@ pub async fn confirm_with_status(
     }
     
     // Show current status
-    println!("{} Current repository status:", "📋".blue());
+    ui_println!("{} {}", "📋".blue(), t!("status-current"));
     show_status_summary(rgit, config).await?;
     
-    // Ask for confirmation
+    // Ask for confirmation; carrying on over local changes is never the default
+    if config.ui.assume_yes {
+        return Ok(false);
+    }
     if !config.is_interactive() {
         return Err(crate::error::RgitError::NonInteractiveEnvironment.into());
     }
     
-    let message = format!("Continue with {} despite uncommitted changes?", operation);
+    let message = t!("status-continue-despite-changes", operation = operation);
     crate::interactive::InteractivePrompt::new()
         .with_message(&message)
         .confirm()
@@ -293,21 +332,9 @@ pub async fn confirm_with_status(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
+    use crate::test_support::create_test_repo;
     use std::fs;
 
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Set up user identity
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[tokio::test]
     async fn test_status_clean_repo() {
         let (_temp_dir, repo) = create_test_repo();
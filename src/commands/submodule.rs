@@ -9,8 +9,13 @@ use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::{InteractivePrompt, ProgressDisplay, TableDisplay};
-use crate::submodule::SubmoduleManager;
-use crate::utils::parse_git_url;
+use crate::output::decoration;
+use crate::submodule::{
+    deinit_submodule as deinit_submodule_from_repo, remote_drift, set_tracked_branch, sync_submodule,
+    CommitTransaction, SubmoduleManager, UpdateOptions,
+};
+use crate::timings::{self, Phase};
+use crate::validation::{url_problem, validate_message, validate_relative_path};
 
 /// Execute submodule command
 pub async fn execute(args: &SubmoduleArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
@@ -23,15 +28,21 @@ pub async fn execute(args: &SubmoduleArgs, rgit: &RgitCore, config: &Config) ->
         SubmoduleCommands::Init { paths, all } => {
             init_submodules(&submodule_manager, paths, *all, config).await
         }
-        SubmoduleCommands::Update { paths, init, recursive, merge, rebase, remote, force } => {
-            update_submodules(&submodule_manager, paths, *init, *recursive, *merge, *rebase, *remote, *force, config).await
+        SubmoduleCommands::Update { paths, init, recursive, merge, rebase, remote, force, jobs } => {
+            update_submodules(&submodule_manager, paths, *init, *recursive, *merge, *rebase, *remote, *force, *jobs, config).await
         }
         SubmoduleCommands::Status { recursive, health } => {
             show_submodule_status(&submodule_manager, *recursive, *health, config).await
         }
+        SubmoduleCommands::SetBranch { path, branch, default: _ } => {
+            set_submodule_branch(&submodule_manager, path, branch.as_deref())
+        }
         SubmoduleCommands::Sync { paths, recursive } => {
             sync_submodules(&submodule_manager, paths, *recursive, config).await
         }
+        SubmoduleCommands::CommitAll { message } => {
+            commit_all_submodules(&submodule_manager, message, config)
+        }
         SubmoduleCommands::Deinit { path, force, remove } => {
             deinit_submodule(&submodule_manager, path, *force, *remove, config).await
         }
@@ -101,7 +112,7 @@ async fn add_submodule(
     }
     
     // Update to get the actual content
-    submodule.update(true, None)?;
+    timings::time(Phase::Network, || submodule.update(true, None))?;
     
     if let Some(ref pb) = progress {
         pb.finish_with_message("✅ Submodule added successfully");
@@ -187,6 +198,7 @@ async fn update_submodules(
     rebase: bool,
     remote: bool,
     force: bool,
+    jobs: Option<usize>,
     config: &Config,
 ) -> Result<()> {
     manager.rgit.log("Updating submodules...");
@@ -212,63 +224,41 @@ async fn update_submodules(
     // Show update plan
     show_update_preview(&target_submodules, init, recursive, merge, rebase, remote, config)?;
     
-    let progress = if config.ui.progress {
-        Some(ProgressDisplay::new("Updating submodules")
-            .with_total(target_submodules.len() as u64)
-            .create_progress_bar())
-    } else {
-        None
-    };
+    let jobs = manager.update_jobs(jobs);
+    if jobs > 1 && target_submodules.len() > 1 {
+        manager.rgit.log(&format!("Updating {} submodules, {} at a time", target_submodules.len(), jobs));
+    }
+    
+    let target_paths: Vec<PathBuf> = target_submodules.iter().map(|s| s.path().to_path_buf()).collect();
+    let options = UpdateOptions { init, recursive, force, remote };
+    let outcomes = manager.update_paths(&target_paths, options, jobs)?;
     
     let mut updated = 0;
     let mut failed = 0;
-    
-    for (i, submodule) in target_submodules.iter().enumerate() {
-        let name = submodule.name().unwrap_or("unknown");
-        
-        if let Some(ref pb) = progress {
-            pb.set_position(i as u64);
-            pb.set_message(&format!("Updating {}", name));
-        }
-        
-        // Get a mutable reference to the submodule
-        let mut mutable_submodule = manager.rgit.repo.find_submodule(submodule.path().to_str().unwrap())?;
-        
-        // Initialize if needed and requested
-        if init && mutable_submodule.open().is_err() {
-            if let Err(e) = mutable_submodule.init(false) {
-                manager.rgit.warning(&format!("Failed to initialize '{}': {}", name, e));
-                failed += 1;
-                continue;
-            }
-        }
-        
-        // Update the submodule
-        match update_single_submodule(&mut mutable_submodule, merge, rebase, remote, force) {
-            Ok(()) => {
-                manager.rgit.success(&format!("Updated '{}'", name));
+    for outcome in &outcomes {
+        match &outcome.error {
+            None => {
+                manager.rgit.success(&format!("Updated '{}' ({})", outcome.name, outcome.summary));
                 updated += 1;
-                
-                // Recursive update if requested
-                if recursive {
-                    if let Err(e) = update_submodule_recursively(submodule, config).await {
-                        manager.rgit.warning(&format!("Recursive update failed for '{}': {}", name, e));
-                    }
-                }
             }
-            Err(e) => {
-                manager.rgit.warning(&format!("Failed to update '{}': {}", name, e));
+            Some(e) => {
+                manager.rgit.warning(&format!("Failed to update '{}': {}", outcome.name, e));
                 failed += 1;
             }
         }
     }
     
-    if let Some(ref pb) = progress {
-        pb.finish_with_message(&format!("✅ Updated {} submodules", updated));
-    }
-    
     // Show summary
     show_update_summary(updated, failed, config)?;
+    if remote && updated > 0 {
+        ui_println!("{} Stage the new submodule commits with {}", "💡".blue(), "rgit add <path>".cyan());
+    }
+    
+    if failed > 0 {
+        return Err(RgitError::SubmoduleOperationFailed(
+            format!("{} of {} submodules failed to update", failed, outcomes.len())
+        ).into());
+    }
     
     Ok(())
 }
@@ -289,7 +279,7 @@ async fn show_submodule_status(
         return Ok(());
     }
     
-    println!("{} Submodule Status Report", "📦".blue().bold());
+    ui_println!("{} Submodule Status Report", "📦".blue().bold());
     println!();
     
     if health {
@@ -299,7 +289,7 @@ async fn show_submodule_status(
     }
     
     // Show status table
-    show_submodule_status_table(&submodules, recursive, config)?;
+    show_submodule_status_table(&manager.rgit.repo, &submodules, recursive, config)?;
     
     // Show recommendations
     show_submodule_recommendations(&submodules, config)?;
@@ -307,12 +297,26 @@ async fn show_submodule_status(
     Ok(())
 }
 
+/// Set or clear the branch a submodule follows
+fn set_submodule_branch(manager: &SubmoduleManager<'_>, path: &str, branch: Option<&str>) -> Result<()> {
+    let name = set_tracked_branch(&manager.rgit.repo, path, branch)?;
+    
+    match branch {
+        Some(branch) => manager.rgit.success(&format!("Submodule '{}' now follows '{}'", name, branch)),
+        None => manager.rgit.success(&format!("Submodule '{}' now follows its remote's default branch", name)),
+    }
+    ui_println!("{} Commit {} to share it, then run {}", 
+                "💡".blue(), ".gitmodules".cyan(), "rgit submodule update --remote".cyan());
+    
+    Ok(())
+}
+
 /// Sync submodule URLs from .gitmodules
 async fn sync_submodules(
     manager: &SubmoduleManager<'_>,
     paths: &[String],
     recursive: bool,
-    config: &Config,
+    _config: &Config,
 ) -> Result<()> {
     manager.rgit.log("Syncing submodule URLs...");
     
@@ -329,22 +333,15 @@ async fn sync_submodules(
         filter_submodules_by_path(&submodules, paths)?
     };
     
+    let paths: Vec<PathBuf> = target_submodules.iter()
+        .map(|submodule| submodule.path().to_path_buf())
+        .collect();
     let mut synced = 0;
     
-    for submodule in target_submodules {
-        let name = submodule.name().unwrap_or("unknown");
-        
-        // Sync would update the remote URL from .gitmodules to .git/config
-        manager.rgit.log(&format!("Syncing URLs for '{}'", name));
-        // In real implementation: submodule.sync()?;
-        
-        synced += 1;
-        
-        if recursive {
-            // Recursively sync nested submodules
-            if let Ok(sub_repo) = submodule.open() {
-                sync_nested_submodules(&sub_repo, config).await?;
-            }
+    for path in paths {
+        for synced_path in sync_submodule(&manager.rgit.repo, &path, recursive)? {
+            ui_println!("  {} Synced URL for '{}'", "🔗".blue(), synced_path.display().to_string().cyan());
+            synced += 1;
         }
     }
     
@@ -355,6 +352,66 @@ async fn sync_submodules(
     Ok(())
 }
 
+/// Commit staged work in each submodule and the new pointers in the
+/// superproject, undoing the submodule commits if any step fails
+fn commit_all_submodules(manager: &SubmoduleManager<'_>, message: &str, config: &Config) -> Result<()> {
+    let repo = &manager.rgit.repo;
+    validate_message(message)?;
+    
+    let plan = CommitTransaction::plan(repo)?;
+    if plan.is_empty() && !manager.rgit.has_staged_changes()? {
+        manager.rgit.info("Nothing staged in the superproject or its submodules");
+        return Ok(());
+    }
+    
+    ui_println!("{} Commit Plan:", "📋".blue().bold());
+    for path in &plan {
+        ui_println!("  {} {}", "📦".blue(), path.display().to_string().cyan());
+    }
+    ui_println!("  {} {}", "🏠".blue(), "superproject".cyan());
+    println!();
+    
+    let confirmed = !config.is_interactive() || InteractivePrompt::new()
+        .with_message(format!("Create {} commit{}?", plan.len() + 1, if plan.is_empty() { "" } else { "s" }))
+        .confirm()?;
+    if !confirmed {
+        manager.rgit.info("Commit cancelled");
+        return Ok(());
+    }
+    
+    let transaction = match CommitTransaction::commit(repo, message) {
+        Ok(transaction) => transaction,
+        Err(e) => {
+            ui_println!("{} A submodule commit failed; all submodules were rolled back", "↩️".yellow());
+            return Err(e);
+        }
+    };
+    for commit in transaction.commits() {
+        ui_println!("  {} {} {}", 
+                    "✅".green(), 
+                    commit.path.display().to_string().cyan(), 
+                    crate::utils::shorten_oid(&commit.commit, 8).yellow());
+    }
+    
+    match manager.rgit.commit(message, false) {
+        Ok(oid) => {
+            ui_println!("  {} {} {}", "✅".green(), "superproject".cyan(), crate::utils::shorten_oid(&oid, 8).yellow());
+            manager.rgit.success(&format!("Committed {} submodule{} and the superproject",
+                                          transaction.commits().len(),
+                                          if transaction.commits().len() == 1 { "" } else { "s" }));
+            Ok(())
+        }
+        Err(e) => {
+            transaction.rollback(repo)?;
+            ui_println!("{} The superproject commit failed; {} submodule commit{} rolled back",
+                        "↩️".yellow(),
+                        transaction.commits().len(),
+                        if transaction.commits().len() == 1 { " was" } else { "s were" });
+            Err(e)
+        }
+    }
+}
+
 /// Deinitialize/remove a submodule
 async fn deinit_submodule(
     manager: &SubmoduleManager<'_>,
@@ -390,7 +447,7 @@ async fn deinit_submodule(
     }
     
     // Perform deinit
-    deinit_submodule_implementation(manager.rgit, &submodule, remove)?;
+    deinit_submodule_implementation(manager.rgit, submodule.path(), remove)?;
     
     if remove {
         manager.rgit.success(&format!("Removed submodule '{}'", name));
@@ -418,7 +475,7 @@ async fn foreach_submodule(
         return Ok(());
     }
     
-    println!("{} Executing: {}", "🔄".blue(), command.cyan().bold());
+    ui_println!("{} Executing: {}", "🔄".blue(), command.cyan().bold());
     println!();
     
     let mut success_count = 0;
@@ -433,7 +490,7 @@ async fn foreach_submodule(
             continue;
         }
         
-        println!("{} Entering '{}'", "📁".blue(), name.cyan());
+        ui_println!("{} Entering '{}'", "📁".blue(), name.cyan());
         
         match execute_command_in_submodule(command, path) {
             Ok(output) => {
@@ -463,10 +520,10 @@ async fn foreach_submodule(
     }
     
     // Show summary
-    println!("{} Foreach completed:", "📊".blue().bold());
-    println!("  {} {} successful", "✅".green(), success_count);
+    ui_println!("{} Foreach completed:", "📊".blue().bold());
+    ui_println!("  {} {} successful", "✅".green(), success_count);
     if error_count > 0 {
-        println!("  {} {} failed", "❌".red(), error_count);
+        ui_println!("  {} {} failed", "❌".red(), error_count);
     }
     
     Ok(())
@@ -478,15 +535,10 @@ async fn foreach_submodule(
 
 /// Validate submodule add inputs
 fn validate_submodule_add_inputs(url: &str, path: &str, _config: &Config) -> Result<()> {
-    // Validate URL
-    if parse_git_url(url).is_none() {
-        return Err(RgitError::SubmoduleInvalidUrl(url.to_string()).into());
-    }
-    
-    // Validate path
-    if path.is_empty() || path.contains("..") || path.starts_with('/') {
-        return Err(RgitError::InvalidPath(PathBuf::from(path)).into());
+    if let Some(problem) = url_problem(url) {
+        return Err(RgitError::SubmoduleInvalidUrl(format!("{} ({})", url, problem)).into());
     }
+    validate_relative_path(path)?;
     
     Ok(())
 }
@@ -504,7 +556,7 @@ fn show_submodule_add_preview(
         return Ok(());
     }
     
-    println!("{} Submodule Add Preview:", "👁️".blue().bold());
+    ui_println!("{} Submodule Add Preview:", "👁️".blue().bold());
     println!("  {} {}", "URL:".bold(), url.cyan());
     println!("  {} {}", "Path:".bold(), path.yellow());
     
@@ -582,7 +634,7 @@ fn show_init_preview(submodules: &[&Submodule<'_>], config: &Config) -> Result<(
         return Ok(());
     }
     
-    println!("{} Will initialize {} submodule{}:", 
+    ui_println!("{} Will initialize {} submodule{}:", 
             "📋".blue(),
             submodules.len(),
             if submodules.len() == 1 { "" } else { "s" });
@@ -590,7 +642,7 @@ fn show_init_preview(submodules: &[&Submodule<'_>], config: &Config) -> Result<(
     for submodule in submodules {
         let name = submodule.name().unwrap_or("unknown");
         let path = submodule.path().display();
-        println!("  {} {} ({})", "•".blue(), name.cyan(), path.to_string().dimmed());
+        ui_println!("  {} {} ({})", "•".blue(), name.cyan(), path.to_string().dimmed());
     }
     
     println!();
@@ -599,11 +651,11 @@ fn show_init_preview(submodules: &[&Submodule<'_>], config: &Config) -> Result<(
 
 /// Show initialization summary
 fn show_init_summary(initialized: usize, skipped: usize, _config: &Config) -> Result<()> {
-    println!("\n{} Initialization Summary:", "📊".blue().bold());
-    println!("  {} {} initialized", "✅".green(), initialized);
+    ui_println!("\n{} Initialization Summary:", "📊".blue().bold());
+    ui_println!("  {} {} initialized", "✅".green(), initialized);
     
     if skipped > 0 {
-        println!("  {} {} already initialized", "ℹ️".blue(), skipped);
+        ui_println!("  {} {} already initialized", "ℹ️".blue(), skipped);
     }
     
     Ok(())
@@ -623,8 +675,8 @@ fn show_update_preview(
         return Ok(());
     }
     
-    println!("{} Update Plan:", "📋".blue().bold());
-    println!("  {} {} submodule{}", "📦".blue(), submodules.len(), if submodules.len() == 1 { "" } else { "s" });
+    ui_println!("{} Update Plan:", "📋".blue().bold());
+    ui_println!("  {} {} submodule{}", "📦".blue(), submodules.len(), if submodules.len() == 1 { "" } else { "s" });
     
     let mut options = Vec::new();
     if init { options.push("initialize if needed"); }
@@ -634,70 +686,48 @@ fn show_update_preview(
     if remote { options.push("track remote"); }
     
     if !options.is_empty() {
-        println!("  {} Options: {}", "⚙️".blue(), options.join(", ").cyan());
+        ui_println!("  {} Options: {}", "⚙️".blue(), options.join(", ").cyan());
     }
     
     println!();
     Ok(())
 }
 
-/// Update a single submodule
-fn update_single_submodule(
-    submodule: &mut Submodule<'_>,
-    _merge: bool,
-    _rebase: bool,
-    _remote: bool,
-    _force: bool,
-) -> Result<()> {
-    // In real implementation, this would handle different update strategies
-    submodule.update(true, None)?;
-    Ok(())
-}
-
-/// Update submodule recursively
-async fn update_submodule_recursively(
-    _submodule: &Submodule<'_>,
-    _config: &Config,
-) -> Result<()> {
-    // In real implementation, this would recursively update nested submodules
-    Ok(())
-}
-
 /// Show update summary
 fn show_update_summary(updated: usize, failed: usize, _config: &Config) -> Result<()> {
-    println!("\n{} Update Summary:", "📊".blue().bold());
-    println!("  {} {} updated successfully", "✅".green(), updated);
+    ui_println!("\n{} Update Summary:", "📊".blue().bold());
+    ui_println!("  {} {} updated successfully", "✅".green(), updated);
     
     if failed > 0 {
-        println!("  {} {} failed to update", "❌".red(), failed);
+        ui_println!("  {} {} failed to update", "❌".red(), failed);
     }
     
     Ok(())
 }
 
 /// Show health summary
-fn show_health_summary(
+pub fn show_health_summary(
     health: &crate::submodule::SubmoduleHealth,
     config: &Config,
 ) -> Result<()> {
     if health.is_healthy() {
-        println!("{} All submodules are healthy", "🎉".green());
+        ui_println!("{} All submodules are healthy", "🎉".green());
         return Ok(());
     }
     
-    println!("{} Submodule Health Issues:", "⚠️".yellow().bold());
+    ui_println!("{} Submodule Health Issues:", "⚠️".yellow().bold());
     
     for (name, status) in &health.submodules {
         if !status.issues.is_empty() {
-            println!("\n📦 {} ({}):", name.yellow(), status.path.display().to_string().dimmed());
+            ui_println!("\n📦 {} ({}):", name.yellow(), status.path.display().to_string().dimmed());
             
             for issue in &status.issues {
                 let severity_icon = issue.severity().icon();
-                println!("  {} {}", severity_icon, issue.description());
+                ui_println!("  {} {}", severity_icon, issue.description());
                 
                 if config.ui.interactive {
                     for suggestion in issue.suggestions() {
-                        println!("    {} {}", "💡".blue(), suggestion.dimmed());
+                        ui_println!("    {} {}", "💡".blue(), suggestion.dimmed());
                     }
                 }
             }
@@ -710,6 +740,7 @@ fn show_health_summary(
 
 /// Show submodule status table
 fn show_submodule_status_table(
+    repo: &Repository,
     submodules: &[Submodule<'_>],
     recursive: bool,
     config: &Config,
@@ -720,6 +751,7 @@ fn show_submodule_status_table(
             "Path".to_string(),
             "Status".to_string(),
             "Branch/Commit".to_string(),
+            "Remote".to_string(),
             "Issues".to_string(),
         ])
         .with_max_width(config.terminal_width());
@@ -729,8 +761,9 @@ fn show_submodule_status_table(
         let path = submodule.path().display().to_string();
         
         let (status, branch_info, issues) = get_submodule_table_info(submodule)?;
+        let remote = get_submodule_drift_info(repo, submodule);
         
-        table.add_row(vec![name, path, status, branch_info, issues]);
+        table.add_row(vec![name, path, status, branch_info, remote, issues]);
         
         if recursive {
             // Add nested submodules with indentation
@@ -749,9 +782,9 @@ fn show_submodule_status_table(
 /// Get submodule information for table display
 fn get_submodule_table_info(submodule: &Submodule<'_>) -> Result<(String, String, String)> {
     let status = if submodule.open().is_ok() {
-        "✅ OK".green().to_string()
+        format!("{} OK", decoration("✅")).green().to_string()
     } else {
-        "❓ Not Init".red().to_string()
+        format!("{} Not Init", decoration("❓")).red().to_string()
     };
     
     let branch_info = if let Ok(sub_repo) = submodule.open() {
@@ -769,6 +802,28 @@ fn get_submodule_table_info(submodule: &Submodule<'_>) -> Result<(String, String
     Ok((status, branch_info, issues))
 }
 
+/// Describe how far the recorded commit is from the tracked remote branch,
+/// as of the submodule's last fetch
+fn get_submodule_drift_info(repo: &Repository, submodule: &Submodule<'_>) -> String {
+    match remote_drift(repo, submodule) {
+        Ok(Some(drift)) => {
+            let mut parts = Vec::new();
+            if drift.ahead > 0 {
+                parts.push(format!("{} ahead", drift.ahead));
+            }
+            if drift.behind > 0 {
+                parts.push(format!("{} behind", drift.behind));
+            }
+            if parts.is_empty() {
+                format!("{}: up to date", drift.branch).green().to_string()
+            } else {
+                format!("{}: {}", drift.branch, parts.join(", ")).yellow().to_string()
+            }
+        }
+        _ => "-".dimmed().to_string(),
+    }
+}
+
 /// Get branch information for submodule
 fn get_submodule_branch_info(repo: &Repository) -> Result<String> {
     match repo.head() {
@@ -787,7 +842,7 @@ fn get_submodule_branch_info(repo: &Repository) -> Result<String> {
 
 /// Get issues summary for submodule
 fn get_submodule_issues_summary(repo: &Repository) -> Result<String> {
-    let statuses = repo.statuses(None)?;
+    let statuses = timings::time(Phase::StatusScan, || repo.statuses(None))?;
     
     if statuses.is_empty() {
         Ok("None".green().to_string())
@@ -810,8 +865,9 @@ fn add_nested_submodules_to_table(
         let path = submodule.path().display().to_string();
         
         let (status, branch_info, issues) = get_submodule_table_info(&submodule)?;
+        let remote = get_submodule_drift_info(repo, &submodule);
         
-        table.add_row(vec![name, path, status, branch_info, issues]);
+        table.add_row(vec![name, path, status, branch_info, remote, issues]);
         
         // Recurse further if needed (limit depth to prevent infinite recursion)
         if depth < 3 {
@@ -859,9 +915,9 @@ fn show_submodule_recommendations(submodules: &[Submodule<'_>], config: &Config)
     }
     
     if !recommendations.is_empty() {
-        println!("{} Recommendations:", "💡".blue().bold());
+        ui_println!("{} Recommendations:", "💡".blue().bold());
         for recommendation in recommendations {
-            println!("  • {}", recommendation.cyan());
+            ui_println!("  • {}", recommendation.cyan());
         }
         println!();
     }
@@ -878,7 +934,7 @@ fn show_deinit_preview(submodule: &Submodule<'_>, remove: bool, config: &Config)
     let name = submodule.name().unwrap_or("unknown");
     let path = submodule.path().display();
     
-    println!("{} Deinit Preview:", "👁️".blue().bold());
+    ui_println!("{} Deinit Preview:", "👁️".blue().bold());
     println!("  {} {}", "Submodule:".bold(), name.cyan());
     println!("  {} {}", "Path:".bold(), path.to_string().yellow());
     
@@ -906,15 +962,16 @@ fn confirm_submodule_deinit(name: &str, remove: bool, config: &Config) -> Result
 }
 
 /// Deinitialize submodule implementation
-fn deinit_submodule_implementation(
-    _rgit: &RgitCore,
-    _submodule: &Submodule<'_>,
-    _remove: bool,
-) -> Result<()> {
-    // In real implementation, this would:
-    // 1. Remove working tree content
-    // 2. Remove from .git/config
-    // 3. If remove=true, also remove from .gitmodules and git index
+fn deinit_submodule_implementation(rgit: &RgitCore, path: &Path, remove: bool) -> Result<()> {
+    let name = deinit_submodule_from_repo(&rgit.repo, path, remove)?;
+    rgit.log(&format!("Cleared working tree and config of '{}'", name));
+    
+    if remove {
+        ui_println!("{} Staged the removal of '{}' and its .gitmodules entry", "💡".blue(), path.display());
+        println!("   Run {} to record it", "rgit commit".cyan());
+    } else {
+        ui_println!("{} Run {} to bring it back", "💡".blue(), "rgit submodule update --init".cyan());
+    }
     
     Ok(())
 }
@@ -945,23 +1002,17 @@ async fn execute_foreach_recursively(
     Ok(())
 }
 
-/// Sync nested submodules
-async fn sync_nested_submodules(_repo: &Repository, _config: &Config) -> Result<()> {
-    // In real implementation, this would sync nested submodules
-    Ok(())
-}
-
 /// Show next steps after adding submodule
 fn show_submodule_add_next_steps(path: &str, config: &Config) -> Result<()> {
     if !config.ui.interactive {
         return Ok(());
     }
     
-    println!("\n{} Next steps:", "💡".blue());
-    println!("  • {} - Stage the submodule", "rgit add .gitmodules".cyan());
-    println!("  • {} - Commit the submodule addition", "rgit commit".cyan());
-    println!("  • {} - Check submodule status", "rgit submodule status".cyan());
-    println!("  • {} - Work in the submodule", format!("cd {}", path).cyan());
+    ui_println!("\n{} Next steps:", "💡".blue());
+    ui_println!("  • {} - Stage the submodule", "rgit add .gitmodules".cyan());
+    ui_println!("  • {} - Commit the submodule addition", "rgit commit".cyan());
+    ui_println!("  • {} - Check submodule status", "rgit submodule status".cyan());
+    ui_println!("  • {} - Work in the submodule", format!("cd {}", path).cyan());
     
     Ok(())
 }
@@ -969,19 +1020,9 @@ fn show_submodule_add_next_steps(path: &str, config: &Config) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::create_test_repo;
     use tempfile::TempDir;
 
-    fn create_test_repo() -> (TempDir, Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = Repository::init(temp_dir.path()).unwrap();
-        
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[test]
     fn test_validate_submodule_add_inputs() {
         let config = Config::default();
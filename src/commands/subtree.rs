@@ -0,0 +1,603 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{
+    Commit, Direction, FetchOptions, ObjectType, Oid, PushOptions, Remote, RemoteCallbacks, Repository, Sort,
+    Tree,
+};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::cli::{SubtreeArgs, SubtreeCommands};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::journal::{Journal, JournalEntry};
+use crate::network;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+use crate::validation::validate_relative_path;
+
+/// Trailer naming the directory a subtree commit belongs to
+const DIR_TRAILER: &str = "git-subtree-dir";
+/// Trailer naming the upstream commit a subtree commit brought in
+const SPLIT_TRAILER: &str = "git-subtree-split";
+/// Trailer naming the superproject commit a subtree was merged into
+const MAINLINE_TRAILER: &str = "git-subtree-mainline";
+
+/// Where fetched upstream commits are kept, one ref per prefix
+const FETCHED_REFS: &str = "refs/rgit/subtree";
+/// Where `push` parks the split history while pushing it
+const SPLIT_REFS: &str = "refs/rgit/subtree-split";
+
+/// Execute the subtree command
+pub async fn execute(args: &SubtreeArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    match &args.action {
+        SubtreeCommands::Add { prefix, repository, reference, squash, message } => {
+            let prefix = normalize_prefix(prefix)?;
+            let upstream = SubtreeUpstream {
+                repository: repository.clone(),
+                reference: reference.clone(),
+                squash: *squash,
+            };
+            add(rgit, &prefix, &upstream, message.as_deref(), config).await
+        }
+        SubtreeCommands::Pull { prefix, repository, reference, squash, message } => {
+            let prefix = normalize_prefix(prefix)?;
+            let upstream = resolve_upstream(&rgit.repo, &prefix, repository.as_deref(), reference.as_deref(), *squash)?;
+            pull(rgit, &prefix, &upstream, message.as_deref(), config).await
+        }
+        SubtreeCommands::Push { prefix, repository, reference } => {
+            let prefix = normalize_prefix(prefix)?;
+            let upstream = resolve_upstream(&rgit.repo, &prefix, repository.as_deref(), reference.as_deref(), false)?;
+            push(rgit, &prefix, &upstream, config).await
+        }
+        SubtreeCommands::Split { prefix, branch } => {
+            let prefix = normalize_prefix(prefix)?;
+            split_command(rgit, &prefix, branch.as_deref())
+        }
+    }
+}
+
+/// Prefixes are working tree relative directories, written with `/`
+fn normalize_prefix(prefix: &str) -> Result<String> {
+    let path = validate_relative_path(prefix.trim_end_matches('/'))?;
+    Ok(path.to_string_lossy().replace('\\', "/"))
+}
+
+// =============================================================================
+// Remembered Upstreams
+// =============================================================================
+
+/// Where a subtree comes from, remembered in `.git/config` under
+/// `subtree.<prefix>.*` so `pull` and `push` can be run with just the prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubtreeUpstream {
+    /// Remote name or URL
+    pub repository: String,
+    /// Branch or tag
+    pub reference: String,
+    /// Whether upstream history is squashed into one commit per merge
+    pub squash: bool,
+}
+
+/// The upstream remembered for `prefix`, if it was added or pulled before
+pub fn remembered_upstream(repo: &Repository, prefix: &str) -> Option<SubtreeUpstream> {
+    let config = repo.config().ok()?;
+    Some(SubtreeUpstream {
+        repository: config.get_string(&format!("subtree.{}.remote", prefix)).ok()?,
+        reference: config.get_string(&format!("subtree.{}.ref", prefix)).ok()?,
+        squash: config.get_bool(&format!("subtree.{}.squash", prefix)).unwrap_or(false),
+    })
+}
+
+fn remember_upstream(repo: &Repository, prefix: &str, upstream: &SubtreeUpstream) -> Result<()> {
+    let mut config = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+    config.set_str(&format!("subtree.{}.remote", prefix), &upstream.repository)?;
+    config.set_str(&format!("subtree.{}.ref", prefix), &upstream.reference)?;
+    config.set_bool(&format!("subtree.{}.squash", prefix), upstream.squash)?;
+    Ok(())
+}
+
+/// Fill in whatever wasn't given on the command line from the remembered upstream
+fn resolve_upstream(
+    repo: &Repository,
+    prefix: &str,
+    repository: Option<&str>,
+    reference: Option<&str>,
+    squash: bool,
+) -> Result<SubtreeUpstream> {
+    let remembered = remembered_upstream(repo, prefix);
+    let missing = || RgitError::InvalidArgument(format!(
+        "No upstream remembered for '{}'; pass a repository and ref", prefix
+    ));
+    Ok(SubtreeUpstream {
+        repository: match repository {
+            Some(repository) => repository.to_string(),
+            None => remembered.as_ref().ok_or_else(missing)?.repository.clone(),
+        },
+        reference: match reference {
+            Some(reference) => reference.to_string(),
+            None => remembered.as_ref().ok_or_else(missing)?.reference.clone(),
+        },
+        squash: squash || remembered.is_some_and(|upstream| upstream.squash),
+    })
+}
+
+// =============================================================================
+// Add and Pull
+// =============================================================================
+
+async fn add(rgit: &RgitCore, prefix: &str, upstream: &SubtreeUpstream, message: Option<&str>, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    ensure_clean(rgit)?;
+    let head = repo.head()?.peel_to_commit()?;
+    if head.tree()?.get_path(Path::new(prefix)).is_ok() || rgit.root_dir().join(prefix).exists() {
+        return Err(RgitError::OperationFailed(format!("'{}' already exists", prefix)).into());
+    }
+
+    ui_println!("{} Fetching {} from {}", "📥".blue(), upstream.reference.cyan(), upstream.repository.cyan());
+    let fetched = repo.find_commit(fetch_upstream(repo, prefix, upstream, config).await?)?;
+    let signature = rgit.get_signature()?;
+    let merged = if upstream.squash { squash_commit(repo, prefix, &fetched, &signature)? } else { fetched.clone() };
+
+    let tree = replace_subtree(repo, &head.tree()?, prefix, Some(fetched.tree_id()))?;
+    let message = message.map(str::to_string).unwrap_or_else(|| format!(
+        "Add '{}/' from commit '{}'\n\n{}: {}\n{}: {}\n{}: {}\n",
+        prefix, fetched.id(), DIR_TRAILER, prefix, MAINLINE_TRAILER, head.id(), SPLIT_TRAILER, fetched.id()
+    ));
+    let oid = commit_subtree_merge(repo, &head, &merged, tree, &message, &signature, "subtree add")?;
+    remember_upstream(repo, prefix, upstream)?;
+
+    ui_println!("{} Added '{}' from {} at {}",
+                "✅".green().bold(),
+                format!("{}/", prefix).cyan(),
+                upstream.reference.cyan(),
+                shorten_oid(&fetched.id(), 8).yellow());
+    println!("  {} {}", "Commit:".bold(), shorten_oid(&oid, 8).yellow());
+    ui_println!("{} Update it later with {}", "💡".blue(), format!("rgit subtree pull -P {}", prefix).cyan());
+    Ok(())
+}
+
+async fn pull(rgit: &RgitCore, prefix: &str, upstream: &SubtreeUpstream, message: Option<&str>, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    ensure_clean(rgit)?;
+    let head = repo.head()?.peel_to_commit()?;
+    let last = last_subtree_merge(repo, &head, prefix)?.ok_or_else(|| RgitError::OperationFailed(format!(
+        "'{}' wasn't added with 'rgit subtree add'", prefix
+    )))?;
+
+    ui_println!("{} Fetching {} from {}", "📥".blue(), upstream.reference.cyan(), upstream.repository.cyan());
+    let fetched = repo.find_commit(fetch_upstream(repo, prefix, upstream, config).await?)?;
+    if trailer(&last, SPLIT_TRAILER) == Some(fetched.id().to_string()) {
+        ui_println!("{} '{}' is already up to date", "✅".green(), format!("{}/", prefix).cyan());
+        return Ok(());
+    }
+
+    // Three-way merge of the directory: the last upstream we merged is the base
+    let base = last.parent(1)?.tree()?;
+    let ours = subtree_at(repo, &head.tree()?, prefix)?.ok_or_else(|| RgitError::OperationFailed(format!(
+        "'{}' no longer exists on this branch", prefix
+    )))?;
+    let mut index = repo.merge_trees(&base, &ours, &fetched.tree()?, None)?;
+    if index.has_conflicts() {
+        let mut paths = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+                paths.push(format!("{}/{}", prefix, String::from_utf8_lossy(&entry.path)));
+            }
+        }
+        return Err(RgitError::MergeConflict(paths).into());
+    }
+    let merged_tree = index.write_tree_to(repo)?;
+
+    let signature = rgit.get_signature()?;
+    let merged = if upstream.squash { squash_commit(repo, prefix, &fetched, &signature)? } else { fetched.clone() };
+    let tree = replace_subtree(repo, &head.tree()?, prefix, Some(merged_tree))?;
+    let message = message.map(str::to_string).unwrap_or_else(|| format!(
+        "Merge '{}/' from commit '{}'\n\n{}: {}\n{}: {}\n{}: {}\n",
+        prefix, fetched.id(), DIR_TRAILER, prefix, MAINLINE_TRAILER, head.id(), SPLIT_TRAILER, fetched.id()
+    ));
+    let oid = commit_subtree_merge(repo, &head, &merged, tree, &message, &signature, "subtree pull")?;
+    remember_upstream(repo, prefix, upstream)?;
+
+    ui_println!("{} Merged {} into '{}' ({})",
+                "✅".green().bold(),
+                shorten_oid(&fetched.id(), 8).yellow(),
+                format!("{}/", prefix).cyan(),
+                shorten_oid(&oid, 8).yellow());
+    Ok(())
+}
+
+/// Subtree merges rewrite the working tree, so local changes would be lost
+fn ensure_clean(rgit: &RgitCore) -> Result<()> {
+    if !rgit.is_clean()? {
+        return Err(RgitError::MergeWorkingTreeDirty.into());
+    }
+    Ok(())
+}
+
+/// A parentless commit standing in for `upstream`'s whole history
+fn squash_commit<'r>(
+    repo: &'r Repository,
+    prefix: &str,
+    upstream: &Commit<'_>,
+    signature: &git2::Signature<'_>,
+) -> Result<Commit<'r>> {
+    let message = format!(
+        "Squashed '{}/' content from commit {}\n\n{}: {}\n{}: {}\n",
+        prefix, shorten_oid(&upstream.id(), 8), DIR_TRAILER, prefix, SPLIT_TRAILER, upstream.id()
+    );
+    let oid = repo.commit(None, signature, signature, &message, &upstream.tree()?, &[])?;
+    Ok(repo.find_commit(oid)?)
+}
+
+/// Check out `tree` and commit it on top of HEAD with `merged` as second parent
+fn commit_subtree_merge(
+    repo: &Repository,
+    head: &Commit<'_>,
+    merged: &Commit<'_>,
+    tree: Oid,
+    message: &str,
+    signature: &git2::Signature<'_>,
+    operation: &str,
+) -> Result<Oid> {
+    let tree = repo.find_tree(tree)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    repo.checkout_tree(tree.as_object(), Some(&mut checkout))?;
+    let oid = repo.commit(Some("HEAD"), signature, signature, message, &tree, &[head, merged])?;
+
+    let head_ref = repo.head()?;
+    let reference = if head_ref.is_branch() { head_ref.name().unwrap_or("HEAD") } else { "HEAD" };
+    Journal::for_repo(repo).record(&JournalEntry::new(operation, reference).with_old_oid(head.id()).with_new_oid(oid))?;
+    Ok(oid)
+}
+
+/// The newest commit on HEAD's first-parent line that merged upstream
+/// history into `prefix`
+fn last_subtree_merge<'r>(repo: &'r Repository, head: &Commit<'r>, prefix: &str) -> Result<Option<Commit<'r>>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(head.id())?;
+    revwalk.simplify_first_parent()?;
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() == 2 && trailer(&commit, DIR_TRAILER).as_deref() == Some(prefix) {
+            return Ok(Some(commit));
+        }
+    }
+    Ok(None)
+}
+
+/// The value of `key: value` in the commit message
+fn trailer(commit: &Commit<'_>, key: &str) -> Option<String> {
+    commit.message()?.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+// =============================================================================
+// Fetching and Pushing
+// =============================================================================
+
+/// Remote callbacks shared by fetch and push
+fn remote_callbacks(config: &Config) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+    callbacks
+}
+
+/// A configured remote by name, or an anonymous one for a URL, with the
+/// configured URL rewrites applied
+fn open_remote<'r>(repo: &'r Repository, repository: &str, config: &Config, direction: Direction) -> Result<Remote<'r>> {
+    let remote = match repo.find_remote(repository) {
+        Ok(remote) => remote,
+        Err(_) => repo.remote_anonymous(repository)
+            .map_err(|_| RgitError::RemoteNotFound(repository.to_string()))?,
+    };
+    Ok(network::rewritten(repo, remote, config, direction)?)
+}
+
+/// Fetch `upstream.reference` and return the commit it points at
+async fn fetch_upstream(repo: &Repository, prefix: &str, upstream: &SubtreeUpstream, config: &Config) -> Result<Oid> {
+    let mut remote = open_remote(repo, &upstream.repository, config, Direction::Fetch)?;
+
+    // Accept branch and tag names as well as full refs, like git
+    network::with_retries(config, || {
+        timings::time(Phase::Network, || remote.connect_auth(Direction::Fetch, Some(remote_callbacks(config)), None))
+            .map(drop)
+    }, |e| RgitError::FetchFailed(e.message().to_string())).await?;
+    let source = {
+        let advertised = remote.list()?;
+        let candidates = [
+            upstream.reference.clone(),
+            format!("refs/heads/{}", upstream.reference),
+            format!("refs/tags/{}", upstream.reference),
+        ];
+        candidates.into_iter()
+            .find(|candidate| advertised.iter().any(|head| head.name() == candidate))
+            .ok_or_else(|| RgitError::OperationFailed(format!(
+                "No branch or tag '{}' in {}", upstream.reference, upstream.repository
+            )))?
+    };
+    remote.disconnect()?;
+
+    let target = format!("{}/{}", FETCHED_REFS, prefix);
+    let refspec = format!("+{}:{}", source, target);
+    network::with_retries(config, || {
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(remote_callbacks(config));
+        timings::time(Phase::Network, || remote.fetch(&[refspec.as_str()], Some(&mut options), None))
+    }, |e| RgitError::FetchFailed(e.message().to_string())).await?;
+
+    Ok(repo.find_reference(&target)?.peel_to_commit()?.id())
+}
+
+async fn push(rgit: &RgitCore, prefix: &str, upstream: &SubtreeUpstream, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let head = repo.head()?.peel_to_commit()?;
+    let tip = split(repo, &head, prefix)?;
+
+    let split_ref = format!("{}/{}", SPLIT_REFS, prefix);
+    repo.reference(&split_ref, tip, true, "subtree push")?;
+    let destination = if upstream.reference.starts_with("refs/") {
+        upstream.reference.clone()
+    } else {
+        format!("refs/heads/{}", upstream.reference)
+    };
+
+    ui_println!("{} Pushing '{}' ({}) to {} {}",
+                "📤".blue(),
+                format!("{}/", prefix).cyan(),
+                shorten_oid(&tip, 8).yellow(),
+                upstream.repository.cyan(),
+                upstream.reference.cyan());
+
+    let mut remote = open_remote(repo, &upstream.repository, config, Direction::Push)?;
+    let refspec = format!("{}:{}", split_ref, destination);
+    let result = network::with_retries(config, || {
+        let mut callbacks = remote_callbacks(config);
+        callbacks.push_update_reference(|refname, status| match status {
+            Some(msg) => Err(git2::Error::from_str(&format!("{} rejected: {}", refname, msg))),
+            None => Ok(()),
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        timings::time(Phase::Network, || remote.push(&[refspec.as_str()], Some(&mut options)))
+    }, |e| RgitError::PushRejected(e.message().to_string())).await;
+    repo.find_reference(&split_ref)?.delete()?;
+    result?;
+
+    ui_println!("{} Pushed '{}' to {}", "✅".green().bold(), format!("{}/", prefix).cyan(), upstream.reference.cyan());
+    Ok(())
+}
+
+// =============================================================================
+// Splitting
+// =============================================================================
+
+fn split_command(rgit: &RgitCore, prefix: &str, branch: Option<&str>) -> Result<()> {
+    let repo = &rgit.repo;
+    let head = repo.head()?.peel_to_commit()?;
+    let tip = split(repo, &head, prefix)?;
+
+    if let Some(branch) = branch {
+        if let Ok(existing) = repo.find_branch(branch, git2::BranchType::Local) {
+            let current = existing.get().peel_to_commit()?.id();
+            if current != tip && !repo.graph_descendant_of(tip, current)? {
+                return Err(RgitError::OperationFailed(format!(
+                    "Branch '{}' is not an ancestor of the split history {}", branch, shorten_oid(&tip, 8)
+                )).into());
+            }
+        }
+        repo.branch(branch, &repo.find_commit(tip)?, true)?;
+        rgit.success(&format!("Split '{}/' into branch '{}'", prefix, branch));
+    }
+    // The bare id goes to stdout so scripts can use it
+    println!("{}", tip);
+    Ok(())
+}
+
+/// Rewrite the history of `prefix` up to `head` into commits with the
+/// directory as their root, returning the new tip.
+///
+/// Rewriting is deterministic, so splitting again reuses earlier results,
+/// and commits merged in with `subtree add`/`pull` map back to the upstream
+/// commits themselves so pushes fast-forward the upstream branch.
+pub fn split(repo: &Repository, head: &Commit<'_>, prefix: &str) -> Result<Oid> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    revwalk.push(head.id())?;
+
+    let mut rewritten: HashMap<Oid, Option<Oid>> = HashMap::new();
+    for oid in revwalk {
+        let oid = oid?;
+        let commit = repo.find_commit(oid)?;
+        let Some(subtree) = subtree_at(repo, &commit.tree()?, prefix)? else {
+            rewritten.insert(oid, None);
+            continue;
+        };
+
+        // The second parent of a subtree merge is upstream history (or its squash)
+        let merged_upstream = (commit.parent_count() == 2
+            && trailer(&commit, DIR_TRAILER).as_deref() == Some(prefix))
+            .then(|| upstream_commit(repo, &commit))
+            .transpose()?;
+
+        let mut parents: Vec<Oid> = Vec::new();
+        for (index, parent) in commit.parent_ids().enumerate() {
+            let mapped = match merged_upstream {
+                Some(upstream) if index == 1 => Some(upstream),
+                _ => rewritten.get(&parent).copied().flatten(),
+            };
+            if let Some(mapped) = mapped {
+                if !parents.contains(&mapped) {
+                    parents.push(mapped);
+                }
+            }
+        }
+        // Drop parents already reachable through another one
+        let all = parents.clone();
+        parents.retain(|&parent| {
+            !all.iter().any(|&other| other != parent && repo.graph_descendant_of(other, parent).unwrap_or(false))
+        });
+
+        let unchanged = match parents.as_slice() {
+            [parent] => repo.find_commit(*parent)?.tree_id() == subtree.id(),
+            _ => false,
+        };
+        let new = if unchanged {
+            parents[0]
+        } else {
+            let parent_commits = parents.iter()
+                .map(|parent| repo.find_commit(*parent))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            let parent_refs: Vec<&Commit> = parent_commits.iter().collect();
+            repo.commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                commit.message_raw().unwrap_or(""),
+                &subtree,
+                &parent_refs,
+            )?
+        };
+        rewritten.insert(oid, Some(new));
+    }
+
+    rewritten.get(&head.id()).copied().flatten().ok_or_else(|| RgitError::OperationFailed(format!(
+        "'{}' has no history on this branch", prefix
+    )).into())
+}
+
+/// The upstream commit a subtree merge brought in: the split trailer's
+/// commit when it's available, otherwise the merged parent itself
+fn upstream_commit(repo: &Repository, merge: &Commit<'_>) -> Result<Oid> {
+    let merged = merge.parent(1)?;
+    let split = trailer(&merged, SPLIT_TRAILER)
+        .and_then(|id| Oid::from_str(&id).ok())
+        .filter(|id| *id != merged.id() && repo.find_commit(*id).is_ok());
+    Ok(split.unwrap_or(merged.id()))
+}
+
+// =============================================================================
+// Trees
+// =============================================================================
+
+/// The tree at `prefix` inside `root`, if there is one
+fn subtree_at<'r>(repo: &'r Repository, root: &Tree<'_>, prefix: &str) -> Result<Option<Tree<'r>>> {
+    match root.get_path(Path::new(prefix)) {
+        Ok(entry) if entry.kind() == Some(ObjectType::Tree) => Ok(Some(repo.find_tree(entry.id())?)),
+        _ => Ok(None),
+    }
+}
+
+/// `root` with the directory at `prefix` replaced by `subtree`, or removed
+fn replace_subtree(repo: &Repository, root: &Tree<'_>, prefix: &str, subtree: Option<Oid>) -> Result<Oid> {
+    let (name, rest) = match prefix.split_once('/') {
+        Some((name, rest)) => (name, Some(rest)),
+        None => (prefix, None),
+    };
+    let mut builder = repo.treebuilder(Some(root))?;
+    let replacement = match rest {
+        None => subtree,
+        Some(rest) => {
+            let child = match root.get_name(name) {
+                Some(entry) if entry.kind() == Some(ObjectType::Tree) => repo.find_tree(entry.id())?,
+                _ => repo.find_tree(repo.treebuilder(None)?.write()?)?,
+            };
+            let replaced = repo.find_tree(replace_subtree(repo, &child, rest, subtree)?)?;
+            (!replaced.is_empty()).then(|| replaced.id())
+        }
+    };
+    match replacement {
+        Some(id) => {
+            builder.insert(name, id, 0o040000)?;
+        }
+        None => {
+            if builder.get(name)?.is_some() {
+                builder.remove(name)?;
+            }
+        }
+    }
+    Ok(builder.write()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    fn upstream(library: &TestRepo, squash: bool) -> SubtreeUpstream {
+        SubtreeUpstream { repository: library.url(), reference: DEFAULT_BRANCH.to_string(), squash }
+    }
+
+    fn read(repo: &TestRepo, path: &str) -> String {
+        std::fs::read_to_string(repo.path().join(path)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_pull_and_split_round_trip() {
+        let app = TestRepo::new();
+        app.commit_file("README.md", "app\n", "App");
+        let library = TestRepo::new();
+        let first = library.commit_file("lib.rs", "one\ntwo\n", "Library");
+
+        let rgit = app.core();
+        add(&rgit, "vendor/lib", &upstream(&library, false), None, &Config::default()).await.unwrap();
+        assert_eq!(read(&app, "vendor/lib/lib.rs"), "one\ntwo\n");
+        assert_eq!(remembered_upstream(app.repo(), "vendor/lib"), Some(upstream(&library, false)));
+        let head = app.repo().find_commit(app.head()).unwrap();
+        assert_eq!(head.parent_id(1).unwrap(), first);
+
+        // Local and upstream edits to different lines merge cleanly
+        app.commit_file("vendor/lib/lib.rs", "one\ntwo\nlocal\n", "Patch library");
+        library.commit_file("lib.rs", "ONE\ntwo\n", "Upstream change");
+        let upstream_tip = library.head();
+        let remembered = resolve_upstream(app.repo(), "vendor/lib", None, None, false).unwrap();
+        pull(&rgit, "vendor/lib", &remembered, None, &Config::default()).await.unwrap();
+        assert_eq!(read(&app, "vendor/lib/lib.rs"), "ONE\ntwo\nlocal\n");
+        assert!(app.repo().statuses(None).unwrap().is_empty());
+
+        // The split history builds on upstream's, adding the local patch
+        let head = app.repo().find_commit(app.head()).unwrap();
+        let tip = split(app.repo(), &head, "vendor/lib").unwrap();
+        let tip_commit = app.repo().find_commit(tip).unwrap();
+        assert!(app.repo().graph_descendant_of(tip, upstream_tip).unwrap());
+        assert_eq!(tip_commit.tree().unwrap().get_path(Path::new("lib.rs")).unwrap().id(),
+                   head.tree().unwrap().get_path(Path::new("vendor/lib/lib.rs")).unwrap().id());
+        assert_eq!(split(app.repo(), &head, "vendor/lib").unwrap(), tip);
+        assert!(split(app.repo(), &head, "missing").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_squashed_subtree() {
+        let app = TestRepo::new();
+        app.commit_file("README.md", "app\n", "App");
+        let library = TestRepo::new();
+        library.commit_file("lib.rs", "v1\n", "v1");
+
+        let rgit = app.core();
+        add(&rgit, "lib", &upstream(&library, true), None, &Config::default()).await.unwrap();
+        let squash = app.repo().find_commit(app.head()).unwrap().parent(1).unwrap();
+        assert_eq!(squash.parent_count(), 0);
+        assert!(squash.summary().unwrap().starts_with("Squashed 'lib/' content"));
+
+        library.commit_file("lib.rs", "v2\n", "v2");
+        let remembered = resolve_upstream(app.repo(), "lib", None, None, false).unwrap();
+        assert!(remembered.squash);
+        pull(&rgit, "lib", &remembered, None, &Config::default()).await.unwrap();
+        assert_eq!(read(&app, "lib/lib.rs"), "v2\n");
+        // Nothing new upstream is a no-op
+        let head = app.head();
+        pull(&rgit, "lib", &remembered, None, &Config::default()).await.unwrap();
+        assert_eq!(app.head(), head);
+
+        // Adding over an existing directory is refused
+        assert!(add(&rgit, "lib", &upstream(&library, false), None, &Config::default()).await.is_err());
+        assert_eq!(normalize_prefix("vendor/x/").unwrap(), "vendor/x");
+        assert!(normalize_prefix("../x").is_err());
+    }
+}
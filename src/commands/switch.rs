@@ -0,0 +1,811 @@
+use anyhow::Result;
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, CheckoutNotificationType, Commit, ErrorCode, Oid, Repository, Signature, StashFlags, StatusOptions};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::SwitchArgs;
+use crate::commands::cherry_pick::conflicted_paths;
+use crate::config::Config;
+use crate::core::{resolve_commit, RgitCore};
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+use crate::validation::validate_branch_name;
+
+/// Index mode of submodule entries, whose directories an orphan switch leaves alone
+const GITLINK_MODE: u32 = 0o160000;
+
+/// Files listed before an orphan switch's confirmation summarizes the rest
+const ORPHAN_PREVIEW_LIMIT: usize = 20;
+
+/// Execute the switch command
+pub async fn execute(args: &SwitchArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let target = SwitchTarget::from_args(args)?;
+    let options = SwitchOptions {
+        discard_changes: args.discard_changes,
+        guess: !args.no_guess,
+        track: if args.track { Some(true) } else if args.no_track { Some(false) } else { None },
+        dirty: DirtyTreeAction::from_config(args.merge, config),
+    };
+    if let SwitchTarget::Orphan(name) = &target {
+        confirm_orphan(&rgit.repo, name, &options, config)?;
+    }
+
+    let outcome = switch(&rgit.repo, &target, &options)?;
+    show_switched(&rgit.repo, &outcome);
+    Ok(())
+}
+
+/// Where to move HEAD
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwitchTarget {
+    /// An existing branch, `-` for the previous one, or a remote branch to track
+    Branch(String),
+    /// A new branch at `start` (HEAD when None); `force` resets an existing one
+    Create { name: String, start: Option<String>, force: bool },
+    /// Any commit, leaving HEAD detached
+    Detach(String),
+    /// A new branch with no history, starting from an empty index
+    Orphan(String),
+}
+
+impl SwitchTarget {
+    fn from_args(args: &SwitchArgs) -> Result<Self> {
+        let create = args.create.as_ref().map(|name| (name, false))
+            .or(args.force_create.as_ref().map(|name| (name, true)));
+
+        if let Some(name) = &args.orphan {
+            return Ok(SwitchTarget::Orphan(name.clone()));
+        }
+
+        Ok(match (create, args.target.clone()) {
+            (Some((name, force)), start) => SwitchTarget::Create { name: name.clone(), start, force },
+            (None, Some(target)) if args.detach => SwitchTarget::Detach(target),
+            (None, None) if args.detach => SwitchTarget::Detach("HEAD".to_string()),
+            (None, Some(target)) => SwitchTarget::Branch(target),
+            (None, None) => return Err(RgitError::InvalidArgument(
+                "missing branch to switch to (use -c <name> to create one)".to_string()
+            ).into()),
+        })
+    }
+}
+
+/// How to treat the working tree and new branches
+#[derive(Debug, Clone, Default)]
+pub struct SwitchOptions {
+    /// Overwrite local changes that are in the way
+    pub discard_changes: bool,
+    /// Create a tracking branch when the name only exists on one remote
+    pub guess: bool,
+    /// Force (`Some(true)`) or suppress (`Some(false)`) upstream tracking for new branches
+    pub track: Option<bool>,
+    /// What to do when local changes are in the way
+    pub dirty: DirtyTreeAction,
+}
+
+/// What a switch did
+#[derive(Debug, Clone, PartialEq)]
+pub enum Switched {
+    /// HEAD already was on this branch
+    AlreadyOn(String),
+    /// HEAD now points at a branch
+    Branch { name: String, created: bool, reset_from: Option<Oid>, upstream: Option<String> },
+    /// HEAD is detached at a commit
+    Detached(Oid),
+    /// HEAD is on a branch without commits; `removed` tracked files were cleared
+    Orphan { name: String, removed: usize },
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+/// The commit to check out and what to do with branches around it
+struct Plan<'r> {
+    commit: Commit<'r>,
+    /// Branch HEAD ends up on, or None for a detached HEAD
+    branch: Option<String>,
+    /// Whether the branch must be created (or reset) first
+    create: bool,
+    force: bool,
+    upstream: Option<String>,
+}
+
+fn plan<'r>(repo: &'r Repository, target: &SwitchTarget, options: &SwitchOptions) -> Result<Plan<'r>> {
+    match target {
+        SwitchTarget::Branch(name) if name == "-" => {
+            let (object, reference) = repo.revparse_ext("@{-1}")
+                .map_err(|_| RgitError::InvalidReference("no previous branch to switch back to".to_string()))?;
+            match reference.as_ref().filter(|r| r.is_branch()).and_then(|r| r.shorthand()) {
+                Some(previous) => plan(repo, &SwitchTarget::Branch(previous.to_string()), options),
+                None => Err(not_a_branch("-", &object.peel_to_commit()?)),
+            }
+        }
+        SwitchTarget::Branch(name) => {
+            if let Ok(branch) = repo.find_branch(name, BranchType::Local) {
+                return Ok(Plan {
+                    commit: branch.get().peel_to_commit()?,
+                    branch: Some(name.clone()),
+                    create: false,
+                    force: false,
+                    upstream: None,
+                });
+            }
+
+            if options.guess {
+                if let Some(remote_branch) = guess_remote_branch(repo, name)? {
+                    let start = SwitchTarget::Create { name: name.clone(), start: Some(remote_branch), force: false };
+                    return plan(repo, &start, options);
+                }
+            }
+
+            match resolve_commit(repo, name) {
+                Ok(commit) => Err(not_a_branch(name, &commit)),
+                Err(_) => Err(RgitError::BranchNotFound(name.clone()).into()),
+            }
+        }
+        SwitchTarget::Create { name, start, force } => {
+            validate_branch_name(name)?;
+            if !force && repo.find_branch(name, BranchType::Local).is_ok() {
+                return Err(RgitError::BranchAlreadyExists(name.clone()).into());
+            }
+
+            let start = start.as_deref().unwrap_or("HEAD");
+            let commit = resolve_commit(repo, start)?;
+            let upstream = match options.track {
+                Some(false) => None,
+                Some(true) if start != "HEAD" => Some(start.to_string()),
+                Some(true) => return Err(RgitError::InvalidArgument(
+                    "--track needs a start point to track".to_string()
+                ).into()),
+                None => repo.find_branch(start, BranchType::Remote).ok().map(|_| start.to_string()),
+            };
+
+            Ok(Plan { commit, branch: Some(name.clone()), create: true, force: *force, upstream })
+        }
+        SwitchTarget::Detach(spec) => Ok(Plan {
+            commit: resolve_commit(repo, spec)?,
+            branch: None,
+            create: false,
+            force: false,
+            upstream: None,
+        }),
+        SwitchTarget::Orphan(name) => Err(RgitError::InvalidArgument(
+            format!("orphan branch '{}' has no commit to plan a checkout for", name)
+        ).into()),
+    }
+}
+
+/// The `<remote>/<name>` branch when exactly one remote has `name`
+pub fn guess_remote_branch(repo: &Repository, name: &str) -> Result<Option<String>> {
+    let mut candidates = Vec::new();
+    for remote in repo.remotes()?.iter().flatten() {
+        let candidate = format!("{}/{}", remote, name);
+        if repo.find_branch(&candidate, BranchType::Remote).is_ok() {
+            candidates.push(candidate);
+        }
+    }
+
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(candidates.pop()),
+        _ => Err(RgitError::InvalidReference(format!(
+            "'{}' exists on several remotes ({}); use rgit switch -c {} <remote>/{}",
+            name, candidates.join(", "), name, name
+        )).into()),
+    }
+}
+
+fn not_a_branch(spec: &str, commit: &Commit) -> anyhow::Error {
+    RgitError::CheckoutFailed(format!(
+        "'{}' is not a branch; use --detach to check out commit {}",
+        spec, shorten_oid(&commit.id(), 8)
+    )).into()
+}
+
+// =============================================================================
+// Switching
+// =============================================================================
+
+/// What a switch did, including what happened to local changes in the way
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchOutcome {
+    pub switched: Switched,
+    pub carried: Option<CarriedChanges>,
+}
+
+/// Local changes that were stashed and reapplied around a switch
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarriedChanges {
+    pub action: DirtyTreeAction,
+    /// Files left with conflict markers
+    pub conflicts: Vec<String>,
+    /// Whether `stash@{0}` still holds the changes
+    pub kept_in_stash: bool,
+}
+
+/// Move HEAD to `target`, updating the index and working tree.
+///
+/// Local changes are carried along. When the switch would overwrite some of
+/// them, `options.dirty` decides whether to stash and reapply them or give up
+/// before anything changes.
+pub fn switch(repo: &Repository, target: &SwitchTarget, options: &SwitchOptions) -> Result<SwitchOutcome> {
+    if let SwitchTarget::Orphan(name) = target {
+        let switched = switch_orphan(repo, name, options.discard_changes)?;
+        return Ok(SwitchOutcome { switched, carried: None });
+    }
+
+    let plan = plan(repo, target, options)?;
+    let head = repo.head().ok();
+    let current_branch = head.as_ref()
+        .filter(|h| h.is_branch())
+        .and_then(|h| h.shorthand().map(String::from));
+
+    if let (false, Some(name)) = (plan.create, &plan.branch) {
+        if current_branch.as_ref() == Some(name) {
+            return Ok(SwitchOutcome { switched: Switched::AlreadyOn(name.clone()), carried: None });
+        }
+    }
+
+    let mut stashed = None;
+    let blocked = checkout_commit(repo, &plan.commit, options.discard_changes)?;
+    if !blocked.is_empty() {
+        let destination = plan.branch.clone().unwrap_or_else(|| shorten_oid(&plan.commit.id(), 8));
+        let action = match options.dirty {
+            DirtyTreeAction::Ask => choose_action(&destination, &blocked)?,
+            action => action,
+        };
+        match action {
+            DirtyTreeAction::Abort if options.dirty == DirtyTreeAction::Ask => {
+                return Err(RgitError::OperationCancelled.into());
+            }
+            DirtyTreeAction::Abort | DirtyTreeAction::Ask => return Err(blocked_error(&blocked)),
+            DirtyTreeAction::Stash | DirtyTreeAction::Merge => {}
+        }
+
+        stash_local_changes(repo, &destination, &blocked)?;
+        let still_blocked = checkout_commit(repo, &plan.commit, false)?;
+        if !still_blocked.is_empty() {
+            reapply_stash(repo, DirtyTreeAction::Stash)?;
+            return Err(blocked_error(&still_blocked));
+        }
+        stashed = Some(action);
+    }
+
+    let switched = move_head(repo, plan)?;
+    let carried = match stashed {
+        Some(action) => Some(reapply_stash(repo, action)?),
+        None => None,
+    };
+    Ok(SwitchOutcome { switched, carried })
+}
+
+/// Point HEAD at the planned branch or commit, creating the branch if needed
+fn move_head(repo: &Repository, plan: Plan) -> Result<Switched> {
+    let Some(name) = plan.branch else {
+        repo.set_head_detached(plan.commit.id())?;
+        return Ok(Switched::Detached(plan.commit.id()));
+    };
+
+    let refname = format!("refs/heads/{}", name);
+    let mut reset_from = None;
+    if plan.create {
+        if let Ok(existing) = repo.find_reference(&refname) {
+            let old = existing.peel_to_commit()?.id();
+            if old != plan.commit.id() {
+                Journal::for_repo(repo).record(&JournalEntry::new("switch --force-create", &refname)
+                    .with_old_oid(old)
+                    .with_new_oid(plan.commit.id()))?;
+                reset_from = Some(old);
+            }
+        }
+        repo.reference(&refname, plan.commit.id(), plan.force, "branch: Created by rgit switch")?;
+        if let Some(upstream) = &plan.upstream {
+            repo.find_branch(&name, BranchType::Local)?.set_upstream(Some(upstream))?;
+        }
+    }
+
+    repo.set_head(&refname)?;
+    Ok(Switched::Branch { name, created: plan.create && reset_from.is_none(), reset_from, upstream: plan.upstream })
+}
+
+/// Check out `commit` without touching HEAD.
+///
+/// Returns the paths whose local changes block the checkout, in which case
+/// nothing was changed. With `discard_changes` nothing blocks.
+fn checkout_commit(repo: &Repository, commit: &Commit, discard_changes: bool) -> Result<Vec<String>> {
+    let mut conflicts = Vec::new();
+    let result = {
+        let mut checkout = CheckoutBuilder::new();
+        if discard_changes {
+            checkout.force();
+        } else {
+            checkout.safe()
+                .notify_on(CheckoutNotificationType::CONFLICT)
+                .notify(|_, path, _, _, _| {
+                    if let Some(path) = path {
+                        conflicts.push(path.to_string_lossy().to_string());
+                    }
+                    true
+                });
+        }
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+    };
+
+    match result {
+        Ok(()) => Ok(Vec::new()),
+        Err(e) if e.code() == ErrorCode::Conflict || !conflicts.is_empty() => {
+            if conflicts.is_empty() {
+                conflicts.push(e.message().to_string());
+            }
+            Ok(conflicts)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn blocked_error(blocked: &[String]) -> anyhow::Error {
+    show_blocked(blocked);
+    ui_println!("\n{} Use {} to carry them over, {} to throw them away,",
+            "💡".blue(), "--merge".cyan(), "--discard-changes".cyan());
+    println!("   or {} to stash them automatically", "rgit config set checkout.autostash true".cyan());
+    RgitError::CheckoutFailed(format!("local changes would be overwritten: {}", blocked.join(", "))).into()
+}
+
+fn show_blocked(blocked: &[String]) {
+    ui_println!("{} Local changes to these files would be overwritten:", "⚠️".yellow());
+    for path in blocked {
+        ui_println!("  {} {}", "•".red(), path);
+    }
+}
+
+// =============================================================================
+// Local changes in the way
+// =============================================================================
+
+/// What to do when local changes block a switch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DirtyTreeAction {
+    /// Refuse to switch
+    #[default]
+    Abort,
+    /// Stash the changes, switch and reapply them, keeping the stash on conflicts
+    Stash,
+    /// Carry the changes over with a three-way merge, leaving conflict markers
+    Merge,
+    /// Ask which of the above to do
+    Ask,
+}
+
+impl DirtyTreeAction {
+    /// `--merge` wins; otherwise ask when prompts are available, else follow `checkout.autostash`
+    pub fn from_config(merge: bool, config: &Config) -> Self {
+        if merge {
+            DirtyTreeAction::Merge
+        } else if config.is_interactive() {
+            DirtyTreeAction::Ask
+        } else if config.checkout.autostash {
+            DirtyTreeAction::Stash
+        } else {
+            DirtyTreeAction::Abort
+        }
+    }
+}
+
+fn choose_action(destination: &str, blocked: &[String]) -> Result<DirtyTreeAction> {
+    show_blocked(blocked);
+    println!();
+
+    const ACTIONS: [DirtyTreeAction; 3] = [DirtyTreeAction::Stash, DirtyTreeAction::Merge, DirtyTreeAction::Abort];
+    let options = [
+        "Stash them, switch, and reapply them (kept in the stash if that conflicts)".to_string(),
+        "Merge them into the new branch, leaving conflict markers to resolve".to_string(),
+        "Abort and stay where I am".to_string(),
+    ];
+    let selection = InteractivePrompt::new()
+        .with_message(format!("Switch to '{}' anyway?", destination))
+        .with_options(&options)
+        .with_default(0)
+        .select()?;
+
+    Ok(ACTIONS[selection])
+}
+
+/// Stash tracked changes, plus untracked files when they are what's in the way
+fn stash_local_changes(repo: &Repository, destination: &str, blocked: &[String]) -> Result<()> {
+    let untracked = blocked.iter()
+        .any(|path| repo.status_file(Path::new(path)).map(|s| s.is_wt_new()).unwrap_or(false));
+    let mut flags = StashFlags::DEFAULT;
+    if untracked {
+        flags |= StashFlags::INCLUDE_UNTRACKED;
+    }
+
+    let mut stash_repo = Repository::open(repo.path())?;
+    let signature = stash_repo.signature()
+        .or_else(|_| Signature::now("rgit", "rgit@localhost"))?;
+    stash_repo.stash_save(&signature, &format!("rgit autostash before switching to {}", destination), Some(flags))?;
+    ui_println!("{} Stashed local changes", "📦".blue());
+    Ok(())
+}
+
+/// Reapply `stash@{0}` after switching, dropping it unless it is still needed
+fn reapply_stash(repo: &Repository, action: DirtyTreeAction) -> Result<CarriedChanges> {
+    let mut stash_repo = Repository::open(repo.path())?;
+    if let Err(e) = stash_repo.stash_apply(0, None) {
+        ui_println!("{} Could not reapply local changes: {}", "⚠️".yellow(), e.message());
+        return Ok(CarriedChanges { action, conflicts: Vec::new(), kept_in_stash: true });
+    }
+
+    let mut index = stash_repo.index()?;
+    index.read(true)?;
+    let conflicts = conflicted_paths(&index)?;
+    let kept_in_stash = !conflicts.is_empty() && action == DirtyTreeAction::Stash;
+    if !kept_in_stash {
+        stash_repo.stash_drop(0)?;
+    }
+    Ok(CarriedChanges { action, conflicts, kept_in_stash })
+}
+
+// =============================================================================
+// Orphan branches
+// =============================================================================
+
+/// What starting an orphan branch would do to the working tree
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanPreview {
+    /// Tracked files that are removed from the index and the working tree
+    pub removed: Vec<String>,
+    /// Files with uncommitted changes that would be lost
+    pub changed: Vec<String>,
+}
+
+pub fn preview_orphan(repo: &Repository) -> Result<OrphanPreview> {
+    let removed = repo.index()?.iter()
+        .filter(|entry| entry.mode != GITLINK_MODE)
+        .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+        .collect();
+
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).exclude_submodules(true);
+    let changed = timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?.iter()
+        .filter_map(|entry| entry.path().map(String::from))
+        .collect();
+    Ok(OrphanPreview { removed, changed })
+}
+
+/// Show what `--orphan` will do and, when prompts are allowed, ask first
+pub fn confirm_orphan(repo: &Repository, name: &str, options: &SwitchOptions, config: &Config) -> Result<()> {
+    validate_branch_name(name)?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(RgitError::BranchAlreadyExists(name.to_string()).into());
+    }
+
+    let preview = preview_orphan(repo)?;
+    ui_println!("{} '{}' will start a new history with no commits", "🌱".green(), name.cyan());
+    if preview.removed.is_empty() {
+        println!("  The index is already empty");
+    } else {
+        println!("  {} tracked file{} will be removed from the index and the working tree:",
+                preview.removed.len(), if preview.removed.len() == 1 { "" } else { "s" });
+        for path in preview.removed.iter().take(ORPHAN_PREVIEW_LIMIT) {
+            println!("    {} {}", "-".red(), path);
+        }
+        if preview.removed.len() > ORPHAN_PREVIEW_LIMIT {
+            ui_println!("    … and {} more", preview.removed.len() - ORPHAN_PREVIEW_LIMIT);
+        }
+        println!("  They stay committed on the current branch. Untracked and ignored files are kept.");
+    }
+    if !preview.changed.is_empty() {
+        if !options.discard_changes {
+            show_blocked(&preview.changed);
+            ui_println!("\n{} Commit or stash them first, or pass {} to throw them away",
+                    "💡".blue(), "--discard-changes".cyan());
+            return Err(RgitError::BranchHasUncommittedChanges.into());
+        }
+        println!("  {} uncommitted change{} will be discarded",
+                preview.changed.len(), if preview.changed.len() == 1 { "" } else { "s" });
+    }
+
+    if config.is_interactive() && !preview.removed.is_empty() {
+        let proceed = InteractivePrompt::new()
+            .with_message(format!("Create orphan branch '{}'?", name))
+            .confirm()?;
+        if !proceed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+    Ok(())
+}
+
+/// Point HEAD at a branch that doesn't exist yet and clear every tracked
+/// file, so the next commit starts a new root. Untracked files are kept.
+fn switch_orphan(repo: &Repository, name: &str, discard_changes: bool) -> Result<Switched> {
+    validate_branch_name(name)?;
+    let refname = format!("refs/heads/{}", name);
+    if repo.find_reference(&refname).is_ok() {
+        return Err(RgitError::BranchAlreadyExists(name.to_string()).into());
+    }
+    let preview = preview_orphan(repo)?;
+    if !preview.changed.is_empty() && !discard_changes {
+        return Err(RgitError::BranchHasUncommittedChanges.into());
+    }
+    let workdir = repo.workdir()
+        .ok_or_else(|| RgitError::InvalidRepositoryState("bare repository has no working tree".to_string()))?;
+
+    for path in &preview.removed {
+        let full = workdir.join(path);
+        if full.symlink_metadata().is_ok() {
+            fs::remove_file(&full)?;
+        }
+        // Drop directories the removal left empty
+        for dir in full.ancestors().skip(1).take_while(|dir| *dir != workdir) {
+            if fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+
+    let mut index = repo.index()?;
+    index.clear()?;
+    timings::time(Phase::IndexWrite, || index.write())?;
+    repo.set_head(&refname)?;
+    Ok(Switched::Orphan { name: name.to_string(), removed: preview.removed.len() })
+}
+
+// =============================================================================
+// Output
+// =============================================================================
+
+pub fn show_switched(repo: &Repository, outcome: &SwitchOutcome) {
+    match &outcome.switched {
+        Switched::AlreadyOn(name) => {
+            ui_println!("{} Already on '{}'", "ℹ️".blue(), name.cyan());
+        }
+        Switched::Branch { name, created, reset_from, upstream } => {
+            if let Some(old) = reset_from {
+                ui_println!("{} Reset branch '{}' (was {})", "🔄".green().bold(), name.cyan(), shorten_oid(old, 8).yellow());
+            } else if *created {
+                ui_println!("{} Switched to a new branch '{}'", "✅".green().bold(), name.cyan());
+            } else {
+                ui_println!("{} Switched to branch '{}'", "✅".green().bold(), name.cyan());
+            }
+            if let Some(upstream) = upstream {
+                ui_println!("{} Branch '{}' set up to track '{}'", "🔗".blue(), name.cyan(), upstream.cyan());
+            }
+        }
+        Switched::Orphan { name, removed } => {
+            ui_println!("{} Switched to a new orphan branch '{}'", "✅".green().bold(), name.cyan());
+            if *removed > 0 {
+                ui_println!("{} Cleared {} tracked file{}", "🧹".blue(), removed, if *removed == 1 { "" } else { "s" });
+            }
+            ui_println!("{} Add files and {} to make the branch's first commit", "💡".blue(), "rgit commit".cyan());
+        }
+        Switched::Detached(oid) => {
+            let summary = repo.find_commit(*oid).ok()
+                .and_then(|c| c.summary().map(String::from))
+                .unwrap_or_default();
+            ui_println!("{} HEAD is now detached at {} {}", "⚠️".yellow(), shorten_oid(oid, 8).yellow(), summary);
+            ui_println!("{} To keep commits made from here, create a branch: {}",
+                    "💡".blue(), "rgit switch -c <name>".cyan());
+        }
+    }
+
+    let Some(carried) = &outcome.carried else { return };
+    if carried.conflicts.is_empty() && !carried.kept_in_stash {
+        ui_println!("{} Reapplied your local changes", "♻️".green());
+        return;
+    }
+    if !carried.conflicts.is_empty() {
+        ui_println!("{} Your local changes conflict with the new branch:", "⚠️".yellow());
+        for path in &carried.conflicts {
+            ui_println!("  {} {}", "•".red(), path);
+        }
+        ui_println!("{} Resolve the conflict markers with {}", "💡".blue(), "rgit resolve".cyan());
+    }
+    if carried.kept_in_stash {
+        ui_println!("{} Your changes are kept in {} until you drop it with {}",
+                "📦".blue(), "stash@{0}".cyan(), "rgit stash drop".cyan());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn branch(name: &str) -> SwitchTarget {
+        SwitchTarget::Branch(name.to_string())
+    }
+
+    fn create(name: &str, start: Option<&str>, force: bool) -> SwitchTarget {
+        SwitchTarget::Create { name: name.to_string(), start: start.map(String::from), force }
+    }
+
+    fn current(repo: &TestRepo) -> String {
+        repo.repo().head().unwrap().shorthand().unwrap().to_string()
+    }
+
+    fn read(repo: &TestRepo, path: &str) -> String {
+        std::fs::read_to_string(repo.path().join(path)).unwrap()
+    }
+
+    fn stash_count(repo: &TestRepo) -> usize {
+        let mut handle = Repository::open(repo.path()).unwrap();
+        let mut count = 0;
+        handle.stash_foreach(|_, _, _| { count += 1; true }).unwrap();
+        count
+    }
+
+    /// `other` and `main` differ in `file.txt`, which has an uncommitted edit
+    /// on its first line
+    fn blocked_repo(other_content: &str) -> TestRepo {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "a\nb\nc\n", "Initial");
+        repo.branch("other").checkout("other");
+        repo.commit_file("file.txt", other_content, "Other");
+        repo.checkout("main");
+        repo.commit_file("file.txt", "a\nb\nc\nd\n", "Main");
+        repo.write("file.txt", "local\nb\nc\nd\n");
+        repo
+    }
+
+    #[test]
+    fn test_switch_between_branches() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "main\n", "Initial");
+        let options = SwitchOptions::default();
+
+        let switched = switch(repo.repo(), &create("feature", None, false), &options).unwrap().switched;
+        assert!(matches!(switched, Switched::Branch { created: true, .. }));
+        repo.commit_file("feature.txt", "feature\n", "Feature work");
+
+        switch(repo.repo(), &branch("main"), &options).unwrap();
+        assert_eq!(current(&repo), "main");
+        assert!(!repo.path().join("feature.txt").exists());
+
+        switch(repo.repo(), &branch("-"), &options).unwrap();
+        assert_eq!(current(&repo), "feature");
+        assert_eq!(switch(repo.repo(), &branch("feature"), &options).unwrap().switched,
+                   Switched::AlreadyOn("feature".to_string()));
+
+        assert!(switch(repo.repo(), &create("feature", None, false), &options).is_err());
+        assert!(switch(repo.repo(), &create("bad..name", None, false), &options).is_err());
+        assert!(switch(repo.repo(), &branch("missing"), &options).is_err());
+    }
+
+    #[test]
+    fn test_switch_refuses_to_overwrite_changes() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "one\n", "Initial");
+        repo.branch("other");
+        repo.commit_file("file.txt", "two\n", "Second");
+        repo.write("file.txt", "local\n");
+
+        let error = switch(repo.repo(), &branch("other"), &SwitchOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("file.txt"));
+        assert_eq!(current(&repo), "main");
+        assert_eq!(std::fs::read_to_string(repo.path().join("file.txt")).unwrap(), "local\n");
+
+        // Changes to files the switch doesn't touch come along
+        repo.write("file.txt", "two\n").write("untouched.txt", "new\n");
+        switch(repo.repo(), &branch("other"), &SwitchOptions::default()).unwrap();
+        assert!(repo.path().join("untouched.txt").exists());
+
+        repo.write("file.txt", "local\n");
+        let discard = SwitchOptions { discard_changes: true, ..SwitchOptions::default() };
+        switch(repo.repo(), &branch("main"), &discard).unwrap();
+        assert_eq!(std::fs::read_to_string(repo.path().join("file.txt")).unwrap(), "two\n");
+    }
+
+    #[test]
+    fn test_switch_detach_and_force_create() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        let options = SwitchOptions::default();
+
+        assert!(switch(repo.repo(), &branch(&first.to_string()), &options).is_err());
+        assert_eq!(switch(repo.repo(), &SwitchTarget::Detach(first.to_string()), &options).unwrap().switched,
+                   Switched::Detached(first));
+        assert!(repo.repo().head_detached().unwrap());
+
+        // --force-create moves an existing branch and journals where it was
+        let switched = switch(repo.repo(), &create("main", None, true), &options).unwrap().switched;
+        assert!(matches!(switched, Switched::Branch { reset_from: Some(old), .. } if old == second));
+        assert_eq!(repo.head(), first);
+        let entries = Journal::for_repo(repo.repo()).entries().unwrap();
+        assert_eq!(entries.last().unwrap().old_oid, Some(second.to_string()));
+    }
+
+    #[test]
+    fn test_switch_guesses_remote_tracking_branch() {
+        let repo = TestRepo::new();
+        let oid = repo.commit_file("file.txt", "one\n", "Initial");
+        let _remote = repo.add_remote("origin");
+        repo.repo().reference("refs/remotes/origin/topic", oid, false, "test").unwrap();
+
+        let options = SwitchOptions { guess: true, ..SwitchOptions::default() };
+        let switched = switch(repo.repo(), &branch("topic"), &options).unwrap().switched;
+        assert_eq!(switched, Switched::Branch {
+            name: "topic".to_string(),
+            created: true,
+            reset_from: None,
+            upstream: Some("origin/topic".to_string()),
+        });
+        let upstream = repo.repo().find_branch("topic", BranchType::Local).unwrap().upstream().unwrap();
+        assert_eq!(upstream.name().unwrap(), Some("origin/topic"));
+    }
+
+    #[test]
+    fn test_autostash_reapplies_changes() {
+        let repo = blocked_repo("a\nb\nc\n");
+        let options = SwitchOptions { dirty: DirtyTreeAction::Stash, ..SwitchOptions::default() };
+
+        let outcome = switch(repo.repo(), &branch("other"), &options).unwrap();
+        assert_eq!(current(&repo), "other");
+        assert_eq!(read(&repo, "file.txt"), "local\nb\nc\n");
+        assert_eq!(outcome.carried, Some(CarriedChanges {
+            action: DirtyTreeAction::Stash,
+            conflicts: Vec::new(),
+            kept_in_stash: false,
+        }));
+        assert_eq!(stash_count(&repo), 0);
+    }
+
+    #[test]
+    fn test_conflicting_changes_keep_stash_or_markers() {
+        // Stash keeps the stash entry as a safety net
+        let repo = blocked_repo("other\nb\nc\n");
+        let options = SwitchOptions { dirty: DirtyTreeAction::Stash, ..SwitchOptions::default() };
+        let carried = switch(repo.repo(), &branch("other"), &options).unwrap().carried.unwrap();
+        assert_eq!(carried.conflicts, vec!["file.txt"]);
+        assert!(carried.kept_in_stash);
+        assert!(read(&repo, "file.txt").contains("<<<<<<<"));
+        assert_eq!(stash_count(&repo), 1);
+
+        // Merge leaves only the conflict markers
+        let repo = blocked_repo("other\nb\nc\n");
+        let options = SwitchOptions { dirty: DirtyTreeAction::Merge, ..SwitchOptions::default() };
+        let carried = switch(repo.repo(), &branch("other"), &options).unwrap().carried.unwrap();
+        assert!(!carried.kept_in_stash);
+        assert_eq!(stash_count(&repo), 0);
+    }
+
+    #[test]
+    fn test_switch_orphan_clears_tracked_files() {
+        let repo = TestRepo::new();
+        repo.commit_file("src/lib.rs", "fn main() {}\n", "Initial commit");
+        repo.write("notes.txt", "untracked\n");
+        repo.write("src/lib.rs", "changed\n");
+
+        let options = SwitchOptions::default();
+        assert!(switch(repo.repo(), &SwitchTarget::Orphan("gh-pages".to_string()), &options).is_err());
+        assert_eq!(current(&repo), "main");
+
+        let options = SwitchOptions { discard_changes: true, ..Default::default() };
+        let outcome = switch(repo.repo(), &SwitchTarget::Orphan("gh-pages".to_string()), &options).unwrap();
+        assert_eq!(outcome.switched, Switched::Orphan { name: "gh-pages".to_string(), removed: 1 });
+
+        assert_eq!(repo.repo().find_reference("HEAD").unwrap().symbolic_target(), Some("refs/heads/gh-pages"));
+        assert!(repo.repo().head().is_err());
+        assert!(repo.repo().index().unwrap().is_empty());
+        assert!(!repo.path().join("src").exists());
+        assert_eq!(read(&repo, "notes.txt"), "untracked\n");
+        assert!(repo.repo().find_branch("main", BranchType::Local).is_ok());
+    }
+
+    #[test]
+    fn test_dirty_tree_action_from_config() {
+        let mut config = Config::default();
+        config.ui.interactive = false;
+        assert_eq!(DirtyTreeAction::from_config(false, &config), DirtyTreeAction::Abort);
+        assert_eq!(DirtyTreeAction::from_config(true, &config), DirtyTreeAction::Merge);
+
+        config.set_value("checkout.autostash", "true").unwrap();
+        assert_eq!(DirtyTreeAction::from_config(false, &config), DirtyTreeAction::Stash);
+    }
+}
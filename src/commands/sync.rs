@@ -2,16 +2,24 @@ use anyhow::Result;
 use colored::*;
 use git2::*;
 
-use crate::cli::SyncArgs;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::cli::{FetchArgs, SyncArgs};
 use crate::config::Config;
 use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::{InteractivePrompt, ProgressDisplay};
+use crate::journal::{Journal, JournalEntry};
 use crate::submodule::SubmoduleManager;
+use crate::commands::fetch::fetch_remote_with_options;
 use crate::commands::status::{quick_status_check, StatusSummary};
 
 /// Execute the sync command - intelligent pull + push workflow
 pub async fn execute(args: &SyncArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.all {
+        return sync_all_branches(rgit, config, args.dry_run).await;
+    }
     rgit.log("Starting sync operation...");
     
     // Pre-sync validation
@@ -61,6 +69,7 @@ async fn validate_sync_prerequisites(
     // Check for uncommitted changes
     let status = rgit.status()?;
     if !status.is_clean() && !args.pull_only {
+        #[allow(invalid_reference_casting)]
         unsafe {
             handle_uncommitted_changes(&mut *(rgit as *const _ as *mut _), config, &status).await?
         };
@@ -125,11 +134,11 @@ async fn handle_uncommitted_changes(
     }
     
     // Show current changes
-    println!("{} Current changes:", "📋".blue());
+    ui_println!("{} Current changes:", "📋".blue());
     let total_changes = status.total_changes();
-    println!("  {} {} staged", "📦".green(), status.staged.len());
-    println!("  {} {} unstaged", "📝".yellow(), status.unstaged.len());
-    println!("  {} {} untracked", "❓".red(), status.untracked.len());
+    ui_println!("  {} {} staged", "📦".green(), status.staged.len());
+    ui_println!("  {} {} unstaged", "📝".yellow(), status.unstaged.len());
+    ui_println!("  {} {} untracked", "❓".red(), status.untracked.len());
     
     let options = vec![
         "Stash changes and continue",
@@ -166,7 +175,7 @@ async fn stash_changes_for_sync(rgit: &mut RgitCore) -> Result<()> {
     rgit.log("Stashing changes for sync...");
 
     // Get signature first, then drop immutable borrow before mutable borrow
-    let signature = rgit.get_signature()?;
+    let signature = rgit.get_signature()?.to_owned();
     let stash_message = format!(
         "rgit sync auto-stash on {}",
         chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
@@ -223,7 +232,7 @@ async fn show_pre_sync_status(rgit: &RgitCore, config: &Config) -> Result<()> {
     let status_summary = quick_status_check(rgit)?;
     let branch_info = rgit.get_branch_info()?;
     
-    println!("{} Pre-sync status:", "📊".blue().bold());
+    ui_println!("{} Pre-sync status:", "📊".blue().bold());
     println!("   {} {}", "Branch:".bold(), branch_info.name.cyan());
     
     if let Some(ref upstream) = branch_info.upstream {
@@ -265,14 +274,14 @@ async fn perform_dry_run_sync(
     config: &Config, 
     args: &SyncArgs
 ) -> Result<SyncResult> {
-    println!("{} Dry run mode - showing what would happen:", "🔍".blue().bold());
+    ui_println!("{} Dry run mode - showing what would happen:", "🔍".blue().bold());
     
     let mut result = SyncResult::default();
     
     if !args.push_only {
-        println!("\n{} Pull phase:", "⬇️".blue());
+        ui_println!("\n{} Pull phase:", "⬇️".blue());
         let pull_result = simulate_pull(rgit, config).await?;
-        println!("  {} Would fetch {} commit{}", 
+        ui_println!("  {} Would fetch {} commit{}", 
                 "•".blue(),
                 pull_result.commits_fetched,
                 if pull_result.commits_fetched == 1 { "" } else { "s" });
@@ -280,16 +289,16 @@ async fn perform_dry_run_sync(
     }
     
     if !args.pull_only {
-        println!("\n{} Push phase:", "⬆️".blue());
+        ui_println!("\n{} Push phase:", "⬆️".blue());
         let push_result = simulate_push(rgit, config).await?;
-        println!("  {} Would push {} commit{}", 
+        ui_println!("  {} Would push {} commit{}", 
                 "•".blue(),
                 push_result.commits_pushed,
                 if push_result.commits_pushed == 1 { "" } else { "s" });
         result.push_result = Some(push_result);
     }
     
-    println!("\n{} No actual changes were made", "ℹ️".blue());
+    ui_println!("\n{} No actual changes were made", "ℹ️".blue());
     Ok(result)
 }
 
@@ -531,25 +540,25 @@ async fn show_sync_results(
         return Ok(());
     }
     
-    println!("\n{} Sync completed!", "🎉".green().bold());
+    ui_println!("\n{} Sync completed!", "🎉".green().bold());
     
     // Pull results
     if let Some(ref pull) = result.pull_result {
         if pull.commits_fetched > 0 {
-            println!("   {} Pulled {} commit{}", 
+            ui_println!("   {} Pulled {} commit{}", 
                     "⬇️".blue(),
                     pull.commits_fetched,
                     if pull.commits_fetched == 1 { "" } else { "s" });
             
             if pull.fast_forward {
-                println!("      {} Fast-forward merge", "⚡".green());
+                ui_println!("      {} Fast-forward merge", "⚡".green());
             }
         } else {
-            println!("   {} Already up to date", "⬇️".blue());
+            ui_println!("   {} Already up to date", "⬇️".blue());
         }
         
         if !pull.conflicts.is_empty() {
-            println!("   {} {} conflict{} resolved", 
+            ui_println!("   {} {} conflict{} resolved", 
                     "⚔️".yellow(),
                     pull.conflicts.len(),
                     if pull.conflicts.len() == 1 { "" } else { "s" });
@@ -560,15 +569,15 @@ async fn show_sync_results(
     if let Some(ref push) = result.push_result {
         if push.success {
             if push.commits_pushed > 0 {
-                println!("   {} Pushed {} commit{}", 
+                ui_println!("   {} Pushed {} commit{}", 
                         "⬆️".blue(),
                         push.commits_pushed,
                         if push.commits_pushed == 1 { "" } else { "s" });
             } else {
-                println!("   {} Nothing to push", "⬆️".blue());
+                ui_println!("   {} Nothing to push", "⬆️".blue());
             }
         } else {
-            println!("   {} Push failed", "⬆️".red());
+            ui_println!("   {} Push failed", "⬆️".red());
         }
     }
     
@@ -579,6 +588,209 @@ async fn show_sync_results(
     Ok(())
 }
 
+// =============================================================================
+// All Branches
+// =============================================================================
+
+/// What `sync --all` did with one branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum BranchOutcome {
+    /// Fast-forwarded by this many commits
+    Advanced(usize),
+    UpToDate,
+    /// Has this many commits the upstream lacks; pushing them is left to the user
+    Ahead(usize),
+    Diverged { ahead: usize, behind: usize },
+    Skipped(String),
+}
+
+#[derive(Debug, Clone)]
+struct BranchSync {
+    branch: String,
+    upstream: String,
+    outcome: BranchOutcome,
+}
+
+/// Fetch the remotes local branches track, then fast-forward every branch
+/// that is only behind its upstream
+async fn sync_all_branches(rgit: &RgitCore, config: &Config, dry_run: bool) -> Result<()> {
+    let repo = &rgit.repo;
+    let remotes = upstream_remotes(repo)?;
+
+    let mut failed = BTreeSet::new();
+    for remote in &remotes {
+        if dry_run {
+            ui_println!("{} Not fetching {} in a dry run; comparing with what was fetched last", "ℹ️".blue(), remote.cyan());
+            continue;
+        }
+        ui_println!("{} Fetching {}", "📡".blue(), remote.cyan());
+        if let Err(e) = fetch_remote_with_options(repo, remote, &FetchArgs::default(), config).await {
+            ui_println!("  {} {}", "❌".red(), e);
+            failed.insert(remote.clone());
+        }
+    }
+
+    let results = fast_forward_branches(repo, &failed, dry_run)?;
+    show_branch_syncs(&results, dry_run);
+    Ok(())
+}
+
+/// Remotes that local branches track, without `.` for local upstreams
+fn upstream_remotes(repo: &Repository) -> Result<BTreeSet<String>> {
+    let mut remotes = BTreeSet::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let Some(refname) = branch.get().name() else { continue };
+        if let Some(remote) = repo.branch_upstream_remote(refname).ok().and_then(|buf| buf.as_str().map(str::to_string)) {
+            if remote != "." {
+                remotes.insert(remote);
+            }
+        }
+    }
+    Ok(remotes)
+}
+
+/// Compare every branch that has an upstream with it, fast-forwarding those
+/// that are only behind. The checked-out branch is skipped when it has local
+/// changes, and branches checked out in other worktrees are left alone.
+fn fast_forward_branches(repo: &Repository, failed_remotes: &BTreeSet<String>, dry_run: bool) -> Result<Vec<BranchSync>> {
+    let head = repo.head().ok().filter(|head| head.is_branch()).and_then(|head| head.name().map(str::to_string));
+    let elsewhere = worktree_branches(repo);
+    let dirty = has_tracked_changes(repo)?;
+
+    let mut results = Vec::new();
+    for branch in repo.branches(Some(BranchType::Local))? {
+        let (branch, _) = branch?;
+        let (Some(refname), Some(local)) = (branch.get().name(), branch.get().target()) else { continue };
+        // Branches without an upstream have nothing to sync with
+        let Some(upstream_ref) = repo.branch_upstream_name(refname).ok().and_then(|buf| buf.as_str().map(str::to_string)) else {
+            continue;
+        };
+        let remote = repo.branch_upstream_remote(refname).ok().and_then(|buf| buf.as_str().map(str::to_string));
+        let name = branch.name()?.unwrap_or(refname).to_string();
+        let upstream = upstream_ref.strip_prefix("refs/remotes/")
+            .or_else(|| upstream_ref.strip_prefix("refs/heads/"))
+            .unwrap_or(&upstream_ref)
+            .to_string();
+
+        let outcome = match repo.refname_to_id(&upstream_ref) {
+            _ if remote.as_ref().is_some_and(|remote| failed_remotes.contains(remote)) => {
+                BranchOutcome::Skipped(format!("fetching {} failed", remote.unwrap_or_default()))
+            }
+            Err(_) => BranchOutcome::Skipped("the upstream is gone".to_string()),
+            Ok(target) => {
+                let (ahead, behind) = repo.graph_ahead_behind(local, target)?;
+                let is_head = head.as_deref() == Some(refname);
+                if behind == 0 && ahead == 0 {
+                    BranchOutcome::UpToDate
+                } else if behind == 0 {
+                    BranchOutcome::Ahead(ahead)
+                } else if ahead > 0 {
+                    BranchOutcome::Diverged { ahead, behind }
+                } else if is_head && dirty {
+                    BranchOutcome::Skipped("local changes".to_string())
+                } else if let Some(path) = elsewhere.get(refname) {
+                    BranchOutcome::Skipped(format!("checked out in {}", path))
+                } else if dry_run {
+                    BranchOutcome::Advanced(behind)
+                } else {
+                    match fast_forward_branch(repo, refname, local, target, is_head) {
+                        Ok(()) => BranchOutcome::Advanced(behind),
+                        Err(e) => BranchOutcome::Skipped(e.to_string()),
+                    }
+                }
+            }
+        };
+        results.push(BranchSync { branch: name, upstream, outcome });
+    }
+    Ok(results)
+}
+
+/// Move `refname` from `old` to `new`, updating the working tree when it is
+/// the checked-out branch
+fn fast_forward_branch(repo: &Repository, refname: &str, old: Oid, new: Oid, is_head: bool) -> Result<()> {
+    if is_head {
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.safe();
+        repo.checkout_tree(&repo.find_object(new, None)?, Some(&mut checkout))?;
+    }
+    repo.find_reference(refname)?.set_target(new, "sync --all: fast-forward")?;
+    Journal::for_repo(repo).record(&JournalEntry::new("sync --all", refname).with_old_oid(old).with_new_oid(new))
+}
+
+/// Staged or unstaged changes to tracked files
+fn has_tracked_changes(repo: &Repository) -> Result<bool> {
+    if repo.is_bare() {
+        return Ok(false);
+    }
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    Ok(!repo.statuses(Some(&mut options))?.is_empty())
+}
+
+/// Branches checked out in linked worktrees other than this one, by ref
+/// name, with the worktree's path
+fn worktree_branches(repo: &Repository) -> HashMap<String, String> {
+    let here = repo.workdir().and_then(|workdir| workdir.canonicalize().ok());
+    let mut branches = HashMap::new();
+    let Ok(names) = repo.worktrees() else { return branches };
+    for name in names.iter().flatten() {
+        let Ok(worktree) = repo.find_worktree(name) else { continue };
+        let path: &Path = worktree.path();
+        if path.canonicalize().ok() == here {
+            continue;
+        }
+        let Ok(checkout) = Repository::open_from_worktree(&worktree) else { continue };
+        if let Some(refname) = checkout.head().ok().and_then(|head| head.name().map(str::to_string)) {
+            branches.insert(refname, path.display().to_string());
+        }
+    }
+    branches
+}
+
+fn show_branch_syncs(results: &[BranchSync], dry_run: bool) {
+    if results.is_empty() {
+        ui_println!("{} No local branch tracks an upstream", "ℹ️".blue());
+        println!("  Set one with {}", "rgit branch --set-upstream-to origin/<branch>".cyan());
+        return;
+    }
+
+    ui_println!("\n{} Branches:", "📊".blue().bold());
+    let width = results.iter().map(|result| console::measure_text_width(&result.branch)).max().unwrap_or(0);
+    let (mut advanced, mut diverged, mut skipped) = (0, 0, 0);
+    for result in results {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        let (icon, description) = match &result.outcome {
+            BranchOutcome::Advanced(count) => {
+                advanced += 1;
+                let verb = if dry_run { "would advance" } else { "advanced" };
+                ("⬆️".green(), format!("{} {} commit{} to {}", verb, count, plural(*count), result.upstream).green())
+            }
+            BranchOutcome::UpToDate => ("✅".green(), format!("up to date with {}", result.upstream).normal()),
+            BranchOutcome::Ahead(count) => ("📤".blue(), format!(
+                "{} commit{} ahead of {}; push with rgit push", count, plural(*count), result.upstream
+            ).normal()),
+            BranchOutcome::Diverged { ahead, behind } => {
+                diverged += 1;
+                ("🔀".yellow(), format!(
+                    "diverged from {} ({} ahead, {} behind); rebase or merge it", result.upstream, ahead, behind
+                ).yellow())
+            }
+            BranchOutcome::Skipped(reason) => {
+                skipped += 1;
+                ("⏭️".yellow(), format!("skipped: {}", reason).yellow())
+            }
+        };
+        let padding = " ".repeat(width.saturating_sub(console::measure_text_width(&result.branch)));
+        ui_println!("  {} {}{}  {}", icon, result.branch.cyan(), padding, description);
+    }
+
+    println!();
+    ui_println!("{} {} advanced, {} diverged, {} skipped, {} unchanged",
+            if dry_run { "🧪" } else { "🎉" },
+            advanced, diverged, skipped, results.len() - advanced - diverged - skipped);
+}
+
 // =============================================================================
 // Data Structures
 // =============================================================================
@@ -623,6 +835,7 @@ pub async fn quick_sync(rgit: &RgitCore, config: &Config) -> Result<()> {
         pull_only: false,
         force: false,
         submodules: config.submodules.auto_init,
+        all: false,
         dry_run: false,
     };
     
@@ -638,18 +851,7 @@ pub fn needs_sync(rgit: &RgitCore) -> Result<bool> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_repo() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        let mut config = repo.config().unwrap();
-        config.set_str("user.name", "Test User").unwrap();
-        config.set_str("user.email", "test@example.com").unwrap();
-        
-        (temp_dir, repo)
-    }
+    use crate::test_support::{create_test_repo, TestRepo, DEFAULT_BRANCH};
 
     #[tokio::test]
     async fn test_validate_repository_state() {
@@ -700,6 +902,7 @@ mod tests {
             pull_only: false,
             force: false,
             submodules: false,
+            all: false,
             dry_run: true,
         };
         
@@ -709,4 +912,62 @@ mod tests {
         // This might fail due to no remote, which is expected
         // In a real test environment, we'd set up proper remotes
     }
+
+    #[test]
+    fn test_fast_forward_branches() {
+        let test_repo = TestRepo::new();
+        let base = test_repo.commit_file("README.md", "hello\n", "Initial commit");
+        let _origin = test_repo.add_remote("origin");
+        let newer = test_repo.commit_file("README.md", "hello again\n", "Update readme");
+        let repo = test_repo.repo();
+        repo.reset(&repo.find_object(base, None).unwrap(), ResetType::Hard, None).unwrap();
+        let tree = repo.find_commit(base).unwrap().tree().unwrap();
+        let signature = repo.signature().unwrap();
+        let parent = repo.find_commit(base).unwrap();
+        let other = repo.commit(None, &signature, &signature, "Diverge", &tree, &[&parent]).unwrap();
+
+        // branch -> (local, upstream); `main` is checked out and behind
+        let mut config = repo.config().unwrap();
+        for (name, local, upstream) in [
+            (DEFAULT_BRANCH, base, Some(newer)),
+            ("behind", base, Some(newer)),
+            ("current", newer, Some(newer)),
+            ("ahead", newer, Some(base)),
+            ("diverged", other, Some(newer)),
+            ("gone", base, None),
+        ] {
+            repo.reference(&format!("refs/heads/{}", name), local, true, "test").unwrap();
+            if let Some(upstream) = upstream {
+                repo.reference(&format!("refs/remotes/origin/{}", name), upstream, true, "test").unwrap();
+            }
+            config.set_str(&format!("branch.{}.remote", name), "origin").unwrap();
+            config.set_str(&format!("branch.{}.merge", name), &format!("refs/heads/{}", name)).unwrap();
+        }
+        repo.branch("untracked", &parent, false).unwrap();
+
+        let outcomes = |dry_run| {
+            fast_forward_branches(repo, &BTreeSet::new(), dry_run).unwrap().into_iter()
+                .map(|result| (result.branch, result.outcome))
+                .collect::<HashMap<_, _>>()
+        };
+
+        test_repo.write("README.md", "edited\n");
+        let first = outcomes(false);
+        assert_eq!(first.len(), 6);
+        assert_eq!(first[DEFAULT_BRANCH], BranchOutcome::Skipped("local changes".to_string()));
+        assert_eq!(first["behind"], BranchOutcome::Advanced(1));
+        assert_eq!(first["current"], BranchOutcome::UpToDate);
+        assert_eq!(first["ahead"], BranchOutcome::Ahead(1));
+        assert_eq!(first["diverged"], BranchOutcome::Diverged { ahead: 1, behind: 1 });
+        assert_eq!(first["gone"], BranchOutcome::Skipped("the upstream is gone".to_string()));
+        assert_eq!(repo.refname_to_id("refs/heads/behind").unwrap(), newer);
+
+        // A clean checkout fast-forwards the working tree along with the branch
+        test_repo.write("README.md", "hello\n");
+        assert_eq!(outcomes(true)[DEFAULT_BRANCH], BranchOutcome::Advanced(1));
+        assert_eq!(test_repo.head(), base);
+        assert_eq!(outcomes(false)[DEFAULT_BRANCH], BranchOutcome::Advanced(1));
+        assert_eq!(test_repo.head(), newer);
+        assert_eq!(std::fs::read_to_string(test_repo.path().join("README.md")).unwrap(), "hello again\n");
+    }
 }
\ No newline at end of file
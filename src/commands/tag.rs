@@ -0,0 +1,298 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Oid, Pathspec, PathspecFlags, Repository, Time};
+use std::path::Path;
+use std::process::Command;
+
+use crate::cli::{DiffContentArgs, RenameArgs, ShowArgs, TagArgs, TagCommands, TagListArgs};
+use crate::commands::branch::{RefDates, RefSort};
+use crate::commands::show;
+use crate::config::Config;
+use crate::core::{resolve_commit, resolve_object, RgitCore};
+use crate::error::RgitError;
+use crate::utils::{format_columns, format_time_ago, pad_string, shorten_oid, TextAlign};
+use crate::validation::validate_tag_name;
+
+/// Execute the tag command
+pub async fn execute(args: &TagArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    match &args.action {
+        None => list_tags(rgit, &[], &args.list, config),
+        Some(TagCommands::List { patterns, options }) => list_tags(rgit, patterns, options, config),
+        Some(TagCommands::Create { name, commit, message, sign }) => {
+            create_tag(rgit, name, commit.as_deref(), message.as_deref(), *sign)
+        }
+        Some(TagCommands::Delete { name }) => delete_tag(&rgit.repo, name),
+        Some(TagCommands::Show { name }) => {
+            if rgit.repo.find_reference(&format!("refs/tags/{}", name)).is_err() {
+                return Err(RgitError::TagNotFound(name.clone()).into());
+            }
+            let args = ShowArgs {
+                object: Some(format!("refs/tags/{}", name)),
+                stat: false,
+                name_only: false,
+                no_highlight: false,
+                tree: false,
+                renames: RenameArgs::default(),
+                content: DiffContentArgs::default(),
+            };
+            show::execute(&args, rgit, config).await
+        }
+    }
+}
+
+// =============================================================================
+// Listing
+// =============================================================================
+
+/// A tag as shown in listings
+#[derive(Debug, Clone)]
+pub struct TagInfo {
+    pub name: String,
+    /// The tag object for annotated tags, the target for lightweight ones
+    pub id: Oid,
+    /// Annotation subject, or the tagged commit's subject
+    pub subject: String,
+    pub annotated: bool,
+    /// The tagged commit, if the tag points at one
+    pub commit: Option<Oid>,
+    pub dates: RefDates,
+}
+
+/// Every tag in the repository, sorted by name
+pub fn collect_tags(repo: &Repository) -> Result<Vec<TagInfo>> {
+    let mut tags = Vec::new();
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+        let (Some(full_name), Some(id)) = (reference.name(), reference.target()) else { continue };
+        let name = full_name.trim_start_matches("refs/tags/").to_string();
+
+        let annotation = repo.find_tag(id).ok();
+        let commit = reference.peel_to_commit().ok();
+        let mut dates = commit.as_ref().map(RefDates::of_commit).unwrap_or_default();
+        if let Some(tagger) = annotation.as_ref().and_then(|tag| tag.tagger()) {
+            dates.creator = tagger.when().seconds();
+        }
+
+        let subject = match (&annotation, &commit) {
+            (Some(tag), _) => tag.message().unwrap_or("").lines().next().unwrap_or("").to_string(),
+            (None, Some(commit)) => commit.summary().unwrap_or("").to_string(),
+            (None, None) => String::new(),
+        };
+
+        tags.push(TagInfo {
+            name,
+            id,
+            subject,
+            annotated: annotation.is_some(),
+            commit: commit.map(|commit| commit.id()),
+            dates,
+        });
+    }
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(tags)
+}
+
+/// Which tags a listing shows
+pub struct TagFilter {
+    patterns: Option<Pathspec>,
+    contains: Option<Oid>,
+    points_at: Option<Oid>,
+    merged: Option<Oid>,
+    no_merged: Option<Oid>,
+}
+
+impl TagFilter {
+    pub fn new(repo: &Repository, patterns: &[String], options: &TagListArgs) -> Result<Self> {
+        let commit = |spec: &Option<String>| -> Result<Option<Oid>> {
+            spec.as_deref().map(|spec| Ok(resolve_commit(repo, spec)?.id())).transpose()
+        };
+        let points_at = match &options.points_at {
+            Some(spec) => Some(resolve_object(repo, spec)?.id()),
+            None => None,
+        };
+        Ok(Self {
+            patterns: if patterns.is_empty() { None } else { Some(Pathspec::new(patterns)?) },
+            contains: commit(&options.contains)?,
+            points_at,
+            merged: commit(&options.merged)?,
+            no_merged: commit(&options.no_merged)?,
+        })
+    }
+
+    pub fn matches(&self, repo: &Repository, tag: &TagInfo) -> Result<bool> {
+        // Patterns match like `git tag -l`: globs, with `*` crossing `/`
+        if let Some(patterns) = &self.patterns {
+            if !patterns.matches_path(Path::new(&tag.name), PathspecFlags::DEFAULT) {
+                return Ok(false);
+            }
+        }
+        if let Some(object) = self.points_at {
+            if tag.id != object && tag.commit != Some(object) {
+                return Ok(false);
+            }
+        }
+
+        let reaches = |from: Oid, to: Oid| -> Result<bool> {
+            Ok(from == to || repo.graph_descendant_of(from, to)?)
+        };
+        // (commit, whether the tag must contain it rather than be reachable from it, negated)
+        let checks = [
+            (self.contains, true, false),
+            (self.merged, false, false),
+            (self.no_merged, false, true),
+        ];
+        for (commit, tag_contains, negated) in checks {
+            let Some(commit) = commit else { continue };
+            let Some(tagged) = tag.commit else { return Ok(false) };
+            let related = if tag_contains { reaches(tagged, commit)? } else { reaches(commit, tagged)? };
+            if related == negated {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn list_tags(rgit: &RgitCore, patterns: &[String], options: &TagListArgs, config: &Config) -> Result<()> {
+    let sort = options.sort.as_deref().map(RefSort::parse).transpose()?.unwrap_or_default();
+    let filter = TagFilter::new(&rgit.repo, patterns, options)?;
+    let mut tags = Vec::new();
+    for tag in collect_tags(&rgit.repo)? {
+        if filter.matches(&rgit.repo, &tag)? {
+            tags.push(tag);
+        }
+    }
+    sort.apply(&mut tags, |tag| (&tag.name, tag.dates));
+
+    ui_println!("{} Repository tags:", "🏷️".yellow().bold());
+    if tags.is_empty() {
+        ui_println!("  {} No tags found", "ℹ️".blue());
+        return Ok(());
+    }
+
+    if options.column {
+        let names: Vec<String> = tags.iter().map(|tag| tag.name.yellow().to_string()).collect();
+        for line in format_columns(&names, config.terminal_width().saturating_sub(2)) {
+            println!("  {}", line);
+        }
+    } else if rgit.verbose {
+        let width = tags.iter().map(|tag| tag.name.chars().count()).max().unwrap_or(0);
+        for tag in &tags {
+            println!("  {} {} {} {}",
+                     pad_string(&tag.name, width, TextAlign::Left).yellow(),
+                     shorten_oid(&tag.id, 8).dimmed(),
+                     tag.subject,
+                     format!("({})", format_time_ago(Time::new(tag.dates.creator, 0))).dimmed());
+        }
+    } else {
+        for tag in &tags {
+            println!("  {}", tag.name.yellow());
+        }
+    }
+
+    Ok(())
+}
+
+// =============================================================================
+// Creating and Deleting
+// =============================================================================
+
+fn create_tag(rgit: &RgitCore, name: &str, target: Option<&str>, message: Option<&str>, sign: bool) -> Result<()> {
+    let repo = &rgit.repo;
+    validate_tag_name(name)?;
+    if repo.find_reference(&format!("refs/tags/{}", name)).is_ok() {
+        return Err(RgitError::TagAlreadyExists(name.to_string()).into());
+    }
+    let object = resolve_object(repo, target.unwrap_or("HEAD"))?;
+
+    if sign {
+        // libgit2 can't sign, so signed tags go through git and its gpg setup
+        let output = Command::new("git")
+            .arg("--git-dir")
+            .arg(repo.path())
+            .args(["tag", "-s", name, &object.id().to_string(), "-m", message.unwrap_or(name)])
+            .output()
+            .map_err(|_| RgitError::OperationFailed("Signing tags requires git to be installed".to_string()))?;
+        if !output.status.success() {
+            return Err(RgitError::OperationFailed(format!(
+                "Failed to sign tag: {}", String::from_utf8_lossy(&output.stderr).trim()
+            )).into());
+        }
+    } else if let Some(message) = message {
+        repo.tag(name, &object, &rgit.get_signature()?, message, false)?;
+    } else {
+        repo.tag_lightweight(name, &object, false)?;
+    }
+
+    ui_println!("{} Created {}tag '{}' at {}",
+                "✅".green(),
+                if sign { "signed " } else if message.is_some() { "annotated " } else { "" },
+                name.yellow(),
+                shorten_oid(&object.id(), 8).dimmed());
+    ui_println!("{} Share it with {}", "💡".blue(), "rgit push --tags".cyan());
+    Ok(())
+}
+
+fn delete_tag(repo: &Repository, name: &str) -> Result<()> {
+    let reference = repo.find_reference(&format!("refs/tags/{}", name))
+        .map_err(|_| RgitError::TagNotFound(name.to_string()))?;
+    let was = reference.target().map(|id| shorten_oid(&id, 8)).unwrap_or_default();
+    repo.tag_delete(name)?;
+    ui_println!("{} Deleted tag '{}' (was {})", "🗑️".red(), name.yellow(), was.dimmed());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_collects_lightweight_and_annotated_tags() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "a\n", "First release");
+        repo.tag("v1.0");
+        repo.commit_file("a.txt", "b\n", "Second release");
+        repo.annotated_tag("v2.0", "Version two\n\nDetails");
+
+        let tags = collect_tags(repo.repo()).unwrap();
+        assert_eq!(tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["v1.0", "v2.0"]);
+        assert_eq!((tags[0].id, tags[0].annotated, tags[0].subject.as_str()), (first, false, "First release"));
+        assert!(tags[1].annotated);
+        assert_eq!(tags[1].subject, "Version two");
+    }
+
+    #[test]
+    fn test_filters_tags() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "a\n", "First");
+        repo.tag("v1.0").tag("release/1");
+        repo.branch("side");
+        let second = repo.commit_file("a.txt", "b\n", "Second");
+        repo.annotated_tag("v2.0", "Version two");
+        repo.checkout("side");
+        repo.commit_file("b.txt", "b\n", "Side");
+        repo.tag("side-1");
+
+        let names = |patterns: &[&str], options: TagListArgs| -> Vec<String> {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            let filter = TagFilter::new(repo.repo(), &patterns, &options).unwrap();
+            collect_tags(repo.repo()).unwrap().into_iter()
+                .filter(|tag| filter.matches(repo.repo(), tag).unwrap())
+                .map(|tag| tag.name)
+                .collect()
+        };
+
+        assert_eq!(names(&["v*"], TagListArgs::default()), ["v1.0", "v2.0"]);
+        assert_eq!(names(&["release/*", "side-?"], TagListArgs::default()), ["release/1", "side-1"]);
+        let contains = TagListArgs { contains: Some(second.to_string()), ..Default::default() };
+        assert_eq!(names(&[], contains), ["v2.0"]);
+        let points_at = TagListArgs { points_at: Some(first.to_string()), ..Default::default() };
+        assert_eq!(names(&[], points_at), ["release/1", "v1.0"]);
+        // HEAD is on side, which doesn't have the second commit
+        let merged = TagListArgs { merged: Some("HEAD".to_string()), ..Default::default() };
+        assert_eq!(names(&[], merged), ["release/1", "side-1", "v1.0"]);
+        let no_merged = TagListArgs { no_merged: Some("HEAD".to_string()), ..Default::default() };
+        assert_eq!(names(&[], no_merged), ["v2.0"]);
+    }
+}
@@ -0,0 +1,215 @@
+use anyhow::Result;
+use colored::*;
+use git2::Time;
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use crate::cli::TodosArgs;
+use crate::commands::blame::{blame_file, BlameLine, UNCOMMITTED_AUTHOR};
+use crate::commands::grep::{collect_candidates, search, Context, MatchOptions, Matcher, Source};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::TableDisplay;
+use crate::utils::{current_time, format_date, format_time_ago};
+use crate::validation::validate_pathspec;
+
+/// A marker right after a comment leader (`//`, `#`, `/*`, `*`, `--`, `;`,
+/// `%` or `<!--`), so prose and strings that mention the words don't count.
+/// Captures the marker, an optional `(assignee)` and the note.
+const MARKER_PATTERN: &str = r"(?://|#|/\*|\*|--|;|%|<!--)\s*\b(FIXME|XXX|HACK|TODO)\b(?:\(([^)]*)\))?[:!]*\s*(.*)";
+
+/// How urgent a marker is; FIXME and XXX flag known bugs, HACK a shortcut
+/// to revisit and TODO missing work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    fn of(marker: &str) -> Self {
+        match marker {
+            "FIXME" | "XXX" => Priority::High,
+            "HACK" => Priority::Medium,
+            _ => Priority::Low,
+        }
+    }
+
+    fn label(self) -> ColoredString {
+        match self {
+            Priority::High => "high".red().bold(),
+            Priority::Medium => "medium".yellow(),
+            Priority::Low => "low".normal(),
+        }
+    }
+}
+
+/// A marker comment and who wrote it
+#[derive(Debug, Clone, Serialize)]
+struct Todo {
+    priority: Priority,
+    marker: String,
+    path: String,
+    line: usize,
+    /// Name in parentheses after the marker, as in `TODO(alice): ...`
+    assignee: Option<String>,
+    text: String,
+    /// `null` for lines that aren't committed yet
+    commit: Option<String>,
+    author: String,
+    email: String,
+    date: String,
+    #[serde(skip)]
+    time: Time,
+}
+
+/// Execute the todos command
+pub async fn execute(args: &TodosArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+    let cwd = std::env::current_dir()?;
+    let pathspecs = args.paths.iter()
+        .map(|path| validate_pathspec(workdir, &cwd, path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let matcher = Matcher::new(MARKER_PATTERN, &MatchOptions::default())?;
+    let candidates = collect_candidates(repo, &Source::Worktree, &pathspecs)?;
+    let threads = config.advanced.performance.threads.max(1);
+    let files = search(repo.path(), &candidates, &matcher, Context::default(), threads)?;
+
+    let mut todos = Vec::new();
+    for file in files.iter().filter(|file| !file.binary) {
+        // Files added since HEAD have no history to blame yet
+        let blamed = blame_file(repo, &file.path).unwrap_or_default();
+        for line in file.groups.iter().flatten() {
+            if let Some(todo) = parse_todo(&file.path, line.number, &line.text, blamed.get(line.number - 1)) {
+                todos.push(todo);
+            }
+        }
+    }
+    if let Some(author) = &args.author {
+        let author = author.to_lowercase();
+        todos.retain(|todo| todo.author.to_lowercase().contains(&author) || todo.email.to_lowercase().contains(&author));
+    }
+    // Most urgent first, and the longest-standing of those before newer ones
+    todos.sort_by(|a, b| a.priority.cmp(&b.priority)
+        .then_with(|| a.time.cmp(&b.time))
+        .then_with(|| a.path.cmp(&b.path))
+        .then_with(|| a.line.cmp(&b.line)));
+    let total = todos.len();
+    if let Some(limit) = args.limit {
+        todos.truncate(limit);
+    }
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&todos)?);
+        return Ok(());
+    }
+    if todos.is_empty() {
+        ui_println!("{} No TODO, FIXME, XXX or HACK markers found", "✅".green());
+        return Ok(());
+    }
+
+    ui_println!("{} {} marker{} in tracked files", "📝".blue(), total, if total == 1 { "" } else { "s" });
+    println!();
+    let mut table = TableDisplay::new().with_max_width(config.terminal_width()).with_headers(vec![
+        "Priority".to_string(),
+        "Location".to_string(),
+        "Note".to_string(),
+        "Author".to_string(),
+        "Added".to_string(),
+    ]);
+    for todo in &todos {
+        let note = match &todo.assignee {
+            Some(assignee) => format!("{}({}): {}", todo.marker, assignee, todo.text),
+            None => format!("{}: {}", todo.marker, todo.text),
+        };
+        table.add_row(vec![
+            todo.priority.label().to_string(),
+            format!("{}:{}", todo.path, todo.line),
+            note,
+            todo.author.clone(),
+            match &todo.commit {
+                Some(commit) => format!("{} ({})", format_time_ago(todo.time), &commit[..7]),
+                None => "-".to_string(),
+            },
+        ]);
+    }
+    table.display();
+    if total > todos.len() {
+        println!("{}", format!("... and {} more", total - todos.len()).dimmed());
+    }
+    Ok(())
+}
+
+/// The marker on `text`, if it has one, attributed to the commit in `blamed`.
+/// Lines without blame are treated as not committed yet.
+fn parse_todo(path: &str, line: usize, text: &str, blamed: Option<&BlameLine>) -> Option<Todo> {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    let marker = MARKER.get_or_init(|| {
+        Regex::new(MARKER_PATTERN).expect("valid marker regex")
+    });
+    let captures = marker.captures(text)?;
+    let kind = captures[1].to_string();
+
+    // Drop the end of block comments so only the note remains
+    let note = captures[3].trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+    let (commit, author, email, time) = match blamed {
+        Some(blamed) if !blamed.commit.is_zero() => {
+            (Some(blamed.commit.to_string()), blamed.author.clone(), blamed.email.clone(), blamed.time)
+        }
+        _ => (None, UNCOMMITTED_AUTHOR.to_string(), String::new(), current_time()),
+    };
+    Some(Todo {
+        priority: Priority::of(&kind),
+        marker: kind,
+        path: path.to_string(),
+        line,
+        assignee: captures.get(2).map(|name| name.as_str().trim().to_string()).filter(|name| !name.is_empty()),
+        text: note.to_string(),
+        commit,
+        author,
+        email,
+        date: format_date(time),
+        time,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_parse_todo() {
+        let todo = parse_todo("src/lib.rs", 3, "    // FIXME(alice): handle EOF */", None).unwrap();
+        assert_eq!(todo.priority, Priority::High);
+        assert_eq!(todo.assignee.as_deref(), Some("alice"));
+        assert_eq!(todo.text, "handle EOF");
+        assert_eq!(todo.commit, None);
+        assert_eq!(todo.author, UNCOMMITTED_AUTHOR);
+
+        assert_eq!(parse_todo("a.py", 1, "# TODO split this", None).unwrap().priority, Priority::Low);
+        assert!(parse_todo("a.py", 1, "let todo_list = TODOS;", None).is_none());
+        assert!(parse_todo("cli.rs", 1, "/// List TODO and FIXME comments", None).is_none());
+        assert!(parse_todo("cli.rs", 1, "print(\"TODO: later\")", None).is_none());
+    }
+
+    #[test]
+    fn test_todos_are_attributed_by_blame() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("src/lib.rs", "fn a() {}\n// HACK: skip the cache\n", "Add lib");
+        let repo = test_repo.repo();
+        let blamed = blame_file(repo, "src/lib.rs").unwrap();
+
+        let todo = parse_todo("src/lib.rs", 2, &blamed[1].content, blamed.get(1)).unwrap();
+        assert_eq!(todo.priority, Priority::Medium);
+        assert_eq!(todo.text, "skip the cache");
+        assert_eq!(todo.commit, Some(repo.head().unwrap().target().unwrap().to_string()));
+        assert_ne!(todo.author, UNCOMMITTED_AUTHOR);
+    }
+}
@@ -0,0 +1,574 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{ErrorCode, Oid, Repository, ResetType};
+use std::collections::HashMap;
+
+use crate::cli::{ResetArgs, UndoArgs};
+use crate::commands::show::print_patch;
+use crate::commands::{backup, push, reset};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::InteractivePrompt;
+use crate::journal::{Journal, JournalEntry};
+use crate::output::decoration;
+use crate::utils::{calculate_file_changes, format_time_ago, shorten_oid};
+
+/// Journal operation name of an undo; its detail names the operation it reverts
+const UNDO_OPERATION: &str = "undo";
+
+/// An operation touching several refs records them one after another, so
+/// entries this close together (in seconds) may belong to the same operation
+const GROUP_WINDOW_SECS: i64 = 5;
+
+/// Files listed per ref in a preview before the rest are counted
+const MAX_PREVIEW_FILES: usize = 15;
+
+/// Execute the undo command
+pub async fn execute(args: &UndoArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if let Some(count) = args.commits {
+        return undo_commits(count, args, rgit, config).await;
+    }
+
+    let repo = &rgit.repo;
+    let operations = operations(&Journal::for_repo(repo).entries()?);
+    let mode = MoveMode::from_args(args);
+    if args.list {
+        list(&operations);
+        return Ok(());
+    }
+    if args.interactive {
+        return browse(&operations, mode, rgit, config);
+    }
+
+    let (number, operation) = match args.operation {
+        Some(number) => number.checked_sub(1)
+            .and_then(|n| operations.get(n))
+            .map(|operation| (number, operation))
+            .ok_or_else(|| RgitError::InvalidArgument(format!(
+                "there is no operation {} in the journal (see rgit undo --list)", number
+            )))?,
+        None => operations.iter().enumerate()
+            .find(|(_, operation)| !operation.undone && operation.name != UNDO_OPERATION)
+            .map(|(n, operation)| (n + 1, operation))
+            .ok_or_else(|| RgitError::OperationFailed("the operation journal has nothing left to undo".to_string()))?,
+    };
+
+    let refs = plan(repo, operation)?;
+    show_plan(repo, number, operation, &refs)?;
+    if refs.is_empty() {
+        return Ok(());
+    }
+    if config.is_interactive() && config.advanced.safety.confirm_destructive {
+        let confirmed = InteractivePrompt::new()
+            .with_message(format!("Undo {}?", operation.name))
+            .confirm()?;
+        if !confirmed {
+            return Err(RgitError::OperationCancelled.into());
+        }
+    }
+    apply(rgit, config, operation, &refs, mode)
+}
+
+/// `--commits N`: a reset to `HEAD~N`, which journals and snapshots like any reset
+async fn undo_commits(count: usize, args: &UndoArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if count == 0 {
+        return Err(RgitError::InvalidArgument("--commits needs at least 1".to_string()).into());
+    }
+    let reset_args = ResetArgs {
+        commit: Some(format!("HEAD~{}", count)),
+        soft: args.soft,
+        mixed: !args.soft && !args.hard,
+        hard: args.hard,
+        force: false,
+    };
+    reset::execute(&reset_args, rgit, config).await
+}
+
+// =============================================================================
+// Operations
+// =============================================================================
+
+/// Journal entries made by one run of a command
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub name: String,
+    pub timestamp: DateTime<Utc>,
+    /// In the order they were recorded
+    pub entries: Vec<JournalEntry>,
+    /// Whether a later undo reverted it (and wasn't itself undone)
+    pub undone: bool,
+}
+
+impl Operation {
+    /// What an undo of this operation records as its detail
+    fn key(&self) -> String {
+        format!("{} at {}", self.name, self.timestamp.to_rfc3339())
+    }
+
+    /// Name plus what it touched, e.g. "reset --hard heads/main"
+    pub fn describe(&self) -> String {
+        let name = match self.entries[0].detail.rsplit_once(" at ") {
+            Some((reverted, _)) if self.name == UNDO_OPERATION => format!("{} of {}", self.name, reverted),
+            _ => self.name.clone(),
+        };
+        match self.entries.as_slice() {
+            [entry] => format!("{} {}", name, entry.reference.trim_start_matches("refs/")),
+            entries => format!("{} ({} refs)", name, entries.len()),
+        }
+    }
+
+    fn age(&self) -> String {
+        format_time_ago(git2::Time::new(self.timestamp.timestamp(), 0))
+    }
+}
+
+/// Group journal entries into operations, newest first. Entries belong to
+/// the same operation when they share a name and detail, were recorded
+/// within a few seconds, and each touches a different ref.
+pub fn operations(entries: &[JournalEntry]) -> Vec<Operation> {
+    let mut operations: Vec<Operation> = Vec::new();
+    for entry in entries {
+        match operations.last_mut() {
+            Some(operation) if operation.name == entry.operation
+                && operation.entries[0].detail == entry.detail
+                && (entry.timestamp - operation.timestamp).num_seconds() <= GROUP_WINDOW_SECS
+                && operation.entries.iter().all(|e| e.reference != entry.reference) => {
+                operation.entries.push(entry.clone());
+            }
+            _ => operations.push(Operation {
+                name: entry.operation.clone(),
+                timestamp: entry.timestamp,
+                entries: vec![entry.clone()],
+                undone: false,
+            }),
+        }
+    }
+
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    for n in 0..operations.len() {
+        if operations[n].name == UNDO_OPERATION {
+            if let Some(&target) = by_key.get(&operations[n].entries[0].detail) {
+                operations[target].undone = true;
+                // Undoing an undo brings back what it reverted
+                if operations[target].name == UNDO_OPERATION {
+                    if let Some(&original) = by_key.get(&operations[target].entries[0].detail) {
+                        operations[original].undone = false;
+                    }
+                }
+            }
+        }
+        by_key.insert(operations[n].key(), n);
+    }
+
+    operations.reverse();
+    operations
+}
+
+fn operation_label(number: usize, operation: &Operation) -> String {
+    format!("{:>3}  {}  ({}){}",
+            number,
+            operation.describe(),
+            operation.age(),
+            if operation.undone { " [undone]" } else { "" })
+}
+
+fn list(operations: &[Operation]) {
+    if operations.is_empty() {
+        ui_println!("{} The operation journal is empty", "ℹ️".blue());
+        return;
+    }
+    ui_println!("{} Operations, newest first\n", "📜".blue());
+    for (n, operation) in operations.iter().enumerate() {
+        let label = operation_label(n + 1, operation);
+        if operation.undone {
+            println!("{}", label.dimmed());
+        } else {
+            println!("{}", label);
+        }
+    }
+    ui_println!("\n{} {} previews and undoes one; {} picks from a list",
+            "💡".blue(), "rgit undo --operation <NUMBER>".cyan(), "rgit undo --interactive".cyan());
+}
+
+// =============================================================================
+// Planning
+// =============================================================================
+
+/// A ref an undo puts back
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefUndo {
+    pub name: String,
+    /// Remote the ref lives on, for `push --delete`
+    pub remote: Option<String>,
+    /// What the operation left behind; None when it deleted the ref
+    pub recorded: Option<Oid>,
+    /// What the ref points to now. Remote refs aren't queried, so they
+    /// count as unchanged since the operation.
+    pub current: Option<Oid>,
+    /// What it pointed to before; None when the operation created it
+    pub restore: Option<Oid>,
+}
+
+impl RefUndo {
+    pub fn short_name(&self) -> &str {
+        self.name.trim_start_matches("refs/")
+    }
+
+    /// Whether the ref moved again after the operation, so undoing it
+    /// also throws away the later changes
+    pub fn moved_since(&self) -> bool {
+        self.current != self.recorded
+    }
+}
+
+/// The refs an undo of `operation` would change, with their current values
+pub fn plan(repo: &Repository, operation: &Operation) -> Result<Vec<RefUndo>> {
+    let remote = (operation.name == "push --delete").then(|| operation.entries[0].detail.clone());
+    let parse = |oid: &Option<String>| oid.as_deref().map(Oid::from_str).transpose();
+
+    let mut refs = Vec::new();
+    for entry in &operation.entries {
+        let recorded = parse(&entry.new_oid)?;
+        let current = match remote {
+            Some(_) => recorded,
+            None => repo.refname_to_id(&entry.reference).ok(),
+        };
+        let restore = parse(&entry.old_oid)?;
+        if current != restore {
+            refs.push(RefUndo { name: entry.reference.clone(), remote: remote.clone(), recorded, current, restore });
+        }
+    }
+    Ok(refs)
+}
+
+/// The checked-out ref: a branch, or HEAD itself when detached
+fn checked_out(repo: &Repository) -> Option<String> {
+    let head = repo.head().ok()?;
+    if head.is_branch() { head.name().map(String::from) } else { Some("HEAD".to_string()) }
+}
+
+fn is_checked_out(repo: &Repository, undo: &RefUndo) -> bool {
+    undo.remote.is_none() && (undo.name == "HEAD" || checked_out(repo).as_deref() == Some(undo.name.as_str()))
+}
+
+fn show_plan(repo: &Repository, number: usize, operation: &Operation, refs: &[RefUndo]) -> Result<()> {
+    ui_println!("{} #{} {} {}", "↩️".blue(), number, operation.describe().bold(), format!("({})", operation.age()).dimmed());
+    if refs.is_empty() {
+        ui_println!("{} Every ref it touched is already back where it was; nothing to undo", "✅".green());
+        return Ok(());
+    }
+
+    let short = |oid: Option<Oid>| oid.map(|id| shorten_oid(&id, 8)).unwrap_or_default();
+    for undo in refs {
+        let line = match (&undo.remote, undo.current, undo.restore) {
+            (Some(remote), _, _) => format!("{} {}/{} {} {}", "+".green(), remote,
+                                            undo.name.trim_start_matches("refs/heads/"), "recreated at".dimmed(), short(undo.restore).yellow()),
+            (None, _, None) => format!("{} {} {}", "-".red(), undo.short_name(), "deleted (the operation created it)".dimmed()),
+            (None, None, Some(_)) => format!("{} {} {} {}", "+".green(), undo.short_name(), "recreated at".dimmed(), short(undo.restore).yellow()),
+            (None, Some(current), Some(restore)) => {
+                let (dropped, regained) = repo.graph_ahead_behind(current, restore).unwrap_or((0, 0));
+                format!("{} {} {} {} {} {}", "~".yellow(), undo.short_name(), short(undo.current), decoration("→"), short(undo.restore).yellow(),
+                        format!("({} commit{} dropped, {} restored)", dropped, plural(dropped), regained).dimmed())
+            }
+        };
+        println!("  {}", line);
+        if undo.moved_since() && undo.remote.is_none() {
+            ui_println!("    {} {}", "⚠️".yellow(),
+                    format!("moved since, from {} to {}; undoing discards that too", short(undo.recorded), short(undo.current)).yellow());
+        }
+
+        if let (None, Some(current), Some(restore)) = (&undo.remote, undo.current, undo.restore) {
+            let stats = calculate_file_changes(repo, Some(current), Some(restore))?;
+            let label = if is_checked_out(repo, undo) { "files (working tree):" } else { "files:" };
+            if !stats.per_file.is_empty() {
+                println!("    {}", label.dimmed());
+            }
+            for file in stats.per_file.iter().take(MAX_PREVIEW_FILES) {
+                println!("      {} {} {}", file.status_letter().to_string().yellow(), file.display_path(),
+                        format!("+{} -{}", file.additions, file.deletions).dimmed());
+            }
+            if stats.per_file.len() > MAX_PREVIEW_FILES {
+                ui_println!("      {} {}", "…".dimmed(), format!("and {} more", stats.per_file.len() - MAX_PREVIEW_FILES).dimmed());
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
+
+/// Full patch from each ref's current value to the one it would get back
+fn show_diff(repo: &Repository, refs: &[RefUndo], config: &Config) -> Result<()> {
+    for undo in refs.iter().filter(|undo| undo.remote.is_none()) {
+        let (Some(current), Some(restore)) = (undo.current, undo.restore) else { continue };
+        let from = repo.find_commit(current)?.tree()?;
+        let to = repo.find_commit(restore)?.tree()?;
+        let diff = repo.diff_tree_to_tree(Some(&from), Some(&to), None)?;
+        ui_println!("{} {}\n", "📊".blue(), undo.short_name().cyan());
+        print_patch(repo, &diff, config, false)?;
+        println!();
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Browser
+// =============================================================================
+
+/// Pick an operation from the journal, preview its undo and apply it with one key
+fn browse(operations: &[Operation], mode: MoveMode, rgit: &RgitCore, config: &Config) -> Result<()> {
+    // Without asking, nothing is undone
+    if config.ui.assume_yes {
+        ui_println!("{} Nothing undone; pick an operation with {}", "💡".blue(), "rgit undo --operation <NUMBER>".cyan());
+        return Ok(());
+    }
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+    if operations.is_empty() {
+        ui_println!("{} The operation journal is empty", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let repo = &rgit.repo;
+    let mut options: Vec<String> = operations.iter().enumerate()
+        .map(|(n, operation)| operation_label(n + 1, operation))
+        .collect();
+    options.push("Done".to_string());
+    let mut selected = 0;
+    loop {
+        selected = InteractivePrompt::new()
+            .with_message("Operation to undo")
+            .with_options(&options)
+            .with_default(selected)
+            .fuzzy_search()
+            .select()?;
+        let Some(operation) = operations.get(selected) else { return Ok(()) };
+
+        let refs = plan(repo, operation)?;
+        show_plan(repo, selected + 1, operation, &refs)?;
+        if refs.is_empty() {
+            continue;
+        }
+        loop {
+            let key = InteractivePrompt::new()
+                .with_message("What now?")
+                .keypress(&[('u', "undo it"), ('d', "full diff"), ('q', "back")])?;
+            match key {
+                'u' => return apply(rgit, config, operation, &refs, mode),
+                'd' => show_diff(repo, &refs, config)?,
+                _ => break,
+            }
+        }
+    }
+}
+
+// =============================================================================
+// Undoing
+// =============================================================================
+
+/// How the checked-out ref moves, and what happens to local changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveMode {
+    /// Move the ref only
+    Soft,
+    /// Update the files that differ, refusing to overwrite local changes
+    Keep,
+    /// Reset the index and working tree, discarding local changes
+    Hard,
+}
+
+impl MoveMode {
+    fn from_args(args: &UndoArgs) -> Self {
+        if args.soft {
+            MoveMode::Soft
+        } else if args.hard {
+            MoveMode::Hard
+        } else {
+            MoveMode::Keep
+        }
+    }
+}
+
+fn apply(rgit: &RgitCore, config: &Config, operation: &Operation, refs: &[RefUndo], mode: MoveMode) -> Result<()> {
+    backup::snapshot_before(rgit, config, "undo")?;
+    undo_refs(&rgit.repo, config, operation, refs, mode)?;
+
+    ui_println!("{} Undid {}", "✅".green().bold(), operation.describe());
+    ui_println!("\n{} To redo: {}", "💡".blue(), "rgit undo --operation 1".cyan());
+    Ok(())
+}
+
+/// Put every ref back, the checked-out one first so a refusal to overwrite
+/// local changes leaves everything as it was. Each change is journaled as
+/// an undo of `operation`.
+pub fn undo_refs(repo: &Repository, config: &Config, operation: &Operation, refs: &[RefUndo], mode: MoveMode) -> Result<()> {
+    let (head, others): (Vec<&RefUndo>, Vec<&RefUndo>) = refs.iter().partition(|undo| is_checked_out(repo, undo));
+    if let Some(undo) = head.first() {
+        let Some(target) = undo.restore else {
+            return Err(RgitError::OperationFailed(format!(
+                "{} is checked out and the undo would delete it; switch to another branch first", undo.short_name()
+            )).into());
+        };
+        if mode == MoveMode::Keep {
+            checkout_keeping_changes(repo, target, true)?;
+        }
+    }
+
+    let journal = Journal::for_repo(repo);
+    for undo in head.iter().chain(others.iter()) {
+        let mut entry = JournalEntry::new(UNDO_OPERATION, &undo.name).with_detail(operation.key());
+        if let Some(current) = undo.current {
+            entry = entry.with_old_oid(current);
+        }
+        if let Some(restore) = undo.restore {
+            entry = entry.with_new_oid(restore);
+        }
+        journal.record(&entry)?;
+
+        let message = format!("rgit: undo {}", operation.name);
+        match (&undo.remote, undo.restore) {
+            (Some(remote), restore) => restore_remote(repo, config, remote, &undo.name, restore)?,
+            (None, Some(target)) if is_checked_out(repo, undo) => move_checked_out(repo, undo, target, mode)?,
+            (None, Some(target)) => {
+                repo.reference(&undo.name, target, true, &message)?;
+            }
+            (None, None) => {
+                if let Ok(mut reference) = repo.find_reference(&undo.name) {
+                    reference.delete()?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check out `target` over the current HEAD, failing on local changes it would overwrite
+fn checkout_keeping_changes(repo: &Repository, target: Oid, dry_run: bool) -> Result<()> {
+    let commit = repo.find_commit(target)?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.safe();
+    if dry_run {
+        checkout.dry_run();
+    }
+    repo.checkout_tree(commit.as_object(), Some(&mut checkout)).map_err(|e| match e.code() {
+        ErrorCode::Conflict => RgitError::OperationFailed(
+            "undoing would overwrite local changes; commit or stash them, or use --hard to discard them or --soft to keep the files as they are".to_string()
+        ).into(),
+        _ => anyhow::Error::from(e),
+    })
+}
+
+fn move_checked_out(repo: &Repository, undo: &RefUndo, target: Oid, mode: MoveMode) -> Result<()> {
+    if let Some(current) = undo.current {
+        repo.reference("ORIG_HEAD", current, true, "rgit: undo")?;
+    }
+    if mode == MoveMode::Keep {
+        checkout_keeping_changes(repo, target, false)?;
+    }
+    if undo.name == "HEAD" {
+        repo.set_head_detached(target)?;
+    } else {
+        repo.reference(&undo.name, target, true, "rgit: undo")?;
+    }
+    if mode == MoveMode::Hard {
+        repo.reset(repo.find_commit(target)?.as_object(), ResetType::Hard, None)?;
+    }
+    Ok(())
+}
+
+/// Push a remote branch back to `target`, or delete it when there's none
+fn restore_remote(repo: &Repository, config: &Config, remote_name: &str, name: &str, target: Option<Oid>) -> Result<()> {
+    let mut remote = repo.find_remote(remote_name)
+        .map_err(|_| RgitError::RemoteNotFound(remote_name.to_string()))?;
+    let refspec = match target {
+        Some(oid) => format!("{}:{}", oid, name),
+        None => format!(":{}", name),
+    };
+    push::push_refspecs(&mut remote, &[refspec], config)?;
+
+    if let (Some(oid), Some(branch)) = (target, name.strip_prefix("refs/heads/")) {
+        repo.reference(&format!("refs/remotes/{}/{}", remote_name, branch), oid, true, "rgit: undo")?;
+    }
+    Ok(())
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    fn entry(operation: &str, reference: &str, seconds: i64) -> JournalEntry {
+        let mut entry = JournalEntry::new(operation, reference).with_old_oid(Oid::zero());
+        entry.timestamp = DateTime::from_timestamp(1_700_000_000 + seconds, 0).unwrap();
+        entry
+    }
+
+    #[test]
+    fn test_operations_group_entries_and_track_undos() {
+        let filter = entry("filter", "refs/heads/main", 0);
+        let entries = vec![
+            filter.clone(),
+            entry("filter", "refs/heads/feature", 1),
+            entry("reset --hard", "refs/heads/main", 2),
+            entry("reset --hard", "refs/heads/main", 3),
+            entry("undo", "refs/heads/main", 60).with_detail(operations(std::slice::from_ref(&filter))[0].key()),
+        ];
+        let ops = operations(&entries);
+        let names: Vec<(&str, usize, bool)> = ops.iter()
+            .map(|op| (op.name.as_str(), op.entries.len(), op.undone))
+            .collect();
+        assert_eq!(names, [("undo", 1, false), ("reset --hard", 1, false), ("reset --hard", 1, false), ("filter", 2, true)]);
+        assert_eq!(ops[3].describe(), "filter (2 refs)");
+        assert_eq!(ops[0].describe(), "undo of filter heads/main");
+
+        // Undoing the undo redoes the filter
+        let mut entries = entries;
+        entries.push(entry("undo", "refs/heads/main", 120).with_detail(ops[0].key()));
+        let ops = operations(&entries);
+        assert!(ops[1].undone);
+        assert!(!ops[4].undone);
+    }
+
+    #[test]
+    fn test_undo_restores_refs_and_files() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        let journal = Journal::for_repo(repo.repo());
+        journal.record(&JournalEntry::new("reset --hard", "refs/heads/main").with_old_oid(second).with_new_oid(first)).unwrap();
+        journal.record(&JournalEntry::new("reset --hard", "refs/heads/topic").with_new_oid(first)).unwrap();
+        let target = repo.repo().find_commit(first).unwrap();
+        repo.repo().reset(target.as_object(), ResetType::Hard, None).unwrap();
+        repo.branch("topic");
+
+        let ops = operations(&journal.entries().unwrap());
+        let refs = plan(repo.repo(), &ops[0]).unwrap();
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].restore, Some(second));
+        assert!(!refs[0].moved_since());
+
+        // Local changes the undo would overwrite stop it before anything moves
+        repo.write("file.txt", "local\n");
+        assert!(undo_refs(repo.repo(), &Config::default(), &ops[0], &refs, MoveMode::Keep).is_err());
+        assert_eq!(repo.head(), first);
+        assert!(repo.repo().find_branch("topic", git2::BranchType::Local).is_ok());
+
+        repo.write("file.txt", "one\n");
+        undo_refs(repo.repo(), &Config::default(), &ops[0], &refs, MoveMode::Keep).unwrap();
+        assert_eq!(repo.head(), second);
+        assert_eq!(std::fs::read_to_string(repo.path().join("file.txt")).unwrap(), "two\n");
+        assert!(repo.repo().find_branch("topic", git2::BranchType::Local).is_err());
+
+        let ops = operations(&journal.entries().unwrap());
+        assert_eq!(ops[0].name, UNDO_OPERATION);
+        assert!(ops[1].undone);
+        assert!(plan(repo.repo(), &ops[1]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Pathspec, PathspecFlags};
+use std::path::{Path, PathBuf};
+
+use crate::cli::UnstageArgs;
+use crate::commands::add::{AddConfig, PatchMode, PatchProcessor};
+use crate::commands::restore::{plan_restore, restore_paths, RestoreSource, RestoreTargets};
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::interactive::{FileItem, FileSelector};
+use crate::validation::validate_pathspec;
+
+/// Execute the unstage command
+pub async fn execute(args: &UnstageArgs, rgit: &RgitCore, config: &Config) -> Result<()> {
+    if args.patch {
+        return unstage_hunks(rgit, &args.paths, config);
+    }
+
+    let repo = &rgit.repo;
+    let (specs, cwd) = if args.paths.is_empty() {
+        let picked = pick_staged_files(rgit, config)?;
+        if picked.is_empty() {
+            ui_println!("{} No staged changes to unstage", "ℹ️".blue());
+            return Ok(());
+        }
+        // Picked paths are relative to the working tree root
+        (picked, rgit.repo_path.clone())
+    } else {
+        (args.paths.clone(), std::env::current_dir()?)
+    };
+
+    let targets = RestoreTargets { index: true, worktree: false };
+    let source = RestoreSource::resolve(repo, None, targets)?;
+    let paths = plan_restore(repo, &rgit.repo_path, &cwd, &specs, &source)?;
+    restore_paths(repo, &rgit.repo_path, &paths, &source, targets)?;
+
+    for path in &paths {
+        ui_println!("  {} {}", "↩️".blue(), path);
+    }
+    ui_println!("{} Unstaged {} file{}; the working tree is unchanged",
+            "✅".green().bold(),
+            paths.len(),
+            if paths.len() == 1 { "" } else { "s" });
+    Ok(())
+}
+
+/// Let the user choose from the staged files; empty when nothing is staged
+fn pick_staged_files(rgit: &RgitCore, config: &Config) -> Result<Vec<String>> {
+    let staged = rgit.status()?.staged;
+    if staged.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !config.is_interactive() {
+        return Err(RgitError::InvalidArgument(
+            "no paths given; name the files to unstage".to_string()
+        ).into());
+    }
+
+    let items = staged.iter()
+        .map(|file| FileItem {
+            path: PathBuf::from(&file.path),
+            status: file.status_symbol(true).to_string(),
+            size: Some(file.size),
+            selected: false,
+        })
+        .collect();
+    let selected = FileSelector::new()
+        .with_prompt("Select files to unstage")
+        .with_files(items)
+        .with_details()
+        .select()?;
+
+    if selected.is_empty() {
+        return Err(RgitError::OperationCancelled.into());
+    }
+    Ok(selected.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+// =============================================================================
+// Hunks
+// =============================================================================
+
+/// Interactively take hunks of the staged changes back out of the index,
+/// like `git reset -p`. Shared by `unstage -p` and `restore --staged -p`.
+pub fn unstage_hunks(rgit: &RgitCore, specs: &[String], config: &Config) -> Result<()> {
+    if !config.is_interactive() {
+        return Err(RgitError::NonInteractiveEnvironment.into());
+    }
+
+    let files = staged_files_matching(rgit, specs)?;
+    if files.is_empty() {
+        ui_println!("{} No staged changes to unstage", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let processor = PatchProcessor::new(&rgit.repo, AddConfig::default())
+        .with_mode(PatchMode::Unstage);
+
+    let mut total = 0;
+    for file in &files {
+        total += processor.process_file(Path::new(file))?;
+    }
+
+    if total == 0 {
+        ui_println!("\n{} No hunks were unstaged", "ℹ️".blue());
+    } else {
+        ui_println!("\n{} Unstaged {} hunk{}; the working tree is unchanged",
+                "✅".green().bold(),
+                total,
+                if total == 1 { "" } else { "s" });
+        ui_println!("{} Use {} to review what is still staged", "💡".blue(), "rgit diff --staged".cyan());
+    }
+    Ok(())
+}
+
+/// Staged files matched by `specs`, or all of them when none are given
+fn staged_files_matching(rgit: &RgitCore, specs: &[String]) -> Result<Vec<String>> {
+    let staged: Vec<String> = rgit.status()?.staged.into_iter().map(|f| f.path).collect();
+    if specs.is_empty() {
+        return Ok(staged);
+    }
+
+    let cwd = std::env::current_dir()?;
+    let relative = specs.iter()
+        .map(|spec| validate_pathspec(&rgit.repo_path, &cwd, spec))
+        .collect::<Result<Vec<_>, _>>()?;
+    if relative.iter().any(|s| s.is_empty()) {
+        // The working tree root
+        return Ok(staged);
+    }
+    let pathspec = Pathspec::new(&relative)?;
+
+    Ok(staged.into_iter()
+        .filter(|path| pathspec.matches_path(Path::new(path), PathspecFlags::DEFAULT))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[tokio::test]
+    async fn test_unstage_keeps_worktree() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "Initial commit");
+        repo.write("a.txt", "two\n").write("b.txt", "new\n").stage("a.txt").stage("b.txt");
+
+        let args = UnstageArgs { paths: vec![repo.path().join("a.txt").to_string_lossy().to_string()], patch: false };
+        execute(&args, &repo.core(), &Config::default()).await.unwrap();
+
+        let staged: Vec<String> = staged_files_matching(&repo.core(), &[]).unwrap();
+        assert_eq!(staged, vec!["b.txt".to_string()]);
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt")).unwrap(), "two\n");
+    }
+}
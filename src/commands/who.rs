@@ -0,0 +1,181 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Repository, Sort, Time};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::cli::WhoArgs;
+use crate::codeowners::{CodeOwners, Rule};
+use crate::commands::rm::index_paths_matching;
+use crate::config::Config;
+use crate::core::{DiffOptionsBuilder, RgitCore};
+use crate::error::RgitError;
+use crate::utils::{format_time_ago, parse_date_spec};
+use crate::validation::validate_pathspec;
+
+/// Someone who changed the matching files
+#[derive(Debug, Clone, PartialEq)]
+struct Committer {
+    name: String,
+    email: String,
+    commits: usize,
+    last_commit: Time,
+}
+
+/// Execute the who command
+pub async fn execute(args: &WhoArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let workdir = rgit.repo.workdir().ok_or(RgitError::NotInRepository)?;
+    let since = parse_date_spec(&args.since)
+        .ok_or_else(|| RgitError::InvalidArgument(format!("Unrecognized date: {}", args.since)))?;
+
+    let cwd = std::env::current_dir()?;
+    let index = rgit.repo.index()?;
+    let mut specs = Vec::new();
+    let mut files = Vec::new();
+    for path in &args.paths {
+        let spec = validate_pathspec(workdir, &cwd, path)?;
+        let matched = index_paths_matching(&index, &spec)?;
+        if matched.is_empty() {
+            return Err(RgitError::InvalidArgument(format!("'{}' did not match any tracked files", path)).into());
+        }
+        files.extend(matched);
+        specs.push(spec);
+    }
+    files.sort();
+    files.dedup();
+
+    ui_println!("{} Who knows {} ({} file{})",
+            "👥".blue(), args.paths.join(" ").cyan(), files.len(), if files.len() == 1 { "" } else { "s" });
+    println!();
+
+    let committers = recent_committers(&rgit.repo, &specs, since)?;
+    if committers.is_empty() {
+        ui_println!("{} No commits touched these files since {}", "💡".blue(), args.since);
+        println!("   Look further back with {}", "--since '5 years ago'".cyan());
+    } else {
+        println!("{} since {}:", "Recent committers".bold(), args.since);
+        let name_width = committers.iter().take(args.limit)
+            .map(|c| console::measure_text_width(&c.name) + c.email.len() + 3)
+            .max()
+            .unwrap_or(0);
+        for (rank, committer) in committers.iter().take(args.limit).enumerate() {
+            let who = format!("{} <{}>", committer.name, committer.email);
+            let padding = " ".repeat(name_width.saturating_sub(console::measure_text_width(&who)));
+            println!("  {}. {}{}  {} commit{}, last {}",
+                    rank + 1,
+                    who.bold(),
+                    padding,
+                    committer.commits.to_string().green(),
+                    if committer.commits == 1 { "" } else { "s" },
+                    format_time_ago(committer.last_commit).dimmed());
+        }
+        if committers.len() > args.limit {
+            println!("  {}", format!("... and {} more", committers.len() - args.limit).dimmed());
+        }
+    }
+
+    if let Some(codeowners) = CodeOwners::load(workdir)? {
+        println!();
+        show_code_owners(&codeowners, &files);
+    }
+
+    Ok(())
+}
+
+/// Authors of non-merge commits since `since` that changed paths matching
+/// `specs`, most commits first
+fn recent_committers(repo: &Repository, specs: &[String], since: i64) -> Result<Vec<Committer>> {
+    let mailmap = repo.mailmap().ok();
+    let mut revwalk = repo.revwalk()?;
+    revwalk.set_sorting(Sort::TIME)?;
+    revwalk.push_head()?;
+
+    let mut by_email: HashMap<String, Committer> = HashMap::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < since {
+            break;
+        }
+        if commit.parent_count() > 1 || !touches(repo, &commit, specs)? {
+            continue;
+        }
+
+        let author = match &mailmap {
+            Some(mailmap) => commit.author_with_mailmap(mailmap)?,
+            None => commit.author(),
+        };
+        let email = author.email().unwrap_or("").to_string();
+        let committer = by_email.entry(email.to_lowercase()).or_insert_with(|| Committer {
+            name: author.name().unwrap_or("unknown").to_string(),
+            email,
+            commits: 0,
+            last_commit: author.when(),
+        });
+        committer.commits += 1;
+        if author.when() > committer.last_commit {
+            committer.last_commit = author.when();
+        }
+    }
+
+    let mut committers: Vec<Committer> = by_email.into_values().collect();
+    committers.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| b.last_commit.cmp(&a.last_commit)));
+    Ok(committers)
+}
+
+/// Whether `commit` changed anything matching `specs` against its parent
+fn touches(repo: &Repository, commit: &git2::Commit, specs: &[String]) -> Result<bool> {
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let mut options = DiffOptionsBuilder::new().paths(specs.iter().cloned()).build();
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), Some(&mut options))?;
+    Ok(diff.deltas().len() > 0)
+}
+
+/// The CODEOWNERS rules covering `files`, with how many files each decides
+fn show_code_owners(codeowners: &CodeOwners, files: &[String]) {
+    println!("{} ({}):", "Code owners".bold(), codeowners.path.dimmed());
+
+    // Rules in file order, each with the number of files it decides
+    let mut decided: BTreeMap<usize, (&Rule, usize)> = BTreeMap::new();
+    let mut unowned = 0;
+    for file in files {
+        match codeowners.rule_for(file).filter(|rule| !rule.owners.is_empty()) {
+            Some(rule) => decided.entry(rule.line).or_insert((rule, 0)).1 += 1,
+            None => unowned += 1,
+        }
+    }
+
+    for (rule, count) in decided.into_values() {
+        println!("  {}  {}",
+                rule.owners.join(" ").green(),
+                format!("{} (line {}), {} file{}", rule.pattern, rule.line, count, if count == 1 { "" } else { "s" }).dimmed());
+    }
+    if unowned > 0 {
+        ui_println!("  {} {} file{} without an owner",
+                "⚠️".yellow(), unowned, if unowned == 1 { "" } else { "s" });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_recent_committers() {
+        let test_repo = TestRepo::new();
+        test_repo.commit_file("src/net/client.rs", "fn a() {}\n", "Add client");
+        test_repo.commit_file("src/net/client.rs", "fn b() {}\n", "Change client");
+        test_repo.commit_file("README.md", "hello\n", "Add readme");
+
+        let repo = test_repo.repo();
+        let committers = recent_committers(repo, &["src/net".to_string()], 0).unwrap();
+        assert_eq!(committers.len(), 1);
+        assert_eq!(committers[0].commits, 2);
+
+        let future = chrono::Utc::now().timestamp() + 3600;
+        assert!(recent_committers(repo, &["src/net".to_string()], future).unwrap().is_empty());
+        assert!(recent_committers(repo, &["docs".to_string()], 0).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,371 @@
+use anyhow::Result;
+use colored::*;
+use git2::{ConfigLevel, Repository};
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use crate::commands::config::level_name;
+use crate::config::Config;
+use crate::utils::{expand_home, is_valid_email};
+
+/// Execute the whoami command
+pub async fn execute(config: &Config) -> Result<()> {
+    let repo = Repository::discover(".").ok();
+    let git_config = match &repo {
+        Some(repo) => repo.config()?,
+        None => git2::Config::open_default()?,
+    };
+
+    ui_println!("{} Commit identity{}", "👤".blue().bold(),
+            repo.as_ref()
+                .and_then(|r| r.workdir())
+                .map(|dir| format!(" for {}", dir.display()).dimmed().to_string())
+                .unwrap_or_default());
+    println!();
+
+    let name = lookup(&git_config, repo.as_ref(), "user.name");
+    let email = lookup(&git_config, repo.as_ref(), "user.email");
+    let signing_key = lookup(&git_config, repo.as_ref(), "user.signingkey");
+    let format = git_config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string());
+    let sign_commits = git_config.get_bool("commit.gpgsign").unwrap_or(false) || config.git.sign_commits;
+
+    show_value("Name", &name);
+    show_value("Email", &email);
+    show_value("Signing key", &signing_key);
+    println!("  {:<12} {} ({})", "Signing:".bold(),
+            if sign_commits { "enabled".green() } else { "disabled".dimmed() },
+            format);
+
+    let mut warnings = Vec::new();
+
+    if name.is_none() || email.is_none() {
+        warnings.push("Commits will fail until user.name and user.email are set".to_string());
+    }
+    if let Some(email) = &email {
+        if !is_valid_email(&email.value) {
+            warnings.push(format!("'{}' does not look like an email address", email.value));
+        }
+    }
+
+    // Values that override or disagree with the git configuration
+    for (var, configured) in [
+        ("GIT_AUTHOR_NAME", &name),
+        ("GIT_AUTHOR_EMAIL", &email),
+        ("GIT_COMMITTER_NAME", &name),
+        ("GIT_COMMITTER_EMAIL", &email),
+    ] {
+        if let Ok(value) = std::env::var(var) {
+            if configured.as_ref().map(|c| &c.value) != Some(&value) {
+                warnings.push(format!("{} is set to '{}' and overrides git for tools that honor it", var, value));
+            }
+        }
+    }
+    for (key, rgit_value, configured) in [
+        ("user.name", &config.user.name, &name),
+        ("user.email", &config.user.email, &email),
+    ] {
+        if let (Some(rgit_value), Some(configured)) = (rgit_value, configured) {
+            if rgit_value != &configured.value {
+                warnings.push(format!("rgit {} is '{}' but commits use '{}'", key, rgit_value, configured.value));
+            }
+        }
+    }
+
+    println!();
+    match &signing_key {
+        Some(key) => {
+            let program = signing_program(&git_config, &format, config);
+            match check_signing_key(&format, &key.value, program.as_deref()) {
+                Ok(()) => ui_println!("{} Signing key is usable", "✅".green()),
+                Err(reason) => {
+                    ui_println!("{} Signing key is not usable: {}", "❌".red(), reason);
+                    if sign_commits {
+                        warnings.push("Commit signing is enabled, so commits will fail".to_string());
+                    }
+                }
+            }
+        }
+        None if sign_commits => warnings.push("Commit signing is enabled but no user.signingkey is set".to_string()),
+        None => {}
+    }
+
+    if warnings.is_empty() {
+        ui_println!("{} Identity looks good", "✅".green());
+    } else {
+        println!();
+        for warning in &warnings {
+            ui_println!("{} {}", "⚠️".yellow(), warning);
+        }
+        ui_println!("\n{} Use {} to adjust your git identity", "💡".blue(), "rgit config --git edit".cyan());
+    }
+
+    Ok(())
+}
+
+/// A configuration value together with where it was defined
+#[derive(Debug, Clone, PartialEq)]
+struct SourcedValue {
+    value: String,
+    level: ConfigLevel,
+    path: Option<PathBuf>,
+}
+
+/// Look up the effective value of a git key and the file it came from
+fn lookup(git_config: &git2::Config, repo: Option<&Repository>, key: &str) -> Option<SourcedValue> {
+    let entry = git_config.get_entry(key).ok()?;
+    let value = entry.value()?.to_string();
+    let level = entry.level();
+
+    Some(SourcedValue { value, level, path: level_path(level, repo) })
+}
+
+/// File that backs a configuration level
+fn level_path(level: ConfigLevel, repo: Option<&Repository>) -> Option<PathBuf> {
+    match level {
+        ConfigLevel::Local => repo.map(|r| r.path().join("config")),
+        ConfigLevel::Global => git2::Config::find_global().ok(),
+        ConfigLevel::XDG => git2::Config::find_xdg().ok(),
+        ConfigLevel::System => git2::Config::find_system().ok(),
+        _ => None,
+    }
+}
+
+fn show_value(label: &str, value: &Option<SourcedValue>) {
+    let label = format!("{}:", label);
+    match value {
+        Some(v) => println!("  {:<12} {} {}",
+                label.bold(),
+                v.value.cyan(),
+                format!("({}{})",
+                        level_name(v.level),
+                        v.path.as_ref().map(|p| format!(": {}", p.display())).unwrap_or_default()).dimmed()),
+        None => println!("  {:<12} {}", label.bold(), "(not set)".yellow()),
+    }
+}
+
+/// Program git signs with for `format`
+pub(crate) fn signing_program(git_config: &git2::Config, format: &str, config: &Config) -> Option<String> {
+    match format {
+        "ssh" => git_config.get_string("gpg.ssh.program").ok(),
+        "x509" => git_config.get_string("gpg.x509.program").ok(),
+        _ => git_config.get_string("gpg.program").ok()
+            .or_else(|| config.integrations.gpg.program.clone()),
+    }
+}
+
+/// Check that the configured signing key can actually be used
+pub(crate) fn check_signing_key(format: &str, key: &str, program: Option<&str>) -> std::result::Result<(), String> {
+    match format {
+        "ssh" => check_ssh_key(key),
+        "x509" => check_gpg_key(program.unwrap_or("gpgsm"), key),
+        _ => check_gpg_key(program.unwrap_or("gpg"), key),
+    }
+}
+
+fn check_gpg_key(program: &str, key: &str) -> std::result::Result<(), String> {
+    let output = Command::new(program)
+        .args(["--batch", "--list-secret-keys", key])
+        .output()
+        .map_err(|_| format!("'{}' is not installed", program))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("no secret key '{}' in {}", key, program))
+    }
+}
+
+/// The public key itself when user.signingkey holds one instead of a path
+pub(crate) fn literal_ssh_key(key: &str) -> Option<&str> {
+    key.strip_prefix("key::").or_else(|| key.starts_with("ssh-").then_some(key))
+}
+
+fn check_ssh_key(key: &str) -> std::result::Result<(), String> {
+    // Literal public keys must be loaded in the agent
+    if let Some(public_key) = literal_ssh_key(key) {
+        let output = Command::new("ssh-add").arg("-L").output()
+            .map_err(|_| "ssh-add is not installed".to_string())?;
+        let agent_keys = String::from_utf8_lossy(&output.stdout);
+        let key_body = public_key.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+        return if agent_keys.lines().any(|line| line.starts_with(&key_body)) {
+            Ok(())
+        } else {
+            Err("key is not loaded in ssh-agent".to_string())
+        };
+    }
+
+    let path = expand_home(key);
+    if !path.exists() {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    // A public key path needs its private half next to it
+    if path.extension().is_some_and(|ext| ext == "pub") && !path.with_extension("").exists() {
+        return Err(format!("private key for {} not found", path.display()));
+    }
+    Ok(())
+}
+
+/// Check that the agent holding keys for `format` answers, describing it
+pub(crate) fn check_signing_agent(format: &str) -> std::result::Result<String, String> {
+    if format == "ssh" {
+        return check_ssh_agent();
+    }
+    // Starts gpg-agent if it isn't running, as signing would
+    let output = Command::new("gpg-connect-agent").arg("/bye").output()
+        .map_err(|_| "gpg-connect-agent is not installed".to_string())?;
+    if output.status.success() {
+        Ok("gpg-agent is reachable".to_string())
+    } else {
+        Err(format!("gpg-agent is not reachable: {}", last_line(&output.stderr)))
+    }
+}
+
+/// Check that ssh-agent answers, describing how many keys it holds
+pub(crate) fn check_ssh_agent() -> std::result::Result<String, String> {
+    if std::env::var_os("SSH_AUTH_SOCK").is_none() {
+        return Err("SSH_AUTH_SOCK is not set".to_string());
+    }
+    let output = Command::new("ssh-add").arg("-l").output()
+        .map_err(|_| "ssh-add is not installed".to_string())?;
+    // ssh-add exits with 1 for an empty agent and 2 when it can't reach one
+    match output.status.code() {
+        Some(0) => {
+            let keys = String::from_utf8_lossy(&output.stdout).lines().count();
+            Ok(format!("ssh-agent holds {} key(s)", keys))
+        }
+        Some(1) => Ok("ssh-agent holds no keys".to_string()),
+        _ => Err(format!("ssh-agent is not reachable: {}", last_line(&output.stderr))),
+    }
+}
+
+/// Data signed by [`sign_roundtrip`]
+const ROUNDTRIP_PAYLOAD: &[u8] = b"rgit signing roundtrip\n";
+
+/// Sign a test payload the way git signs commits and verify the signature
+pub(crate) fn sign_roundtrip(format: &str, key: &str, program: Option<&str>) -> std::result::Result<(), String> {
+    let dir = tempfile::TempDir::new().map_err(|e| e.to_string())?;
+    let signature_path = dir.path().join("payload.sig");
+
+    if format == "ssh" {
+        let program = program.unwrap_or("ssh-keygen");
+        let mut sign = Command::new(program);
+        sign.args(["-Y", "sign", "-n", "git", "-f"]);
+        match literal_ssh_key(key) {
+            // Like git, hand a literal key over as a file and sign through the agent
+            Some(public_key) => {
+                let key_path = dir.path().join("key.pub");
+                fs::write(&key_path, public_key).map_err(|e| e.to_string())?;
+                sign.arg(key_path).arg("-U");
+            }
+            None => {
+                sign.arg(expand_home(key));
+            }
+        }
+        let signature = run_with_input(&mut sign, ROUNDTRIP_PAYLOAD)
+            .map_err(|e| format!("signing failed: {}", e))?;
+        fs::write(&signature_path, signature).map_err(|e| e.to_string())?;
+
+        let mut verify = Command::new(program);
+        verify.args(["-Y", "check-novalidate", "-n", "git", "-s"]).arg(&signature_path);
+        run_with_input(&mut verify, ROUNDTRIP_PAYLOAD)
+            .map_err(|e| format!("verification failed: {}", e))?;
+    } else {
+        let program = program.unwrap_or(if format == "x509" { "gpgsm" } else { "gpg" });
+        let mut sign = Command::new(program);
+        sign.args(["--status-fd=2", "-bsau", key]);
+        let signature = run_with_input(&mut sign, ROUNDTRIP_PAYLOAD)
+            .map_err(|e| format!("signing failed: {}", e))?;
+        fs::write(&signature_path, signature).map_err(|e| e.to_string())?;
+
+        let mut verify = Command::new(program);
+        verify.args(["--batch", "--verify"]).arg(&signature_path).arg("-");
+        run_with_input(&mut verify, ROUNDTRIP_PAYLOAD)
+            .map_err(|e| format!("verification failed: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Run `command` with `input` on stdin, returning its stdout
+fn run_with_input(command: &mut Command, input: &[u8]) -> std::result::Result<Vec<u8>, String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|_| format!("'{}' is not installed", program))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(output.stdout)
+    } else {
+        Err(last_line(&output.stderr))
+    }
+}
+
+/// The last message in a tool's stderr, skipping gpg status lines
+fn last_line(stderr: &[u8]) -> String {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(str::trim)
+        .rev()
+        .find(|line| !line.is_empty() && !line.starts_with("[GNUPG:]"))
+        .unwrap_or("no details")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_lookup_reports_local_source() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        repo.config().unwrap().set_str("user.email", "local@example.com").unwrap();
+
+        let git_config = repo.config().unwrap();
+        let email = lookup(&git_config, Some(&repo), "user.email").unwrap();
+        assert_eq!(email.value, "local@example.com");
+        assert_eq!(email.level, ConfigLevel::Local);
+        assert_eq!(email.path, Some(repo.path().join("config")));
+
+        assert!(lookup(&git_config, Some(&repo), "user.doesnotexist").is_none());
+    }
+
+    #[test]
+    fn test_check_ssh_key_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let public = temp_dir.path().join("id_test.pub");
+        std::fs::write(&public, "ssh-ed25519 AAAA test").unwrap();
+
+        let public_path = public.display().to_string();
+        assert!(check_ssh_key(&public_path).is_err());
+
+        std::fs::write(temp_dir.path().join("id_test"), "private").unwrap();
+        assert!(check_ssh_key(&public_path).is_ok());
+        assert!(check_ssh_key("/does/not/exist").is_err());
+    }
+
+    #[test]
+    fn test_sign_roundtrip_with_ssh_key() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = temp_dir.path().join("id_ed25519");
+        let generated = Command::new("ssh-keygen")
+            .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+            .arg(&key)
+            .status();
+        if !generated.is_ok_and(|status| status.success()) {
+            return; // ssh-keygen isn't available
+        }
+
+        assert!(sign_roundtrip("ssh", &key.display().to_string(), None).is_ok());
+        let missing = temp_dir.path().join("missing").display().to_string();
+        assert!(sign_roundtrip("ssh", &missing, None).unwrap_err().starts_with("signing failed"));
+    }
+}
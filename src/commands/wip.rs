@@ -0,0 +1,180 @@
+use anyhow::Result;
+use colored::*;
+use git2::{Commit, Oid, Repository, Signature};
+
+use crate::cli::{WipArgs, WipCommands};
+use crate::commands::backup::snapshot_trees;
+use crate::config::Config;
+use crate::core::RgitCore;
+use crate::error::RgitError;
+use crate::timings::{self, Phase};
+use crate::utils::shorten_oid;
+
+/// Trailer marking a WIP commit and naming the commit that holds its index
+const INDEX_TRAILER: &str = "Rgit-Wip-Index";
+
+/// Namespace keeping each WIP commit's index commit alive
+const REF_PREFIX: &str = "refs/rgit/wip/";
+
+/// Execute the wip command
+pub async fn execute(args: &WipArgs, rgit: &RgitCore, _config: &Config) -> Result<()> {
+    let signature = rgit.get_signature()?;
+    match args.action {
+        None => match save(&rgit.repo, args.message.as_deref(), &signature)? {
+            Some(wip) => {
+                ui_println!("{} Saved work in progress as {}", "💾".green(), shorten_oid(&wip, 8).yellow());
+                ui_println!("{} Use {} to pick up where you left off", "💡".blue(), "rgit wip pop".cyan());
+            }
+            None => ui_println!("{} Nothing to save, working tree clean", "ℹ️".blue()),
+        },
+        Some(WipCommands::Pop) => {
+            let popped = pop(&rgit.repo)?;
+            ui_println!("{} Restored work in progress from {}", "↩️".green(), shorten_oid(&popped.wip, 8).yellow());
+            if !popped.kept_staging {
+                ui_println!("{} The staged changes weren't recorded, so everything is unstaged", "⚠️".yellow());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Commit every tracked and untracked change on top of HEAD as a WIP
+/// commit, recording the index separately so `pop` can restore it.
+/// Returns `None` when there is nothing to save.
+pub fn save(repo: &Repository, message: Option<&str>, signature: &Signature) -> Result<Option<Oid>> {
+    let head = repo.head().ok().and_then(|head| head.peel_to_commit().ok())
+        .ok_or_else(|| RgitError::InvalidRepositoryState("nothing has been committed yet".to_string()))?;
+    let (index_tree, worktree_tree) = snapshot_trees(repo, true)?
+        .ok_or_else(|| RgitError::InvalidRepositoryState("resolve conflicts before saving work in progress".to_string()))?;
+    if index_tree.id() == head.tree_id() && worktree_tree.id() == index_tree.id() {
+        return Ok(None);
+    }
+
+    let index_commit = repo.commit(None, signature, signature, "rgit wip: index", &index_tree, &[&head])?;
+    repo.reference(&format!("{}{}", REF_PREFIX, index_commit), index_commit, true, "rgit wip")?;
+
+    let subject = match message {
+        Some(message) => format!("WIP: {}", message.trim()),
+        None => format!("WIP on {}", branch_label(repo)),
+    };
+    let message = format!("{}\n\n{}: {}", subject, INDEX_TRAILER, index_commit);
+    let wip = repo.commit(Some("HEAD"), signature, signature, &message, &worktree_tree, &[&head])?;
+
+    // Everything is committed now, untracked files included
+    let mut index = repo.index()?;
+    index.read_tree(&worktree_tree)?;
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(Some(wip))
+}
+
+/// What `pop` undid
+#[derive(Debug)]
+pub struct Popped {
+    pub wip: Oid,
+    /// Whether the staged changes came back as staged
+    pub kept_staging: bool,
+}
+
+/// Move HEAD back past the WIP commit it points at and restore the index
+/// from when it was saved. The working tree is left alone, so anything
+/// changed since the WIP commit survives.
+pub fn pop(repo: &Repository) -> Result<Popped> {
+    let wip = repo.head()?.peel_to_commit()?;
+    let recorded = wip_index(&wip)
+        .ok_or_else(|| RgitError::InvalidArgument(format!("HEAD ({}) is not a WIP commit", shorten_oid(&wip.id(), 8))))?;
+    let parent = wip.parent(0)?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() || index.write_tree()? != wip.tree_id() {
+        return Err(RgitError::InvalidRepositoryState(
+            "changes were staged after the WIP commit; commit or unstage them first".to_string()
+        ).into());
+    }
+
+    let index_commit = repo.find_commit(recorded).ok().filter(|c| c.parent_id(0).ok() == Some(parent.id()));
+    let index_tree = match &index_commit {
+        Some(commit) => commit.tree()?,
+        None => parent.tree()?,
+    };
+
+    repo.head()?.set_target(parent.id(), "rgit wip pop")?;
+    index.read_tree(&index_tree)?;
+    timings::time(Phase::IndexWrite, || index.write())?;
+    if let Ok(mut reference) = repo.find_reference(&format!("{}{}", REF_PREFIX, recorded)) {
+        reference.delete()?;
+    }
+
+    Ok(Popped { wip: wip.id(), kept_staging: index_commit.is_some() })
+}
+
+/// The index commit a WIP commit records, if it is one
+fn wip_index(commit: &Commit) -> Option<Oid> {
+    commit.message()?.lines().rev().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == INDEX_TRAILER).then(|| Oid::from_str(value.trim()).ok()).flatten()
+    })
+}
+
+fn branch_label(repo: &Repository) -> String {
+    match repo.head() {
+        Ok(head) if head.is_branch() => head.shorthand().unwrap_or("HEAD").to_string(),
+        _ => "detached HEAD".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use git2::{Status, StatusOptions};
+
+    fn signature() -> Signature<'static> {
+        Signature::now("Test", "test@example.com").unwrap()
+    }
+
+    fn status_of(repo: &Repository, path: &str) -> Status {
+        let mut options = StatusOptions::new();
+        options.include_untracked(true);
+        repo.statuses(Some(&mut options)).unwrap().iter()
+            .find(|entry| entry.path() == Some(path))
+            .map(|entry| entry.status())
+            .unwrap_or(Status::CURRENT)
+    }
+
+    #[test]
+    fn test_save_and_pop_round_trip() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "Initial commit");
+        repo.commit_file("b.txt", "keep\n", "Add b");
+        repo.write("a.txt", "staged\n").stage("a.txt");
+        repo.write("a.txt", "staged and more\n");
+        repo.write("new.txt", "untracked\n");
+        repo.remove("b.txt");
+
+        let wip = save(repo.repo(), Some("halfway"), &signature()).unwrap().unwrap();
+        let commit = repo.repo().find_commit(wip).unwrap();
+        assert!(commit.summary().unwrap().starts_with("WIP: halfway"));
+        assert!(commit.tree().unwrap().get_path(std::path::Path::new("new.txt")).is_ok());
+        assert!(repo.repo().statuses(None).unwrap().is_empty());
+        assert_eq!(save(repo.repo(), None, &signature()).unwrap(), None);
+
+        let popped = pop(repo.repo()).unwrap();
+        assert!(popped.kept_staging);
+        let head = repo.repo().head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(head.summary(), Some("Add b"));
+        assert_eq!(status_of(repo.repo(), "a.txt"), Status::INDEX_MODIFIED | Status::WT_MODIFIED);
+        assert_eq!(status_of(repo.repo(), "b.txt"), Status::WT_DELETED);
+        assert_eq!(status_of(repo.repo(), "new.txt"), Status::WT_NEW);
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt")).unwrap(), "staged and more\n");
+        assert!(repo.repo().references_glob("refs/rgit/wip/*").unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_pop_requires_wip_head() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "Initial commit");
+        repo.commit_file("a.txt", "two\n", "Second");
+        assert!(pop(repo.repo()).is_err());
+        assert_eq!(repo.repo().head().unwrap().peel_to_commit().unwrap().summary(), Some("Second"));
+    }
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use colored::*;
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::cli::{WorkspaceArgs, WorkspaceCommands};
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::interactive::TableDisplay;
+use crate::workspace::{run_operation, RepoOutcome, RepoReport, Workspace, WorkspaceOperation, MANIFEST_FILE};
+
+/// Execute the workspace command
+pub async fn execute(args: &WorkspaceArgs, config: &Config) -> Result<()> {
+    let current_dir = std::env::current_dir()?;
+
+    match &args.action {
+        WorkspaceCommands::Init { scan } => init_workspace(&current_dir, *scan),
+        WorkspaceCommands::Add { path, name } => {
+            let mut workspace = Workspace::discover(&current_dir)?;
+            let repo = workspace.add_repo(path, name.as_deref())?.clone();
+            workspace.save()?;
+            ui_println!("{} Registered {} ({})", "✅".green(), repo.name.cyan(), repo.path.display());
+            Ok(())
+        }
+        WorkspaceCommands::Remove { name } => {
+            let mut workspace = Workspace::discover(&current_dir)?;
+            workspace.remove_repo(name)?;
+            workspace.save()?;
+            ui_println!("{} Unregistered {}", "✅".green(), name.cyan());
+            Ok(())
+        }
+        WorkspaceCommands::List => list_repos(&Workspace::discover(&current_dir)?),
+        WorkspaceCommands::Status => {
+            run_and_report(&current_dir, WorkspaceOperation::Status, args, config).await
+        }
+        WorkspaceCommands::Pull => {
+            run_and_report(&current_dir, WorkspaceOperation::Pull, args, config).await
+        }
+        WorkspaceCommands::Push => {
+            run_and_report(&current_dir, WorkspaceOperation::Push, args, config).await
+        }
+        WorkspaceCommands::Foreach { command } => {
+            run_and_report(&current_dir, WorkspaceOperation::Foreach(command.clone()), args, config).await
+        }
+    }
+}
+
+/// Create a workspace, optionally registering repositories found below it
+fn init_workspace(root: &Path, scan: bool) -> Result<()> {
+    let mut workspace = Workspace::init(root)?;
+    ui_println!("{} Created {} in {}", "✅".green(), MANIFEST_FILE.cyan(), root.display());
+
+    if scan {
+        let mut entries: Vec<_> = fs::read_dir(root)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.join(".git").exists())
+            .collect();
+        entries.sort();
+
+        for path in entries {
+            match workspace.add_repo(&path, None) {
+                Ok(repo) => ui_println!("  {} {}", "➕".green(), repo.name.cyan()),
+                Err(e) => ui_println!("  {} {}: {}", "⚠️".yellow(), path.display(), e),
+            }
+        }
+        workspace.save()?;
+    }
+
+    ui_println!("\n{} Next steps:", "💡".blue());
+    ui_println!("  • {} - Register a repository", "rgit workspace add <path>".cyan());
+    ui_println!("  • {} - Check every repository", "rgit workspace status".cyan());
+    Ok(())
+}
+
+/// List registered repositories
+fn list_repos(workspace: &Workspace) -> Result<()> {
+    ui_println!("{} Workspace at {}", "🗂️".blue().bold(), workspace.root.display().to_string().dimmed());
+
+    if workspace.manifest.repos.is_empty() {
+        ui_println!("  {} No repositories registered", "ℹ️".blue());
+        ui_println!("  {} Use {} to add one", "💡".blue(), "rgit workspace add <path>".cyan());
+        return Ok(());
+    }
+
+    for repo in &workspace.manifest.repos {
+        let exists = workspace.repo_path(repo).exists();
+        let icon = if exists { "📁".normal() } else { "❓".yellow() };
+        ui_println!("  {} {} {}", icon, repo.name.cyan().bold(), repo.path.display().to_string().dimmed());
+    }
+    Ok(())
+}
+
+/// Run an operation across the workspace and print the aggregated table
+async fn run_and_report(
+    current_dir: &Path,
+    operation: WorkspaceOperation,
+    args: &WorkspaceArgs,
+    config: &Config,
+) -> Result<()> {
+    let workspace = Workspace::discover(current_dir)?;
+    if workspace.manifest.repos.is_empty() {
+        ui_println!("{} No repositories registered in this workspace", "ℹ️".blue());
+        return Ok(());
+    }
+
+    let jobs = args.jobs.unwrap_or(config.advanced.performance.threads);
+    let label = match &operation {
+        WorkspaceOperation::Status => "Checking".to_string(),
+        WorkspaceOperation::Pull => "Pulling".to_string(),
+        WorkspaceOperation::Push => "Pushing".to_string(),
+        WorkspaceOperation::Foreach(command) => format!("Running '{}' in", command),
+    };
+    ui_println!("{} {} {} repositories...", "🗂️".blue().bold(), label, workspace.manifest.repos.len());
+
+    let start = Instant::now();
+    let reports = run_operation(&workspace, operation, jobs, config).await;
+
+    println!();
+    display_report_table(&reports, config.terminal_width());
+    show_report_summary(&reports, start.elapsed());
+
+    let failed = reports.iter().filter(|r| r.outcome == RepoOutcome::Failed).count();
+    if failed > 0 {
+        return Err(RgitError::OperationFailed(format!("{} of {} repositories failed", failed, reports.len())).into());
+    }
+    Ok(())
+}
+
+/// Print one row per repository
+pub fn display_report_table(reports: &[RepoReport], width: usize) {
+    let mut table = TableDisplay::new().with_max_width(width).with_headers(vec![
+        "Repository".to_string(),
+        "State".to_string(),
+        "Branch".to_string(),
+        "Changes".to_string(),
+        "Ahead/Behind".to_string(),
+        "Details".to_string(),
+    ]);
+
+    for report in reports {
+        table.add_row(vec![
+            report.name.clone(),
+            report.outcome.label().to_string(),
+            report.branch.clone(),
+            report.changes.to_string(),
+            format!("{}/{}", report.ahead, report.behind),
+            report.detail.clone(),
+        ]);
+    }
+
+    table.display();
+}
+
+/// Print totals per outcome
+pub fn show_report_summary(reports: &[RepoReport], elapsed: std::time::Duration) {
+    let count = |outcome: RepoOutcome| reports.iter().filter(|r| r.outcome == outcome).count();
+
+    println!();
+    ui_println!("{} Summary ({:.1}s):", "📊".blue().bold(), elapsed.as_secs_f64());
+    let rows = [
+        (RepoOutcome::UpToDate, "up to date"),
+        (RepoOutcome::Updated, "updated"),
+        (RepoOutcome::Attention, "need attention"),
+        (RepoOutcome::Skipped, "skipped"),
+        (RepoOutcome::Failed, "failed"),
+    ];
+    for (outcome, label) in rows {
+        let n = count(outcome);
+        if n > 0 {
+            ui_println!("  {} {} {}", outcome.icon(), n, label);
+        }
+    }
+}
@@ -1,19 +1,71 @@
 use anyhow::{Context, Result};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tracing::{debug, warn};
 
 use crate::error::RgitError;
+use crate::theme::{self, Element};
+use crate::utils::{is_valid_email, parse_date_spec, parse_duration_spec, parse_size};
 
 /// Main configuration structure for rgit
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// UI and display preferences
     pub ui: UiConfig,
+    /// Colors of single elements on top of the `ui.theme` preset
+    #[serde(default)]
+    pub theme: ThemeConfig,
     /// Git operation defaults
     pub git: GitConfig,
+    /// Automatic fetch behavior
+    #[serde(default)]
+    pub fetch: FetchConfig,
+    /// Branch switching behavior
+    #[serde(default)]
+    pub checkout: CheckoutConfig,
+    /// How `rgit pull` integrates fetched commits
+    #[serde(default)]
+    pub pull: PullConfig,
+    /// Where `rgit clean` puts removed files
+    #[serde(default)]
+    pub clean: CleanConfig,
+    /// How diff, show and log pair up moved files
+    #[serde(default)]
+    pub diff: DiffConfig,
+    /// Repository snapshots
+    #[serde(default)]
+    pub backup: BackupConfig,
+    /// Repository maintenance
+    #[serde(default)]
+    pub gc: GcConfig,
+    /// What `rgit audit` flags
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// Credential scanning of staged changes
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// Checks on what gets committed
+    #[serde(default)]
+    pub commit: CommitConfig,
+    /// Checks on what gets pushed
+    #[serde(default)]
+    pub push: PushConfig,
+    /// Forge shorthands and where clones go
+    #[serde(default)]
+    pub clone: CloneConfig,
+    /// URL rewrites by replacement base, like git's `url.<base>.insteadOf`
+    #[serde(default)]
+    pub url: HashMap<String, UrlRewrite>,
+    /// Transport preferences by remote name
+    #[serde(default)]
+    pub remote: HashMap<String, RemotePreferences>,
+    /// Caching of HTTPS credentials
+    #[serde(default)]
+    pub credential: CredentialConfig,
     /// Submodule management settings
     pub submodules: SubmoduleConfig,
     /// Integration settings
@@ -28,11 +80,11 @@ pub struct Config {
 pub struct UiConfig {
     /// Use colored output
     pub colors: bool,
-    /// Color theme (dark, light, auto)
+    /// Color theme (auto, dark, light, solarized, monochrome)
     pub theme: String,
     /// Show progress bars
     pub progress: bool,
-    /// Use emoji icons
+    /// Use emoji icons; without them icons become ASCII labels
     pub icons: bool,
     /// Interactive prompts enabled
     pub interactive: bool,
@@ -40,6 +92,57 @@ pub struct UiConfig {
     pub editor: Option<String>,
     /// Terminal width override
     pub width: Option<usize>,
+    /// Pager for long output ("off" to disable; defaults to git's pager)
+    #[serde(default)]
+    pub pager: Option<String>,
+    /// Answer every confirmation with its default instead of asking, for
+    /// scripts and CI
+    #[serde(default)]
+    pub assume_yes: bool,
+    /// Plain ASCII output without emoji, box drawing, colors or redrawn
+    /// progress lines, for screen readers and dumb terminals
+    #[serde(default)]
+    pub accessible: bool,
+}
+
+/// Colors for parts of the output: a color name like "green" or
+/// "bright blue", "#rrggbb", or "none". Unset elements keep the preset's.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub added: Option<String>,
+    pub removed: Option<String>,
+    pub modified: Option<String>,
+    pub untracked: Option<String>,
+    pub branch: Option<String>,
+    pub commit: Option<String>,
+    pub hunk: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl ThemeConfig {
+    /// Each element with the color configured for it
+    pub fn colors(&self) -> [(Element, Option<&str>); 11] {
+        Element::ALL.map(|element| {
+            let value = match element {
+                Element::Added => &self.added,
+                Element::Removed => &self.removed,
+                Element::Modified => &self.modified,
+                Element::Untracked => &self.untracked,
+                Element::Branch => &self.branch,
+                Element::Commit => &self.commit,
+                Element::Hunk => &self.hunk,
+                Element::Success => &self.success,
+                Element::Warning => &self.warning,
+                Element::Error => &self.error,
+                Element::Hint => &self.hint,
+            };
+            (element, value.as_deref())
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,6 +161,156 @@ pub struct GitConfig {
     pub pull_rebase: bool,
     /// Prune on fetch
     pub auto_prune: bool,
+    /// Clamp future or pre-1980 commit dates to now when rewriting history
+    #[serde(default)]
+    pub normalize_dates: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FetchConfig {
+    /// Fetch before status/branch views at most once per interval (e.g. "15m")
+    pub auto: Option<String>,
+    /// Also start that fetch in the background from log, diff and other read commands
+    pub background: bool,
+    /// Never contact remotes implicitly
+    pub offline: bool,
+    /// Times a clone or fetch is tried again after a transient network error
+    pub retries: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckoutConfig {
+    /// Stash and reapply local changes that block a switch when prompts are off
+    pub autostash: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PullConfig {
+    /// "merge", "rebase" or "ff-only"; unset follows `git.pull_rebase`
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanConfig {
+    /// "repo" keeps removed files under `.git/rgit/trash`, "system" uses the
+    /// desktop trash and "off" deletes them outright
+    pub trash: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiffConfig {
+    /// "true" reports renames, "copies" also reports copies and "false"
+    /// shows moved files as a deletion and an addition
+    pub renames: String,
+    /// Similarity in percent at which a deleted and an added file are paired
+    pub rename_threshold: u16,
+    /// Draw changed images inline: "off", "auto", "kitty" or "sixel"
+    pub image_preview: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Snapshot refs and local changes before rebase, reset and clean
+    pub auto_before_destructive: bool,
+    /// Automatic snapshots to keep, newest first (0 keeps all); their age
+    /// is limited by `advanced.safety.backup_retention`
+    pub keep: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GcConfig {
+    /// Unreachable objects younger than this survive `rgit gc`: "now",
+    /// "never", a date or a relative date such as "2.weeks.ago"
+    pub prune_expiry: String,
+    /// Loose objects above which `rgit gc --auto` and doctor repack
+    pub auto_loose_objects: usize,
+    /// Pack files above which `rgit gc --auto` and doctor consolidate them
+    pub auto_pack_limit: usize,
+    /// Write a commit-graph during gc to speed up history walks
+    pub commit_graph: bool,
+    /// Write a multi-pack-index during gc to speed up object lookups
+    pub multi_pack_index: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    /// Comma-separated author emails or names that need no review; `*`
+    /// matches anything, as in `*@example.com`. Unset skips the check.
+    pub allowed_authors: Option<String>,
+    /// Blobs larger than this are reported, e.g. "5MB"
+    pub max_blob_size: String,
+    /// Commits walked when no range is given
+    pub max_commits: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    /// Refuse to commit staged changes that look like they hold credentials
+    pub scan_on_commit: bool,
+    /// Comma-separated pathspecs that are never scanned, e.g. "tests/fixtures/*"
+    pub allow_paths: Option<String>,
+    /// Extra regular expressions by name; the first capture group, if any,
+    /// is the part that gets masked in reports
+    pub patterns: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitConfig {
+    /// Staged files larger than this get a warning, e.g. "10MB"
+    pub warn_file_size: String,
+    /// Staged files larger than this block the commit; unset never blocks
+    pub max_file_size: Option<String>,
+    /// Add a Signed-off-by trailer to every commit
+    pub signoff: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PushConfig {
+    /// Refuse pushes that break the repository's .rgit/policy.toml
+    pub enforce_policy: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CloneConfig {
+    /// How forge shorthands like gh:owner/repo are cloned: "https" or "ssh"
+    pub protocol: String,
+    /// Where clones go when no directory is given; `{host}`, `{owner}` and
+    /// `{repo}` are filled in, e.g. "~/src/{host}/{owner}/{repo}"
+    pub destination: Option<String>,
+}
+
+/// URL prefixes replaced by the base this rewrite is configured under, e.g.
+/// `[url."git@github.com:"]` with `instead_of = ["https://github.com/"]`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrlRewrite {
+    /// Prefixes rewritten for fetches and pushes
+    pub instead_of: Vec<String>,
+    /// Prefixes rewritten for pushes only, unless the remote has a push URL
+    pub push_instead_of: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemotePreferences {
+    /// Talk to the remote over "https" or "ssh", whichever its URL uses
+    pub protocol: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CredentialConfig {
+    /// Keep HTTPS credentials typed at a prompt in memory for later
+    /// invocations, like git-credential-cache
+    pub cache: bool,
+    /// How long cached credentials live, e.g. "15m"
+    pub cache_timeout: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,12 +335,25 @@ pub struct IntegrationConfig {
     pub diff_tool: Option<String>,
     /// External merge tool
     pub merge_tool: Option<String>,
+    /// Custom diff/merge tool commands by name
+    #[serde(default)]
+    pub tools: HashMap<String, ToolCommand>,
     /// GPG signing configuration
     pub gpg: GpgConfig,
     /// Hooks configuration
     pub hooks: HooksConfig,
 }
 
+/// Shell commands for a custom tool; `$LOCAL`, `$REMOTE`, `$BASE` and
+/// `$MERGED` are set in the environment like git's `mergetool.<tool>.cmd`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolCommand {
+    /// Command run for `rgit diff --tool`
+    pub diff: Option<String>,
+    /// Command run for `rgit mergetool` and `rgit resolve`
+    pub merge: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpgConfig {
     /// Enable GPG signing
@@ -114,7 +380,8 @@ pub struct UserConfig {
     pub name: Option<String>,
     /// User's email
     pub email: Option<String>,
-    /// Preferred language
+    /// Language for startup, error, `status` and `explain` messages, e.g.
+    /// "es", or "auto" to follow the system locale. Other output is English.
     pub language: String,
     /// Timezone
     pub timezone: Option<String>,
@@ -132,6 +399,9 @@ pub struct AdvancedConfig {
     pub performance: PerformanceConfig,
     /// Safety settings
     pub safety: SafetyConfig,
+    /// Log file settings
+    #[serde(default)]
+    pub logging: LoggingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -157,23 +427,56 @@ pub struct PerformanceConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SafetyConfig {
     /// Require confirmation for destructive operations
     pub confirm_destructive: bool,
     /// Auto-backup before major operations
     pub auto_backup: bool,
+    /// Save the working tree under refs/rgit/snapshots before reset, clean,
+    /// rebase and forced checkouts
+    pub auto_snapshot: bool,
     /// Maximum backup retention days
     pub backup_retention: u32,
     /// Prevent force push without --force-with-lease
     pub safe_force_push: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Also write logs to `logs/rgit.log` in the data directory
+    pub to_file: bool,
+    /// Write logs here instead, even with `to_file` off
+    pub file: Option<PathBuf>,
+    /// Start a new log file once the current one reaches this size
+    pub max_file_size: String,
+    /// Number of rotated log files to keep
+    pub keep_files: usize,
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             ui: UiConfig::default(),
+            theme: ThemeConfig::default(),
             git: GitConfig::default(),
             submodules: SubmoduleConfig::default(),
+            fetch: FetchConfig::default(),
+            checkout: CheckoutConfig::default(),
+            pull: PullConfig::default(),
+            clean: CleanConfig::default(),
+            diff: DiffConfig::default(),
+            backup: BackupConfig::default(),
+            gc: GcConfig::default(),
+            audit: AuditConfig::default(),
+            secrets: SecretsConfig::default(),
+            commit: CommitConfig::default(),
+            push: PushConfig::default(),
+            clone: CloneConfig::default(),
+            url: HashMap::new(),
+            remote: HashMap::new(),
+            credential: CredentialConfig::default(),
             integrations: IntegrationConfig::default(),
             user: UserConfig::default(),
             advanced: AdvancedConfig::default(),
@@ -191,6 +494,9 @@ impl Default for UiConfig {
             interactive: true,
             editor: std::env::var("EDITOR").ok(),
             width: None,
+            pager: None,
+            assume_yes: false,
+            accessible: false,
         }
     }
 }
@@ -205,6 +511,129 @@ impl Default for GitConfig {
             push_tags: false,
             pull_rebase: false,
             auto_prune: true,
+            normalize_dates: false,
+        }
+    }
+}
+
+impl Default for CleanConfig {
+    fn default() -> Self {
+        Self {
+            trash: "repo".to_string(),
+        }
+    }
+}
+
+impl Default for DiffConfig {
+    fn default() -> Self {
+        Self {
+            renames: "true".to_string(),
+            rename_threshold: 50,
+            image_preview: "off".to_string(),
+        }
+    }
+}
+
+impl DiffConfig {
+    /// Whether `value` is a setting `diff.renames` accepts
+    pub fn is_valid_renames(value: &str) -> bool {
+        value.eq_ignore_ascii_case("copies") || parse_bool(value).is_some()
+    }
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            auto_before_destructive: true,
+            keep: 20,
+        }
+    }
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            prune_expiry: "2.weeks.ago".to_string(),
+            auto_loose_objects: 6700,
+            auto_pack_limit: 50,
+            commit_graph: true,
+            multi_pack_index: true,
+        }
+    }
+}
+
+impl GcConfig {
+    /// Whether `spec` is an expiry git understands: `never` or a date
+    pub fn is_valid_expiry(spec: &str) -> bool {
+        spec.trim().eq_ignore_ascii_case("never") || parse_date_spec(spec).is_some()
+    }
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            allowed_authors: None,
+            max_blob_size: "5MB".to_string(),
+            max_commits: 500,
+        }
+    }
+}
+
+impl AuditConfig {
+    /// The allowlist patterns, empty when none are configured
+    pub fn allowed_authors(&self) -> Vec<String> {
+        self.allowed_authors.iter()
+            .flat_map(|list| list.split(','))
+            .map(|pattern| pattern.trim().to_string())
+            .filter(|pattern| !pattern.is_empty())
+            .collect()
+    }
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            scan_on_commit: true,
+            allow_paths: None,
+            patterns: HashMap::new(),
+        }
+    }
+}
+
+impl SecretsConfig {
+    /// The exempt pathspecs, empty when none are configured
+    pub fn allow_paths(&self) -> Vec<String> {
+        self.allow_paths.iter()
+            .flat_map(|list| list.split(','))
+            .map(|spec| spec.trim().to_string())
+            .filter(|spec| !spec.is_empty())
+            .collect()
+    }
+}
+
+impl Default for CommitConfig {
+    fn default() -> Self {
+        Self {
+            warn_file_size: "10MB".to_string(),
+            max_file_size: None,
+            signoff: false,
+        }
+    }
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            enforce_policy: true,
+        }
+    }
+}
+
+impl Default for CloneConfig {
+    fn default() -> Self {
+        Self {
+            protocol: "https".to_string(),
+            destination: None,
         }
     }
 }
@@ -227,6 +656,7 @@ impl Default for IntegrationConfig {
         Self {
             diff_tool: None,
             merge_tool: None,
+            tools: HashMap::new(),
             gpg: GpgConfig::default(),
             hooks: HooksConfig::default(),
         }
@@ -243,6 +673,53 @@ impl Default for GpgConfig {
     }
 }
 
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            auto: None,
+            background: false,
+            offline: false,
+            retries: 3,
+        }
+    }
+}
+
+impl Default for CredentialConfig {
+    fn default() -> Self {
+        Self {
+            cache: false,
+            cache_timeout: "15m".to_string(),
+        }
+    }
+}
+
+impl CredentialConfig {
+    /// How long cached credentials live
+    pub fn timeout(&self) -> Duration {
+        parse_duration_spec(&self.cache_timeout).unwrap_or(Duration::from_secs(900))
+    }
+}
+
+impl FetchConfig {
+    /// Parse an interval such as `30s`, `15m` or `2h`; `off`, `never` and `0` disable auto-fetch
+    pub fn parse_interval(spec: &str) -> Option<Duration> {
+        match spec.trim().to_lowercase().as_str() {
+            "off" | "never" | "false" | "0" => Some(Duration::ZERO),
+            other => parse_duration_spec(other),
+        }
+    }
+
+    /// Interval between automatic fetches, or `None` when disabled
+    pub fn auto_interval(&self) -> Option<Duration> {
+        if self.offline {
+            return None;
+        }
+        self.auto.as_deref()
+            .and_then(Self::parse_interval)
+            .filter(|interval| !interval.is_zero())
+    }
+}
+
 impl Default for HooksConfig {
     fn default() -> Self {
         Self {
@@ -258,7 +735,7 @@ impl Default for UserConfig {
         Self {
             name: None,
             email: None,
-            language: "en".to_string(),
+            language: "auto".to_string(),
             timezone: None,
         }
     }
@@ -272,6 +749,7 @@ impl Default for AdvancedConfig {
             cache: CacheConfig::default(),
             performance: PerformanceConfig::default(),
             safety: SafetyConfig::default(),
+            logging: LoggingConfig::default(),
         }
     }
 }
@@ -302,12 +780,24 @@ impl Default for SafetyConfig {
         Self {
             confirm_destructive: true,
             auto_backup: true,
+            auto_snapshot: true,
             backup_retention: 30,
             safe_force_push: true,
         }
     }
 }
 
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            to_file: true,
+            file: None,
+            max_file_size: "1MB".to_string(),
+            keep_files: 5,
+        }
+    }
+}
+
 impl Config {
     /// Load configuration from default locations
     pub fn load() -> Result<Self> {
@@ -317,28 +807,41 @@ impl Config {
             Self::load_from_file(&config_path)
         } else {
             debug!("No configuration file found, using defaults");
-            let config = Self::default();
+            let mut config = Self::default();
+            config.apply_environment_overrides();
             config.ensure_directories()?;
             Ok(config)
         }
     }
 
+    /// The configuration file as written, without the environment
+    /// overrides; what `rgit config` changes and saves
+    pub fn load_stored() -> Result<Self> {
+        let config_path = Self::get_config_path()?;
+        if config_path.exists() {
+            Self::read_file(&config_path)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
     /// Load configuration from a specific file
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = fs::read_to_string(path.as_ref())
-            .with_context(|| format!("Failed to read config file: {}", path.as_ref().display()))?;
-        
-        let mut config: Config = toml::from_str(&content)
-            .with_context(|| "Failed to parse configuration file")?;
+        let mut config = Self::read_file(path.as_ref())?;
         
         config.apply_environment_overrides();
         config.ensure_directories()?;
-        config.validate()?;
         
         debug!("Loaded configuration from {}", path.as_ref().display());
         Ok(config)
     }
 
+    fn read_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        toml::from_str(&content).with_context(|| "Failed to parse configuration file")
+    }
+
     /// Save configuration to default location
     pub fn save(&self) -> Result<()> {
         let config_path = Self::get_config_path()?;
@@ -405,6 +908,26 @@ impl Config {
             self.ui.editor = Some(editor);
         }
 
+        if let Ok(pager) = std::env::var("RGIT_PAGER") {
+            self.ui.pager = Some(pager);
+        }
+
+        if let Ok(value) = std::env::var("RGIT_NONINTERACTIVE") {
+            if value == "1" || value.to_lowercase() == "true" {
+                self.ui.assume_yes = true;
+            }
+        }
+
+        if let Ok(value) = std::env::var("RGIT_ACCESSIBLE") {
+            if value == "1" || value.to_lowercase() == "true" {
+                self.ui.accessible = true;
+            }
+        }
+
+        if let Ok(language) = std::env::var("RGIT_LANG") {
+            self.user.language = language;
+        }
+
         // Git overrides
         if let Ok(remote) = std::env::var("RGIT_DEFAULT_REMOTE") {
             self.git.default_remote = remote;
@@ -414,6 +937,13 @@ impl Config {
             self.git.default_branch = branch;
         }
 
+        // A one-off way past the push policy, e.g. for an emergency fix
+        if let Ok(value) = std::env::var("RGIT_SKIP_PUSH_POLICY") {
+            if value == "1" || value.to_lowercase() == "true" {
+                self.push.enforce_policy = false;
+            }
+        }
+
         // Advanced overrides
         if let Ok(value) = std::env::var("RGIT_VERBOSE") {
             if value == "1" || value.to_lowercase() == "true" {
@@ -424,6 +954,12 @@ impl Config {
         if let Ok(level) = std::env::var("RGIT_LOG_LEVEL") {
             self.advanced.log_level = level;
         }
+
+        if let Ok(value) = std::env::var("RGIT_OFFLINE") {
+            if value == "1" || value.to_lowercase() == "true" {
+                self.fetch.offline = true;
+            }
+        }
     }
 
     /// Ensure required directories exist
@@ -449,12 +985,20 @@ impl Config {
     /// Validate configuration settings
     fn validate(&self) -> Result<()> {
         // Validate theme
-        if !["auto", "dark", "light"].contains(&self.ui.theme.as_str()) {
+        if !theme::THEMES.contains(&self.ui.theme.as_str()) {
             return Err(RgitError::InvalidConfigValue {
                 key: "ui.theme".to_string(),
                 value: self.ui.theme.clone(),
             }.into());
         }
+        for (element, value) in self.theme.colors() {
+            if let Some(value) = value.filter(|value| theme::parse_color(value).is_none()) {
+                return Err(RgitError::InvalidConfigValue {
+                    key: format!("theme.{}", element.name()),
+                    value: value.to_string(),
+                }.into());
+            }
+        }
 
         // Validate log level
         if !["error", "warn", "info", "debug", "trace"].contains(&self.advanced.log_level.as_str()) {
@@ -472,6 +1016,131 @@ impl Config {
             }.into());
         }
 
+        if let Some(auto) = &self.fetch.auto {
+            if FetchConfig::parse_interval(auto).is_none() {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "fetch.auto".to_string(),
+                    value: auto.clone(),
+                }.into());
+            }
+        }
+
+        if let Some(mode) = &self.pull.mode {
+            if !["merge", "rebase", "ff-only"].contains(&mode.as_str()) {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "pull.mode".to_string(),
+                    value: mode.clone(),
+                }.into());
+            }
+        }
+
+        if !["repo", "system", "off"].contains(&self.clean.trash.as_str()) {
+            return Err(RgitError::InvalidConfigValue {
+                key: "clean.trash".to_string(),
+                value: self.clean.trash.clone(),
+            }.into());
+        }
+
+        if !DiffConfig::is_valid_renames(&self.diff.renames) {
+            return Err(RgitError::InvalidConfigValue {
+                key: "diff.renames".to_string(),
+                value: self.diff.renames.clone(),
+            }.into());
+        }
+
+        if !["off", "auto", "kitty", "sixel"].contains(&self.diff.image_preview.as_str()) {
+            return Err(RgitError::InvalidConfigValue {
+                key: "diff.image_preview".to_string(),
+                value: self.diff.image_preview.clone(),
+            }.into());
+        }
+
+        if self.diff.rename_threshold > 100 {
+            return Err(RgitError::InvalidConfigValue {
+                key: "diff.rename_threshold".to_string(),
+                value: self.diff.rename_threshold.to_string(),
+            }.into());
+        }
+
+        if !GcConfig::is_valid_expiry(&self.gc.prune_expiry) {
+            return Err(RgitError::InvalidConfigValue {
+                key: "gc.prune_expiry".to_string(),
+                value: self.gc.prune_expiry.clone(),
+            }.into());
+        }
+
+        if parse_size(&self.advanced.logging.max_file_size).is_none() {
+            return Err(RgitError::InvalidConfigValue {
+                key: "advanced.logging.max_file_size".to_string(),
+                value: self.advanced.logging.max_file_size.clone(),
+            }.into());
+        }
+
+        if parse_size(&self.audit.max_blob_size).is_none() {
+            return Err(RgitError::InvalidConfigValue {
+                key: "audit.max_blob_size".to_string(),
+                value: self.audit.max_blob_size.clone(),
+            }.into());
+        }
+
+        for (name, pattern) in &self.secrets.patterns {
+            if Regex::new(pattern).is_err() {
+                return Err(RgitError::InvalidConfigValue {
+                    key: format!("secrets.patterns.{}", name),
+                    value: pattern.clone(),
+                }.into());
+            }
+        }
+
+        if parse_size(&self.commit.warn_file_size).is_none() {
+            return Err(RgitError::InvalidConfigValue {
+                key: "commit.warn_file_size".to_string(),
+                value: self.commit.warn_file_size.clone(),
+            }.into());
+        }
+
+        if let Some(max) = &self.commit.max_file_size {
+            if parse_size(max).is_none() {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "commit.max_file_size".to_string(),
+                    value: max.clone(),
+                }.into());
+            }
+        }
+
+        if !["https", "ssh"].contains(&self.clone.protocol.as_str()) {
+            return Err(RgitError::InvalidConfigValue {
+                key: "clone.protocol".to_string(),
+                value: self.clone.protocol.clone(),
+            }.into());
+        }
+
+        for (name, preferences) in &self.remote {
+            if let Some(protocol) = preferences.protocol.as_ref().filter(|p| !["https", "ssh"].contains(&p.as_str())) {
+                return Err(RgitError::InvalidConfigValue {
+                    key: format!("remote.{}.protocol", name),
+                    value: protocol.clone(),
+                }.into());
+            }
+        }
+
+        if parse_duration_spec(&self.credential.cache_timeout).is_none() {
+            return Err(RgitError::InvalidConfigValue {
+                key: "credential.cache_timeout".to_string(),
+                value: self.credential.cache_timeout.clone(),
+            }.into());
+        }
+
+        // Without {repo} every clone would land in the same directory
+        if let Some(destination) = &self.clone.destination {
+            if !destination.contains("{repo}") {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "clone.destination".to_string(),
+                    value: destination.clone(),
+                }.into());
+            }
+        }
+
         if self.submodules.max_jobs == 0 {
             return Err(RgitError::InvalidConfigValue {
                 key: "submodules.max_jobs".to_string(),
@@ -479,6 +1148,16 @@ impl Config {
             }.into());
         }
 
+        // Validate user email
+        if let Some(ref email) = self.user.email {
+            if !is_valid_email(email) {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "user.email".to_string(),
+                    value: email.clone(),
+                }.into());
+            }
+        }
+
         Ok(())
     }
 
@@ -492,6 +1171,22 @@ impl Config {
         if !other.ui.interactive { self.ui.interactive = false; }
         if other.ui.editor.is_some() { self.ui.editor = other.ui.editor.clone(); }
         if other.ui.width.is_some() { self.ui.width = other.ui.width; }
+        if other.ui.pager.is_some() { self.ui.pager = other.ui.pager.clone(); }
+        if other.ui.assume_yes { self.ui.assume_yes = true; }
+        if other.ui.accessible { self.ui.accessible = true; }
+
+        // Theme colors
+        if other.theme.added.is_some() { self.theme.added = other.theme.added.clone(); }
+        if other.theme.removed.is_some() { self.theme.removed = other.theme.removed.clone(); }
+        if other.theme.modified.is_some() { self.theme.modified = other.theme.modified.clone(); }
+        if other.theme.untracked.is_some() { self.theme.untracked = other.theme.untracked.clone(); }
+        if other.theme.branch.is_some() { self.theme.branch = other.theme.branch.clone(); }
+        if other.theme.commit.is_some() { self.theme.commit = other.theme.commit.clone(); }
+        if other.theme.hunk.is_some() { self.theme.hunk = other.theme.hunk.clone(); }
+        if other.theme.success.is_some() { self.theme.success = other.theme.success.clone(); }
+        if other.theme.warning.is_some() { self.theme.warning = other.theme.warning.clone(); }
+        if other.theme.error.is_some() { self.theme.error = other.theme.error.clone(); }
+        if other.theme.hint.is_some() { self.theme.hint = other.theme.hint.clone(); }
 
         // Git settings
         if other.git.default_remote != "origin" { self.git.default_remote = other.git.default_remote.clone(); }
@@ -501,10 +1196,93 @@ impl Config {
         if other.git.push_tags { self.git.push_tags = true; }
         if other.git.pull_rebase { self.git.pull_rebase = true; }
         if !other.git.auto_prune { self.git.auto_prune = false; }
+        if other.git.normalize_dates { self.git.normalize_dates = true; }
+
+        // Fetch settings
+        if other.fetch.auto.is_some() { self.fetch.auto = other.fetch.auto.clone(); }
+        if other.fetch.background { self.fetch.background = true; }
+        if other.fetch.offline { self.fetch.offline = true; }
+        if other.fetch.retries != 3 { self.fetch.retries = other.fetch.retries; }
+
+        // Checkout settings
+        if other.checkout.autostash { self.checkout.autostash = true; }
+
+        // Pull settings
+        if other.pull.mode.is_some() { self.pull.mode = other.pull.mode.clone(); }
+
+        // Clean settings
+        if other.clean.trash != "repo" { self.clean.trash = other.clean.trash.clone(); }
+
+        // Diff settings
+        if other.diff.renames != "true" { self.diff.renames = other.diff.renames.clone(); }
+        if other.diff.rename_threshold != 50 { self.diff.rename_threshold = other.diff.rename_threshold; }
+        if other.diff.image_preview != "off" { self.diff.image_preview = other.diff.image_preview.clone(); }
+
+        // Backup settings
+        if !other.backup.auto_before_destructive { self.backup.auto_before_destructive = false; }
+        if other.backup.keep != 20 { self.backup.keep = other.backup.keep; }
+
+        // Gc settings
+        if other.gc.prune_expiry != "2.weeks.ago" { self.gc.prune_expiry = other.gc.prune_expiry.clone(); }
+        if other.gc.auto_loose_objects != 6700 { self.gc.auto_loose_objects = other.gc.auto_loose_objects; }
+        if other.gc.auto_pack_limit != 50 { self.gc.auto_pack_limit = other.gc.auto_pack_limit; }
+        if !other.gc.commit_graph { self.gc.commit_graph = false; }
+        if !other.gc.multi_pack_index { self.gc.multi_pack_index = false; }
+
+        // Audit settings
+        if other.audit.allowed_authors.is_some() { self.audit.allowed_authors = other.audit.allowed_authors.clone(); }
+        if other.audit.max_blob_size != "5MB" { self.audit.max_blob_size = other.audit.max_blob_size.clone(); }
+        if other.audit.max_commits != 500 { self.audit.max_commits = other.audit.max_commits; }
+
+        // Secrets settings
+        if !other.secrets.scan_on_commit { self.secrets.scan_on_commit = false; }
+        if other.secrets.allow_paths.is_some() { self.secrets.allow_paths = other.secrets.allow_paths.clone(); }
+        for (name, pattern) in &other.secrets.patterns {
+            self.secrets.patterns.insert(name.clone(), pattern.clone());
+        }
+
+        // Commit settings
+        if other.commit.warn_file_size != "10MB" { self.commit.warn_file_size = other.commit.warn_file_size.clone(); }
+        if other.commit.max_file_size.is_some() { self.commit.max_file_size = other.commit.max_file_size.clone(); }
+        if other.commit.signoff { self.commit.signoff = true; }
+
+        // Push settings
+        if !other.push.enforce_policy { self.push.enforce_policy = false; }
+
+        // Clone settings
+        if other.clone.protocol != "https" { self.clone.protocol = other.clone.protocol.clone(); }
+        if other.clone.destination.is_some() { self.clone.destination = other.clone.destination.clone(); }
+
+        // URL rewrites and remote preferences
+        for (base, rewrite) in &other.url {
+            self.url.insert(base.clone(), rewrite.clone());
+        }
+        for (name, preferences) in &other.remote {
+            self.remote.insert(name.clone(), preferences.clone());
+        }
+
+        // Credential settings
+        if other.credential.cache { self.credential.cache = true; }
+        if other.credential.cache_timeout != "15m" { self.credential.cache_timeout = other.credential.cache_timeout.clone(); }
+
+        // Integration settings
+        if other.integrations.diff_tool.is_some() { self.integrations.diff_tool = other.integrations.diff_tool.clone(); }
+        if other.integrations.merge_tool.is_some() { self.integrations.merge_tool = other.integrations.merge_tool.clone(); }
+        for (name, tool) in &other.integrations.tools {
+            self.integrations.tools.insert(name.clone(), tool.clone());
+        }
+
+        // User settings
+        if other.user.language != "auto" { self.user.language = other.user.language.clone(); }
 
         // Advanced settings
         if other.advanced.verbose { self.advanced.verbose = true; }
         if other.advanced.log_level != "info" { self.advanced.log_level = other.advanced.log_level.clone(); }
+        if !other.advanced.safety.auto_snapshot { self.advanced.safety.auto_snapshot = false; }
+        if !other.advanced.logging.to_file { self.advanced.logging.to_file = false; }
+        if other.advanced.logging.file.is_some() { self.advanced.logging.file = other.advanced.logging.file.clone(); }
+        if other.advanced.logging.max_file_size != "1MB" { self.advanced.logging.max_file_size = other.advanced.logging.max_file_size.clone(); }
+        if other.advanced.logging.keep_files != 5 { self.advanced.logging.keep_files = other.advanced.logging.keep_files; }
     }
 
     /// Get user identity from configuration and git config
@@ -522,9 +1300,10 @@ impl Config {
         Ok((name, email))
     }
 
-    /// Check if interactive mode is available
+    /// Check if interactive mode is available. With `ui.assume_yes` rgit
+    /// never asks, so confirmations take the path they take without a terminal.
     pub fn is_interactive(&self) -> bool {
-        self.ui.interactive && atty::is(atty::Stream::Stdin)
+        self.ui.interactive && !self.ui.assume_yes && atty::is(atty::Stream::Stdin)
     }
 
     /// Get terminal width
@@ -551,6 +1330,304 @@ impl Config {
     }
 }
 
+// =============================================================================
+// Key-Based Access
+// =============================================================================
+
+/// Type of value stored under a configuration key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigValueKind {
+    Bool,
+    Integer,
+    String,
+    OptionalString,
+    OptionalInteger,
+}
+
+impl ConfigValueKind {
+    /// Whether the key may be left unset
+    pub fn is_optional(&self) -> bool {
+        matches!(self, ConfigValueKind::OptionalString | ConfigValueKind::OptionalInteger)
+    }
+
+    /// Short hint describing accepted input
+    pub fn hint(&self) -> &'static str {
+        match self {
+            ConfigValueKind::Bool => "true/false",
+            ConfigValueKind::Integer => "number",
+            ConfigValueKind::String => "text",
+            ConfigValueKind::OptionalString => "text, empty to clear",
+            ConfigValueKind::OptionalInteger => "number, empty to clear",
+        }
+    }
+}
+
+/// A user-editable configuration key
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigKey {
+    /// Dotted key name, e.g. `ui.theme`
+    pub name: &'static str,
+    /// Type of the stored value
+    pub kind: ConfigValueKind,
+    /// Human readable description
+    pub description: &'static str,
+}
+
+impl ConfigKey {
+    const fn new(name: &'static str, kind: ConfigValueKind, description: &'static str) -> Self {
+        Self { name, kind, description }
+    }
+
+    /// Section the key belongs to (everything before the last dot)
+    pub fn section(&self) -> &'static str {
+        self.name.rsplit_once('.').map(|(section, _)| section).unwrap_or(self.name)
+    }
+}
+
+/// All keys that can be read and written with `rgit config`
+pub const CONFIG_KEYS: &[ConfigKey] = {
+    use ConfigValueKind::*;
+    &[
+        ConfigKey::new("ui.colors", Bool, "Use colored output"),
+        ConfigKey::new("ui.theme", String, "Color theme (auto, dark, light, solarized, monochrome)"),
+        ConfigKey::new("ui.progress", Bool, "Show progress bars"),
+        ConfigKey::new("ui.icons", Bool, "Use emoji icons"),
+        ConfigKey::new("ui.interactive", Bool, "Enable interactive prompts"),
+        ConfigKey::new("ui.editor", OptionalString, "Editor for commit messages"),
+        ConfigKey::new("ui.width", OptionalInteger, "Terminal width override"),
+        ConfigKey::new("ui.pager", OptionalString, "Pager for long output (off to disable)"),
+        ConfigKey::new("ui.assume_yes", Bool, "Answer confirmations with their default instead of asking"),
+        ConfigKey::new("ui.accessible", Bool, "Plain ASCII output for screen readers and dumb terminals"),
+        ConfigKey::new("theme.added", OptionalString, "Color of added lines and staged files"),
+        ConfigKey::new("theme.removed", OptionalString, "Color of removed lines"),
+        ConfigKey::new("theme.modified", OptionalString, "Color of unstaged changes"),
+        ConfigKey::new("theme.untracked", OptionalString, "Color of untracked files"),
+        ConfigKey::new("theme.branch", OptionalString, "Color of branch names"),
+        ConfigKey::new("theme.commit", OptionalString, "Color of commit ids"),
+        ConfigKey::new("theme.hunk", OptionalString, "Color of diff hunk headers"),
+        ConfigKey::new("theme.success", OptionalString, "Color of success messages"),
+        ConfigKey::new("theme.warning", OptionalString, "Color of warnings"),
+        ConfigKey::new("theme.error", OptionalString, "Color of errors"),
+        ConfigKey::new("theme.hint", OptionalString, "Color of suggested commands"),
+        ConfigKey::new("git.default_remote", String, "Default remote name"),
+        ConfigKey::new("git.default_branch", String, "Default branch for new repositories"),
+        ConfigKey::new("git.auto_stage", Bool, "Auto-stage on commit"),
+        ConfigKey::new("git.sign_commits", Bool, "Sign commits by default"),
+        ConfigKey::new("git.push_tags", Bool, "Push tags with branches"),
+        ConfigKey::new("git.pull_rebase", Bool, "Rebase instead of merge on pull"),
+        ConfigKey::new("git.auto_prune", Bool, "Prune on fetch"),
+        ConfigKey::new("git.normalize_dates", Bool, "Clamp implausible commit dates when rewriting history"),
+        ConfigKey::new("fetch.auto", OptionalString, "Auto-fetch interval before status/branch (e.g. 15m, off)"),
+        ConfigKey::new("fetch.background", Bool, "Also auto-fetch in the background from log, diff and other read commands"),
+        ConfigKey::new("fetch.offline", Bool, "Never fetch implicitly"),
+        ConfigKey::new("fetch.retries", Integer, "Times to retry a clone or fetch after a transient network error"),
+        ConfigKey::new("checkout.autostash", Bool, "Stash and reapply blocking changes when switching without prompts"),
+        ConfigKey::new("pull.mode", OptionalString, "How pull integrates fetched commits (merge, rebase, ff-only)"),
+        ConfigKey::new("clean.trash", String, "Where rgit clean puts removed files (repo, system, off)"),
+        ConfigKey::new("diff.renames", String, "Detect moved files in diff, show and log (true, false, copies)"),
+        ConfigKey::new("diff.rename_threshold", Integer, "Similarity percentage at which files count as renamed"),
+        ConfigKey::new("diff.image_preview", String, "Draw changed images inline (off, auto, kitty, sixel)"),
+        ConfigKey::new("backup.auto_before_destructive", Bool, "Snapshot refs and changes before rebase, reset and clean"),
+        ConfigKey::new("backup.keep", Integer, "Automatic snapshots to keep (0 keeps all)"),
+        ConfigKey::new("gc.prune_expiry", String, "Age at which unreachable objects are pruned (now, never, 2.weeks.ago)"),
+        ConfigKey::new("gc.auto_loose_objects", Integer, "Loose objects that make rgit gc --auto repack"),
+        ConfigKey::new("gc.auto_pack_limit", Integer, "Pack files that make rgit gc --auto consolidate packs"),
+        ConfigKey::new("gc.commit_graph", Bool, "Write a commit-graph during gc"),
+        ConfigKey::new("gc.multi_pack_index", Bool, "Write a multi-pack-index during gc"),
+        ConfigKey::new("audit.allowed_authors", OptionalString, "Comma-separated authors rgit audit accepts (e.g. *@example.com)"),
+        ConfigKey::new("audit.max_blob_size", String, "Blob size above which rgit audit reports a file (e.g. 5MB)"),
+        ConfigKey::new("audit.max_commits", Integer, "Commits rgit audit checks when no range is given"),
+        ConfigKey::new("secrets.scan_on_commit", Bool, "Block commits whose staged changes look like they hold credentials"),
+        ConfigKey::new("secrets.allow_paths", OptionalString, "Comma-separated pathspecs the secret scanner skips"),
+        ConfigKey::new("commit.warn_file_size", String, "Staged file size that triggers a warning on commit (e.g. 10MB)"),
+        ConfigKey::new("commit.max_file_size", OptionalString, "Staged file size that blocks a commit (e.g. 50MB)"),
+        ConfigKey::new("commit.signoff", Bool, "Add a Signed-off-by trailer to every commit"),
+        ConfigKey::new("push.enforce_policy", Bool, "Refuse pushes that break the repository's .rgit/policy.toml"),
+        ConfigKey::new("clone.protocol", String, "Protocol for forge shorthands like gh:owner/repo (https or ssh)"),
+        ConfigKey::new("clone.destination", OptionalString, "Where clones go, e.g. ~/src/{host}/{owner}/{repo}"),
+        ConfigKey::new("credential.cache", Bool, "Keep HTTPS credentials typed at a prompt in memory for later commands"),
+        ConfigKey::new("credential.cache_timeout", String, "How long cached credentials live (e.g. 15m)"),
+        ConfigKey::new("submodules.auto_init", Bool, "Auto-initialize submodules on clone"),
+        ConfigKey::new("submodules.recursive", Bool, "Update submodules recursively"),
+        ConfigKey::new("submodules.health_check", Bool, "Check submodule health before operations"),
+        ConfigKey::new("submodules.auto_stash", Bool, "Auto-stash submodule changes"),
+        ConfigKey::new("submodules.parallel", Bool, "Run submodule operations in parallel"),
+        ConfigKey::new("submodules.max_jobs", Integer, "Maximum parallel submodule jobs"),
+        ConfigKey::new("integrations.diff_tool", OptionalString, "External diff tool (vscode, meld, kdiff3, vimdiff, araxis or a custom tool)"),
+        ConfigKey::new("integrations.merge_tool", OptionalString, "External merge tool (vscode, meld, kdiff3, vimdiff, araxis or a custom tool)"),
+        ConfigKey::new("integrations.gpg.enabled", Bool, "Enable GPG signing"),
+        ConfigKey::new("integrations.gpg.key_id", OptionalString, "GPG key ID"),
+        ConfigKey::new("integrations.gpg.program", OptionalString, "GPG program path"),
+        ConfigKey::new("integrations.hooks.pre_commit", Bool, "Run pre-commit hooks"),
+        ConfigKey::new("integrations.hooks.commit_msg", Bool, "Run commit-msg hooks"),
+        ConfigKey::new("integrations.hooks.pre_push", Bool, "Run pre-push hooks"),
+        ConfigKey::new("user.name", OptionalString, "Your name"),
+        ConfigKey::new("user.email", OptionalString, "Your email address"),
+        ConfigKey::new("user.language", String, "Language for status, explain and error messages (auto follows LANG)"),
+        ConfigKey::new("user.timezone", OptionalString, "Timezone"),
+        ConfigKey::new("advanced.verbose", Bool, "Enable verbose logging"),
+        ConfigKey::new("advanced.log_level", String, "Log level (error, warn, info, debug, trace)"),
+        ConfigKey::new("advanced.cache.enabled", Bool, "Enable caching"),
+        ConfigKey::new("advanced.cache.directory", OptionalString, "Cache directory"),
+        ConfigKey::new("advanced.cache.ttl", Integer, "Cache TTL in seconds"),
+        ConfigKey::new("advanced.cache.max_size", Integer, "Maximum cache size in MB"),
+        ConfigKey::new("advanced.performance.threads", Integer, "Number of worker threads"),
+        ConfigKey::new("advanced.performance.buffer_size", Integer, "I/O buffer size"),
+        ConfigKey::new("advanced.performance.use_mmap", Bool, "Memory-map large files"),
+        ConfigKey::new("advanced.safety.confirm_destructive", Bool, "Confirm destructive operations"),
+        ConfigKey::new("advanced.safety.auto_backup", Bool, "Auto-backup before major operations"),
+        ConfigKey::new("advanced.safety.auto_snapshot", Bool, "Save a safety snapshot before reset, clean, rebase and forced checkouts"),
+        ConfigKey::new("advanced.safety.backup_retention", Integer, "Backup retention in days"),
+        ConfigKey::new("advanced.safety.safe_force_push", Bool, "Require --force-with-lease for force pushes"),
+        ConfigKey::new("advanced.logging.to_file", Bool, "Write logs to the data directory's logs folder"),
+        ConfigKey::new("advanced.logging.file", OptionalString, "Log file path, instead of the data directory's logs folder"),
+        ConfigKey::new("advanced.logging.max_file_size", String, "Rotate the log file at this size (e.g. 1MB)"),
+        ConfigKey::new("advanced.logging.keep_files", Integer, "Number of rotated log files to keep"),
+    ]
+};
+
+impl Config {
+    /// Look up a known configuration key
+    pub fn find_key(name: &str) -> Option<&'static ConfigKey> {
+        CONFIG_KEYS.iter().find(|key| key.name == name)
+    }
+
+    /// Get the current value of a key as a string (`None` when unset)
+    pub fn get_value(&self, name: &str) -> Result<Option<String>> {
+        Self::find_key(name).ok_or_else(|| RgitError::UnknownConfigKey(name.to_string()))?;
+        let tree = toml::Value::try_from(self).context("Failed to serialize configuration")?;
+        Ok(lookup_value(&tree, name).map(format_value))
+    }
+
+    /// Get the built-in default value of a key
+    pub fn default_value(name: &str) -> Result<Option<String>> {
+        Self::default().get_value(name)
+    }
+
+    /// Set a key from its string representation, validating the result
+    pub fn set_value(&mut self, name: &str, raw: &str) -> Result<()> {
+        let key = Self::find_key(name).ok_or_else(|| RgitError::UnknownConfigKey(name.to_string()))?;
+        let value = parse_value(key, raw)?;
+        self.replace_value(name, value)
+    }
+
+    /// Reset a key to its built-in default
+    pub fn unset_value(&mut self, name: &str) -> Result<()> {
+        Self::find_key(name).ok_or_else(|| RgitError::UnknownConfigKey(name.to_string()))?;
+        let defaults = toml::Value::try_from(Self::default()).context("Failed to serialize configuration")?;
+        let value = lookup_value(&defaults, name).cloned();
+        self.replace_value(name, value)
+    }
+
+    /// Put every invalid value back to its default, returning why each one
+    /// was rejected. Loading doesn't fail on them, so a typo in the file
+    /// can still be fixed with `rgit config`.
+    pub fn reset_invalid_values(&mut self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut reset = Vec::new();
+        while let Err(error) = self.validate() {
+            problems.push(error.to_string());
+            let Some(RgitError::InvalidConfigValue { key, .. }) = error.downcast_ref::<RgitError>() else { break };
+            if reset.contains(key) {
+                break;
+            }
+            let Ok(defaults) = toml::Value::try_from(Self::default()) else { break };
+            match self.with_value(key, lookup_value(&defaults, key).cloned()) {
+                Ok(updated) => *self = updated,
+                Err(_) => break,
+            }
+            reset.push(key.clone());
+        }
+        problems
+    }
+
+    /// Replace a single value and rebuild the configuration from it
+    fn replace_value(&mut self, name: &str, value: Option<toml::Value>) -> Result<()> {
+        let updated = self.with_value(name, value)?;
+        updated.validate()?;
+
+        *self = updated;
+        Ok(())
+    }
+
+    /// The configuration with a single value replaced, not yet validated
+    fn with_value(&self, name: &str, value: Option<toml::Value>) -> Result<Config> {
+        let mut tree = toml::Value::try_from(self).context("Failed to serialize configuration")?;
+        let (section, leaf) = name.rsplit_once('.')
+            .ok_or_else(|| RgitError::UnknownConfigKey(name.to_string()))?;
+
+        let mut table = tree.as_table_mut()
+            .ok_or_else(|| RgitError::ConfigurationError("Malformed configuration".to_string()))?;
+        for part in section.split('.') {
+            table = table.get_mut(part)
+                .and_then(|node| node.as_table_mut())
+                .ok_or_else(|| RgitError::UnknownConfigKey(name.to_string()))?;
+        }
+
+        match value {
+            Some(value) => { table.insert(leaf.to_string(), value); }
+            None => { table.remove(leaf); }
+        }
+
+        let updated: Config = tree.try_into().map_err(|_| RgitError::InvalidConfigValue {
+            key: name.to_string(),
+            value: self.get_value(name).ok().flatten().unwrap_or_default(),
+        })?;
+        Ok(updated)
+    }
+}
+
+/// Walk a dotted path through a TOML tree
+fn lookup_value<'v>(tree: &'v toml::Value, name: &str) -> Option<&'v toml::Value> {
+    name.split('.').try_fold(tree, |node, part| node.get(part))
+}
+
+/// Format a TOML value for display (strings without quotes)
+fn format_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Parse user input into a TOML value of the key's type
+fn parse_value(key: &ConfigKey, raw: &str) -> Result<Option<toml::Value>> {
+    let trimmed = raw.trim();
+    let invalid = || RgitError::InvalidConfigValue {
+        key: key.name.to_string(),
+        value: raw.to_string(),
+    };
+
+    if trimmed.is_empty() {
+        return if key.kind.is_optional() { Ok(None) } else { Err(invalid().into()) };
+    }
+
+    let value = match key.kind {
+        ConfigValueKind::Bool => toml::Value::Boolean(parse_bool(trimmed).ok_or_else(invalid)?),
+        ConfigValueKind::Integer | ConfigValueKind::OptionalInteger => {
+            let number: i64 = trimmed.parse().map_err(|_| invalid())?;
+            if number < 0 {
+                return Err(invalid().into());
+            }
+            toml::Value::Integer(number)
+        }
+        ConfigValueKind::String | ConfigValueKind::OptionalString => {
+            toml::Value::String(trimmed.to_string())
+        }
+    };
+
+    Ok(Some(value))
+}
+
+/// Parse a boolean in the forms git accepts
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
 /// Configuration builder for easy configuration creation
 pub struct ConfigBuilder {
     config: Config,
@@ -610,6 +1687,11 @@ mod tests {
         assert!(config.ui.colors);
         assert_eq!(config.git.default_remote, "origin");
         assert_eq!(config.git.default_branch, "main");
+        assert!(config.advanced.safety.auto_snapshot);
+        assert!(!config.ui.assume_yes);
+        assert!(!config.ui.accessible);
+        assert!(config.advanced.logging.to_file);
+        assert_eq!(config.advanced.logging.keep_files, 5);
     }
 
     #[test]
@@ -669,20 +1751,127 @@ mod tests {
         assert!(config.validate().is_err());
     }
 
+    #[test]
+    fn test_reset_invalid_values() {
+        let mut config = Config::default();
+        assert!(config.reset_invalid_values().is_empty());
+
+        config.ui.theme = "invalid".to_string();
+        config.remote.entry("origin".to_string()).or_default().protocol = Some("ftp".to_string());
+        let problems = config.reset_invalid_values();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("ui.theme"));
+        assert!(problems[1].contains("remote.origin.protocol"));
+        assert_eq!(config.ui.theme, Config::default().ui.theme);
+        assert_eq!(config.remote["origin"].protocol, None);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_get_and_set_value() {
+        let mut config = Config::default();
+        assert_eq!(config.get_value("ui.theme").unwrap(), Some("auto".to_string()));
+
+        config.set_value("ui.theme", "dark").unwrap();
+        config.set_value("git.pull_rebase", "yes").unwrap();
+        config.set_value("submodules.max_jobs", "4").unwrap();
+        config.set_value("user.email", "dev@example.com").unwrap();
+        config.set_value("theme.branch", "#268bd2").unwrap();
+
+        assert_eq!(config.ui.theme, "dark");
+        assert_eq!(config.theme.branch.as_deref(), Some("#268bd2"));
+        assert!(config.git.pull_rebase);
+        assert_eq!(config.submodules.max_jobs, 4);
+        assert_eq!(config.user.email.as_deref(), Some("dev@example.com"));
+
+        // Empty input clears optional keys
+        config.set_value("user.email", "").unwrap();
+        assert!(config.user.email.is_none());
+        assert_eq!(config.get_value("user.email").unwrap(), None);
+    }
+
+    #[test]
+    fn test_set_value_validation() {
+        let mut config = Config::default();
+
+        assert!(config.set_value("ui.theme", "neon").is_err());
+        assert!(config.set_value("theme.added", "chartreuse").is_err());
+        assert!(config.set_value("ui.colors", "maybe").is_err());
+        assert!(config.set_value("submodules.max_jobs", "-1").is_err());
+        assert!(config.set_value("submodules.max_jobs", "0").is_err());
+        assert!(config.set_value("user.email", "not-an-email").is_err());
+        assert!(config.set_value("no.such.key", "1").is_err());
+        assert!(config.set_value("fetch.auto", "soon").is_err());
+        assert!(config.set_value("diff.renames", "sometimes").is_err());
+        assert!(config.set_value("diff.rename_threshold", "101").is_err());
+        assert!(config.set_value("commit.max_file_size", "huge").is_err());
+        assert!(config.set_value("clone.protocol", "ftp").is_err());
+        assert!(config.set_value("clone.destination", "~/src").is_err());
+        config.set_value("diff.renames", "copies").unwrap();
+
+        // Failed updates leave the configuration untouched
+        assert_eq!(config.ui.theme, "auto");
+        assert!(config.user.email.is_none());
+    }
+
+    #[test]
+    fn test_fetch_auto_interval() {
+        let mut config = Config::default();
+        assert!(config.fetch.auto_interval().is_none());
+
+        config.set_value("fetch.auto", "15m").unwrap();
+        assert_eq!(config.fetch.auto_interval(), Some(Duration::from_secs(900)));
+
+        config.fetch.offline = true;
+        assert!(config.fetch.auto_interval().is_none());
+
+        config.fetch.offline = false;
+        assert!(config.set_value("fetch.auto", "99999999999999999d").is_err());
+        config.set_value("fetch.auto", "off").unwrap();
+        assert!(config.fetch.auto_interval().is_none());
+    }
+
+    #[test]
+    fn test_unset_value() {
+        let mut config = Config::default();
+        config.set_value("git.default_remote", "upstream").unwrap();
+        config.unset_value("git.default_remote").unwrap();
+        assert_eq!(config.git.default_remote, "origin");
+    }
+
+    #[test]
+    fn test_config_keys_resolve() {
+        let config = Config::default();
+        for key in CONFIG_KEYS {
+            assert!(config.get_value(key.name).is_ok(), "key {} should resolve", key.name);
+        }
+        assert_eq!(Config::find_key("integrations.gpg.enabled").unwrap().section(), "integrations.gpg");
+    }
+
     #[test]
     fn test_environment_overrides() {
         std::env::set_var("RGIT_NO_COLOR", "1");
         std::env::set_var("RGIT_THEME", "dark");
+        std::env::set_var("RGIT_NONINTERACTIVE", "1");
+        std::env::set_var("RGIT_LANG", "es");
+        std::env::set_var("RGIT_ACCESSIBLE", "1");
         
         let mut config = Config::default();
         config.apply_environment_overrides();
         
         assert!(!config.ui.colors);
         assert_eq!(config.ui.theme, "dark");
+        assert!(config.ui.assume_yes);
+        assert!(!config.is_interactive());
+        assert_eq!(config.user.language, "es");
+        assert!(config.ui.accessible);
         
         // Cleanup
         std::env::remove_var("RGIT_NO_COLOR");
         std::env::remove_var("RGIT_THEME");
+        std::env::remove_var("RGIT_NONINTERACTIVE");
+        std::env::remove_var("RGIT_LANG");
+        std::env::remove_var("RGIT_ACCESSIBLE");
     }
 
     #[test]
@@ -0,0 +1,476 @@
+//! Conflict markers and the index stages behind them.
+//!
+//! A conflicted file is parsed into [`ConflictedText`], a run of clean text and
+//! [`ConflictHunk`]s. Each hunk can be resolved on its own and the file
+//! rendered back, keeping markers for hunks that are still unresolved. The
+//! index side ([`ConflictSides`]) gives whole-file choices and staging.
+
+use anyhow::Result;
+use git2::build::CheckoutBuilder;
+use git2::{Oid, Repository};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::RgitError;
+use crate::interactive::ConflictType;
+use crate::timings::{self, Phase};
+use crate::tools::{Tool, ToolFiles, ToolKind};
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+// =============================================================================
+// Conflict markers
+// =============================================================================
+
+/// One `<<<<<<<` ... `>>>>>>>` block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictHunk {
+    pub ours_label: String,
+    pub ours: String,
+    /// Only present in diff3-style markers
+    pub base: Option<String>,
+    pub base_label: String,
+    pub theirs: String,
+    pub theirs_label: String,
+}
+
+impl ConflictHunk {
+    /// The hunk as conflict markers again
+    pub fn to_markers(&self) -> String {
+        let mut text = marker_line(OURS_MARKER, &self.ours_label);
+        text.push_str(&self.ours);
+        if let Some(base) = &self.base {
+            text.push_str(&marker_line(BASE_MARKER, &self.base_label));
+            text.push_str(base);
+        }
+        text.push_str(&format!("{}\n", SEPARATOR));
+        text.push_str(&self.theirs);
+        text.push_str(&marker_line(THEIRS_MARKER, &self.theirs_label));
+        text
+    }
+}
+
+fn marker_line(marker: &str, label: &str) -> String {
+    if label.is_empty() {
+        format!("{}\n", marker)
+    } else {
+        format!("{} {}\n", marker, label)
+    }
+}
+
+/// How a single hunk was resolved
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HunkResolution {
+    Ours,
+    Theirs,
+    Base,
+    OursThenTheirs,
+    TheirsThenOurs,
+    /// Replaced by hand
+    Edited(String),
+}
+
+impl HunkResolution {
+    pub fn apply(&self, hunk: &ConflictHunk) -> String {
+        match self {
+            HunkResolution::Ours => hunk.ours.clone(),
+            HunkResolution::Theirs => hunk.theirs.clone(),
+            HunkResolution::Base => hunk.base.clone().unwrap_or_default(),
+            HunkResolution::OursThenTheirs => format!("{}{}", hunk.ours, hunk.theirs),
+            HunkResolution::TheirsThenOurs => format!("{}{}", hunk.theirs, hunk.ours),
+            HunkResolution::Edited(text) => text.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Conflict(ConflictHunk),
+}
+
+/// A file's contents split around its conflict markers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictedText {
+    pub segments: Vec<Segment>,
+}
+
+/// Where the parser is inside a marker block
+enum Section {
+    Ours,
+    Base,
+    Theirs,
+}
+
+impl ConflictedText {
+    /// Parse conflict markers. An unterminated block is kept as plain text.
+    pub fn parse(text: &str) -> Self {
+        let mut segments = Vec::new();
+        let mut clean = String::new();
+        let mut open: Option<(Section, ConflictHunk, String)> = None;
+
+        for line in text.split_inclusive('\n') {
+            let bare = line.trim_end_matches(['\n', '\r']);
+            match open.take() {
+                None => match marker_label(bare, OURS_MARKER) {
+                    Some(label) => {
+                        let hunk = ConflictHunk {
+                            ours_label: label,
+                            ours: String::new(),
+                            base: None,
+                            base_label: String::new(),
+                            theirs: String::new(),
+                            theirs_label: String::new(),
+                        };
+                        open = Some((Section::Ours, hunk, line.to_string()));
+                    }
+                    None => clean.push_str(line),
+                },
+                Some((section, mut hunk, mut raw)) => {
+                    raw.push_str(line);
+                    let section = match section {
+                        Section::Ours | Section::Base if bare == SEPARATOR => Section::Theirs,
+                        Section::Ours if marker_label(bare, BASE_MARKER).is_some() => {
+                            hunk.base_label = marker_label(bare, BASE_MARKER).unwrap_or_default();
+                            hunk.base = Some(String::new());
+                            Section::Base
+                        }
+                        Section::Ours => {
+                            hunk.ours.push_str(line);
+                            Section::Ours
+                        }
+                        Section::Base => {
+                            hunk.base.get_or_insert_with(String::new).push_str(line);
+                            Section::Base
+                        }
+                        Section::Theirs => match marker_label(bare, THEIRS_MARKER) {
+                            Some(label) => {
+                                hunk.theirs_label = label;
+                                if !clean.is_empty() {
+                                    segments.push(Segment::Text(std::mem::take(&mut clean)));
+                                }
+                                segments.push(Segment::Conflict(hunk));
+                                continue;
+                            }
+                            None => {
+                                hunk.theirs.push_str(line);
+                                Section::Theirs
+                            }
+                        },
+                    };
+                    open = Some((section, hunk, raw));
+                }
+            }
+        }
+
+        if let Some((_, _, raw)) = open {
+            clean.push_str(&raw);
+        }
+        if !clean.is_empty() {
+            segments.push(Segment::Text(clean));
+        }
+        Self { segments }
+    }
+
+    pub fn hunks(&self) -> impl Iterator<Item = &ConflictHunk> {
+        self.segments.iter().filter_map(|s| match s {
+            Segment::Conflict(hunk) => Some(hunk),
+            Segment::Text(_) => None,
+        })
+    }
+
+    pub fn hunk_count(&self) -> usize {
+        self.hunks().count()
+    }
+
+    /// Render the file with `resolutions[i]` applied to the i-th hunk;
+    /// hunks without a resolution keep their markers
+    pub fn render(&self, resolutions: &[Option<HunkResolution>]) -> String {
+        let mut text = String::new();
+        let mut hunk_index = 0;
+        for segment in &self.segments {
+            match segment {
+                Segment::Text(clean) => text.push_str(clean),
+                Segment::Conflict(hunk) => {
+                    match resolutions.get(hunk_index).and_then(|r| r.as_ref()) {
+                        Some(resolution) => text.push_str(&resolution.apply(hunk)),
+                        None => text.push_str(&hunk.to_markers()),
+                    }
+                    hunk_index += 1;
+                }
+            }
+        }
+        text
+    }
+
+    /// Copy base sections from a diff3-style rendering of the same conflict.
+    /// Hunks are matched by position and only filled when both sides agree.
+    pub fn fill_bases(&mut self, diff3: &ConflictedText) {
+        let mut bases = diff3.hunks();
+        for segment in &mut self.segments {
+            if let Segment::Conflict(hunk) = segment {
+                let Some(other) = bases.next() else { return };
+                if hunk.base.is_none() && hunk.ours == other.ours && hunk.theirs == other.theirs {
+                    hunk.base = other.base.clone();
+                    hunk.base_label = other.base_label.clone();
+                }
+            }
+        }
+    }
+}
+
+fn marker_label(line: &str, marker: &str) -> Option<String> {
+    let rest = line.strip_prefix(marker)?;
+    if rest.is_empty() {
+        return Some(String::new());
+    }
+    rest.strip_prefix(' ').map(|label| label.to_string())
+}
+
+/// Whether text still contains a complete conflict block
+pub fn has_conflict_markers(text: &str) -> bool {
+    ConflictedText::parse(text).hunk_count() > 0
+}
+
+// =============================================================================
+// Index stages
+// =============================================================================
+
+/// Which version of a conflicted file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Base,
+    Ours,
+    Theirs,
+}
+
+/// The blobs staged for one conflicted path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConflictSides {
+    pub path: String,
+    pub ancestor: Option<Oid>,
+    pub ours: Option<Oid>,
+    pub theirs: Option<Oid>,
+}
+
+impl ConflictSides {
+    pub fn conflict_type(&self) -> ConflictType {
+        match (&self.ancestor, &self.ours, &self.theirs) {
+            (None, Some(_), Some(_)) => ConflictType::AddAdd,
+            (_, None, Some(_)) => ConflictType::DeleteModify,
+            (_, Some(_), None) => ConflictType::ModifyDelete,
+            _ => ConflictType::Content,
+        }
+    }
+
+    fn blob_id(&self, side: Side) -> Option<Oid> {
+        match side {
+            Side::Base => self.ancestor,
+            Side::Ours => self.ours,
+            Side::Theirs => self.theirs,
+        }
+    }
+
+    /// Contents of one side, None when that side deleted the file
+    pub fn contents(&self, repo: &Repository, side: Side) -> Result<Option<Vec<u8>>> {
+        match self.blob_id(side) {
+            Some(id) => Ok(Some(repo.find_blob(id)?.content().to_vec())),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether any side is binary, which rules out hunk-level resolution
+    pub fn is_binary(&self, repo: &Repository) -> Result<bool> {
+        for id in [self.ancestor, self.ours, self.theirs].into_iter().flatten() {
+            if repo.find_blob(id)?.is_binary() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+/// Every conflicted path in the index
+pub fn conflict_sides(repo: &Repository) -> Result<Vec<ConflictSides>> {
+    let mut sides = Vec::new();
+    for conflict in repo.index()?.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict.our.as_ref().or(conflict.their.as_ref()).or(conflict.ancestor.as_ref());
+        let Some(entry) = entry else { continue };
+        sides.push(ConflictSides {
+            path: String::from_utf8_lossy(&entry.path).to_string(),
+            ancestor: conflict.ancestor.as_ref().map(|e| e.id),
+            ours: conflict.our.as_ref().map(|e| e.id),
+            theirs: conflict.their.as_ref().map(|e| e.id),
+        });
+    }
+    Ok(sides)
+}
+
+/// The conflicted file as libgit2 would write it with diff3 markers,
+/// rendered outside the working tree so local edits are untouched
+pub fn diff3_text(repo: &Repository, path: &str) -> Result<Option<String>> {
+    let scratch = tempfile::tempdir()?;
+    let mut index = repo.index()?;
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force()
+        .allow_conflicts(true)
+        .conflict_style_diff3(true)
+        .target_dir(scratch.path())
+        .path(path);
+    repo.checkout_index(Some(&mut index), Some(&mut checkout))?;
+
+    match fs::read(scratch.path().join(path)) {
+        Ok(bytes) => Ok(String::from_utf8(bytes).ok()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Absolute path of a repository-relative path
+fn workdir_path(repo: &Repository, path: &str) -> Result<PathBuf> {
+    let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+    Ok(workdir.join(path))
+}
+
+/// Write the resolved contents of `path` and stage them
+pub fn stage_resolved(repo: &Repository, path: &str, contents: &[u8]) -> Result<()> {
+    let full = workdir_path(repo, path)?;
+    if let Some(parent) = full.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&full, contents)?;
+    mark_resolved(repo, path)
+}
+
+/// Stage `path` as it is in the working tree, or its removal if it is gone
+pub fn mark_resolved(repo: &Repository, path: &str) -> Result<()> {
+    let mut index = repo.index()?;
+    if workdir_path(repo, path)?.exists() {
+        index.add_path(Path::new(path))?;
+    } else {
+        index.remove_path(Path::new(path))?;
+    }
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
+/// Resolve a whole file to one side, deleting it if that side did
+pub fn take_side(repo: &Repository, sides: &ConflictSides, side: Side) -> Result<()> {
+    match sides.contents(repo, side)? {
+        Some(contents) => stage_resolved(repo, &sides.path, &contents),
+        None => {
+            let full = workdir_path(repo, &sides.path)?;
+            if full.exists() {
+                fs::remove_file(full)?;
+            }
+            mark_resolved(repo, &sides.path)
+        }
+    }
+}
+
+// =============================================================================
+// Merge tool
+// =============================================================================
+
+/// Run a merge tool on one conflicted file.
+///
+/// The three sides are written next to the file as `LOCAL`, `BASE` and
+/// `REMOTE` and removed once the tool exits.
+pub fn run_mergetool(repo: &Repository, sides: &ConflictSides, tool: &Tool) -> Result<()> {
+    let workdir = repo.workdir().ok_or(RgitError::NotInRepository)?;
+    let merged = workdir.join(&sides.path);
+
+    let mut stage_files = Vec::new();
+    for (side, label) in [(Side::Ours, "LOCAL"), (Side::Base, "BASE"), (Side::Theirs, "REMOTE")] {
+        let file = stage_file_path(&merged, label);
+        fs::write(&file, sides.contents(repo, side)?.unwrap_or_default())?;
+        stage_files.push(file);
+    }
+
+    let files = ToolFiles {
+        local: &stage_files[0],
+        remote: &stage_files[2],
+        base: Some(&stage_files[1]),
+        merged: &merged,
+    };
+    let result = tool.launch(ToolKind::Merge, &files, workdir);
+    for file in &stage_files {
+        let _ = fs::remove_file(file);
+    }
+    result
+}
+
+/// `dir/name_LABEL.ext` for `dir/name.ext`
+fn stage_file_path(merged: &Path, label: &str) -> PathBuf {
+    let stem = merged.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let name = match merged.extension() {
+        Some(ext) => format!("{}_{}.{}", stem, label, ext.to_string_lossy()),
+        None => format!("{}_{}", stem, label),
+    };
+    merged.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    const TWO_HUNKS: &str = "top\n\
+<<<<<<< HEAD\nours 1\n=======\ntheirs 1\n>>>>>>> feature\n\
+middle\n\
+<<<<<<< HEAD\nours 2\n||||||| base\nbase 2\n=======\ntheirs 2\n>>>>>>> feature\n\
+bottom\n";
+
+    #[test]
+    fn test_parse_and_render_markers() {
+        let parsed = ConflictedText::parse(TWO_HUNKS);
+        assert_eq!(parsed.hunk_count(), 2);
+        let hunks: Vec<_> = parsed.hunks().collect();
+        assert_eq!(hunks[0].ours, "ours 1\n");
+        assert_eq!(hunks[0].base, None);
+        assert_eq!(hunks[1].base.as_deref(), Some("base 2\n"));
+        assert_eq!(hunks[1].theirs_label, "feature");
+
+        assert_eq!(parsed.render(&[]), TWO_HUNKS);
+        assert_eq!(
+            parsed.render(&[Some(HunkResolution::Theirs), Some(HunkResolution::Base)]),
+            "top\ntheirs 1\nmiddle\nbase 2\nbottom\n"
+        );
+        let partial = parsed.render(&[Some(HunkResolution::OursThenTheirs), None]);
+        assert!(partial.starts_with("top\nours 1\ntheirs 1\nmiddle\n<<<<<<< HEAD\n"));
+        assert!(has_conflict_markers(&partial));
+    }
+
+    #[test]
+    fn test_unterminated_markers_are_text() {
+        let text = "a\n<<<<<<< HEAD\nb\n=======\nc\n";
+        let parsed = ConflictedText::parse(text);
+        assert_eq!(parsed.hunk_count(), 0);
+        assert_eq!(parsed.render(&[]), text);
+    }
+
+    #[test]
+    fn test_sides_and_diff3_bases() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+        repo.conflict("file.txt");
+
+        let sides = conflict_sides(repo.repo()).unwrap();
+        assert_eq!(sides.len(), 1);
+        assert!(matches!(sides[0].conflict_type(), ConflictType::Content));
+
+        let on_disk = fs::read_to_string(repo.path().join("file.txt")).unwrap();
+        let mut parsed = ConflictedText::parse(&on_disk);
+        let diff3 = diff3_text(repo.repo(), "file.txt").unwrap().unwrap();
+        parsed.fill_bases(&ConflictedText::parse(&diff3));
+        assert_eq!(parsed.hunks().next().unwrap().base.as_deref(), Some("base\n"));
+        assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), on_disk);
+
+        take_side(repo.repo(), &sides[0], Side::Theirs).unwrap();
+        assert!(!repo.repo().index().unwrap().has_conflicts());
+        assert_eq!(fs::read_to_string(repo.path().join("file.txt")).unwrap(), "theirs\n");
+    }
+}
@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use tracing::{debug, info, warn};
 use colored::*;
 use crate::error::RgitError;
+use crate::timings::{self, Phase};
+use crate::validation::validate_message;
 // Remove unused imports
 // use crate::utils::{format_time, calculate_file_changes, get_branch_status};
 
@@ -34,8 +36,11 @@ impl std::fmt::Debug for RgitCore {
 impl RgitCore {
     /// Create a new RgitCore instance by discovering the repository
     pub fn new(verbose: bool) -> Result<Self> {
-        let repo = Repository::discover(".")
-            .context("Not in a git repository. Use 'rgit init' to create one.")?;
+        let repo = timings::time(Phase::RepoOpen, || Repository::discover("."))
+            .map_err(|e| match e.code() {
+                git2::ErrorCode::NotFound => RgitError::NotInRepository.into(),
+                _ => anyhow::Error::from(e).context("Cannot open the git repository"),
+            })?;
         
         let repo_path = repo.workdir()
             .ok_or_else(|| RgitError::NotInRepository)?
@@ -56,7 +61,7 @@ impl RgitCore {
 
     /// Create RgitCore from an existing repository path
     pub fn from_path<P: AsRef<Path>>(path: P, verbose: bool) -> Result<Self> {
-        let repo = Repository::open(path.as_ref())
+        let repo = timings::time(Phase::RepoOpen, || Repository::open(path.as_ref()))
             .context("Failed to open repository")?;
         
         let repo_path = repo.workdir()
@@ -77,31 +82,31 @@ impl RgitCore {
     /// Log a message if verbose mode is enabled
     pub fn log(&self, message: &str) {
         if self.verbose {
-            println!("{} {}", "🔍".blue(), message.dimmed());
+            ui_println!("{} {}", "🔍".blue(), message.dimmed());
         }
         debug!("{}", message);
     }
 
     /// Print success message
     pub fn success(&self, message: &str) {
-        println!("{} {}", "✅".green(), message);
+        ui_println!("{} {}", "✅".green(), message);
         info!("Success: {}", message);
     }
 
     /// Print warning message
     pub fn warning(&self, message: &str) {
-        println!("{} {}", "⚠️".yellow(), message.yellow());
+        ui_println!("{} {}", "⚠️".yellow(), message.yellow());
         warn!("{}", message);
     }
 
     /// Print error message
     pub fn error(&self, message: &str) {
-        println!("{} {}", "❌".red(), message.red());
+        ui_println!("{} {}", "❌".red(), message.red());
     }
 
     /// Print info message with icon
     pub fn info(&self, message: &str) {
-        println!("{} {}", "ℹ️".blue(), message);
+        ui_println!("{} {}", "ℹ️".blue(), message);
     }
 
     // =========================================================================
@@ -110,6 +115,9 @@ impl RgitCore {
 
     /// Get the current branch name
     pub fn current_branch(&self) -> Result<String> {
+        if let Some(name) = self.unborn_branch() {
+            return Ok(name);
+        }
         let head = self.repo.head()
             .context("Failed to get HEAD reference")?;
         
@@ -120,13 +128,24 @@ impl RgitCore {
         }
     }
 
+    /// The branch HEAD names while it has no commits yet, as in a new repository
+    fn unborn_branch(&self) -> Option<String> {
+        match self.repo.head() {
+            Err(e) if e.code() == ErrorCode::UnbornBranch => {
+                let head = self.repo.find_reference("HEAD").ok()?;
+                head.symbolic_target()?.strip_prefix("refs/heads/").map(str::to_string)
+            }
+            _ => None,
+        }
+    }
+
     /// Get repository status with enhanced information
     pub fn status(&self) -> Result<RepositoryStatus> {
         let mut opts = StatusOptions::new();
         opts.include_untracked(true);
         opts.include_ignored(false);
         
-        let statuses = self.repo.statuses(Some(&mut opts))?;
+        let statuses = timings::time(Phase::StatusScan, || self.repo.statuses(Some(&mut opts)))?;
         let mut status = RepositoryStatus::default();
 
         // Process each file status
@@ -163,6 +182,9 @@ impl RgitCore {
 
     /// Get detailed branch information including upstream tracking
     pub fn get_branch_info(&self) -> Result<BranchInfo> {
+        if let Some(name) = self.unborn_branch() {
+            return Ok(BranchInfo { name, ..Default::default() });
+        }
         let head = self.repo.head()?;
         let branch_name = if head.is_branch() {
             head.shorthand().unwrap_or("HEAD").to_string()
@@ -260,7 +282,7 @@ impl RgitCore {
             }
         }
         
-        index.write()?;
+        timings::time(Phase::IndexWrite, || index.write())?;
         Ok(())
     }
 
@@ -270,7 +292,7 @@ impl RgitCore {
         self.log("Adding all changes...");
         
         index.add_all(["*"].iter(), IndexAddOption::DEFAULT, None)?;
-        index.write()?;
+        timings::time(Phase::IndexWrite, || index.write())?;
         
         Ok(())
     }
@@ -281,7 +303,7 @@ impl RgitCore {
         self.log("Updating tracked files...");
         
         index.update_all(["*"].iter(), None)?;
-        index.write()?;
+        timings::time(Phase::IndexWrite, || index.write())?;
         
         Ok(())
     }
@@ -292,12 +314,18 @@ impl RgitCore {
 
     /// Create a commit with the given message
     pub fn commit(&self, message: &str, amend: bool) -> Result<Oid> {
-        if message.trim().is_empty() {
-            return Err(RgitError::EmptyCommitMessage.into());
-        }
+        validate_message(message)?;
 
         let signature = self.get_signature()?;
         let mut index = self.repo.index()?;
+        if index.has_conflicts() {
+            let conflicted = index.conflicts()?
+                .filter_map(|c| c.ok())
+                .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+                .map(|e| String::from_utf8_lossy(&e.path).to_string())
+                .collect();
+            return Err(RgitError::MergeConflict(conflicted).into());
+        }
         let tree_id = index.write_tree()?;
         let tree = self.repo.find_tree(tree_id)?;
 
@@ -323,27 +351,33 @@ impl RgitCore {
                 None
             };
 
-            let parents = if let Some(ref commit) = parent_commit {
-                vec![commit]
-            } else {
-                vec![]
+            // A merge in progress records the merged commit as a second parent
+            let merge_head = match self.repo.refname_to_id("MERGE_HEAD") {
+                Ok(oid) => Some(self.repo.find_commit(oid)?),
+                Err(_) => None,
             };
 
-            self.repo.commit(
+            let parents: Vec<&Commit> = parent_commit.iter().chain(merge_head.iter()).collect();
+
+            let commit_id = self.repo.commit(
                 Some("HEAD"),
                 &signature,
                 &signature,
                 message,
                 &tree,
                 &parents,
-            )?
+            )?;
+            if merge_head.is_some() {
+                self.repo.cleanup_state()?;
+            }
+            commit_id
         };
 
         Ok(commit_id)
     }
 
     /// Get or create a signature for commits
-    pub fn get_signature(&self) -> Result<Signature> {
+    pub fn get_signature(&self) -> Result<Signature<'_>> {
         // Try to get from cache first
         if let (Some(name), Some(email)) = (
             self.config_cache.get("user.name"),
@@ -425,13 +459,13 @@ impl RgitCore {
 
     /// Check if the repository is in a clean state
     pub fn is_clean(&self) -> Result<bool> {
-        let statuses = self.repo.statuses(None)?;
+        let statuses = timings::time(Phase::StatusScan, || self.repo.statuses(None))?;
         Ok(statuses.is_empty())
     }
 
     /// Check if there are staged changes
     pub fn has_staged_changes(&self) -> Result<bool> {
-        let statuses = self.repo.statuses(None)?;
+        let statuses = timings::time(Phase::StatusScan, || self.repo.statuses(None))?;
         
         for entry in statuses.iter() {
             let status = entry.status();
@@ -454,6 +488,244 @@ impl RgitCore {
     pub fn git_dir(&self) -> &Path {
         self.repo.path()
     }
+
+    /// Resolve any revspec, including ranges
+    pub fn resolve_revision(&self, spec: &str) -> Result<Revision<'_>> {
+        resolve_revision(&self.repo, spec)
+    }
+}
+
+// =============================================================================
+// Revision Resolution
+// =============================================================================
+
+/// A parsed revspec
+pub enum Revision<'r> {
+    /// One object, e.g. `HEAD~3`, `:/fix typo` or `HEAD:src/main.rs`
+    Single(Object<'r>),
+    /// `from..to`, or `from...to` when symmetric
+    Range {
+        from: Commit<'r>,
+        to: Commit<'r>,
+        symmetric: bool,
+    },
+    /// `commit^!`: the commit without any of its ancestors
+    Only(Commit<'r>),
+}
+
+impl<'r> Revision<'r> {
+    /// Add the commits selected by this revision to a revwalk
+    pub fn push_to(&self, repo: &Repository, revwalk: &mut Revwalk) -> Result<()> {
+        match self {
+            Revision::Single(object) => revwalk.push(object.peel_to_commit()?.id())?,
+            Revision::Range { from, to, symmetric: false } => {
+                revwalk.push(to.id())?;
+                revwalk.hide(from.id())?;
+            }
+            Revision::Range { from, to, symmetric: true } => {
+                revwalk.push(from.id())?;
+                revwalk.push(to.id())?;
+                if let Ok(base) = repo.merge_base(from.id(), to.id()) {
+                    revwalk.hide(base)?;
+                }
+            }
+            Revision::Only(commit) => {
+                revwalk.push(commit.id())?;
+                for parent in commit.parent_ids() {
+                    revwalk.hide(parent)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Resolve a revspec using git's full syntax.
+///
+/// Everything libgit2 understands is accepted (`HEAD~3`, `main..feature`,
+/// `a...b`, `@{upstream}`, `:/message`, `HEAD@{2.days.ago}`, `v1.0^{tree}`),
+/// plus `commit^!`. Failures become friendly errors instead of raw git2 messages.
+pub fn resolve_revision<'r>(repo: &'r Repository, spec: &str) -> Result<Revision<'r>> {
+    let spec = spec.trim();
+    if spec.is_empty() {
+        return Err(RgitError::InvalidArgument("Empty revision".to_string()).into());
+    }
+
+    if let Some(base) = spec.strip_suffix("^!") {
+        return Ok(Revision::Only(resolve_commit(repo, base)?));
+    }
+
+    let parsed = repo.revparse(spec).map_err(|e| revision_error(repo, spec, e))?;
+    let mode = parsed.mode();
+
+    if mode.contains(RevparseMode::RANGE) {
+        let peel = |object: Option<&Object<'r>>| -> Result<Commit<'r>> {
+            object
+                .ok_or_else(|| RgitError::InvalidReference(spec.to_string()))?
+                .peel_to_commit()
+                .map_err(|_| RgitError::InvalidCommit(spec.to_string()).into())
+        };
+        return Ok(Revision::Range {
+            from: peel(parsed.from())?,
+            to: peel(parsed.to())?,
+            symmetric: mode.contains(RevparseMode::MERGE_BASE),
+        });
+    }
+
+    parsed.from()
+        .cloned()
+        .map(Revision::Single)
+        .ok_or_else(|| RgitError::InvalidReference(spec.to_string()).into())
+}
+
+/// Resolve a revspec that must name exactly one object
+pub fn resolve_object<'r>(repo: &'r Repository, spec: &str) -> Result<Object<'r>> {
+    match resolve_revision(repo, spec)? {
+        Revision::Single(object) => Ok(object),
+        Revision::Only(commit) => Ok(commit.into_object()),
+        Revision::Range { .. } => Err(RgitError::InvalidArgument(
+            format!("'{}' is a range, but a single revision is needed", spec)
+        ).into()),
+    }
+}
+
+/// Resolve a revspec to a commit, peeling tags
+pub fn resolve_commit<'r>(repo: &'r Repository, spec: &str) -> Result<Commit<'r>> {
+    resolve_object(repo, spec)?
+        .peel_to_commit()
+        .map_err(|_| RgitError::InvalidCommit(format!("'{}' does not point to a commit", spec)).into())
+}
+
+/// Translate a revparse failure into an error the user can act on
+fn revision_error(repo: &Repository, spec: &str, error: git2::Error) -> anyhow::Error {
+    debug!("revparse '{}' failed: {}", spec, error);
+
+    if ["@{u}", "@{upstream}", "@{push}"].iter().any(|s| spec.contains(s)) {
+        return RgitError::NoUpstreamBranch.into();
+    }
+    match error.code() {
+        ErrorCode::Ambiguous => RgitError::InvalidReference(
+            format!("'{}' is ambiguous; use more characters of the object id", spec)
+        ).into(),
+        _ if spec.contains("@{") && repo.head().is_err() => RgitError::InvalidReference(
+            format!("'{}': the repository has no history yet", spec)
+        ).into(),
+        _ => RgitError::InvalidReference(format!("unknown revision '{}'", spec)).into(),
+    }
+}
+
+// =============================================================================
+// Diffing
+// =============================================================================
+
+/// Builder for the [`DiffOptions`] the diffing commands share, so pathspecs
+/// and context are handled the same way everywhere
+#[derive(Debug, Clone, Default)]
+pub struct DiffOptionsBuilder {
+    paths: Vec<String>,
+    context_lines: Option<u32>,
+    include_untracked: bool,
+    show_binary: bool,
+    ignore_whitespace: bool,
+    ignore_blank_lines: bool,
+}
+
+impl DiffOptionsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the diff to these paths, relative to the working tree root
+    pub fn paths<I, S>(mut self, paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.paths.extend(paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// Lines of context around each hunk (git's default is 3)
+    pub fn context_lines(mut self, lines: u32) -> Self {
+        self.context_lines = Some(lines);
+        self
+    }
+
+    /// Report untracked files in working tree diffs
+    pub fn include_untracked(mut self) -> Self {
+        self.include_untracked = true;
+        self
+    }
+
+    /// Include binary contents so the diff can be applied
+    pub fn show_binary(mut self) -> Self {
+        self.show_binary = true;
+        self
+    }
+
+    /// Compare lines without regard to whitespace (`-w`)
+    pub fn ignore_whitespace(mut self, ignore: bool) -> Self {
+        self.ignore_whitespace = ignore;
+        self
+    }
+
+    /// Drop changes that only add or remove blank lines
+    pub fn ignore_blank_lines(mut self, ignore: bool) -> Self {
+        self.ignore_blank_lines = ignore;
+        self
+    }
+
+    pub fn build(&self) -> DiffOptions {
+        let mut options = DiffOptions::new();
+        for path in &self.paths {
+            // The working tree root matches everything
+            if !path.is_empty() {
+                options.pathspec(path);
+            }
+        }
+        if let Some(lines) = self.context_lines {
+            options.context_lines(lines);
+        }
+        options.include_untracked(self.include_untracked);
+        options.show_binary(self.show_binary);
+        options.ignore_whitespace(self.ignore_whitespace);
+        options.ignore_blank_lines(self.ignore_blank_lines);
+        options
+    }
+}
+
+/// Whether a diff pairs deleted and added files up as renames or copies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenameDetection {
+    pub renames: bool,
+    pub copies: bool,
+    /// Similarity in percent at which two files are paired
+    pub threshold: u16,
+}
+
+impl RenameDetection {
+    /// Leave moved files as a deletion and an addition
+    pub fn off() -> Self {
+        Self { renames: false, copies: false, threshold: 50 }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.renames || self.copies
+    }
+
+    /// Rewrite matching deletions and additions in `diff` into renames or copies
+    pub fn apply(&self, diff: &mut Diff) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        let mut options = DiffFindOptions::new();
+        options.renames(true).rename_threshold(self.threshold);
+        if self.copies {
+            options.copies(true).copy_threshold(self.threshold);
+        }
+        diff.find_similar(Some(&mut options))?;
+        Ok(())
+    }
 }
 
 // =============================================================================
@@ -568,13 +840,7 @@ impl BranchInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
-
-    fn create_test_repo() -> (TempDir, Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = Repository::init(temp_dir.path()).unwrap();
-        (temp_dir, repo)
-    }
+    use crate::test_support::{create_test_repo, TestRepo};
 
     #[test]
     fn test_rgit_core_creation() {
@@ -593,4 +859,37 @@ mod tests {
         let (_temp_dir, _repo) = create_test_repo();
         // Test branch information retrieval
     }
+
+    #[test]
+    fn test_resolve_revisions() {
+        let test_repo = TestRepo::new();
+        let repo = test_repo.repo();
+        let commit = |message: &str| test_repo.commit_file("file", message, message);
+        let first = commit("first");
+        let second = commit("second: fix typo");
+        let third = commit("third");
+        repo.branch("base", &repo.find_commit(first).unwrap(), false).unwrap();
+
+        assert_eq!(resolve_commit(repo, "HEAD~2").unwrap().id(), first);
+        assert_eq!(resolve_commit(repo, ":/fix typo").unwrap().id(), second);
+        assert!(resolve_object(repo, "HEAD:file").unwrap().as_blob().is_some());
+
+        match resolve_revision(repo, "base..HEAD").unwrap() {
+            Revision::Range { from, to, symmetric } => {
+                assert_eq!((from.id(), to.id(), symmetric), (first, third, false));
+            }
+            _ => panic!("expected a range"),
+        }
+        assert!(matches!(resolve_revision(repo, "base...HEAD").unwrap(), Revision::Range { symmetric: true, .. }));
+
+        let mut revwalk = repo.revwalk().unwrap();
+        resolve_revision(repo, "HEAD^!").unwrap().push_to(repo, &mut revwalk).unwrap();
+        assert_eq!(revwalk.map(|oid| oid.unwrap()).collect::<Vec<_>>(), vec![third]);
+
+        let err = resolve_commit(repo, "no-such-branch").unwrap_err();
+        assert!(err.to_string().contains("unknown revision"));
+        let err = resolve_commit(repo, "@{upstream}").unwrap_err();
+        assert!(matches!(err.downcast_ref::<RgitError>(), Some(RgitError::NoUpstreamBranch)));
+        assert!(resolve_commit(repo, "base..HEAD").is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,329 @@
+//! An in-memory cache for HTTPS credentials, like git-credential-cache.
+//!
+//! With `credential.cache` on, a username and password typed at a prompt go
+//! to a small daemon (`rgit credential daemon`, started on demand) that keeps
+//! them in memory for `credential.cache_timeout` and hands them to later
+//! rgit invocations over a Unix socket only the user can open. Nothing is
+//! written to disk. The daemon exits once its cache is empty, or on
+//! `rgit credential clear`.
+//!
+//! Like git's cache this needs Unix sockets; elsewhere nothing is cached.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// A username and password for one host
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credential {
+    pub username: String,
+    pub password: String,
+}
+
+/// What a client asks the daemon, one JSON line per connection
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "lowercase")]
+enum Request {
+    Get { key: String },
+    Store { key: String, credential: Credential, ttl_secs: u64 },
+    Erase { key: String },
+    Exit,
+}
+
+/// The cache key for `url`: its scheme, user name and host, without the
+/// path, or `None` for URLs that aren't HTTP(S). Like git-credential-cache,
+/// different users on one host get separate entries.
+pub fn cache_key(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    if !["http", "https"].contains(&parsed.scheme()) {
+        return None;
+    }
+    let user = match parsed.username() {
+        "" => String::new(),
+        name => format!("{}@", name),
+    };
+    let host = parsed.host_str()?;
+    Some(match parsed.port() {
+        Some(port) => format!("{}://{}{}:{}", parsed.scheme(), user, host, port),
+        None => format!("{}://{}{}", parsed.scheme(), user, host),
+    })
+}
+
+/// Where the daemon listens
+pub fn socket_path(config: &Config) -> Result<PathBuf> {
+    Ok(config.get_cache_dir()?.join("credential").join("socket"))
+}
+
+/// Credentials by key, each with when it expires
+#[derive(Debug, Default)]
+struct Cache {
+    entries: HashMap<String, (Credential, Instant)>,
+}
+
+impl Cache {
+    fn handle(&mut self, request: Request, now: Instant) -> Option<Credential> {
+        self.purge(now);
+        match request {
+            Request::Get { key } => self.entries.get(&key).map(|(credential, _)| credential.clone()),
+            Request::Store { key, credential, ttl_secs } => {
+                self.entries.insert(key, (credential, now + Duration::from_secs(ttl_secs)));
+                None
+            }
+            Request::Erase { key } => {
+                self.entries.remove(&key);
+                None
+            }
+            Request::Exit => {
+                self.entries.clear();
+                None
+            }
+        }
+    }
+
+    fn purge(&mut self, now: Instant) {
+        self.entries.retain(|_, (_, expires)| *expires > now);
+    }
+}
+
+/// The cached credential for `key`, if a daemon has one
+pub fn get(config: &Config, key: &str) -> Option<Credential> {
+    let reply = imp::send(&socket_path(config).ok()?, &Request::Get { key: key.to_string() }).ok()?;
+    serde_json::from_str(&reply).ok().flatten()
+}
+
+/// Cache `credential` for `credential.cache_timeout`, starting the daemon
+/// if it isn't running
+pub fn store(config: &Config, key: &str, credential: Credential) -> Result<()> {
+    let socket = socket_path(config)?;
+    let request = Request::Store {
+        key: key.to_string(),
+        credential,
+        ttl_secs: config.credential.timeout().as_secs(),
+    };
+    if imp::send(&socket, &request).is_err() {
+        imp::spawn_daemon(&socket)?;
+        imp::send(&socket, &request)?;
+    }
+    Ok(())
+}
+
+/// Forget the credential for `key`, e.g. after the remote rejected it
+pub fn erase(config: &Config, key: &str) {
+    if let Ok(socket) = socket_path(config) {
+        let _ = imp::send(&socket, &Request::Erase { key: key.to_string() });
+    }
+}
+
+/// Forget every cached credential and stop the daemon. Returns whether one
+/// was running.
+pub fn clear(config: &Config) -> Result<bool> {
+    Ok(imp::send(&socket_path(config)?, &Request::Exit).is_ok())
+}
+
+/// Run the daemon on `socket` until its cache is empty or it's told to exit
+pub fn serve(socket: &Path) -> Result<()> {
+    imp::serve(socket)
+}
+
+#[cfg(unix)]
+mod imp {
+    use anyhow::{Context, Result};
+    use std::fs;
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::os::unix::process::CommandExt;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::{Duration, Instant};
+    use tracing::debug;
+
+    use super::{Cache, Request};
+
+    /// How long a new daemon waits for its first credential before giving up
+    const STARTUP_GRACE: Duration = Duration::from_secs(10);
+    /// How long a client may take to send its request
+    const CLIENT_TIMEOUT: Duration = Duration::from_secs(2);
+    /// How often an idle daemon checks for expired credentials
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    pub fn send(socket: &Path, request: &Request) -> Result<String> {
+        let mut stream = UnixStream::connect(socket)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        writeln!(stream, "{}", serde_json::to_string(request)?)?;
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply)?;
+        Ok(reply)
+    }
+
+    pub fn spawn_daemon(socket: &Path) -> Result<()> {
+        let exe = std::env::current_exe().context("Cannot find the rgit executable")?;
+        // Its own process group, so Ctrl-C in the terminal doesn't end it
+        Command::new(exe)
+            .args(["credential", "daemon", "--socket"])
+            .arg(socket)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn()
+            .context("Failed to start the credential cache daemon")?;
+
+        let started = Instant::now();
+        while started.elapsed() < CLIENT_TIMEOUT {
+            if UnixStream::connect(socket).is_ok() {
+                return Ok(());
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        anyhow::bail!("The credential cache daemon didn't start")
+    }
+
+    pub fn serve(socket: &Path) -> Result<()> {
+        if let Some(dir) = socket.parent() {
+            fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+        }
+        // A socket left behind by a daemon that died
+        let _ = fs::remove_file(socket);
+        let listener = UnixListener::bind(socket)?;
+        fs::set_permissions(socket, fs::Permissions::from_mode(0o600))?;
+
+        // Polling lets expired credentials end the daemon between clients
+        listener.set_nonblocking(true)?;
+        let mut cache = Cache::default();
+        let started = Instant::now();
+        loop {
+            match listener.accept() {
+                Ok((stream, _)) => match answer(stream, &mut cache) {
+                    Ok(true) => break,
+                    Ok(false) => {}
+                    Err(e) => debug!("Credential cache request failed: {}", e),
+                },
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    cache.purge(Instant::now());
+                    if cache.entries.is_empty() && started.elapsed() >= STARTUP_GRACE {
+                        break;
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(e) => debug!("Credential cache connection failed: {}", e),
+            }
+        }
+        let _ = fs::remove_file(socket);
+        Ok(())
+    }
+
+    /// Answer one client, returning whether it asked the daemon to exit
+    fn answer(stream: UnixStream, cache: &mut Cache) -> Result<bool> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        let request: Request = serde_json::from_str(&line)?;
+        let exit = matches!(request, Request::Exit);
+        let reply = cache.handle(request, Instant::now());
+        writeln!(&stream, "{}", serde_json::to_string(&reply)?)?;
+        Ok(exit)
+    }
+}
+
+#[cfg(not(unix))]
+mod imp {
+    use anyhow::Result;
+    use std::path::Path;
+
+    use super::Request;
+
+    pub fn send(_socket: &Path, _request: &Request) -> Result<String> {
+        anyhow::bail!("The credential cache needs Unix sockets")
+    }
+
+    pub fn spawn_daemon(_socket: &Path) -> Result<()> {
+        anyhow::bail!("The credential cache needs Unix sockets")
+    }
+
+    pub fn serve(_socket: &Path) -> Result<()> {
+        anyhow::bail!("The credential cache needs Unix sockets")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn credential(password: &str) -> Credential {
+        Credential { username: "me".to_string(), password: password.to_string() }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(cache_key("https://github.com/org/repo.git").as_deref(), Some("https://github.com"));
+        assert_eq!(cache_key("https://me@github.com/org/repo.git").as_deref(), Some("https://me@github.com"));
+        assert_eq!(cache_key("http://me@git.example.com:8080/repo").as_deref(), Some("http://me@git.example.com:8080"));
+        assert_eq!(cache_key("git@github.com:org/repo.git"), None);
+        assert_eq!(cache_key("ssh://git@github.com/org/repo.git"), None);
+    }
+
+    #[test]
+    fn test_cache_expiry() {
+        let mut cache = Cache::default();
+        let now = Instant::now();
+        let key = "https://github.com".to_string();
+        cache.handle(Request::Store { key: key.clone(), credential: credential("a"), ttl_secs: 60 }, now);
+        assert_eq!(cache.handle(Request::Get { key: key.clone() }, now), Some(credential("a")));
+        assert_eq!(cache.handle(Request::Get { key: key.clone() }, now + Duration::from_secs(61)), None);
+        assert!(cache.entries.is_empty());
+
+        cache.handle(Request::Store { key: key.clone(), credential: credential("b"), ttl_secs: 60 }, now);
+        cache.handle(Request::Erase { key: key.clone() }, now);
+        assert_eq!(cache.handle(Request::Get { key }, now), None);
+    }
+
+    #[test]
+    fn test_cache_keeps_users_on_one_host_apart() {
+        let mut cache = Cache::default();
+        let now = Instant::now();
+        let alice = cache_key("https://alice@github.com/org/repo.git").unwrap();
+        let bob = cache_key("https://bob@github.com/org/other.git").unwrap();
+        assert_ne!(alice, bob);
+
+        let bobs = Credential { username: "bob".to_string(), password: "b".to_string() };
+        cache.handle(Request::Store { key: alice.clone(), credential: credential("a"), ttl_secs: 60 }, now);
+        cache.handle(Request::Store { key: bob.clone(), credential: bobs.clone(), ttl_secs: 60 }, now);
+        assert_eq!(cache.handle(Request::Get { key: alice.clone() }, now), Some(credential("a")));
+        assert_eq!(cache.handle(Request::Get { key: bob.clone() }, now), Some(bobs));
+
+        cache.handle(Request::Erase { key: bob.clone() }, now);
+        assert_eq!(cache.handle(Request::Get { key: alice }, now), Some(credential("a")));
+        assert_eq!(cache.handle(Request::Get { key: bob }, now), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_daemon_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let socket = dir.path().join("credential").join("socket");
+        let server = {
+            let socket = socket.clone();
+            std::thread::spawn(move || serve(&socket))
+        };
+        while std::os::unix::net::UnixStream::connect(&socket).is_err() {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let key = "https://github.com".to_string();
+        imp::send(&socket, &Request::Store { key: key.clone(), credential: credential("a"), ttl_secs: 60 }).unwrap();
+        let reply = imp::send(&socket, &Request::Get { key }).unwrap();
+        assert_eq!(serde_json::from_str::<Option<Credential>>(&reply).unwrap(), Some(credential("a")));
+
+        imp::send(&socket, &Request::Exit).unwrap();
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,503 @@
+//! `--dry-run` for write commands without a dry-run mode of their own.
+//!
+//! The command runs against a scratch copy of the repository, and rgit
+//! reports how the copy changed: refs moved, commits created, changes
+//! staged and files written. The copy's git directory borrows the
+//! original's objects through `objects/info/alternates`, so only refs, the
+//! index and the working tree are copied; ignored directories are copied
+//! empty.
+//!
+//! Each remote's push URL points at a scratch bare repository seeded from
+//! the remote-tracking branches, so pushes are reported instead of sent.
+//! Fetches still read from the real remotes, but only update the copy; for
+//! remotes on the same machine they read the scratch repository instead,
+//! since libgit2 pushes to those through their fetch URL.
+
+use anyhow::{Context, Result};
+use colored::*;
+use git2::{Delta, Index, ObjectType, Oid, Repository};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+use crate::error::RgitError;
+use crate::output::decoration;
+use crate::utils::shorten_oid;
+
+/// Commits and files listed in a plan before the rest are counted
+const MAX_LISTED: usize = 20;
+
+/// A ref that a command created, moved or deleted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefMove {
+    pub name: String,
+    /// None when the command created it
+    pub before: Option<Oid>,
+    /// None when the command deleted it
+    pub after: Option<Oid>,
+}
+
+/// A scratch repository standing in for a remote's push URL
+struct PushSink {
+    remote: String,
+    path: PathBuf,
+    seeded: BTreeMap<String, Oid>,
+}
+
+/// A scratch copy of a repository
+pub struct Sandbox {
+    /// Removed, copy and all, when the sandbox is dropped
+    _dir: TempDir,
+    original: PathBuf,
+    workdir: PathBuf,
+    cwd: PathBuf,
+    /// Working tree of the copy before the command, path to blob id
+    files: BTreeMap<String, Oid>,
+    sinks: Vec<PushSink>,
+}
+
+impl Sandbox {
+    /// Copy the repository containing `start`
+    pub fn create(start: &Path) -> Result<Self> {
+        let repo = Repository::discover(start).map_err(|_| RgitError::NotInRepository)?;
+        let Some(original) = repo.workdir().filter(|_| !repo.is_worktree()).map(Path::to_path_buf) else {
+            return Err(RgitError::OperationNotSupported(
+                "--dry-run in a bare repository or a linked worktree".to_string()
+            ).into());
+        };
+
+        let dir = tempfile::Builder::new().prefix("rgit-dry-run").tempdir()?;
+        let workdir = dir.path().join("repo");
+        copy_git_dir(repo.path(), &workdir.join(".git"))
+            .context("Failed to copy the repository for the dry run")?;
+        let mut files = BTreeMap::new();
+        copy_worktree(&repo, &original, &original, &workdir, &mut files)
+            .context("Failed to copy the working tree for the dry run")?;
+
+        let copy = Repository::open(&workdir)?;
+        let sinks = redirect_pushes(&copy, &repo, dir.path())?;
+        Ok(Self {
+            _dir: dir,
+            original: original.canonicalize()?,
+            workdir,
+            cwd: start.canonicalize()?,
+            files,
+            sinks,
+        })
+    }
+
+    /// Move into the copy, at the same place relative to the repository root
+    pub fn enter(&self) -> Result<()> {
+        let relative = self.cwd
+            .strip_prefix(&self.original)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let target = self.workdir.join(relative);
+        fs::create_dir_all(&target)?;
+        std::env::set_current_dir(&target)?;
+        Ok(())
+    }
+
+    /// Back to where the sandbox was created from
+    pub fn leave(&self) -> Result<()> {
+        std::env::set_current_dir(&self.cwd)?;
+        Ok(())
+    }
+
+    /// Everything that differs between the repository and its copy
+    pub fn plan(&self) -> Result<Plan> {
+        let original = Repository::open(&self.original)?;
+        let copy = Repository::open(&self.workdir)?;
+
+        let before = ref_targets(&original)?;
+        let after = ref_targets(&copy)?;
+        let refs = ref_moves(&before, &after);
+
+        let head = (describe_head(&original), describe_head(&copy));
+        let head = if head.0 != head.1 { Some(head) } else { None };
+
+        let mut commits = Vec::new();
+        let mut walk = copy.revwalk()?;
+        let mut new_tips = refs.iter().filter_map(|r| r.after).collect::<Vec<_>>();
+        new_tips.extend(copy.head().ok().and_then(|h| h.target()));
+        for tip in new_tips {
+            if let Ok(commit) = copy.find_object(tip, None).and_then(|o| o.peel_to_commit()) {
+                walk.push(commit.id())?;
+            }
+        }
+        for oid in before.values() {
+            if let Ok(commit) = copy.find_object(*oid, None).and_then(|o| o.peel_to_commit()) {
+                walk.hide(commit.id())?;
+            }
+        }
+        for oid in walk {
+            let commit = copy.find_commit(oid?)?;
+            commits.push((commit.id(), commit.summary().unwrap_or("").to_string()));
+        }
+
+        let staged = match (index_tree(&copy, Index::open(&original.path().join("index"))?), index_tree(&copy, copy.index()?)) {
+            (Some(before), Some(after)) if before != after => {
+                let diff = copy.diff_tree_to_tree(Some(&copy.find_tree(before)?), Some(&copy.find_tree(after)?), None)?;
+                diff.deltas()
+                    .filter_map(|delta| {
+                        let path = delta.new_file().path().or(delta.old_file().path())?;
+                        Some((status_letter(delta.status()), path.to_string_lossy().replace('\\', "/")))
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+
+        let mut now = BTreeMap::new();
+        hash_worktree(&self.workdir, &self.workdir, &mut now)?;
+        let mut files: Vec<(char, String)> = Vec::new();
+        for (path, oid) in &now {
+            match self.files.get(path) {
+                None => files.push(('A', path.clone())),
+                Some(old) if old != oid => files.push(('M', path.clone())),
+                Some(_) => {}
+            }
+        }
+        files.extend(self.files.keys().filter(|path| !now.contains_key(*path)).map(|path| ('D', path.clone())));
+        files.sort_by(|a, b| a.1.cmp(&b.1));
+
+        let mut pushes = Vec::new();
+        for sink in &self.sinks {
+            let after = ref_targets(&Repository::open_bare(&sink.path)?)?;
+            pushes.extend(ref_moves(&sink.seeded, &after).into_iter().map(|r| (sink.remote.clone(), r)));
+        }
+
+        Ok(Plan { head, refs, commits, staged, files, pushes })
+    }
+}
+
+/// What a dry run would have changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Plan {
+    /// HEAD before and after, when it changed
+    pub head: Option<(String, String)>,
+    pub refs: Vec<RefMove>,
+    /// New commits, newest first
+    pub commits: Vec<(Oid, String)>,
+    /// Index changes, as status letter and path
+    pub staged: Vec<(char, String)>,
+    /// Working tree changes, as status letter and path
+    pub files: Vec<(char, String)>,
+    /// Refs a push would update, by remote
+    pub pushes: Vec<(String, RefMove)>,
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        *self == Plan::default()
+    }
+
+    pub fn print(&self) {
+        ui_println!("{} {}", "🧪".blue(), "Dry run: nothing was changed".bold());
+        if self.is_empty() {
+            println!("   The command wouldn't change anything");
+            return;
+        }
+
+        if let Some((before, after)) = &self.head {
+            ui_println!("\n{} {} → {}", "HEAD:".bold(), before, after.cyan());
+        }
+        if !self.refs.is_empty() {
+            println!("\n{}", "Refs:".bold());
+            for r in &self.refs {
+                println!("  {}", describe_move(r, r.name.trim_start_matches("refs/")));
+            }
+        }
+        if !self.commits.is_empty() {
+            println!("\n{}", "Commits created:".bold());
+            for (oid, summary) in self.commits.iter().take(MAX_LISTED) {
+                println!("  {} {}", shorten_oid(oid, 8).yellow(), summary);
+            }
+            more(self.commits.len());
+        }
+        if !self.staged.is_empty() {
+            println!("\n{}", "Index:".bold());
+            list_files(&self.staged);
+        }
+        if !self.files.is_empty() {
+            println!("\n{}", "Working tree:".bold());
+            list_files(&self.files);
+        }
+        if !self.pushes.is_empty() {
+            println!("\n{}", "Pushes (not sent):".bold());
+            for (remote, r) in &self.pushes {
+                let name = format!("{}/{}", remote, r.name.trim_start_matches("refs/heads/"));
+                println!("  {}", describe_move(r, &name));
+            }
+        }
+    }
+}
+
+fn describe_move(r: &RefMove, name: &str) -> String {
+    let short = |oid: Option<Oid>| oid.map(|id| shorten_oid(&id, 8)).unwrap_or_default();
+    match (r.before, r.after) {
+        (None, _) => format!("{} {} {}", "+".green(), name, short(r.after).yellow()),
+        (_, None) => format!("{} {} {}", "-".red(), name, format!("(was {})", short(r.before)).dimmed()),
+        _ => format!("{} {} {} {} {}", "~".yellow(), name, short(r.before), decoration("→"), short(r.after).yellow()),
+    }
+}
+
+fn list_files(files: &[(char, String)]) {
+    for (status, path) in files.iter().take(MAX_LISTED) {
+        let status = match status {
+            'A' => status.to_string().green(),
+            'D' => status.to_string().red(),
+            _ => status.to_string().yellow(),
+        };
+        println!("  {} {}", status, path);
+    }
+    more(files.len());
+}
+
+fn more(count: usize) {
+    if count > MAX_LISTED {
+        ui_println!("  {} {}", "…".dimmed(), format!("and {} more", count - MAX_LISTED).dimmed());
+    }
+}
+
+fn status_letter(status: Delta) -> char {
+    match status {
+        Delta::Added => 'A',
+        Delta::Deleted => 'D',
+        Delta::Renamed => 'R',
+        Delta::Typechange => 'T',
+        _ => 'M',
+    }
+}
+
+// =============================================================================
+// Copying
+// =============================================================================
+
+/// Copy a git directory, borrowing its objects instead of copying them.
+/// Git directories inside it, such as those of submodules, are treated alike.
+fn copy_git_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to)?;
+    let is_git_dir = from.join("HEAD").is_file() && from.join("objects").is_dir();
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let (source, target) = (entry.path(), to.join(&name));
+        if is_git_dir && name == "objects" {
+            borrow_objects(&source, &target)?;
+        } else if entry.file_type()?.is_dir() {
+            copy_git_dir(&source, &target)?;
+        } else if !name.to_string_lossy().ends_with(".lock") {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// An empty object directory that reads through to `from`
+fn borrow_objects(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to.join("info"))?;
+    fs::create_dir_all(to.join("pack"))?;
+    let mut alternates = format!("{}\n", from.canonicalize()?.display());
+    if let Ok(existing) = fs::read_to_string(from.join("info").join("alternates")) {
+        alternates.push_str(&existing);
+    }
+    fs::write(to.join("info").join("alternates"), alternates)?;
+    Ok(())
+}
+
+/// Copy a working tree directory, recording each file's blob id
+fn copy_worktree(repo: &Repository, root: &Path, dir: &Path, to: &Path, files: &mut BTreeMap<String, Oid>) -> Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = relative_path(root, &path);
+        if relative == ".git" {
+            continue;
+        }
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            let link = fs::read_link(&path)?;
+            copy_link(&link, &target)?;
+            files.insert(relative, Oid::hash_object(ObjectType::Blob, link.to_string_lossy().as_bytes())?);
+        } else if file_type.is_dir() {
+            // Build output and the like would only slow the copy down
+            if repo.is_path_ignored(format!("{}/", relative)).unwrap_or(false) {
+                fs::create_dir_all(&target)?;
+            } else {
+                copy_worktree(repo, root, &path, &target, files)?;
+            }
+        } else {
+            fs::copy(&path, &target)?;
+            files.insert(relative, Oid::hash_file(ObjectType::Blob, &path)?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_link(link: &Path, target: &Path) -> Result<()> {
+    std::os::unix::fs::symlink(link, target)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn copy_link(link: &Path, target: &Path) -> Result<()> {
+    fs::write(target, link.to_string_lossy().as_bytes())?;
+    Ok(())
+}
+
+/// Blob ids of the copy's working tree after the command
+fn hash_worktree(root: &Path, dir: &Path, files: &mut BTreeMap<String, Oid>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative = relative_path(root, &path);
+        if relative == ".git" {
+            continue;
+        }
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            let link = fs::read_link(&path)?;
+            files.insert(relative, Oid::hash_object(ObjectType::Blob, link.to_string_lossy().as_bytes())?);
+        } else if file_type.is_dir() {
+            hash_worktree(root, &path, files)?;
+        } else {
+            files.insert(relative, Oid::hash_file(ObjectType::Blob, &path)?);
+        }
+    }
+    Ok(())
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+/// Point every remote's push URL at a scratch repository holding what the
+/// remote-tracking branches say the remote has
+fn redirect_pushes(copy: &Repository, original: &Repository, scratch: &Path) -> Result<Vec<PushSink>> {
+    let mut sinks = Vec::new();
+    for (n, remote) in copy.remotes()?.iter().flatten().enumerate() {
+        let path = scratch.join("remotes").join(format!("{}.git", n));
+        let sink = Repository::init_bare(&path)?;
+        borrow_objects(&original.path().join("objects"), &sink.path().join("objects"))?;
+
+        let prefix = format!("refs/remotes/{}/", remote);
+        let mut seeded = BTreeMap::new();
+        for (name, oid) in ref_targets(copy)? {
+            let Some(branch) = name.strip_prefix(&prefix).filter(|b| *b != "HEAD") else { continue };
+            let name = format!("refs/heads/{}", branch);
+            sink.reference(&name, oid, true, "rgit dry run")?;
+            seeded.insert(name, oid);
+        }
+
+        let url = path.to_string_lossy().to_string();
+        copy.remote_set_pushurl(remote, Some(&url))?;
+        // libgit2 pushes to a local remote's URL even when a push URL is set
+        if copy.find_remote(remote)?.url().is_some_and(is_local_url) {
+            copy.remote_set_url(remote, &url)?;
+        }
+        sinks.push(PushSink { remote: remote.to_string(), path, seeded });
+    }
+    Ok(sinks)
+}
+
+/// Whether `url` names a repository on this machine rather than a server
+fn is_local_url(url: &str) -> bool {
+    url.starts_with("file://") || Path::new(url).exists()
+}
+
+// =============================================================================
+// Comparing
+// =============================================================================
+
+/// Direct refs outside rgit's own namespace
+fn ref_targets(repo: &Repository) -> Result<BTreeMap<String, Oid>> {
+    let mut targets = BTreeMap::new();
+    for reference in repo.references()? {
+        let reference = reference?;
+        if let (Some(name), Some(oid)) = (reference.name(), reference.target()) {
+            if !name.starts_with("refs/rgit/") {
+                targets.insert(name.to_string(), oid);
+            }
+        }
+    }
+    Ok(targets)
+}
+
+fn ref_moves(before: &BTreeMap<String, Oid>, after: &BTreeMap<String, Oid>) -> Vec<RefMove> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+    names.into_iter()
+        .map(|name| RefMove { name: name.clone(), before: before.get(name).copied(), after: after.get(name).copied() })
+        .filter(|r| r.before != r.after)
+        .collect()
+}
+
+fn describe_head(repo: &Repository) -> String {
+    match repo.find_reference("HEAD") {
+        Ok(head) => match head.symbolic_target() {
+            Some(target) => target.trim_start_matches("refs/heads/").to_string(),
+            None => head.target().map(|oid| format!("detached at {}", shorten_oid(&oid, 8))).unwrap_or_default(),
+        },
+        Err(_) => String::new(),
+    }
+}
+
+/// An index as a tree in `repo`, or None while it has conflicts
+fn index_tree(repo: &Repository, mut index: Index) -> Option<Oid> {
+    if index.has_conflicts() {
+        return None;
+    }
+    index.write_tree_to(repo).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_plan_reports_changes_to_the_copy_only() {
+        let repo = TestRepo::new();
+        let head = repo.commit_file("a.txt", "one\n", "First");
+        repo.write("b.txt", "untracked\n");
+        let remote = repo.add_remote("origin");
+
+        let sandbox = Sandbox::create(repo.path()).unwrap();
+        assert!(sandbox.plan().unwrap().is_empty());
+
+        let copy = Repository::open(&sandbox.workdir).unwrap();
+        fs::write(sandbox.workdir.join("a.txt"), "two\n").unwrap();
+        let mut index = copy.index().unwrap();
+        index.add_path(Path::new("a.txt")).unwrap();
+        index.write().unwrap();
+        let tree = copy.find_tree(index.write_tree().unwrap()).unwrap();
+        let parent = copy.find_commit(head).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        let commit = copy.commit(Some("HEAD"), &signature, &signature, "Second", &tree, &[&parent]).unwrap();
+        fs::remove_file(sandbox.workdir.join("b.txt")).unwrap();
+        copy.find_remote("origin").unwrap()
+            .push(&["refs/heads/main:refs/heads/main"], None).unwrap();
+
+        let plan = sandbox.plan().unwrap();
+        let moved = |name: &str| RefMove { name: name.to_string(), before: Some(head), after: Some(commit) };
+        assert_eq!(plan.refs, vec![moved("refs/heads/main"), moved("refs/remotes/origin/main")]);
+        assert_eq!(plan.commits, vec![(commit, "Second".to_string())]);
+        assert_eq!(plan.staged, vec![('M', "a.txt".to_string())]);
+        assert_eq!(plan.files, vec![('M', "a.txt".to_string()), ('D', "b.txt".to_string())]);
+        assert_eq!(plan.pushes.len(), 1);
+        assert_eq!(plan.pushes[0].1.after, Some(commit));
+
+        // The repository itself is untouched
+        assert_eq!(repo.head(), head);
+        assert_eq!(fs::read_to_string(repo.path().join("a.txt")).unwrap(), "one\n");
+        assert!(repo.path().join("b.txt").exists());
+        assert!(repo.repo().find_commit(commit).is_err());
+        assert_eq!(remote.repo().refname_to_id("refs/heads/main").unwrap(), head);
+    }
+}
@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::error_catalog::{self, ErrorExplanation};
+
 /// Comprehensive error types for rgit operations
 #[derive(Error, Debug)]
 pub enum RgitError {
@@ -87,6 +89,12 @@ pub enum RgitError {
     #[error("User identity not configured. Set user.name and user.email")]
     UserIdentityNotConfigured,
     
+    #[error("Staged changes contain {0} possible secret(s)")]
+    SecretsDetected(usize),
+    
+    #[error("{count} staged file(s) exceed commit.max_file_size ({limit})")]
+    StagedFilesTooLarge { count: usize, limit: String },
+    
     // =========================================================================
     // Branch Errors
     // =========================================================================
@@ -238,6 +246,19 @@ pub enum RgitError {
     #[error("GPG signing failed: {0}")]
     GpgSigningFailed(String),
     
+    // =========================================================================
+    // Workspace Errors
+    // =========================================================================
+    
+    #[error("Not in an rgit workspace (no .rgit-workspace.toml found)")]
+    WorkspaceNotFound,
+    
+    #[error("Repository already registered in workspace: {0}")]
+    WorkspaceRepoExists(String),
+    
+    #[error("Repository not registered in workspace: {0}")]
+    WorkspaceRepoNotFound(String),
+    
     // =========================================================================
     // Configuration Errors
     // =========================================================================
@@ -254,6 +275,9 @@ pub enum RgitError {
     #[error("Permission denied reading configuration")]
     ConfigPermissionDenied,
     
+    #[error("Unknown configuration key: {0}")]
+    UnknownConfigKey(String),
+    
     // =========================================================================
     // Operation Errors
     // =========================================================================
@@ -267,7 +291,10 @@ pub enum RgitError {
     #[error("Operation failed: {0}")]
     OperationFailed(String),
     
-    #[error("Interactive operation not available in non-TTY environment")]
+    #[error("Health check found {errors} error(s) and {warnings} warning(s)")]
+    HealthCheckFailed { errors: usize, warnings: usize },
+    
+    #[error("Interactive operation not available in non-TTY environment or with --yes")]
     NonInteractiveEnvironment,
     
     #[error("Command execution failed: {0}")]
@@ -396,54 +423,127 @@ impl RgitError {
         }
     }
 
-    /// Get suggested recovery actions for this error
-    pub fn recovery_suggestions(&self) -> Vec<&'static str> {
+    /// Stable code shown with the error; `rgit explain <code>` tells more.
+    /// Codes follow declaration order and are never reused, see
+    /// [`crate::error_catalog`].
+    pub fn code(&self) -> &'static str {
         match self {
-            RgitError::NotInRepository => vec![
-                "Navigate to a git repository directory",
-                "Run 'rgit init' to create a new repository",
-            ],
-            RgitError::UserIdentityNotConfigured => vec![
-                "Set your name: git config user.name \"Your Name\"",
-                "Set your email: git config user.email \"your@email.com\"",
-            ],
-            RgitError::EmptyCommitMessage => vec![
-                "Provide a meaningful commit message",
-                "Use 'rgit commit -m \"your message\"'",
-            ],
-            RgitError::MergeConflict(_) => vec![
-                "Use 'rgit resolve' for interactive conflict resolution",
-                "Edit conflicted files manually and then 'rgit add' them",
-                "Use 'rgit status' to see all conflicts",
-            ],
-            RgitError::BranchHasUncommittedChanges => vec![
-                "Commit your changes: 'rgit commit'",
-                "Stash your changes: 'rgit stash save'",
-                "Discard changes: 'rgit checkout -- .'",
-            ],
-            RgitError::AuthenticationError(_) => vec![
-                "Check your credentials",
-                "Set up SSH keys for authentication",
-                "Use 'rgit doctor' to verify configuration",
-            ],
-            RgitError::NetworkError(_) => vec![
-                "Check your internet connection",
-                "Verify the remote repository URL",
-                "Try again later if the remote server is temporarily unavailable",
-            ],
-            RgitError::SubmoduleUncommittedChanges(_) => vec![
-                "Commit changes in the submodule",
-                "Use 'rgit submodule status' to see all submodule states",
-                "Stash submodule changes if needed",
-            ],
-            RgitError::NoRemoteConfigured => vec![
-                "Add a remote: 'rgit remote add origin <url>'",
-                "Clone from a remote repository instead",
-            ],
-            _ => vec!["Use 'rgit doctor' for diagnostics", "Check 'rgit --help' for usage"],
+            RgitError::NotInRepository => "RG-0001",
+            RgitError::RepositoryNotInitialized => "RG-0002",
+            RgitError::RepositoryNotFound(_) => "RG-0003",
+            RgitError::RepositoryCorrupted => "RG-0004",
+            RgitError::InvalidRepositoryState(_) => "RG-0005",
+            RgitError::DirectoryNotEmpty(_) => "RG-0006",
+            RgitError::CloneFailed(_) => "RG-0007",
+            RgitError::UncommittedChanges => "RG-0008",
+            RgitError::NoUpstreamBranch => "RG-0009",
+            RgitError::FastForwardNotPossible => "RG-0010",
+            RgitError::MergeNotPossible => "RG-0011",
+            RgitError::FileNotFound(_) => "RG-0012",
+            RgitError::FileIgnored(_) => "RG-0013",
+            RgitError::IndexLocked => "RG-0014",
+            RgitError::IndexCorrupted => "RG-0015",
+            RgitError::EmptyDirectory(_) => "RG-0016",
+            RgitError::PermissionDenied(_) => "RG-0017",
+            RgitError::EmptyCommitMessage => "RG-0018",
+            RgitError::NothingToCommit => "RG-0019",
+            RgitError::CannotAmendInitialCommit => "RG-0020",
+            RgitError::CommitFailed(_) => "RG-0021",
+            RgitError::InvalidCommit(_) => "RG-0022",
+            RgitError::UserIdentityNotConfigured => "RG-0023",
+            RgitError::SecretsDetected(_) => "RG-0024",
+            RgitError::StagedFilesTooLarge { .. } => "RG-0025",
+            RgitError::BranchNotFound(_) => "RG-0026",
+            RgitError::BranchAlreadyExists(_) => "RG-0027",
+            RgitError::CannotDeleteCurrentBranch(_) => "RG-0028",
+            RgitError::BranchHasUncommittedChanges => "RG-0029",
+            RgitError::CheckoutFailed(_) => "RG-0030",
+            RgitError::DetachedHead => "RG-0031",
+            RgitError::InvalidBranchName(_) => "RG-0032",
+            RgitError::NoRemoteConfigured => "RG-0033",
+            RgitError::RemoteNotFound(_) => "RG-0034",
+            RgitError::RemoteAlreadyExists(_) => "RG-0035",
+            RgitError::InvalidRemoteUrl(_) => "RG-0036",
+            RgitError::PushRejected(_) => "RG-0037",
+            RgitError::PullFailed(_) => "RG-0038",
+            RgitError::FetchFailed(_) => "RG-0039",
+            RgitError::AuthenticationError(_) => "RG-0040",
+            RgitError::NetworkError(_) => "RG-0041",
+            RgitError::SshKeyError => "RG-0042",
+            RgitError::CertificateError => "RG-0043",
+            RgitError::ConnectionTimeout => "RG-0044",
+            RgitError::RemoteUnavailable => "RG-0045",
+            RgitError::MergeConflict(_) => "RG-0046",
+            RgitError::MergeWorkingTreeDirty => "RG-0047",
+            RgitError::MergeAborted => "RG-0048",
+            RgitError::RebaseFailed(_) => "RG-0049",
+            RgitError::RebaseConflict(_) => "RG-0050",
+            RgitError::NothingToRebase => "RG-0051",
+            RgitError::CherryPickFailed(_) => "RG-0052",
+            RgitError::SubmoduleError(_) => "RG-0053",
+            RgitError::SubmoduleNotFound(_) => "RG-0054",
+            RgitError::SubmoduleNotInitialized(_) => "RG-0055",
+            RgitError::SubmoduleUncommittedChanges(_) => "RG-0056",
+            RgitError::SubmoduleInvalidUrl(_) => "RG-0057",
+            RgitError::SubmoduleOperationFailed(_) => "RG-0058",
+            RgitError::NoStashEntries => "RG-0059",
+            RgitError::StashIndexOutOfRange(_) => "RG-0060",
+            RgitError::NothingToStash => "RG-0061",
+            RgitError::StashApplyFailed(_) => "RG-0062",
+            RgitError::TagNotFound(_) => "RG-0063",
+            RgitError::TagAlreadyExists(_) => "RG-0064",
+            RgitError::InvalidTagName(_) => "RG-0065",
+            RgitError::GpgSigningFailed(_) => "RG-0066",
+            RgitError::WorkspaceNotFound => "RG-0067",
+            RgitError::WorkspaceRepoExists(_) => "RG-0068",
+            RgitError::WorkspaceRepoNotFound(_) => "RG-0069",
+            RgitError::ConfigurationError(_) => "RG-0070",
+            RgitError::InvalidConfigValue { .. } => "RG-0071",
+            RgitError::ConfigFileNotFound(_) => "RG-0072",
+            RgitError::ConfigPermissionDenied => "RG-0073",
+            RgitError::UnknownConfigKey(_) => "RG-0074",
+            RgitError::OperationCancelled => "RG-0075",
+            RgitError::OperationNotSupported(_) => "RG-0076",
+            RgitError::OperationFailed(_) => "RG-0077",
+            RgitError::HealthCheckFailed { .. } => "RG-0078",
+            RgitError::NonInteractiveEnvironment => "RG-0079",
+            RgitError::CommandExecutionFailed(_) => "RG-0080",
+            RgitError::InvalidArgument(_) => "RG-0081",
+            RgitError::InvalidPath(_) => "RG-0082",
+            RgitError::InvalidReference(_) => "RG-0083",
+            RgitError::InvalidObjectId(_) => "RG-0084",
+            RgitError::PathOutsideRepository(_) => "RG-0085",
+            RgitError::IoError(_) => "RG-0086",
+            RgitError::FileSystemError(_) => "RG-0087",
+            RgitError::InsufficientDiskSpace => "RG-0088",
+            RgitError::TempFileCreationFailed => "RG-0089",
+            RgitError::ParseError(_) => "RG-0090",
+            RgitError::InvalidDateFormat(_) => "RG-0091",
+            RgitError::InvalidTimeFormat(_) => "RG-0092",
+            RgitError::EncodingError(_) => "RG-0093",
+            RgitError::ExternalEditorFailed(_) => "RG-0094",
+            RgitError::DiffToolFailed(_) => "RG-0095",
+            RgitError::MergeToolFailed(_) => "RG-0096",
+            RgitError::GpgToolFailed => "RG-0097",
+            RgitError::Git2Error(_) => "RG-0098",
+            RgitError::JsonError(_) => "RG-0099",
+            RgitError::TomlError(_) => "RG-0100",
+            RgitError::RegexError(_) => "RG-0101",
+            RgitError::Utf8Error(_) => "RG-0102",
+            RgitError::ChronoError(_) => "RG-0103",
         }
     }
 
+    /// The catalog entry for this error
+    pub fn explanation(&self) -> &'static ErrorExplanation {
+        error_catalog::lookup(self.code()).expect("every error code has a catalog entry")
+    }
+
+    /// Get suggested recovery actions for this error
+    pub fn recovery_suggestions(&self) -> Vec<&'static str> {
+        self.explanation().fixes.to_vec()
+    }
+
     /// Get the error category for grouping similar errors
     pub fn category(&self) -> ErrorCategory {
         match self {
@@ -465,7 +565,9 @@ impl RgitError {
             | RgitError::CannotAmendInitialCommit
             | RgitError::CommitFailed(_)
             | RgitError::InvalidCommit(_)
-            | RgitError::UserIdentityNotConfigured => ErrorCategory::Commit,
+            | RgitError::UserIdentityNotConfigured
+            | RgitError::SecretsDetected(_)
+            | RgitError::StagedFilesTooLarge { .. } => ErrorCategory::Commit,
             
             RgitError::BranchNotFound(_)
             | RgitError::BranchAlreadyExists(_)
@@ -507,12 +609,21 @@ impl RgitError {
             RgitError::ConfigurationError(_)
             | RgitError::InvalidConfigValue { .. }
             | RgitError::ConfigFileNotFound(_)
-            | RgitError::ConfigPermissionDenied => ErrorCategory::Configuration,
+            | RgitError::ConfigPermissionDenied
+            | RgitError::UnknownConfigKey(_) => ErrorCategory::Configuration,
             
             _ => ErrorCategory::Other,
         }
     }
 
+    /// Process exit code: 2 when a health check found errors, 1 otherwise
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RgitError::HealthCheckFailed { errors, .. } if *errors > 0 => 2,
+            _ => 1,
+        }
+    }
+
     /// Check if this error should trigger a help message
     pub fn show_help(&self) -> bool {
         match self {
@@ -623,6 +734,13 @@ mod tests {
         assert!(!RgitError::RepositoryCorrupted.is_recoverable());
     }
 
+    #[test]
+    fn test_exit_codes() {
+        assert_eq!(RgitError::HealthCheckFailed { errors: 1, warnings: 3 }.exit_code(), 2);
+        assert_eq!(RgitError::HealthCheckFailed { errors: 0, warnings: 3 }.exit_code(), 1);
+        assert_eq!(RgitError::NotInRepository.exit_code(), 1);
+    }
+
     #[test]
     fn test_recovery_suggestions() {
         let suggestions = RgitError::NotInRepository.recovery_suggestions();
@@ -630,6 +748,14 @@ mod tests {
         assert!(suggestions[0].contains("repository"));
     }
 
+    #[test]
+    fn test_codes() {
+        assert_eq!(RgitError::NotInRepository.code(), "RG-0001");
+        assert_eq!(RgitError::SshKeyError.code(), "RG-0042");
+        assert_eq!(RgitError::ChronoError(chrono::NaiveDate::parse_from_str("x", "%Y").unwrap_err()).code(), "RG-0103");
+        assert_eq!(RgitError::MergeConflict(vec![]).explanation().title, "Merge conflict");
+    }
+
     #[test]
     fn test_error_category_properties() {
         let category = ErrorCategory::Repository;
@@ -0,0 +1,1362 @@
+//! The error catalog behind `rgit explain`.
+//!
+//! Every [`RgitError`](crate::error::RgitError) variant has a stable code,
+//! shown next to the error, and an entry here with a longer explanation,
+//! common causes and fixes. Entries are indexed by code, so codes are never
+//! renumbered or reused: a new variant takes the next free code and gets its
+//! entry appended at the end.
+
+/// What `rgit explain <code>` prints for one error
+#[derive(Debug)]
+pub struct ErrorExplanation {
+    pub code: &'static str,
+    pub title: &'static str,
+    pub explanation: &'static str,
+    pub causes: &'static [&'static str],
+    pub fixes: &'static [&'static str],
+}
+
+/// Look up a code. `RG-0042`, `rg42` and `42` all find the same entry.
+pub fn lookup(code: &str) -> Option<&'static ErrorExplanation> {
+    let code = code.trim();
+    let digits = match code.get(..2) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("rg") => code[2..].trim_start_matches('-'),
+        _ => code,
+    };
+    let number: usize = digits.parse().ok()?;
+    CATALOG.get(number.checked_sub(1)?)
+}
+
+pub const CATALOG: &[ErrorExplanation] = &[
+    // =========================================================================
+    // Repository Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0001",
+        title: "Not in a git repository",
+        explanation: "The command works on a repository, but neither the current directory nor any of its parents contains one.",
+        causes: &[
+            "The shell is in a directory outside the project",
+            "The project was never initialised, or its .git directory was removed",
+            "GIT_DIR points somewhere that isn't a repository",
+        ],
+        fixes: &[
+            "Navigate to a git repository directory",
+            "Run 'rgit init' to create a new repository",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0002",
+        title: "Repository is not initialized",
+        explanation: "A repository was found but it has no usable state yet, so the command has nothing to work on.",
+        causes: &[
+            "An interrupted 'rgit init' or clone left a partial .git directory",
+        ],
+        fixes: &[
+            "Run 'rgit init' again in the directory",
+            "Clone the repository afresh if it came from a remote",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0003",
+        title: "Repository path does not exist",
+        explanation: "The path given for a repository doesn't exist or doesn't contain one.",
+        causes: &[
+            "A typo in the path",
+            "The repository was moved or deleted",
+        ],
+        fixes: &[
+            "Check the path and try again",
+            "Run 'rgit init <path>' to create a repository there",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0004",
+        title: "Repository is corrupted or damaged",
+        explanation: "git could not read the repository's internal data. Objects, refs or the index may be missing or truncated.",
+        causes: &[
+            "A disk or file-system failure",
+            "A git process killed part-way through a write",
+            "Files inside .git edited or removed by hand or by a sync tool",
+        ],
+        fixes: &[
+            "Run 'rgit fsck' to find what is damaged",
+            "Run 'rgit fsck --repair' to fix what can be fixed",
+            "Recover from a backup with 'rgit backup recover', or clone again",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0005",
+        title: "Repository is in an invalid state",
+        explanation: "The repository is in a state the command can't start from, such as an unfinished merge or rebase.",
+        causes: &[
+            "A merge, rebase, cherry-pick or revert is still in progress",
+            "HEAD points at something unexpected",
+        ],
+        fixes: &[
+            "Run 'rgit status' to see what is in progress",
+            "Finish or abort the operation, e.g. 'rgit rebase --abort'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0006",
+        title: "Directory is not empty",
+        explanation: "The target directory already has files in it, and rgit won't mix a new repository into them.",
+        causes: &[
+            "Cloning or initialising into a directory that is already in use",
+        ],
+        fixes: &[
+            "Choose a different or new directory",
+            "Move the existing files out of the way first",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0007",
+        title: "Clone failed",
+        explanation: "The repository could not be cloned. The message after the code says which step failed.",
+        causes: &[
+            "A wrong URL or a repository you don't have access to",
+            "Network or authentication problems",
+            "Not enough disk space for the checkout",
+        ],
+        fixes: &[
+            "Check the URL with 'rgit ls-remote <url>'",
+            "Use 'rgit doctor' to verify credentials and connectivity",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0008",
+        title: "Uncommitted changes",
+        explanation: "The working tree has changes that the operation could overwrite, so it stopped before touching anything.",
+        causes: &[
+            "Pulling or syncing with local edits that aren't committed",
+        ],
+        fixes: &[
+            "Commit your changes: 'rgit commit'",
+            "Stash your changes: 'rgit stash save'",
+            "Let rgit stash and reapply them: 'rgit pull --autostash'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0009",
+        title: "No upstream branch configured",
+        explanation: "The current branch doesn't track a remote branch, so there is nothing to pull from or compare with.",
+        causes: &[
+            "The branch was created locally and never pushed",
+            "The upstream was removed or renamed on the remote",
+        ],
+        fixes: &[
+            "Push and set the upstream: 'rgit push --set-upstream origin <branch>'",
+            "Track an existing remote branch: 'rgit branch --set-upstream-to origin/<branch>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0010",
+        title: "Fast-forward merge is not possible",
+        explanation: "A fast-forward was required, but the branches have diverged: each has commits the other lacks.",
+        causes: &[
+            "New commits were made both locally and on the remote",
+            "pull.ff or merge --ff-only is set",
+        ],
+        fixes: &[
+            "Rebase onto the other branch: 'rgit pull --rebase'",
+            "Merge instead of fast-forwarding: 'rgit merge <branch>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0011",
+        title: "Merge is not possible",
+        explanation: "The branches could not be merged, for example because they share no history.",
+        causes: &[
+            "Branches with unrelated histories",
+            "A merge that is already in progress",
+        ],
+        fixes: &[
+            "Check the branches with 'rgit log --graph'",
+            "Finish or abort the current merge first",
+        ],
+    },
+    // =========================================================================
+    // File and Index Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0012",
+        title: "File not found",
+        explanation: "A path given to the command doesn't exist in the working tree.",
+        causes: &[
+            "A typo in the path",
+            "The path is relative to a different directory than the current one",
+        ],
+        fixes: &[
+            "Check the path with 'rgit status'",
+            "Use a path relative to the current directory",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0013",
+        title: "File is ignored",
+        explanation: "The file matches a .gitignore rule, so it isn't added unless you insist.",
+        causes: &[
+            "A pattern in .gitignore, .git/info/exclude or the global excludes file",
+        ],
+        fixes: &[
+            "Add it anyway: 'rgit add --force <file>'",
+            "Find the rule with 'git check-ignore -v <file>' and adjust it",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0014",
+        title: "Index is locked",
+        explanation: "The index lock file .git/index.lock exists, which means another git process is writing the index, or one crashed while doing so.",
+        causes: &[
+            "Another git command or an editor integration is running",
+            "A git process was killed and left the lock behind",
+        ],
+        fixes: &[
+            "Wait for the other git process to finish",
+            "If none is running, delete .git/index.lock",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0015",
+        title: "Index is corrupted",
+        explanation: "The index (the staging area) can't be read.",
+        causes: &[
+            "A crash or full disk while the index was written",
+        ],
+        fixes: &[
+            "Rebuild it: delete .git/index and run 'rgit reset'",
+            "Run 'rgit fsck' to check the rest of the repository",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0016",
+        title: "Cannot add empty directory",
+        explanation: "git tracks files, not directories, so an empty directory has nothing to add.",
+        causes: &[
+            "Adding a directory with no files in it",
+        ],
+        fixes: &[
+            "Put a placeholder file such as .gitkeep in the directory",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0017",
+        title: "Permission denied",
+        explanation: "The operating system refused access to a file the command needs.",
+        causes: &[
+            "Files owned by another user, often after running git with sudo",
+            "A file locked by another program",
+        ],
+        fixes: &[
+            "Check the file's owner and permissions",
+            "Close programs that hold the file open",
+        ],
+    },
+    // =========================================================================
+    // Commit Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0018",
+        title: "Commit message cannot be empty",
+        explanation: "A commit needs a message, and the one given was empty or only comments.",
+        causes: &[
+            "The editor was closed without writing a message",
+            "-m was given an empty string",
+        ],
+        fixes: &[
+            "Provide a meaningful commit message",
+            "Use 'rgit commit -m \"your message\"'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0019",
+        title: "Nothing to commit",
+        explanation: "The index matches HEAD, so a commit would record no changes.",
+        causes: &[
+            "Changes were made but not staged",
+            "Everything is already committed",
+        ],
+        fixes: &[
+            "Stage changes first: 'rgit add <files>'",
+            "See what changed with 'rgit status'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0020",
+        title: "Cannot amend initial commit",
+        explanation: "There is no commit yet, so there is nothing to amend.",
+        causes: &[
+            "Running 'rgit commit --amend' in a new repository",
+        ],
+        fixes: &[
+            "Make the first commit without --amend",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0021",
+        title: "Commit failed",
+        explanation: "The commit could not be created. The message after the code says why.",
+        causes: &[
+            "A pre-commit or commit-msg hook rejected the commit",
+            "Signing the commit failed",
+        ],
+        fixes: &[
+            "Fix what the hook reports and commit again",
+            "Check signing with 'rgit whoami'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0022",
+        title: "Invalid commit reference",
+        explanation: "The revision given doesn't name a commit.",
+        causes: &[
+            "A typo in a hash, branch or tag name",
+            "The commit only exists on a remote that hasn't been fetched",
+        ],
+        fixes: &[
+            "Find the commit with 'rgit log --oneline'",
+            "Fetch first: 'rgit fetch'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0023",
+        title: "User identity not configured",
+        explanation: "Commits record an author name and email, and neither git nor rgit configuration provides them.",
+        causes: &[
+            "A fresh machine or user account",
+            "user.name or user.email unset in this repository's config",
+        ],
+        fixes: &[
+            "Set your name: git config user.name \"Your Name\"",
+            "Set your email: git config user.email \"your@email.com\"",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0024",
+        title: "Staged changes contain possible secrets",
+        explanation: "The secret scanner found what look like credentials, such as API tokens or private keys, in staged changes. Once pushed they are hard to take back.",
+        causes: &[
+            "A token or key pasted into a source or config file",
+            "A .env or credentials file staged by accident",
+        ],
+        fixes: &[
+            "Remove the credentials and re-stage the files with 'rgit add'",
+            "Add 'rgit:allow-secret' to lines that are not secrets",
+            "Use 'rgit commit --allow-secrets' to commit anyway",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0025",
+        title: "Staged files too large",
+        explanation: "Some staged files are bigger than commit.max_file_size. Large files bloat every clone for good.",
+        causes: &[
+            "Build output, archives or media added by accident",
+        ],
+        fixes: &[
+            "Track large files with Git LFS: git lfs track \"*.ext\"",
+            "Add them to .gitignore and 'rgit unstage' them",
+            "Use 'rgit commit --allow-large-files' to commit anyway",
+        ],
+    },
+    // =========================================================================
+    // Branch Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0026",
+        title: "Branch not found",
+        explanation: "No local branch has the given name.",
+        causes: &[
+            "A typo in the name",
+            "The branch only exists on a remote",
+        ],
+        fixes: &[
+            "List branches with 'rgit branch --remotes' to include remote ones",
+            "Fetch and switch to the remote branch: 'rgit switch <branch>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0027",
+        title: "Branch already exists",
+        explanation: "A branch with that name exists already, and creating it again would move it.",
+        causes: &[
+            "Reusing the name of an existing branch",
+        ],
+        fixes: &[
+            "Pick another name",
+            "Switch to the existing branch: 'rgit switch <branch>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0028",
+        title: "Cannot delete current branch",
+        explanation: "The branch is checked out, and deleting it would leave HEAD pointing nowhere.",
+        causes: &[
+            "Deleting the branch you are on",
+        ],
+        fixes: &[
+            "Switch to another branch first: 'rgit switch main'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0029",
+        title: "Branch has uncommitted changes",
+        explanation: "Switching would overwrite uncommitted changes in the working tree.",
+        causes: &[
+            "Edits on the current branch that aren't committed",
+        ],
+        fixes: &[
+            "Commit your changes: 'rgit commit'",
+            "Stash your changes: 'rgit stash save'",
+            "Discard changes: 'rgit restore .'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0030",
+        title: "Cannot checkout",
+        explanation: "The checkout would overwrite files in the working tree, so nothing was changed.",
+        causes: &[
+            "Local edits to files that differ on the target branch",
+            "Untracked files that the target branch tracks",
+        ],
+        fixes: &[
+            "Commit or stash the changes in the way: 'rgit stash save'",
+            "Discard them while switching: 'rgit switch --discard-changes <branch>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0031",
+        title: "Detached HEAD state",
+        explanation: "HEAD points at a commit rather than a branch, and the command needs a branch.",
+        causes: &[
+            "Checking out a tag or commit hash",
+            "A rebase or bisect in progress",
+        ],
+        fixes: &[
+            "Switch to a branch: 'rgit switch <branch>'",
+            "Create a branch here: 'rgit switch -c <name>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0032",
+        title: "Invalid branch name",
+        explanation: "The name breaks git's ref naming rules.",
+        causes: &[
+            "Spaces, '..', '~', '^', ':' or a trailing '.lock' in the name",
+        ],
+        fixes: &[
+            "Use letters, digits, '-', '_' and '/' only",
+        ],
+    },
+    // =========================================================================
+    // Remote Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0033",
+        title: "No remote configured",
+        explanation: "The repository has no remotes, so there is nowhere to push to or fetch from.",
+        causes: &[
+            "A repository created with 'rgit init' that was never connected",
+        ],
+        fixes: &[
+            "Add a remote: 'rgit remote add origin <url>'",
+            "Clone from a remote repository instead",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0034",
+        title: "Remote not found",
+        explanation: "No remote has the given name.",
+        causes: &[
+            "A typo in the remote name",
+            "The remote was removed or renamed",
+        ],
+        fixes: &[
+            "List remotes with 'rgit remote'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0035",
+        title: "Remote already exists",
+        explanation: "A remote with that name is already configured.",
+        causes: &[
+            "Adding a remote twice",
+        ],
+        fixes: &[
+            "Pick another name, or change the existing remote's URL",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0036",
+        title: "Invalid remote URL",
+        explanation: "The URL isn't one git can use to reach a repository.",
+        causes: &[
+            "A typo, or a web page URL instead of the clone URL",
+        ],
+        fixes: &[
+            "Copy the clone URL (https:// or git@host:path) from the hosting service",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0037",
+        title: "Push rejected",
+        explanation: "The remote refused the push. Usually the remote branch has commits you don't have, or a server-side rule blocked it.",
+        causes: &[
+            "Someone else pushed first",
+            "A protected branch or a failing server hook",
+        ],
+        fixes: &[
+            "Pull and integrate first: 'rgit pull --rebase'",
+            "Push to a new branch and open a pull request",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0038",
+        title: "Pull failed",
+        explanation: "Fetching or integrating the upstream changes failed. The message after the code says which.",
+        causes: &[
+            "Network or authentication problems",
+            "Conflicts while merging or rebasing",
+        ],
+        fixes: &[
+            "Run 'rgit status' to see where it stopped",
+            "Use 'rgit doctor' to check the remote",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0039",
+        title: "Fetch failed",
+        explanation: "Objects or refs could not be downloaded from the remote.",
+        causes: &[
+            "Network or authentication problems",
+            "A refspec that names refs the remote doesn't have",
+        ],
+        fixes: &[
+            "Check the remote with 'rgit ls-remote <remote>'",
+            "Use 'rgit doctor' to check connectivity",
+        ],
+    },
+    // =========================================================================
+    // Authentication and Network Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0040",
+        title: "Authentication failed",
+        explanation: "The remote didn't accept the credentials offered.",
+        causes: &[
+            "An expired token or changed password",
+            "No SSH key loaded in the agent",
+            "No access to the repository",
+        ],
+        fixes: &[
+            "Check your credentials",
+            "Set up SSH keys for authentication",
+            "Use 'rgit doctor' to verify configuration",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0041",
+        title: "Network error",
+        explanation: "The remote couldn't be reached.",
+        causes: &[
+            "No internet connection, or a proxy or firewall in the way",
+            "A wrong host name in the remote URL",
+        ],
+        fixes: &[
+            "Check your internet connection",
+            "Verify the remote repository URL",
+            "Try again later if the remote server is temporarily unavailable",
+            "Raise 'fetch.retries' if the connection keeps dropping",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0042",
+        title: "SSH key not found or invalid",
+        explanation: "No usable SSH key was found for an ssh:// or git@ remote.",
+        causes: &[
+            "No key in ~/.ssh and none loaded in ssh-agent",
+            "A key with a passphrase that the agent doesn't hold",
+        ],
+        fixes: &[
+            "Load your key: 'ssh-add ~/.ssh/id_ed25519'",
+            "Test it: 'ssh -T git@<host>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0043",
+        title: "Certificate verification failed",
+        explanation: "The server's TLS certificate couldn't be verified, so the connection was refused.",
+        causes: &[
+            "A proxy that intercepts TLS with its own certificate",
+            "An outdated system certificate store",
+            "A self-signed certificate on the server",
+        ],
+        fixes: &[
+            "Install your organisation's CA certificate",
+            "Point http.sslCAInfo at the right certificate bundle",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0044",
+        title: "Connection timeout",
+        explanation: "The remote didn't answer in time.",
+        causes: &[
+            "A slow or unreliable network",
+            "A firewall silently dropping the connection",
+        ],
+        fixes: &[
+            "Try again",
+            "Try https instead of ssh, or the other way round",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0045",
+        title: "Remote server unavailable",
+        explanation: "The remote answered but isn't serving requests right now.",
+        causes: &[
+            "An outage or maintenance at the hosting service",
+        ],
+        fixes: &[
+            "Check the service's status page and try again later",
+        ],
+    },
+    // =========================================================================
+    // Merge and Rebase Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0046",
+        title: "Merge conflict",
+        explanation: "Both sides changed the same lines, and git can't decide which change to keep. The files are left with conflict markers.",
+        causes: &[
+            "Overlapping edits on the two branches",
+        ],
+        fixes: &[
+            "Use 'rgit resolve' for interactive conflict resolution",
+            "Edit conflicted files manually and then 'rgit add' them",
+            "Use 'rgit status' to see all conflicts",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0047",
+        title: "Working tree dirty before merge",
+        explanation: "A merge needs a clean working tree so a conflict can't mix with your own edits.",
+        causes: &[
+            "Uncommitted changes when starting a merge",
+        ],
+        fixes: &[
+            "Commit your changes: 'rgit commit'",
+            "Stash your changes: 'rgit stash save'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0048",
+        title: "Merge aborted",
+        explanation: "The merge was stopped and the branch is back where it started.",
+        causes: &[
+            "Declining a confirmation, or 'rgit merge --abort'",
+        ],
+        fixes: &[
+            "Run the merge again when ready",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0049",
+        title: "Rebase failed",
+        explanation: "The rebase could not continue. The message after the code says why.",
+        causes: &[
+            "Uncommitted changes in the working tree",
+            "A commit that couldn't be applied",
+        ],
+        fixes: &[
+            "Run 'rgit status' to see where it stopped",
+            "Give up and go back: 'rgit rebase --abort'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0050",
+        title: "Rebase conflict",
+        explanation: "A commit being replayed conflicts with the new base. The rebase is paused on it.",
+        causes: &[
+            "The new base changed the same lines as the commit",
+        ],
+        fixes: &[
+            "Resolve with 'rgit resolve', then 'rgit rebase --continue'",
+            "Skip the commit: 'rgit rebase --skip'",
+            "Give up and go back: 'rgit rebase --abort'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0051",
+        title: "Nothing to rebase",
+        explanation: "The branch already contains the target, so there are no commits to replay.",
+        causes: &[
+            "The branch is up to date with its base",
+        ],
+        fixes: &[
+            "Nothing needs doing; check with 'rgit log --graph'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0052",
+        title: "Cherry-pick failed",
+        explanation: "The commit could not be applied on top of the current branch.",
+        causes: &[
+            "Conflicts with changes on the current branch",
+            "Picking a merge commit without choosing a parent",
+        ],
+        fixes: &[
+            "Resolve conflicts, then 'rgit cherry-pick --continue'",
+            "Give up: 'rgit cherry-pick --abort'",
+        ],
+    },
+    // =========================================================================
+    // Submodule Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0053",
+        title: "Submodule error",
+        explanation: "An operation on a submodule failed.",
+        causes: &[
+            "A submodule that isn't initialised or can't be fetched",
+        ],
+        fixes: &[
+            "Use 'rgit submodule status' to check submodule health",
+            "Use 'rgit doctor' for diagnostics",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0054",
+        title: "Submodule not found",
+        explanation: "No submodule has the given name or path.",
+        causes: &[
+            "A typo, or a submodule missing from .gitmodules",
+        ],
+        fixes: &[
+            "List submodules with 'rgit submodule status'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0055",
+        title: "Submodule not initialized",
+        explanation: "The submodule is registered but its repository hasn't been cloned yet.",
+        causes: &[
+            "A clone made without --recurse-submodules",
+        ],
+        fixes: &[
+            "Initialise it: 'rgit submodule update --init'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0056",
+        title: "Submodule has uncommitted changes",
+        explanation: "A submodule has changes that the operation would lose.",
+        causes: &[
+            "Edits made inside the submodule's directory",
+        ],
+        fixes: &[
+            "Commit changes in the submodule",
+            "Use 'rgit submodule status' to see all submodule states",
+            "Stash submodule changes if needed",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0057",
+        title: "Submodule URL is invalid",
+        explanation: "The URL in .gitmodules can't be used to reach the submodule's repository.",
+        causes: &[
+            "A typo, or a relative URL that doesn't resolve against the superproject's remote",
+        ],
+        fixes: &[
+            "Fix the URL in .gitmodules, then 'rgit submodule sync'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0058",
+        title: "Submodule operation failed",
+        explanation: "A submodule could not be updated, added or removed.",
+        causes: &[
+            "Network problems while fetching the submodule",
+            "The recorded commit no longer exists upstream",
+        ],
+        fixes: &[
+            "Use 'rgit submodule status' to see which submodule failed",
+            "Use 'rgit doctor' for diagnostics",
+        ],
+    },
+    // =========================================================================
+    // Stash Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0059",
+        title: "No stash entries",
+        explanation: "The stash is empty.",
+        causes: &[
+            "Nothing was stashed, or every entry was popped or dropped",
+        ],
+        fixes: &[
+            "Stash changes with 'rgit stash save'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0060",
+        title: "Stash index out of range",
+        explanation: "There is no stash entry with that number.",
+        causes: &[
+            "Entries are renumbered after a pop or drop",
+        ],
+        fixes: &[
+            "List entries with 'rgit stash list'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0061",
+        title: "Nothing to stash",
+        explanation: "The working tree and index match HEAD, so there is nothing to save.",
+        causes: &[
+            "Only untracked files changed",
+        ],
+        fixes: &[
+            "Include untracked files: 'rgit stash save --include-untracked'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0062",
+        title: "Stash apply failed",
+        explanation: "The stashed changes conflict with the working tree. The stash entry is kept.",
+        causes: &[
+            "The same files changed since stashing",
+        ],
+        fixes: &[
+            "Resolve conflicts, then drop the entry: 'rgit stash drop'",
+            "Apply on a clean working tree instead",
+        ],
+    },
+    // =========================================================================
+    // Tag Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0063",
+        title: "Tag not found",
+        explanation: "No tag has the given name.",
+        causes: &[
+            "A typo, or a tag that hasn't been fetched",
+        ],
+        fixes: &[
+            "List tags with 'rgit tag'",
+            "Fetch tags: 'rgit fetch --tags'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0064",
+        title: "Tag already exists",
+        explanation: "A tag with that name exists already. Moving a published tag confuses everyone who fetched it.",
+        causes: &[
+            "Reusing a release version",
+        ],
+        fixes: &[
+            "Pick a new name",
+            "Replace it deliberately: 'rgit tag delete <name>', then create it again",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0065",
+        title: "Invalid tag name",
+        explanation: "The name breaks git's ref naming rules.",
+        causes: &[
+            "Spaces, '..', '~', '^' or ':' in the name",
+        ],
+        fixes: &[
+            "Use names like v1.2.3",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0066",
+        title: "GPG signing failed",
+        explanation: "The commit or tag could not be signed.",
+        causes: &[
+            "The signing key isn't available or has expired",
+            "gpg-agent can't ask for the passphrase in this terminal",
+        ],
+        fixes: &[
+            "Check the key and signing setup with 'rgit whoami'",
+            "Set GPG_TTY: export GPG_TTY=$(tty)",
+        ],
+    },
+    // =========================================================================
+    // Workspace Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0067",
+        title: "Not in an rgit workspace",
+        explanation: "No .rgit-workspace.toml was found in this directory or its parents.",
+        causes: &[
+            "The workspace was never created, or the shell is outside it",
+        ],
+        fixes: &[
+            "Create a workspace: 'rgit workspace init'",
+            "Run the command from inside a workspace directory",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0068",
+        title: "Repository already in workspace",
+        explanation: "The repository is already registered in the workspace.",
+        causes: &[
+            "Adding the same repository twice",
+        ],
+        fixes: &[
+            "List repositories with 'rgit workspace list'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0069",
+        title: "Repository not in workspace",
+        explanation: "The workspace has no repository by that name.",
+        causes: &[
+            "A typo, or a repository that was never added",
+        ],
+        fixes: &[
+            "List repositories with 'rgit workspace list'",
+        ],
+    },
+    // =========================================================================
+    // Configuration Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0070",
+        title: "Configuration error",
+        explanation: "rgit's configuration couldn't be loaded or used.",
+        causes: &[
+            "A syntax error in config.toml",
+            "Values that contradict each other",
+        ],
+        fixes: &[
+            "Check the file with 'rgit config list'",
+            "Move the file aside to start from the defaults",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0071",
+        title: "Invalid configuration value",
+        explanation: "A setting has a value rgit doesn't accept.",
+        causes: &[
+            "A typo, or a unit rgit doesn't know (sizes look like 5MB)",
+        ],
+        fixes: &[
+            "See what the key expects with 'rgit config list'",
+            "Set it again: 'rgit config set <key> <value>'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0072",
+        title: "Configuration file not found",
+        explanation: "The configuration file given doesn't exist.",
+        causes: &[
+            "A wrong path passed to --config",
+        ],
+        fixes: &[
+            "Check the path, or leave out --config to use the default",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0073",
+        title: "Permission denied reading configuration",
+        explanation: "The configuration file exists but can't be read.",
+        causes: &[
+            "The file is owned by another user",
+        ],
+        fixes: &[
+            "Fix the file's permissions",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0074",
+        title: "Unknown configuration key",
+        explanation: "No setting has that name.",
+        causes: &[
+            "A typo in the key",
+        ],
+        fixes: &[
+            "List the keys with 'rgit config list'",
+        ],
+    },
+    // =========================================================================
+    // Operation Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0075",
+        title: "Operation cancelled",
+        explanation: "The operation was cancelled at a prompt, and nothing was changed.",
+        causes: &[
+            "Answering no to a confirmation",
+        ],
+        fixes: &[
+            "Run the command again when ready",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0076",
+        title: "Operation not supported",
+        explanation: "rgit doesn't support this combination of command and options.",
+        causes: &[
+            "Options that can't be combined",
+            "A feature rgit doesn't implement yet",
+        ],
+        fixes: &[
+            "Check 'rgit <command> --help' for the supported options",
+            "Fall back to plain git for this operation",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0077",
+        title: "Operation failed",
+        explanation: "The operation failed. The message after the code says why.",
+        causes: &[
+            "Depends on the command; see the message",
+        ],
+        fixes: &[
+            "Use 'rgit doctor' for diagnostics",
+            "Run again with --verbose and check 'rgit doctor --logs'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0078",
+        title: "Health check found problems",
+        explanation: "'rgit doctor' found errors or warnings. It exits with 1 for warnings and 2 for errors so CI can gate on it.",
+        causes: &[
+            "Problems listed in the health report",
+        ],
+        fixes: &[
+            "Follow the suggestions in the report",
+            "Let rgit fix what it can when 'rgit doctor' offers",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0079",
+        title: "Interactive operation not available",
+        explanation: "The command needs an answer from you, but there is no terminal to ask, or --yes said not to ask.",
+        causes: &[
+            "Running in CI, a script or a pipe",
+            "--yes or RGIT_NONINTERACTIVE with a command that has no safe default",
+        ],
+        fixes: &[
+            "Pass the answer as an option instead (see 'rgit <command> --help')",
+            "Run the command in a terminal",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0080",
+        title: "Command execution failed",
+        explanation: "An external program rgit runs, like git or a hook, failed or couldn't be started.",
+        causes: &[
+            "The program isn't installed or isn't on PATH",
+            "The program exited with an error",
+        ],
+        fixes: &[
+            "Check that git is installed: 'git --version'",
+            "Run the program by hand to see its output",
+        ],
+    },
+    // =========================================================================
+    // Validation Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0081",
+        title: "Invalid argument",
+        explanation: "An argument isn't valid for this command.",
+        causes: &[
+            "A typo, or a value in the wrong format",
+        ],
+        fixes: &[
+            "Check 'rgit <command> --help'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0082",
+        title: "Invalid path",
+        explanation: "The path can't be used here.",
+        causes: &[
+            "A path with characters git can't store",
+            "A path inside .git",
+        ],
+        fixes: &[
+            "Use a path inside the working tree",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0083",
+        title: "Invalid reference",
+        explanation: "The name doesn't resolve to a branch, tag or commit.",
+        causes: &[
+            "A typo, or a ref that hasn't been fetched",
+        ],
+        fixes: &[
+            "List branches and tags with 'rgit branch --remotes' and 'rgit tag'",
+            "Fetch first: 'rgit fetch'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0084",
+        title: "Invalid object ID",
+        explanation: "The object ID is malformed or names an object the repository doesn't have.",
+        causes: &[
+            "A truncated or mistyped hash",
+            "An ambiguous short hash",
+        ],
+        fixes: &[
+            "Use more characters of the hash",
+            "Find it with 'rgit log --oneline'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0085",
+        title: "Path is outside repository",
+        explanation: "The path points outside the working tree, which git doesn't track.",
+        causes: &[
+            "A '..' path or an absolute path to another directory",
+        ],
+        fixes: &[
+            "Use a path inside the repository",
+        ],
+    },
+    // =========================================================================
+    // I/O and System Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0086",
+        title: "I/O error",
+        explanation: "Reading or writing a file failed.",
+        causes: &[
+            "Missing permissions",
+            "A full disk or a removed directory",
+        ],
+        fixes: &[
+            "Check permissions and free space",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0087",
+        title: "File system error",
+        explanation: "A file-system operation failed. The message after the code says which.",
+        causes: &[
+            "Missing permissions, or a file locked by another program",
+        ],
+        fixes: &[
+            "Check permissions and close programs holding the file",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0088",
+        title: "Disk space insufficient",
+        explanation: "There isn't enough free space for the operation.",
+        causes: &[
+            "A full disk or quota",
+        ],
+        fixes: &[
+            "Free space, or shrink the repository with 'rgit gc'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0089",
+        title: "Temporary file creation failed",
+        explanation: "rgit couldn't create a temporary file.",
+        causes: &[
+            "TMPDIR points at a missing or read-only directory",
+            "A full disk",
+        ],
+        fixes: &[
+            "Check TMPDIR and free space",
+        ],
+    },
+    // =========================================================================
+    // Parse and Format Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0090",
+        title: "Parse error",
+        explanation: "Some input couldn't be parsed.",
+        causes: &[
+            "Input in an unexpected format",
+        ],
+        fixes: &[
+            "Check the format the command expects in 'rgit <command> --help'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0091",
+        title: "Invalid date format",
+        explanation: "The date isn't in a format rgit understands.",
+        causes: &[
+            "An unsupported format",
+        ],
+        fixes: &[
+            "Use 2024-01-31, '2 weeks ago' or 'yesterday'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0092",
+        title: "Invalid time format",
+        explanation: "The time or duration isn't in a format rgit understands.",
+        causes: &[
+            "An unsupported format",
+        ],
+        fixes: &[
+            "Use 14:30 for times and 30m, 2h or 7d for durations",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0093",
+        title: "Encoding error",
+        explanation: "Text couldn't be decoded.",
+        causes: &[
+            "A file or message that isn't UTF-8",
+        ],
+        fixes: &[
+            "Convert the text to UTF-8",
+        ],
+    },
+    // =========================================================================
+    // External Tool Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0094",
+        title: "External editor failed",
+        explanation: "The editor exited with an error or couldn't be started.",
+        causes: &[
+            "EDITOR or core.editor names a program that isn't installed",
+        ],
+        fixes: &[
+            "Set an editor: git config --global core.editor \"nano\"",
+            "Pass the message directly with -m",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0095",
+        title: "Diff tool failed",
+        explanation: "The external diff tool exited with an error or couldn't be started.",
+        causes: &[
+            "The tool isn't installed or isn't on PATH",
+        ],
+        fixes: &[
+            "Check integrations.diff_tool with 'rgit config list'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0096",
+        title: "Merge tool failed",
+        explanation: "The external merge tool exited with an error or couldn't be started.",
+        causes: &[
+            "The tool isn't installed or isn't on PATH",
+        ],
+        fixes: &[
+            "Check integrations.merge_tool with 'rgit config list'",
+            "Resolve with 'rgit resolve' instead",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0097",
+        title: "GPG tool not found or failed",
+        explanation: "gpg couldn't be run to sign or verify.",
+        causes: &[
+            "gpg isn't installed, or gpg.program names the wrong binary",
+        ],
+        fixes: &[
+            "Install gnupg, or set gpg.program",
+            "Check signing with 'rgit whoami'",
+        ],
+    },
+    // =========================================================================
+    // Wrapped External Errors
+    // =========================================================================
+    ErrorExplanation {
+        code: "RG-0098",
+        title: "Git library error",
+        explanation: "libgit2, the library rgit uses to read and write repositories, reported an error. Its message follows the code.",
+        causes: &[
+            "Depends on the message; often a missing object, a lock or an unsupported repository feature",
+        ],
+        fixes: &[
+            "Run 'rgit fsck' to check the repository",
+            "Try the same operation with plain git to compare",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0099",
+        title: "JSON parsing error",
+        explanation: "A JSON file or response couldn't be parsed.",
+        causes: &[
+            "A hand-edited JSON file with a syntax error",
+            "An unexpected response from a hosting service",
+        ],
+        fixes: &[
+            "Check the file named in the message",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0100",
+        title: "TOML parsing error",
+        explanation: "A TOML file, such as config.toml or .rgit-workspace.toml, couldn't be parsed.",
+        causes: &[
+            "A syntax error, often a missing quote or bracket",
+        ],
+        fixes: &[
+            "Fix the line and column given in the message",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0101",
+        title: "Regular expression error",
+        explanation: "A regular expression is invalid.",
+        causes: &[
+            "An unbalanced bracket or parenthesis, or an unescaped special character",
+        ],
+        fixes: &[
+            "Escape special characters with '\\'",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0102",
+        title: "UTF-8 encoding error",
+        explanation: "Bytes that should be UTF-8 text aren't.",
+        causes: &[
+            "A file name or message in another encoding",
+        ],
+        fixes: &[
+            "Convert the text to UTF-8",
+        ],
+    },
+    ErrorExplanation {
+        code: "RG-0103",
+        title: "Date/time error",
+        explanation: "A date or time couldn't be parsed.",
+        causes: &[
+            "An unsupported format",
+        ],
+        fixes: &[
+            "Use 2024-01-31 or 2024-01-31T14:30:00",
+        ],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_match_positions() {
+        for (index, entry) in CATALOG.iter().enumerate() {
+            assert_eq!(entry.code, format!("RG-{:04}", index + 1));
+            assert!(!entry.fixes.is_empty(), "{} has no fixes", entry.code);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("RG-0042").unwrap().code, "RG-0042");
+        assert_eq!(lookup("rg42").unwrap().code, "RG-0042");
+        assert_eq!(lookup("42").unwrap().code, "RG-0042");
+        assert!(lookup("RG-0000").is_none());
+        assert!(lookup("RG-9999").is_none());
+        assert!(lookup("oops").is_none());
+    }
+}
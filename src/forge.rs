@@ -0,0 +1,261 @@
+//! Forge shorthands for clone.
+//!
+//! `gh:owner/repo`, `gl:owner/repo` and `cb:owner/repo` name a repository on
+//! GitHub, GitLab or Codeberg. `gh:owner` names an owner instead, whose
+//! repositories are listed through the forge's API so one can be picked.
+//!
+//! API requests go through `curl` and send a token from the environment when
+//! one is set (`GITHUB_TOKEN`/`GH_TOKEN`, `GITLAB_TOKEN`, `GITEA_TOKEN`), which
+//! also makes private repositories visible.
+
+use anyhow::Result;
+use serde_json::Value;
+use std::process::Command;
+
+use crate::error::RgitError;
+
+/// Repositories fetched per API request
+const PAGE_SIZE: usize = 100;
+
+/// Stop listing after this many pages
+const MAX_PAGES: usize = 5;
+
+/// A code hosting service rgit has a shorthand for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Forge {
+    GitHub,
+    GitLab,
+    Codeberg,
+}
+
+impl Forge {
+    pub const ALL: [Forge; 3] = [Forge::GitHub, Forge::GitLab, Forge::Codeberg];
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Forge::GitHub => "gh",
+            Forge::GitLab => "gl",
+            Forge::Codeberg => "cb",
+        }
+    }
+
+    pub fn host(&self) -> &'static str {
+        match self {
+            Forge::GitHub => "github.com",
+            Forge::GitLab => "gitlab.com",
+            Forge::Codeberg => "codeberg.org",
+        }
+    }
+
+    /// Clone URL for `owner/repo` over "https" or "ssh"
+    pub fn clone_url(&self, owner: &str, repo: &str, protocol: &str) -> String {
+        match protocol {
+            "ssh" => format!("git@{}:{}/{}.git", self.host(), owner, repo),
+            _ => format!("https://{}/{}/{}.git", self.host(), owner, repo),
+        }
+    }
+
+    /// API endpoints listing an owner's repositories, tried in order until one answers
+    fn list_endpoints(&self, owner: &str) -> Vec<String> {
+        match self {
+            Forge::GitHub => vec![
+                format!("https://api.github.com/users/{}/repos?sort=updated", owner),
+            ],
+            // Groups and users live in different namespaces; nested groups are path-encoded
+            Forge::GitLab => {
+                let id = owner.replace('/', "%2F");
+                vec![
+                    format!("https://gitlab.com/api/v4/groups/{}/projects?include_subgroups=true&order_by=last_activity_at", id),
+                    format!("https://gitlab.com/api/v4/users/{}/projects?order_by=last_activity_at", id),
+                ]
+            }
+            Forge::Codeberg => vec![
+                format!("https://codeberg.org/api/v1/users/{}/repos", owner),
+            ],
+        }
+    }
+
+    /// Authorization header built from the forge's token variable
+    fn auth_header(&self) -> Option<String> {
+        let token = |names: &[&str]| names.iter().find_map(|name| std::env::var(name).ok().filter(|t| !t.is_empty()));
+        match self {
+            Forge::GitHub => token(&["GITHUB_TOKEN", "GH_TOKEN"]).map(|t| format!("Authorization: Bearer {}", t)),
+            Forge::GitLab => token(&["GITLAB_TOKEN"]).map(|t| format!("PRIVATE-TOKEN: {}", t)),
+            Forge::Codeberg => token(&["GITEA_TOKEN"]).map(|t| format!("Authorization: token {}", t)),
+        }
+    }
+
+    /// Turn one page of an API response into repositories
+    fn parse_repos(&self, body: &str) -> Result<Vec<ForgeRepo>> {
+        let value: Value = serde_json::from_str(body)
+            .map_err(|e| RgitError::ParseError(format!("{} API response: {}", self.host(), e)))?;
+        let Some(items) = value.as_array() else {
+            return Err(RgitError::ParseError(format!("{} API response is not a list", self.host())).into());
+        };
+
+        let (name_key, full_name_key, https_key, ssh_key) = match self {
+            Forge::GitLab => ("path", "path_with_namespace", "http_url_to_repo", "ssh_url_to_repo"),
+            Forge::GitHub | Forge::Codeberg => ("name", "full_name", "clone_url", "ssh_url"),
+        };
+        let text = |item: &Value, key: &str| item.get(key).and_then(Value::as_str).map(String::from);
+
+        Ok(items.iter()
+            .filter_map(|item| Some(ForgeRepo {
+                name: text(item, name_key)?,
+                full_name: text(item, full_name_key)?,
+                description: text(item, "description").filter(|d| !d.trim().is_empty()),
+                https_url: text(item, https_key)?,
+                ssh_url: text(item, ssh_key)?,
+                archived: item.get("archived").and_then(Value::as_bool).unwrap_or(false),
+            }))
+            .collect())
+    }
+}
+
+/// What a shorthand like `gh:owner` or `gh:owner/repo` names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shorthand {
+    pub forge: Forge,
+    pub owner: String,
+    /// None when only the owner was given
+    pub repo: Option<String>,
+}
+
+impl Shorthand {
+    /// Parse `<prefix>:owner[/repo]`; anything else isn't a shorthand
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (prefix, path) = spec.split_once(':')?;
+        let forge = Forge::ALL.into_iter().find(|forge| forge.prefix() == prefix)?;
+        let path = path.trim_matches('/').trim_end_matches(".git");
+        if path.is_empty() || path.split('/').any(|part| part.is_empty() || part.starts_with('.')) {
+            return None;
+        }
+
+        // GitLab owners can be nested groups, so the repository is always the last part
+        let (owner, repo) = match path.rsplit_once('/') {
+            Some((owner, repo)) if forge == Forge::GitLab || !owner.contains('/') => (owner, Some(repo.to_string())),
+            Some(_) => return None,
+            None => (path, None),
+        };
+        Some(Self { forge, owner: owner.to_string(), repo })
+    }
+}
+
+/// A repository as a forge's API lists it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRepo {
+    pub name: String,
+    /// `owner/name`
+    pub full_name: String,
+    pub description: Option<String>,
+    pub https_url: String,
+    pub ssh_url: String,
+    pub archived: bool,
+}
+
+impl ForgeRepo {
+    pub fn clone_url(&self, protocol: &str) -> &str {
+        match protocol {
+            "ssh" => &self.ssh_url,
+            _ => &self.https_url,
+        }
+    }
+}
+
+/// Every repository `owner` has on `forge`, most recently updated first
+pub fn list_repos(forge: Forge, owner: &str) -> Result<Vec<ForgeRepo>> {
+    let mut last_error = None;
+    for endpoint in forge.list_endpoints(owner) {
+        match list_pages(forge, &endpoint) {
+            Ok(repos) => return Ok(repos),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| RgitError::NetworkError(format!("no API for {}", forge.host())).into()))
+}
+
+/// Follow an endpoint's pages until one comes back short
+fn list_pages(forge: Forge, endpoint: &str) -> Result<Vec<ForgeRepo>> {
+    let separator = if endpoint.contains('?') { '&' } else { '?' };
+    let mut repos = Vec::new();
+    for page in 1..=MAX_PAGES {
+        // GitHub and GitLab read per_page, Gitea reads limit
+        let url = format!("{}{}per_page={}&limit={}&page={}", endpoint, separator, PAGE_SIZE, PAGE_SIZE, page);
+        let batch = forge.parse_repos(&get(forge, &url)?)?;
+        let done = batch.len() < PAGE_SIZE;
+        repos.extend(batch);
+        if done {
+            break;
+        }
+    }
+    Ok(repos)
+}
+
+fn get(forge: Forge, url: &str) -> Result<String> {
+    let mut command = Command::new("curl");
+    command.args(["-fsSL", "--max-time", "20", "-H", "Accept: application/json"]);
+    if let Some(header) = forge.auth_header() {
+        command.args(["-H", &header]);
+    }
+    let output = command.arg(url).output()
+        .map_err(|e| RgitError::CommandExecutionFailed(format!("curl: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(RgitError::NetworkError(format!("{}: {}", forge.host(), stderr.trim())).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Fill a `clone.destination` template's `{host}`, `{owner}` and `{repo}`
+pub fn destination(template: &str, host: &str, owner: &str, repo: &str) -> String {
+    template
+        .replace("{host}", host)
+        .replace("{owner}", owner)
+        .replace("{repo}", repo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shorthand() {
+        assert_eq!(Shorthand::parse("gh:myorg"),
+                   Some(Shorthand { forge: Forge::GitHub, owner: "myorg".into(), repo: None }));
+        assert_eq!(Shorthand::parse("cb:me/tool.git"),
+                   Some(Shorthand { forge: Forge::Codeberg, owner: "me".into(), repo: Some("tool".into()) }));
+        assert_eq!(Shorthand::parse("gl:group/sub/project"),
+                   Some(Shorthand { forge: Forge::GitLab, owner: "group/sub".into(), repo: Some("project".into()) }));
+        assert_eq!(Shorthand::parse("gh:a/b/c"), None);
+        assert_eq!(Shorthand::parse("gh:"), None);
+        assert_eq!(Shorthand::parse("git@github.com:user/repo.git"), None);
+        assert_eq!(Shorthand::parse("https://github.com/user/repo"), None);
+    }
+
+    #[test]
+    fn test_parse_api_responses() {
+        let github = r#"[{"name": "tool", "full_name": "me/tool", "description": "A tool",
+            "clone_url": "https://github.com/me/tool.git", "ssh_url": "git@github.com:me/tool.git", "archived": true},
+            {"name": "broken"}]"#;
+        let repos = Forge::GitHub.parse_repos(github).unwrap();
+        assert_eq!(repos.len(), 1);
+        assert!(repos[0].archived);
+        assert_eq!(repos[0].clone_url("ssh"), "git@github.com:me/tool.git");
+        assert_eq!(repos[0].clone_url("https"), "https://github.com/me/tool.git");
+
+        let gitlab = r#"[{"path": "project", "path_with_namespace": "group/project", "description": "",
+            "http_url_to_repo": "https://gitlab.com/group/project.git", "ssh_url_to_repo": "git@gitlab.com:group/project.git"}]"#;
+        let repos = Forge::GitLab.parse_repos(gitlab).unwrap();
+        assert_eq!(repos[0].full_name, "group/project");
+        assert_eq!(repos[0].description, None);
+
+        assert!(Forge::GitHub.parse_repos(r#"{"message": "Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_clone_urls_and_destination() {
+        assert_eq!(Forge::GitHub.clone_url("me", "tool", "ssh"), "git@github.com:me/tool.git");
+        assert_eq!(Forge::Codeberg.clone_url("me", "tool", "https"), "https://codeberg.org/me/tool.git");
+        assert_eq!(destination("~/src/{host}/{owner}/{repo}", "github.com", "me", "tool"), "~/src/github.com/me/tool");
+    }
+}
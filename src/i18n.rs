@@ -0,0 +1,221 @@
+//! Translated user-facing messages.
+//!
+//! Messages live in Fluent files under `locales/`, one per language, and
+//! are looked up by key with [`t!`](crate::t). The locale comes from
+//! `user.language` (`RGIT_LANG`), or with `auto` from `LC_ALL`,
+//! `LC_MESSAGES` and `LANG`. A message missing from the chosen locale falls
+//! back to English.
+//!
+//! Scope: the catalog covers startup messages, the error heading and hints
+//! (not the error text itself), the dry-run banner, the timings and
+//! confirmation helpers, `status` and `explain`. Every other command prints
+//! English whatever the locale. A command only moves into the catalog as a
+//! whole, so no command mixes languages within its output.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::config::Config;
+
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Bundled catalogs; English must come first and have every message
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("es", include_str!("../locales/es.ftl")),
+];
+
+static LOCALE: OnceLock<&'static str> = OnceLock::new();
+static BUNDLES: OnceLock<HashMap<&'static str, FluentBundle<FluentResource>>> = OnceLock::new();
+
+/// Pick the locale for this process from configuration and the environment
+pub fn init(config: &Config) {
+    let _ = LOCALE.set(resolve(&config.user.language, |name| std::env::var(name).ok()));
+}
+
+/// The locale messages are shown in
+pub fn locale() -> &'static str {
+    LOCALE.get().copied().unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Locales with a bundled catalog
+pub fn available_locales() -> Vec<&'static str> {
+    CATALOGS.iter().map(|(locale, _)| *locale).collect()
+}
+
+/// The bundled locale that best matches `language`, a tag like `es`,
+/// `es-MX` or `es_MX.UTF-8`, or `auto` to use the environment
+fn resolve(language: &str, env: impl Fn(&str) -> Option<String>) -> &'static str {
+    let requested = if language.eq_ignore_ascii_case("auto") {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter()
+            .filter_map(|name| env(name))
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+    } else {
+        language.to_string()
+    };
+    let tag = requested.split(['.', '@']).next().unwrap_or("").replace('_', "-").to_lowercase();
+    let primary = tag.split('-').next().unwrap_or("");
+    CATALOGS.iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| locale.eq_ignore_ascii_case(&tag))
+        .or_else(|| CATALOGS.iter().map(|(locale, _)| *locale).find(|locale| *locale == primary))
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+fn bundles() -> &'static HashMap<&'static str, FluentBundle<FluentResource>> {
+    BUNDLES.get_or_init(|| CATALOGS.iter().map(|(locale, source)| (*locale, bundle(locale, source))).collect())
+}
+
+/// A bundle with the messages in `source`. Entries with syntax errors are
+/// skipped; the catalog test keeps the bundled files free of them.
+fn bundle(locale: &str, source: &str) -> FluentBundle<FluentResource> {
+    let language: LanguageIdentifier = locale.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    // Bidi isolation marks would end up in terminals, logs and pipes
+    bundle.set_use_isolating(false);
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, _)| resource);
+    bundle.add_resource_overriding(resource);
+    bundle
+}
+
+/// Format the message `key` in the current locale. Prefer the [`t!`](crate::t)
+/// macro, which builds `args` from `name = value` pairs.
+pub fn message(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    format_in(locale(), key, args)
+}
+
+fn format_in(locale: &str, key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let bundles = bundles();
+    [locale, DEFAULT_LOCALE].iter()
+        .filter_map(|locale| bundles.get(locale))
+        .find_map(|bundle| format(bundle, key, args))
+        // Show the key rather than nothing; the catalog test catches this
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// The message `key` from `bundle`, if it has one
+fn format(bundle: &FluentBundle<FluentResource>, key: &str, args: &[(&str, &dyn Display)]) -> Option<String> {
+    let pattern = bundle.get_message(key)?.value()?;
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, argument(value.to_string()));
+    }
+    let mut errors = Vec::new();
+    Some(bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned())
+}
+
+/// Counts become numbers so selectors can pick plural forms; anything else,
+/// including text that only looks numeric like `007`, stays as written
+fn argument(value: String) -> FluentValue<'static> {
+    match value.parse::<i64>() {
+        Ok(number) if number.to_string() == value && number.unsigned_abs() < 1 << 53 => FluentValue::from(number),
+        _ => FluentValue::from(value),
+    }
+}
+
+/// Look up a translated message: `t!("key")` or `t!("key", name = value, ...)`
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::message($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message($key, &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn test_resolve_locale() {
+        let env = |lang: &'static str| move |name: &str| (name == "LANG").then(|| lang.to_string());
+        assert_eq!(resolve("es", env("")), "es");
+        assert_eq!(resolve("es-MX", env("")), "es");
+        assert_eq!(resolve("auto", env("es_ES.UTF-8")), "es");
+        assert_eq!(resolve("auto", env("C.UTF-8")), "en");
+        assert_eq!(resolve("auto", env("")), "en");
+        assert_eq!(resolve("xx", env("es_ES.UTF-8")), "en");
+    }
+
+    #[test]
+    fn test_format_with_variables_and_plurals() {
+        let source = "\
+greeting = Hello, { $name }!
+changes = { $count ->
+        [0] no changes
+        [one] { $count } change
+       *[other] { $count } changes
+    } so far
+brace = { \"{\" }literal{ \"}\" }
+";
+        let bundle = bundle("en", source);
+        assert_eq!(format(&bundle, "greeting", &[("name", &"Ana")]).unwrap(), "Hello, Ana!");
+        assert_eq!(format(&bundle, "brace", &[]).unwrap(), "{literal}");
+        assert_eq!(format(&bundle, "missing", &[]), None);
+
+        for (count, expected) in [(0, "no changes so far"), (1, "1 change so far"), (5, "5 changes so far")] {
+            assert_eq!(format(&bundle, "changes", &[("count", &count)]).unwrap(), expected);
+        }
+        assert_eq!(format(&bundle, "greeting", &[("name", &"007")]).unwrap(), "Hello, 007!");
+    }
+
+    #[test]
+    fn test_fallback_to_english() {
+        assert_eq!(format_in("es", "status-working-tree-clean", &[]), "Directorio de trabajo limpio");
+        assert_eq!(format_in("xx", "status-working-tree-clean", &[]), "Working tree clean");
+        assert_eq!(format_in("en", "no-such-message", &[]), "no-such-message");
+    }
+
+    /// Every key used in the code has an English message, and translations
+    /// only have keys English has
+    #[test]
+    fn test_catalogs_cover_the_code() {
+        let english = &bundles()[DEFAULT_LOCALE];
+        for (locale, source) in CATALOGS {
+            if let Err((_, errors)) = FluentResource::try_new(source.to_string()) {
+                panic!("{}.ftl doesn't parse: {:?}", locale, errors);
+            }
+            for key in source.lines().filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase())) {
+                let key = key.split('=').next().unwrap().trim();
+                assert!(english.has_message(key), "{} has '{}', which English lacks", locale, key);
+            }
+        }
+
+        fn visit(dir: &Path, keys: &mut Vec<String>) {
+            for entry in fs::read_dir(dir).unwrap() {
+                let path = entry.unwrap().path();
+                if path.is_dir() {
+                    visit(&path, keys);
+                } else if path.extension().is_some_and(|ext| ext == "rs") {
+                    let source = fs::read_to_string(&path).unwrap();
+                    for (index, _) in source.match_indices("t!(\"") {
+                        let line_start = source[..index].rfind('\n').map_or(0, |newline| newline + 1);
+                        let is_comment = source[line_start..index].trim_start().starts_with("//");
+                        if is_comment || source[..index].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                            continue;
+                        }
+                        let start = index + 4;
+                        let end = start + source[start..].find('"').unwrap();
+                        keys.push(source[start..end].to_string());
+                    }
+                }
+            }
+        }
+        let mut keys = Vec::new();
+        visit(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut keys);
+        assert!(!keys.is_empty());
+        for key in &keys {
+            assert!(english.has_message(key), "'{}' is used but has no English message", key);
+        }
+    }
+}
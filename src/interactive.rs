@@ -1,19 +1,32 @@
 use anyhow::Result;
 use colored::*;
-use colored::{Color, Colorize}; // Add this line for color constants
 use dialoguer::{
     theme::ColorfulTheme, Confirm, Editor, FuzzySelect, Input, MultiSelect, Password, Select,
 };
 use std::collections::HashMap;
+use git2::{PackBuilderStage, Progress, RemoteCallbacks, Repository};
+use std::cell::RefCell;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
+use crate::config::Config;
+use crate::conflict::{
+    diff3_text, has_conflict_markers, mark_resolved, run_mergetool,
+    stage_resolved, take_side, ConflictHunk, ConflictSides, ConflictedText, HunkResolution,
+    Segment, Side,
+};
 use crate::error::RgitError;
+use crate::output;
+use crate::theme::{console_style, Element, Themed};
+use crate::tools::Tool;
+use crate::utils::{truncate_by_width, word_wrap};
 
 /// Builder for creating interactive prompts with consistent styling
 pub struct InteractivePrompt {
     message: String,
     options: Vec<String>,
     default: Option<usize>,
+    initial_text: Option<String>,
     theme: ColorfulTheme,
     allow_empty: bool,
     multiselect: bool,
@@ -27,6 +40,7 @@ impl InteractivePrompt {
             message: String::new(),
             options: Vec::new(),
             default: None,
+            initial_text: None,
             theme: Self::create_theme(),
             allow_empty: false,
             multiselect: false,
@@ -36,7 +50,7 @@ impl InteractivePrompt {
 
     /// Set the prompt message
     pub fn with_message(mut self, message: impl Into<String>) -> Self {
-        self.message = message.into();
+        self.message = crate::output::plain(&message.into()).into_owned();
         self
     }
 
@@ -52,6 +66,12 @@ impl InteractivePrompt {
         self
     }
 
+    /// Pre-fill a text input
+    pub fn with_initial_text(mut self, text: impl Into<String>) -> Self {
+        self.initial_text = Some(text.into());
+        self
+    }
+
     /// Allow empty input
     pub fn allow_empty(mut self) -> Self {
         self.allow_empty = true;
@@ -125,10 +145,40 @@ impl InteractivePrompt {
         let mut input = Input::with_theme(&self.theme);
         input = input.with_prompt(&self.message);
         input = input.allow_empty(self.allow_empty);
+        if let Some(text) = &self.initial_text {
+            input = input.with_initial_text(text);
+        }
 
         Ok(input.interact_text()?)
     }
 
+    /// Wait for one keystroke out of `keys`, each listed with what it does.
+    /// Esc picks the last key, which should be the way out.
+    pub fn keypress(&self, keys: &[(char, &str)]) -> Result<char> {
+        let term = console::Term::stderr();
+        let choices: Vec<String> = keys.iter()
+            .map(|(key, label)| format!("[{}] {}", key, label))
+            .collect();
+        term.write_line(&format!("{} {}", self.message.bold(), choices.join("  ").dimmed()))?;
+
+        loop {
+            match term.read_key()? {
+                console::Key::Char(pressed) => {
+                    let pressed = pressed.to_ascii_lowercase();
+                    if keys.iter().any(|(key, _)| *key == pressed) {
+                        return Ok(pressed);
+                    }
+                }
+                console::Key::Escape => {
+                    if let Some((key, _)) = keys.last() {
+                        return Ok(*key);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
     /// Show a confirmation prompt
     pub fn confirm(&self) -> Result<bool> {
         let confirm = Confirm::with_theme(&self.theme)
@@ -157,32 +207,38 @@ impl InteractivePrompt {
 
     /// Create the custom theme
     fn create_theme() -> ColorfulTheme {
+        // Glyphs screen readers read out oddly become plain ASCII
+        let (prompt_suffix, success, suffix, error, active, checked, unchecked) = if crate::output::is_accessible() {
+            (":", "ok", "-", "error", ">", "[x]", "[ ]")
+        } else {
+            ("›", "✓", "·", "✗", "❯", "✓", "✗")
+        };
         ColorfulTheme {
-            defaults_style: console::Style::new().for_stderr().cyan(),
+            defaults_style: console_style(Element::Hint),
             prompt_style: console::Style::new().for_stderr().bold(),
-            prompt_prefix: console::style("?".to_string()).for_stderr().yellow(),
-            prompt_suffix: console::style("›".to_string())
+            prompt_prefix: console_style(Element::Warning).apply_to("?".to_string()),
+            prompt_suffix: console::style(prompt_suffix.to_string())
                 .for_stderr()
                 .black()
                 .bright(),
-            success_prefix: console::style("✓".to_string()).for_stderr().green(),
-            success_suffix: console::style("·".to_string())
+            success_prefix: console_style(Element::Success).apply_to(success.to_string()),
+            success_suffix: console::style(suffix.to_string())
                 .for_stderr()
                 .black()
                 .bright(),
-            error_prefix: console::style("✗".to_string()).for_stderr().red(),
-            error_style: console::Style::new().for_stderr().red(),
+            error_prefix: console_style(Element::Error).apply_to(error.to_string()),
+            error_style: console_style(Element::Error),
             hint_style: console::Style::new().for_stderr().black().bright(),
-            values_style: console::Style::new().for_stderr().green(),
-            active_item_style: console::Style::new().for_stderr().cyan().bold(),
+            values_style: console_style(Element::Success),
+            active_item_style: console_style(Element::Hint).bold(),
             inactive_item_style: console::Style::new().for_stderr(),
-            active_item_prefix: console::style("❯".to_string()).for_stderr().green(),
+            active_item_prefix: console_style(Element::Success).apply_to(active.to_string()),
             inactive_item_prefix: console::style(" ".to_string()).for_stderr(),
-            checked_item_prefix: console::style("✓".to_string()).for_stderr().green(),
-            unchecked_item_prefix: console::style("✗".to_string()).for_stderr().red(),
-            picked_item_prefix: console::style("❯".to_string()).for_stderr().green(),
+            checked_item_prefix: console_style(Element::Added).apply_to(checked.to_string()),
+            unchecked_item_prefix: console_style(Element::Removed).apply_to(unchecked.to_string()),
+            picked_item_prefix: console_style(Element::Success).apply_to(active.to_string()),
             unpicked_item_prefix: console::style(" ".to_string()).for_stderr(),
-            fuzzy_cursor_style: console::Style::new().for_stderr().yellow().bold(),
+            fuzzy_cursor_style: console_style(Element::Warning).bold(),
             fuzzy_match_highlight_style: console::Style::new().for_stderr().bold(),
         }
     }
@@ -198,6 +254,7 @@ impl Default for InteractivePrompt {
 pub struct FileSelector {
     files: Vec<FileItem>,
     show_details: bool,
+    prompt: String,
 }
 
 #[derive(Debug, Clone)]
@@ -214,9 +271,16 @@ impl FileSelector {
         Self {
             files: Vec::new(),
             show_details: false,
+            prompt: "Select files to stage".to_string(),
         }
     }
 
+    /// Set the question asked above the list
+    pub fn with_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.prompt = prompt.into();
+        self
+    }
+
     /// Add files to the selector
     pub fn with_files(mut self, files: Vec<FileItem>) -> Self {
         self.files = files;
@@ -238,7 +302,7 @@ impl FileSelector {
         let items = self.format_file_items();
 
         let selected_indices = InteractivePrompt::new()
-            .with_message("Select files to stage")
+            .with_message(&self.prompt)
             .with_options(&items)
             .multiselect_prompt()?;
 
@@ -253,16 +317,16 @@ impl FileSelector {
         self.files
             .iter()
             .map(|item| {
-                let status_color = match item.status.as_str() {
-                    "modified" => Color::Yellow,
-                    "new" => Color::Green,
-                    "deleted" => Color::Red,
-                    _ => Color::White,
+                let status = match item.status.as_str() {
+                    "modified" => item.status.themed(Element::Modified),
+                    "new" => item.status.themed(Element::Added),
+                    "deleted" => item.status.themed(Element::Removed),
+                    _ => item.status.normal(),
                 };
 
                 let mut display = format!(
                     "{} {}",
-                    item.status.color(status_color).bold(),
+                    status.bold(),
                     item.path.display().to_string().white()
                 );
 
@@ -387,7 +451,7 @@ impl CommitMessageEditor {
 
         // Check first line length
         if lines[0].len() > 72 {
-            eprintln!(
+            ui_eprintln!(
                 "{} First line should be 72 characters or less",
                 "⚠️".yellow()
             );
@@ -395,7 +459,7 @@ impl CommitMessageEditor {
 
         // Check for blank line after first line if there are more lines
         if lines.len() > 1 && !lines[1].is_empty() {
-            eprintln!(
+            ui_eprintln!(
                 "{} Consider adding a blank line after the first line",
                 "💡".blue()
             );
@@ -412,159 +476,321 @@ impl Default for CommitMessageEditor {
 }
 
 /// Interactive conflict resolution assistant
-pub struct ConflictResolver {
-    conflicts: Vec<ConflictFile>,
-}
-
-#[derive(Debug, Clone)]
-pub struct ConflictFile {
-    pub path: PathBuf,
-    pub conflict_type: ConflictType,
+pub struct ConflictResolver<'r> {
+    repo: &'r Repository,
+    conflicts: Vec<ConflictSides>,
+    tool: Option<Tool>,
 }
 
 #[derive(Debug, Clone)]
 pub enum ConflictType {
     Content,
     AddAdd,
+    /// Deleted on our side, modified on theirs
     DeleteModify,
+    /// Modified on our side, deleted on theirs
     ModifyDelete,
     Rename,
 }
 
-impl ConflictResolver {
+impl ConflictType {
+    fn describe(&self) -> &'static str {
+        match self {
+            ConflictType::Content => "both modified",
+            ConflictType::AddAdd => "both added",
+            ConflictType::DeleteModify => "deleted by us",
+            ConflictType::ModifyDelete => "deleted by them",
+            ConflictType::Rename => "renamed",
+        }
+    }
+}
+
+/// What happened to the files offered to a [`ConflictResolver`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResolveSummary {
+    pub resolved: Vec<String>,
+    pub remaining: Vec<String>,
+}
+
+impl<'r> ConflictResolver<'r> {
     /// Create a new conflict resolver
-    pub fn new(conflicts: Vec<ConflictFile>) -> Self {
-        Self { conflicts }
+    pub fn new(repo: &'r Repository, conflicts: Vec<ConflictSides>) -> Self {
+        Self { repo, conflicts, tool: None }
+    }
+
+    /// Offer a merge tool for each file
+    pub fn with_tool(mut self, tool: Option<Tool>) -> Self {
+        self.tool = tool;
+        self
     }
 
     /// Start interactive conflict resolution
-    pub fn resolve(&self) -> Result<()> {
+    pub fn resolve(&self) -> Result<ResolveSummary> {
+        let mut summary = ResolveSummary::default();
         if self.conflicts.is_empty() {
-            println!("{} No conflicts to resolve", "✅".green());
-            return Ok(());
+            ui_println!("{} No conflicts to resolve", "✅".green());
+            return Ok(summary);
         }
 
-        println!("{} {} conflicts detected", "⚔️".red(), self.conflicts.len());
+        ui_println!("{} {} conflicts detected", "⚔️".red(), self.conflicts.len());
 
         for (i, conflict) in self.conflicts.iter().enumerate() {
-            println!(
-                "\n{} Conflict {} of {}: {}",
+            ui_println!(
+                "\n{} Conflict {} of {}: {} ({})",
                 "📁".blue(),
                 i + 1,
                 self.conflicts.len(),
-                conflict.path.display().to_string().yellow()
+                conflict.path.yellow(),
+                conflict.conflict_type().describe()
             );
 
-            self.resolve_single_conflict(conflict)?;
+            if self.resolve_single_conflict(conflict)? {
+                ui_println!("{} Resolved and staged {}", "✅".green(), conflict.path);
+                summary.resolved.push(conflict.path.clone());
+            } else {
+                summary.remaining.push(conflict.path.clone());
+            }
         }
 
-        println!("\n{} All conflicts resolved!", "🎉".green());
-        Ok(())
+        if summary.remaining.is_empty() {
+            ui_println!("\n{} All conflicts resolved!", "🎉".green());
+        }
+        Ok(summary)
     }
 
-    /// Resolve a single conflict
-    fn resolve_single_conflict(&self, conflict: &ConflictFile) -> Result<()> {
-        let options = match conflict.conflict_type {
-            ConflictType::Content => vec![
-                "Edit file manually",
-                "Use merge tool",
-                "Take ours (current branch)",
-                "Take theirs (merging branch)",
-                "Skip this file",
-            ],
-            ConflictType::AddAdd => vec![
-                "Keep both files with rename",
-                "Keep ours",
-                "Keep theirs",
-                "Edit manually",
-                "Skip this file",
-            ],
-            ConflictType::DeleteModify => vec![
-                "Keep modified file",
-                "Keep deleted (remove file)",
-                "Edit manually",
-                "Skip this file",
-            ],
-            ConflictType::ModifyDelete => vec![
-                "Keep modified file",
-                "Keep deleted (remove file)",
-                "Edit manually",
-                "Skip this file",
-            ],
-            ConflictType::Rename => vec![
-                "Accept rename",
-                "Keep original name",
-                "Choose different name",
-                "Skip this file",
-            ],
-        };
+    /// Resolve a single conflict, returning whether it was staged
+    fn resolve_single_conflict(&self, conflict: &ConflictSides) -> Result<bool> {
+        let both_present = conflict.ours.is_some() && conflict.theirs.is_some();
+        if !both_present {
+            return self.resolve_deletion(conflict);
+        }
 
+        let text = !conflict.is_binary(self.repo)?;
+        let mut options = Vec::new();
+        if text {
+            options.push(FileChoice::Hunks);
+        }
+        options.extend([FileChoice::Ours, FileChoice::Theirs]);
+        if text {
+            options.push(FileChoice::Editor);
+        }
+        if let Some(tool) = &self.tool {
+            options.push(FileChoice::MergeTool(tool));
+        }
+        options.push(FileChoice::Skip);
+
+        let labels: Vec<String> = options.iter().map(|o| o.label()).collect();
         let selection = InteractivePrompt::new()
-            .with_message(&format!("How to resolve {}?", conflict.path.display()))
-            .with_options(&options)
+            .with_message(format!("How to resolve {}?", conflict.path))
+            .with_options(&labels)
+            .with_default(0)
             .select()?;
 
-        self.execute_resolution(conflict, selection)?;
-        Ok(())
-    }
-
-    /// Execute the chosen resolution
-    fn execute_resolution(&self, conflict: &ConflictFile, choice: usize) -> Result<()> {
-        match (conflict.conflict_type.clone(), choice) {
-            (ConflictType::Content, 0) => {
-                // Edit file manually
+        match &options[selection] {
+            FileChoice::Hunks => self.resolve_hunks(conflict),
+            FileChoice::Ours => {
+                take_side(self.repo, conflict, Side::Ours)?;
+                Ok(true)
+            }
+            FileChoice::Theirs => {
+                take_side(self.repo, conflict, Side::Theirs)?;
+                Ok(true)
+            }
+            FileChoice::Editor => {
                 self.open_editor(&conflict.path)?;
+                self.stage_if_clean(&conflict.path)
             }
-            (ConflictType::Content, 1) => {
-                // Use merge tool
-                self.open_merge_tool(&conflict.path)?;
+            FileChoice::MergeTool(tool) => {
+                run_mergetool(self.repo, conflict, tool)?;
+                self.stage_if_clean(&conflict.path)
             }
-            (ConflictType::Content, 2) => {
-                // Take ours
-                self.take_ours(&conflict.path)?;
+            FileChoice::Skip => Ok(false),
+        }
+    }
+
+    /// One side deleted the file and the other changed it
+    fn resolve_deletion(&self, conflict: &ConflictSides) -> Result<bool> {
+        let (kept, deleted) = if conflict.ours.is_some() {
+            (Side::Ours, Side::Theirs)
+        } else {
+            (Side::Theirs, Side::Ours)
+        };
+
+        let selection = InteractivePrompt::new()
+            .with_message(format!("How to resolve {}?", conflict.path))
+            .with_options(&["Keep modified file", "Keep deleted (remove file)", "Skip this file"])
+            .with_default(0)
+            .select()?;
+
+        match selection {
+            0 => take_side(self.repo, conflict, kept)?,
+            1 => take_side(self.repo, conflict, deleted)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Walk the conflict hunks of a file with base/ours/theirs panes
+    fn resolve_hunks(&self, conflict: &ConflictSides) -> Result<bool> {
+        let workdir = self.repo.workdir().ok_or(RgitError::NotInRepository)?;
+        let full_path = workdir.join(&conflict.path);
+        let mut parsed = ConflictedText::parse(&std::fs::read_to_string(&full_path)?);
+        if parsed.hunk_count() == 0 {
+            ui_println!("{} {} has no conflict markers left", "💡".blue(), conflict.path);
+            return self.stage_if_clean(&conflict.path);
+        }
+        if let Some(diff3) = diff3_text(self.repo, &conflict.path)? {
+            parsed.fill_bases(&ConflictedText::parse(&diff3));
+        }
+
+        let total = parsed.hunk_count();
+        let mut resolutions = Vec::with_capacity(total);
+        let mut preceding = "";
+        for segment in &parsed.segments {
+            let hunk = match segment {
+                Segment::Text(text) => {
+                    preceding = text;
+                    continue;
+                }
+                Segment::Conflict(hunk) => hunk,
+            };
+
+            show_hunk(hunk, preceding, resolutions.len() + 1, total);
+            resolutions.push(self.choose_hunk_resolution(hunk)?);
+            preceding = "";
+        }
+
+        let resolved = resolutions.iter().all(|r| r.is_some());
+        let contents = parsed.render(&resolutions);
+        if resolved {
+            stage_resolved(self.repo, &conflict.path, contents.as_bytes())?;
+        } else {
+            std::fs::write(&full_path, contents)?;
+            let left = resolutions.iter().filter(|r| r.is_none()).count();
+            ui_println!("{} {} conflict{} left in {}", "⚠️".yellow(), left, if left == 1 { "" } else { "s" }, conflict.path);
+        }
+        Ok(resolved)
+    }
+
+    fn choose_hunk_resolution(&self, hunk: &ConflictHunk) -> Result<Option<HunkResolution>> {
+        let mut options = vec![
+            (format!("Take ours ({})", side_label(&hunk.ours_label, "current")), Some(HunkResolution::Ours)),
+            (format!("Take theirs ({})", side_label(&hunk.theirs_label, "incoming")), Some(HunkResolution::Theirs)),
+            ("Take both, ours first".to_string(), Some(HunkResolution::OursThenTheirs)),
+            ("Take both, theirs first".to_string(), Some(HunkResolution::TheirsThenOurs)),
+        ];
+        if hunk.base.is_some() {
+            options.push(("Take base".to_string(), Some(HunkResolution::Base)));
+        }
+        options.push(("Edit this hunk".to_string(), None));
+        let edit = options.len() - 1;
+        options.push(("Leave unresolved".to_string(), None));
+
+        let labels: Vec<&String> = options.iter().map(|(label, _)| label).collect();
+        let selection = InteractivePrompt::new()
+            .with_message("Resolve this hunk")
+            .with_options(&labels)
+            .with_default(0)
+            .select()?;
+
+        if selection == edit {
+            let mut edited = InteractivePrompt::new().with_message(hunk.to_markers()).editor()?;
+            if !edited.is_empty() {
+                edited.push('\n');
             }
-            (ConflictType::Content, 3) => {
-                // Take theirs
-                self.take_theirs(&conflict.path)?;
+            if has_conflict_markers(&edited) {
+                ui_println!("{} The edit still has conflict markers; leaving the hunk unresolved", "⚠️".yellow());
+                return Ok(None);
             }
-            _ => {
-                println!("Resolution not implemented for this choice");
+            return Ok(Some(HunkResolution::Edited(edited)));
+        }
+        Ok(options.swap_remove(selection).1)
+    }
+
+    /// Stage a file edited outside rgit once its markers are gone
+    fn stage_if_clean(&self, path: &str) -> Result<bool> {
+        let workdir = self.repo.workdir().ok_or(RgitError::NotInRepository)?;
+        let full_path = workdir.join(path);
+        if let Ok(contents) = std::fs::read_to_string(&full_path) {
+            if has_conflict_markers(&contents) {
+                ui_println!("{} {} still has conflict markers", "⚠️".yellow(), path);
+                return Ok(false);
             }
         }
 
-        Ok(())
+        let stage = InteractivePrompt::new()
+            .with_message(format!("Mark {} as resolved?", path))
+            .confirm()?;
+        if stage {
+            mark_resolved(self.repo, path)?;
+        }
+        Ok(stage)
     }
 
     /// Open file in editor
-    fn open_editor(&self, path: &PathBuf) -> Result<()> {
+    fn open_editor(&self, path: &str) -> Result<()> {
         let editor = std::env::var("EDITOR").unwrap_or_else(|_| "nano".to_string());
+        let workdir = self.repo.workdir().ok_or(RgitError::NotInRepository)?;
 
-        std::process::Command::new(editor).arg(path).status()?;
+        std::process::Command::new(editor).arg(workdir.join(path)).status()?;
 
         Ok(())
     }
+}
 
-    /// Open merge tool
-    fn open_merge_tool(&self, path: &PathBuf) -> Result<()> {
-        let merge_tool = std::env::var("MERGE_TOOL").unwrap_or_else(|_| "vimdiff".to_string());
-
-        std::process::Command::new(merge_tool).arg(path).status()?;
+/// Whole-file choices for a conflict where both sides kept the file
+enum FileChoice<'t> {
+    Hunks,
+    Ours,
+    Theirs,
+    Editor,
+    MergeTool(&'t Tool),
+    Skip,
+}
 
-        Ok(())
+impl FileChoice<'_> {
+    fn label(&self) -> String {
+        match self {
+            FileChoice::Hunks => "Resolve hunk by hunk".to_string(),
+            FileChoice::Ours => "Take ours (current branch)".to_string(),
+            FileChoice::Theirs => "Take theirs (merging branch)".to_string(),
+            FileChoice::Editor => "Edit file manually".to_string(),
+            FileChoice::MergeTool(tool) => format!("Use merge tool ({})", tool.name()),
+            FileChoice::Skip => "Skip this file".to_string(),
+        }
     }
+}
 
-    /// Take our version
-    fn take_ours(&self, _path: &PathBuf) -> Result<()> {
-        println!("Taking our version...");
-        // Implementation would resolve conflict by taking local version
-        Ok(())
+fn side_label<'a>(label: &'a str, fallback: &'a str) -> &'a str {
+    if label.is_empty() { fallback } else { label }
+}
+
+/// Print a hunk as ours/base/theirs panes under a little leading context
+fn show_hunk(hunk: &ConflictHunk, preceding: &str, number: usize, total: usize) {
+    ui_println!("\n{} {} {}", "──".bold(), format!("Hunk {} of {}", number, total).bold(), "──".bold());
+    let context: Vec<&str> = preceding.lines().collect();
+    for line in &context[context.len().saturating_sub(3)..] {
+        println!("    {}", line.dimmed());
     }
 
-    /// Take their version
-    fn take_theirs(&self, _path: &PathBuf) -> Result<()> {
-        println!("Taking their version...");
-        // Implementation would resolve conflict by taking remote version
-        Ok(())
+    let panes = [
+        (format!("ours ({})", side_label(&hunk.ours_label, "current")), Some(&hunk.ours), Some(Element::Added)),
+        ("base".to_string(), hunk.base.as_ref(), None),
+        (format!("theirs ({})", side_label(&hunk.theirs_label, "incoming")), Some(&hunk.theirs), Some(Element::Branch)),
+    ];
+    for (title, body, element) in panes {
+        let Some(body) = body else { continue };
+        let paint = |text: &str| match element {
+            Some(element) => text.themed(element),
+            None => text.normal(),
+        };
+        println!("  {}", paint(&title).bold());
+        if body.is_empty() {
+            ui_println!("  {} {}", paint("│"), "(empty)".dimmed());
+        }
+        for line in body.lines() {
+            println!("  {} {}", paint(&output::decoration("│")), line);
+        }
     }
 }
 
@@ -625,10 +851,176 @@ impl ProgressDisplay {
 
         pb.set_style(style);
         pb.set_message(self.message.clone());
+        // A bar redrawn in place garbles screen readers and dumb terminals
+        if crate::output::is_accessible() {
+            pb.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+        }
         pb
     }
 }
 
+/// Progress of a clone, fetch or push fed by libgit2's callbacks: objects
+/// received or sent, throughput, delta resolution, and the server's own
+/// `remote:` messages, so long transfers don't look frozen
+pub struct TransferProgress {
+    bar: indicatif::ProgressBar,
+    state: RefCell<TransferState>,
+}
+
+#[derive(Default)]
+struct TransferState {
+    started: Option<Instant>,
+    sending: bool,
+    objects: usize,
+    bytes: usize,
+    deltas: usize,
+    /// Whether the bar shows counts, rather than a spinner with the
+    /// server's latest progress line
+    counting: bool,
+    /// Sideband text after the last line break
+    sideband: String,
+}
+
+impl TransferProgress {
+    /// A bar on stderr, hidden with `ui.progress = false` or when stderr
+    /// isn't a terminal
+    pub fn new(config: &Config) -> Self {
+        let bar = ProgressDisplay::new("").with_total(0).create_progress_bar();
+        if !config.ui.progress {
+            bar.set_draw_target(indicatif::ProgressDrawTarget::hidden());
+        }
+        Self { bar, state: RefCell::new(TransferState::default()) }
+    }
+
+    /// Report through `callbacks` for as long as they live
+    pub fn attach<'a>(&'a self, callbacks: &mut RemoteCallbacks<'a>) {
+        callbacks.transfer_progress(move |stats| {
+            self.received(&stats);
+            true
+        });
+        callbacks.sideband_progress(move |data| {
+            self.sideband(data);
+            true
+        });
+        callbacks.pack_progress(move |stage, current, total| {
+            let phase = match stage {
+                PackBuilderStage::AddingObjects => "Counting objects",
+                PackBuilderStage::Deltafication => "Compressing objects",
+            };
+            self.count(phase, current, total, None);
+        });
+        callbacks.push_transfer_progress(move |current, total, bytes| {
+            {
+                let mut state = self.state.borrow_mut();
+                state.sending = true;
+                state.objects = current;
+                state.bytes = bytes;
+            }
+            self.count("Writing objects", current, total, Some(bytes));
+        });
+    }
+
+    fn received(&self, stats: &Progress) {
+        {
+            let mut state = self.state.borrow_mut();
+            state.objects = stats.received_objects();
+            state.bytes = stats.received_bytes();
+            state.deltas = stats.indexed_deltas();
+        }
+        if stats.received_objects() < stats.total_objects() {
+            self.count("Receiving objects", stats.received_objects(), stats.total_objects(), Some(stats.received_bytes()));
+        } else if stats.total_deltas() > 0 {
+            self.count("Resolving deltas", stats.indexed_deltas(), stats.total_deltas(), None);
+        }
+    }
+
+    fn count(&self, phase: &'static str, current: usize, total: usize, bytes: Option<usize>) {
+        let mut state = self.state.borrow_mut();
+        let started = *state.started.get_or_insert_with(Instant::now);
+        if !state.counting {
+            state.counting = true;
+            self.bar.set_style(
+                indicatif::ProgressStyle::default_bar()
+                    .template("{spinner:.green} {prefix} [{bar:30.cyan/blue}] {pos}/{len} {msg}")
+                    .unwrap()
+                    .progress_chars("=> "),
+            );
+        }
+        self.bar.set_prefix(phase);
+        self.bar.set_length(total as u64);
+        self.bar.set_position(current as u64);
+        match bytes {
+            Some(bytes) => self.bar.set_message(format!("{}, {}", format_size(bytes as u64), rate(bytes, started.elapsed()))),
+            None => self.bar.set_message(""),
+        }
+    }
+
+    fn sideband(&self, data: &[u8]) {
+        let lines = split_sideband(&mut self.state.borrow_mut().sideband, data);
+        for (line, finished) in lines {
+            if finished {
+                self.println(&format!("remote: {}", line));
+            } else if !self.state.borrow().counting {
+                // Redrawn lines like "Counting objects: 45%" update in place
+                self.bar.set_style(indicatif::ProgressStyle::default_spinner().template("{spinner:.green} {msg}").unwrap());
+                self.bar.set_message(format!("remote: {}", line));
+                self.bar.tick();
+            }
+        }
+    }
+
+    /// Print a line above the bar
+    pub fn println(&self, line: &str) {
+        if self.bar.is_hidden() {
+            ui_eprintln!("{}", line);
+        } else {
+            self.bar.println(line);
+        }
+    }
+
+    /// Hide the bar while `f` prints
+    pub fn suspend<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.bar.suspend(f)
+    }
+
+    /// Clear the bar and print what was transferred
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+        let state = self.state.borrow();
+        let Some(started) = state.started.filter(|_| state.objects > 0) else { return };
+        let elapsed = started.elapsed();
+        let mut summary = format!("{} {} objects ({}, {})",
+            if state.sending { "Sent" } else { "Received" },
+            state.objects, format_size(state.bytes as u64), rate(state.bytes, elapsed));
+        if state.deltas > 0 {
+            summary.push_str(&format!(", resolved {} deltas", state.deltas));
+        }
+        ui_println!("{} {} in {:.1}s", "📦".blue(), summary, elapsed.as_secs_f64());
+    }
+}
+
+fn rate(bytes: usize, elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs_f64().max(0.001);
+    format!("{}/s", format_size((bytes as f64 / seconds) as u64))
+}
+
+/// Take the complete lines out of sideband data, each with whether it ended
+/// with a newline (a finished line) or a carriage return (one the server
+/// redraws). A partial line stays in `buffer` for the next call.
+fn split_sideband(buffer: &mut String, data: &[u8]) -> Vec<(String, bool)> {
+    buffer.push_str(&String::from_utf8_lossy(data));
+    let mut lines = Vec::new();
+    while let Some(end) = buffer.find(['\r', '\n']) {
+        let finished = buffer.as_bytes()[end] == b'\n';
+        let line = buffer[..end].trim_end().to_string();
+        buffer.drain(..=end);
+        if !line.is_empty() {
+            lines.push((line, finished));
+        }
+    }
+    lines
+}
+
 /// Utility functions for interactive components
 pub fn format_size(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
@@ -663,14 +1055,21 @@ pub struct TableDisplay {
     headers: Vec<String>,
     rows: Vec<Vec<String>>,
     max_width: usize,
+    /// Wrap long cells onto more lines instead of truncating them
+    wrap: bool,
 }
 
 impl TableDisplay {
+    /// Columns are never squeezed narrower than this
+    const MIN_COLUMN_WIDTH: usize = 3;
+    const SEPARATOR: &'static str = " | ";
+
     pub fn new() -> Self {
         Self {
             headers: Vec::new(),
             rows: Vec::new(),
             max_width: 80,
+            wrap: false,
         }
     }
 
@@ -688,85 +1087,111 @@ impl TableDisplay {
         self
     }
 
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Print the table; cells are printed as given, so callers pass any
+    /// glyphs in them through [`output::decoration`]
     pub fn display(&self) {
-        if self.headers.is_empty() && self.rows.is_empty() {
-            return;
+        for line in self.render() {
+            println!("{}", line);
         }
+    }
 
-        let mut all_rows = Vec::new();
-        if !self.headers.is_empty() {
-            all_rows.push(self.headers.clone());
+    /// The table as lines of text
+    fn render(&self) -> Vec<String> {
+        if self.headers.is_empty() && self.rows.is_empty() {
+            return Vec::new();
         }
-        all_rows.extend(self.rows.clone());
 
-        let col_widths = self.calculate_column_widths(&all_rows);
-
-        // Print header
+        let col_widths = self.calculate_column_widths();
+        let mut lines = Vec::new();
         if !self.headers.is_empty() {
-            self.print_row(&self.headers, &col_widths, true);
-            self.print_separator(&col_widths);
+            lines.extend(self.render_row(&self.headers, &col_widths, true));
+            let separators: Vec<String> = col_widths.iter().map(|&width| "-".repeat(width)).collect();
+            lines.push(separators.join("-|-"));
         }
-
-        // Print rows
         for row in &self.rows {
-            self.print_row(row, &col_widths, false);
+            lines.extend(self.render_row(row, &col_widths, false));
         }
+        lines
     }
 
-    fn calculate_column_widths(&self, rows: &[Vec<String>]) -> Vec<usize> {
-        if rows.is_empty() {
-            return Vec::new();
-        }
-
-        let num_cols = rows[0].len();
+    /// Widths that fit the content, narrowing the widest columns first
+    /// until the table fits `max_width`
+    fn calculate_column_widths(&self) -> Vec<usize> {
+        let rows = std::iter::once(&self.headers).chain(&self.rows);
+        let num_cols = rows.clone().map(Vec::len).max().unwrap_or(0);
         let mut widths = vec![0; num_cols];
-
         for row in rows {
-            for (i, cell) in row.iter().enumerate() {
-                if i < widths.len() {
-                    widths[i] = widths[i].max(unicode_width::UnicodeWidthStr::width(cell.as_str()));
-                }
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(console::measure_text_width(cell));
             }
         }
 
-        // Adjust for terminal width
-        let total_width: usize = widths.iter().sum::<usize>() + (num_cols - 1) * 3;
-        if total_width > self.max_width {
-            let ratio = self.max_width as f64 / total_width as f64;
-            for width in &mut widths {
-                *width = (*width as f64 * ratio) as usize;
-            }
+        let separators = num_cols.saturating_sub(1) * Self::SEPARATOR.len();
+        while widths.iter().sum::<usize>() + separators > self.max_width {
+            let Some(widest) = widths.iter_mut().filter(|width| **width > Self::MIN_COLUMN_WIDTH).max() else {
+                break;
+            };
+            *widest -= 1;
         }
-
         widths
     }
 
-    fn print_row(&self, row: &[String], widths: &[usize], is_header: bool) {
-        let formatted_cells: Vec<String> = row
-            .iter()
-            .zip(widths.iter())
-            .map(|(cell, &width)| {
-                let truncated = if cell.len() > width {
-                    format!("{}...", &cell[..width.saturating_sub(3)])
+    fn render_row(&self, row: &[String], widths: &[usize], is_header: bool) -> Vec<String> {
+        let cells: Vec<Vec<String>> = widths.iter().enumerate()
+            .map(|(i, &width)| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                if console::measure_text_width(cell) <= width {
+                    vec![cell.to_string()]
+                } else if self.wrap {
+                    wrap_cell(&console::strip_ansi_codes(cell), width)
                 } else {
-                    cell.clone()
-                };
-
-                if is_header {
-                    format!("{:<width$}", truncated.bold(), width = width)
-                } else {
-                    format!("{:<width$}", truncated, width = width)
+                    vec![truncate_by_width(&console::strip_ansi_codes(cell), width)]
                 }
             })
             .collect();
 
-        println!("{}", formatted_cells.join(" | "));
+        let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+        (0..height).map(|line| {
+            let formatted: Vec<String> = cells.iter().zip(widths)
+                .map(|(cell, &width)| {
+                    let text = cell.get(line).map(String::as_str).unwrap_or("");
+                    let padding = " ".repeat(width.saturating_sub(console::measure_text_width(text)));
+                    if is_header {
+                        format!("{}{}", text.bold(), padding)
+                    } else {
+                        format!("{}{}", text, padding)
+                    }
+                })
+                .collect();
+            formatted.join(Self::SEPARATOR).trim_end().to_string()
+        }).collect()
     }
+}
 
-    fn print_separator(&self, widths: &[usize]) {
-        let separators: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
-        println!("{}", separators.join("-|-"));
+/// Lines of at most `width` columns, broken between words and inside
+/// words too long for a line
+fn wrap_cell(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for line in word_wrap(text, width) {
+        let mut current = String::new();
+        let mut current_width = 0;
+        for c in line.chars() {
+            let char_width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            if current_width + char_width > width && !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            current.push(c);
+            current_width += char_width;
+        }
+        lines.push(current);
     }
+    lines
 }
 
 impl Default for TableDisplay {
@@ -815,6 +1240,19 @@ mod tests {
         assert!(selector.show_details);
     }
 
+    #[test]
+    fn test_split_sideband() {
+        let mut buffer = String::new();
+        assert_eq!(split_sideband(&mut buffer, b"Enumerating objects: 5, done.\nCounting obj"), vec![
+            ("Enumerating objects: 5, done.".to_string(), true),
+        ]);
+        assert_eq!(split_sideband(&mut buffer, b"ects:  40% (2/5)\rCounting objects: 100% (5/5), done.\n"), vec![
+            ("Counting objects:  40% (2/5)".to_string(), false),
+            ("Counting objects: 100% (5/5), done.".to_string(), true),
+        ]);
+        assert!(buffer.is_empty());
+    }
+
     #[test]
     fn test_table_display() {
         let mut table = TableDisplay::new()
@@ -829,4 +1267,22 @@ mod tests {
         assert_eq!(table.headers.len(), 2);
         assert_eq!(table.rows.len(), 2);
     }
+
+    #[test]
+    fn test_table_truncates_and_wraps_by_display_width() {
+        let mut table = TableDisplay::new()
+            .with_headers(vec!["Path".to_string(), "Note".to_string()])
+            .with_max_width(20);
+        table.add_row(vec!["文档/说明书.md".to_string(), "🚀 launch plan".to_string()]);
+        table.add_row(vec!["a".to_string(), "ok".to_string()]);
+
+        let lines = table.render();
+        assert_eq!(lines[2], "文档/...  | 🚀 la...");
+        for line in &lines {
+            assert!(console::measure_text_width(line) <= 20, "{:?} is too wide", line);
+        }
+
+        let lines = table.with_wrap(true).render();
+        assert_eq!(lines[2..], ["文档/说明 | 🚀", "书.md     | launch", "          | plan", "a         | ok"]);
+    }
 }
@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use tracing::debug;
+
+/// Directory inside `.git` where rgit keeps its own state
+const RGIT_DIR: &str = "rgit";
+
+/// Journal file name (one JSON entry per line)
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// A single recorded operation.
+///
+/// Entries are written *before* a destructive operation runs so the previous
+/// state can always be recovered, even if the operation itself fails halfway.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    /// When the entry was recorded
+    pub timestamp: DateTime<Utc>,
+    /// Operation name, e.g. "push --delete"
+    pub operation: String,
+    /// Reference affected by the operation
+    pub reference: String,
+    /// Object the reference pointed to before the operation
+    pub old_oid: Option<String>,
+    /// Object the reference points to afterwards (None when deleted)
+    pub new_oid: Option<String>,
+    /// Free-form details, such as the remote name
+    pub detail: String,
+}
+
+impl JournalEntry {
+    pub fn new(operation: &str, reference: &str) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            operation: operation.to_string(),
+            reference: reference.to_string(),
+            old_oid: None,
+            new_oid: None,
+            detail: String::new(),
+        }
+    }
+
+    pub fn with_old_oid(mut self, oid: impl ToString) -> Self {
+        self.old_oid = Some(oid.to_string());
+        self
+    }
+
+    pub fn with_new_oid(mut self, oid: impl ToString) -> Self {
+        self.new_oid = Some(oid.to_string());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = detail.into();
+        self
+    }
+}
+
+/// Append-only log of reference changes made by rgit
+#[derive(Debug, Clone)]
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    /// Journal stored in the repository's git directory
+    pub fn for_repo(repo: &Repository) -> Self {
+        Self {
+            path: repo.path().join(RGIT_DIR).join(JOURNAL_FILE),
+        }
+    }
+
+    /// Location of the journal file
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// Append an entry
+    pub fn record(&self, entry: &JournalEntry) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Failed to open journal: {}", self.path.display()))?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+
+        debug!("Journal: {} {} ({:?} -> {:?})", entry.operation, entry.reference, entry.old_oid, entry.new_oid);
+        Ok(())
+    }
+
+    /// All entries, oldest first. Unreadable lines are skipped.
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read journal: {}", self.path.display()))?;
+        Ok(content.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_record_and_read_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path()).unwrap();
+        let journal = Journal::for_repo(&repo);
+
+        assert!(journal.entries().unwrap().is_empty());
+
+        let first = JournalEntry::new("push --delete", "refs/heads/feature")
+            .with_old_oid("abc123")
+            .with_detail("origin");
+        let second = JournalEntry::new("branch -d", "refs/heads/old").with_old_oid("def456");
+        journal.record(&first).unwrap();
+        journal.record(&second).unwrap();
+
+        // A corrupt line must not hide the rest of the journal
+        let mut file = OpenOptions::new().append(true).open(journal.path()).unwrap();
+        writeln!(file, "not json").unwrap();
+
+        let entries = journal.entries().unwrap();
+        assert_eq!(entries, vec![first, second]);
+    }
+}
@@ -0,0 +1,45 @@
+//! rgit - a Git CLI with enhanced UX and intelligent submodule handling.
+//!
+//! The `rgit` binary is a thin wrapper around this library. Enable the
+//! `test-support` feature to get [`test_support`], the fixtures rgit's own
+//! tests use to script repositories.
+
+// First, so its ui_print! macros are in scope in every module below
+#[macro_use]
+pub mod output;
+
+pub mod binary;
+pub mod checks;
+pub mod cli;
+pub mod codeowners;
+pub mod commands;
+pub mod config;
+pub mod conflict;
+pub mod dry_run;
+pub mod core;
+pub mod credential;
+pub mod error;
+pub mod error_catalog;
+pub mod forge;
+pub mod i18n;
+pub mod interactive;
+pub mod journal;
+pub mod logging;
+pub mod network;
+pub mod operation;
+pub mod pager;
+pub mod policy;
+pub mod safety;
+pub mod secrets;
+pub mod sequencer;
+pub mod status;
+pub mod theme;
+pub mod timings;
+pub mod submodule;
+pub mod tools;
+pub mod utils;
+pub mod validation;
+pub mod workspace;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
@@ -0,0 +1,178 @@
+//! Log files for bug reports.
+//!
+//! Besides the usual stderr output, rgit appends JSON lines to
+//! `logs/rgit.log` in the data directory, or to `advanced.logging.file`
+//! (`--log-file`). A run is short, so the file is rotated when it's opened:
+//! once it has reached `advanced.logging.max_file_size` it becomes
+//! `rgit.log.1`, older files move up one and anything past `keep_files` is
+//! dropped.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, LoggingConfig};
+use crate::utils::parse_size;
+
+pub const LOG_FILE: &str = "rgit.log";
+
+/// Where logs go, or `None` when file logging is off
+pub fn log_path(config: &Config) -> Option<PathBuf> {
+    let logging = &config.advanced.logging;
+    if let Some(file) = &logging.file {
+        return Some(file.clone());
+    }
+    if !logging.to_file {
+        return None;
+    }
+    Config::get_data_dir().ok().map(|dir| dir.join("logs").join(LOG_FILE))
+}
+
+/// Open `path` for appending, rotating it first if it has grown too big
+pub fn open(path: &Path, logging: &LoggingConfig) -> io::Result<File> {
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let max_size = parse_size(&logging.max_file_size).unwrap_or(u64::MAX);
+    if fs::metadata(path).map(|meta| meta.len() >= max_size).unwrap_or(false) {
+        rotate(path, logging.keep_files)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn rotate(path: &Path, keep: usize) -> io::Result<()> {
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+    let _ = fs::remove_file(rotated(path, keep));
+    for n in (0..keep).rev() {
+        let from = rotated(path, n);
+        if from.exists() {
+            fs::rename(&from, rotated(path, n + 1))?;
+        }
+    }
+    Ok(())
+}
+
+/// The log file and its rotated copies that exist, newest first
+pub fn log_files(path: &Path) -> Vec<PathBuf> {
+    (0..)
+        .map(|n| rotated(path, n))
+        .take_while(|file| file.exists())
+        .collect()
+}
+
+/// One warning or error read back from a log file
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+    /// Structured fields other than the message, like `command`
+    pub fields: Vec<(String, String)>,
+}
+
+/// The last `limit` warnings and errors across the log files, oldest first.
+/// Lines that aren't rgit JSON are skipped.
+pub fn recent_problems(path: &Path, limit: usize) -> Vec<LogEntry> {
+    let mut entries: Vec<LogEntry> = log_files(path)
+        .iter()
+        .rev()
+        .filter_map(|file| File::open(file).ok())
+        .flat_map(|file| BufReader::new(file).lines().map_while(Result::ok))
+        .filter_map(|line| parse_entry(&line))
+        .filter(|entry| entry.level == "WARN" || entry.level == "ERROR")
+        .collect();
+    let skip = entries.len().saturating_sub(limit);
+    entries.drain(..skip);
+    entries
+}
+
+fn parse_entry(line: &str) -> Option<LogEntry> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    let text = |value: &serde_json::Value| match value {
+        serde_json::Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    let mut message = String::new();
+    let mut fields = Vec::new();
+    for (name, value) in value.get("fields")?.as_object()? {
+        if name == "message" {
+            message = text(value);
+        } else {
+            fields.push((name.clone(), text(value)));
+        }
+    }
+    Some(LogEntry {
+        timestamp: value.get("timestamp").map(text).unwrap_or_default(),
+        level: value.get("level")?.as_str()?.to_string(),
+        message,
+        fields,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_rotation_keeps_the_newest_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("logs").join(LOG_FILE);
+        let logging = LoggingConfig { max_file_size: "4B".to_string(), keep_files: 2, ..LoggingConfig::default() };
+
+        for run in ["one\n", "two\n", "three\n"] {
+            let mut file = open(&path, &logging).unwrap();
+            io::Write::write_all(&mut file, run.as_bytes()).unwrap();
+        }
+
+        assert_eq!(log_files(&path), vec![path.clone(), rotated(&path, 1), rotated(&path, 2)]);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "three\n");
+        assert_eq!(fs::read_to_string(rotated(&path, 2)).unwrap(), "one\n");
+
+        open(&path, &logging).unwrap();
+        assert_eq!(fs::read_to_string(rotated(&path, 2)).unwrap(), "two\n");
+        assert!(!rotated(&path, 3).exists());
+    }
+
+    #[test]
+    fn test_recent_problems() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOG_FILE);
+        fs::write(rotated(&path, 1), concat!(
+            r#"{"timestamp":"t1","level":"ERROR","fields":{"message":"old failure","command":"push"},"target":"rgit"}"#, "\n",
+        )).unwrap();
+        fs::write(&path, concat!(
+            r#"{"timestamp":"t2","level":"INFO","fields":{"message":"fine"},"target":"rgit"}"#, "\n",
+            "not json\n",
+            r#"{"timestamp":"t3","level":"WARN","fields":{"message":"careful","attempt":2},"target":"rgit"}"#, "\n",
+        )).unwrap();
+
+        let problems = recent_problems(&path, 10);
+        assert_eq!(problems, vec![
+            LogEntry {
+                timestamp: "t1".to_string(),
+                level: "ERROR".to_string(),
+                message: "old failure".to_string(),
+                fields: vec![("command".to_string(), "push".to_string())],
+            },
+            LogEntry {
+                timestamp: "t3".to_string(),
+                level: "WARN".to_string(),
+                message: "careful".to_string(),
+                fields: vec![("attempt".to_string(), "2".to_string())],
+            },
+        ]);
+        assert_eq!(recent_problems(&path, 1)[0].message, "careful");
+    }
+}
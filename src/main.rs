@@ -1,53 +1,92 @@
 use anyhow::Result;
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches};
 use colored::*;
 use std::process;
+use std::sync::Mutex;
 use tracing::{debug, error, info};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{EnvFilter, Layer};
 
-mod cli;
-mod config;
-mod core;
-mod error;
-mod interactive;
-mod status;
-mod submodule;
-mod utils;
-mod commands;
-
-use cli::{Cli, Commands};
-use config::Config;
-use core::RgitCore;
-use error::RgitError;
+use rgit::cli::{BackupArgs, BackupCommands, Cli, Commands};
+use rgit::commands;
+use rgit::config::Config;
+use rgit::core::RgitCore;
+use rgit::error::RgitError;
+use rgit::{t, ui_eprintln, ui_println};
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing for debugging
-    init_tracing();
-
     // Parse command line arguments
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let command_name = matches.subcommand_name().unwrap_or("rgit").to_string();
 
     // Initialize global configuration
-    let config = match Config::load() {
+    let mut config = match Config::load() {
         Ok(config) => config,
         Err(e) => {
-            eprintln!("{} Failed to load configuration: {}", "❌".red(), e);
+            rgit::i18n::init(&Config::default());
+            ui_eprintln!("{} {}", "❌".red(), t!("config-load-failed", error = e));
             process::exit(1);
         }
     };
+    let invalid_values = config.reset_invalid_values();
+
+    // Plain ASCII output for screen readers and dumb terminals
+    rgit::output::set_icons(config.ui.icons);
+    if config.ui.accessible || std::env::var("TERM").is_ok_and(|term| term == "dumb") {
+        rgit::output::set_accessible(true);
+        config.ui.colors = false;
+        config.ui.progress = false;
+    }
 
     // Handle global flags
-    if cli.no_color {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    if cli.no_color || !config.ui.colors || no_color_env {
         colored::control::set_override(false);
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+    if cli.yes {
+        config.ui.assume_yes = true;
+    }
+    if let Some(path) = &cli.log_file {
+        config.advanced.logging.file = Some(path.clone());
     }
 
+    // Messages follow user.language, or the system locale
+    rgit::i18n::init(&config);
+    rgit::theme::init(&config);
+    for problem in &invalid_values {
+        ui_eprintln!("{} {}", "⚠️".yellow(), t!("config-value-ignored", problem = problem));
+    }
+
+    // Initialize tracing for debugging and the log file
+    init_tracing(&cli, &config);
+
     // Show welcome message for interactive commands
     if cli.verbose {
         print_banner();
     }
 
+    // Remind the user of an interrupted rebase, merge or cherry-pick
+    if shows_operation_banner(&cli.command) {
+        if let Ok(repo) = git2::Repository::discover(".") {
+            rgit::operation::show_banner(&repo);
+        }
+    }
+
     // Execute the command
-    let result = execute_command(cli, config).await;
+    let result = if cli.timings {
+        commands::utils::execute_with_timing(&command_name, || run(cli, config)).await
+            .and_then(|outcome| {
+                commands::utils::show_timings(&command_name, &outcome);
+                outcome.into_result()
+            })
+    } else {
+        run(cli, config).await
+    };
 
     // Handle results with proper error formatting
     match result {
@@ -55,23 +94,57 @@ async fn main() {
             debug!("Command executed successfully");
         }
         Err(e) => {
-            error!("Command failed: {}", e);
-            print_error(&e);
-            process::exit(1);
+            error!(command = %command_name, "Command failed: {}", e);
+            let rgit_error = e.downcast_ref::<RgitError>();
+            // The health report already explains what failed
+            if !matches!(rgit_error, Some(RgitError::HealthCheckFailed { .. })) {
+                print_error(&e);
+            }
+            process::exit(rgit_error.map(RgitError::exit_code).unwrap_or(1));
         }
     }
 }
 
-/// Initialize tracing for debugging and logging
-fn init_tracing() {
-    let subscriber = tracing_subscriber::FmtSubscriber::builder()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive("rgit=info".parse().unwrap())
-        )
+/// Initialize tracing for debugging and logging. Besides stderr, rgit's own
+/// events go to the log file as JSON at `advanced.log_level`, or debug with
+/// --verbose.
+fn init_tracing(cli: &Cli, config: &Config) {
+    let stderr_layer = tracing_subscriber::fmt::layer()
         .with_target(false)
         .compact()
-        .finish();
+        .with_filter(
+            EnvFilter::from_default_env()
+                .add_directive("rgit=info".parse().unwrap())
+        );
+
+    let log_file = rgit::logging::log_path(config).and_then(|path| {
+        match rgit::logging::open(&path, &config.advanced.logging) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                // Only worth mentioning when the user asked for this file
+                if cli.log_file.is_some() {
+                    ui_eprintln!("{} {}", "⚠️".yellow(), t!("log-file-unwritable", path = path.display(), error = e));
+                }
+                None
+            }
+        }
+    });
+    let file_layer = log_file.map(|file| {
+        let level = if cli.verbose || config.advanced.verbose {
+            LevelFilter::DEBUG
+        } else {
+            config.advanced.log_level.parse().unwrap_or(LevelFilter::INFO)
+        };
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_ansi(false)
+            .with_writer(Mutex::new(file))
+            .with_filter(Targets::new().with_target("rgit", level))
+    });
+
+    let subscriber = tracing_subscriber::registry()
+        .with(stderr_layer)
+        .with(file_layer);
 
     tracing::subscriber::set_global_default(subscriber)
         .expect("Failed to set tracing subscriber");
@@ -79,30 +152,172 @@ fn init_tracing() {
 
 /// Print the application banner for verbose mode
 fn print_banner() {
-    println!("{}", format!("
+    println!("{}", rgit::output::decoration(&format!("
 ╭─────────────────────────────────────────╮
 │  🦀 {} - A Superior Git CLI in Rust   │
 │     Version {}                      │
 │     Making Git operations delightful    │
 ╰─────────────────────────────────────────╯
-", "rgit".cyan().bold(), env!("CARGO_PKG_VERSION")).cyan());
+", "rgit".cyan().bold(), env!("CARGO_PKG_VERSION"))).cyan());
+}
+
+/// Commands that drive an operation report its state themselves
+fn shows_operation_banner(command: &Commands) -> bool {
+    !matches!(command,
+        Commands::Init(_) | Commands::Clone(_) | Commands::Merge(_) | Commands::Rebase(_)
+        | Commands::CherryPick(_) | Commands::Revert(_))
+}
+
+/// Commands whose output can run long enough to need a pager
+fn pages_output(command: &Commands) -> bool {
+    match command {
+        Commands::Log(args) => !args.interactive,
+        Commands::Diff(args) => args.tool.is_none(),
+        Commands::Show(args) => !args.tree,
+        Commands::Object(args) => !(args.r#type || args.size),
+        Commands::Blame(_) | Commands::Who(_) | Commands::Grep(_) => true,
+        Commands::Todos(args) => !args.json,
+        Commands::Reflog(args) => !args.interactive,
+        Commands::Audit(args) => args.output.is_none(),
+        Commands::Explain(args) => args.code.is_none(),
+        _ => false,
+    }
+}
+
+/// Read commands that refresh remote-tracking refs in the background when
+/// `fetch.background` is on; status and branch wait for their own fetch
+fn fetches_in_background(command: &Commands) -> bool {
+    matches!(command,
+        Commands::Log(_) | Commands::Diff(_) | Commands::Show(_) | Commands::Blame(_) | Commands::Who(_)
+        | Commands::Todos(_) | Commands::Owners(_) | Commands::Grep(_))
+}
+
+/// The destructive operation a command is about to run, for the safety
+/// snapshot taken beforehand. A reset without a mode may turn out hard.
+fn destructive_operation(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Reset(args) if !args.soft && !args.mixed => Some("reset"),
+        Commands::Clean(args) if !args.dry_run && !args.restore => Some("clean"),
+        Commands::Rebase(_) => Some("rebase"),
+        Commands::Checkout(args) if args.force => Some("checkout --force"),
+        Commands::Switch(args) if args.discard_changes => Some("switch --discard-changes"),
+        Commands::Restore(args) if args.from_backup.is_some() || args.worktree || !args.staged => Some("restore"),
+        Commands::Undo(args) if args.hard => Some("undo --hard"),
+        _ => None,
+    }
+}
+
+/// Execute the command, or with `--dry-run` show what it would change
+async fn run(cli: Cli, config: Config) -> Result<()> {
+    if cli.dry_run {
+        execute_dry_run(cli, config).await
+    } else {
+        execute_command(cli, config).await
+    }
+}
+
+/// How a command honours the global `--dry-run`
+enum DryRun {
+    /// The command has a dry-run mode of its own, now switched on
+    Native,
+    /// The command only reads, so it runs as usual
+    ReadOnly,
+    /// The command runs on a scratch copy of the repository
+    Sandbox,
+    /// The command works outside the current repository
+    Unsupported(&'static str),
+}
+
+fn dry_run_mode(command: &mut Commands) -> DryRun {
+    match command {
+        Commands::Mv(args) => args.dry_run = true,
+        Commands::Rm(args) => args.dry_run = true,
+        Commands::Clean(args) if !args.restore => args.dry_run = true,
+        Commands::Fetch(args) => args.dry_run = true,
+        Commands::Sync(args) => args.dry_run = true,
+        Commands::Filter(args) => args.dry_run = true,
+        Commands::Hash(args) => args.write = false,
+        Commands::Backup(BackupArgs { action: Some(BackupCommands::Prune { dry_run, .. }), .. }) => *dry_run = true,
+        // Picking from the log cherry-picks onto the current branch
+        Commands::Log(args) if args.interactive => return DryRun::Sandbox,
+        Commands::Status(_) | Commands::Log(_) | Commands::Diff(_) | Commands::Show(_) | Commands::Blame(_) | Commands::Who(_) | Commands::Todos(_)
+        | Commands::Owners(_) | Commands::Cat(_) | Commands::Ls(_) | Commands::Object(_) | Commands::Grep(_) | Commands::LsRemote(_) | Commands::Audit(_) | Commands::Scan(_)
+        | Commands::Explain(_) => return DryRun::ReadOnly,
+        Commands::Init(_) => return DryRun::Unsupported("init"),
+        Commands::Clone(_) => return DryRun::Unsupported("clone"),
+        Commands::Mirror(_) => return DryRun::Unsupported("mirror"),
+        Commands::Config(_) => return DryRun::Unsupported("config"),
+        Commands::Whoami => return DryRun::Unsupported("whoami"),
+        Commands::Credential(_) => return DryRun::Unsupported("credential"),
+        Commands::Workspace(_) => return DryRun::Unsupported("workspace"),
+        _ => return DryRun::Sandbox,
+    }
+    DryRun::Native
+}
+
+/// Execute the command without changing anything, then show what it would have changed
+async fn execute_dry_run(mut cli: Cli, mut config: Config) -> Result<()> {
+    // An automatic fetch would move remote-tracking refs in the real repository
+    config.fetch.auto = None;
+
+    match dry_run_mode(&mut cli.command) {
+        DryRun::Native | DryRun::ReadOnly => execute_command(cli, config).await,
+        DryRun::Unsupported(command) => Err(RgitError::OperationNotSupported(
+            t!("dry-run-unsupported", command = command)
+        ).into()),
+        DryRun::Sandbox => {
+            let sandbox = rgit::dry_run::Sandbox::create(&std::env::current_dir()?)?;
+            ui_println!("{} {}\n", "🧪".blue(), t!("dry-run-banner").dimmed());
+
+            // Snapshots of a copy that is about to be thrown away are only noise
+            config.advanced.safety.auto_snapshot = false;
+            config.backup.auto_before_destructive = false;
+
+            sandbox.enter()?;
+            let result = execute_command(cli, config).await;
+            sandbox.leave()?;
+
+            println!();
+            sandbox.plan()?.print();
+            result
+        }
+    }
 }
 
 /// Execute the parsed command with proper error handling
 async fn execute_command(cli: Cli, config: Config) -> Result<()> {
     debug!("Executing command: {:?}", cli.command);
 
+    if let Some(operation) = destructive_operation(&cli.command) {
+        rgit::safety::snapshot_before(operation, &config)?;
+    }
+
+    if !cli.dry_run && fetches_in_background(&cli.command) {
+        if let Ok(rgit) = RgitCore::new(false) {
+            commands::fetch::auto_fetch_in_background(&rgit, &config);
+        }
+    }
+
+    // Output goes to the pager until this is dropped at the end of the command
+    let _pager = if !cli.no_pager && pages_output(&cli.command) {
+        rgit::pager::start(&config)
+    } else {
+        None
+    };
+
     match &cli.command {
         // Repository initialization commands
         Commands::Init(args) => {
             commands::init::execute(args, &config).await
         }
         Commands::Clone(args) => {
-            let rgit = RgitCore::new(cli.verbose)?;
-            commands::clone::execute(args, &rgit, &config).await
+            commands::clone::execute(args, &config).await
         }
 
         // Core Git operations
+        Commands::Status(args) if args.all_repos.is_some() => {
+            commands::status::execute_all_repos(args, &config).await
+        }
         Commands::Status(args) => {
             let rgit = RgitCore::new(cli.verbose)?;
             commands::status::execute(args, &rgit, &config).await
@@ -111,10 +326,26 @@ async fn execute_command(cli: Cli, config: Config) -> Result<()> {
             let mut rgit = RgitCore::new(cli.verbose)?;
             commands::add::execute(args, &mut rgit, &config).await
         }
+        Commands::Unstage(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::unstage::execute(args, &rgit, &config).await
+        }
         Commands::Commit(args) => {
             let rgit = RgitCore::new(cli.verbose)?;
             commands::commit::execute(args, &rgit, &config).await
         }
+        Commands::Mv(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::mv::execute(args, &rgit, &config).await
+        }
+        Commands::Rm(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::rm::execute(args, &rgit, &config).await
+        }
+        Commands::Reset(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::reset::execute(args, &rgit, &config).await
+        }
         Commands::Push(args) => {
             let rgit = RgitCore::new(cli.verbose)?;
             commands::push::execute(args, &rgit, &config).await
@@ -134,42 +365,66 @@ async fn execute_command(cli: Cli, config: Config) -> Result<()> {
             commands::branch::execute(args, &rgit, &config).await
         }
         Commands::Checkout(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::checkout::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::checkout::execute(args, &rgit, &config).await
+        }
+        Commands::Switch(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::switch::execute(args, &rgit, &config).await
         }
         Commands::Merge(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::merge::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::merge::execute(args, &rgit, &config).await
         }
         Commands::Rebase(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::rebase::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::rebase::execute(args, &rgit, &config).await
         }
 
         // History and information
         Commands::Log(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::log::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::log::execute(args, &rgit, &config).await
         }
         Commands::Diff(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::diff::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::diff::execute(args, &rgit, &config).await
         }
         Commands::Show(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::show::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::show::execute(args, &rgit, &config).await
+        }
+        Commands::Cat(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::cat::execute(args, &rgit, &config).await
+        }
+        Commands::Ls(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::ls::execute(args, &rgit, &config).await
+        }
+        Commands::Hash(args) => {
+            commands::hash::execute(args, &config).await
+        }
+        Commands::Object(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::object::execute(args, &rgit, &config).await
         }
 
         Commands::Blame(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::blame::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::blame::execute(args, &rgit, &config).await
+        }
+        Commands::Who(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::who::execute(args, &rgit, &config).await
+        }
+        Commands::Todos(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::todos::execute(args, &rgit, &config).await
+        }
+        Commands::Owners(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::owners::execute(args, &rgit, &config).await
         }
 
         // Submodule operations
@@ -177,23 +432,35 @@ async fn execute_command(cli: Cli, config: Config) -> Result<()> {
             let rgit = RgitCore::new(cli.verbose)?;
             commands::submodule::execute(args, &rgit, &config).await
         }
+        Commands::Subtree(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::subtree::execute(args, &rgit, &config).await
+        }
 
         // Advanced operations
         Commands::Stash(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::stash::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::stash::execute(args, &rgit, &config).await
+        }
+        Commands::Wip(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::wip::execute(args, &rgit, &config).await
         }
         Commands::Tag(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::tag::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::tag::execute(args, &rgit, &config).await
         }
         Commands::Remote(args) => {
         //    let rgit = RgitCore::new(cli.verbose)?;
         //    commands::remote::execute(args, &rgit, &config).await
             todo!()
         }
+        Commands::LsRemote(args) => {
+            commands::ls_remote::execute(args, &config).await
+        }
+        Commands::Mirror(args) => {
+            commands::mirror::execute(args, &config).await
+        }
 
         // Ease-of-use commands
         Commands::Sync(args) => {
@@ -205,37 +472,51 @@ async fn execute_command(cli: Cli, config: Config) -> Result<()> {
             commands::quick_commit::execute(args, &rgit, &config).await
         }
         Commands::Undo(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::undo::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::undo::execute(args, &rgit, &config).await
         }
         Commands::Clean(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::clean::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::clean::execute(args, &rgit, &config).await
         }
 
         // Utility commands
-        Commands::Doctor => {
-            commands::doctor::execute(&config).await
+        Commands::Doctor(args) => {
+            commands::doctor::execute(args, &config).await
         }
         Commands::Learn(args) => {
             commands::learn::execute(args, &config).await
         }
+        Commands::Explain(args) => {
+            commands::explain::execute(args, &config).await
+        }
+        Commands::Config(args) => {
+            commands::config::execute(args, &config).await
+        }
+        Commands::Whoami => {
+            commands::whoami::execute(&config).await
+        }
+        Commands::Credential(args) => {
+            commands::credential::execute(args, &config).await
+        }
+        Commands::Workspace(args) => {
+            commands::workspace::execute(args, &config).await
+        }
         Commands::Resolve => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::resolve::execute(&rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::resolve::execute(&rgit, &config).await
+        }
+        Commands::Mergetool(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::mergetool::execute(args, &rgit, &config).await
         }
         Commands::Backup(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::backup::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::backup::execute(args, &rgit, &config).await
         }
         Commands::Restore(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::restore::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::restore::execute(args, &rgit, &config).await
         }
 
         // Advanced Git operations
@@ -245,36 +526,64 @@ async fn execute_command(cli: Cli, config: Config) -> Result<()> {
             todo!()
         }
         Commands::Reflog(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::reflog::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::reflog::execute(args, &rgit, &config).await
+        }
+        Commands::Recover(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::recover::execute(args, &rgit, &config).await
         }
         Commands::Gc(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::gc::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::gc::execute(args, &rgit, &config).await
         }
         Commands::Fsck(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::fsck::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::fsck::execute(args, &rgit, &config).await
+        }
+        Commands::Audit(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::audit::execute(args, &rgit, &config).await
+        }
+        Commands::Scan(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::scan::execute(args, &rgit, &config).await
+        }
+        Commands::Filter(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::filter::execute(args, &rgit, &config).await
+        }
+        Commands::Import(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::import::execute(args, &rgit, &config).await
+        }
+        Commands::Export(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::export::execute(args, &rgit, &config).await
         }
         Commands::CherryPick(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::cherry_pick::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::cherry_pick::execute(args, &rgit, &config).await
+        }
+        Commands::Revert(args) => {
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::revert::execute(args, &rgit, &config).await
         }
         Commands::Grep(args) => {
-        //    let rgit = RgitCore::new(cli.verbose)?;
-        //    commands::grep::execute(args, &rgit, &config).await
-            todo!()
+            let rgit = RgitCore::new(cli.verbose)?;
+            commands::grep::execute(args, &rgit, &config).await
         }
     }
 }
 
 /// Print formatted error messages with helpful suggestions
 fn print_error(error: &anyhow::Error) {
-    eprintln!("{} {}", "❌".red().bold(), "Error:".red().bold());
+    let rgit_error = error.chain().find_map(|cause| cause.downcast_ref::<RgitError>());
+    match rgit_error {
+        Some(rgit_error) => ui_eprintln!("{} {} {}", "❌".red().bold(), t!("error-heading").red().bold(),
+                                         format!("[{}]:", rgit_error.code()).red().bold()),
+        None => ui_eprintln!("{} {}", "❌".red().bold(), format!("{}:", t!("error-heading")).red().bold()),
+    }
     
     // Print the main error
     eprintln!("   {}", error.to_string().white());
@@ -282,44 +591,22 @@ fn print_error(error: &anyhow::Error) {
     // Print the error chain
     let mut current = error.source();
     while let Some(err) = current {
-        eprintln!("   {} {}", "└─".dimmed(), err.to_string().dimmed());
+        ui_eprintln!("   {} {}", "└─".dimmed(), err.to_string().dimmed());
         current = err.source();
     }
     
-    // Print helpful suggestions based on error type
-    if let Some(rgit_error) = error.downcast_ref::<RgitError>() {
-        print_error_suggestions(rgit_error);
+    // Print the most likely fix from the error catalog
+    if let Some(fix) = rgit_error.and_then(|e| e.recovery_suggestions().first().copied()) {
+        ui_eprintln!("   {} {}", "💡".yellow(), fix.yellow());
     }
     
     eprintln!();
-    eprintln!("{} Use {} for help or {} for tutorials", 
-             "💡".yellow(), 
-             "rgit --help".cyan(), 
-             "rgit learn".cyan());
-}
-
-/// Print context-specific suggestions for different error types
-fn print_error_suggestions(error: &RgitError) {
-    let suggestion = match error {
-        RgitError::NotInRepository => {
-            "Try running 'rgit init' to create a new repository or navigate to an existing one"
-        }
-        RgitError::SubmoduleError(_) => {
-            "Use 'rgit submodule status' to check submodule health or 'rgit doctor' for diagnostics"
-        }
-        RgitError::MergeConflict(_) => {
-            "Use 'rgit resolve' for interactive conflict resolution or 'rgit status' to see conflicts"
-        }
-        RgitError::AuthenticationError(_) => {
-            "Check your Git credentials or use 'rgit doctor' to verify remote connections"
-        }
-        RgitError::NetworkError(_) => {
-            "Check your internet connection and remote URL configuration"
-        }
-        _ => return,
-    };
-    
-    eprintln!("   {} {}", "💡".yellow(), suggestion.yellow());
+    match rgit_error {
+        Some(rgit_error) => ui_eprintln!("{} {}", "💡".yellow(),
+                                         t!("error-explain-hint", command = format!("rgit explain {}", rgit_error.code()).cyan())),
+        None => ui_eprintln!("{} {}", "💡".yellow(),
+                             t!("error-help-hint", help = "rgit --help".cyan(), learn = "rgit learn".cyan())),
+    }
 }
 
 #[cfg(test)]
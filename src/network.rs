@@ -0,0 +1,438 @@
+//! How rgit reaches remotes: retrying transfers on flaky networks,
+//! and rewriting remote URLs.
+//!
+//! A transfer that fails with a transient network error (a dropped
+//! connection, a timeout, a 5xx from the server) is tried again up to
+//! `fetch.retries` times, waiting twice as long before each attempt.
+//! Authentication failures and other errors are reported right away, since
+//! trying again can't fix them.
+//!
+//! libgit2 can't pick up a half-received pack, so a retried fetch negotiates
+//! with the remote again: whatever earlier attempts stored is advertised as
+//! already present and isn't downloaded twice. A failed clone is removed by
+//! libgit2 and starts over.
+//!
+//! URLs are rewritten with the `[url."<base>"]` sections of the config, like
+//! git's `url.<base>.insteadOf` and `pushInsteadOf`, and then switched to the
+//! protocol a `[remote.<name>]` section prefers. The repository's own remote
+//! configuration is left as it is.
+//!
+//! [`credentials`] answers libgit2's requests for credentials the same way
+//! for every transfer.
+
+use colored::*;
+use git2::{Cred, CredentialType, Direction, ErrorClass, ErrorCode, Remote, Repository};
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::config::Config;
+use crate::credential::{self, Credential};
+use crate::error::{RgitError, RgitResult};
+use crate::interactive::InteractivePrompt;
+use crate::utils::parse_git_url;
+
+/// Wait before the first retry; each later retry waits twice as long
+const FIRST_BACKOFF: Duration = Duration::from_secs(1);
+/// Longest wait between attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// What kind of failure a network operation ran into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Failure {
+    /// The connection dropped or timed out; worth another try
+    Transient,
+    /// The remote rejected the credentials
+    Auth,
+    /// Anything else, like a missing repository or a bad certificate
+    Permanent,
+}
+
+/// Work out whether `error` is worth retrying. libgit2's error code and
+/// class decide first; the message only says more about transport errors,
+/// where it carries the remote's answer.
+pub fn classify(error: &git2::Error) -> Failure {
+    let message = error.message().to_lowercase();
+    let mentions = |needles: &[&str]| needles.iter().any(|needle| message.contains(needle));
+
+    if error.code() == ErrorCode::Auth {
+        return Failure::Auth;
+    }
+    if error.code() == ErrorCode::Certificate || error.class() == ErrorClass::Ssl {
+        return Failure::Permanent;
+    }
+    if error.code() == ErrorCode::Eof {
+        return Failure::Transient;
+    }
+    match error.class() {
+        ErrorClass::Net | ErrorClass::Http | ErrorClass::Ssh
+            if mentions(&["authenticat", "permission denied", "401", "403", "credentials"]) => Failure::Auth,
+        ErrorClass::Net | ErrorClass::Os | ErrorClass::Http | ErrorClass::Ssh => {
+            // A local file rgit can't write is no network error
+            if mentions(&["404", "not found", "does not appear to be a git repository", "permission denied"]) {
+                Failure::Permanent
+            } else if error.class() != ErrorClass::Ssh
+                || mentions(&["timed out", "timeout", "connection", "reset", "broken pipe", "eof"])
+            {
+                Failure::Transient
+            } else {
+                Failure::Permanent
+            }
+        }
+        _ if mentions(&["early eof", "timed out", "connection reset", "unexpected disconnect"]) => Failure::Transient,
+        _ => Failure::Permanent,
+    }
+}
+
+/// How long to wait before retry number `retry` (counting from 1)
+pub fn backoff(retry: u32) -> Duration {
+    FIRST_BACKOFF
+        .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// Run `attempt` until it succeeds, retrying transient network errors up to
+/// `fetch.retries` times. Authentication failures become
+/// [`RgitError::AuthenticationError`], transient errors that outlast the
+/// retries [`RgitError::NetworkError`], and any other error goes through
+/// `other`.
+pub async fn with_retries<T>(
+    config: &Config,
+    mut attempt: impl FnMut() -> Result<T, git2::Error>,
+    other: impl FnOnce(git2::Error) -> RgitError,
+) -> RgitResult<T> {
+    let retries = config.fetch.retries;
+    let mut retry = 0;
+    loop {
+        let error = match attempt() {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        match classify(&error) {
+            Failure::Auth => {
+                return Err(RgitError::AuthenticationError(format!(
+                    "{} (not retried: the remote rejected the credentials)",
+                    error.message()
+                )));
+            }
+            Failure::Transient if retry < retries => {
+                retry += 1;
+                let wait = backoff(retry);
+                ui_println!(
+                    "{} Network error: {}; retrying in {}s (attempt {} of {})",
+                    "⚠️".yellow(),
+                    error.message(),
+                    wait.as_secs(),
+                    retry + 1,
+                    retries + 1
+                );
+                tokio::time::sleep(wait).await;
+            }
+            Failure::Transient => {
+                let attempts = retries + 1;
+                return Err(RgitError::NetworkError(format!(
+                    "{} (gave up after {} attempt{})",
+                    error.message(),
+                    attempts,
+                    if attempts == 1 { "" } else { "s" }
+                )));
+            }
+            Failure::Permanent => return Err(other(error)),
+        }
+    }
+}
+
+/// [`with_retries`] for code on a worker thread rather than in the async
+/// runtime, like workspace and submodule jobs. Waits between attempts block
+/// the thread, which only holds up that one job.
+pub fn with_retries_blocking<T>(
+    config: &Config,
+    attempt: impl FnMut() -> Result<T, git2::Error>,
+    other: impl FnOnce(git2::Error) -> RgitError,
+) -> RgitResult<T> {
+    let retrying = with_retries(config, attempt, other);
+    match tokio::runtime::Handle::try_current() {
+        Ok(runtime) => runtime.block_on(retrying),
+        Err(_) => tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .map_err(|e| RgitError::OperationFailed(format!("couldn't start a timer for retries: {}", e)))?
+            .block_on(retrying),
+    }
+}
+
+/// Apply the configured rewrites to `url`. Like git, the longest matching
+/// prefix wins, and for pushes a `push_instead_of` match comes first.
+pub fn rewrite_url(config: &Config, url: &str, direction: Direction) -> String {
+    let longest = |prefixes: fn(&crate::config::UrlRewrite) -> &Vec<String>| {
+        config.url.iter()
+            .flat_map(|(base, rewrite)| prefixes(rewrite).iter().map(move |prefix| (base, prefix)))
+            .filter(|(_, prefix)| !prefix.is_empty() && url.starts_with(prefix.as_str()))
+            .max_by(|(a_base, a), (b_base, b)| a.len().cmp(&b.len()).then(b_base.cmp(a_base)))
+            .map(|(base, prefix)| format!("{}{}", base, &url[prefix.len()..]))
+    };
+    let pushed = match direction {
+        Direction::Push => longest(|rewrite| &rewrite.push_instead_of),
+        Direction::Fetch => None,
+    };
+    pushed.or_else(|| longest(|rewrite| &rewrite.instead_of)).unwrap_or_else(|| url.to_string())
+}
+
+/// `url` over `protocol` ("https" or "ssh"), or `None` when it's a URL that
+/// can't be switched, like a local path
+pub fn with_protocol(url: &str, protocol: &str) -> Option<String> {
+    let info = parse_git_url(url)?;
+    match (info.protocol.as_str(), protocol) {
+        (current, wanted) if current == wanted => Some(url.to_string()),
+        ("https", "ssh") => Some(format!("git@{}:{}.git", info.host, info.path)),
+        ("ssh", "https") => Some(format!("https://{}/{}.git", info.host, info.path)),
+        _ => None,
+    }
+}
+
+/// The URL rgit uses to fetch from or push to `remote`
+pub fn remote_url(config: &Config, remote: &Remote, direction: Direction) -> Option<String> {
+    let push_url = remote.pushurl().filter(|_| direction == Direction::Push);
+    let url = match push_url {
+        // Like git, an explicit push URL isn't subject to `push_instead_of`
+        Some(url) => rewrite_url(config, url, Direction::Fetch),
+        None => rewrite_url(config, remote.url()?, direction),
+    };
+    let protocol = remote.name()
+        .and_then(|name| config.remote.get(name))
+        .and_then(|preferences| preferences.protocol.as_deref());
+    Some(match protocol {
+        Some(protocol) => with_protocol(&url, protocol).unwrap_or(url),
+        None => url,
+    })
+}
+
+/// `remote`, or when rewriting changes its URL an in-memory remote for the
+/// new one. In-memory remotes have no name and no configured refspecs, so
+/// callers pass explicit refspecs and update remote-tracking refs of pushes
+/// themselves.
+pub fn rewritten<'repo>(
+    repo: &'repo Repository,
+    remote: Remote<'repo>,
+    config: &Config,
+    direction: Direction,
+) -> Result<Remote<'repo>, git2::Error> {
+    let current = match direction {
+        Direction::Push => remote.pushurl().or_else(|| remote.url()),
+        Direction::Fetch => remote.url(),
+    };
+    match remote_url(config, &remote, direction) {
+        Some(url) if Some(url.as_str()) != current => repo.remote_anonymous(&url),
+        _ => Ok(remote),
+    }
+}
+
+/// A detached remote for `url`, rewritten like the URL of a configured one.
+/// For URLs given on the command line outside a repository.
+pub fn detached(config: &Config, url: &str, direction: Direction) -> Result<Remote<'static>, git2::Error> {
+    let remote = Remote::create_detached(url)?;
+    match remote_url(config, &remote, direction) {
+        Some(rewritten) if rewritten != url => Remote::create_detached(rewritten.as_str()),
+        _ => Ok(remote),
+    }
+}
+
+/// A credentials callback for libgit2: keys from the SSH agent, and for
+/// HTTPS a username and password from the credential cache, git's
+/// credential helpers or a prompt. Typed credentials are cached when
+/// `credential.cache` is on. libgit2 asks again for the same URL when the
+/// remote rejected the last answer, which is then forgotten and prompted for.
+pub fn credentials(
+    config: &Config,
+) -> impl FnMut(&str, Option<&str>, CredentialType) -> Result<Cred, git2::Error> + '_ {
+    let mut answered = HashSet::new();
+    move |url, username_from_url, allowed| {
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"));
+        }
+        if !allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            return Cred::default();
+        }
+
+        let key = credential::cache_key(url);
+        let rejected = !answered.insert(url.to_string());
+        if let Some(key) = key.as_deref().filter(|_| config.credential.cache) {
+            if rejected {
+                credential::erase(config, key);
+            } else if let Some(cached) = credential::get(config, key) {
+                return Cred::userpass_plaintext(&cached.username, &cached.password);
+            }
+        }
+        if !rejected {
+            if let Ok(cred) = git2::Config::open_default().and_then(|git| Cred::credential_helper(&git, url, username_from_url)) {
+                return Ok(cred);
+            }
+        }
+        if !config.is_interactive() {
+            return Err(git2::Error::new(ErrorCode::Auth, ErrorClass::Callback, format!("authentication required for {}", url)));
+        }
+
+        let typed = prompt_credential(url, username_from_url)
+            .map_err(|e| git2::Error::new(ErrorCode::Auth, ErrorClass::Callback, e.to_string()))?;
+        if let Some(key) = key.as_deref().filter(|_| config.credential.cache) {
+            if let Err(e) = credential::store(config, key, typed.clone()) {
+                debug!("Failed to cache credentials: {}", e);
+            }
+        }
+        Cred::userpass_plaintext(&typed.username, &typed.password)
+    }
+}
+
+fn prompt_credential(url: &str, username: Option<&str>) -> anyhow::Result<Credential> {
+    ui_println!("{} Credentials for {}", "🔑".yellow(), url.cyan());
+    let username = match username {
+        Some(username) => username.to_string(),
+        None => InteractivePrompt::new().with_message("Username").input()?,
+    };
+    let password = InteractivePrompt::new().with_message("Password").password()?;
+    Ok(Credential { username, password })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: ErrorCode, class: ErrorClass, message: &str) -> git2::Error {
+        git2::Error::new(code, class, message)
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Net, "failed to connect: Connection timed out")), Failure::Transient);
+        assert_eq!(classify(&error(ErrorCode::Eof, ErrorClass::Indexer, "early EOF")), Failure::Transient);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Http, "unexpected http status code: 503")), Failure::Transient);
+        assert_eq!(classify(&error(ErrorCode::Auth, ErrorClass::Http, "too many redirects or authentication replays")), Failure::Auth);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Ssh, "Failed to authenticate SSH session")), Failure::Auth);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Http, "unexpected http status code: 404")), Failure::Permanent);
+        assert_eq!(classify(&error(ErrorCode::Certificate, ErrorClass::Ssl, "certificate has expired")), Failure::Permanent);
+        assert_eq!(classify(&error(ErrorCode::NotFound, ErrorClass::Reference, "reference not found")), Failure::Permanent);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Ssh, "Permission denied (publickey)")), Failure::Auth);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Http, "unexpected http status code: 403")), Failure::Auth);
+        // Only transport errors are about credentials
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Os, "failed to make directory '.git/objects/ab': Permission denied")), Failure::Permanent);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Filesystem, "permission denied")), Failure::Permanent);
+        assert_eq!(classify(&error(ErrorCode::GenericError, ErrorClass::Config, "invalid credentials helper")), Failure::Permanent);
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_with_retries() {
+        let mut config = Config::default();
+        config.fetch.retries = 0;
+        let other = |error: git2::Error| RgitError::FetchFailed(error.message().to_string());
+
+        let mut calls = 0;
+        let result = with_retries(&config, || {
+            calls += 1;
+            Err::<(), _>(error(ErrorCode::Auth, ErrorClass::Http, "authentication required"))
+        }, other).await;
+        assert!(matches!(result, Err(RgitError::AuthenticationError(_))));
+        assert_eq!(calls, 1);
+
+        let result = with_retries(&config, || Err::<(), _>(error(ErrorCode::Eof, ErrorClass::Net, "early EOF")), other).await;
+        assert!(matches!(result, Err(RgitError::NetworkError(message)) if message.contains("1 attempt)")));
+
+        let result = with_retries(&config, || Err::<(), _>(error(ErrorCode::NotFound, ErrorClass::Http, "unexpected http status code: 404")), other).await;
+        assert!(matches!(result, Err(RgitError::FetchFailed(_))));
+
+        assert_eq!(with_retries(&config, || Ok(7), other).await.unwrap(), 7);
+    }
+
+    #[test]
+    fn test_with_retries_blocking() {
+        let other = |error: git2::Error| RgitError::FetchFailed(error.message().to_string());
+        assert_eq!(with_retries_blocking(&Config::default(), || Ok(7), other).unwrap(), 7);
+
+        // From a blocking task of a running runtime, as workspace jobs call it
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async {
+            tokio::task::spawn_blocking(move || {
+                let mut config = Config::default();
+                config.fetch.retries = 0;
+                with_retries_blocking(&config, || Err::<(), _>(error(ErrorCode::Eof, ErrorClass::Net, "early EOF")), other)
+            }).await
+        });
+        assert!(matches!(result.unwrap(), Err(RgitError::NetworkError(_))));
+    }
+
+    fn rewriting_config() -> Config {
+        let mut config = Config::default();
+        config.url.insert("git@github.com:".to_string(), crate::config::UrlRewrite {
+            instead_of: vec!["https://github.com/".to_string()],
+            push_instead_of: vec![],
+        });
+        config.url.insert("git@github.com:mirror/".to_string(), crate::config::UrlRewrite {
+            instead_of: vec!["https://github.com/org/".to_string()],
+            push_instead_of: vec!["gh:".to_string()],
+        });
+        config.url.insert("ssh://push.example.com/".to_string(), crate::config::UrlRewrite {
+            instead_of: vec![],
+            push_instead_of: vec!["https://example.com/".to_string()],
+        });
+        config
+    }
+
+    #[test]
+    fn test_rewrite_url() {
+        let config = rewriting_config();
+        assert_eq!(rewrite_url(&config, "https://github.com/me/tool.git", Direction::Fetch), "git@github.com:me/tool.git");
+        // The longest matching prefix wins
+        assert_eq!(rewrite_url(&config, "https://github.com/org/tool", Direction::Fetch), "git@github.com:mirror/tool");
+        assert_eq!(rewrite_url(&config, "https://example.com/x.git", Direction::Fetch), "https://example.com/x.git");
+        assert_eq!(rewrite_url(&config, "https://example.com/x.git", Direction::Push), "ssh://push.example.com/x.git");
+        assert_eq!(rewrite_url(&config, "gh:tool", Direction::Push), "git@github.com:mirror/tool");
+        assert_eq!(rewrite_url(&config, "/srv/git/tool", Direction::Push), "/srv/git/tool");
+    }
+
+    #[test]
+    fn test_with_protocol() {
+        assert_eq!(with_protocol("https://github.com/me/tool.git", "ssh").as_deref(), Some("git@github.com:me/tool.git"));
+        assert_eq!(with_protocol("git@gitlab.com:group/sub/tool.git", "https").as_deref(), Some("https://gitlab.com/group/sub/tool.git"));
+        assert_eq!(with_protocol("git@github.com:me/tool", "ssh").as_deref(), Some("git@github.com:me/tool"));
+        assert_eq!(with_protocol("/srv/git/tool", "ssh"), None);
+    }
+
+    #[test]
+    fn test_rewritten_remote() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let repo = Repository::init(dir.path()).unwrap();
+        repo.remote("origin", "https://github.com/me/tool.git").unwrap();
+        repo.remote("local", "/srv/git/tool").unwrap();
+
+        let mut config = rewriting_config();
+        let remote = rewritten(&repo, repo.find_remote("origin").unwrap(), &config, Direction::Fetch).unwrap();
+        assert_eq!(remote.url(), Some("git@github.com:me/tool.git"));
+        assert_eq!(remote.name(), None);
+        // The repository's configuration is untouched
+        assert_eq!(repo.find_remote("origin").unwrap().url(), Some("https://github.com/me/tool.git"));
+
+        config.url.clear();
+        config.remote.insert("origin".to_string(), crate::config::RemotePreferences { protocol: Some("ssh".to_string()) });
+        let remote = repo.find_remote("origin").unwrap();
+        assert_eq!(remote_url(&config, &remote, Direction::Push).as_deref(), Some("git@github.com:me/tool.git"));
+
+        let remote = rewritten(&repo, repo.find_remote("local").unwrap(), &config, Direction::Fetch).unwrap();
+        assert_eq!(remote.name(), Some("local"));
+    }
+
+    #[test]
+    fn test_detached_remote() {
+        let config = rewriting_config();
+        let remote = detached(&config, "https://github.com/me/tool.git", Direction::Fetch).unwrap();
+        assert_eq!(remote.url(), Some("git@github.com:me/tool.git"));
+        let remote = detached(&config, "/srv/git/tool", Direction::Fetch).unwrap();
+        assert_eq!(remote.url(), Some("/srv/git/tool"));
+    }
+}
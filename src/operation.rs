@@ -0,0 +1,183 @@
+//! Detection of multi-step operations left in progress.
+//!
+//! A rebase, merge, cherry-pick or revert that stops on conflicts leaves
+//! state behind in the git directory. [`InProgress::detect`] reads it back so
+//! any command can remind the user where they are and how to move on.
+
+use anyhow::Result;
+use colored::*;
+use git2::{Oid, Repository, RepositoryState};
+
+use crate::commands::cherry_pick::conflicted_paths;
+use crate::output::decoration;
+use crate::sequencer;
+use crate::utils::shorten_oid;
+
+/// The kind of operation in progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationKind {
+    Rebase,
+    Merge,
+    CherryPick,
+    Revert,
+}
+
+impl OperationKind {
+    /// The operation git's own state files describe, if any
+    pub fn from_state(state: RepositoryState) -> Option<Self> {
+        match state {
+            RepositoryState::Merge => Some(OperationKind::Merge),
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some(OperationKind::CherryPick),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(OperationKind::Revert),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+                Some(OperationKind::Rebase)
+            }
+            _ => None,
+        }
+    }
+
+    /// The rgit subcommand that drives this operation
+    pub fn command(&self) -> &'static str {
+        match self {
+            OperationKind::Rebase => "rebase",
+            OperationKind::Merge => "merge",
+            OperationKind::CherryPick => "cherry-pick",
+            OperationKind::Revert => "revert",
+        }
+    }
+
+    pub fn continue_command(&self) -> String {
+        format!("rgit {} --continue", self.command())
+    }
+
+    pub fn abort_command(&self) -> String {
+        format!("rgit {} --abort", self.command())
+    }
+
+    /// Merges are a single step and reverts don't support skipping
+    pub fn skip_command(&self) -> Option<String> {
+        match self {
+            OperationKind::Rebase | OperationKind::CherryPick => Some(format!("rgit {} --skip", self.command())),
+            OperationKind::Merge | OperationKind::Revert => None,
+        }
+    }
+}
+
+/// An interrupted operation and how far it got
+#[derive(Debug, Clone)]
+pub struct InProgress {
+    pub kind: OperationKind,
+    /// 1-based step in progress and the number of steps, for sequences
+    pub step: Option<(usize, usize)>,
+    /// The commit being applied or merged
+    pub current: Option<Oid>,
+    /// Paths still conflicted in the index
+    pub conflicts: Vec<String>,
+}
+
+impl InProgress {
+    /// Read the operation in progress, if any. rgit's own sequence files
+    /// come first since a `--no-commit` pick leaves no git state behind.
+    pub fn detect(repo: &Repository) -> Result<Option<Self>> {
+        let (kind, step, current) = if let Some((kind, sequence)) = sequencer::in_progress(repo)? {
+            (kind, Some((sequence.position(), sequence.total())), sequence.current())
+        } else {
+            match OperationKind::from_state(repo.state()) {
+                Some(OperationKind::Rebase) => {
+                    let (step, current) = rebase_step(repo);
+                    (OperationKind::Rebase, step, current)
+                }
+                Some(OperationKind::Merge) => (OperationKind::Merge, None, read_head(repo, "MERGE_HEAD")),
+                Some(OperationKind::CherryPick) => (OperationKind::CherryPick, None, read_head(repo, "CHERRY_PICK_HEAD")),
+                Some(OperationKind::Revert) => (OperationKind::Revert, None, read_head(repo, "REVERT_HEAD")),
+                None => return Ok(None),
+            }
+        };
+
+        let conflicts = conflicted_paths(&repo.index()?)?;
+        Ok(Some(InProgress { kind, step, current, conflicts }))
+    }
+
+    /// A short block describing the operation and the ways out of it. The
+    /// lines carry a commit summary, so their glyphs are already decorations.
+    pub fn banner_lines(&self, repo: &Repository) -> Vec<String> {
+        let step = match self.step {
+            Some((step, total)) => format!(" (step {}/{})", step, total),
+            None => String::new(),
+        };
+        let mut lines = vec![format!("{} {} in progress{}", decoration("🚧").yellow(), self.kind.command().bold(), step)];
+
+        if let Some(id) = self.current {
+            let summary = repo.find_commit(id).ok()
+                .and_then(|c| c.summary().map(String::from))
+                .unwrap_or_default();
+            lines.push(format!("   {} {} {}", "at".dimmed(), shorten_oid(&id, 8).yellow(), summary));
+        }
+        if !self.conflicts.is_empty() {
+            lines.push(format!("   {} {} conflicted file{}; {} to fix them",
+                               decoration("⚠️").yellow(),
+                               self.conflicts.len(),
+                               if self.conflicts.len() == 1 { "" } else { "s" },
+                               "rgit resolve".cyan()));
+        }
+
+        let mut next = vec![self.kind.continue_command().cyan().to_string()];
+        next.extend(self.kind.skip_command().map(|c| c.cyan().to_string()));
+        next.push(self.kind.abort_command().cyan().to_string());
+        lines.push(format!("   {} {}", decoration("💡").blue(), next.join(&decoration(" · "))));
+        lines
+    }
+}
+
+/// Step and current commit of a rebase, when libgit2 can read its state
+fn rebase_step(repo: &Repository) -> (Option<(usize, usize)>, Option<Oid>) {
+    let Ok(mut rebase) = repo.open_rebase(None) else {
+        return (None, None);
+    };
+    let total = rebase.len();
+    match rebase.operation_current() {
+        Some(current) => {
+            let id = rebase.nth(current).map(|op| op.id());
+            (Some((current + 1, total)), id)
+        }
+        None => (None, None),
+    }
+}
+
+fn read_head(repo: &Repository, name: &str) -> Option<Oid> {
+    repo.refname_to_id(name).ok()
+}
+
+/// Print a banner for the operation in progress, if any
+pub fn show_banner(repo: &Repository) {
+    if let Ok(Some(in_progress)) = InProgress::detect(repo) {
+        for line in in_progress.banner_lines(repo) {
+            eprintln!("{}", line);
+        }
+        eprintln!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+
+    #[test]
+    fn test_detects_merge_with_conflicts() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "base\n", "Base");
+        assert!(InProgress::detect(repo.repo()).unwrap().is_none());
+
+        repo.conflict("a.txt");
+        let in_progress = InProgress::detect(repo.repo()).unwrap().unwrap();
+        assert_eq!(in_progress.kind, OperationKind::Merge);
+        assert_eq!(in_progress.conflicts, vec!["a.txt"]);
+        assert!(in_progress.current.is_some());
+        assert_eq!(in_progress.kind.skip_command(), None);
+
+        let banner = in_progress.banner_lines(repo.repo()).join("\n");
+        assert!(banner.contains("rgit merge --continue"));
+        assert!(banner.contains("rgit merge --abort"));
+    }
+}
@@ -0,0 +1,308 @@
+//! Accessible output.
+//!
+//! rgit's own messages are printed with [`ui_print!`], [`ui_println!`],
+//! [`ui_eprint!`] and [`ui_eprintln!`]. Normally they print what they're
+//! given. With `ui.accessible` (or `TERM=dumb`) the message goes through
+//! [`plain`] first: emoji and box drawing become ASCII labels and progress
+//! lines redrawn in place with `\r` are dropped, since both garble screen
+//! readers and dumb terminals. With `ui.icons = false` only the glyphs are
+//! replaced.
+//!
+//! Only the format string and arguments that are nothing but glyphs (like
+//! `"✅".green()`) are rewritten. Every other argument is printed byte for
+//! byte, so a commit summary, branch or path interpolated into a message
+//! keeps its own emoji and arrows. Lines built ahead of time mix both, so
+//! their glyphs go through [`decoration`] and they're printed with the
+//! standard macros, like log graph rails next to commit messages.
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Mark where an interpolated argument starts and ends while a message is
+/// filtered; private-use characters nothing else prints
+const CONTENT_START: char = '\u{E000}';
+const CONTENT_END: char = '\u{E001}';
+
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+static ICONS: AtomicBool = AtomicBool::new(true);
+
+/// Switch accessible output on or off for the rest of the process
+pub fn set_accessible(enabled: bool) {
+    ACCESSIBLE.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_accessible() -> bool {
+    ACCESSIBLE.load(Ordering::Relaxed)
+}
+
+/// Show or replace emoji and other icons
+pub fn set_icons(enabled: bool) {
+    ICONS.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether messages are rewritten before printing
+fn filtering() -> bool {
+    is_accessible() || !ICONS.load(Ordering::Relaxed)
+}
+
+/// The text as it should be printed, or `None` to print it as is
+fn rendered(args: fmt::Arguments) -> Option<String> {
+    if !filtering() {
+        return None;
+    }
+    Some(filter(&args.to_string(), is_accessible()))
+}
+
+/// Replace the glyphs in `text` outside the content markers and drop the
+/// markers
+fn filter(text: &str, accessible: bool) -> String {
+    // A line that starts with `\r` and doesn't end redraws the line before
+    if accessible && text.starts_with('\r') && !text.ends_with('\n') {
+        return String::new();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(CONTENT_START) {
+        out.push_str(&replace_glyphs(&rest[..start], accessible));
+        rest = &rest[start + CONTENT_START.len_utf8()..];
+        let end = rest.find(CONTENT_END).unwrap_or(rest.len());
+        out.push_str(&rest[..end]);
+        rest = rest.get(end + CONTENT_END.len_utf8()..).unwrap_or("");
+    }
+    out.push_str(&replace_glyphs(rest, accessible));
+    out
+}
+
+/// An argument of the `ui_print!` macros. While messages are filtered it
+/// marks itself as content to print as is, unless it is only decoration.
+#[doc(hidden)]
+pub struct Arg<'a, T: ?Sized>(pub &'a T);
+
+impl<T: fmt::Display + ?Sized> fmt::Display for Arg<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !filtering() || is_decoration(&self.0.to_string()) {
+            return self.0.fmt(f);
+        }
+        f.write_char(CONTENT_START)?;
+        self.0.fmt(f)?;
+        f.write_char(CONTENT_END)
+    }
+}
+
+impl<T: fmt::Debug + ?Sized> fmt::Debug for Arg<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !filtering() {
+            return self.0.fmt(f);
+        }
+        f.write_char(CONTENT_START)?;
+        self.0.fmt(f)?;
+        f.write_char(CONTENT_END)
+    }
+}
+
+/// Whether `text` is only glyphs, whitespace and color codes
+fn is_decoration(text: &str) -> bool {
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // A color code runs to its final letter
+            '\u{1b}' => {
+                if !chars.by_ref().any(|c| c.is_ascii_alphabetic()) {
+                    return false;
+                }
+            }
+            '\u{FE0F}' | '\u{200D}' => {}
+            c if c.is_whitespace() || replacement(c).is_some() => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[doc(hidden)]
+pub fn print_stdout(args: fmt::Arguments) {
+    match rendered(args) {
+        Some(text) => ::std::print!("{}", text),
+        None => ::std::print!("{}", args),
+    }
+}
+
+#[doc(hidden)]
+pub fn print_stderr(args: fmt::Arguments) {
+    match rendered(args) {
+        Some(text) => ::std::eprint!("{}", text),
+        None => ::std::eprint!("{}", args),
+    }
+}
+
+#[macro_export]
+macro_rules! ui_print {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::print_stdout(::std::format_args!($fmt $(, $crate::output::Arg(&$arg))*))
+    };
+}
+
+#[macro_export]
+macro_rules! ui_println {
+    () => {
+        $crate::output::print_stdout(::std::format_args!("\n"))
+    };
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::print_stdout(::std::format_args!(::std::concat!($fmt, "\n") $(, $crate::output::Arg(&$arg))*))
+    };
+}
+
+#[macro_export]
+macro_rules! ui_eprint {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::print_stderr(::std::format_args!($fmt $(, $crate::output::Arg(&$arg))*))
+    };
+}
+
+#[macro_export]
+macro_rules! ui_eprintln {
+    () => {
+        $crate::output::print_stderr(::std::format_args!("\n"))
+    };
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        $crate::output::print_stderr(::std::format_args!(::std::concat!($fmt, "\n") $(, $crate::output::Arg(&$arg))*))
+    };
+}
+
+/// A decoration printed next to repository content, with its glyphs
+/// replaced when icons are off
+pub fn decoration(text: &str) -> Cow<'_, str> {
+    if is_accessible() || !ICONS.load(Ordering::Relaxed) {
+        without_icons(text)
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// ASCII for a glyph rgit prints, or `""` for one that is only decoration
+fn replacement(glyph: char) -> Option<&'static str> {
+    Some(match glyph {
+        '✅' | '✓' | '✔' => "[ok]",
+        '❌' | '✗' | '✘' => "[error]",
+        '⚠' => "[warning]",
+        '💡' => "[hint]",
+        'ℹ' => "[info]",
+        '•' | '·' => "-",
+        '●' | '◆' => "*",
+        '○' | '◇' => "o",
+        '→' | '❯' | '›' => "->",
+        '←' | '↩' => "<-",
+        '↑' | '⬆' => "^",
+        '↓' | '⬇' => "v",
+        '↕' => "^v",
+        '…' => "...",
+        '—' | '–' => "-",
+        '×' => "x",
+        '█' | '▓' => "#",
+        '░' | '▒' => ".",
+        '─' | '━' | '┄' => "-",
+        '═' => "=",
+        '│' | '┃' | '┆' | '║' => "|",
+        '╭' | '╮' | '╰' | '╯' | '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' => "+",
+        // Emoji used as decoration are dropped
+        '\u{1F000}'..='\u{1FAFF}' | '\u{2600}'..='\u{27BF}' | '\u{2300}'..='\u{23FF}' | '\u{2B00}'..='\u{2BFF}' => "",
+        _ => return None,
+    })
+}
+
+/// `text` with glyphs replaced by ASCII and in-place progress redraws
+/// removed. Letters outside ASCII, like in file names or translations, stay.
+pub fn plain(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() && !text.contains('\r') {
+        return Cow::Borrowed(text);
+    }
+    // A line that starts with `\r` and doesn't end redraws the line before
+    if text.starts_with('\r') && !text.ends_with('\n') {
+        return Cow::Borrowed("");
+    }
+    Cow::Owned(replace_glyphs(text, true))
+}
+
+/// `text` with glyphs replaced by ASCII
+pub fn without_icons(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(replace_glyphs(text, false))
+}
+
+fn replace_glyphs(text: &str, strip_carriage_returns: bool) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        // Invisible characters that turn the one before into emoji
+        if c == '\u{FE0F}' || c == '\u{200D}' {
+            continue;
+        }
+        if c == '\r' && strip_carriage_returns {
+            if chars.peek() == Some(&'\n') {
+                out.push(c);
+            }
+            continue;
+        }
+        match replacement(c) {
+            None => out.push(c),
+            // Dropping a decoration drops the space that followed it too
+            Some("") => {
+                while chars.peek() == Some(&'\u{FE0F}') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&' ') {
+                    chars.next();
+                }
+            }
+            Some(ascii) => out.push_str(ascii),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_replaces_glyphs() {
+        assert_eq!(plain("✅ Committed"), "[ok] Committed");
+        assert_eq!(plain("⚠️ 2 files"), "[warning] 2 files");
+        assert_eq!(plain("📋 Current status:"), "Current status:");
+        assert_eq!(plain("  • rgit add - Stage"), "  - rgit add - Stage");
+        assert_eq!(plain("╭──╮\n│ah│\n╰──╯"), "+--+\n|ah|\n+--+");
+        assert_eq!(plain("\u{1b}[32m✅\u{1b}[0m Done"), "\u{1b}[32m[ok]\u{1b}[0m Done");
+        assert_eq!(plain("Directorio de trabajo limpio ñ"), "Directorio de trabajo limpio ñ");
+        assert_eq!(without_icons("\r💡 Tip"), "\r[hint] Tip");
+    }
+
+    #[test]
+    fn test_plain_drops_progress_redraws() {
+        assert_eq!(plain("\r📦 Progress: 40%"), "");
+        assert_eq!(plain("\r✅ Updated main\n"), "[ok] Updated main\n");
+        assert_eq!(plain("line\r\n"), "line\r\n");
+    }
+
+    #[test]
+    fn test_filter_leaves_content_alone() {
+        let content = |text: &str| format!("{}{}{}", CONTENT_START, text, CONTENT_END);
+        let line = format!("✅ Reset to {} {}", content("abc1234"), content("Add ✨ notes → docs"));
+        assert_eq!(filter(&line, true), "[ok] Reset to abc1234 Add ✨ notes → docs");
+        assert_eq!(filter(&format!("  {} {}", content("📁 docs"), "→"), false), "  📁 docs ->");
+        assert_eq!(filter(&format!("\r{}", content("📦 42%")), true), "");
+    }
+
+    #[test]
+    fn test_is_decoration() {
+        assert!(is_decoration("✅"));
+        assert!(is_decoration("\u{1b}[33m⚠️\u{1b}[0m"));
+        assert!(is_decoration(" · "));
+        assert!(!is_decoration("main"));
+        assert!(!is_decoration("✨ notes"));
+        assert!(!is_decoration("\u{1b}[36mfeature\u{1b}[0m"));
+    }
+}
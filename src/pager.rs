@@ -0,0 +1,144 @@
+//! Paging long output.
+//!
+//! The pager is chosen like git chooses it: `ui.pager` (or `RGIT_PAGER`),
+//! then `GIT_PAGER`, `core.pager` and `PAGER`, falling back to `less -RFX`.
+//! Standard output is redirected into the pager for as long as the returned
+//! [`Pager`] is alive.
+
+use crate::config::{parse_bool, Config};
+
+/// Pager used when nothing is configured; quits when output fits one screen
+pub const DEFAULT_PAGER: &str = "less -RFX";
+
+/// Pick the pager command, or `None` when paging is turned off
+pub fn resolve(
+    ui_pager: Option<&str>,
+    git_pager_env: Option<&str>,
+    core_pager: Option<&str>,
+    pager_env: Option<&str>,
+) -> Option<String> {
+    let command = ui_pager
+        .or(git_pager_env)
+        .or(core_pager)
+        .or(pager_env)
+        .unwrap_or(DEFAULT_PAGER)
+        .trim();
+
+    // "cat" and "off" turn paging off, as does an empty PAGER
+    if command.is_empty() || command == "cat" || parse_bool(command) == Some(false) {
+        None
+    } else {
+        Some(command.to_string())
+    }
+}
+
+/// The pager command for the current environment and repository
+pub fn command(config: &Config) -> Option<String> {
+    let git_config = git2::Repository::discover(".")
+        .and_then(|repo| repo.config())
+        .or_else(|_| git2::Config::open_default())
+        .ok();
+    let core_pager = git_config.and_then(|config| config.get_string("core.pager").ok());
+
+    resolve(
+        config.ui.pager.as_deref(),
+        std::env::var("GIT_PAGER").ok().as_deref(),
+        core_pager.as_deref(),
+        std::env::var("PAGER").ok().as_deref(),
+    )
+}
+
+/// A running pager; dropping it closes the pager's input and waits for it
+#[cfg(unix)]
+pub struct Pager {
+    child: std::process::Child,
+    /// Duplicate of the original stdout, restored on drop
+    saved_stdout: std::os::unix::io::RawFd,
+}
+
+#[cfg(not(unix))]
+pub struct Pager;
+
+/// Start paging stdout when it is a terminal and a pager is configured
+#[cfg(unix)]
+pub fn start(config: &Config) -> Option<Pager> {
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+    use std::process::{Command, Stdio};
+
+    if !atty::is(atty::Stream::Stdout) {
+        return None;
+    }
+    let pager = command(config)?;
+
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(&pager).stdin(Stdio::piped());
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    if std::env::var_os("LV").is_none() {
+        command.env("LV", "-c");
+    }
+    let mut child = command.spawn().ok()?;
+    let input = child.stdin.take()?;
+
+    // Decide on colors while stdout is still the terminal
+    colored::control::set_override(colored::control::SHOULD_COLORIZE.should_colorize());
+    let _ = std::io::stdout().flush();
+
+    // SAFETY: plain descriptor juggling on fds this process owns
+    let saved_stdout = unsafe {
+        let saved = libc::dup(libc::STDOUT_FILENO);
+        if saved < 0 || libc::dup2(input.as_raw_fd(), libc::STDOUT_FILENO) < 0 {
+            if saved >= 0 {
+                libc::close(saved);
+            }
+            drop(input);
+            let _ = child.wait();
+            return None;
+        }
+        // Quitting the pager early should end rgit quietly, like git
+        libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+        saved
+    };
+    drop(input);
+
+    Some(Pager { child, saved_stdout })
+}
+
+#[cfg(not(unix))]
+pub fn start(_config: &Config) -> Option<Pager> {
+    None
+}
+
+#[cfg(unix)]
+impl Drop for Pager {
+    fn drop(&mut self) {
+        use std::io::Write;
+
+        let _ = std::io::stdout().flush();
+        // SAFETY: restores the descriptor saved in `start`, closing the pipe
+        unsafe {
+            libc::dup2(self.saved_stdout, libc::STDOUT_FILENO);
+            libc::close(self.saved_stdout);
+        }
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_follows_git_precedence() {
+        assert_eq!(resolve(None, None, None, None).as_deref(), Some(DEFAULT_PAGER));
+        assert_eq!(resolve(None, None, Some("most"), Some("more")).as_deref(), Some("most"));
+        assert_eq!(resolve(None, Some("delta"), Some("most"), None).as_deref(), Some("delta"));
+        assert_eq!(resolve(Some("bat -p"), Some("delta"), None, None).as_deref(), Some("bat -p"));
+
+        assert_eq!(resolve(Some("off"), Some("delta"), None, None), None);
+        assert_eq!(resolve(None, None, Some("cat"), None), None);
+        assert_eq!(resolve(None, None, None, Some("")), None);
+    }
+}
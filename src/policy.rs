@@ -0,0 +1,284 @@
+use anyhow::{Context, Result};
+use git2::{Commit, Oid, Repository};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::error::RgitError;
+use crate::utils::{humanize_size, parse_size, shorten_oid};
+
+/// Repository-local push policy, relative to the working tree root
+pub const POLICY_FILE: &str = ".rgit/policy.toml";
+
+/// Rules a repository sets for what may be pushed, checked by `rgit push`
+/// before anything is sent
+///
+/// ```toml
+/// [push]
+/// protected_branches = ["main", "release/*"]
+/// linear_history = true
+/// signed_commits = true
+/// max_commit_size = "5MB"
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub push: PushPolicy,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PushPolicy {
+    /// Branches that only change through pull requests; `*` matches anything
+    #[serde(default)]
+    pub protected_branches: Vec<String>,
+    /// Refuse merge commits
+    #[serde(default)]
+    pub linear_history: bool,
+    /// Refuse commits without a signature
+    #[serde(default)]
+    pub signed_commits: bool,
+    /// Refuse commits whose added and changed files add up to more, e.g. "5MB"
+    pub max_commit_size: Option<String>,
+}
+
+/// The rule a push breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    ProtectedBranch,
+    LinearHistory,
+    SignedCommits,
+    MaxCommitSize,
+}
+
+impl Rule {
+    pub fn title(self) -> &'static str {
+        match self {
+            Rule::ProtectedBranch => "Protected branch",
+            Rule::LinearHistory => "Merge commits",
+            Rule::SignedCommits => "Unsigned commits",
+            Rule::MaxCommitSize => "Oversized commits",
+        }
+    }
+
+    /// How to bring the push in line with the rule
+    pub fn hint(self) -> &'static str {
+        match self {
+            Rule::ProtectedBranch => "Push to a new branch and open a pull request instead; protected branches can't be deleted",
+            Rule::LinearHistory => "Rebase onto the upstream instead of merging it: rgit pull --rebase",
+            Rule::SignedCommits => "Set up signing (rgit whoami shows how), then re-sign: git rebase --exec 'git commit --amend --no-edit -S' <upstream>",
+            Rule::MaxCommitSize => "Move large files out of the commits, or track them with Git LFS",
+        }
+    }
+}
+
+/// One way a push breaks the policy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    pub rule: Rule,
+    /// Ref on the remote the offending commits would land on
+    pub destination: String,
+    pub detail: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.detail, self.destination)
+    }
+}
+
+impl Policy {
+    /// Load the policy for the working tree at `root`, if it defines one
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(POLICY_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let policy: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+        policy.validate()?;
+        Ok(Some(policy))
+    }
+
+    /// Catch mistakes that would otherwise only show up as odd results
+    fn validate(&self) -> Result<()> {
+        if let Some(size) = &self.push.max_commit_size {
+            if parse_size(size).is_none() {
+                return Err(RgitError::InvalidConfigValue {
+                    key: "push.max_commit_size".to_string(),
+                    value: size.clone(),
+                }.into());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PushPolicy {
+    /// Check the commits `[+]src:dst` refspecs would send to `remote`: those
+    /// not reachable from any of its remote-tracking refs. A `:dst` refspec
+    /// deletes `dst`.
+    pub fn evaluate(&self, repo: &Repository, remote: &str, refspecs: &[String]) -> Result<Vec<Violation>> {
+        let max_size = self.max_commit_size.as_deref().and_then(parse_size);
+        let mut violations = Vec::new();
+
+        for spec in refspecs {
+            let (source, destination) = spec.trim_start_matches('+').split_once(':').unwrap_or((spec, spec));
+            if source.is_empty() {
+                if let Some(branch) = destination.strip_prefix("refs/heads/").filter(|branch| self.is_protected(branch)) {
+                    violations.push(Violation {
+                        rule: Rule::ProtectedBranch,
+                        destination: destination.to_string(),
+                        detail: format!("{} would be deleted", branch),
+                    });
+                }
+                continue;
+            }
+            let Ok(tip) = repo.revparse_single(source).and_then(|object| object.peel_to_commit()) else {
+                continue;
+            };
+            let commits = new_commits(repo, remote, tip.id())?;
+            if commits.is_empty() {
+                continue;
+            }
+            let violation = |rule, detail| Violation { rule, destination: destination.to_string(), detail };
+
+            if let Some(branch) = destination.strip_prefix("refs/heads/") {
+                if self.is_protected(branch) {
+                    violations.push(violation(Rule::ProtectedBranch, format!(
+                        "{} commit{} would land directly on {}",
+                        commits.len(), if commits.len() == 1 { "" } else { "s" }, branch
+                    )));
+                }
+            }
+            for commit in &commits {
+                let subject = format!("{} {}", shorten_oid(&commit.id(), 7), commit.summary().unwrap_or(""));
+                if self.linear_history && commit.parent_count() > 1 {
+                    violations.push(violation(Rule::LinearHistory, subject.clone()));
+                }
+                if self.signed_commits && repo.extract_signature(&commit.id(), None).is_err() {
+                    violations.push(violation(Rule::SignedCommits, subject.clone()));
+                }
+                if let Some(max) = max_size {
+                    let size = commit_size(repo, commit)?;
+                    if size > max {
+                        violations.push(violation(Rule::MaxCommitSize, format!(
+                            "{} adds {}, over {}", subject, humanize_size(size), humanize_size(max)
+                        )));
+                    }
+                }
+            }
+        }
+
+        violations.sort_by_key(|violation| violation.rule);
+        violations.dedup();
+        Ok(violations)
+    }
+
+    fn is_protected(&self, branch: &str) -> bool {
+        self.protected_branches.iter().any(|pattern| glob_matches(pattern, branch))
+    }
+}
+
+/// Commits reachable from `tip` but from none of `remote`'s tracking refs,
+/// newest first
+fn new_commits<'r>(repo: &'r Repository, remote: &str, tip: Oid) -> Result<Vec<Commit<'r>>> {
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push(tip)?;
+    for reference in repo.references_glob(&format!("refs/remotes/{}/*", remote))? {
+        if let Some(oid) = reference?.target() {
+            revwalk.hide(oid)?;
+        }
+    }
+    revwalk.map(|oid| Ok(repo.find_commit(oid?)?)).collect()
+}
+
+/// Bytes of the files `commit` adds or changes against its first parent
+fn commit_size(repo: &Repository, commit: &Commit) -> Result<u64> {
+    let parent_tree = match commit.parents().next() {
+        Some(parent) => Some(parent.tree()?),
+        None => None,
+    };
+    let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+    let odb = repo.odb()?;
+    let mut size = 0;
+    for delta in diff.deltas() {
+        let file = delta.new_file();
+        if file.exists() && !file.id().is_zero() {
+            if let Ok((bytes, _)) = odb.read_header(file.id()) {
+                size += bytes as u64;
+            }
+        }
+    }
+    Ok(size)
+}
+
+/// Whether `name` matches `pattern`, where `*` matches anything
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let regex = format!("^{}$", regex::escape(pattern).replace(r"\*", ".*"));
+    Regex::new(&regex).map(|regex| regex.is_match(name)).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
+
+    #[test]
+    fn test_evaluates_push_policy() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "hello\n", "Initial commit");
+        let _origin = repo.add_remote("origin");
+        repo.commit_file("big.bin", &"x".repeat(4096), "Add big file");
+        repo.commit_file("README.md", "hello again\n", "Update readme");
+
+        let policy = PushPolicy {
+            protected_branches: vec!["release/*".to_string(), DEFAULT_BRANCH.to_string()],
+            linear_history: true,
+            signed_commits: true,
+            max_commit_size: Some("1KB".to_string()),
+        };
+        let specs = vec![format!("refs/heads/{0}:refs/heads/{0}", DEFAULT_BRANCH)];
+        let violations = policy.evaluate(repo.repo(), "origin", &specs).unwrap();
+        let rules: Vec<Rule> = violations.iter().map(|violation| violation.rule).collect();
+        assert_eq!(rules, vec![Rule::ProtectedBranch, Rule::SignedCommits, Rule::SignedCommits, Rule::MaxCommitSize]);
+        assert!(violations[0].detail.starts_with("2 commits would land directly on"));
+        assert!(violations[3].detail.contains("Add big file"));
+
+        // Other branches aren't protected
+        let pushed = format!("refs/heads/{}:refs/heads/feature", DEFAULT_BRANCH);
+        let on_feature = policy.evaluate(repo.repo(), "origin", &[pushed]).unwrap();
+        assert!(on_feature.iter().all(|violation| violation.rule != Rule::ProtectedBranch));
+        assert_eq!(PushPolicy::default().evaluate(repo.repo(), "origin", &specs).unwrap(), vec![]);
+
+        let deletes = vec![format!(":refs/heads/{}", DEFAULT_BRANCH), ":refs/heads/feature".to_string()];
+        let deleted = policy.evaluate(repo.repo(), "origin", &deletes).unwrap();
+        assert_eq!(deleted.len(), 1);
+        assert_eq!(deleted[0].detail, format!("{} would be deleted", DEFAULT_BRANCH));
+    }
+
+    #[test]
+    fn test_rejects_invalid_policy_file() {
+        let repo = TestRepo::new();
+        assert!(Policy::load(repo.path()).unwrap().is_none());
+
+        repo.write(POLICY_FILE, "[push]\nmax_commit_size = \"huge\"\n");
+        assert!(Policy::load(repo.path()).is_err());
+        repo.write(POLICY_FILE, "[push]\nsigned = true\n");
+        assert!(Policy::load(repo.path()).is_err());
+        repo.write(POLICY_FILE, "[push]\nprotected_branches = [\"main\"]\n");
+        assert_eq!(Policy::load(repo.path()).unwrap().unwrap().push.protected_branches, vec!["main"]);
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("release/*", "release/1.2"));
+        assert!(glob_matches("main", "main"));
+        assert!(!glob_matches("main", "main-old"));
+    }
+}
@@ -0,0 +1,259 @@
+//! Safety-net snapshots taken before destructive commands.
+//!
+//! Before a command that can throw work away (a hard reset, `clean`, a
+//! rebase, a forced checkout), rgit commits the working tree, untracked
+//! files included, on top of HEAD and points `refs/rgit/snapshots/<id>` at
+//! it. Unchanged files share HEAD's objects, so a snapshot is one commit and
+//! a handful of trees: cheap enough to take every time.
+//! `rgit backup recover` puts the branch and the files back.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use colored::*;
+use git2::build::CheckoutBuilder;
+use git2::{Commit, Oid, Repository, ResetType, Signature};
+
+use crate::commands::backup::snapshot_trees;
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::journal::{Journal, JournalEntry};
+
+/// Namespace of the snapshot refs
+pub const REF_PREFIX: &str = "refs/rgit/snapshots/";
+
+/// Subject of every snapshot commit, followed by the operation
+const SUBJECT_PREFIX: &str = "rgit safety snapshot before ";
+
+/// Body line naming the branch HEAD was on
+const HEAD_PREFIX: &str = "HEAD: ";
+
+/// A saved working tree and the branch it belonged to
+#[derive(Debug, Clone, PartialEq)]
+pub struct SafetySnapshot {
+    /// Timestamp-based name, e.g. `20240102-150405`
+    pub id: String,
+    /// Commit holding the working tree
+    pub commit: Oid,
+    /// Operation it was taken before, e.g. "reset"
+    pub operation: String,
+    /// Branch HEAD was on; None when detached
+    pub branch: Option<String>,
+    /// Commit HEAD pointed to
+    pub head: Oid,
+    pub created: DateTime<Utc>,
+}
+
+impl SafetySnapshot {
+    fn from_commit(id: &str, commit: &Commit) -> Option<Self> {
+        let message = commit.message()?;
+        let operation = message.lines().next()?.strip_prefix(SUBJECT_PREFIX)?;
+        let branch = message.lines()
+            .find_map(|line| line.strip_prefix(HEAD_PREFIX))
+            .filter(|name| name.starts_with("refs/"))
+            .map(String::from);
+        Some(Self {
+            id: id.to_string(),
+            commit: commit.id(),
+            operation: operation.to_string(),
+            branch,
+            head: commit.parent_id(0).ok()?,
+            created: Utc.timestamp_opt(commit.time().seconds(), 0).single()?,
+        })
+    }
+
+    /// Where HEAD was, e.g. "main" or "detached HEAD"
+    pub fn head_description(&self) -> String {
+        match &self.branch {
+            Some(branch) => branch.trim_start_matches("refs/heads/").to_string(),
+            None => "detached HEAD".to_string(),
+        }
+    }
+
+    pub fn age(&self) -> String {
+        crate::utils::format_time_ago(git2::Time::new(self.created.timestamp(), 0))
+    }
+}
+
+/// Snapshot HEAD and the working tree before `operation`. None when HEAD
+/// has no commits yet. While the index has conflicts, or in a bare
+/// repository, only HEAD is saved.
+pub fn take(repo: &Repository, operation: &str, signature: &Signature) -> Result<Option<SafetySnapshot>> {
+    let Ok(head) = repo.head() else { return Ok(None) };
+    let Ok(head_commit) = head.peel_to_commit() else { return Ok(None) };
+    let tree = match snapshot_trees(repo, true)? {
+        Some((_, worktree)) => worktree,
+        None => head_commit.tree()?,
+    };
+
+    let branch = if head.is_branch() { head.name().map(String::from) } else { None };
+    let message = format!("{}{}\n\n{}{}\n", SUBJECT_PREFIX, operation, HEAD_PREFIX, branch.as_deref().unwrap_or("detached"));
+    let oid = repo.commit(None, signature, signature, &message, &tree, &[&head_commit])?;
+
+    let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let mut id = stamp.clone();
+    let mut n = 2;
+    while repo.find_reference(&format!("{}{}", REF_PREFIX, id)).is_ok() {
+        id = format!("{}-{}", stamp, n);
+        n += 1;
+    }
+    repo.reference(&format!("{}{}", REF_PREFIX, id), oid, false, &format!("rgit: snapshot before {}", operation))?;
+
+    let commit = repo.find_commit(oid)?;
+    Ok(SafetySnapshot::from_commit(&id, &commit))
+}
+
+/// All snapshots, newest first
+pub fn list(repo: &Repository) -> Result<Vec<SafetySnapshot>> {
+    let mut snapshots = Vec::new();
+    for reference in repo.references_glob(&format!("{}*", REF_PREFIX))? {
+        let reference = reference?;
+        let Some(id) = reference.name().and_then(|name| name.strip_prefix(REF_PREFIX)).map(String::from) else { continue };
+        if let Ok(commit) = reference.peel_to_commit() {
+            snapshots.extend(SafetySnapshot::from_commit(&id, &commit));
+        }
+    }
+    snapshots.sort_by(|a, b| b.created.cmp(&a.created).then_with(|| b.id.cmp(&a.id)));
+    Ok(snapshots)
+}
+
+/// A snapshot by id or unique id prefix, or the newest one
+pub fn find(repo: &Repository, id: Option<&str>) -> Result<SafetySnapshot> {
+    let snapshots = list(repo)?;
+    let Some(id) = id else {
+        return snapshots.into_iter().next()
+            .ok_or_else(|| RgitError::OperationFailed("there are no safety snapshots yet".to_string()).into());
+    };
+
+    let mut matches: Vec<SafetySnapshot> = snapshots.into_iter().filter(|s| s.id.starts_with(id)).collect();
+    if let Some(exact) = matches.iter().position(|s| s.id == id) {
+        return Ok(matches.swap_remove(exact));
+    }
+    match matches.len() {
+        1 => Ok(matches.remove(0)),
+        0 => Err(RgitError::InvalidArgument(format!("no safety snapshot named '{}'", id)).into()),
+        _ => Err(RgitError::InvalidArgument(format!("'{}' matches {} safety snapshots", id, matches.len())).into()),
+    }
+}
+
+/// Delete snapshots past `backup.keep` and `advanced.safety.backup_retention`,
+/// returning how many went
+pub fn prune(repo: &Repository, config: &Config) -> Result<usize> {
+    let keep = config.backup.keep;
+    let retention_days = config.advanced.safety.backup_retention;
+    let now = Utc::now();
+    let mut pruned = 0;
+    for (n, snapshot) in list(repo)?.iter().enumerate() {
+        if (keep > 0 && n >= keep)
+            || (retention_days > 0 && now - snapshot.created > Duration::days(retention_days.into())) {
+            repo.find_reference(&format!("{}{}", REF_PREFIX, snapshot.id))?.delete()?;
+            pruned += 1;
+        }
+    }
+    Ok(pruned)
+}
+
+/// Snapshot the repository in the current directory before `operation`,
+/// when `advanced.safety.auto_snapshot` is on. Does nothing outside a
+/// repository, where there is no work to lose.
+pub fn snapshot_before(operation: &str, config: &Config) -> Result<Option<SafetySnapshot>> {
+    if !config.advanced.safety.auto_snapshot {
+        return Ok(None);
+    }
+    let Ok(repo) = Repository::discover(".") else { return Ok(None) };
+
+    let signature = repo.signature().or_else(|_| Signature::now("rgit", "rgit@localhost"))?;
+    let snapshot = take(&repo, operation, &signature).with_context(|| format!(
+        "Failed to take a safety snapshot before {} (set advanced.safety.auto_snapshot to false to go without)", operation
+    ))?;
+    prune(&repo, config)?;
+    if let Some(snapshot) = &snapshot {
+        ui_println!("{} {}", "🛟".blue(),
+                format!("Safety snapshot {} saved; rgit backup recover puts it back", snapshot.id).dimmed());
+    }
+    Ok(snapshot)
+}
+
+/// Put back what a snapshot saved: HEAD returns to its branch and commit,
+/// the working tree to the saved files, and the index to that commit, so
+/// saved changes come back unstaged and saved untracked files untracked.
+pub fn recover(repo: &Repository, snapshot: &SafetySnapshot) -> Result<()> {
+    let saved = repo.find_commit(snapshot.commit)?;
+    let head = repo.find_commit(snapshot.head)?;
+    let reference = snapshot.branch.as_deref().unwrap_or("HEAD");
+
+    let mut entry = JournalEntry::new("backup recover", reference)
+        .with_new_oid(head.id())
+        .with_detail(format!("safety snapshot {}", snapshot.id));
+    if let Ok(old) = repo.refname_to_id(reference) {
+        entry = entry.with_old_oid(old);
+        repo.reference("ORIG_HEAD", old, true, "rgit: recover")?;
+    }
+    Journal::for_repo(repo).record(&entry)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+    repo.checkout_tree(saved.as_object(), Some(&mut checkout))?;
+    match &snapshot.branch {
+        Some(branch) => {
+            repo.reference(branch, head.id(), true, &format!("rgit: recover safety snapshot {}", snapshot.id))?;
+            repo.set_head(branch)?;
+        }
+        None => repo.set_head_detached(head.id())?,
+    }
+    repo.reset(head.as_object(), ResetType::Mixed, None)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestRepo;
+    use std::fs;
+
+    fn signature() -> Signature<'static> {
+        Signature::now("Test", "test@example.com").unwrap()
+    }
+
+    #[test]
+    fn test_take_and_recover() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("a.txt", "one\n", "First");
+        let second = repo.commit_file("a.txt", "two\n", "Second");
+        repo.write("a.txt", "local edit\n").write("new.txt", "untracked\n");
+
+        let snapshot = take(repo.repo(), "reset", &signature()).unwrap().unwrap();
+        assert_eq!(snapshot.operation, "reset");
+        assert_eq!(snapshot.branch.as_deref(), Some("refs/heads/main"));
+        assert_eq!(snapshot.head, second);
+        assert_eq!(list(repo.repo()).unwrap(), vec![snapshot.clone()]);
+
+        // What the destructive command would do
+        let target = repo.repo().find_commit(first).unwrap();
+        repo.repo().reset(target.as_object(), ResetType::Hard, None).unwrap();
+        fs::remove_file(repo.path().join("new.txt")).unwrap();
+
+        recover(repo.repo(), &find(repo.repo(), None).unwrap()).unwrap();
+        assert_eq!(repo.head(), second);
+        assert_eq!(fs::read_to_string(repo.path().join("a.txt")).unwrap(), "local edit\n");
+        assert_eq!(fs::read_to_string(repo.path().join("new.txt")).unwrap(), "untracked\n");
+        let status = |path: &str| repo.repo().status_file(std::path::Path::new(path)).unwrap();
+        assert_eq!(status("a.txt"), git2::Status::WT_MODIFIED);
+        assert_eq!(status("new.txt"), git2::Status::WT_NEW);
+    }
+
+    #[test]
+    fn test_find_and_prune() {
+        let repo = TestRepo::new();
+        repo.commit_file("a.txt", "one\n", "First");
+        let older = take(repo.repo(), "clean", &signature()).unwrap().unwrap();
+        let newer = take(repo.repo(), "rebase", &signature()).unwrap().unwrap();
+        assert_ne!(older.id, newer.id);
+        assert_eq!(find(repo.repo(), Some(&older.id)).unwrap().operation, "clean");
+        assert!(find(repo.repo(), Some("nope")).is_err());
+
+        let mut config = Config::default();
+        config.backup.keep = 1;
+        assert_eq!(prune(repo.repo(), &config).unwrap(), 1);
+        assert_eq!(list(repo.repo()).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,426 @@
+//! The sequencer behind cherry-pick and revert.
+//!
+//! Both apply a list of commits one at a time, stop on the first conflict and
+//! pick up again with `--continue`, `--skip` or `--abort`. They differ only in
+//! how each commit is applied and committed, which they supply as an
+//! [`Action`]. The state lives in the git directory, in a file named after the
+//! command, so an interrupted sequence survives between invocations.
+
+use anyhow::{Context, Result};
+use colored::*;
+use git2::{Commit, Oid, Repository, RepositoryState, ResetType, Sort};
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::commands::cherry_pick::{conflicted_paths, ensure_no_tracked_changes};
+use crate::config::Config;
+use crate::core::{resolve_revision, Revision, RgitCore};
+use crate::error::RgitError;
+use crate::operation::OperationKind;
+use crate::utils::shorten_oid;
+
+/// Operations that run through the sequencer
+const SEQUENCED: [OperationKind; 2] = [OperationKind::CherryPick, OperationKind::Revert];
+
+/// How a sequence applies and commits each commit. Implemented by the
+/// command's options, which are saved with the sequence.
+pub trait Action: Serialize + DeserializeOwned {
+    /// The operation this drives; its command also names the state file
+    const KIND: OperationKind;
+    /// Progress wording, e.g. "Reverting"
+    const DOING: &'static str;
+    /// Result wording, e.g. "Reverted"
+    const DONE: &'static str;
+    /// Whether ranges are applied oldest first rather than newest first
+    const OLDEST_FIRST: bool;
+
+    /// Marker printed before each step
+    fn icon() -> ColoredString;
+
+    /// Whether results are left in the index instead of committed
+    fn no_commit(&self) -> bool;
+
+    /// Apply a commit to the index and working tree, returning the conflicted paths
+    fn apply(&self, repo: &Repository, commit: &Commit, config: &Config) -> Result<Vec<String>>;
+
+    /// Commit the staged result of applying `commit`
+    fn commit(&self, rgit: &RgitCore, commit: &Commit, config: &Config) -> Result<Oid>;
+
+    /// Whether the step in progress stopped on conflicts and only needs
+    /// finishing, rather than applying again
+    fn stopped(&self, _repo: &Repository) -> bool {
+        true
+    }
+}
+
+// =============================================================================
+// Sequencing State
+// =============================================================================
+
+/// Persistent state of a multi-commit sequence.
+///
+/// Stored in the git directory so a sequence interrupted by conflicts can be
+/// resumed with `--continue` or rolled back with `--abort`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sequence<O> {
+    /// HEAD before the sequence started (restored on abort)
+    pub original_head: String,
+    /// Commits still to apply, in order; the first entry is in progress
+    pub todo: Vec<String>,
+    /// Commits already applied
+    pub done: Vec<String>,
+    /// Commits dropped with `--skip`
+    #[serde(default)]
+    pub skipped: Vec<String>,
+    /// Options for the whole sequence
+    pub options: O,
+}
+
+fn state_path(repo: &Repository, kind: OperationKind) -> PathBuf {
+    repo.path().join(format!("rgit-{}.json", kind.command()))
+}
+
+impl<O> Sequence<O> {
+    /// Number of commits in the whole sequence
+    pub fn total(&self) -> usize {
+        self.todo.len() + self.done.len() + self.skipped.len()
+    }
+
+    /// 1-based position of the commit in progress
+    pub fn position(&self) -> usize {
+        self.done.len() + self.skipped.len() + 1
+    }
+
+    /// The commit in progress
+    pub fn current(&self) -> Option<Oid> {
+        self.todo.first().and_then(|id| Oid::from_str(id).ok())
+    }
+}
+
+impl<O: DeserializeOwned> Sequence<O> {
+    fn read(repo: &Repository, kind: OperationKind) -> Result<Option<Self>> {
+        let path = state_path(repo, kind);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Ok(Some(serde_json::from_str(&content).map_err(RgitError::from)?))
+    }
+}
+
+impl<A: Action> Sequence<A> {
+    /// Location of the state file for a repository
+    pub fn path(repo: &Repository) -> PathBuf {
+        state_path(repo, A::KIND)
+    }
+
+    /// Load the in-progress sequence, if any
+    pub fn load(repo: &Repository) -> Result<Option<Self>> {
+        Self::read(repo, A::KIND)
+    }
+
+    /// Persist the sequence
+    pub fn save(&self, repo: &Repository) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).map_err(RgitError::from)?;
+        fs::write(Self::path(repo), content)?;
+        Ok(())
+    }
+
+    /// Remove the state file
+    pub fn clear(repo: &Repository) -> Result<()> {
+        let path = Self::path(repo);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn load_required(repo: &Repository) -> Result<Self> {
+        Self::load(repo)?.ok_or_else(|| {
+            RgitError::InvalidRepositoryState(format!("no {} in progress", A::KIND.command())).into()
+        })
+    }
+
+    /// Move the commit in progress to `done`
+    fn advance(&mut self, repo: &Repository) -> Result<()> {
+        let current = self.todo.remove(0);
+        self.done.push(current);
+        self.save(repo)
+    }
+}
+
+/// The sequence in progress, whichever command started it
+pub fn in_progress(repo: &Repository) -> Result<Option<(OperationKind, Sequence<IgnoredAny>)>> {
+    for kind in SEQUENCED {
+        if let Some(sequence) = Sequence::read(repo, kind)? {
+            return Ok(Some((kind, sequence)));
+        }
+    }
+    Ok(None)
+}
+
+// =============================================================================
+// Sequence Operations
+// =============================================================================
+
+/// Start applying `commits`, in the given order, on top of HEAD
+pub fn start<A: Action>(rgit: &RgitCore, commits: Vec<Oid>, options: A, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+
+    let running = in_progress(repo)?.map(|(kind, _)| kind)
+        .or_else(|| OperationKind::from_state(repo.state()));
+    if let Some(kind) = running {
+        return Err(RgitError::InvalidRepositoryState(format!(
+            "a {} is in progress; use '{}' or '{}'", kind.command(), kind.continue_command(), kind.abort_command()
+        )).into());
+    }
+    if repo.state() != RepositoryState::Clean {
+        return Err(RgitError::InvalidRepositoryState("another operation is in progress".to_string()).into());
+    }
+    ensure_no_tracked_changes(rgit)?;
+
+    let head = repo.head()?.peel_to_commit()?;
+    let sequence = Sequence {
+        original_head: head.id().to_string(),
+        todo: commits.iter().map(|oid| oid.to_string()).collect(),
+        done: Vec::new(),
+        skipped: Vec::new(),
+        options,
+    };
+    sequence.save(repo)?;
+
+    ui_println!("{} {} {} commit{} on {}",
+            A::icon(),
+            A::DOING,
+            sequence.todo.len(),
+            if sequence.todo.len() == 1 { "" } else { "s" },
+            rgit.current_branch()?.cyan());
+
+    run(rgit, sequence, config)
+}
+
+/// Finish the commit that stopped on conflicts and apply the rest
+pub fn resume<A: Action>(rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let mut sequence = Sequence::<A>::load_required(repo)?;
+
+    let mut index = repo.index()?;
+    if index.has_conflicts() {
+        let conflicts = conflicted_paths(&index)?;
+        ui_println!("{} Resolve these conflicts and stage the files first:", "⚠️".yellow());
+        for path in &conflicts {
+            ui_println!("  {} {}", "•".red(), path);
+        }
+        return Err(RgitError::MergeConflict(conflicts).into());
+    }
+
+    if let Some(oid) = sequence.current() {
+        // The stopped step is finished once its changes are staged
+        if sequence.options.stopped(repo) {
+            if !sequence.options.no_commit() {
+                index.read(false)?;
+                sequence.options.commit(rgit, &repo.find_commit(oid)?, config)?;
+            }
+            repo.cleanup_state()?;
+            sequence.advance(repo)?;
+        }
+    }
+
+    run(rgit, sequence, config)
+}
+
+/// Drop the commit that stopped on conflicts and apply the rest
+pub fn skip<A: Action>(rgit: &RgitCore, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let mut sequence = Sequence::<A>::load_required(repo)?;
+    if sequence.options.no_commit() {
+        // The index holds earlier steps too, so the current one cannot be peeled off
+        return Err(RgitError::InvalidArgument(format!(
+            "--skip is not available with --no-commit; use '{}'", A::KIND.abort_command()
+        )).into());
+    }
+
+    let head = repo.head()?.peel_to_commit()?;
+    repo.reset(head.as_object(), ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+
+    if let Some(oid) = sequence.current() {
+        let commit = repo.find_commit(oid)?;
+        ui_println!("  {} Skipped {} {}",
+                "⏭️".yellow(),
+                shorten_oid(&oid, 8).yellow(),
+                commit.summary().unwrap_or("").dimmed());
+        let current = sequence.todo.remove(0);
+        sequence.skipped.push(current);
+        sequence.save(repo)?;
+    }
+
+    run(rgit, sequence, config)
+}
+
+/// Abort the sequence and restore the original HEAD
+pub fn abort<A: Action>(rgit: &RgitCore) -> Result<()> {
+    let repo = &rgit.repo;
+    let sequence = Sequence::<A>::load_required(repo)?;
+
+    let original = repo.find_commit(Oid::from_str(&sequence.original_head)?)?;
+    repo.reset(original.as_object(), ResetType::Hard, None)?;
+    repo.cleanup_state()?;
+    Sequence::<A>::clear(repo)?;
+
+    ui_println!("{} Aborted the {}; {} restored to {}",
+            "↩️".yellow(),
+            A::KIND.command(),
+            rgit.current_branch()?.cyan(),
+            shorten_oid(&original.id(), 8).yellow());
+    Ok(())
+}
+
+/// Apply remaining commits, pausing on the first conflict
+fn run<A: Action>(rgit: &RgitCore, mut sequence: Sequence<A>, config: &Config) -> Result<()> {
+    let repo = &rgit.repo;
+    let total = sequence.total();
+
+    while let Some(oid) = sequence.current() {
+        let commit = repo.find_commit(oid)?;
+
+        ui_println!("  {} [{}/{}] {} {}",
+                A::icon(),
+                sequence.position(),
+                total,
+                shorten_oid(&oid, 8).yellow(),
+                commit.summary().unwrap_or("").white());
+
+        let conflicts = sequence.options.apply(repo, &commit, config)?;
+        if !conflicts.is_empty() {
+            sequence.save(repo)?;
+            show_conflict_help::<A>(&commit, &conflicts);
+            return Err(RgitError::MergeConflict(conflicts).into());
+        }
+
+        if !sequence.options.no_commit() {
+            sequence.options.commit(rgit, &commit, config)?;
+            repo.cleanup_state()?;
+        }
+
+        sequence.advance(repo)?;
+    }
+
+    Sequence::<A>::clear(repo)?;
+    show_summary(rgit, &sequence)
+}
+
+/// Resolve commit and range arguments, ordering ranges the way `A` applies them
+pub fn resolve_commits<A: Action>(repo: &Repository, specs: &[String]) -> Result<Vec<Oid>> {
+    let mut sorting = Sort::TOPOLOGICAL | Sort::TIME;
+    if A::OLDEST_FIRST {
+        sorting |= Sort::REVERSE;
+    }
+
+    let mut commits = Vec::new();
+    for spec in specs {
+        match resolve_revision(repo, spec)? {
+            Revision::Single(object) => commits.push(object.peel_to_commit()
+                .map_err(|_| RgitError::InvalidCommit(format!("'{}' does not point to a commit", spec)))?
+                .id()),
+            revision => {
+                let mut revwalk = repo.revwalk()?;
+                revwalk.set_sorting(sorting)?;
+                revision.push_to(repo, &mut revwalk)?;
+                let before = commits.len();
+                for oid in revwalk {
+                    commits.push(oid?);
+                }
+                if commits.len() == before {
+                    return Err(RgitError::InvalidArgument(format!("'{}' selects no commits", spec)).into());
+                }
+            }
+        }
+    }
+    Ok(commits)
+}
+
+fn show_conflict_help<A: Action>(commit: &Commit, conflicts: &[String]) {
+    println!();
+    ui_println!("{} Conflicts while {} {}:",
+            "⚠️".yellow().bold(),
+            A::DOING.to_lowercase(),
+            shorten_oid(&commit.id(), 8).yellow());
+    for path in conflicts {
+        ui_println!("  {} {}", "•".red(), path);
+    }
+    println!();
+    ui_println!("{} Next steps:", "💡".blue());
+    ui_println!("  • {} - Resolve the conflicts, then {} the files", "rgit resolve".cyan(), "rgit add".cyan());
+    ui_println!("  • {} - Apply the remaining commits", A::KIND.continue_command().cyan());
+    if let Some(skip) = A::KIND.skip_command() {
+        ui_println!("  • {} - Drop this commit and go on", skip.cyan());
+    }
+    ui_println!("  • {} - Restore the branch to where it was", A::KIND.abort_command().cyan());
+}
+
+fn show_summary<A: Action>(rgit: &RgitCore, sequence: &Sequence<A>) -> Result<()> {
+    let count = sequence.done.len();
+    println!();
+    if sequence.options.no_commit() {
+        ui_println!("{} {} {} commit{} into the index (not committed)",
+                "✅".green(),
+                A::DONE,
+                count,
+                if count == 1 { "" } else { "s" });
+        ui_println!("{} Use {} to commit the result", "💡".blue(), "rgit commit".cyan());
+    } else {
+        ui_println!("{} {} {} commit{} on {}",
+                "✅".green(),
+                A::DONE,
+                count,
+                if count == 1 { "" } else { "s" },
+                rgit.current_branch()?.cyan());
+    }
+    if !sequence.skipped.is_empty() {
+        let skipped = sequence.skipped.iter()
+            .map(|id| Ok(shorten_oid(&Oid::from_str(id)?, 8)))
+            .collect::<Result<Vec<_>>>()?;
+        ui_println!("{} Skipped {}: {}", "⏭️".yellow(), skipped.len(), skipped.join(", "));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::cherry_pick::PickOptions;
+    use crate::commands::revert::RevertOptions;
+    use crate::test_support::TestRepo;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config.ui.interactive = false;
+        config
+    }
+
+    #[test]
+    fn test_stopped_sequence_blocks_the_other_command() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "one\n", "First");
+        let second = repo.commit_file("file.txt", "two\n", "Second");
+        repo.commit_file("file.txt", "three\n", "Third");
+        assert!(in_progress(repo.repo()).unwrap().is_none());
+
+        assert!(start(&repo.core(), vec![second], RevertOptions::default(), &config()).is_err());
+        let (kind, sequence) = in_progress(repo.repo()).unwrap().unwrap();
+        assert_eq!(kind, OperationKind::Revert);
+        assert_eq!((sequence.position(), sequence.total()), (1, 1));
+        assert_eq!(sequence.current(), Some(second));
+
+        let error = start(&repo.core(), vec![second], PickOptions::default(), &config()).unwrap_err();
+        assert!(error.to_string().contains("rgit revert --continue"));
+
+        abort::<RevertOptions>(&repo.core()).unwrap();
+        assert!(in_progress(repo.repo()).unwrap().is_none());
+        assert_eq!(repo.repo().find_commit(repo.head()).unwrap().summary(), Some("Third"));
+    }
+}
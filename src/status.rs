@@ -5,6 +5,8 @@ use std::collections::HashMap;
 use unicode_width::UnicodeWidthStr;
 
 use crate::core::{RgitCore, RepositoryStatus, FileStatus, BranchInfo};
+use crate::output::decoration;
+use crate::theme::{Element, Themed};
 use crate::utils::{format_time_ago, humanize_size, truncate_string};
 
 /// Enhanced status display with beautiful formatting
@@ -91,8 +93,8 @@ impl StatusDisplay {
             let index_status = self.get_short_status_char(file.status, true);
             let workdir_status = self.get_short_status_char(file.status, false);
             println!("{}{} {}", 
-                    index_status.to_string().green(), 
-                    workdir_status.to_string().red(), 
+                    index_status.to_string().themed(Element::Added), 
+                    workdir_status.to_string().themed(Element::Modified), 
                     file.path);
         }
 
@@ -100,13 +102,13 @@ impl StatusDisplay {
             let index_status = self.get_short_status_char(file.status, true);
             let workdir_status = self.get_short_status_char(file.status, false);
             println!("{}{} {}", 
-                    index_status.to_string().green(), 
-                    workdir_status.to_string().red(), 
+                    index_status.to_string().themed(Element::Added), 
+                    workdir_status.to_string().themed(Element::Modified), 
                     file.path);
         }
 
         for file in &status.untracked {
-            println!("?? {}", file.path.red());
+            println!("?? {}", file.path.themed(Element::Untracked));
         }
 
         Ok(())
@@ -162,7 +164,7 @@ impl StatusDisplay {
             .and_then(|n| n.to_str())
             .unwrap_or("repository");
 
-        println!("{} {} {}", 
+        ui_println!("{} {} {}", 
                 "📁".blue(), 
                 "Repository:".bold(), 
                 repo_name.cyan().bold());
@@ -174,7 +176,7 @@ impl StatusDisplay {
             } else {
                 repo_path
             };
-            println!("   📍 {}", truncated_path.dimmed());
+            ui_println!("   📍 {}", truncated_path.dimmed());
         }
 
         Ok(())
@@ -184,14 +186,14 @@ impl StatusDisplay {
     fn display_branch_info(&self, branch_info: &BranchInfo) -> Result<()> {
         // Branch name with status
         let branch_icon = if branch_info.is_current { "🌿" } else { "📋" };
-        print!("{} {} {}", 
-               branch_icon.blue(), 
-               "On branch".bold(), 
-               branch_info.name.cyan().bold());
+        ui_print!("{} {} {}", 
+                  branch_icon.blue(), 
+                  "On branch".bold(), 
+                  branch_info.name.themed(Element::Branch).bold());
 
         // Detached HEAD warning
         if branch_info.name.contains("detached") {
-            print!(" {}", "(detached HEAD)".yellow().bold());
+            print!(" {}", "(detached HEAD)".themed(Element::Warning).bold());
         }
         println!();
 
@@ -213,35 +215,35 @@ impl StatusDisplay {
     fn display_tracking_info(&self, branch_info: &BranchInfo) -> Result<()> {
         match &branch_info.upstream {
             Some(upstream) => {
-                print!("   🔗 Tracking {}", upstream.cyan());
+                ui_print!("   🔗 Tracking {}", upstream.themed(Element::Branch));
                 
                 match (branch_info.ahead, branch_info.behind) {
-                    (0, 0) => println!(" {}", "(up to date)".green()),
+                    (0, 0) => println!(" {}", "(up to date)".themed(Element::Success)),
                     (ahead, 0) if ahead > 0 => {
-                        println!(" {} {} ahead", 
-                                "↑".green().bold(), 
-                                format!("({} commit{})", ahead, if ahead == 1 { "" } else { "s" }).green())
+                        ui_println!(" {} {} ahead", 
+                                "↑".themed(Element::Added).bold(), 
+                                format!("({} commit{})", ahead, if ahead == 1 { "" } else { "s" }).themed(Element::Added))
                     }
                     (0, behind) if behind > 0 => {
-                        println!(" {} {} behind", 
-                                "↓".red().bold(), 
-                                format!("({} commit{})", behind, if behind == 1 { "" } else { "s" }).red())
+                        ui_println!(" {} {} behind", 
+                                "↓".themed(Element::Removed).bold(), 
+                                format!("({} commit{})", behind, if behind == 1 { "" } else { "s" }).themed(Element::Removed))
                     }
                     (ahead, behind) if ahead > 0 && behind > 0 => {
-                        println!(" {} {} ahead, {} {} behind",
-                                "↑".green().bold(),
-                                format!("({} commit{})", ahead, if ahead == 1 { "" } else { "s" }).green(),
-                                "↓".red().bold(),
-                                format!("({} commit{})", behind, if behind == 1 { "" } else { "s" }).red())
+                        ui_println!(" {} {} ahead, {} {} behind",
+                                "↑".themed(Element::Added).bold(),
+                                format!("({} commit{})", ahead, if ahead == 1 { "" } else { "s" }).themed(Element::Added),
+                                "↓".themed(Element::Removed).bold(),
+                                format!("({} commit{})", behind, if behind == 1 { "" } else { "s" }).themed(Element::Removed))
                     }
                     _ => println!(),
                 }
             }
             None => {
-                println!("   {} {}", "⚠️".yellow(), "No upstream branch configured".yellow());
+                ui_println!("   {} {}", "⚠️".yellow(), "No upstream branch configured".themed(Element::Warning));
                 if !branch_info.name.contains("detached") {
                     println!("      Use 'rgit push --set-upstream origin {}' to set upstream", 
-                            branch_info.name.cyan());
+                            branch_info.name.themed(Element::Branch));
                 }
             }
         }
@@ -254,8 +256,8 @@ impl StatusDisplay {
             let time_ago = format_time_ago(commit.time);
             let short_message = truncate_string(&commit.message.lines().next().unwrap_or(""), 60);
             
-            println!("   📝 Last commit: {} {} by {} {}",
-                    commit.oid[..8].yellow(),
+            ui_println!("   📝 Last commit: {} {} by {} {}",
+                    commit.oid[..8].themed(Element::Commit),
                     short_message.white(),
                     commit.author.cyan(),
                     time_ago.dimmed());
@@ -270,16 +272,16 @@ impl StatusDisplay {
         let unstaged_count = status.unstaged.len();
         let untracked_count = status.untracked.len();
 
-        println!("{} {} total changes:", "📊".blue(), total_changes.to_string().bold());
+        ui_println!("{} {} total changes:", "📊".blue(), total_changes.to_string().bold());
         
         if staged_count > 0 {
-            println!("   {} {} staged", "✅".green(), staged_count.to_string().green().bold());
+            ui_println!("   {} {} staged", "✅".green(), staged_count.to_string().themed(Element::Added).bold());
         }
         if unstaged_count > 0 {
-            println!("   {} {} unstaged", "📝".yellow(), unstaged_count.to_string().yellow().bold());
+            ui_println!("   {} {} unstaged", "📝".yellow(), unstaged_count.to_string().themed(Element::Modified).bold());
         }
         if untracked_count > 0 {
-            println!("   {} {} untracked", "❓".red(), untracked_count.to_string().red().bold());
+            ui_println!("   {} {} untracked", "❓".red(), untracked_count.to_string().themed(Element::Untracked).bold());
         }
 
         Ok(())
@@ -287,9 +289,9 @@ impl StatusDisplay {
 
     /// Display staged changes section
     fn display_staged_changes(&self, staged: &[FileStatus]) -> Result<()> {
-        println!("{} {} to be committed:", 
+        ui_println!("{} {} to be committed:", 
                 "📦".green().bold(), 
-                "Changes".green().bold());
+                "Changes".themed(Element::Added).bold());
         
         for file in staged {
             self.display_file_status(file, true)?;
@@ -301,34 +303,34 @@ impl StatusDisplay {
 
     /// Display unstaged changes section
     fn display_unstaged_changes(&self, unstaged: &[FileStatus]) -> Result<()> {
-        println!("{} {} not staged for commit:", 
+        ui_println!("{} {} not staged for commit:", 
                 "📝".yellow().bold(), 
-                "Changes".yellow().bold());
+                "Changes".themed(Element::Modified).bold());
         
         for file in unstaged {
             self.display_file_status(file, false)?;
         }
         
-        println!("  {} Use \"{}\" to stage changes",
+        ui_println!("  {} Use \"{}\" to stage changes",
                 "💡".blue(),
-                "rgit add <file>...".cyan());
+                "rgit add <file>...".themed(Element::Hint));
         println!();
         Ok(())
     }
 
     /// Display untracked files section
     fn display_untracked_files(&self, untracked: &[FileStatus]) -> Result<()> {
-        println!("{} {} files:", 
+        ui_println!("{} {} files:", 
                 "❓".red().bold(), 
-                "Untracked".red().bold());
+                "Untracked".themed(Element::Untracked).bold());
         
         for file in untracked {
             self.display_file_status(file, false)?;
         }
         
-        println!("  {} Use \"{}\" to include in what will be committed",
+        ui_println!("  {} Use \"{}\" to include in what will be committed",
                 "💡".blue(),
-                "rgit add <file>...".cyan());
+                "rgit add <file>...".themed(Element::Hint));
         println!();
         Ok(())
     }
@@ -336,28 +338,17 @@ impl StatusDisplay {
     /// Display individual file status with formatting
     fn display_file_status(&self, file: &FileStatus, staged: bool) -> Result<()> {
         let status_symbol = file.status_symbol(staged);
-        let (status_icon, status_color) = if staged {
-            ("✓", "green")
+        let (status_icon, element) = if staged {
+            ("✓", Element::Added)
         } else if status_symbol == "untracked" {
-            ("?", "red")
+            ("?", Element::Untracked)
         } else {
-            ("○", "yellow")
+            ("○", Element::Modified)
         };
 
         let mut line = format!("  {} {}:",
-            match status_color {
-                "green" => status_icon.green().bold().to_string(),
-                "red" => status_icon.red().bold().to_string(),
-                "yellow" => status_icon.yellow().bold().to_string(),
-                _ => status_icon.normal().to_string(),
-            },
-            match status_color {
-                "green" => status_symbol.green().to_string(),
-                "red" => status_symbol.red().to_string(),
-                "yellow" => status_symbol.yellow().to_string(),
-                _ => status_symbol.normal().to_string(),
-            }
-        );
+            decoration(status_icon).themed(element).bold(),
+            status_symbol.themed(element));
 
         // File path with proper formatting
         let file_path = if file.path.len() > 50 {
@@ -393,9 +384,9 @@ impl StatusDisplay {
 
     /// Display clean working tree status
     fn display_clean_status(&self) -> Result<()> {
-        println!("{} {}", 
+        ui_println!("{} {}", 
                 "✨".green(), 
-                "Working tree clean".green().bold());
+                "Working tree clean".themed(Element::Success).bold());
         
         if self.show_details {
             println!("   Nothing to commit, working tree clean");
@@ -407,19 +398,19 @@ impl StatusDisplay {
     /// Display helpful hints based on current status
     fn display_hints(&self, status: &RepositoryStatus) -> Result<()> {
         if !status.is_clean() {
-            println!("{} {} Helpful commands:", "💡".blue(), "Tip:".bold());
+            ui_println!("{} {} Helpful commands:", "💡".blue(), "Tip:".bold());
             
             if !status.unstaged.is_empty() || !status.untracked.is_empty() {
-                println!("   • {} - Interactive file selection", "rgit add".cyan());
+                ui_println!("   • {} - Interactive file selection", "rgit add".themed(Element::Hint));
             }
             
             if !status.staged.is_empty() {
-                println!("   • {} - Commit staged changes", "rgit commit".cyan());
+                ui_println!("   • {} - Commit staged changes", "rgit commit".themed(Element::Hint));
             }
             
             if !status.is_clean() {
-                println!("   • {} - Quick commit workflow", "rgit quick-commit".cyan());
-                println!("   • {} - Sync with remote", "rgit sync".cyan());
+                ui_println!("   • {} - Quick commit workflow", "rgit quick-commit".themed(Element::Hint));
+                ui_println!("   • {} - Sync with remote", "rgit sync".themed(Element::Hint));
             }
             
             println!();
@@ -433,7 +424,7 @@ impl StatusDisplay {
         let submodules = rgit.repo.submodules()?;
         
         if !submodules.is_empty() {
-            println!("{} {} status:", "📦".blue().bold(), "Submodule".blue().bold());
+            ui_println!("{} {} status:", "📦".blue().bold(), "Submodule".blue().bold());
             
             for submodule in submodules {
                 let name = submodule.name().unwrap_or("unknown");
@@ -445,14 +436,14 @@ impl StatusDisplay {
                     "❓"
                 };
                 
-                println!("  {} {} {}", 
+                ui_println!("  {} {} {}", 
                         status_icon,
                         name.cyan(),
                         format!("({})", path).dimmed());
             }
             
-            println!("   💡 Use \"{}\" for detailed submodule information",
-                    "rgit submodule status".cyan());
+            ui_println!("   💡 Use \"{}\" for detailed submodule information",
+                    "rgit submodule status".themed(Element::Hint));
             println!();
         }
         
@@ -543,20 +534,8 @@ pub fn calculate_column_widths(rows: &[Vec<String>], terminal_width: usize) -> V
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
     use std::fs;
 
-    fn create_test_repo_with_files() -> (TempDir, git2::Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = git2::Repository::init(temp_dir.path()).unwrap();
-        
-        // Create some test files
-        fs::write(temp_dir.path().join("test.txt"), "test content").unwrap();
-        fs::write(temp_dir.path().join("new_file.txt"), "new content").unwrap();
-        
-        (temp_dir, repo)
-    }
-
     #[test]
     fn test_status_display_creation() {
         let display = StatusDisplay::new();
@@ -1,6 +1,8 @@
 use anyhow::{Context, Result};
 use colored::*;
 use git2::*;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
@@ -9,6 +11,8 @@ use crate::core::RgitCore;
 use crate::error::RgitError;
 use crate::interactive::InteractivePrompt;
 use crate::config::Config;
+use crate::network;
+use crate::timings::{self, Phase};
 
 /// Intelligent submodule manager with proactive health checking
 pub struct SubmoduleManager<'a> {
@@ -179,13 +183,13 @@ impl<'a> SubmoduleManager<'a> {
         
         for (name, status) in &health.submodules {
             if !status.issues.is_empty() {
-                println!("  {} {} ({} issues)", 
+                ui_println!("  {} {} ({} issues)", 
                         "📦".red(), 
                         name.yellow(), 
                         status.issues.len().to_string().red());
                 
                 for issue in &status.issues {
-                    println!("    {} {}", "•".red(), issue.description());
+                    ui_println!("    {} {}", "•".red(), issue.description());
                 }
             }
         }
@@ -196,7 +200,7 @@ impl<'a> SubmoduleManager<'a> {
 
     /// Display detailed submodule status
     pub fn display_detailed_status(&self, health: &SubmoduleHealth) -> Result<()> {
-        println!("\n{} Detailed Submodule Status:", "📋".blue().bold());
+        ui_println!("\n{} Detailed Submodule Status:", "📋".blue().bold());
         
         for (name, status) in &health.submodules {
             self.display_submodule_details(name, status)?;
@@ -207,33 +211,33 @@ impl<'a> SubmoduleManager<'a> {
 
     /// Display details for a single submodule
     pub fn display_submodule_details(&self, name: &str, status: &SubmoduleStatus) -> Result<()> {
-        println!("\n🗂️  {} {}", name.cyan().bold(), 
+        ui_println!("\n🗂️  {} {}", name.cyan().bold(), 
                 format!("({})", status.path.display()).dimmed());
         
         if let Some(ref url) = status.url {
-            println!("   {} {}", "🔗".blue(), url.cyan());
+            ui_println!("   {} {}", "🔗".blue(), url.cyan());
         }
         
         if let Some(ref branch) = status.branch {
-            println!("   {} {}", "🌿".green(), branch.green());
+            ui_println!("   {} {}", "🌿".green(), branch.green());
         }
         
         if status.initialized {
-            println!("   {} Initialized", "✅".green());
+            ui_println!("   {} Initialized", "✅".green());
         } else {
-            println!("   {} Not initialized", "❓".red());
+            ui_println!("   {} Not initialized", "❓".red());
         }
         
         if status.issues.is_empty() {
-            println!("   {} No issues detected", "🎉".green());
+            ui_println!("   {} No issues detected", "🎉".green());
         } else {
-            println!("   {} Issues:", "⚠️".yellow());
+            ui_println!("   {} Issues:", "⚠️".yellow());
             for issue in &status.issues {
-                println!("     {} {}", "•".red(), issue.description());
+                ui_println!("     {} {}", "•".red(), issue.description());
                 
                 // Show suggested fixes
                 for suggestion in issue.suggestions() {
-                    println!("       {} {}", "💡".yellow(), suggestion.dimmed());
+                    ui_println!("       {} {}", "💡".yellow(), suggestion.dimmed());
                 }
             }
         }
@@ -354,7 +358,7 @@ impl<'a> SubmoduleManager<'a> {
 
     /// Check if submodule has uncommitted changes
     pub fn has_uncommitted_changes(&self, repo: &Repository) -> Result<bool> {
-        let statuses = repo.statuses(None)?;
+        let statuses = timings::time(Phase::StatusScan, || repo.statuses(None))?;
         Ok(!statuses.is_empty())
     }
 
@@ -413,38 +417,91 @@ impl<'a> SubmoduleManager<'a> {
         url.starts_with("https://") || 
         url.starts_with("git://") || 
         url.starts_with("ssh://") ||
-        url.contains("@") && url.contains(":")
+        url.starts_with("file://") ||
+        url.contains("@") && url.contains(":") ||
+        // Local paths, and URLs relative to the superproject's remote
+        url.starts_with('/') || url.starts_with("./") || url.starts_with("../")
     }
 
     /// Update all submodules
     pub fn update_all(&self, recursive: bool, init: bool) -> Result<()> {
         info!("Updating all submodules (recursive: {}, init: {})", recursive, init);
         
-        let submodules = self.rgit.repo.submodules()?;
+        let paths: Vec<PathBuf> = self.rgit.repo.submodules()?
+            .iter()
+            .map(|submodule| submodule.path().to_path_buf())
+            .collect();
+        let options = UpdateOptions { init, recursive, ..Default::default() };
+        let outcomes = self.update_paths(&paths, options, self.update_jobs(None))?;
         
-        for mut submodule in submodules {
-            let name = submodule.name().unwrap_or("unknown");
-            self.rgit.log(&format!("Updating submodule: {}", name));
-            
-            if init && !submodule.open().is_ok() {
-                submodule.init(false)?;
-            }
-            
-            submodule.update(init, None)?;
-            
-            if recursive {
-                // Recursively update nested submodules
-                if let Ok(sub_repo) = submodule.open() {
-                    let sub_manager = SubmoduleManager {
-                        rgit: &RgitCore::from_path(sub_repo.workdir().unwrap(), self.rgit.verbose)?,
-                        config: self.config,
-                    };
-                    sub_manager.update_all(true, init)?;
+        let failed: Vec<String> = outcomes.iter()
+            .filter_map(|outcome| outcome.error.as_ref().map(|e| format!("{}: {}", outcome.name, e)))
+            .collect();
+        if !failed.is_empty() {
+            return Err(RgitError::SubmoduleOperationFailed(failed.join("; ")).into());
+        }
+        Ok(())
+    }
+
+    /// How many submodules to update at once: `requested`, or
+    /// `submodules.max_jobs` when `submodules.parallel` is on
+    pub fn update_jobs(&self, requested: Option<usize>) -> usize {
+        let configured = if self.config.submodules.parallel { self.config.submodules.max_jobs } else { 1 };
+        requested.unwrap_or(configured).max(1)
+    }
+
+    /// Update the submodules at `paths`, `jobs` at a time, with a progress
+    /// bar per submodule. Failures are reported per submodule, in order.
+    pub fn update_paths(&self, paths: &[PathBuf], options: UpdateOptions, jobs: usize) -> Result<Vec<UpdateOutcome>> {
+        let git_dir = self.rgit.repo.path().to_path_buf();
+        let progress = if self.config.ui.progress {
+            MultiProgress::new()
+        } else {
+            MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+        };
+        let style = ProgressStyle::with_template("{spinner:.green} {prefix:.cyan} [{bar:25.cyan/blue}] {pos}/{len} {msg}")?
+            .progress_chars("=> ");
+
+        // Initialising writes the superproject's config, which only one
+        // thread can lock at a time, so it happens up front. Failures show
+        // up again, per submodule, when the update retries the init.
+        if options.init {
+            for path in paths {
+                let Some(mut submodule) = path.to_str().and_then(|p| self.rgit.repo.find_submodule(p).ok()) else { continue };
+                if submodule.open().is_err() {
+                    let _ = submodule.init(false);
                 }
             }
         }
-        
-        Ok(())
+
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs.max(1)).build()?;
+        let outcomes = pool.install(|| {
+            paths.par_iter()
+                .map(|path| {
+                    let bar = progress.add(ProgressBar::new(0).with_style(style.clone()));
+                    bar.set_prefix(path.display().to_string());
+                    bar.set_message("fetching");
+                    bar.enable_steady_tick(std::time::Duration::from_millis(120));
+
+                    // Repositories can't be shared between threads, so each update opens its own
+                    let result = Repository::open(&git_dir)
+                        .map_err(anyhow::Error::from)
+                        .and_then(|repo| update_submodule(&repo, path, options, &bar, self.config));
+                    let (name, summary, error) = match result {
+                        Ok((name, summary)) => {
+                            bar.finish_with_message(format!("✅ {}", summary));
+                            (name, summary, None)
+                        }
+                        Err(e) => {
+                            bar.abandon_with_message(format!("❌ {}", e));
+                            (path.display().to_string(), String::new(), Some(e.to_string()))
+                        }
+                    };
+                    UpdateOutcome { name, path: path.clone(), summary, error }
+                })
+                .collect()
+        });
+        Ok(outcomes)
     }
 
     /// Execute command in all submodules
@@ -477,10 +534,471 @@ impl<'a> SubmoduleManager<'a> {
     }
 }
 
+// =============================================================================
+// Updating
+// =============================================================================
+
+/// Update one submodule (and, when asked, the submodules inside it),
+/// reporting fetch progress on `bar`. Returns the submodule's name and a
+/// summary of where it ended up.
+fn update_submodule(repo: &Repository, path: &Path, options: UpdateOptions, bar: &ProgressBar, config: &Config) -> Result<(String, String)> {
+    let path_str = path.to_str()
+        .ok_or_else(|| RgitError::SubmoduleError(format!("Invalid submodule path: {}", path.display())))?;
+    let mut submodule = repo.find_submodule(path_str)?;
+    let name = submodule.name().unwrap_or(path_str).to_string();
+
+    if options.init && submodule.open().is_err() {
+        submodule.init(false)?;
+    }
+
+    // With --remote, a checked out submodule moves on from wherever it is
+    if !options.remote || submodule.open().is_err() {
+        let mut update_options = SubmoduleUpdateOptions::new();
+        update_options.fetch(progress_fetch_options(bar, config));
+        if options.force {
+            let mut checkout = build::CheckoutBuilder::new();
+            checkout.force();
+            update_options.checkout(checkout);
+        }
+        network::with_retries_blocking(config, || {
+            timings::time(Phase::Network, || submodule.update(options.init, Some(&mut update_options)))
+        }, |e| RgitError::SubmoduleError(e.message().to_string()))?;
+    }
+
+    let sub_repo = submodule.open()?;
+    let summary = if options.remote {
+        bar.set_message("fetching remote branch");
+        let branch = tracked_branch(repo, &submodule, &sub_repo).ok_or_else(|| RgitError::SubmoduleError(
+            format!("No branch to follow for '{}'; set one with 'rgit submodule set-branch'", name)
+        ))?;
+        let remote = sub_repo.find_remote(SUBMODULE_REMOTE)?;
+        // A rewritten remote is anonymous, so the refspecs are passed explicitly
+        let refspecs: Vec<String> = remote.fetch_refspecs()?.iter().flatten().map(String::from).collect();
+        let mut remote = network::rewritten(&sub_repo, remote, config, Direction::Fetch)?;
+        network::with_retries_blocking(config, || {
+            timings::time(Phase::Network, || remote.fetch(&refspecs, Some(&mut progress_fetch_options(bar, config)), None))
+        }, |e| RgitError::SubmoduleError(e.message().to_string()))?;
+        let tip = fast_forward_to_remote(&sub_repo, &branch, options.force)?;
+        format!("{}/{} at {}", SUBMODULE_REMOTE, branch, crate::utils::shorten_oid(&tip, 7))
+    } else {
+        let head = sub_repo.head()?.peel_to_commit()?.id();
+        format!("checked out {}", crate::utils::shorten_oid(&head, 7))
+    };
+
+    if options.recursive {
+        for nested in sub_repo.submodules()? {
+            bar.set_message(format!("updating {}", nested.path().display()));
+            update_submodule(&sub_repo, nested.path(), options, bar, config)?;
+        }
+    }
+    Ok((name, summary))
+}
+
+/// Fetch options that report transfer progress on `bar`
+fn progress_fetch_options<'a>(bar: &'a ProgressBar, config: &'a Config) -> FetchOptions<'a> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+    callbacks.transfer_progress(|stats| {
+        bar.set_length(stats.total_objects() as u64);
+        bar.set_position(stats.received_objects() as u64);
+        true
+    });
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    fetch_options
+}
+
+/// Move the submodule to the tip of `origin/<branch>`, refusing to drop
+/// local commits unless `force`. A checked out `branch` is fast-forwarded,
+/// anything else is left detached at the tip, like `git submodule update --remote`.
+fn fast_forward_to_remote(sub_repo: &Repository, branch: &str, force: bool) -> Result<Oid> {
+    let tip = sub_repo.refname_to_id(&format!("refs/remotes/{}/{}", SUBMODULE_REMOTE, branch))
+        .map_err(|_| RgitError::SubmoduleError(format!("Remote branch '{}/{}' not found", SUBMODULE_REMOTE, branch)))?;
+    let head = sub_repo.head()?;
+    let current = head.peel_to_commit()?.id();
+    if current != tip && !force && !sub_repo.graph_descendant_of(tip, current)? {
+        return Err(RgitError::SubmoduleError(format!(
+            "HEAD has commits that aren't on '{}/{}'; use --force to move it anyway", SUBMODULE_REMOTE, branch
+        )).into());
+    }
+
+    let mut checkout = build::CheckoutBuilder::new();
+    if force {
+        checkout.force();
+    } else {
+        checkout.safe();
+    }
+    sub_repo.checkout_tree(sub_repo.find_commit(tip)?.as_object(), Some(&mut checkout))?;
+
+    let on_branch = head.is_branch() && head.shorthand() == Some(branch);
+    match head.name() {
+        Some(head_name) if on_branch => {
+            sub_repo.reference(head_name, tip, true, "submodule update --remote: fast-forward")?;
+        }
+        _ => sub_repo.set_head_detached(tip)?,
+    }
+    Ok(tip)
+}
+
+// =============================================================================
+// Branch Tracking
+// =============================================================================
+
+/// The remote `update --remote` fetches from in each submodule
+pub const SUBMODULE_REMOTE: &str = "origin";
+
+/// The branch `update --remote` follows: `submodule.<name>.branch` from
+/// `.git/config` or `.gitmodules`, where "." means the superproject's
+/// current branch, falling back to the remote's default branch
+pub fn tracked_branch(repo: &Repository, submodule: &Submodule<'_>, sub_repo: &Repository) -> Option<String> {
+    let configured = submodule.name()
+        .and_then(|name| {
+            repo.config().ok()?.get_string(&format!("submodule.{}.branch", name)).ok()
+        })
+        .or_else(|| submodule.branch().map(str::to_string));
+
+    match configured.as_deref() {
+        Some(".") => repo.head().ok()?.shorthand().map(str::to_string),
+        Some(branch) => Some(branch.to_string()),
+        None => {
+            let default = sub_repo.find_reference(&format!("refs/remotes/{}/HEAD", SUBMODULE_REMOTE)).ok()?;
+            let target = default.symbolic_target()?;
+            target.strip_prefix(&format!("refs/remotes/{}/", SUBMODULE_REMOTE)).map(str::to_string)
+        }
+    }
+}
+
+/// Record `branch` as the one the submodule at `path` follows in
+/// `.gitmodules`, or go back to the remote's default branch with `None`.
+/// Returns the submodule's name.
+pub fn set_tracked_branch(repo: &Repository, path: &str, branch: Option<&str>) -> Result<String> {
+    let submodule = repo.find_submodule(path)
+        .map_err(|_| RgitError::SubmoduleNotFound(path.to_string()))?;
+    let name = submodule.name().unwrap_or(path).to_string();
+    let workdir = repo.workdir()
+        .ok_or_else(|| RgitError::SubmoduleError("Submodules need a working tree".to_string()))?;
+
+    let mut gitmodules = git2::Config::open(&workdir.join(".gitmodules"))?;
+    let key = format!("submodule.{}.branch", name);
+    match branch {
+        Some(branch) => gitmodules.set_str(&key, branch)?,
+        None => match gitmodules.remove(&key) {
+            Err(e) if e.code() != ErrorCode::NotFound => return Err(e.into()),
+            _ => {}
+        },
+    }
+    Ok(name)
+}
+
+/// How far the commit a submodule is recorded at is from its remote branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteDrift {
+    pub branch: String,
+    /// Commits recorded in the superproject that the remote branch lacks
+    pub ahead: usize,
+    /// Commits on the remote branch newer than the recorded one
+    pub behind: usize,
+}
+
+/// Compare the recorded commit with the last fetched tip of the tracked
+/// branch, or `None` when the submodule isn't checked out or tracks nothing
+pub fn remote_drift(repo: &Repository, submodule: &Submodule<'_>) -> Result<Option<RemoteDrift>> {
+    let Ok(sub_repo) = submodule.open() else { return Ok(None) };
+    let Some(branch) = tracked_branch(repo, submodule, &sub_repo) else { return Ok(None) };
+    let Some(recorded) = submodule.index_id().or_else(|| submodule.head_id()) else { return Ok(None) };
+    let Ok(tip) = sub_repo.refname_to_id(&format!("refs/remotes/{}/{}", SUBMODULE_REMOTE, branch)) else {
+        return Ok(None);
+    };
+    let (ahead, behind) = sub_repo.graph_ahead_behind(recorded, tip)?;
+    Ok(Some(RemoteDrift { branch, ahead, behind }))
+}
+
+// =============================================================================
+// Syncing and Deinitializing
+// =============================================================================
+
+/// Copy the URL of the submodule at `path` from `.gitmodules` into
+/// `.git/config` and the submodule's own `origin`, and with `recursive` do
+/// the same inside it. Returns the paths synced, nested ones included.
+pub fn sync_submodule(repo: &Repository, path: &Path, recursive: bool) -> Result<Vec<PathBuf>> {
+    let path_str = path.to_str()
+        .ok_or_else(|| RgitError::SubmoduleError(format!("Invalid submodule path: {}", path.display())))?;
+    let mut submodule = repo.find_submodule(path_str)
+        .map_err(|_| RgitError::SubmoduleNotFound(path_str.to_string()))?;
+    submodule.sync()?;
+    let mut synced = vec![path.to_path_buf()];
+
+    if recursive {
+        if let Ok(sub_repo) = submodule.open() {
+            for nested in sub_repo.submodules()? {
+                synced.extend(sync_submodule(&sub_repo, nested.path(), true)?
+                    .into_iter()
+                    .map(|nested_path| path.join(nested_path)));
+            }
+        }
+    }
+    Ok(synced)
+}
+
+/// Deinitialize the submodule at `path`: empty its working tree and drop its
+/// `.git/config` entries, leaving the clone in `.git/modules` for a later
+/// `init`. With `remove`, also delete the directory, its `.gitmodules` entry
+/// and its index entry, staging the change like `git rm` does.
+pub fn deinit_submodule(repo: &Repository, path: &Path, remove: bool) -> Result<String> {
+    let path_str = path.to_str()
+        .ok_or_else(|| RgitError::SubmoduleError(format!("Invalid submodule path: {}", path.display())))?;
+    let submodule = repo.find_submodule(path_str)
+        .map_err(|_| RgitError::SubmoduleNotFound(path_str.to_string()))?;
+    let name = submodule.name().unwrap_or(path_str).to_string();
+    let submodule_path = submodule.path().to_path_buf();
+
+    let workdir = repo.workdir()
+        .ok_or_else(|| RgitError::SubmoduleError("Submodules need a working tree".to_string()))?;
+    let worktree = workdir.join(&submodule_path);
+    if worktree.is_dir() {
+        std::fs::remove_dir_all(&worktree)
+            .with_context(|| format!("Failed to remove {}", worktree.display()))?;
+    }
+    if !remove {
+        // Like git, leave an empty directory behind
+        std::fs::create_dir_all(&worktree)?;
+    }
+
+    remove_submodule_section(&repo.path().join("config"), &name)?;
+
+    if remove {
+        let gitmodules = workdir.join(".gitmodules");
+        if gitmodules.is_file() {
+            remove_submodule_section(&gitmodules, &name)?;
+        }
+        let mut index = repo.index()?;
+        index.remove_path(&submodule_path)?;
+        if gitmodules.is_file() {
+            index.add_path(Path::new(".gitmodules"))?;
+        }
+        timings::time(Phase::IndexWrite, || index.write())?;
+    }
+    Ok(name)
+}
+
+/// Delete the `[submodule "<name>"]` section from the config file at `path`
+fn remove_submodule_section(path: &Path, name: &str) -> Result<()> {
+    let prefix = format!("submodule.{}.", name);
+    let mut config = git2::Config::open(path)?;
+    let mut keys: Vec<String> = Vec::new();
+    let mut entries = config.entries(None)?;
+    while let Some(entry) = entries.next() {
+        if let Some(key) = entry?.name() {
+            if key.starts_with(&prefix) && !keys.iter().any(|k| k == key) {
+                keys.push(key.to_string());
+            }
+        }
+    }
+    drop(entries);
+    for key in keys {
+        config.remove_multivar(&key, ".*")?;
+    }
+    drop(config);
+
+    // libgit2 keeps the emptied section header around, git doesn't
+    let header = format!("[submodule \"{}\"]", name);
+    let content = std::fs::read_to_string(path)?;
+    let mut lines: Vec<&str> = content.lines().collect();
+    if let Some(index) = lines.iter().position(|line| line.trim() == header) {
+        let empty = lines[index + 1..].iter()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty() && !line.starts_with(['#', ';']))
+            .is_none_or(|line| line.starts_with('['));
+        if empty {
+            lines.remove(index);
+            let mut updated = lines.join("\n");
+            if !updated.is_empty() {
+                updated.push('\n');
+            }
+            std::fs::write(path, updated)?;
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// Committing Across Submodules
+// =============================================================================
+
+/// A submodule commit made by a [`CommitTransaction`]
+#[derive(Debug, Clone)]
+pub struct SubmoduleCommit {
+    /// Path from the superproject's root
+    pub path: PathBuf,
+    pub previous: Oid,
+    pub commit: Oid,
+}
+
+/// Commits staged work in every submodule, innermost first, and stages the
+/// new pointers in each parent, so the superproject commit that follows
+/// records them all. Until [`CommitTransaction::rollback`] is ruled out,
+/// every touched repository can be put back as it was.
+#[derive(Debug, Default)]
+pub struct CommitTransaction {
+    /// The superproject's working tree
+    root: PathBuf,
+    commits: Vec<SubmoduleCommit>,
+    /// Index files as they were before the transaction touched them
+    indexes: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl CommitTransaction {
+    /// Submodules that `commit` would commit in, innermost first: those with
+    /// staged changes and those containing one
+    pub fn plan(repo: &Repository) -> Result<Vec<PathBuf>> {
+        fn visit(repo: &Repository, prefix: &Path, planned: &mut Vec<PathBuf>) -> Result<bool> {
+            let mut any = false;
+            for submodule in repo.submodules()? {
+                let Ok(sub_repo) = submodule.open() else { continue };
+                let path = prefix.join(submodule.path());
+                let nested = visit(&sub_repo, &path, planned)?;
+                if nested || has_staged_changes(&sub_repo)? {
+                    planned.push(path);
+                    any = true;
+                }
+            }
+            Ok(any)
+        }
+
+        let mut planned = Vec::new();
+        visit(repo, Path::new(""), &mut planned)?;
+        Ok(planned)
+    }
+
+    /// Commit every submodule with staged changes using `message` and stage
+    /// the results in the superproject. Rolls back on the first failure.
+    pub fn commit(repo: &Repository, message: &str) -> Result<Self> {
+        let root = repo.workdir()
+            .ok_or_else(|| RgitError::SubmoduleError("Submodules need a working tree".to_string()))?;
+        let mut transaction = Self { root: root.to_path_buf(), ..Default::default() };
+        let result = transaction.snapshot(repo)
+            .and_then(|_| transaction.commit_nested(repo, Path::new(""), message))
+            .and_then(|committed| stage_paths(repo, &committed));
+        match result {
+            Ok(()) => Ok(transaction),
+            Err(e) => {
+                transaction.rollback(repo)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// The submodule commits made so far, innermost first
+    pub fn commits(&self) -> &[SubmoduleCommit] {
+        &self.commits
+    }
+
+    /// Move every committed submodule back to its previous commit, keeping
+    /// the changes staged, and restore the indexes that were touched,
+    /// reloading the superproject `repo`'s
+    pub fn rollback(&self, repo: &Repository) -> Result<()> {
+        for commit in self.commits.iter().rev() {
+            let sub_repo = Repository::open(self.root.join(&commit.path))?;
+            let previous = sub_repo.find_object(commit.previous, None)?;
+            sub_repo.reset(&previous, ResetType::Soft, None)?;
+        }
+        for (path, content) in &self.indexes {
+            match content {
+                Some(content) => std::fs::write(path, content)?,
+                None => {
+                    if path.exists() {
+                        std::fs::remove_file(path)?;
+                    }
+                }
+            }
+        }
+        repo.index()?.read(true)?;
+        Ok(())
+    }
+
+    fn snapshot(&mut self, repo: &Repository) -> Result<()> {
+        let path = repo.path().join("index");
+        let content = if path.exists() { Some(std::fs::read(&path)?) } else { None };
+        self.indexes.push((path, content));
+        Ok(())
+    }
+
+    /// Commit inside the submodules of `repo`, returning the paths (relative
+    /// to `repo`) of those that got a new commit
+    fn commit_nested(&mut self, repo: &Repository, prefix: &Path, message: &str) -> Result<Vec<PathBuf>> {
+        let mut committed = Vec::new();
+        for submodule in repo.submodules()? {
+            let Ok(sub_repo) = submodule.open() else { continue };
+            let path = prefix.join(submodule.path());
+            self.snapshot(&sub_repo)?;
+
+            let nested = self.commit_nested(&sub_repo, &path, message)?;
+            stage_paths(&sub_repo, &nested)?;
+            if !has_staged_changes(&sub_repo)? {
+                continue;
+            }
+
+            let previous = sub_repo.head()?.peel_to_commit()?.id();
+            let workdir = sub_repo.workdir()
+                .ok_or_else(|| RgitError::SubmoduleError(format!("'{}' has no working tree", path.display())))?;
+            let commit = RgitCore::from_path(workdir, false)?.commit(message, false)
+                .with_context(|| format!("Failed to commit in '{}'", path.display()))?;
+            self.commits.push(SubmoduleCommit { path, previous, commit });
+            committed.push(submodule.path().to_path_buf());
+        }
+        Ok(committed)
+    }
+}
+
+/// Whether the index differs from HEAD, conflicts included
+fn has_staged_changes(repo: &Repository) -> Result<bool> {
+    let staged = Status::INDEX_NEW | Status::INDEX_MODIFIED | Status::INDEX_DELETED
+        | Status::INDEX_RENAMED | Status::INDEX_TYPECHANGE | Status::CONFLICTED;
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).exclude_submodules(false);
+    Ok(timings::time(Phase::StatusScan, || repo.statuses(Some(&mut options)))?.iter().any(|entry| entry.status().intersects(staged)))
+}
+
+/// Stage the current HEADs of the submodules at `paths`
+fn stage_paths(repo: &Repository, paths: &[PathBuf]) -> Result<()> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+    let mut index = repo.index()?;
+    for path in paths {
+        index.add_path(path)?;
+    }
+    timings::time(Phase::IndexWrite, || index.write())?;
+    Ok(())
+}
+
 // =============================================================================
 // Data Structures
 // =============================================================================
 
+/// How `update_paths` updates each submodule
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UpdateOptions {
+    /// Initialize submodules that aren't yet
+    pub init: bool,
+    /// Also update submodules nested inside each one
+    pub recursive: bool,
+    /// Throw away local changes in the submodule's working tree
+    pub force: bool,
+    /// Move to the tip of the tracked remote branch instead of the recorded commit
+    pub remote: bool,
+}
+
+/// Result of updating one submodule
+#[derive(Debug, Clone)]
+pub struct UpdateOutcome {
+    pub name: String,
+    pub path: PathBuf,
+    /// Where the submodule ended up, e.g. "checked out 1a2b3c4"
+    pub summary: String,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Default)]
 pub struct SubmoduleHealth {
     pub submodules: HashMap<String, SubmoduleStatus>,
@@ -641,14 +1159,169 @@ impl IssueSeverity {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use tempfile::TempDir;
+    use crate::test_support::{TestRepo, DEFAULT_BRANCH};
 
-    fn create_test_repo_with_submodule() -> (TempDir, Repository) {
-        let temp_dir = TempDir::new().unwrap();
-        let repo = Repository::init(temp_dir.path()).unwrap();
+    #[test]
+    fn test_update_paths_in_parallel() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "app\n", "App");
+        let mut libraries = Vec::new();
+        for path in ["vendor/a", "vendor/b"] {
+            let library = TestRepo::new();
+            let old = library.commit_file("lib.rs", "v1\n", "v1");
+            repo.add_submodule(&library, path);
+            let new = library.commit_file("lib.rs", "v2\n", "v2");
 
-        
-        (temp_dir, repo)
+            // Record v2 in the superproject, then leave the checkout at v1
+            let sub_repo = repo.repo().find_submodule(path).unwrap().open().unwrap();
+            sub_repo.find_remote("origin").unwrap().fetch(&["refs/heads/*:refs/remotes/origin/*"], None, None).unwrap();
+            sub_repo.set_head_detached(new).unwrap();
+            sub_repo.checkout_head(Some(build::CheckoutBuilder::new().force())).unwrap();
+            repo.stage(path);
+            repo.commit_staged(&format!("Bump {}", path));
+            sub_repo.set_head_detached(old).unwrap();
+            sub_repo.checkout_head(Some(build::CheckoutBuilder::new().force())).unwrap();
+            libraries.push((library, path, new));
+        }
+
+        let rgit = RgitCore::from_path(repo.path(), false).unwrap();
+        let config = Config::minimal();
+        let manager = SubmoduleManager::new(&rgit, &config);
+        let mut paths: Vec<PathBuf> = libraries.iter().map(|(_, path, _)| PathBuf::from(path)).collect();
+        paths.push(PathBuf::from("vendor/missing"));
+        let outcomes = manager.update_paths(&paths, UpdateOptions::default(), 2).unwrap();
+
+        assert_eq!(outcomes.len(), 3);
+        for ((_, path, new), outcome) in libraries.iter().zip(&outcomes) {
+            assert!(outcome.error.is_none(), "{:?}", outcome.error);
+            assert_eq!(outcome.name, *path);
+            let sub_repo = repo.repo().find_submodule(path).unwrap().open().unwrap();
+            assert_eq!(sub_repo.head().unwrap().target(), Some(*new));
+        }
+        assert!(outcomes[2].error.is_some());
+        assert_eq!(manager.update_jobs(Some(0)), 1);
+    }
+
+    #[test]
+    fn test_update_remote_follows_tracked_branch() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "app\n", "App");
+        let library = TestRepo::new();
+        let recorded = library.commit_file("lib.rs", "v1\n", "v1");
+        repo.add_submodule(&library, "vendor/lib");
+        library.branch("stable");
+        let tip = library.commit_file("lib.rs", "v2\n", "v2");
+
+        let rgit = RgitCore::from_path(repo.path(), false).unwrap();
+        let config = Config::minimal();
+        let manager = SubmoduleManager::new(&rgit, &config);
+        let submodule = || repo.repo().find_submodule("vendor/lib").unwrap();
+        let sub_repo = submodule().open().unwrap();
+        assert_eq!(tracked_branch(repo.repo(), &submodule(), &sub_repo).as_deref(), Some(DEFAULT_BRANCH));
+
+        let options = UpdateOptions { remote: true, ..Default::default() };
+        let paths = [PathBuf::from("vendor/lib")];
+        let outcomes = manager.update_paths(&paths, options, 1).unwrap();
+        assert!(outcomes[0].error.is_none(), "{:?}", outcomes[0].error);
+        assert_eq!(outcomes[0].summary, format!("origin/{} at {}", DEFAULT_BRANCH, crate::utils::shorten_oid(&tip, 7)));
+        assert_eq!(sub_repo.head().unwrap().target(), Some(tip));
+        let drift = remote_drift(repo.repo(), &submodule()).unwrap().unwrap();
+        assert_eq!((drift.branch.as_str(), drift.ahead, drift.behind), (DEFAULT_BRANCH, 0, 1));
+
+        // Following another branch moves back to it, but only with --force
+        // since the checkout now has a commit the branch lacks
+        assert_eq!(set_tracked_branch(repo.repo(), "vendor/lib", Some("stable")).unwrap(), "vendor/lib");
+        assert_eq!(submodule().branch(), Some("stable"));
+        let outcomes = manager.update_paths(&paths, options, 1).unwrap();
+        assert!(outcomes[0].error.as_deref().unwrap().contains("origin/stable"));
+        let force = UpdateOptions { force: true, ..options };
+        manager.update_paths(&paths, force, 1).unwrap();
+        assert_eq!(sub_repo.head().unwrap().target(), Some(recorded));
+
+        set_tracked_branch(repo.repo(), "vendor/lib", None).unwrap();
+        assert_eq!(submodule().branch(), None);
+    }
+
+    #[test]
+    fn test_commit_transaction_commits_and_rolls_back() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "app\n", "App");
+        let libraries: Vec<TestRepo> = ["vendor/a", "vendor/b"].iter().map(|path| {
+            let library = TestRepo::new();
+            library.commit_file("lib.rs", "v1\n", "v1");
+            repo.add_submodule(&library, path);
+            library
+        }).collect();
+        let open = |path: &str| repo.repo().find_submodule(path).unwrap().open().unwrap();
+        let stage_change = |path: &str| {
+            let sub_repo = open(path);
+            std::fs::write(sub_repo.workdir().unwrap().join("lib.rs"), "v2\n").unwrap();
+            let mut index = sub_repo.index().unwrap();
+            index.add_path(Path::new("lib.rs")).unwrap();
+            index.write().unwrap();
+        };
+        let head_of = |path: &str| open(path).head().unwrap().target().unwrap();
+        let recorded = |path: &str| repo.repo().index().unwrap().get_path(Path::new(path), 0).unwrap().id;
+
+        assert!(CommitTransaction::plan(repo.repo()).unwrap().is_empty());
+        stage_change("vendor/a");
+        assert_eq!(CommitTransaction::plan(repo.repo()).unwrap(), [PathBuf::from("vendor/a")]);
+
+        let before = head_of("vendor/a");
+        let transaction = CommitTransaction::commit(repo.repo(), "Bump a").unwrap();
+        assert_eq!(transaction.commits().len(), 1);
+        let after = head_of("vendor/a");
+        assert_ne!(after, before);
+        assert_eq!(recorded("vendor/a"), after);
+
+        transaction.rollback(repo.repo()).unwrap();
+        assert_eq!(head_of("vendor/a"), before);
+        assert_eq!(recorded("vendor/a"), before);
+        assert!(has_staged_changes(&open("vendor/a")).unwrap());
+
+        // A failure in the second submodule undoes the first one's commit
+        open("vendor/b").set_head("refs/heads/unborn").unwrap();
+        stage_change("vendor/b");
+        assert!(CommitTransaction::commit(repo.repo(), "Bump both").is_err());
+        assert_eq!(head_of("vendor/a"), before);
+        assert_eq!(recorded("vendor/a"), before);
+        drop(libraries);
+    }
+
+    #[test]
+    fn test_sync_and_deinit_submodule() {
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "app\n", "App");
+        let library = TestRepo::new();
+        library.commit_file("lib.rs", "v1\n", "v1");
+        repo.add_submodule(&library, "vendor/lib");
+
+        // Point .gitmodules somewhere else, as if the library moved
+        let moved = TestRepo::new();
+        let moved_url = format!("file://{}", moved.path().display());
+        git2::Config::open(&repo.path().join(".gitmodules")).unwrap()
+            .set_str("submodule.vendor/lib.url", &moved_url).unwrap();
+        let synced = sync_submodule(repo.repo(), Path::new("vendor/lib"), true).unwrap();
+        assert_eq!(synced, [PathBuf::from("vendor/lib")]);
+        let local_url = |repo: &TestRepo| {
+            git2::Config::open(&repo.repo().path().join("config")).unwrap()
+                .get_string("submodule.vendor/lib.url").ok()
+        };
+        assert_eq!(local_url(&repo).as_deref(), Some(moved_url.as_str()));
+        let sub_repo = repo.repo().find_submodule("vendor/lib").unwrap().open().unwrap();
+        assert_eq!(sub_repo.find_remote("origin").unwrap().url(), Some(moved_url.as_str()));
+
+        assert_eq!(deinit_submodule(repo.repo(), Path::new("vendor/lib"), false).unwrap(), "vendor/lib");
+        let worktree = repo.path().join("vendor/lib");
+        assert!(worktree.is_dir() && std::fs::read_dir(&worktree).unwrap().next().is_none());
+        assert_eq!(local_url(&repo), None);
+        assert!(repo.repo().index().unwrap().get_path(Path::new("vendor/lib"), 0).is_some());
+
+        deinit_submodule(repo.repo(), Path::new("vendor/lib"), true).unwrap();
+        assert!(!worktree.exists());
+        assert!(repo.repo().index().unwrap().get_path(Path::new("vendor/lib"), 0).is_none());
+        let gitmodules = std::fs::read_to_string(repo.path().join(".gitmodules")).unwrap();
+        assert!(!gitmodules.contains("vendor/lib"), "{}", gitmodules);
     }
 
     #[test]
@@ -0,0 +1,341 @@
+//! Scripted repositories for tests.
+//!
+//! Available to rgit's own unit tests and, with the `test-support` feature, to
+//! integration tests and downstream crates:
+//!
+//! ```ignore
+//! use rgit::test_support::TestRepo;
+//!
+//! let repo = TestRepo::new();
+//! repo.commit_file("README.md", "hello\n", "Initial commit");
+//! repo.branch("feature").checkout("feature");
+//! repo.commit_file("src/lib.rs", "pub fn a() {}\n", "Add lib");
+//!
+//! let output = repo.command(env!("CARGO_BIN_EXE_rgit"))
+//!     .args(["log", "--oneline"])
+//!     .output()
+//!     .unwrap();
+//! ```
+//!
+//! Helpers panic on failure, like assertions, so tests stay short.
+
+use git2::build::CheckoutBuilder;
+use git2::{BranchType, Commit, IndexAddOption, Oid, Repository, RepositoryInitOptions, Signature};
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+use crate::core::RgitCore;
+
+/// Identity configured in every test repository
+pub const TEST_USER_NAME: &str = "Test User";
+pub const TEST_USER_EMAIL: &str = "test@example.com";
+
+/// Branch new test repositories start on
+pub const DEFAULT_BRANCH: &str = "main";
+
+/// Create an empty repository with a test identity in a temporary directory
+pub fn create_test_repo() -> (TempDir, Repository) {
+    TestRepo::new().into_parts()
+}
+
+/// A repository in a temporary directory, removed when dropped
+pub struct TestRepo {
+    dir: TempDir,
+    /// Isolated home directory for commands run with [`TestRepo::command`]
+    home: TempDir,
+    repo: Repository,
+}
+
+impl TestRepo {
+    /// Empty repository on `main` with a test identity
+    pub fn new() -> Self {
+        let dir = TempDir::new().expect("create temp dir");
+        let mut opts = RepositoryInitOptions::new();
+        opts.initial_head(DEFAULT_BRANCH);
+        let repo = Repository::init_opts(dir.path(), &opts).expect("init repository");
+        Self::configure(&repo);
+
+        Self { dir, home: TempDir::new().expect("create temp home"), repo }
+    }
+
+    /// Bare repository, useful as a remote
+    pub fn bare() -> Self {
+        let dir = TempDir::new().expect("create temp dir");
+        let mut opts = RepositoryInitOptions::new();
+        opts.bare(true).initial_head(DEFAULT_BRANCH);
+        let repo = Repository::init_opts(dir.path(), &opts).expect("init bare repository");
+        Self::configure(&repo);
+
+        Self { dir, home: TempDir::new().expect("create temp home"), repo }
+    }
+
+    fn configure(repo: &Repository) {
+        let mut config = repo.config().expect("open repository config");
+        config.set_str("user.name", TEST_USER_NAME).unwrap();
+        config.set_str("user.email", TEST_USER_EMAIL).unwrap();
+    }
+
+    /// Working directory (or git directory for bare repositories)
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// A `file://` URL other repositories can fetch this one from
+    pub fn url(&self) -> String {
+        file_url(self.path())
+    }
+
+    pub fn repo(&self) -> &Repository {
+        &self.repo
+    }
+
+    /// Give up the fixture, keeping the directory alive as long as the `TempDir`
+    pub fn into_parts(self) -> (TempDir, Repository) {
+        (self.dir, self.repo)
+    }
+
+    /// rgit's view of this repository
+    pub fn core(&self) -> RgitCore {
+        RgitCore::from_path(self.path(), false).expect("open repository with rgit")
+    }
+
+    /// Commit HEAD points at
+    pub fn head(&self) -> Oid {
+        self.repo.head().and_then(|h| h.peel_to_commit()).expect("HEAD commit").id()
+    }
+
+    // -------------------------------------------------------------------------
+    // Working tree and index
+    // -------------------------------------------------------------------------
+
+    /// Write a file relative to the working directory, creating parent directories
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let full = self.path().join(path);
+        if let Some(parent) = full.parent() {
+            fs::create_dir_all(parent).unwrap();
+        }
+        fs::write(full, content).unwrap();
+        self
+    }
+
+    /// Delete a file from the working directory
+    pub fn remove(&self, path: &str) -> &Self {
+        fs::remove_file(self.path().join(path)).unwrap();
+        self
+    }
+
+    /// Stage a single path
+    pub fn stage(&self, path: &str) -> &Self {
+        let mut index = self.repo.index().unwrap();
+        index.add_path(Path::new(path)).unwrap();
+        index.write().unwrap();
+        self
+    }
+
+    /// Stage every change, including deletions
+    pub fn stage_all(&self) -> &Self {
+        let mut index = self.repo.index().unwrap();
+        index.add_all(["*"], IndexAddOption::DEFAULT, None).unwrap();
+        index.update_all(["*"], None).unwrap();
+        index.write().unwrap();
+        self
+    }
+
+    // -------------------------------------------------------------------------
+    // History
+    // -------------------------------------------------------------------------
+
+    /// Commit whatever is staged on top of HEAD
+    pub fn commit_staged(&self, message: &str) -> Oid {
+        let mut index = self.repo.index().unwrap();
+        let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let sig = Signature::now(TEST_USER_NAME, TEST_USER_EMAIL).unwrap();
+        let parents: Vec<Commit> = self.repo.head().ok()
+            .and_then(|h| h.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+
+        self.repo.commit(Some("HEAD"), &sig, &sig, message, &tree, &parent_refs).unwrap()
+    }
+
+    /// Stage everything and commit
+    pub fn commit(&self, message: &str) -> Oid {
+        self.stage_all();
+        self.commit_staged(message)
+    }
+
+    /// Write one file and commit it
+    pub fn commit_file(&self, path: &str, content: &str, message: &str) -> Oid {
+        self.write(path, content).stage(path);
+        self.commit_staged(message)
+    }
+
+    /// Create a branch at HEAD
+    pub fn branch(&self, name: &str) -> &Self {
+        let head = self.repo.head().and_then(|h| h.peel_to_commit()).expect("HEAD commit");
+        self.repo.branch(name, &head, false).unwrap();
+        self
+    }
+
+    /// Switch to a local branch, overwriting the working tree
+    pub fn checkout(&self, name: &str) -> &Self {
+        let refname = format!("refs/heads/{}", name);
+        let tree = self.repo.revparse_single(&refname).unwrap().peel_to_tree().unwrap();
+        self.repo.checkout_tree(tree.as_object(), Some(CheckoutBuilder::new().force())).unwrap();
+        self.repo.set_head(&refname).unwrap();
+        self
+    }
+
+    /// Lightweight tag at HEAD
+    pub fn tag(&self, name: &str) -> &Self {
+        let head = self.repo.head().unwrap().peel_to_commit().unwrap();
+        self.repo.tag_lightweight(name, head.as_object(), false).unwrap();
+        self
+    }
+
+    /// Annotated tag at HEAD
+    pub fn annotated_tag(&self, name: &str, message: &str) -> Oid {
+        let head = self.repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now(TEST_USER_NAME, TEST_USER_EMAIL).unwrap();
+        self.repo.tag(name, head.as_object(), &sig, message, false).unwrap()
+    }
+
+    /// Leave a merge in progress that conflicts on `path`.
+    ///
+    /// Commits different contents for `path` on the current branch and on a new
+    /// `conflicting` branch, then merges that branch. Requires an existing commit.
+    pub fn conflict(&self, path: &str) -> &Self {
+        let current = self.repo.head().unwrap().shorthand().unwrap().to_string();
+
+        self.branch("conflicting").checkout("conflicting");
+        self.commit_file(path, "theirs\n", "Change on conflicting branch");
+        self.checkout(&current);
+        self.commit_file(path, "ours\n", "Change on current branch");
+
+        let theirs = self.repo.find_branch("conflicting", BranchType::Local).unwrap();
+        let annotated = self.repo.reference_to_annotated_commit(theirs.get()).unwrap();
+        self.repo.merge(&[&annotated], None, None).unwrap();
+        assert!(self.repo.index().unwrap().has_conflicts(), "merge did not conflict");
+        self
+    }
+
+    // -------------------------------------------------------------------------
+    // Remotes and submodules
+    // -------------------------------------------------------------------------
+
+    /// Create a bare remote, add it under `name` and push the current branch to it
+    pub fn add_remote(&self, name: &str) -> TestRepo {
+        let remote_repo = TestRepo::bare();
+        let url = file_url(remote_repo.path());
+        let mut remote = self.repo.remote(name, &url).unwrap();
+
+        if let Ok(head) = self.repo.head() {
+            let branch = head.shorthand().unwrap().to_string();
+            let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+            remote.push(&[refspec.as_str()], None).unwrap();
+            remote.fetch(&[branch.as_str()], None, None).unwrap();
+
+            let mut config = self.repo.config().unwrap();
+            config.set_str(&format!("branch.{}.remote", branch), name).unwrap();
+            config.set_str(&format!("branch.{}.merge", branch), &format!("refs/heads/{}", branch)).unwrap();
+        }
+        remote_repo
+    }
+
+    /// Add `source` as a submodule at `path` and commit it.
+    ///
+    /// `source` needs at least one commit.
+    pub fn add_submodule(&self, source: &TestRepo, path: &str) -> &Self {
+        let url = file_url(source.path());
+        let mut submodule = self.repo.submodule(&url, Path::new(path), true).unwrap();
+        let sub_repo = submodule.clone(None).unwrap();
+        Self::configure(&sub_repo);
+        submodule.add_finalize().unwrap();
+        self.commit_staged(&format!("Add submodule {}", path));
+        self
+    }
+
+    // -------------------------------------------------------------------------
+    // Running the binary
+    // -------------------------------------------------------------------------
+
+    /// A command for `program` (usually `env!("CARGO_BIN_EXE_rgit")`) that runs
+    /// in this repository, isolated from the user's git and rgit configuration
+    pub fn command(&self, program: impl AsRef<OsStr>) -> Command {
+        let mut command = Command::new(program);
+        command
+            .current_dir(self.path())
+            .env("HOME", self.home.path())
+            .env("XDG_CONFIG_HOME", self.home.path().join(".config"))
+            .env("XDG_DATA_HOME", self.home.path().join(".local/share"))
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("RGIT_OFFLINE", "1")
+            .env("NO_COLOR", "1")
+            .env("RGIT_LANG", "en")
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE");
+        command
+    }
+}
+
+impl Default for TestRepo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn file_url(path: &Path) -> String {
+    let path: PathBuf = dunce::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    format!("file://{}", path.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripted_history() {
+        let repo = TestRepo::new();
+        let first = repo.commit_file("README.md", "hello\n", "Initial commit");
+        repo.branch("feature").checkout("feature");
+        repo.write("src/lib.rs", "pub fn a() {}\n");
+        let second = repo.commit("Add lib");
+
+        assert_eq!(repo.head(), second);
+        assert_eq!(repo.repo().head().unwrap().shorthand(), Some("feature"));
+        assert_eq!(repo.repo().find_commit(second).unwrap().parent_id(0).unwrap(), first);
+
+        repo.checkout(DEFAULT_BRANCH);
+        assert!(!repo.path().join("src/lib.rs").exists());
+    }
+
+    #[test]
+    fn test_conflict_and_remote() {
+        let repo = TestRepo::new();
+        repo.commit_file("file.txt", "base\n", "Base");
+
+        let remote = repo.add_remote("origin");
+        assert!(remote.repo().find_reference("refs/heads/main").is_ok());
+        assert!(repo.repo().find_reference("refs/remotes/origin/main").is_ok());
+
+        repo.conflict("file.txt");
+        assert_eq!(repo.repo().state(), git2::RepositoryState::Merge);
+    }
+
+    #[test]
+    fn test_submodule() {
+        let library = TestRepo::new();
+        library.commit_file("lib.rs", "pub fn a() {}\n", "Library");
+
+        let repo = TestRepo::new();
+        repo.commit_file("README.md", "app\n", "App");
+        repo.add_submodule(&library, "vendor/lib");
+
+        assert!(repo.path().join("vendor/lib/lib.rs").exists());
+        assert_eq!(repo.repo().submodules().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,269 @@
+//! Colors for the parts of rgit's output.
+//!
+//! `ui.theme` picks a preset: `dark`, `light`, `solarized` or `monochrome`,
+//! or `auto` for dark or light depending on the terminal's background. The
+//! `[theme]` section recolors single elements on top of it:
+//!
+//! ```toml
+//! [theme]
+//! added = "bright green"
+//! branch = "#268bd2"
+//! warning = "none"
+//! ```
+//!
+//! Output that shows one of these elements is colored with
+//! [`themed`](Themed::themed) rather than a fixed color.
+
+use colored::{Color, ColoredString, Colorize};
+use std::sync::OnceLock;
+
+use crate::config::Config;
+
+/// A part of the output that has its own color
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Element {
+    /// Added lines and staged files
+    Added,
+    /// Removed lines
+    Removed,
+    /// Changed files not yet staged
+    Modified,
+    Untracked,
+    Branch,
+    /// Commit ids
+    Commit,
+    /// Diff hunk headers
+    Hunk,
+    Success,
+    Warning,
+    Error,
+    /// Commands and tips to try next
+    Hint,
+}
+
+impl Element {
+    pub const ALL: [Element; 11] = [
+        Element::Added,
+        Element::Removed,
+        Element::Modified,
+        Element::Untracked,
+        Element::Branch,
+        Element::Commit,
+        Element::Hunk,
+        Element::Success,
+        Element::Warning,
+        Element::Error,
+        Element::Hint,
+    ];
+
+    /// Key of the element in the `[theme]` section
+    pub fn name(self) -> &'static str {
+        match self {
+            Element::Added => "added",
+            Element::Removed => "removed",
+            Element::Modified => "modified",
+            Element::Untracked => "untracked",
+            Element::Branch => "branch",
+            Element::Commit => "commit",
+            Element::Hunk => "hunk",
+            Element::Success => "success",
+            Element::Warning => "warning",
+            Element::Error => "error",
+            Element::Hint => "hint",
+        }
+    }
+}
+
+/// Values `ui.theme` accepts
+pub const THEMES: &[&str] = &["auto", "dark", "light", "solarized", "monochrome"];
+
+/// Colors of a preset, in [`Element::ALL`] order
+fn preset_colors(preset: &str) -> [&'static str; 11] {
+    match preset {
+        "light" => ["green", "red", "blue", "magenta", "blue", "magenta", "cyan", "green", "magenta", "red", "blue"],
+        "solarized" => [
+            "#859900", "#dc322f", "#b58900", "#cb4b16", "#268bd2", "#b58900",
+            "#6c71c4", "#859900", "#cb4b16", "#dc322f", "#2aa198",
+        ],
+        "monochrome" => ["none"; 11],
+        _ => ["green", "red", "yellow", "red", "cyan", "yellow", "cyan", "green", "yellow", "red", "cyan"],
+    }
+}
+
+/// The color a `[theme]` value names: a color like `green` or
+/// `bright blue`, `#rrggbb`, or `none` to leave the element uncolored.
+/// `None` when the value isn't a color.
+pub fn parse_color(value: &str) -> Option<Option<Color>> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("none") {
+        return Some(None);
+    }
+    if let Some(hex) = value.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+        return Some(Some(Color::TrueColor { r: channel(0)?, g: channel(2)?, b: channel(4)? }));
+    }
+    value.to_lowercase().replace(['_', '-'], " ").parse::<Color>().ok().map(Some)
+}
+
+/// Colors for every element
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Preset the theme started from
+    pub name: &'static str,
+    colors: [Option<Color>; 11],
+}
+
+impl Theme {
+    /// A preset by name; anything unknown gets the dark one
+    pub fn preset(name: &str) -> Self {
+        let name = THEMES.iter().copied().find(|theme| *theme == name && *theme != "auto").unwrap_or("dark");
+        Self {
+            name,
+            colors: preset_colors(name).map(|value| parse_color(value).flatten()),
+        }
+    }
+
+    /// The preset `ui.theme` picks, recolored by the `[theme]` section
+    pub fn from_config(config: &Config) -> Self {
+        let name = if config.ui.theme == "auto" {
+            auto_preset(std::env::var("COLORFGBG").ok().as_deref())
+        } else {
+            config.ui.theme.as_str()
+        };
+        let mut theme = Self::preset(name);
+        for (element, value) in config.theme.colors() {
+            if let Some(color) = value.and_then(parse_color) {
+                theme.colors[element as usize] = color;
+            }
+        }
+        theme
+    }
+
+    pub fn color(&self, element: Element) -> Option<Color> {
+        self.colors[element as usize]
+    }
+}
+
+/// `light` when COLORFGBG ("foreground;background") says the background
+/// is a light color, otherwise `dark`
+fn auto_preset(colorfgbg: Option<&str>) -> &'static str {
+    let background = colorfgbg.and_then(|value| value.rsplit(';').next()).and_then(|bg| bg.parse::<u8>().ok());
+    match background {
+        Some(7) | Some(9..=15) => "light",
+        _ => "dark",
+    }
+}
+
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Pick the theme for this process from configuration
+pub fn init(config: &Config) {
+    let _ = THEME.set(Theme::from_config(config));
+}
+
+/// The theme output is colored with
+pub fn current() -> &'static Theme {
+    THEME.get_or_init(|| Theme::preset("dark"))
+}
+
+/// Color text as an element of the current theme
+pub trait Themed {
+    fn themed(self, element: Element) -> ColoredString;
+}
+
+impl Themed for &str {
+    fn themed(self, element: Element) -> ColoredString {
+        match current().color(element) {
+            Some(color) => self.color(color),
+            None => self.normal(),
+        }
+    }
+}
+
+impl Themed for ColoredString {
+    fn themed(self, element: Element) -> ColoredString {
+        match current().color(element) {
+            Some(color) => self.color(color),
+            None => self,
+        }
+    }
+}
+
+/// A prompt style in the color of an element
+pub fn console_style(element: Element) -> console::Style {
+    let style = console::Style::new().for_stderr();
+    match current().color(element) {
+        Some(color) => style.fg(console_color(color)),
+        None => style,
+    }
+}
+
+fn console_color(color: Color) -> console::Color {
+    let cube = |value: u8| ((u16::from(value) * 5 + 127) / 255) as u8;
+    match color {
+        Color::Black => console::Color::Black,
+        Color::Red => console::Color::Red,
+        Color::Green => console::Color::Green,
+        Color::Yellow => console::Color::Yellow,
+        Color::Blue => console::Color::Blue,
+        Color::Magenta => console::Color::Magenta,
+        Color::Cyan => console::Color::Cyan,
+        Color::White => console::Color::White,
+        Color::BrightBlack => console::Color::Color256(8),
+        Color::BrightRed => console::Color::Color256(9),
+        Color::BrightGreen => console::Color::Color256(10),
+        Color::BrightYellow => console::Color::Color256(11),
+        Color::BrightBlue => console::Color::Color256(12),
+        Color::BrightMagenta => console::Color::Color256(13),
+        Color::BrightCyan => console::Color::Color256(14),
+        Color::BrightWhite => console::Color::Color256(15),
+        // The nearest color of the 6x6x6 cube
+        Color::TrueColor { r, g, b } => console::Color::Color256(16 + 36 * cube(r) + 6 * cube(g) + cube(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("green"), Some(Some(Color::Green)));
+        assert_eq!(parse_color("Bright Blue"), Some(Some(Color::BrightBlue)));
+        assert_eq!(parse_color("bright_red"), Some(Some(Color::BrightRed)));
+        assert_eq!(parse_color("#268bd2"), Some(Some(Color::TrueColor { r: 0x26, g: 0x8b, b: 0xd2 })));
+        assert_eq!(parse_color("none"), Some(None));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("chartreuse"), None);
+    }
+
+    #[test]
+    fn test_presets_and_overrides() {
+        for name in THEMES {
+            for value in preset_colors(name) {
+                assert!(parse_color(value).is_some(), "{} has bad color {}", name, value);
+            }
+        }
+        assert_eq!(Theme::preset("dark").color(Element::Added), Some(Color::Green));
+        assert_eq!(Theme::preset("monochrome").color(Element::Error), None);
+        assert_eq!(Theme::preset("neon").name, "dark");
+
+        let mut config = Config::default();
+        config.ui.theme = "light".to_string();
+        config.theme.branch = Some("bright magenta".to_string());
+        let theme = Theme::from_config(&config);
+        assert_eq!(theme.name, "light");
+        assert_eq!(theme.color(Element::Branch), Some(Color::BrightMagenta));
+        assert_eq!(theme.color(Element::Added), Some(Color::Green));
+    }
+
+    #[test]
+    fn test_auto_preset() {
+        assert_eq!(auto_preset(Some("0;15")), "light");
+        assert_eq!(auto_preset(Some("15;default;0")), "dark");
+        assert_eq!(auto_preset(None), "dark");
+    }
+}
@@ -0,0 +1,98 @@
+//! Per-phase timings for `--timings`.
+//!
+//! The work that dominates on big repositories (opening the repository,
+//! scanning the working tree, talking to remotes and writing the index) is
+//! wrapped in [`time`]. While recording is on, durations add up per phase;
+//! work done on several threads at once, like parallel submodule updates,
+//! adds up too, so a phase can take longer than the whole command.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A kind of work worth timing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    RepoOpen,
+    StatusScan,
+    Network,
+    IndexWrite,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 4] = [Phase::RepoOpen, Phase::StatusScan, Phase::Network, Phase::IndexWrite];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::RepoOpen => "repo open",
+            Phase::StatusScan => "status scan",
+            Phase::Network => "network",
+            Phase::IndexWrite => "index write",
+        }
+    }
+}
+
+/// Time spent in one phase
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub duration: Duration,
+    /// How many times the phase ran
+    pub count: u32,
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static TOTALS: Mutex<[(Duration, u32); 4]> = Mutex::new([(Duration::ZERO, 0); 4]);
+
+/// Start recording
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Run `work`, adding its duration to `phase` while recording is on
+pub fn time<T>(phase: Phase, work: impl FnOnce() -> T) -> T {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return work();
+    }
+    let start = Instant::now();
+    let value = work();
+    record(phase, start.elapsed());
+    value
+}
+
+pub fn record(phase: Phase, elapsed: Duration) {
+    let mut totals = TOTALS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let (duration, count) = &mut totals[phase as usize];
+    *duration += elapsed;
+    *count += 1;
+}
+
+/// The phases that ran since the last call, in [`Phase::ALL`] order
+pub fn take() -> Vec<PhaseTiming> {
+    let mut totals = TOTALS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let timings = Phase::ALL.iter()
+        .zip(totals.iter())
+        .filter(|(_, (_, count))| *count > 0)
+        .map(|(phase, (duration, count))| PhaseTiming { phase: *phase, duration: *duration, count: *count })
+        .collect();
+    *totals = [(Duration::ZERO, 0); 4];
+    timings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_take() {
+        record(Phase::IndexWrite, Duration::from_millis(5));
+        record(Phase::IndexWrite, Duration::from_millis(7));
+        record(Phase::RepoOpen, Duration::from_millis(1));
+
+        assert_eq!(take(), vec![
+            PhaseTiming { phase: Phase::RepoOpen, duration: Duration::from_millis(1), count: 1 },
+            PhaseTiming { phase: Phase::IndexWrite, duration: Duration::from_millis(12), count: 2 },
+        ]);
+        assert!(take().is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+//! External diff and merge tools.
+//!
+//! rgit knows how to drive a handful of common tools ([`KNOWN_TOOLS`]) and
+//! finds the first installed one when nothing is configured. A tool is chosen
+//! from, in order: the command line, `integrations.diff_tool` /
+//! `integrations.merge_tool`, git's `diff.tool` / `merge.tool`, and
+//! `$MERGE_TOOL`. Custom tools come from `[integrations.tools.<name>]` or git's
+//! `difftool.<name>.cmd` / `mergetool.<name>.cmd`.
+
+use anyhow::Result;
+use git2::Repository;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::Config;
+use crate::error::RgitError;
+
+/// Whether a tool is comparing two files or merging three
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolKind {
+    Diff,
+    Merge,
+}
+
+impl ToolKind {
+    fn noun(&self) -> &'static str {
+        match self {
+            ToolKind::Diff => "diff",
+            ToolKind::Merge => "merge",
+        }
+    }
+}
+
+/// A tool rgit knows how to invoke
+#[derive(Debug)]
+pub struct KnownTool {
+    pub name: &'static str,
+    /// Executables to look for, in order of preference
+    pub programs: &'static [&'static str],
+    /// Arguments with `$LOCAL`, `$REMOTE`, `$BASE` and `$MERGED` placeholders
+    pub diff_args: &'static [&'static str],
+    pub merge_args: &'static [&'static str],
+}
+
+pub const KNOWN_TOOLS: &[KnownTool] = &[
+    KnownTool {
+        name: "vscode",
+        programs: &["code", "code-insiders", "codium"],
+        diff_args: &["--wait", "--diff", "$LOCAL", "$REMOTE"],
+        merge_args: &["--wait", "--merge", "$REMOTE", "$LOCAL", "$BASE", "$MERGED"],
+    },
+    KnownTool {
+        name: "meld",
+        programs: &["meld"],
+        diff_args: &["$LOCAL", "$REMOTE"],
+        merge_args: &["--output=$MERGED", "$LOCAL", "$BASE", "$REMOTE"],
+    },
+    KnownTool {
+        name: "kdiff3",
+        programs: &["kdiff3"],
+        diff_args: &["$LOCAL", "$REMOTE"],
+        merge_args: &["--auto", "-o", "$MERGED", "$BASE", "$LOCAL", "$REMOTE"],
+    },
+    KnownTool {
+        name: "vimdiff",
+        programs: &["vimdiff", "nvim"],
+        diff_args: &["-d", "$LOCAL", "$REMOTE"],
+        merge_args: &["-d", "-c", "4wincmd w | wincmd J", "$LOCAL", "$BASE", "$REMOTE", "$MERGED"],
+    },
+    KnownTool {
+        name: "araxis",
+        programs: &["compare", "araxis"],
+        diff_args: &["-wait", "-2", "$LOCAL", "$REMOTE"],
+        merge_args: &["-wait", "-merge", "-3", "-a1", "$BASE", "$LOCAL", "$REMOTE", "$MERGED"],
+    },
+];
+
+impl KnownTool {
+    pub fn find(name: &str) -> Option<&'static KnownTool> {
+        KNOWN_TOOLS.iter().find(|tool| tool.name == name || tool.programs.contains(&name))
+    }
+
+    /// The first of this tool's programs on `PATH`
+    pub fn installed_program(&self) -> Option<PathBuf> {
+        self.programs.iter().find_map(|program| which::which(program).ok())
+    }
+}
+
+/// Known tools found on `PATH`
+pub fn detect_installed() -> Vec<&'static KnownTool> {
+    KNOWN_TOOLS.iter().filter(|tool| tool.installed_program().is_some()).collect()
+}
+
+/// Files handed to a tool. `base` and `merged` are only used when merging.
+#[derive(Debug, Clone)]
+pub struct ToolFiles<'a> {
+    pub local: &'a Path,
+    pub remote: &'a Path,
+    pub base: Option<&'a Path>,
+    pub merged: &'a Path,
+}
+
+/// A tool ready to launch
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tool {
+    Known { tool: &'static str, program: PathBuf },
+    /// A shell command reading the files from the environment
+    Custom { name: String, command: String },
+}
+
+impl Tool {
+    pub fn name(&self) -> &str {
+        match self {
+            Tool::Known { tool, .. } => tool,
+            Tool::Custom { name, .. } => name,
+        }
+    }
+
+    /// Run the tool and wait for it to exit
+    pub fn launch(&self, kind: ToolKind, files: &ToolFiles, cwd: &Path) -> Result<()> {
+        let vars = [
+            ("LOCAL", files.local),
+            ("REMOTE", files.remote),
+            ("BASE", files.base.unwrap_or(files.local)),
+            ("MERGED", files.merged),
+        ];
+
+        let mut command = match self {
+            Tool::Known { tool, program } => {
+                let known = KnownTool::find(tool).expect("known tools are built from the registry");
+                let args = match kind {
+                    ToolKind::Diff => known.diff_args,
+                    ToolKind::Merge => known.merge_args,
+                };
+                let mut command = Command::new(program);
+                command.args(args.iter().map(|arg| substitute(arg, &vars)));
+                command
+            }
+            Tool::Custom { command: line, .. } => shell(line),
+        };
+        for (name, path) in vars {
+            command.env(name, path);
+        }
+
+        let status = command.current_dir(cwd).status()
+            .map_err(|e| RgitError::CommandExecutionFailed(format!("{}: {}", self.name(), e)))?;
+        if !status.success() {
+            return Err(RgitError::CommandExecutionFailed(
+                format!("{} exited with {}", self.name(), status)
+            ).into());
+        }
+        Ok(())
+    }
+}
+
+fn substitute(arg: &str, vars: &[(&str, &Path)]) -> String {
+    vars.iter().fold(arg.to_string(), |arg, (name, path)| {
+        arg.replace(&format!("${}", name), &path.display().to_string())
+    })
+}
+
+fn shell(line: &str) -> Command {
+    if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", line]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(line);
+        command
+    }
+}
+
+/// Pick the tool to use: `requested` first, then configuration, then the first
+/// installed known tool
+pub fn select_tool(repo: Option<&Repository>, config: &Config, kind: ToolKind, requested: Option<&str>) -> Result<Tool> {
+    let git_config = repo.and_then(|r| r.config().ok()).or_else(|| git2::Config::open_default().ok());
+    let git_value = |key: &str| git_config.as_ref().and_then(|c| c.get_string(key).ok());
+
+    let configured = match kind {
+        ToolKind::Diff => config.integrations.diff_tool.clone()
+            .or_else(|| git_value("diff.tool"))
+            .or_else(|| git_value("merge.tool")),
+        ToolKind::Merge => config.integrations.merge_tool.clone()
+            .or_else(|| git_value("merge.tool"))
+            .or_else(|| std::env::var("MERGE_TOOL").ok()),
+    };
+
+    match requested.filter(|r| !r.is_empty()).map(String::from).or(configured).filter(|n| !n.is_empty()) {
+        Some(name) => {
+            let custom = config.integrations.tools.get(&name).and_then(|tool| match kind {
+                ToolKind::Diff => tool.diff.clone(),
+                ToolKind::Merge => tool.merge.clone(),
+            });
+            let custom = custom.or_else(|| git_value(&format!("{}tool.{}.cmd", kind.noun(), name)));
+            resolve_tool(&name, custom)
+        }
+        None => detect_installed().first()
+            .and_then(|tool| Some(Tool::Known { tool: tool.name, program: tool.installed_program()? }))
+            .ok_or_else(|| RgitError::ConfigurationError(format!(
+                "no {} tool found; install one of {} or set integrations.{}_tool",
+                kind.noun(), tool_names(), kind.noun()
+            )).into()),
+    }
+}
+
+/// A named tool: a custom command if there is one, otherwise a known tool
+fn resolve_tool(name: &str, custom: Option<String>) -> Result<Tool> {
+    if let Some(command) = custom {
+        return Ok(Tool::Custom { name: name.to_string(), command });
+    }
+    let known = KnownTool::find(name).ok_or_else(|| RgitError::ConfigurationError(
+        format!("unknown tool '{}'; known tools are {}", name, tool_names())
+    ))?;
+    let program = known.installed_program().ok_or_else(|| RgitError::CommandExecutionFailed(
+        format!("{} is not installed (looked for {})", known.name, known.programs.join(", "))
+    ))?;
+    Ok(Tool::Known { tool: known.name, program })
+}
+
+fn tool_names() -> String {
+    KNOWN_TOOLS.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ToolCommand;
+
+    #[test]
+    fn test_known_tool_lookup_and_substitution() {
+        assert_eq!(KnownTool::find("code").unwrap().name, "vscode");
+        assert_eq!(KnownTool::find("kdiff3").unwrap().name, "kdiff3");
+        assert!(KnownTool::find("notepad").is_none());
+
+        let vars = [("LOCAL", Path::new("a_LOCAL.rs")), ("MERGED", Path::new("a.rs"))];
+        assert_eq!(substitute("--output=$MERGED", &vars), "--output=a.rs");
+        assert_eq!(substitute("$LOCAL", &vars), "a_LOCAL.rs");
+    }
+
+    #[test]
+    fn test_select_configured_custom_tool() {
+        let mut config = Config::default();
+        config.integrations.merge_tool = Some("mine".to_string());
+        config.integrations.tools.insert("mine".to_string(), ToolCommand {
+            diff: None,
+            merge: Some("cp \"$REMOTE\" \"$MERGED\"".to_string()),
+        });
+
+        let tool = select_tool(None, &config, ToolKind::Merge, None).unwrap();
+        assert_eq!(tool.name(), "mine");
+        assert!(matches!(tool, Tool::Custom { .. }));
+        assert!(select_tool(None, &config, ToolKind::Merge, Some("no-such-tool")).is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let (local, remote, merged) = (dir.path().join("l"), dir.path().join("r"), dir.path().join("m"));
+        std::fs::write(&local, "local\n").unwrap();
+        std::fs::write(&remote, "remote\n").unwrap();
+        let files = ToolFiles { local: &local, remote: &remote, base: None, merged: &merged };
+        tool.launch(ToolKind::Merge, &files, dir.path()).unwrap();
+        assert_eq!(std::fs::read_to_string(&merged).unwrap(), "remote\n");
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use chrono::{DateTime, Local, TimeZone, Utc};
 use colored::*;
-use git2::{Time, Oid, Repository, BranchType};
+use git2::{BranchType, Delta, Diff, Oid, Patch, Repository, Time};
 use regex::Regex;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -66,7 +66,7 @@ pub fn format_date(time: Time) -> String {
 pub fn format_local_date(time: Time) -> String {
     let utc_datetime = Utc.timestamp_opt(time.seconds(), 0)
         .single()
-        .unwrap_or_else(|| Utc::now());
+        .unwrap_or_else(Utc::now);
     
     let local_datetime: DateTime<Local> = utc_datetime.into();
     local_datetime.format("%Y-%m-%d %H:%M:%S").to_string()
@@ -82,6 +82,140 @@ pub fn current_time() -> Time {
     Time::new(now, 0)
 }
 
+/// Parse a date specification into a unix timestamp.
+///
+/// Accepts absolute dates (`2024-01-31`, `2024-01-31 12:00:00`, RFC 3339),
+/// relative dates (`3 days ago`, `2.weeks.ago`) and `now`/`today`/`yesterday`.
+/// Absolute dates without a timezone are interpreted in local time.
+pub fn parse_date_spec(spec: &str) -> Option<i64> {
+    let spec = spec.trim().to_lowercase();
+    let now = Local::now();
+
+    match spec.as_str() {
+        "now" => return Some(now.timestamp()),
+        "today" => return now.date_naive().and_hms_opt(0, 0, 0)
+            .and_then(|dt| Local.from_local_datetime(&dt).single())
+            .map(|dt| dt.timestamp()),
+        "yesterday" => return Some(now.timestamp() - 86400),
+        _ => {}
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(&spec.to_uppercase()) {
+        return Some(datetime.timestamp());
+    }
+    if let Ok(datetime) = chrono::NaiveDateTime::parse_from_str(&spec, "%Y-%m-%d %H:%M:%S") {
+        return Local.from_local_datetime(&datetime).single().map(|dt| dt.timestamp());
+    }
+    if let Ok(date) = chrono::NaiveDate::parse_from_str(&spec, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0)
+            .and_then(|dt| Local.from_local_datetime(&dt).single())
+            .map(|dt| dt.timestamp());
+    }
+
+    // Relative: "<n> <unit>[s] [ago]", with spaces or dots as separators
+    let relative = Regex::new(r"^(\d+)[\s.]*([a-z]+?)s?(?:[\s.]+ago)?$").ok()?;
+    let captures = relative.captures(&spec)?;
+    let amount: i64 = captures[1].parse().ok()?;
+    let unit = match &captures[2] {
+        "second" | "sec" => 1,
+        "minute" | "min" => 60,
+        "hour" => 3600,
+        "day" => 86400,
+        "week" => 604800,
+        "month" => 2592000,
+        "year" => 31536000,
+        _ => return None,
+    };
+    now.timestamp().checked_sub(amount.checked_mul(unit)?)
+}
+
+/// Parse a duration such as `45s`, `15m`, `2h` or `1d` (plain numbers are seconds)
+pub fn parse_duration_spec(spec: &str) -> Option<std::time::Duration> {
+    let spec = spec.trim();
+    let split = spec.find(|c: char| !c.is_ascii_digit()).unwrap_or(spec.len());
+    let (amount, unit) = spec.split_at(split);
+    let amount: u64 = amount.parse().ok()?;
+
+    let seconds = match unit.trim() {
+        "" | "s" | "sec" | "secs" => 1,
+        "m" | "min" | "mins" => 60,
+        "h" | "hr" | "hrs" => 3600,
+        "d" | "day" | "days" => 86400,
+        _ => return None,
+    };
+    // Absurd amounts are typos, not durations
+    amount.checked_mul(seconds).map(std::time::Duration::from_secs)
+}
+
+/// Commit timestamps this far ahead of the local clock are treated as wrong
+pub const MAX_FUTURE_SKEW_SECS: i64 = 24 * 3600;
+
+/// Timestamps before 1980-01-01 almost always come from a broken clock or import
+pub const EARLIEST_PLAUSIBLE_TIMESTAMP: i64 = 315_532_800;
+
+/// A local clock earlier than 2024-01-01 cannot be right for this build
+const CLOCK_SANITY_FLOOR: i64 = 1_704_067_200;
+
+/// A commit timestamp that will confuse log ordering and time-based caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampAnomaly {
+    /// Seconds ahead of the local clock
+    Future(i64),
+    /// Earlier than any plausible commit
+    Ancient,
+}
+
+impl std::fmt::Display for TimestampAnomaly {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TimestampAnomaly::Future(ahead) => write!(f, "{} in the future", humanize_seconds(*ahead)),
+            TimestampAnomaly::Ancient => write!(f, "before 1980"),
+        }
+    }
+}
+
+/// Check a commit timestamp against the local clock
+pub fn check_timestamp(seconds: i64, now: i64) -> Option<TimestampAnomaly> {
+    if seconds - now > MAX_FUTURE_SKEW_SECS {
+        Some(TimestampAnomaly::Future(seconds - now))
+    } else if seconds < EARLIEST_PLAUSIBLE_TIMESTAMP {
+        Some(TimestampAnomaly::Ancient)
+    } else {
+        None
+    }
+}
+
+/// Whether the local clock is obviously wrong
+pub fn system_clock_is_implausible(now: i64) -> bool {
+    now < CLOCK_SANITY_FLOOR
+}
+
+/// Clamp an anomalous signature time to `now`, keeping name, email and offset
+pub fn normalize_signature(signature: &git2::Signature, now: i64) -> Result<git2::Signature<'static>> {
+    let when = signature.when();
+    let seconds = match check_timestamp(when.seconds(), now) {
+        Some(_) => now,
+        None => when.seconds(),
+    };
+
+    Ok(git2::Signature::new(
+        signature.name().unwrap_or(""),
+        signature.email().unwrap_or(""),
+        &Time::new(seconds, when.offset_minutes()),
+    )?)
+}
+
+/// Format a number of seconds as a rough duration ("3 days")
+fn humanize_seconds(seconds: i64) -> String {
+    let (amount, unit) = match seconds {
+        s if s < 3600 => (s / 60, "minute"),
+        s if s < 86400 => (s / 3600, "hour"),
+        s if s < 31_536_000 => (s / 86400, "day"),
+        s => (s / 31_536_000, "year"),
+    };
+    format!("{} {}{}", amount, unit, if amount == 1 { "" } else { "s" })
+}
+
 // =============================================================================
 // String and Text Utilities
 // =============================================================================
@@ -205,6 +339,33 @@ pub fn word_wrap(text: &str, width: usize) -> Vec<String> {
     lines
 }
 
+/// Lay items out in columns filling `width`, top to bottom then left to right.
+///
+/// Widths are measured without color codes, so styled items line up.
+pub fn format_columns(items: &[String], width: usize) -> Vec<String> {
+    const GAP: usize = 2;
+    let widths: Vec<usize> = items.iter().map(|item| console::measure_text_width(item)).collect();
+
+    // Fewest rows whose columns fit; one item per row if nothing does
+    let rows = (1..items.len().max(1)).find(|&rows| {
+        let total: usize = widths.chunks(rows).map(|column| column.iter().max().unwrap_or(&0) + GAP).sum();
+        total - GAP <= width
+    }).unwrap_or(items.len());
+
+    let column_widths: Vec<usize> = widths.chunks(rows.max(1))
+        .map(|column| column.iter().copied().max().unwrap_or(0))
+        .collect();
+    (0..rows).map(|row| {
+        let mut line = String::new();
+        for (column, column_width) in column_widths.iter().enumerate() {
+            let Some(item) = items.get(column * rows + row) else { break };
+            line.push_str(item);
+            line.push_str(&" ".repeat(column_width + GAP - widths[column * rows + row]));
+        }
+        line.trim_end().to_string()
+    }).collect()
+}
+
 /// Highlight search terms in text
 pub fn highlight_matches(text: &str, pattern: &str, case_sensitive: bool) -> String {
     if pattern.is_empty() {
@@ -255,10 +416,45 @@ pub fn humanize_size(bytes: u64) -> String {
     }
 }
 
+/// Parse a size such as `512KB`, `1.5 GB` or `2048` (bytes), in the same
+/// binary units [`humanize_size`] prints
+pub fn parse_size(spec: &str) -> Option<u64> {
+    let spec = spec.trim();
+    let split = spec.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(spec.len());
+    let (amount, unit) = spec.split_at(split);
+    let amount: f64 = amount.parse().ok()?;
+    let multiplier: u64 = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        "T" | "TB" | "TIB" => 1 << 40,
+        _ => return None,
+    };
+    Some((amount * multiplier as f64) as u64)
+}
+
+/// Calculate total repository size
+pub fn calculate_repo_size(git_dir: &Path) -> Result<u64> {
+    fn dir_size(dir: &Path) -> Result<u64> {
+        let mut size = 0;
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_file() {
+                size += entry.metadata()?.len();
+            } else if path.is_dir() {
+                size += dir_size(&path)?;
+            }
+        }
+        Ok(size)
+    }
+    
+    dir_size(git_dir)
+}
+
 /// Calculate file changes (additions, deletions, modifications)
 pub fn calculate_file_changes(repo: &Repository, from: Option<Oid>, to: Option<Oid>) -> Result<FileChangeStats> {
-    let mut stats = FileChangeStats::default();
-    
     let from_tree = if let Some(oid) = from {
         Some(repo.find_commit(oid)?.tree()?)
     } else {
@@ -277,22 +473,7 @@ pub fn calculate_file_changes(repo: &Repository, from: Option<Oid>, to: Option<O
         None,
     )?;
     
-    diff.foreach(
-        &mut |_delta, _progress| true,
-        None,
-        None,
-        Some(&mut |_delta, _hunk, line| {
-            match line.origin() {
-                '+' => stats.additions += 1,
-                '-' => stats.deletions += 1,
-                _ => {}
-            }
-            true
-        }),
-    )?;
-    
-    stats.files = diff.deltas().len();
-    Ok(stats)
+    FileChangeStats::from_diff(&diff)
 }
 
 #[derive(Debug, Default, Clone)]
@@ -300,13 +481,113 @@ pub struct FileChangeStats {
     pub files: usize,
     pub additions: usize,
     pub deletions: usize,
+    /// The counts broken down by file, in diff order
+    pub per_file: Vec<FileStat>,
+}
+
+/// Lines changed in one file of a diff
+#[derive(Debug, Clone)]
+pub struct FileStat {
+    pub path: String,
+    /// Where the file came from when it was renamed or copied
+    pub old_path: Option<String>,
+    pub status: Delta,
+    pub additions: usize,
+    pub deletions: usize,
+    pub binary: bool,
+}
+
+impl FileStat {
+    pub fn total_changes(&self) -> usize {
+        self.additions + self.deletions
+    }
+    
+    /// The path, as `old => new` for renames and copies
+    pub fn display_path(&self) -> String {
+        match &self.old_path {
+            Some(old) => format!("{} => {}", old, self.path),
+            None => self.path.clone(),
+        }
+    }
+    
+    /// Status letter as printed by `git diff --name-status`
+    pub fn status_letter(&self) -> char {
+        match self.status {
+            Delta::Added => 'A',
+            Delta::Deleted => 'D',
+            Delta::Renamed => 'R',
+            Delta::Copied => 'C',
+            Delta::Typechange => 'T',
+            Delta::Conflicted => 'U',
+            Delta::Untracked => '?',
+            _ => 'M',
+        }
+    }
 }
 
 impl FileChangeStats {
+    /// Count the lines added and removed in every file of `diff`
+    pub fn from_diff(diff: &Diff) -> Result<Self> {
+        let mut stats = FileChangeStats::default();
+        
+        for (idx, delta) in diff.deltas().enumerate() {
+            let path = |file: git2::DiffFile| file.path().map(|p| p.to_string_lossy().replace('\\', "/"));
+            let new_path = path(delta.new_file()).or_else(|| path(delta.old_file())).unwrap_or_default();
+            let old_path = path(delta.old_file())
+                .filter(|old| matches!(delta.status(), Delta::Renamed | Delta::Copied) && *old != new_path);
+            
+            let (additions, deletions, binary) = match Patch::from_diff(diff, idx)? {
+                Some(patch) if !delta.flags().is_binary() => {
+                    let (_, additions, deletions) = patch.line_stats()?;
+                    (additions, deletions, false)
+                }
+                _ => (0, 0, true),
+            };
+            
+            stats.additions += additions;
+            stats.deletions += deletions;
+            stats.per_file.push(FileStat {
+                path: new_path,
+                old_path,
+                status: delta.status(),
+                additions,
+                deletions,
+                binary,
+            });
+        }
+        
+        stats.files = stats.per_file.len();
+        Ok(stats)
+    }
+    
     pub fn total_changes(&self) -> usize {
         self.additions + self.deletions
     }
     
+    /// Summary line in the style of `git diff --shortstat`
+    pub fn format_shortstat(&self) -> String {
+        let mut summary = format!(
+            " {} file{} changed",
+            self.files,
+            if self.files == 1 { "" } else { "s" }
+        );
+        if self.additions > 0 || self.deletions == 0 {
+            summary.push_str(&format!(
+                ", {} insertion{}(+)",
+                self.additions,
+                if self.additions == 1 { "" } else { "s" }
+            ));
+        }
+        if self.deletions > 0 || self.additions == 0 {
+            summary.push_str(&format!(
+                ", {} deletion{}(-)",
+                self.deletions,
+                if self.deletions == 1 { "" } else { "s" }
+            ));
+        }
+        summary
+    }
+    
     pub fn format_summary(&self) -> String {
         if self.files == 0 {
             "no changes".to_string()
@@ -338,6 +619,14 @@ pub fn is_path_in_repo(repo_root: &Path, file_path: &Path) -> bool {
         .unwrap_or(false)
 }
 
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => Path::new(path).to_path_buf(),
+    }
+}
+
 /// Find common prefix of multiple paths
 pub fn find_common_prefix(paths: &[PathBuf]) -> Option<PathBuf> {
     if paths.is_empty() {
@@ -440,33 +729,7 @@ impl BranchStatus {
 
 /// Validate Git reference name
 pub fn is_valid_ref_name(name: &str) -> bool {
-    if name.is_empty() || name.len() > 255 {
-        return false;
-    }
-    
-    // Basic validation rules for Git reference names
-    let invalid_chars = [' ', '~', '^', ':', '?', '*', '[', '\\'];
-    if name.chars().any(|c| invalid_chars.contains(&c)) {
-        return false;
-    }
-    
-    if name.starts_with('/') || name.ends_with('/') {
-        return false;
-    }
-    
-    if name.contains("//") || name.contains("..") {
-        return false;
-    }
-    
-    if name.starts_with('.') || name.ends_with('.') {
-        return false;
-    }
-    
-    if name.ends_with(".lock") {
-        return false;
-    }
-    
-    true
+    crate::validation::ref_name_problem(name).is_none()
 }
 
 /// Parse Git URL to extract components
@@ -526,7 +789,7 @@ impl GitUrlInfo {
     pub fn repository_name(&self) -> String {
         self.path
             .split('/')
-            .last()
+            .next_back()
             .unwrap_or("repository")
             .to_string()
     }
@@ -677,6 +940,58 @@ mod tests {
         assert!(formatted.contains("minute"));
     }
 
+    #[test]
+    fn test_date_spec_parsing() {
+        let now = chrono::Utc::now().timestamp();
+
+        let two_days = parse_date_spec("2 days ago").unwrap();
+        assert!((now - 2 * 86400 - two_days).abs() < 5);
+        assert_eq!(parse_date_spec("3.weeks.ago"), parse_date_spec("3 weeks ago"));
+        assert_eq!(parse_date_spec("1 hour ago").map(|t| (now - t) / 60), Some(60));
+
+        assert_eq!(parse_date_spec("2024-01-31T12:00:00Z"), Some(1706702400));
+        assert!(parse_date_spec("2024-01-31").is_some());
+        assert_eq!(parse_date_spec("99999999999999999 years ago"), None);
+        assert!(parse_date_spec("yesterday").unwrap() < now);
+
+        assert!(parse_date_spec("last tuesday").is_none());
+        assert!(parse_date_spec("5 fortnights ago").is_none());
+    }
+
+    #[test]
+    fn test_duration_spec_parsing() {
+        use std::time::Duration;
+        assert_eq!(parse_duration_spec("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration_spec("15m"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration_spec("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration_spec("1 day"), Some(Duration::from_secs(86400)));
+        assert_eq!(parse_duration_spec("m"), None);
+        assert_eq!(parse_duration_spec("5 weeks"), None);
+        assert_eq!(parse_duration_spec("99999999999999999d"), None);
+    }
+
+    #[test]
+    fn test_timestamp_anomalies() {
+        let now = 1_750_000_000;
+        assert_eq!(check_timestamp(now - 86400, now), None);
+        assert_eq!(check_timestamp(now + 3600, now), None);
+        assert_eq!(check_timestamp(now + 3 * 86400, now), Some(TimestampAnomaly::Future(3 * 86400)));
+        assert_eq!(check_timestamp(0, now), Some(TimestampAnomaly::Ancient));
+        assert_eq!(TimestampAnomaly::Future(3 * 86400).to_string(), "3 days in the future");
+
+        assert!(system_clock_is_implausible(0));
+        assert!(!system_clock_is_implausible(now));
+
+        let future = git2::Signature::new("Dev", "dev@example.com", &Time::new(now + 86400 * 400, 120)).unwrap();
+        let normalized = normalize_signature(&future, now).unwrap();
+        assert_eq!(normalized.when().seconds(), now);
+        assert_eq!(normalized.when().offset_minutes(), 120);
+        assert_eq!(normalized.email(), Some("dev@example.com"));
+
+        let fine = git2::Signature::new("Dev", "dev@example.com", &Time::new(now - 60, 0)).unwrap();
+        assert_eq!(normalize_signature(&fine, now).unwrap().when().seconds(), now - 60);
+    }
+
     #[test]
     fn test_string_truncation() {
         assert_eq!(truncate_string("hello world", 5), "he...");
@@ -690,6 +1005,10 @@ mod tests {
         assert_eq!(humanize_size(1024), "1.0 KB");
         assert_eq!(humanize_size(1536), "1.5 KB");
         assert_eq!(humanize_size(1048576), "1.0 MB");
+        assert_eq!(parse_size("1.0 MB"), Some(1048576));
+        assert_eq!(parse_size("512kb"), Some(524288));
+        assert_eq!(parse_size("2048"), Some(2048));
+        assert_eq!(parse_size("lots"), None);
     }
 
     #[test]
@@ -747,6 +1066,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_format_columns() {
+        let items: Vec<String> = ["alpha", "beta", "gamma", "delta", "epsilon"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(format_columns(&items, 80), vec!["alpha  beta  gamma  delta  epsilon"]);
+        assert_eq!(format_columns(&items, 21), vec!["alpha  gamma  epsilon", "beta   delta"]);
+        assert_eq!(format_columns(&items, 20), vec!["alpha  delta", "beta   epsilon", "gamma"]);
+        assert_eq!(format_columns(&items, 3).len(), 5);
+        assert!(format_columns(&[], 80).is_empty());
+    }
+
     #[test]
     fn test_path_utilities() {
         let paths = vec![
@@ -762,7 +1091,7 @@ mod tests {
     #[test]
     fn test_progress_bar() {
         let progress = create_progress_bar(50, 100, 20);
-        assert_eq!(progress.len(), 20);
+        assert_eq!(progress.chars().count(), 20);
         assert!(progress.contains('█'));
         assert!(progress.contains('░'));
     }
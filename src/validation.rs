@@ -0,0 +1,456 @@
+//! Validation of user-supplied input.
+//!
+//! Everything a user can type — refnames, URLs, paths, messages — should pass
+//! through here before it reaches git2. libgit2 reports malformed input with
+//! terse errors at best, and some git2 entry points panic outright (the
+//! `is_valid_name` helpers unwrap a `CString`, so a NUL byte aborts the
+//! process). The checks below follow `git check-ref-format` and git's URL
+//! handling and turn every rejection into an [`RgitError`] that says what is
+//! wrong with the input.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::error::RgitError;
+
+/// Longest single path component most filesystems can store
+const MAX_COMPONENT_LENGTH: usize = 255;
+
+/// Protocols git can clone from
+const URL_SCHEMES: &[&str] = &["http", "https", "ssh", "git", "file", "git+ssh", "ssh+git"];
+
+// =============================================================================
+// Reference Names
+// =============================================================================
+
+/// Explain why `name` is not a valid reference name, following the rules of
+/// `git check-ref-format` (one-level names such as `main` are allowed)
+pub fn ref_name_problem(name: &str) -> Option<String> {
+    if name.is_empty() {
+        return Some("name is empty".to_string());
+    }
+    if name == "@" {
+        return Some("'@' is shorthand for HEAD".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| c.is_ascii_control()) {
+        return Some(format!("contains control character {:?}", c));
+    }
+    if let Some(c) = name.chars().find(|c| matches!(c, ' ' | '~' | '^' | ':' | '?' | '*' | '[' | '\\')) {
+        return Some(format!("contains '{}'", c));
+    }
+    if name.contains("..") {
+        return Some("contains '..'".to_string());
+    }
+    if name.contains("@{") {
+        return Some("contains '@{'".to_string());
+    }
+    if name.starts_with('/') || name.ends_with('/') {
+        return Some("starts or ends with '/'".to_string());
+    }
+    if name.contains("//") {
+        return Some("contains '//'".to_string());
+    }
+    if name.ends_with('.') {
+        return Some("ends with '.'".to_string());
+    }
+
+    for component in name.split('/') {
+        if component.starts_with('.') {
+            return Some(format!("component '{}' starts with '.'", component));
+        }
+        if component.ends_with(".lock") {
+            return Some(format!("component '{}' ends with '.lock'", component));
+        }
+        if component.len() > MAX_COMPONENT_LENGTH {
+            return Some(format!("component is longer than {} bytes", MAX_COMPONENT_LENGTH));
+        }
+    }
+
+    None
+}
+
+/// Problems shared by names the user creates on the command line
+fn user_ref_name_problem(name: &str) -> Option<String> {
+    if name.starts_with('-') {
+        return Some("starts with '-'".to_string());
+    }
+    ref_name_problem(name)
+}
+
+/// Validate a full or partial reference name
+pub fn validate_ref_name(name: &str) -> Result<(), RgitError> {
+    match ref_name_problem(name) {
+        Some(problem) => Err(RgitError::InvalidReference(describe(name, &problem))),
+        None => Ok(()),
+    }
+}
+
+/// Validate the name of a local branch (without `refs/heads/`)
+pub fn validate_branch_name(name: &str) -> Result<(), RgitError> {
+    let problem = if name == "HEAD" {
+        Some("'HEAD' is reserved".to_string())
+    } else {
+        user_ref_name_problem(name)
+    };
+
+    match problem {
+        Some(problem) => Err(RgitError::InvalidBranchName(describe(name, &problem))),
+        None => Ok(()),
+    }
+}
+
+/// Validate the name of a tag (without `refs/tags/`)
+pub fn validate_tag_name(name: &str) -> Result<(), RgitError> {
+    match user_ref_name_problem(name) {
+        Some(problem) => Err(RgitError::InvalidTagName(describe(name, &problem))),
+        None => Ok(()),
+    }
+}
+
+/// Validate the name of a remote
+pub fn validate_remote_name(name: &str) -> Result<(), RgitError> {
+    match user_ref_name_problem(name) {
+        Some(problem) => Err(RgitError::InvalidArgument(
+            format!("Invalid remote name {}", describe(name, &problem)),
+        )),
+        None => Ok(()),
+    }
+}
+
+// =============================================================================
+// URLs
+// =============================================================================
+
+/// Explain why `url` is not something git can clone or fetch from.
+///
+/// Accepts `scheme://` URLs for the protocols git supports, scp-like
+/// `[user@]host:path` addresses and explicit local paths (absolute, or
+/// starting with `./`, `../` or `~`). A bare word is rejected because git
+/// would read it as a path relative to wherever the command happens to run.
+pub fn url_problem(url: &str) -> Option<String> {
+    if url.is_empty() {
+        return Some("URL is empty".to_string());
+    }
+    if url.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Some("contains whitespace or control characters".to_string());
+    }
+
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let scheme = scheme.to_ascii_lowercase();
+        if !URL_SCHEMES.contains(&scheme.as_str()) {
+            return Some(format!("unsupported protocol '{}'", scheme));
+        }
+        if scheme == "file" {
+            return if rest.is_empty() { Some("file URL has no path".to_string()) } else { None };
+        }
+
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        if let Some(problem) = authority_problem(authority, authority_host(authority)) {
+            return Some(problem);
+        }
+        if path.trim_matches('/').is_empty() {
+            return Some("URL has no repository path".to_string());
+        }
+        return None;
+    }
+
+    if is_local_path(url) {
+        return None;
+    }
+
+    // scp-like syntax: the colon must come before any slash
+    if let Some((authority, path)) = url.split_once(':') {
+        if !authority.contains('/') {
+            let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+            if let Some(problem) = authority_problem(authority, host) {
+                return Some(problem);
+            }
+            if path.is_empty() {
+                return Some("URL has no repository path".to_string());
+            }
+            return None;
+        }
+    }
+
+    Some("not a URL (prefix local paths with './')".to_string())
+}
+
+/// Host part of `[user@]host[:port]`, keeping bracketed IPv6 addresses whole
+fn authority_host(authority: &str) -> &str {
+    let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+    if host.starts_with('[') {
+        return host.split_once(']').map_or(host, |(address, _)| address.trim_start_matches('['));
+    }
+    host.split_once(':').map_or(host, |(host, _)| host)
+}
+
+fn authority_problem(authority: &str, host: &str) -> Option<String> {
+    if host.is_empty() {
+        return Some("URL has no host".to_string());
+    }
+    // ssh would read `[user@]host` as an option (CVE-2017-1000117)
+    if authority.starts_with('-') || host.starts_with('-') {
+        return Some(format!("'{}' starts with '-'", authority));
+    }
+    None
+}
+
+fn is_local_path(url: &str) -> bool {
+    let bytes = url.as_bytes();
+    let windows_drive = bytes.len() >= 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+
+    url.starts_with('/')
+        || url.starts_with("./")
+        || url.starts_with("../")
+        || url.starts_with('~')
+        || url == "."
+        || url == ".."
+        || windows_drive
+}
+
+/// Validate a remote or submodule URL
+pub fn validate_url(url: &str) -> Result<(), RgitError> {
+    match url_problem(url) {
+        Some(problem) => Err(RgitError::InvalidRemoteUrl(describe(url, &problem))),
+        None => Ok(()),
+    }
+}
+
+// =============================================================================
+// Paths
+// =============================================================================
+
+/// Validate a path that will be stored in the repository (a submodule path, a
+/// file to create) and return it normalized.
+///
+/// The path must be relative, stay inside the working tree and not point into
+/// `.git`.
+pub fn validate_relative_path(path: &str) -> Result<PathBuf, RgitError> {
+    if path.contains('\0') {
+        return Err(RgitError::InvalidArgument(format!("Path {} contains a NUL byte", path.escape_debug())));
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(path).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(RgitError::InvalidPath(PathBuf::from(path)));
+            }
+        }
+    }
+
+    if normalized.as_os_str().is_empty() || points_into_git_dir(&normalized) {
+        return Err(RgitError::InvalidPath(PathBuf::from(path)));
+    }
+    Ok(normalized)
+}
+
+/// Resolve a path given on the command line (relative to `cwd`) into a path
+/// relative to the working tree `workdir`, with `/` separators.
+///
+/// Resolution is lexical so paths that no longer exist (deleted files in
+/// history) still work. The working tree root itself resolves to `""`.
+pub fn validate_pathspec(workdir: &Path, cwd: &Path, spec: &str) -> Result<String, RgitError> {
+    if spec.contains('\0') {
+        return Err(RgitError::InvalidArgument(format!("Path {} contains a NUL byte", spec.escape_debug())));
+    }
+    if spec.is_empty() {
+        return Err(RgitError::InvalidPath(PathBuf::new()));
+    }
+
+    let workdir = dunce::canonicalize(workdir).unwrap_or_else(|_| workdir.to_path_buf());
+    let cwd = dunce::canonicalize(cwd).unwrap_or_else(|_| cwd.to_path_buf());
+    let absolute = normalize(&cwd.join(spec));
+
+    let relative = absolute.strip_prefix(&workdir)
+        .map_err(|_| RgitError::PathOutsideRepository(absolute.clone()))?;
+    if points_into_git_dir(relative) {
+        return Err(RgitError::InvalidPath(PathBuf::from(spec)));
+    }
+
+    Ok(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn points_into_git_dir(relative: &Path) -> bool {
+    relative.components().next()
+        .is_some_and(|first| first.as_os_str().eq_ignore_ascii_case(".git"))
+}
+
+// =============================================================================
+// Messages
+// =============================================================================
+
+/// Validate a commit, tag or stash message before it is written
+pub fn validate_message(message: &str) -> Result<(), RgitError> {
+    if message.contains('\0') {
+        return Err(RgitError::InvalidArgument("Message contains a NUL byte".to_string()));
+    }
+    if message.trim().is_empty() {
+        return Err(RgitError::EmptyCommitMessage);
+    }
+    Ok(())
+}
+
+/// `'name' (problem)`, with unprintable characters escaped
+fn describe(input: &str, problem: &str) -> String {
+    format!("'{}' ({})", input.escape_debug(), problem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_ref_names() {
+        for name in ["main", "feature/new-ui", "release-1.2", "fix/issue_42", "ünïcode", "a@b", "x.y/z"] {
+            assert!(validate_branch_name(name).is_ok(), "{} should be valid", name);
+        }
+        for name in [
+            "", "-x", "HEAD", "@", "a..b", "a b", "a~1", "a^", "a:b", "a?", "a*", "a[", "a\\b",
+            "/a", "a/", "a//b", ".a", "a/.b", "a.", "a.lock", "a.lock/b", "a@{1}", "a\0b", "a\tb",
+        ] {
+            assert!(validate_branch_name(name).is_err(), "{:?} should be invalid", name);
+        }
+
+        let error = validate_branch_name("bad..name").unwrap_err().to_string();
+        assert!(error.contains("'bad..name'") && error.contains("'..'"), "{}", error);
+        assert!(validate_tag_name("v1.0").is_ok());
+        assert!(matches!(validate_tag_name("-v1"), Err(RgitError::InvalidTagName(_))));
+        assert!(validate_remote_name("upstream").is_ok());
+        assert!(validate_remote_name("-origin").is_err());
+    }
+
+    #[test]
+    fn test_urls() {
+        for url in [
+            "https://github.com/user/repo.git",
+            "http://example.com:8080/repo",
+            "ssh://git@github.com/user/repo.git",
+            "ssh://[::1]:22/repo",
+            "git://example.com/repo",
+            "file:///srv/repo.git",
+            "git@github.com:user/repo.git",
+            "host:repo",
+            "/srv/repo.git",
+            "./sibling",
+            "../sibling",
+            "~/repos/project",
+            "C:\\repos\\project",
+        ] {
+            assert!(validate_url(url).is_ok(), "{} should be valid", url);
+        }
+        for url in [
+            "",
+            "not-a-url",
+            "https://",
+            "https://github.com",
+            "ftp://example.com/repo",
+            "ssh://-oProxyCommand=evil/repo",
+            "-oProxyCommand=evil:repo",
+            "-user@host:repo",
+            "ssh://-user@host/repo",
+            "git@github.com:",
+            "https://github.com/user/repo name",
+            "https://github.com/user/repo\n",
+        ] {
+            assert!(validate_url(url).is_err(), "{:?} should be invalid", url);
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(validate_relative_path("vendor/lib").unwrap(), PathBuf::from("vendor/lib"));
+        assert_eq!(validate_relative_path("./vendor/./lib").unwrap(), PathBuf::from("vendor/lib"));
+        for path in ["", ".", "../x", "a/../../x", "/abs", ".git", ".git/hooks", ".GIT/config", "a\0b"] {
+            assert!(validate_relative_path(path).is_err(), "{:?} should be invalid", path);
+        }
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+
+        assert_eq!(validate_pathspec(root, root, "src/main.rs").unwrap(), "src/main.rs");
+        assert_eq!(validate_pathspec(root, &root.join("src"), "../README.md").unwrap(), "README.md");
+        assert_eq!(validate_pathspec(root, &root.join("src"), "deleted.rs").unwrap(), "src/deleted.rs");
+        assert_eq!(validate_pathspec(root, &root.join("src"), "..").unwrap(), "");
+        assert!(matches!(validate_pathspec(root, root, "../outside"), Err(RgitError::PathOutsideRepository(_))));
+        assert!(matches!(validate_pathspec(root, root, ".git/config"), Err(RgitError::InvalidPath(_))));
+        assert!(validate_pathspec(root, root, "a\0b").is_err());
+    }
+
+    #[test]
+    fn test_messages() {
+        assert!(validate_message("Fix bug\n\nDetails").is_ok());
+        assert!(matches!(validate_message("  \n\t"), Err(RgitError::EmptyCommitMessage)));
+        assert!(matches!(validate_message("Fix\0bug"), Err(RgitError::InvalidArgument(_))));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_validators_never_panic(input in any::<String>()) {
+            let _ = validate_branch_name(&input);
+            let _ = validate_tag_name(&input);
+            let _ = validate_remote_name(&input);
+            let _ = validate_url(&input);
+            let _ = validate_relative_path(&input);
+            let _ = validate_message(&input);
+        }
+
+        #[test]
+        fn prop_branch_names_agree_with_libgit2(name in "[a-z./@{}~^:?*\\[\\\\ \t-]{1,12}|HEAD") {
+            // libgit2 accepts `refs/heads/@`, which git itself refuses as a branch
+            let ours = validate_branch_name(&name).is_ok();
+            let libgit2 = git2::Branch::name_is_valid(&name).unwrap_or(false);
+            prop_assert_eq!(ours, libgit2 && name != "@", "{:?}", name);
+        }
+
+        #[test]
+        fn prop_valid_names_are_safe_for_git2(name in any::<String>()) {
+            if validate_ref_name(&name).is_ok() {
+                prop_assert!(!name.contains('\0'));
+                let full_name = format!("refs/heads/{}", name);
+                prop_assert!(git2::Reference::is_valid_name(&full_name), "{:?}", name);
+            }
+        }
+
+        #[test]
+        fn prop_relative_paths_stay_inside(path in "[a-z./\\\\]{0,16}") {
+            if let Ok(normalized) = validate_relative_path(&path) {
+                prop_assert!(normalized.is_relative());
+                prop_assert!(normalized.components().all(|c| matches!(c, Component::Normal(_))));
+            }
+        }
+
+        #[test]
+        fn prop_valid_urls_cannot_inject_options(url in "[a-z:/@.\\[\\] -]{0,24}") {
+            if validate_url(&url).is_ok() {
+                prop_assert!(!url.contains(' '));
+                prop_assert!(!url.starts_with('-'));
+                if let Some((_, rest)) = url.split_once("://") {
+                    let authority = rest.split('/').next().unwrap_or("");
+                    prop_assert!(!authority.starts_with('-') && !authority.contains("@-"));
+                }
+            }
+        }
+    }
+}
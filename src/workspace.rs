@@ -0,0 +1,560 @@
+use anyhow::{Context, Result};
+use git2::{BranchType, Direction, FetchOptions, PushOptions, RemoteCallbacks, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tracing::debug;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::error::RgitError;
+use crate::network;
+use crate::timings::{self, Phase};
+
+/// File name of the workspace manifest
+pub const MANIFEST_FILE: &str = ".rgit-workspace.toml";
+
+/// Manifest listing the repositories that belong to a workspace
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceManifest {
+    /// Registered repositories
+    #[serde(default)]
+    pub repos: Vec<WorkspaceRepo>,
+}
+
+/// A repository registered in the workspace
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRepo {
+    /// Short name used in reports and commands
+    pub name: String,
+    /// Path relative to the workspace root
+    pub path: PathBuf,
+}
+
+/// A workspace rooted at the directory containing the manifest
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub manifest: WorkspaceManifest,
+}
+
+impl Workspace {
+    /// Find the workspace containing `start` by walking up the directory tree
+    pub fn discover(start: &Path) -> Result<Self> {
+        let start = dunce::canonicalize(start).unwrap_or_else(|_| start.to_path_buf());
+
+        for dir in start.ancestors() {
+            if dir.join(MANIFEST_FILE).is_file() {
+                return Self::load(dir);
+            }
+        }
+
+        Err(RgitError::WorkspaceNotFound.into())
+    }
+
+    /// Load the workspace rooted at `root`
+    pub fn load(root: &Path) -> Result<Self> {
+        let path = root.join(MANIFEST_FILE);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read workspace manifest: {}", path.display()))?;
+        let manifest: WorkspaceManifest = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse workspace manifest: {}", path.display()))?;
+
+        debug!("Loaded workspace with {} repositories from {}", manifest.repos.len(), root.display());
+        Ok(Self { root: root.to_path_buf(), manifest })
+    }
+
+    /// Create a new, empty workspace in `root`
+    pub fn init(root: &Path) -> Result<Self> {
+        if root.join(MANIFEST_FILE).exists() {
+            return Err(RgitError::OperationFailed(format!(
+                "Workspace already exists in {}", root.display()
+            )).into());
+        }
+
+        let workspace = Self {
+            root: dunce::canonicalize(root).unwrap_or_else(|_| root.to_path_buf()),
+            manifest: WorkspaceManifest::default(),
+        };
+        workspace.save()?;
+        Ok(workspace)
+    }
+
+    /// Write the manifest back to disk
+    pub fn save(&self) -> Result<()> {
+        let path = self.root.join(MANIFEST_FILE);
+        let content = toml::to_string_pretty(&self.manifest)
+            .context("Failed to serialize workspace manifest")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write workspace manifest: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Register a repository; `path` may be absolute or relative to the current directory
+    pub fn add_repo(&mut self, path: &Path, name: Option<&str>) -> Result<&WorkspaceRepo> {
+        let absolute = dunce::canonicalize(path)
+            .map_err(|_| RgitError::InvalidPath(path.to_path_buf()))?;
+        Repository::open(&absolute)
+            .map_err(|_| RgitError::RepositoryNotFound(absolute.clone()))?;
+
+        let relative = absolute.strip_prefix(&self.root)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| absolute.clone());
+        let name = name.map(str::to_string).unwrap_or_else(|| {
+            absolute.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| "repo".to_string())
+        });
+
+        if self.find(&name).is_some() {
+            return Err(RgitError::WorkspaceRepoExists(name).into());
+        }
+        if self.manifest.repos.iter().any(|r| self.repo_path(r) == absolute) {
+            return Err(RgitError::WorkspaceRepoExists(absolute.display().to_string()).into());
+        }
+
+        self.manifest.repos.push(WorkspaceRepo { name, path: relative });
+        Ok(self.manifest.repos.last().unwrap())
+    }
+
+    /// Unregister a repository by name
+    pub fn remove_repo(&mut self, name: &str) -> Result<WorkspaceRepo> {
+        let index = self.manifest.repos.iter()
+            .position(|r| r.name == name)
+            .ok_or_else(|| RgitError::WorkspaceRepoNotFound(name.to_string()))?;
+        Ok(self.manifest.repos.remove(index))
+    }
+
+    /// Look up a repository by name
+    pub fn find(&self, name: &str) -> Option<&WorkspaceRepo> {
+        self.manifest.repos.iter().find(|r| r.name == name)
+    }
+
+    /// Absolute path of a registered repository
+    pub fn repo_path(&self, repo: &WorkspaceRepo) -> PathBuf {
+        if repo.path.is_absolute() {
+            repo.path.clone()
+        } else {
+            self.root.join(&repo.path)
+        }
+    }
+}
+
+// =============================================================================
+// Operations
+// =============================================================================
+
+/// Operation to run across every repository in a workspace
+#[derive(Debug, Clone)]
+pub enum WorkspaceOperation {
+    Status,
+    Pull,
+    Push,
+    Foreach(String),
+}
+
+/// Outcome of an operation on a single repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoOutcome {
+    /// Nothing needed to be done
+    UpToDate,
+    /// The operation changed something (pulled, pushed, command ran)
+    Updated,
+    /// The repository has local work that needs attention
+    Attention,
+    /// The operation was skipped for safety
+    Skipped,
+    /// The operation failed
+    Failed,
+}
+
+impl RepoOutcome {
+    pub fn label(&self) -> &'static str {
+        match self {
+            RepoOutcome::UpToDate => "ok",
+            RepoOutcome::Updated => "updated",
+            RepoOutcome::Attention => "attention",
+            RepoOutcome::Skipped => "skipped",
+            RepoOutcome::Failed => "failed",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            RepoOutcome::UpToDate => "✅",
+            RepoOutcome::Updated => "🔄",
+            RepoOutcome::Attention => "📝",
+            RepoOutcome::Skipped => "⏭️",
+            RepoOutcome::Failed => "❌",
+        }
+    }
+}
+
+/// Result row for the aggregated workspace report
+#[derive(Debug, Clone)]
+pub struct RepoReport {
+    pub name: String,
+    pub branch: String,
+    pub changes: usize,
+    pub ahead: usize,
+    pub behind: usize,
+    pub outcome: RepoOutcome,
+    pub detail: String,
+}
+
+impl RepoReport {
+    fn failed(name: &str, error: impl std::fmt::Display) -> Self {
+        Self {
+            name: name.to_string(),
+            branch: "-".to_string(),
+            changes: 0,
+            ahead: 0,
+            behind: 0,
+            outcome: RepoOutcome::Failed,
+            detail: error.to_string(),
+        }
+    }
+}
+
+/// Run an operation across all repositories with at most `jobs` running at once.
+/// Reports are returned in manifest order.
+pub async fn run_operation(workspace: &Workspace, operation: WorkspaceOperation, jobs: usize, config: &Config) -> Vec<RepoReport> {
+    let repos = workspace.manifest.repos.iter()
+        .map(|repo| (repo.name.clone(), workspace.repo_path(repo)))
+        .collect();
+    run_on_repos(repos, operation, jobs, config).await
+}
+
+/// Run an operation on arbitrary `(name, path)` pairs with at most `jobs` running at once.
+/// Reports are returned in input order.
+pub async fn run_on_repos(repos: Vec<(String, PathBuf)>, operation: WorkspaceOperation, jobs: usize, config: &Config) -> Vec<RepoReport> {
+    let semaphore = Arc::new(Semaphore::new(jobs.max(1)));
+    let config = Arc::new(config.clone());
+    let mut handles = Vec::with_capacity(repos.len());
+
+    for (name, path) in &repos {
+        let name = name.clone();
+        let path = path.clone();
+        let operation = operation.clone();
+        let semaphore = Arc::clone(&semaphore);
+        let config = Arc::clone(&config);
+
+        handles.push(tokio::spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let task_name = name.clone();
+            tokio::task::spawn_blocking(move || run_on_repo(&task_name, &path, &operation, &config))
+                .await
+                .unwrap_or_else(|e| RepoReport::failed(&name, e))
+        }));
+    }
+
+    let mut reports = Vec::with_capacity(handles.len());
+    for (handle, (name, _)) in handles.into_iter().zip(&repos) {
+        reports.push(handle.await.unwrap_or_else(|e| RepoReport::failed(name, e)));
+    }
+    reports
+}
+
+/// Find repositories below `root`, descending at most `max_depth` directories.
+///
+/// Only directories with a `.git` directory count; submodules and worktrees
+/// (which have a `.git` file) are left to their parent repository.
+pub fn discover_repositories(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut repos: Vec<PathBuf> = WalkDir::new(root)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|entry| entry.file_type().is_dir() && entry.file_name() != ".git")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .filter(|path| path.join(".git").is_dir())
+        .collect();
+    repos.sort();
+    repos
+}
+
+/// Run an operation on one repository, converting errors into a failed report
+fn run_on_repo(name: &str, path: &Path, operation: &WorkspaceOperation, config: &Config) -> RepoReport {
+    let result = Repository::open(path)
+        .map_err(|_| RgitError::RepositoryNotFound(path.to_path_buf()).into())
+        .and_then(|repo| {
+            let mut report = inspect_repo(name, &repo)?;
+            match operation {
+                WorkspaceOperation::Status => {}
+                WorkspaceOperation::Pull => pull_repo(&repo, &mut report, config)?,
+                WorkspaceOperation::Push => push_repo(&repo, &mut report, config)?,
+                WorkspaceOperation::Foreach(command) => run_command(path, command, &mut report)?,
+            }
+            Ok(report)
+        });
+
+    result.unwrap_or_else(|e: anyhow::Error| RepoReport::failed(name, e))
+}
+
+/// Collect branch, change and tracking information
+fn inspect_repo(name: &str, repo: &Repository) -> Result<RepoReport> {
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(true).include_ignored(false);
+    let changes = timings::time(Phase::StatusScan, || repo.statuses(Some(&mut opts)))?.len();
+
+    let (branch, ahead, behind) = match repo.head() {
+        Ok(head) if head.is_branch() => {
+            let branch = head.shorthand().unwrap_or("HEAD").to_string();
+            let (ahead, behind) = upstream_counts(repo, &branch).unwrap_or((0, 0));
+            (branch, ahead, behind)
+        }
+        Ok(_) => ("HEAD (detached)".to_string(), 0, 0),
+        Err(_) => ("(no commits)".to_string(), 0, 0),
+    };
+
+    let (outcome, detail) = if changes > 0 {
+        (RepoOutcome::Attention, format!("{} uncommitted changes", changes))
+    } else if ahead > 0 || behind > 0 {
+        (RepoOutcome::Attention, "out of sync with upstream".to_string())
+    } else {
+        (RepoOutcome::UpToDate, "clean".to_string())
+    };
+
+    Ok(RepoReport { name: name.to_string(), branch, changes, ahead, behind, outcome, detail })
+}
+
+/// Ahead/behind counts of a local branch relative to its upstream
+fn upstream_counts(repo: &Repository, branch: &str) -> Result<(usize, usize)> {
+    let local = repo.find_branch(branch, BranchType::Local)?;
+    let upstream = local.upstream()?;
+    let local_oid = local.get().target().ok_or(RgitError::DetachedHead)?;
+    let upstream_oid = upstream.get().target().ok_or(RgitError::DetachedHead)?;
+    Ok(repo.graph_ahead_behind(local_oid, upstream_oid)?)
+}
+
+/// Remote callbacks shared by fetch and push
+fn remote_callbacks(config: &Config) -> RemoteCallbacks<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(network::credentials(config));
+    callbacks
+}
+
+/// Upstream remote and merge ref for the current branch
+fn upstream_target(repo: &Repository, branch: &str) -> Result<(String, String)> {
+    let config = repo.config()?;
+    let remote = config.get_string(&format!("branch.{}.remote", branch))
+        .map_err(|_| RgitError::OperationFailed(format!("'{}' has no upstream", branch)))?;
+    let merge = config.get_string(&format!("branch.{}.merge", branch))
+        .map_err(|_| RgitError::OperationFailed(format!("'{}' has no upstream", branch)))?;
+    Ok((remote, merge))
+}
+
+/// The remote-tracking ref of a local branch's upstream, like `refs/remotes/origin/main`
+fn tracking_ref(repo: &Repository, branch: &str) -> Result<String> {
+    let name = repo.branch_upstream_name(&format!("refs/heads/{}", branch))?;
+    Ok(name.as_str().unwrap_or_default().to_string())
+}
+
+/// Fetch the upstream and fast-forward when it is safe to do so
+fn pull_repo(repo: &Repository, report: &mut RepoReport, config: &Config) -> Result<()> {
+    if report.changes > 0 {
+        report.outcome = RepoOutcome::Skipped;
+        report.detail = format!("skipped: {} uncommitted changes", report.changes);
+        return Ok(());
+    }
+    let head = repo.head()?;
+    if !head.is_branch() {
+        report.outcome = RepoOutcome::Skipped;
+        report.detail = "skipped: detached HEAD".to_string();
+        return Ok(());
+    }
+
+    let (remote_name, merge_ref) = upstream_target(repo, &report.branch)?;
+    // Name the tracking ref, which a rewritten (anonymous) remote doesn't know
+    let tracking = tracking_ref(repo, &report.branch)?;
+    let mut remote = network::rewritten(repo, repo.find_remote(&remote_name)?, config, Direction::Fetch)?;
+    let refspec = format!("+{}:{}", merge_ref, tracking);
+    network::with_retries_blocking(config, || {
+        let mut fetch_options = FetchOptions::new();
+        fetch_options.remote_callbacks(remote_callbacks(config));
+        timings::time(Phase::Network, || remote.fetch(&[refspec.as_str()], Some(&mut fetch_options), None))
+    }, |e| RgitError::FetchFailed(e.message().to_string()))?;
+
+    let (ahead, behind) = upstream_counts(repo, &report.branch)?;
+    report.ahead = ahead;
+    report.behind = behind;
+
+    if behind == 0 {
+        report.outcome = RepoOutcome::UpToDate;
+        report.detail = "already up to date".to_string();
+    } else if ahead > 0 {
+        report.outcome = RepoOutcome::Attention;
+        report.detail = "diverged: run 'rgit pull' in this repository".to_string();
+    } else {
+        let upstream = repo.find_branch(&report.branch, BranchType::Local)?.upstream()?;
+        let target = upstream.get().peel_to_commit()?;
+        repo.checkout_tree(target.as_object(), Some(git2::build::CheckoutBuilder::new().safe()))?;
+        let mut reference = repo.head()?;
+        reference.set_target(target.id(), "rgit workspace pull: fast-forward")?;
+
+        report.outcome = RepoOutcome::Updated;
+        report.detail = format!("fast-forwarded {} commit(s)", behind);
+        report.behind = 0;
+    }
+
+    Ok(())
+}
+
+/// Push the current branch to its upstream when it has local commits
+fn push_repo(repo: &Repository, report: &mut RepoReport, config: &Config) -> Result<()> {
+    if !repo.head()?.is_branch() {
+        report.outcome = RepoOutcome::Skipped;
+        report.detail = "skipped: detached HEAD".to_string();
+        return Ok(());
+    }
+    if report.ahead == 0 {
+        report.outcome = if report.behind > 0 { RepoOutcome::Attention } else { RepoOutcome::UpToDate };
+        report.detail = "nothing to push".to_string();
+        return Ok(());
+    }
+    if report.behind > 0 {
+        report.outcome = RepoOutcome::Skipped;
+        report.detail = "skipped: behind upstream, pull first".to_string();
+        return Ok(());
+    }
+
+    let (remote_name, merge_ref) = upstream_target(repo, &report.branch)?;
+    let mut remote = network::rewritten(repo, repo.find_remote(&remote_name)?, config, Direction::Push)?;
+    let refspec = format!("refs/heads/{}:{}", report.branch, merge_ref);
+
+    network::with_retries_blocking(config, || {
+        let mut callbacks = remote_callbacks(config);
+        callbacks.push_update_reference(|refname, status| match status {
+            Some(message) => Err(git2::Error::from_str(&format!("{} rejected: {}", refname, message))),
+            None => Ok(()),
+        });
+        let mut push_options = PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+        timings::time(Phase::Network, || remote.push(&[refspec.as_str()], Some(&mut push_options)))
+    }, |e| RgitError::PushRejected(e.message().to_string()))?;
+    if remote.name().is_none() {
+        let tip = repo.head()?.peel_to_commit()?.id();
+        repo.reference(&tracking_ref(repo, &report.branch)?, tip, true, "rgit workspace push")?;
+    }
+
+    report.outcome = RepoOutcome::Updated;
+    report.detail = format!("pushed {} commit(s)", report.ahead);
+    report.ahead = 0;
+    Ok(())
+}
+
+/// Run a shell command inside the repository
+fn run_command(path: &Path, command: &str, report: &mut RepoReport) -> Result<()> {
+    let output = if cfg!(windows) {
+        Command::new("cmd").args(["/C", command]).current_dir(path).output()?
+    } else {
+        Command::new("sh").arg("-c").arg(command).current_dir(path).output()?
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let last_line = |text: &str| text.lines().last().unwrap_or("").trim().to_string();
+
+    if output.status.success() {
+        report.outcome = RepoOutcome::Updated;
+        report.detail = last_line(&stdout);
+    } else {
+        report.outcome = RepoOutcome::Failed;
+        report.detail = format!("exit {}: {}", output.status.code().unwrap_or(-1), last_line(&stderr));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn init_repo(dir: &Path) {
+        fs::create_dir_all(dir).unwrap();
+        Repository::init(dir).unwrap();
+    }
+
+    #[test]
+    fn test_manifest_roundtrip_and_discovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(&root.join("api"));
+        init_repo(&root.join("web"));
+
+        let mut workspace = Workspace::init(root).unwrap();
+        workspace.add_repo(&root.join("api"), None).unwrap();
+        workspace.add_repo(&root.join("web"), Some("frontend")).unwrap();
+        workspace.save().unwrap();
+
+        let nested = root.join("api").join("src");
+        fs::create_dir_all(&nested).unwrap();
+        let discovered = Workspace::discover(&nested).unwrap();
+
+        assert_eq!(discovered.manifest.repos.len(), 2);
+        assert_eq!(discovered.manifest.repos[0].path, PathBuf::from("api"));
+        assert!(discovered.find("frontend").is_some());
+    }
+
+    #[test]
+    fn test_add_repo_rejects_duplicates_and_non_repos() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(&root.join("api"));
+        fs::create_dir_all(root.join("plain")).unwrap();
+
+        let mut workspace = Workspace::init(root).unwrap();
+        workspace.add_repo(&root.join("api"), None).unwrap();
+
+        assert!(workspace.add_repo(&root.join("api"), Some("other")).is_err());
+        assert!(workspace.add_repo(&root.join("plain"), None).is_err());
+        assert!(workspace.remove_repo("missing").is_err());
+        assert_eq!(workspace.remove_repo("api").unwrap().name, "api");
+    }
+
+    #[tokio::test]
+    async fn test_status_operation_reports_each_repo() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(&root.join("clean"));
+        init_repo(&root.join("dirty"));
+        fs::write(root.join("dirty").join("file.txt"), "content").unwrap();
+
+        let mut workspace = Workspace::init(root).unwrap();
+        workspace.add_repo(&root.join("clean"), None).unwrap();
+        workspace.add_repo(&root.join("dirty"), None).unwrap();
+        workspace.manifest.repos.push(WorkspaceRepo {
+            name: "gone".to_string(),
+            path: PathBuf::from("gone"),
+        });
+
+        let reports = run_operation(&workspace, WorkspaceOperation::Status, 2, &Config::default()).await;
+
+        assert_eq!(reports.len(), 3);
+        assert_eq!(reports[0].outcome, RepoOutcome::UpToDate);
+        assert_eq!(reports[1].outcome, RepoOutcome::Attention);
+        assert_eq!(reports[1].changes, 1);
+        assert_eq!(reports[2].outcome, RepoOutcome::Failed);
+    }
+
+    #[test]
+    fn test_discover_repositories_skips_submodules_and_respects_depth() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        init_repo(&root.join("top"));
+        init_repo(&root.join("top").join("nested"));
+        init_repo(&root.join("group").join("deep").join("repo"));
+        // A submodule checkout has a `.git` file rather than a directory
+        fs::create_dir_all(root.join("top").join("sub")).unwrap();
+        fs::write(root.join("top").join("sub").join(".git"), "gitdir: ../.git/modules/sub").unwrap();
+
+        let found = discover_repositories(root, 2);
+        assert_eq!(found, vec![root.join("top"), root.join("top").join("nested")]);
+
+        let found = discover_repositories(root, 3);
+        assert_eq!(found.len(), 3);
+        assert!(found.contains(&root.join("group").join("deep").join("repo")));
+    }
+}